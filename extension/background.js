@@ -1,14 +1,40 @@
 const NATIVE_HOST = "com.foxus.native";
 const MAX_RECONNECT_ATTEMPTS = 10;
 const INITIAL_RECONNECT_DELAY = 1000;
+// Lets the app show whether the extension is connected
+const HEARTBEAT_INTERVAL = 60 * 1000;
 
 let focusState = {
   active: false,
   budgetRemaining: 0,
-  blockedDomains: []
+  blockedDomains: [],
+  allowedDomains: [],
+  hardBlockedDomains: [],
+  softLimitedDomains: [],
+  budgetAllowedDomains: [],
+  grantSecs: undefined,
+  blocklistVersion: undefined
 };
 
+// Which browser this is, so the app can split time per browser
+function detectBrowser() {
+  const ua = navigator.userAgent;
+  if (ua.includes("Firefox/")) return "firefox";
+  if (ua.includes("Edg/")) return "edge";
+  if (ua.includes("OPR/")) return "opera";
+  if (ua.includes("Vivaldi/")) return "vivaldi";
+  if (navigator.brave) return "brave";
+  return "chrome";
+}
+
+const BROWSER = detectBrowser();
+
+// Label for this browser profile, set from the popup
+let profileName = "";
+
 let nativePort = null;
+// Whether focusState came from the host on this run rather than the cache
+let hasLiveState = false;
 let reconnectAttempts = 0;
 let reconnectTimeoutId = null;
 
@@ -18,10 +44,50 @@ function validateStateMessage(message) {
     typeof message === "object" &&
     typeof message.focusActive === "boolean" &&
     typeof message.budgetRemaining === "number" &&
-    (message.blockedDomains === undefined || Array.isArray(message.blockedDomains))
+    (message.blockedDomains === undefined || Array.isArray(message.blockedDomains)) &&
+    (message.allowedDomains === undefined || Array.isArray(message.allowedDomains)) &&
+    (message.softLimitedDomains === undefined || Array.isArray(message.softLimitedDomains)) &&
+    (message.grantSecs === undefined || typeof message.grantSecs === "number")
+  );
+}
+
+function validateBlocklistMessage(message) {
+  return (
+    message &&
+    typeof message === "object" &&
+    typeof message.fromVersion === "number" &&
+    typeof message.version === "number" &&
+    Array.isArray(message.added) &&
+    Array.isArray(message.removed) &&
+    (message.allowedDomains === undefined || Array.isArray(message.allowedDomains))
   );
 }
 
+// Apply a pushed blocklist diff, or resync if it doesn't build on our version
+function applyBlocklistUpdate(message) {
+  if (focusState.blocklistVersion !== message.fromVersion) {
+    nativePort.postMessage({ type: "request_state" });
+    return;
+  }
+  const removed = new Set(message.removed);
+  focusState.blockedDomains = focusState.blockedDomains
+    .filter(domain => !removed.has(domain))
+    .concat(message.added);
+  focusState.hardBlockedDomains = (focusState.hardBlockedDomains || [])
+    .filter(domain => !removed.has(domain));
+  focusState.softLimitedDomains = (focusState.softLimitedDomains || [])
+    .filter(domain => !removed.has(domain));
+  if (message.allowedDomains) {
+    focusState.allowedDomains = message.allowedDomains;
+  }
+  focusState.blocklistVersion = message.version;
+  chrome.storage.local.set({ focusState });
+  // The diff doesn't say how strictly new domains are blocked
+  if (message.added.length > 0) {
+    nativePort.postMessage({ type: "request_state" });
+  }
+}
+
 function validateBudgetMessage(message) {
   return (
     message &&
@@ -38,20 +104,34 @@ function connectToNative() {
 
   try {
     nativePort = chrome.runtime.connectNative(NATIVE_HOST);
-    reconnectAttempts = 0;
 
     nativePort.onMessage.addListener((message) => {
       console.log("Native message:", message);
+      // connectNative succeeds even when the host fails to start, so only
+      // a reply proves the connection works
+      reconnectAttempts = 0;
       if (message && message.type === "state" && validateStateMessage(message)) {
         focusState = {
           active: message.focusActive,
           budgetRemaining: message.budgetRemaining,
-          blockedDomains: message.blockedDomains || []
+          blockedDomains: message.blockedDomains || [],
+          allowedDomains: message.allowedDomains || [],
+          hardBlockedDomains: message.hardBlockedDomains || [],
+          softLimitedDomains: message.softLimitedDomains || [],
+          budgetAllowedDomains: message.budgetAllowedDomains || [],
+          grantSecs: message.grantSecs,
+          blocklistVersion: message.blocklistVersion
         };
+        hasLiveState = true;
         chrome.storage.local.set({ focusState });
+      } else if (message && message.type === "blocklist_updated" && validateBlocklistMessage(message)) {
+        applyBlocklistUpdate(message);
       } else if (message && message.type === "budget_updated" && validateBudgetMessage(message)) {
         focusState.budgetRemaining = message.remaining;
         chrome.storage.local.set({ focusState });
+      } else if (message && message.type === "error") {
+        // The host skips the bad message and keeps the connection open
+        console.warn(`Native host rejected a message (${message.code}):`, message.message);
       } else if (message && message.type) {
         console.warn("Unhandled or invalid message type:", message.type);
       }
@@ -92,19 +172,44 @@ function loadCachedState() {
       console.error("Failed to load cached state:", chrome.runtime.lastError.message);
       return;
     }
-    if (result.focusState) {
+    // The host's answer is newer than anything cached
+    if (result.focusState && !hasLiveState) {
       focusState = result.focusState;
     }
   });
 }
 
-function sendActivity(url, title) {
+function loadProfileName() {
+  chrome.storage.local.get(["profileName"], (result) => {
+    if (!chrome.runtime.lastError && typeof result.profileName === "string") {
+      profileName = result.profileName;
+    }
+  });
+}
+
+chrome.storage.onChanged.addListener((changes, area) => {
+  if (area === "local" && changes.profileName) {
+    profileName = changes.profileName.newValue || "";
+  }
+});
+
+// Firefox containers take precedence over the profile label
+function profileFor(tab) {
+  if (tab && tab.cookieStoreId && tab.cookieStoreId !== "firefox-default") {
+    return tab.cookieStoreId;
+  }
+  return profileName || undefined;
+}
+
+function sendActivity(url, title, tab) {
   if (nativePort) {
     nativePort.postMessage({
       type: "activity",
       url,
       title,
-      timestamp: Date.now()
+      timestamp: Date.now(),
+      profile: profileFor(tab),
+      browser: BROWSER
     });
   }
 }
@@ -114,14 +219,36 @@ function isDomainBlocked(url) {
 
   try {
     const domain = new URL(url).hostname;
-    return focusState.blockedDomains.some(blocked => {
-      if (blocked.startsWith("*.")) {
-        // *.example.com should match sub.example.com but not notexample.com
-        const suffix = blocked.slice(1); // .example.com
-        return domain.endsWith(suffix) && (domain.length === suffix.length - 1 || domain[domain.length - suffix.length - 1] === ".");
-      }
-      return domain === blocked || domain.endsWith("." + blocked);
-    });
+    // Session exceptions win over the blocklist
+    const allowed = focusState.allowedDomains || [];
+    if (allowed.some(exception => domain === exception || domain.endsWith("." + exception))) {
+      return false;
+    }
+    return focusState.blockedDomains.some(blocked => matchesPattern(domain, blocked));
+  } catch {
+    return false;
+  }
+}
+
+function matchesPattern(domain, blocked) {
+  if (blocked.startsWith("*.")) {
+    // *.example.com should match sub.example.com but not notexample.com
+    const suffix = blocked.slice(1); // .example.com
+    return domain.endsWith(suffix) && (domain.length === suffix.length - 1 || domain[domain.length - suffix.length - 1] === ".");
+  }
+  return domain === blocked || domain.endsWith("." + blocked);
+}
+
+// Whether spending budget can open a blocked URL; anything not listed as
+// soft limited gets the hard block page
+function isSoftLimited(url) {
+  if (focusState.budgetRemaining <= 0) return false;
+  try {
+    const domain = new URL(url).hostname;
+    const hard = focusState.hardBlockedDomains || [];
+    if (hard.some(blocked => matchesPattern(domain, blocked))) return false;
+    const soft = focusState.softLimitedDomains || [];
+    return soft.some(blocked => matchesPattern(domain, blocked));
   } catch {
     return false;
   }
@@ -132,7 +259,7 @@ chrome.tabs.onActivated.addListener(async (activeInfo) => {
   try {
     const tab = await chrome.tabs.get(activeInfo.tabId);
     if (tab && tab.url) {
-      sendActivity(tab.url, tab.title || "");
+      sendActivity(tab.url, tab.title || "", tab);
     }
   } catch (e) {
     // Tab may not exist or be accessible (e.g., chrome:// pages)
@@ -143,7 +270,7 @@ chrome.tabs.onActivated.addListener(async (activeInfo) => {
 // Track URL changes
 chrome.tabs.onUpdated.addListener((tabId, changeInfo, tab) => {
   if (changeInfo.url) {
-    sendActivity(changeInfo.url, tab.title || "");
+    sendActivity(changeInfo.url, tab.title || "", tab);
   }
 });
 
@@ -154,9 +281,13 @@ chrome.webNavigation.onBeforeNavigate.addListener((details) => {
   if (isDomainBlocked(details.url)) {
     const blockedUrl = chrome.runtime.getURL("blocked.html") +
       "?url=" + encodeURIComponent(details.url) +
-      "&budget=" + focusState.budgetRemaining;
+      "&budget=" + focusState.budgetRemaining +
+      (isSoftLimited(details.url) ? "" : "&hard=1");
 
     chrome.tabs.update(details.tabId, { url: blockedUrl });
+    if (nativePort) {
+      nativePort.postMessage({ type: "blocked_attempt", url: details.url });
+    }
   }
 });
 
@@ -166,12 +297,29 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
     sendResponse(focusState);
   } else if (message.type === "use_distraction_time") {
     if (nativePort) {
-      nativePort.postMessage({ type: "use_distraction_time" });
+      const domain = typeof message.domain === "string" ? message.domain : undefined;
+      // The host caps the request at the user's configured grant size
+      const secs = typeof message.secs === "number" ? message.secs : focusState.grantSecs;
+      nativePort.postMessage({
+        type: "use_distraction_time",
+        domain,
+        profile: profileFor(sender.tab),
+        secs
+      });
     }
     sendResponse({ success: true });
   }
   return true;
 });
 
-// Initialize
+setInterval(() => {
+  if (nativePort) {
+    nativePort.postMessage({ type: "heartbeat" });
+  }
+}, HEARTBEAT_INTERVAL);
+
+// Initialize. Keep blocking with the last known state until the host
+// answers, so restarting the browser doesn't open blocked sites.
+loadProfileName();
+loadCachedState();
 connectToNative();
@@ -3,13 +3,18 @@ import { formatTime, isValidHttpUrl, getHostname } from './utils.js';
 const params = new URLSearchParams(window.location.search);
 const blockedUrl = params.get("url");
 const budget = Math.max(0, Math.floor(Number(params.get("budget")) || 0));
+const hardBlocked = params.get("hard") === "1";
 
 const validUrl = isValidHttpUrl(blockedUrl);
 document.getElementById("domain").textContent = validUrl ? getHostname(blockedUrl) : "Unknown site";
 
 document.getElementById("budget-time").textContent = formatTime(budget);
 
-if (budget <= 0 || !validUrl) {
+if (budget <= 0 || hardBlocked || !validUrl) {
+  if (hardBlocked && budget > 0) {
+    document.getElementById("hard-block-reason").textContent =
+      "This site is blocked for the rest of the session.";
+  }
   document.getElementById("soft-block").style.display = "none";
   document.getElementById("hard-block").style.display = "block";
 } else {
@@ -37,7 +42,8 @@ if (budget <= 0 || !validUrl) {
       if (remaining <= 0) {
         clearInterval(countdownInterval);
         countdownInterval = null;
-        chrome.runtime.sendMessage({ type: "use_distraction_time" }, () => {
+        const message = { type: "use_distraction_time", domain: getHostname(blockedUrl) };
+        chrome.runtime.sendMessage(message, () => {
           // Only redirect to validated HTTP(S) URLs to prevent open redirect
           if (validUrl) {
             window.location.href = blockedUrl;
@@ -36,3 +36,13 @@ chrome.runtime.sendMessage({ type: "get_state" }, (state) => {
     updateStatus(false, 0);
   }
 });
+
+const profileInput = document.getElementById("profile-name");
+if (profileInput) {
+  chrome.storage.local.get(["profileName"], (result) => {
+    profileInput.value = result.profileName || "";
+  });
+  profileInput.addEventListener("change", () => {
+    chrome.storage.local.set({ profileName: profileInput.value.trim() });
+  });
+}
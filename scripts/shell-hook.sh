@@ -0,0 +1,32 @@
+# Foxus terminal hook for bash and zsh.
+#
+# Source it from ~/.bashrc or ~/.zshrc:
+#
+#   source /path/to/foxus/scripts/shell-hook.sh
+#
+# On each prompt it tells Foxus the current directory and git repository, so
+# terminal time can be attributed to projects. Set FOXUS_NATIVE_HOST if
+# foxus-native-host isn't on your PATH.
+
+__foxus_report() {
+    __foxus_host="${FOXUS_NATIVE_HOST:-foxus-native-host}"
+    command -v "$__foxus_host" >/dev/null 2>&1 || return 0
+
+    # Report on directory changes, and at least every 10 minutes otherwise
+    if [ "$PWD" = "${__foxus_last_pwd:-}" ] &&
+        [ $((SECONDS - ${__foxus_last_report:-0})) -lt 600 ]; then
+        return 0
+    fi
+    __foxus_last_pwd="$PWD"
+    __foxus_last_report=$SECONDS
+
+    __foxus_repo="$(git rev-parse --show-toplevel 2>/dev/null)"
+    ("$__foxus_host" terminal-context "$PWD" "$__foxus_repo" >/dev/null 2>&1 &)
+}
+
+if [ -n "${ZSH_VERSION:-}" ]; then
+    typeset -ga precmd_functions
+    precmd_functions+=(__foxus_report)
+elif [ -n "${BASH_VERSION:-}" ]; then
+    PROMPT_COMMAND="__foxus_report${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
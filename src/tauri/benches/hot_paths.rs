@@ -0,0 +1,84 @@
+//! Benchmarks for the paths that run constantly or on every dashboard load:
+//! the tracker's activity insert, rule matching, and weekly stats.
+//!
+//! Run with `cargo bench`.
+
+mod support;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use foxus_lib::bench_support::{record_app_activity, weekly_productive_secs};
+use foxus_lib::categorizer::Categorizer;
+use foxus_lib::constants::SECS_PER_DAY;
+use support::{open_db, seed_activities, seed_rules, Rng, APPS, DOMAINS};
+use tempfile::tempdir;
+
+/// 2025-01-06, a Monday, as the first synthetic day.
+const FIRST_DAY: i64 = 1_736_121_600;
+
+const YEAR_DAYS: i64 = 365;
+
+/// One activity per minute of an eight-hour workday.
+const ACTIVITIES_PER_DAY: i64 = 480;
+
+const RULE_COUNT: usize = 1_000;
+
+fn activity_insert(c: &mut Criterion) {
+    let dir = tempdir().expect("create temp dir");
+    let db = open_db(dir.path());
+    let conn = db.connection();
+    let mut timestamp = FIRST_DAY;
+
+    c.bench_function("activity_insert", |b| {
+        b.iter(|| {
+            timestamp += 5;
+            record_app_activity(conn, timestamp, 5, "Code", "main.rs - foxus", 1)
+                .expect("insert activity");
+        });
+    });
+}
+
+fn categorizer_matching(c: &mut Criterion) {
+    let dir = tempdir().expect("create temp dir");
+    let db = open_db(dir.path());
+    seed_rules(db.connection(), &mut Rng::new(7), RULE_COUNT);
+    let categorizer = Categorizer::new(db.connection()).expect("load rules");
+
+    let mut group = c.benchmark_group("categorizer_1k_rules");
+    group.bench_function("match_app", |b| {
+        let mut rng = Rng::new(11);
+        b.iter_batched(
+            || *rng.pick(APPS),
+            |(app, title)| black_box(categorizer.match_app(app, Some(title))),
+            BatchSize::SmallInput,
+        );
+    });
+    group.bench_function("categorize_url", |b| {
+        let mut rng = Rng::new(13);
+        b.iter_batched(
+            || *rng.pick(DOMAINS),
+            |domain| black_box(categorizer.categorize_url(domain, None)),
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+fn weekly_stats(c: &mut Criterion) {
+    let dir = tempdir().expect("create temp dir");
+    let db = open_db(dir.path());
+    seed_activities(
+        db.connection(),
+        &mut Rng::new(17),
+        FIRST_DAY,
+        YEAR_DAYS,
+        ACTIVITIES_PER_DAY,
+    );
+    let now = FIRST_DAY + (YEAR_DAYS - 1) * SECS_PER_DAY + 17 * 3600;
+
+    c.bench_function("weekly_stats_year_of_data", |b| {
+        b.iter(|| weekly_productive_secs(db.connection(), black_box(now)).expect("weekly stats"));
+    });
+}
+
+criterion_group!(benches, activity_insert, categorizer_matching, weekly_stats);
+criterion_main!(benches);
@@ -0,0 +1,122 @@
+//! Deterministic synthetic data for the benchmarks: a year of tracked
+//! activity and rule sets of any size, generated from a fixed seed so runs
+//! compare like for like.
+
+use foxus_lib::bench_support::{record_app_activity, record_browser_activity};
+use foxus_lib::constants::SECS_PER_DAY;
+use foxus_lib::db::{migrations, Database};
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Apps with a typical window title, as the tracker would report them.
+pub const APPS: &[(&str, &str)] = &[
+    ("Code", "main.rs - foxus - Visual Studio Code"),
+    ("Terminal", "zsh - ~/src/foxus"),
+    ("Slack", "#general - Acme"),
+    ("Firefox", "Pull request #42 - GitHub"),
+    ("Spotify", "Discover Weekly"),
+    ("Figma", "Dashboard redesign"),
+    ("Mail", "Inbox (12)"),
+    ("Notion", "Sprint planning"),
+];
+
+pub const DOMAINS: &[&str] = &[
+    "github.com",
+    "docs.rs",
+    "stackoverflow.com",
+    "youtube.com",
+    "news.ycombinator.com",
+    "reddit.com",
+    "mail.google.com",
+    "crates.io",
+];
+
+/// First tracked second of each synthetic day.
+const WORKDAY_START_SECS: i64 = 9 * 3600;
+
+/// Tracked seconds in each synthetic day.
+const WORKDAY_SECS: i64 = 8 * 3600;
+
+/// Xorshift generator; quality doesn't matter here, repeatability does.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        let len = u64::try_from(items.len()).expect("slice length fits in u64");
+        let index = usize::try_from(self.next_u64() % len).expect("index fits in usize");
+        items.get(index).expect("index is below the length")
+    }
+}
+
+/// A migrated database in `dir`, seeded with the default categories and rules.
+pub fn open_db(dir: &Path) -> Database {
+    let db = Database::open(&dir.join("bench.db")).expect("open benchmark database");
+    migrations::run(db.connection()).expect("migrate benchmark database");
+    db
+}
+
+pub fn category_ids(conn: &Connection) -> Vec<i64> {
+    conn.prepare("SELECT id FROM categories")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .expect("read categories")
+}
+
+/// Fill `days` days starting at `first_day` with `per_day` activities spread
+/// evenly over an eight-hour workday, a quarter of them from the browser.
+pub fn seed_activities(conn: &Connection, rng: &mut Rng, first_day: i64, days: i64, per_day: i64) {
+    let categories = category_ids(conn);
+    let duration = WORKDAY_SECS / per_day;
+    let duration_secs = i32::try_from(duration).expect("activity duration fits in i32");
+    let tx = conn.unchecked_transaction().expect("begin seeding");
+
+    for day in 0..days {
+        let day_start = first_day + day * SECS_PER_DAY + WORKDAY_START_SECS;
+        for slot in 0..per_day {
+            let timestamp = day_start + slot * duration;
+            let category_id = *rng.pick(&categories);
+            if rng.next_u64().is_multiple_of(4) {
+                let domain = *rng.pick(DOMAINS);
+                record_browser_activity(&tx, timestamp, duration_secs, domain, category_id)
+            } else {
+                let (app, title) = rng.pick(APPS);
+                record_app_activity(&tx, timestamp, duration_secs, app, title, category_id)
+            }
+            .expect("insert synthetic activity");
+        }
+    }
+
+    tx.commit().expect("commit seeding");
+}
+
+/// Add `count` rules across app, title and domain patterns, none of which
+/// match the synthetic apps and domains, so lookups walk every rule.
+pub fn seed_rules(conn: &Connection, rng: &mut Rng, count: usize) {
+    let categories = category_ids(conn);
+    let tx = conn.unchecked_transaction().expect("begin seeding");
+
+    for i in 0..count {
+        let (pattern, match_type) = match i % 3 {
+            0 => (format!("bench-app-{i}"), "app"),
+            1 => (format!("bench title {i}"), "title"),
+            _ => (format!("bench-{i}.example"), "domain"),
+        };
+        tx.execute(
+            "INSERT INTO rules (pattern, match_type, category_id, priority) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![pattern, match_type, rng.pick(&categories), i % 20],
+        )
+        .expect("insert synthetic rule");
+    }
+
+    tx.commit().expect("commit seeding");
+}
@@ -0,0 +1,33 @@
+//! Run arbitrary byte streams through a native host backed by a scratch
+//! database, so the message handlers see whatever the parser lets through.
+
+#![no_main]
+
+use foxus_lib::categorizer::Categorizer;
+use foxus_lib::db::{migrations, Database};
+use foxus_lib::focus::FocusManager;
+use foxus_lib::native_host::NativeHost;
+use libfuzzer_sys::fuzz_target;
+use std::sync::{Arc, Mutex, OnceLock};
+use tempfile::TempDir;
+
+fn host() -> &'static NativeHost {
+    static HOST: OnceLock<(NativeHost, TempDir)> = OnceLock::new();
+    let (host, _dir) = HOST.get_or_init(|| {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = Database::open(&dir.path().join("fuzz.db")).expect("open database");
+        migrations::run(db.connection()).expect("migrate database");
+        let categorizer = Categorizer::new(db.connection()).expect("load rules");
+        let db = Arc::new(Mutex::new(db));
+        let focus_manager = Arc::new(FocusManager::new(Arc::clone(&db)));
+        let host = NativeHost::new(db, focus_manager, Arc::new(Mutex::new(categorizer)));
+        (host, dir)
+    });
+    host
+}
+
+fuzz_target!(|data: &[u8]| {
+    let host = host();
+    let mut input = data;
+    while host.handle_frame(&mut input).is_ok() {}
+});
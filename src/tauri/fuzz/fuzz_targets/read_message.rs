@@ -0,0 +1,12 @@
+//! Feed arbitrary bytes to the native messaging frame parser until the
+//! stream runs out. Any panic is a bug.
+
+#![no_main]
+
+use foxus_lib::native_host::read_message;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = data;
+    while read_message(&mut input).is_ok() {}
+});
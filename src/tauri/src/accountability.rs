@@ -0,0 +1,264 @@
+use crate::db::{with_connection, Database};
+use crate::local_time::local_today;
+use crate::models::{FocusSession, Setting};
+use crate::reports::WeeklyReport;
+use crate::secrets;
+use crate::tray::format_duration;
+use crate::webhook::post_json;
+use chrono::{Datelike, Local};
+use log::{error, info, warn};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+const ENABLED_SETTING: &str = "accountability.enabled";
+const PARTNER_EMAIL_SETTING: &str = "accountability.partner_email";
+const WEEKLY_REPORT_SETTING: &str = "accountability.weekly_report";
+const ABANDON_NOTICE_SETTING: &str = "accountability.abandon_notice";
+const REPORT_DAY_SETTING: &str = "accountability.report_day";
+/// Local date the last weekly report went out, so restarts don't resend it.
+const LAST_REPORT_SETTING: &str = "accountability.last_report";
+
+/// Keychain key of the webhook URL, which often carries a token (Slack and
+/// Discord webhooks do).
+pub const WEBHOOK_URL_SECRET: &str = "accountability.webhook_url";
+
+/// Weekly reports go out on Mondays (1=Monday, 7=Sunday) unless configured.
+pub const DEFAULT_REPORT_DAY: u32 = 1;
+
+/// Where and when to send accountability messages.
+///
+/// Messages go to the webhook as JSON; `partner_email` travels in the payload
+/// so relays (Zapier, Make, a mail gateway) can forward them. The webhook URL
+/// itself is kept in the keychain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountabilitySettings {
+    pub enabled: bool,
+    pub partner_email: Option<String>,
+    pub weekly_report: bool,
+    pub abandon_notice: bool,
+    pub report_day: u32,
+}
+
+impl AccountabilitySettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let flag =
+            |key: &str| -> Result<bool> { Ok(Setting::get(conn, key)?.as_deref() == Some("true")) };
+        Ok(Self {
+            enabled: flag(ENABLED_SETTING)?,
+            partner_email: Setting::get(conn, PARTNER_EMAIL_SETTING)?,
+            weekly_report: flag(WEEKLY_REPORT_SETTING)?,
+            abandon_notice: flag(ABANDON_NOTICE_SETTING)?,
+            report_day: Setting::get(conn, REPORT_DAY_SETTING)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_REPORT_DAY),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, WEEKLY_REPORT_SETTING, &self.weekly_report.to_string())?;
+        Setting::set(
+            conn,
+            ABANDON_NOTICE_SETTING,
+            &self.abandon_notice.to_string(),
+        )?;
+        Setting::set(conn, REPORT_DAY_SETTING, &self.report_day.to_string())?;
+        match &self.partner_email {
+            Some(email) => Setting::set(conn, PARTNER_EMAIL_SETTING, email),
+            None => Setting::delete(conn, PARTNER_EMAIL_SETTING),
+        }
+    }
+
+    /// The webhook to send a kind of message to, if accountability is on,
+    /// that kind is `wanted`, and a webhook is configured.
+    fn target(&self, wanted: bool) -> Option<String> {
+        if self.enabled && wanted {
+            secrets::read(WEBHOOK_URL_SECRET)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum AccountabilityMessage {
+    WeeklyReport {
+        partner_email: Option<String>,
+        text: String,
+        report: WeeklyReport,
+    },
+    SessionAbandoned {
+        partner_email: Option<String>,
+        text: String,
+        started_at: i64,
+        ended_at: i64,
+        planned_secs: i32,
+    },
+}
+
+fn abandoned_text(session: &FocusSession, ended_at: i64, planned_secs: i32) -> String {
+    let focused = i32::try_from(ended_at - session.started_at).unwrap_or(i32::MAX);
+    format!(
+        "A strict {} focus session was abandoned after {}.",
+        format_duration(planned_secs),
+        format_duration(focused)
+    )
+}
+
+/// Tell the partner right away when a strict session is ended early.
+pub fn on_session_ended(app: &AppHandle, session: &FocusSession) {
+    if !session.is_abandoned() {
+        return;
+    }
+    let (Some(ended_at), Some(planned_secs)) = (session.ended_at, session.planned_duration) else {
+        return;
+    };
+    let Some(settings) = load_settings(app) else {
+        return;
+    };
+    let Some(url) = settings.target(settings.abandon_notice) else {
+        return;
+    };
+
+    let message = AccountabilityMessage::SessionAbandoned {
+        partner_email: settings.partner_email.clone(),
+        text: abandoned_text(session, ended_at, planned_secs),
+        started_at: session.started_at,
+        ended_at,
+        planned_secs,
+    };
+    dispatch(url, message);
+}
+
+/// Send last week's report on the configured weekday, once per day.
+pub fn check_weekly_report(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let (today, _, _) = local_today();
+    let today_date = Local::now().date_naive();
+
+    let compiled = with_connection(&db, |conn| {
+        let settings = AccountabilitySettings::load(conn)?;
+        if !settings.enabled
+            || !settings.weekly_report
+            || today_date.weekday().number_from_monday() != settings.report_day
+            || Setting::get(conn, LAST_REPORT_SETTING)?.as_deref() == Some(today.as_str())
+        {
+            return Ok(None);
+        }
+        Setting::set(conn, LAST_REPORT_SETTING, &today)?;
+
+        let yesterday = today_date.pred_opt().unwrap_or(today_date);
+        let report = WeeklyReport::compile(conn, yesterday)?;
+        Ok(Some((settings, report)))
+    });
+
+    match compiled {
+        Ok(Some((settings, report))) => {
+            // Read outside the database lock, as the keychain may be slow
+            let Some(url) = settings.target(settings.weekly_report) else {
+                return;
+            };
+            let message = AccountabilityMessage::WeeklyReport {
+                partner_email: settings.partner_email,
+                text: report.to_text(),
+                report,
+            };
+            dispatch(url, message);
+        }
+        Ok(None) => {}
+        Err(e) => error!("Failed to compile weekly accountability report: {e}"),
+    }
+}
+
+fn load_settings(app: &AppHandle) -> Option<AccountabilitySettings> {
+    let db = app.try_state::<Arc<Mutex<Database>>>()?;
+    match with_connection(&db, AccountabilitySettings::load) {
+        Ok(settings) => Some(settings),
+        Err(e) => {
+            error!("Failed to load accountability settings: {e}");
+            None
+        }
+    }
+}
+
+/// Send in the background so slow webhooks never block the caller.
+fn dispatch(url: String, message: AccountabilityMessage) {
+    thread::spawn(move || match post_json(&url, &message) {
+        Ok(()) => info!("Sent accountability message"),
+        Err(e) => warn!("Failed to send accountability message: {e}"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_settings_default_off() {
+        let (db, _dir) = setup_test_db();
+        let settings = AccountabilitySettings::load(db.connection()).unwrap();
+
+        assert!(!settings.enabled);
+        assert_eq!(settings.report_day, DEFAULT_REPORT_DAY);
+        assert_eq!(settings.target(true), None);
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut settings = AccountabilitySettings {
+            enabled: true,
+            partner_email: Some("partner@example.com".into()),
+            weekly_report: true,
+            abandon_notice: true,
+            report_day: 5,
+        };
+        settings.save(conn).unwrap();
+        assert_eq!(AccountabilitySettings::load(conn).unwrap(), settings);
+        assert_eq!(settings.target(true), None);
+
+        secrets::set(WEBHOOK_URL_SECRET, "https://hooks.example.com/foxus").unwrap();
+        assert_eq!(
+            settings.target(true).as_deref(),
+            Some("https://hooks.example.com/foxus")
+        );
+        assert_eq!(settings.target(false), None);
+
+        settings.partner_email = None;
+        settings.save(conn).unwrap();
+        assert_eq!(
+            AccountabilitySettings::load(conn).unwrap().partner_email,
+            None
+        );
+    }
+
+    #[test]
+    fn test_abandoned_message_shape() {
+        let mut session = FocusSession::new(600, false);
+        session.started_at = 1000;
+
+        let message = AccountabilityMessage::SessionAbandoned {
+            partner_email: None,
+            text: abandoned_text(&session, 1000 + 600, 1500),
+            started_at: 1000,
+            ended_at: 1600,
+            planned_secs: 1500,
+        };
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(json.get("kind").unwrap(), "session_abandoned");
+        assert_eq!(
+            json.get("text").unwrap(),
+            "A strict 25m focus session was abandoned after 10m."
+        );
+    }
+}
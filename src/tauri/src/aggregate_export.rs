@@ -0,0 +1,203 @@
+//! Anonymized aggregates for sharing with a coach or a research study: time
+//! per category per hour or day, and nothing else. App names, window titles,
+//! URLs, domains and notes never go in.
+
+use crate::error::AppError;
+use crate::models::Activity;
+use chrono::{DateTime, Local, TimeZone};
+use rusqlite::{Connection, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const EXPORT_FILE_NAME: &str = "foxus-aggregates.csv";
+
+/// Label for time no category matched.
+const UNCATEGORIZED: &str = "Uncategorized";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Hour,
+    Day,
+}
+
+impl Bucket {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hour" => Some(Self::Hour),
+            "day" => Some(Self::Day),
+            _ => None,
+        }
+    }
+
+    /// Local label of the bucket holding `timestamp`.
+    fn label<Tz: TimeZone>(self, tz: &Tz, timestamp: i64) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let local = DateTime::from_timestamp(timestamp, 0)
+            .unwrap_or_default()
+            .with_timezone(tz);
+        match self {
+            Self::Hour => local.format("%Y-%m-%d %H:%M").to_string(),
+            Self::Day => local.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateRow {
+    /// Local start of the bucket: "2026-10-15" or "2026-10-15 14:00".
+    pub bucket: String,
+    pub category: String,
+    /// -1, 0 or 1; `None` for uncategorized time.
+    pub productivity: Option<i32>,
+    pub duration_secs: i64,
+}
+
+/// Seconds per category per bucket over `[start, end)`, in `tz`.
+pub fn aggregates<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    start: i64,
+    end: i64,
+    bucket: Bucket,
+) -> Result<Vec<AggregateRow>>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut totals: BTreeMap<(String, String), (Option<i32>, i64)> = BTreeMap::new();
+    for (hour, category, productivity, secs) in Activity::category_hour_totals(conn, start, end)? {
+        let key = (
+            bucket.label(tz, hour),
+            category.unwrap_or_else(|| UNCATEGORIZED.to_string()),
+        );
+        totals.entry(key).or_insert((productivity, 0)).1 += secs;
+    }
+    Ok(totals
+        .into_iter()
+        .map(
+            |((bucket, category), (productivity, duration_secs))| AggregateRow {
+                bucket,
+                category,
+                productivity,
+                duration_secs,
+            },
+        )
+        .collect())
+}
+
+/// Quote a CSV field if it needs it (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(rows: &[AggregateRow]) -> String {
+    let mut csv = String::from("bucket,category,productivity,duration_secs\n");
+    for row in rows {
+        let productivity = row.productivity.map(|p| p.to_string()).unwrap_or_default();
+        let _ = writeln!(
+            csv,
+            "{},{},{productivity},{}",
+            row.bucket,
+            csv_field(&row.category),
+            row.duration_secs
+        );
+    }
+    csv
+}
+
+/// Write the aggregates for `[start, end)` to a CSV file in `folder`.
+/// Returns the path written.
+pub fn export(
+    conn: &Connection,
+    folder: &Path,
+    start: i64,
+    end: i64,
+    bucket: Bucket,
+) -> std::result::Result<PathBuf, AppError> {
+    let csv = to_csv(&aggregates(conn, &Local, start, end, bucket)?);
+    let path = folder.join(EXPORT_FILE_NAME);
+    std::fs::write(&path, csv)
+        .map_err(|e| AppError::Internal(format!("failed to write export: {e}")))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+    use chrono::FixedOffset;
+
+    /// 2026-10-15 09:00 UTC
+    const MORNING: i64 = 1_792_054_800;
+
+    fn save(conn: &Connection, timestamp: i64, secs: i32, category_id: Option<i64>) {
+        let mut activity = Activity::new(
+            timestamp,
+            secs,
+            "app",
+            Some("Secret Client Portal"),
+            Some("Q3 layoffs draft"),
+        );
+        activity.domain = Some("acme-merger.example.com".into());
+        activity.category_id = category_id;
+        activity.save(conn).unwrap();
+    }
+
+    #[test]
+    fn test_aggregates_by_hour_and_day_without_identifying_data() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        save(conn, MORNING, 600, Some(coding.id));
+        save(conn, MORNING + 1200, 300, Some(coding.id));
+        save(conn, MORNING + 3600, 60, None);
+
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let hourly = aggregates(conn, &tz, MORNING, MORNING + 7200, Bucket::Hour).unwrap();
+        assert_eq!(
+            hourly,
+            vec![
+                AggregateRow {
+                    bucket: "2026-10-15 11:00".into(),
+                    category: "Coding".into(),
+                    productivity: Some(coding.productivity),
+                    duration_secs: 900,
+                },
+                AggregateRow {
+                    bucket: "2026-10-15 12:00".into(),
+                    category: UNCATEGORIZED.into(),
+                    productivity: None,
+                    duration_secs: 60,
+                },
+            ]
+        );
+
+        let daily = aggregates(conn, &tz, MORNING, MORNING + 7200, Bucket::Day).unwrap();
+        assert_eq!(daily.len(), 2);
+        assert!(daily.iter().all(|row| row.bucket == "2026-10-15"));
+
+        let csv = to_csv(&daily);
+        assert!(csv.starts_with("bucket,category,productivity,duration_secs\n"));
+        for private in ["Secret", "acme", "layoffs"] {
+            assert!(!csv.contains(private));
+        }
+    }
+
+    #[test]
+    fn test_csv_quotes_category_names() {
+        assert_eq!(csv_field("Deep work"), "Deep work");
+        assert_eq!(csv_field("Email, chat"), "\"Email, chat\"");
+        assert_eq!(csv_field("The \"Zone\""), "\"The \"\"Zone\"\"\"");
+    }
+}
@@ -0,0 +1,146 @@
+//! Log of focus-control actions (starting, ending, extending and unlocking
+//! sessions) with where each came from, to answer "why did my session end?".
+//!
+//! `FocusManager` writes the entries as it makes the change, so every path
+//! that controls a session is covered.
+
+use rusqlite::{params, Connection, Result};
+
+/// What was done to a focus session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Start,
+    /// Ended before or without a planned end.
+    End,
+    /// A timed session reached its planned end.
+    Complete,
+    Extend,
+    /// The emergency unlock countdown was started or checked.
+    UnlockRequested,
+    EmergencyUnlock,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::End => "end",
+            Self::Complete => "complete",
+            Self::Extend => "extend",
+            Self::UnlockRequested => "unlock_requested",
+            Self::EmergencyUnlock => "emergency_unlock",
+        }
+    }
+}
+
+/// Where a focus-control action came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    Tray,
+    /// The app's own windows, through Tauri commands.
+    Ui,
+    Schedule,
+    /// `foxus-native-host launcher` (Raycast, Alfred, scripts).
+    Cli,
+    /// Requests from other devices or services, e.g. mobile sync.
+    Api,
+    /// Foxus itself: timed sessions running out, crash recovery.
+    System,
+}
+
+impl Origin {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tray => "tray",
+            Self::Ui => "ui",
+            Self::Schedule => "schedule",
+            Self::Cli => "cli",
+            Self::Api => "api",
+            Self::System => "system",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub timestamp: i64,
+    pub action: String,
+    pub origin: String,
+    pub session_id: Option<i64>,
+    /// Extra context, e.g. "+10 min" or "planned 25 min".
+    pub detail: Option<String>,
+}
+
+pub fn record(
+    conn: &Connection,
+    timestamp: i64,
+    action: AuditAction,
+    origin: Origin,
+    session_id: Option<i64>,
+    detail: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audit_log (timestamp, action, origin, session_id, detail)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            timestamp,
+            action.as_str(),
+            origin.as_str(),
+            session_id,
+            detail
+        ],
+    )?;
+    Ok(())
+}
+
+/// Entries in `[start, end)`, newest first.
+pub fn find_in_range(conn: &Connection, start: i64, end: i64) -> Result<Vec<AuditEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, action, origin, session_id, detail FROM audit_log
+         WHERE timestamp >= ?1 AND timestamp < ?2
+         ORDER BY timestamp DESC, id DESC",
+    )?;
+    let rows = stmt.query_map(params![start, end], |row| {
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            action: row.get(2)?,
+            origin: row.get(3)?,
+            session_id: row.get(4)?,
+            detail: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_entries_in_range_newest_first() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        record(conn, 100, AuditAction::Start, Origin::Tray, None, None).unwrap();
+        record(
+            conn,
+            200,
+            AuditAction::Extend,
+            Origin::Ui,
+            None,
+            Some("+10 min"),
+        )
+        .unwrap();
+        record(conn, 300, AuditAction::End, Origin::Cli, None, None).unwrap();
+
+        let entries = find_in_range(conn, 100, 300).unwrap();
+        let actions: Vec<_> = entries
+            .iter()
+            .map(|e| (e.action.as_str(), e.origin.as_str()))
+            .collect();
+        assert_eq!(actions, vec![("extend", "ui"), ("start", "tray")]);
+        assert_eq!(entries.first().unwrap().detail.as_deref(), Some("+10 min"));
+    }
+}
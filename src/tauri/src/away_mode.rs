@@ -0,0 +1,250 @@
+//! Away mode for holidays and sick leave. Until the date it's set for, Foxus
+//! records no activity, starts no scheduled sessions and sets no daily goal,
+//! and the days away don't break streaks. It ends by itself on that date.
+
+use crate::db::{with_connection, Database};
+use crate::focus::FocusManager;
+use crate::local_time::{date_in, local_today};
+use crate::models::Setting;
+use crate::notifications::notify;
+use crate::tray::refresh_tray;
+use chrono::{NaiveDate, TimeZone};
+use log::{error, warn};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Id of the last period whose end was announced (or cut short), so each
+/// is announced once.
+const LAST_RESUMED_SETTING: &str = "away_mode.last_resumed";
+
+/// Event emitted when away mode starts or ends, with the current period or
+/// `null`.
+pub const AWAY_MODE_EVENT: &str = "away_mode_changed";
+
+/// Days away: from `start_date` up to, but not including, `until_date`, when
+/// everything resumes. Both are local dates in YYYY-MM-DD format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AwayPeriod {
+    pub id: i64,
+    pub start_date: String,
+    pub until_date: String,
+}
+
+impl AwayPeriod {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            start_date: row.get(1)?,
+            until_date: row.get(2)?,
+        })
+    }
+
+    /// The period covering `date`, if any.
+    pub fn covering(conn: &Connection, date: &str) -> Result<Option<Self>> {
+        conn.query_row(
+            "SELECT id, start_date, until_date FROM away_periods
+             WHERE start_date <= ?1 AND until_date > ?1
+             ORDER BY id DESC LIMIT 1",
+            params![date],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    fn latest(conn: &Connection) -> Result<Option<Self>> {
+        conn.query_row(
+            "SELECT id, start_date, until_date FROM away_periods ORDER BY id DESC LIMIT 1",
+            [],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    /// Go away from `today` until `until`. If already away, the current
+    /// period is moved to end on `until` instead.
+    pub fn start(conn: &Connection, today: &str, until: &str) -> Result<Self> {
+        if let Some(mut period) = Self::covering(conn, today)? {
+            conn.execute(
+                "UPDATE away_periods SET until_date = ?1 WHERE id = ?2",
+                params![until, period.id],
+            )?;
+            period.until_date = until.to_string();
+            return Ok(period);
+        }
+        conn.execute(
+            "INSERT INTO away_periods (start_date, until_date) VALUES (?1, ?2)",
+            params![today, until],
+        )?;
+        Ok(Self {
+            id: conn.last_insert_rowid(),
+            start_date: today.to_string(),
+            until_date: until.to_string(),
+        })
+    }
+
+    /// Come back early, so today counts as a normal day. Returns false if
+    /// not away.
+    pub fn end(conn: &Connection, today: &str) -> Result<bool> {
+        let Some(period) = Self::covering(conn, today)? else {
+            return Ok(false);
+        };
+        if period.start_date == today {
+            conn.execute("DELETE FROM away_periods WHERE id = ?1", params![period.id])?;
+        } else {
+            conn.execute(
+                "UPDATE away_periods SET until_date = ?1 WHERE id = ?2",
+                params![today, period.id],
+            )?;
+        }
+        Setting::set(conn, LAST_RESUMED_SETTING, &period.id.to_string())?;
+        Ok(true)
+    }
+
+    /// Local dates in `[first, last]` spent away.
+    pub fn dates_between(
+        conn: &Connection,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Result<HashSet<NaiveDate>> {
+        let first_str = first.format("%Y-%m-%d").to_string();
+        let last_str = last.format("%Y-%m-%d").to_string();
+        let mut stmt = conn.prepare(
+            "SELECT id, start_date, until_date FROM away_periods
+             WHERE start_date <= ?2 AND until_date > ?1",
+        )?;
+        let periods = stmt
+            .query_map(params![first_str, last_str], Self::from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .filter(|date| {
+                let date = date.format("%Y-%m-%d").to_string();
+                periods
+                    .iter()
+                    .any(|p| p.start_date <= date && date < p.until_date)
+            })
+            .collect())
+    }
+}
+
+/// Whether the local date of `now` in `tz` is spent away.
+pub fn is_away_at<Tz: TimeZone>(conn: &Connection, tz: &Tz, now: i64) -> Result<bool> {
+    let date = date_in(tz, now).format("%Y-%m-%d").to_string();
+    Ok(AwayPeriod::covering(conn, &date)?.is_some())
+}
+
+/// The period covering today, if away.
+pub fn current(conn: &Connection) -> Result<Option<AwayPeriod>> {
+    let (today, _, _) = local_today();
+    AwayPeriod::covering(conn, &today)
+}
+
+/// Whether today is spent away.
+pub fn is_away(conn: &Connection) -> Result<bool> {
+    Ok(current(conn)?.is_some())
+}
+
+/// Come back before away mode runs out. Returns false if not away.
+pub fn come_back(conn: &Connection) -> Result<bool> {
+    let (today, _, _) = local_today();
+    AwayPeriod::end(conn, &today)
+}
+
+/// Bring the rest of the app in line once away mode starts or ends:
+/// scheduled sessions stop or start, and the UI and tray update.
+pub fn on_away_mode_changed(app: &AppHandle, period: Option<&AwayPeriod>) {
+    if let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() {
+        if let Err(e) = focus_manager.check_schedules() {
+            error!("Failed to check focus schedules: {e}");
+        }
+    }
+    if let Err(e) = app.emit(AWAY_MODE_EVENT, period) {
+        warn!("Failed to emit away mode change: {e}");
+    }
+    refresh_tray(app);
+}
+
+/// The period that has just run out and hasn't been announced yet.
+fn resumed(conn: &Connection, today: &str) -> Result<Option<AwayPeriod>> {
+    let Some(period) = AwayPeriod::latest(conn)? else {
+        return Ok(None);
+    };
+    let last_resumed =
+        Setting::get(conn, LAST_RESUMED_SETTING)?.and_then(|v| v.parse::<i64>().ok());
+    if period.until_date.as_str() > today || last_resumed >= Some(period.id) {
+        return Ok(None);
+    }
+    Setting::set(conn, LAST_RESUMED_SETTING, &period.id.to_string())?;
+    Ok(Some(period))
+}
+
+/// Welcome the user back once away mode has run out.
+pub fn check_away_mode(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let (today, _, _) = local_today();
+
+    match with_connection(&db, |conn| resumed(conn, &today)) {
+        Ok(Some(_)) => {
+            notify(
+                app,
+                "Welcome back",
+                "Away mode has ended. Tracking and focus schedules are running again.",
+            );
+            on_away_mode_changed(app, None);
+        }
+        Ok(None) => {}
+        Err(e) => error!("Failed to check away mode: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn date(day: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_away_until_date() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        AwayPeriod::start(conn, "2026-10-15", "2026-10-19").unwrap();
+        assert!(AwayPeriod::covering(conn, "2026-10-14").unwrap().is_none());
+        assert!(AwayPeriod::covering(conn, "2026-10-18").unwrap().is_some());
+        assert!(AwayPeriod::covering(conn, "2026-10-19").unwrap().is_none());
+
+        // Going away again while away moves the return date
+        let period = AwayPeriod::start(conn, "2026-10-16", "2026-10-21").unwrap();
+        assert_eq!(period.start_date, "2026-10-15");
+        let away = AwayPeriod::dates_between(conn, date("2026-10-12"), date("2026-10-25")).unwrap();
+        assert_eq!(away.len(), 6);
+        assert!(away.contains(&date("2026-10-15")) && !away.contains(&date("2026-10-21")));
+
+        // Back early: the return day counts as a normal day
+        assert!(AwayPeriod::end(conn, "2026-10-17").unwrap());
+        assert!(AwayPeriod::covering(conn, "2026-10-17").unwrap().is_none());
+        assert!(!AwayPeriod::end(conn, "2026-10-17").unwrap());
+        assert!(resumed(conn, "2026-10-17").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resumption_is_announced_once() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        AwayPeriod::start(conn, "2026-10-15", "2026-10-19").unwrap();
+
+        assert!(resumed(conn, "2026-10-18").unwrap().is_none());
+        assert!(resumed(conn, "2026-10-19").unwrap().is_some());
+        assert!(resumed(conn, "2026-10-19").unwrap().is_none());
+    }
+}
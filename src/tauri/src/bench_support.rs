@@ -0,0 +1,47 @@
+//! Entry points for the criterion benchmarks in `benches/`, which can only
+//! reach the crate's public API. The app itself doesn't use this module.
+
+use crate::commands::stats::weekly_stats;
+use crate::models::Activity;
+use rusqlite::{Connection, Result};
+
+/// Save one app activity the way the tracker does after each poll.
+pub fn record_app_activity(
+    conn: &Connection,
+    timestamp: i64,
+    duration_secs: i32,
+    app_name: &str,
+    window_title: &str,
+    category_id: i64,
+) -> Result<()> {
+    let mut activity = Activity::new(
+        timestamp,
+        duration_secs,
+        "app",
+        Some(app_name),
+        Some(window_title),
+    );
+    activity.category_id = Some(category_id);
+    activity.save(conn)
+}
+
+/// Save one browser activity the way the extension's reports are stored.
+pub fn record_browser_activity(
+    conn: &Connection,
+    timestamp: i64,
+    duration_secs: i32,
+    domain: &str,
+    category_id: i64,
+) -> Result<()> {
+    let mut activity = Activity::new(timestamp, duration_secs, "browser", None, None);
+    activity.url = Some(format!("https://{domain}/"));
+    activity.domain = Some(domain.to_string());
+    activity.category_id = Some(category_id);
+    activity.save(conn)
+}
+
+/// Build the weekly stats `get_weekly_stats` returns at `now`. Returns the
+/// week's productive seconds so the result can't be optimized away.
+pub fn weekly_productive_secs(conn: &Connection, now: i64) -> Result<i32, String> {
+    weekly_stats(conn, now).map(|stats| stats.total_productive_secs)
+}
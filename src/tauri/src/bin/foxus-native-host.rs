@@ -2,6 +2,16 @@
 //!
 //! This binary runs as a standalone native messaging host for the Foxus Chrome extension.
 //! It communicates via stdin/stdout using Chrome's native messaging protocol.
+//!
+//! `foxus-native-host terminal-context <cwd> [repo]` records where a shell is
+//! and exits; the shell hook in `scripts/shell-hook.sh` calls it on each prompt.
+//!
+//! `foxus-native-host launcher` answers JSON-lines commands for launcher
+//! extensions; see `foxus_lib::launcher`.
+//!
+//! `--data-dir <path>` (or `FOXUS_DATA_DIR`) points any mode at another data
+//! directory, and `--portable` (or a `foxus-portable` file beside the binary)
+//! at the portable one.
 
 // Native messaging host uses stderr for logging because stdout is reserved
 // for the Chrome Native Messaging protocol (length-prefixed JSON).
@@ -12,16 +22,25 @@
 
 use foxus_lib::{
     categorizer::Categorizer,
+    config_file, data_dir,
     db::{migrations, Database},
     focus::FocusManager,
-    get_db_path,
+    get_data_dir, get_db_path,
+    launcher::Launcher,
     native_host::NativeHost,
-    safe_lock,
+    policy, safe_lock, terminal_context,
 };
 use std::sync::{Arc, Mutex};
 
 fn main() {
+    // Before anything looks for the database
+    let args = data_dir::apply_flags(std::env::args().skip(1));
+
     // Initialize with proper error handling
+    policy::load(&policy::policy_path());
+    if let Ok(data_dir) = get_data_dir() {
+        config_file::load(&data_dir);
+    }
     let db_path = match get_db_path() {
         Ok(path) => path,
         Err(e) => {
@@ -43,8 +62,30 @@ fn main() {
         std::process::exit(1);
     }
 
+    let mut args = args.into_iter();
+    let mode = args.next();
+    if mode.as_deref() == Some("terminal-context") {
+        let cwd = args.next().unwrap_or_default();
+        let repo = args.next();
+        if let Err(e) = terminal_context::record_now(db.connection(), &cwd, repo.as_deref()) {
+            eprintln!("Failed to record terminal context: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let db = Arc::new(Mutex::new(db));
 
+    if mode.as_deref() == Some("launcher") {
+        let focus_manager = Arc::new(FocusManager::new(Arc::clone(&db)));
+        let launcher = Launcher::new(db, focus_manager);
+        if let Err(e) = launcher.run(std::io::stdin().lock(), std::io::stdout().lock()) {
+            eprintln!("Launcher error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let categorizer = {
         let db_guard = safe_lock(&db, "Database");
         match Categorizer::new(db_guard.connection()) {
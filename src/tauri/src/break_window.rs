@@ -0,0 +1,40 @@
+use crate::data_dir::webview_dir;
+use tauri::{webview::WebviewWindowBuilder, AppHandle, Manager, WebviewUrl};
+
+/// Window label of the full-screen enforced break window.
+pub const BREAK_WINDOW_LABEL: &str = "break";
+
+/// The frontend renders the break screen instead of the dashboard for this hash.
+const BREAK_URL: &str = "index.html#break";
+
+/// Cover the screen with the break window, creating it if needed.
+pub fn show_break_window(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(BREAK_WINDOW_LABEL) {
+        window.show()?;
+        return window.set_focus();
+    }
+
+    // Not closable by the user: the break ends on its own or via the logged emergency skip
+    let mut builder =
+        WebviewWindowBuilder::new(app, BREAK_WINDOW_LABEL, WebviewUrl::App(BREAK_URL.into()))
+            .title("Foxus Break")
+            .fullscreen(true)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .closable(false);
+    if let Some(dir) = webview_dir() {
+        builder = builder.data_directory(dir);
+    }
+    builder.build()?;
+
+    Ok(())
+}
+
+/// Remove the break window, bypassing its close protection.
+pub fn close_break_window(app: &AppHandle) -> tauri::Result<()> {
+    match app.get_webview_window(BREAK_WINDOW_LABEL) {
+        Some(window) => window.destroy(),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,461 @@
+use crate::break_window::{close_break_window, show_break_window};
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::focus::get_current_day_and_time;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{BreakTrigger, EnforcedBreak, Setting};
+use crate::notifications::{is_quiet_now, notify};
+use crate::safe_lock;
+use crate::tracker::TrackerService;
+use log::{error, info, warn};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const ENABLED_SETTING: &str = "breaks.enabled";
+const WORK_MINUTES_SETTING: &str = "breaks.work_minutes";
+const QUIET_START_SETTING: &str = "breaks.quiet_start";
+const QUIET_END_SETTING: &str = "breaks.quiet_end";
+const ENFORCED_SETTING: &str = "breaks.enforced";
+const BREAK_MINUTES_SETTING: &str = "breaks.break_minutes";
+
+/// Continuous work allowed before the first reminder when unconfigured.
+pub const DEFAULT_WORK_MINUTES: i32 = 50;
+
+/// Length of an enforced break when unconfigured.
+pub const DEFAULT_BREAK_MINUTES: i32 = 5;
+
+/// Event emitted to the frontend when a break is due.
+pub const BREAK_REMINDER_EVENT: &str = "break-reminder";
+
+/// User-configurable break reminder settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakSettings {
+    pub enabled: bool,
+    pub work_minutes: i32,
+    /// Daily ("HH:MM", "HH:MM") window with no reminders. May wrap past midnight.
+    pub quiet_hours: Option<(String, String)>,
+    /// Cover the screen for `break_minutes` instead of only notifying, and
+    /// also after each completed timed focus session.
+    pub enforced: bool,
+    pub break_minutes: i32,
+}
+
+impl Default for BreakSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            work_minutes: DEFAULT_WORK_MINUTES,
+            quiet_hours: None,
+            enforced: false,
+            break_minutes: DEFAULT_BREAK_MINUTES,
+        }
+    }
+}
+
+impl BreakSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let defaults = Self::default();
+        let enabled = Setting::get(conn, ENABLED_SETTING)?.as_deref() != Some("false");
+        let work_minutes = Setting::get(conn, WORK_MINUTES_SETTING)?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.work_minutes);
+        let quiet_hours =
+            Setting::get(conn, QUIET_START_SETTING)?.zip(Setting::get(conn, QUIET_END_SETTING)?);
+        let enforced = Setting::get(conn, ENFORCED_SETTING)?.as_deref() == Some("true");
+        let break_minutes = Setting::get(conn, BREAK_MINUTES_SETTING)?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.break_minutes);
+
+        Ok(Self {
+            enabled,
+            work_minutes,
+            quiet_hours,
+            enforced,
+            break_minutes,
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, WORK_MINUTES_SETTING, &self.work_minutes.to_string())?;
+        Setting::set(conn, ENFORCED_SETTING, &self.enforced.to_string())?;
+        Setting::set(conn, BREAK_MINUTES_SETTING, &self.break_minutes.to_string())?;
+        if let Some((start, end)) = &self.quiet_hours {
+            Setting::set(conn, QUIET_START_SETTING, start)?;
+            Setting::set(conn, QUIET_END_SETTING, end)?;
+        } else {
+            Setting::delete(conn, QUIET_START_SETTING)?;
+            Setting::delete(conn, QUIET_END_SETTING)?;
+        }
+        Ok(())
+    }
+
+    /// Whether `time` (HH:MM) falls inside the quiet hours.
+    fn is_quiet(&self, time: &str) -> bool {
+        let Some((start, end)) = &self.quiet_hours else {
+            return false;
+        };
+        let (start, end) = (start.as_str(), end.as_str());
+        if start <= end {
+            start <= time && time < end
+        } else {
+            // Window wraps past midnight, e.g. 22:00-07:00
+            time >= start || time < end
+        }
+    }
+}
+
+/// Tracks when the user was last reminded so reminders don't repeat every tick.
+#[derive(Debug, Default)]
+pub struct BreakReminder {
+    last_reminded_at: Option<i64>,
+}
+
+impl BreakReminder {
+    /// Decide whether a break reminder is due.
+    ///
+    /// `active_since` is when the current stretch of non-idle activity began;
+    /// going idle clears it, which resets the countdown. Returns the seconds
+    /// worked without a break when a reminder should fire.
+    pub fn check(
+        &mut self,
+        settings: &BreakSettings,
+        active_since: Option<i64>,
+        now: i64,
+        time: &str,
+    ) -> Option<i64> {
+        if !settings.enabled || settings.is_quiet(time) {
+            return None;
+        }
+        let active_since = active_since?;
+        let counting_from = self
+            .last_reminded_at
+            .map_or(active_since, |reminded| reminded.max(active_since));
+
+        if now - counting_from < i64::from(settings.work_minutes) * 60 {
+            return None;
+        }
+        self.last_reminded_at = Some(now);
+        Some(now - active_since)
+    }
+
+    /// Skip the upcoming break: the next reminder waits another full work interval.
+    pub fn skip(&mut self, now: i64) {
+        self.last_reminded_at = Some(now);
+    }
+}
+
+/// App state wrapper for the break reminder.
+#[derive(Default)]
+pub struct BreakReminderState(pub Mutex<BreakReminder>);
+
+#[derive(Clone, Serialize)]
+struct BreakReminderPayload {
+    worked_secs: i64,
+}
+
+/// Remind the user to take a break if they have worked long enough without one.
+/// During quiet hours the reminder waits until they're over.
+pub fn check_break_reminder(app: &AppHandle) {
+    if is_quiet_now(app) {
+        return;
+    }
+    let (Some(db), Some(tracker), Some(reminder)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<TrackerService>>(),
+        app.try_state::<BreakReminderState>(),
+    ) else {
+        return;
+    };
+    let settings = match with_connection(&db, BreakSettings::load) {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to load break settings: {e}");
+            return;
+        }
+    };
+
+    let (_, time) = get_current_day_and_time();
+    let due = safe_lock(&reminder.0, "Break reminder").check(
+        &settings,
+        tracker.active_since(),
+        current_timestamp(),
+        &time,
+    );
+    match due {
+        Some(_) if settings.enforced => {
+            start_enforced_break(app, &settings, BreakTrigger::WorkLimit);
+        }
+        Some(worked_secs) => notify_break(app, worked_secs),
+        None => {}
+    }
+}
+
+/// Follow a completed timed focus session with an enforced break, if enabled.
+pub fn on_focus_session_completed(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    match with_connection(&db, BreakSettings::load) {
+        Ok(settings) if settings.enforced => {
+            start_enforced_break(app, &settings, BreakTrigger::Pomodoro);
+        }
+        Ok(_) => {}
+        Err(e) => error!("Failed to load break settings: {e}"),
+    }
+}
+
+/// Log an enforced break and cover the screen until it is over.
+fn start_enforced_break(app: &AppHandle, settings: &BreakSettings, trigger: BreakTrigger) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let started = with_connection(&db, |conn| {
+        if EnforcedBreak::find_active(conn)?.is_some() {
+            return Ok(None);
+        }
+        let mut enforced = EnforcedBreak::new(settings.break_minutes * 60, trigger);
+        enforced.save(conn)?;
+        Ok(Some(enforced))
+    });
+    let enforced = match started {
+        Ok(Some(enforced)) => enforced,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to start enforced break: {e}");
+            return;
+        }
+    };
+
+    info!(
+        "Starting enforced {} break for {}s",
+        trigger.as_str(),
+        enforced.duration_secs
+    );
+    if let Some(reminder) = app.try_state::<BreakReminderState>() {
+        safe_lock(&reminder.0, "Break reminder").skip(enforced.started_at);
+    }
+    if let Err(e) = show_break_window(app) {
+        error!("Failed to show break window: {e}");
+    }
+
+    let app = app.clone();
+    let duration = Duration::from_secs(u64::try_from(enforced.duration_secs).unwrap_or(0));
+    thread::spawn(move || {
+        thread::sleep(duration);
+        finish_enforced_break(&app);
+    });
+}
+
+/// Complete the active enforced break once its time is up and close the window.
+///
+/// Also runs on each scheduler tick so a break left open by a restart still ends.
+pub fn finish_enforced_break(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let finished = with_connection(&db, |conn| match EnforcedBreak::find_active(conn)? {
+        Some(mut enforced) if enforced.ends_at() <= current_timestamp() => {
+            enforced.complete(conn)?;
+            Ok(true)
+        }
+        Some(_) => Ok(false),
+        None => Ok(true),
+    });
+
+    match finished {
+        Ok(true) => {
+            if let Err(e) = close_break_window(app) {
+                error!("Failed to close break window: {e}");
+            }
+        }
+        Ok(false) => {}
+        Err(e) => error!("Failed to finish enforced break: {e}"),
+    }
+}
+
+/// End the active enforced break early. The skip is recorded on the break.
+pub fn emergency_skip_break(app: &AppHandle) -> std::result::Result<(), AppError> {
+    let db = app
+        .try_state::<Arc<Mutex<Database>>>()
+        .ok_or_else(|| AppError::Internal("database not initialized".into()))?;
+    with_connection(&db, |conn| {
+        if let Some(mut enforced) = EnforcedBreak::find_active(conn)? {
+            enforced.skip(conn)?;
+            warn!(
+                "Enforced break {} skipped after {}s of {}s",
+                enforced.id.unwrap_or(0),
+                enforced.skipped_at.unwrap_or(enforced.started_at) - enforced.started_at,
+                enforced.duration_secs
+            );
+        }
+        Ok(())
+    })?;
+
+    close_break_window(app)
+        .map_err(|e| AppError::Internal(format!("failed to close break window: {e}")))
+}
+
+fn notify_break(app: &AppHandle, worked_secs: i64) {
+    if let Err(e) = app.emit(BREAK_REMINDER_EVENT, BreakReminderPayload { worked_secs }) {
+        warn!("Failed to emit break reminder: {e}");
+    }
+    let body = format!(
+        "You've been working for {} minutes. Step away for a few minutes.",
+        worked_secs / 60
+    );
+    notify(app, "Time for a break", &body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const MINUTE: i64 = 60;
+
+    fn settings() -> BreakSettings {
+        BreakSettings {
+            enabled: true,
+            work_minutes: 50,
+            quiet_hours: None,
+            enforced: false,
+            break_minutes: 5,
+        }
+    }
+
+    #[test]
+    fn test_settings_default_when_unset() {
+        let (db, _dir) = setup_test_db();
+        assert_eq!(
+            BreakSettings::load(db.connection()).unwrap(),
+            BreakSettings::default()
+        );
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut saved = BreakSettings {
+            enabled: false,
+            work_minutes: 25,
+            quiet_hours: Some(("22:00".into(), "07:00".into())),
+            enforced: true,
+            break_minutes: 10,
+        };
+        saved.save(conn).unwrap();
+        assert_eq!(BreakSettings::load(conn).unwrap(), saved);
+
+        saved.quiet_hours = None;
+        saved.save(conn).unwrap();
+        assert_eq!(BreakSettings::load(conn).unwrap().quiet_hours, None);
+    }
+
+    #[test]
+    fn test_reminds_after_work_limit() {
+        let mut reminder = BreakReminder::default();
+        let start = 1000;
+
+        assert_eq!(
+            reminder.check(&settings(), Some(start), start + 49 * MINUTE, "10:00"),
+            None
+        );
+        assert_eq!(
+            reminder.check(&settings(), Some(start), start + 50 * MINUTE, "10:00"),
+            Some(50 * MINUTE)
+        );
+    }
+
+    #[test]
+    fn test_does_not_repeat_until_another_interval() {
+        let mut reminder = BreakReminder::default();
+        let start = 1000;
+
+        reminder.check(&settings(), Some(start), start + 50 * MINUTE, "10:00");
+        assert_eq!(
+            reminder.check(&settings(), Some(start), start + 51 * MINUTE, "10:00"),
+            None
+        );
+        assert_eq!(
+            reminder.check(&settings(), Some(start), start + 100 * MINUTE, "10:00"),
+            Some(100 * MINUTE)
+        );
+    }
+
+    #[test]
+    fn test_idle_resets_countdown() {
+        let mut reminder = BreakReminder::default();
+
+        assert_eq!(
+            reminder.check(&settings(), None, 100 * MINUTE, "10:00"),
+            None
+        );
+
+        // A new stretch started after the idle period counts from its own start
+        let restart = 100 * MINUTE;
+        assert_eq!(
+            reminder.check(&settings(), Some(restart), restart + 30 * MINUTE, "10:00"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_skip_postpones_reminder() {
+        let mut reminder = BreakReminder::default();
+        let start = 1000;
+
+        reminder.skip(start + 45 * MINUTE);
+        assert_eq!(
+            reminder.check(&settings(), Some(start), start + 50 * MINUTE, "10:00"),
+            None
+        );
+        assert!(reminder
+            .check(&settings(), Some(start), start + 95 * MINUTE, "10:00")
+            .is_some());
+    }
+
+    #[test]
+    fn test_disabled_never_reminds() {
+        let mut reminder = BreakReminder::default();
+        let disabled = BreakSettings {
+            enabled: false,
+            ..settings()
+        };
+
+        assert_eq!(
+            reminder.check(&disabled, Some(0), 500 * MINUTE, "10:00"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let overnight = BreakSettings {
+            quiet_hours: Some(("22:00".into(), "07:00".into())),
+            ..settings()
+        };
+        assert!(overnight.is_quiet("23:30"));
+        assert!(overnight.is_quiet("06:59"));
+        assert!(!overnight.is_quiet("07:00"));
+        assert!(!overnight.is_quiet("12:00"));
+
+        let lunch = BreakSettings {
+            quiet_hours: Some(("12:00".into(), "13:00".into())),
+            ..settings()
+        };
+        assert!(lunch.is_quiet("12:30"));
+        assert!(!lunch.is_quiet("13:00"));
+
+        let mut reminder = BreakReminder::default();
+        assert_eq!(
+            reminder.check(&overnight, Some(0), 500 * MINUTE, "23:00"),
+            None
+        );
+    }
+}
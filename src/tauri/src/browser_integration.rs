@@ -0,0 +1,101 @@
+use crate::models::Setting;
+use rusqlite::{Connection, Result};
+
+/// Settings key holding when the native host last heard from the extension.
+const LAST_SEEN_KEY: &str = "browser_integration.last_seen";
+
+/// The extension sends a heartbeat every minute while connected; missing
+/// three in a row means the connection is gone.
+pub const STALE_AFTER_SECS: i64 = 3 * 60;
+
+/// The host records at most one heartbeat per this many seconds, so a busy
+/// browsing session doesn't write a setting every few seconds.
+pub const HEARTBEAT_WRITE_INTERVAL_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Connected,
+    /// Heard from before, but not within `STALE_AFTER_SECS`.
+    Stale,
+    /// Never heard from on this database.
+    NotInstalled,
+}
+
+impl ConnectionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Connected => "connected",
+            Self::Stale => "stale",
+            Self::NotInstalled => "not_installed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrationStatus {
+    pub status: ConnectionStatus,
+    /// Unix timestamp of the last message, if any.
+    pub last_seen: Option<i64>,
+}
+
+/// Note that the native host received a message at `now`.
+pub fn record_heartbeat(conn: &Connection, now: i64) -> Result<()> {
+    Setting::set(conn, LAST_SEEN_KEY, &now.to_string())
+}
+
+pub fn last_seen(conn: &Connection) -> Result<Option<i64>> {
+    Ok(Setting::get(conn, LAST_SEEN_KEY)?.and_then(|v| v.parse().ok()))
+}
+
+/// Whether the browser extension is talking to Foxus as of `now`.
+pub fn status(conn: &Connection, now: i64) -> Result<IntegrationStatus> {
+    let last_seen = last_seen(conn)?;
+    let status = match last_seen {
+        None => ConnectionStatus::NotInstalled,
+        Some(seen) if now - seen <= STALE_AFTER_SECS => ConnectionStatus::Connected,
+        Some(_) => ConnectionStatus::Stale,
+    };
+    Ok(IntegrationStatus { status, last_seen })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_760_000_000;
+
+    #[test]
+    fn test_status_before_any_heartbeat() {
+        let (db, _dir) = setup_test_db();
+        let status = status(db.connection(), NOW).unwrap();
+        assert_eq!(status.status, ConnectionStatus::NotInstalled);
+        assert_eq!(status.last_seen, None);
+    }
+
+    #[test]
+    fn test_status_goes_stale() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        record_heartbeat(conn, NOW).unwrap();
+
+        let fresh = status(conn, NOW + STALE_AFTER_SECS).unwrap();
+        assert_eq!(fresh.status, ConnectionStatus::Connected);
+        assert_eq!(fresh.last_seen, Some(NOW));
+
+        let stale = status(conn, NOW + STALE_AFTER_SECS + 1).unwrap();
+        assert_eq!(stale.status, ConnectionStatus::Stale);
+        assert_eq!(stale.last_seen, Some(NOW));
+    }
+
+    #[test]
+    fn test_unreadable_heartbeat_counts_as_never_seen() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        Setting::set(conn, LAST_SEEN_KEY, "soon").unwrap();
+        assert_eq!(
+            status(conn, NOW).unwrap().status,
+            ConnectionStatus::NotInstalled
+        );
+    }
+}
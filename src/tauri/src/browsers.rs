@@ -0,0 +1,137 @@
+use crate::models::Activity;
+use rusqlite::{Connection, Result};
+
+/// Browsers Foxus tells apart: (id, lowercase app-name fragments). Checked
+/// in order, so forks come before the browser their names contain.
+const BROWSERS: &[(&str, &[&str])] = &[
+    ("edge", &["microsoft edge", "microsoft-edge", "msedge"]),
+    ("brave", &["brave"]),
+    ("opera", &["opera"]),
+    ("vivaldi", &["vivaldi"]),
+    ("chromium", &["chromium"]),
+    ("chrome", &["google chrome", "google-chrome", "chrome"]),
+    ("firefox", &["firefox"]),
+    ("safari", &["safari"]),
+];
+
+/// Arc's app name is a common word, so it has to match exactly.
+const ARC: &str = "arc";
+
+/// Browser id for a tracked app name, or `None` if it isn't a browser.
+pub fn browser_for_app(app_name: &str) -> Option<&'static str> {
+    let app_name = app_name.trim().to_lowercase();
+    if app_name == ARC {
+        return Some(ARC);
+    }
+    BROWSERS
+        .iter()
+        .find(|(_, fragments)| fragments.iter().any(|f| app_name.contains(f)))
+        .map(|(id, _)| *id)
+}
+
+/// The browser id an extension reported, if Foxus knows it.
+pub fn known_browser(reported: &str) -> Option<&'static str> {
+    let reported = reported.trim().to_lowercase();
+    BROWSERS
+        .iter()
+        .map(|(id, _)| *id)
+        .chain([ARC])
+        .find(|id| *id == reported)
+}
+
+/// Time spent in one browser over a range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserUsage {
+    pub browser: String,
+    pub duration_secs: i32,
+    /// Whether the time comes from the tracker; otherwise only the
+    /// extension saw this browser in the foreground.
+    pub tracked_by_app: bool,
+}
+
+/// Seconds per browser over `[start, end)`, most time first.
+///
+/// The tracker and the extension both record browser time, so adding them
+/// up would count it twice. The tracker's samples win for browsers it saw;
+/// extension reports fill in for browsers it didn't (e.g. tracking paused,
+/// or a browser whose app name isn't recognized).
+pub fn usage(conn: &Connection, start: i64, end: i64) -> Result<Vec<BrowserUsage>> {
+    let mut usage: Vec<_> = Activity::browser_totals(conn, start, end)?
+        .into_iter()
+        .map(|(browser, app_secs, extension_secs)| BrowserUsage {
+            browser,
+            duration_secs: if app_secs > 0 {
+                app_secs
+            } else {
+                extension_secs
+            },
+            tracked_by_app: app_secs > 0,
+        })
+        .collect();
+    usage.sort_by(|a, b| {
+        b.duration_secs
+            .cmp(&a.duration_secs)
+            .then_with(|| a.browser.cmp(&b.browser))
+    });
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_760_000_000;
+
+    fn save(conn: &Connection, source: &str, app: Option<&str>, browser: &str, secs: i32) {
+        let mut activity = Activity::new(NOW, secs, source, app, None);
+        activity.browser = Some(browser.to_string());
+        activity.save(conn).unwrap();
+    }
+
+    #[test]
+    fn test_browser_for_app() {
+        assert_eq!(browser_for_app("Google Chrome"), Some("chrome"));
+        assert_eq!(browser_for_app("google-chrome"), Some("chrome"));
+        assert_eq!(browser_for_app("Microsoft Edge"), Some("edge"));
+        assert_eq!(browser_for_app("Brave Browser"), Some("brave"));
+        assert_eq!(browser_for_app("Firefox"), Some("firefox"));
+        assert_eq!(browser_for_app("Arc"), Some("arc"));
+        assert_eq!(browser_for_app("Archive Utility"), None);
+        assert_eq!(browser_for_app("Code"), None);
+    }
+
+    #[test]
+    fn test_known_browser() {
+        assert_eq!(known_browser(" Firefox "), Some("firefox"));
+        assert_eq!(known_browser("arc"), Some("arc"));
+        assert_eq!(known_browser("netscape"), None);
+    }
+
+    #[test]
+    fn test_usage_prefers_tracker_time_per_browser() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        save(conn, "app", Some("Google Chrome"), "chrome", 600);
+        save(conn, "browser", None, "chrome", 30);
+        // Never in the tracker's samples, so the extension's time counts
+        save(conn, "browser", None, "firefox", 20);
+
+        let usage = usage(conn, NOW, NOW + 1).unwrap();
+        assert_eq!(
+            usage,
+            vec![
+                BrowserUsage {
+                    browser: "chrome".into(),
+                    duration_secs: 600,
+                    tracked_by_app: true,
+                },
+                BrowserUsage {
+                    browser: "firefox".into(),
+                    duration_secs: 20,
+                    tracked_by_app: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::models::Setting;
+use rusqlite::{Connection, Result};
+
+const ENABLED_SETTING: &str = "budget_accrual.enabled";
+const INTERVAL_SETTING: &str = "budget_accrual.interval_minutes";
+const REWARD_SETTING: &str = "budget_accrual.reward_secs";
+const MAX_EARNED_SETTING: &str = "budget_accrual.max_earned_secs";
+
+pub const DEFAULT_INTERVAL_MINUTES: i32 = 10;
+pub const DEFAULT_REWARD_SECS: i32 = 60;
+pub const DEFAULT_MAX_EARNED_SECS: i32 = 15 * 60;
+
+/// "Earn it" mode: productive time during a session adds to its distraction
+/// budget instead of the whole budget being fixed when it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccrualSettings {
+    pub enabled: bool,
+    /// Minutes of productive activity per reward.
+    pub interval_minutes: i32,
+    /// Budget seconds added per `interval_minutes`.
+    pub reward_secs: i32,
+    /// Most budget one session can earn, in seconds.
+    pub max_earned_secs: i32,
+}
+
+impl Default for AccrualSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: DEFAULT_INTERVAL_MINUTES,
+            reward_secs: DEFAULT_REWARD_SECS,
+            max_earned_secs: DEFAULT_MAX_EARNED_SECS,
+        }
+    }
+}
+
+impl AccrualSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let number = |key: &str, default: i32| -> Result<i32> {
+            Ok(Setting::get(conn, key)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default))
+        };
+        Ok(Self {
+            enabled: Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"),
+            interval_minutes: number(INTERVAL_SETTING, DEFAULT_INTERVAL_MINUTES)?,
+            reward_secs: number(REWARD_SETTING, DEFAULT_REWARD_SECS)?,
+            max_earned_secs: number(MAX_EARNED_SETTING, DEFAULT_MAX_EARNED_SECS)?,
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, INTERVAL_SETTING, &self.interval_minutes.to_string())?;
+        Setting::set(conn, REWARD_SETTING, &self.reward_secs.to_string())?;
+        Setting::set(conn, MAX_EARNED_SETTING, &self.max_earned_secs.to_string())
+    }
+
+    /// Budget seconds earned by `productive_secs` of productive time.
+    pub fn earned_for(&self, productive_secs: i32) -> i32 {
+        let interval_secs = self.interval_minutes.saturating_mul(60).max(1);
+        (productive_secs / interval_secs)
+            .saturating_mul(self.reward_secs)
+            .min(self.max_earned_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert_eq!(
+            AccrualSettings::load(conn).unwrap(),
+            AccrualSettings::default()
+        );
+
+        let settings = AccrualSettings {
+            enabled: true,
+            interval_minutes: 25,
+            reward_secs: 120,
+            max_earned_secs: 600,
+        };
+        settings.save(conn).unwrap();
+        assert_eq!(AccrualSettings::load(conn).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_earned_for_whole_intervals_up_to_cap() {
+        let settings = AccrualSettings {
+            enabled: true,
+            interval_minutes: 10,
+            reward_secs: 60,
+            max_earned_secs: 150,
+        };
+        assert_eq!(settings.earned_for(599), 0);
+        assert_eq!(settings.earned_for(600), 60);
+        assert_eq!(settings.earned_for(1250), 120);
+        assert_eq!(settings.earned_for(6000), 150);
+    }
+}
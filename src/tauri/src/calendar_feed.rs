@@ -0,0 +1,278 @@
+//! Read-only iCalendar feed of past focus sessions and upcoming focus
+//! schedules, written to `focus.ics` in the data directory so calendar apps
+//! can subscribe to it as a local file.
+
+use crate::constants::SECS_PER_DAY;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::get_data_dir;
+use crate::local_time::day_bounds;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{FocusSchedule, FocusSession, Setting};
+use crate::tray::format_duration;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime};
+use log::error;
+use rusqlite::{Connection, Result};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const ENABLED_SETTING: &str = "calendar.feed_enabled";
+
+const FEED_FILE_NAME: &str = "focus.ics";
+
+/// How far back finished sessions are included.
+const HISTORY_DAYS: i64 = 30;
+
+const WEEKDAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// Whether `focus.ics` is kept up to date. Off by default.
+pub fn feed_enabled(conn: &Connection) -> Result<bool> {
+    Ok(Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"))
+}
+
+pub fn set_feed_enabled(conn: &Connection, enabled: bool) -> Result<()> {
+    Setting::set(conn, ENABLED_SETTING, &enabled.to_string())
+}
+
+/// Where the feed is written, next to the database.
+pub fn feed_path() -> Result<PathBuf, AppError> {
+    let data_dir = get_data_dir().map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(data_dir.join(FEED_FILE_NAME))
+}
+
+/// Escape TEXT values (RFC 5545 section 3.3.11).
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn utc(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// `BYDAY` list for a schedule's days, e.g. `MO,WE,FR`.
+fn by_day(schedule: &FocusSchedule) -> Vec<&'static str> {
+    (1..=7)
+        .filter(|day| schedule.applies_to_day(*day))
+        .filter_map(|day| WEEKDAYS.get(usize::try_from(day - 1).ok()?).copied())
+        .collect()
+}
+
+/// First date from `today` on that the schedule runs.
+fn first_occurrence(schedule: &FocusSchedule, today: NaiveDate) -> Option<NaiveDate> {
+    today
+        .iter_days()
+        .take(7)
+        .find(|date| schedule.applies_to_day(date.weekday().number_from_monday()))
+}
+
+fn write_session(ics: &mut String, session: &FocusSession) {
+    let (Some(id), Some(ended_at)) = (session.id, session.ended_at) else {
+        return;
+    };
+    let distracted = format_duration(session.distraction_used);
+    let _ = write!(
+        ics,
+        "BEGIN:VEVENT\r\n\
+         UID:session-{id}@foxus\r\n\
+         DTSTAMP:{stamp}\r\n\
+         DTSTART:{start}\r\n\
+         DTEND:{stamp}\r\n\
+         SUMMARY:{summary}\r\n\
+         DESCRIPTION:{description}\r\n\
+         TRANSP:OPAQUE\r\n\
+         END:VEVENT\r\n",
+        stamp = utc(ended_at),
+        start = utc(session.started_at),
+        summary = if session.strict {
+            "Focus session (strict)"
+        } else {
+            "Focus session"
+        },
+        description = escape(&format!("{distracted} of distraction time used")),
+    );
+}
+
+fn write_schedule(ics: &mut String, schedule: &FocusSchedule, today: NaiveDate, stamp: &str) {
+    let Some(id) = schedule.id else {
+        return;
+    };
+    let days = by_day(schedule);
+    let (Ok(start_time), Ok(end_time), Some(date)) = (
+        NaiveTime::parse_from_str(&schedule.start_time, "%H:%M"),
+        NaiveTime::parse_from_str(&schedule.end_time, "%H:%M"),
+        first_occurrence(schedule, today),
+    ) else {
+        return;
+    };
+    // A block ending at or before it starts runs past midnight
+    let end_date = if end_time <= start_time {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    // Floating local times: the block follows the user's clock like the schedule does
+    let _ = write!(
+        ics,
+        "BEGIN:VEVENT\r\n\
+         UID:schedule-{id}@foxus\r\n\
+         DTSTAMP:{stamp}\r\n\
+         DTSTART:{start}\r\n\
+         DTEND:{end}\r\n\
+         RRULE:FREQ=WEEKLY;BYDAY={days}\r\n\
+         SUMMARY:Focus block\r\n\
+         TRANSP:OPAQUE\r\n\
+         END:VEVENT\r\n",
+        start = date.and_time(start_time).format("%Y%m%dT%H%M%S"),
+        end = end_date.and_time(end_time).format("%Y%m%dT%H%M%S"),
+        days = days.join(","),
+    );
+}
+
+/// Render the feed. `today_start` stamps the schedule events so the output
+/// only changes when sessions or schedules do (or the day rolls over).
+pub fn render(
+    sessions: &[FocusSession],
+    schedules: &[FocusSchedule],
+    today: NaiveDate,
+    today_start: i64,
+) -> String {
+    let mut ics = String::from(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//Foxus//Focus Sessions//EN\r\n\
+         CALSCALE:GREGORIAN\r\n\
+         X-WR-CALNAME:Foxus\r\n",
+    );
+    for session in sessions {
+        write_session(&mut ics, session);
+    }
+    let stamp = utc(today_start);
+    for schedule in schedules.iter().filter(|s| s.enabled) {
+        write_schedule(&mut ics, schedule, today, &stamp);
+    }
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
+
+/// Render the feed from the database as of now.
+pub fn current_feed(conn: &Connection) -> Result<String> {
+    let now = current_timestamp();
+    let today = Local::now().date_naive();
+    let (today_start, _) = day_bounds(today);
+    let sessions = FocusSession::find_ended_in_range(conn, now - HISTORY_DAYS * SECS_PER_DAY, now)?;
+    let schedules = FocusSchedule::find_enabled(conn)?;
+    Ok(render(&sessions, &schedules, today, today_start))
+}
+
+/// Write the feed to `feed_path()` if it changed. Returns the path.
+pub fn write_feed(conn: &Connection) -> Result<PathBuf, AppError> {
+    let path = feed_path()?;
+    let feed = current_feed(conn)?;
+    if std::fs::read_to_string(&path).ok().as_deref() != Some(feed.as_str()) {
+        // Write then rename so calendar apps never read a half-written file
+        let tmp = path.with_extension("ics.tmp");
+        std::fs::write(&tmp, feed)
+            .and_then(|()| std::fs::rename(&tmp, &path))
+            .map_err(|e| AppError::Internal(format!("failed to write calendar feed: {e}")))?;
+    }
+    Ok(path)
+}
+
+/// Keep `focus.ics` current while the feed is enabled.
+pub fn check_calendar_feed(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    match with_connection(&db, feed_enabled) {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error!("Failed to read calendar feed setting: {e}");
+            return;
+        }
+    }
+    let written = db
+        .lock()
+        .map_err(|_| AppError::LockPoisoned)
+        .and_then(|db| write_feed(db.connection()));
+    if let Err(e) = written {
+        error!("Failed to update calendar feed: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn session(id: i64, started_at: i64, ended_at: i64) -> FocusSession {
+        let mut session = FocusSession::new(600, false);
+        session.id = Some(id);
+        session.started_at = started_at;
+        session.ended_at = Some(ended_at);
+        session.distraction_used = 120;
+        session
+    }
+
+    fn schedule(days: &str, start: &str, end: &str) -> FocusSchedule {
+        let mut schedule = FocusSchedule::new(days, start, end, 600);
+        schedule.id = Some(7);
+        schedule
+    }
+
+    // A Thursday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()
+    }
+
+    #[test]
+    fn test_renders_sessions() {
+        let ics = render(&[session(3, 1_700_000_000, 1_700_003_000)], &[], today(), 0);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:session-3@foxus\r\n"));
+        assert!(ics.contains("DTSTART:20231114T221320Z\r\n"));
+        assert!(ics.contains("DTEND:20231114T230320Z\r\n"));
+        assert!(ics.contains("SUMMARY:Focus session\r\n"));
+    }
+
+    #[test]
+    fn test_renders_schedules_as_weekly_events() {
+        let ics = render(&[], &[schedule("1,3,5", "09:00", "11:30")], today(), 0);
+        assert!(ics.contains("UID:schedule-7@foxus\r\n"));
+        // Next Friday is the first occurrence from Thursday
+        assert!(ics.contains("DTSTART:20260116T090000\r\n"));
+        assert!(ics.contains("DTEND:20260116T113000\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r\n"));
+
+        let overnight = render(&[], &[schedule("4", "22:00", "01:00")], today(), 0);
+        assert!(overnight.contains("DTSTART:20260115T220000\r\n"));
+        assert!(overnight.contains("DTEND:20260116T010000\r\n"));
+
+        let mut disabled = schedule("4", "09:00", "10:00");
+        disabled.enabled = false;
+        assert!(!render(&[], &[disabled], today(), 0).contains("VEVENT"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_feed_setting() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(!feed_enabled(conn).unwrap());
+        set_feed_enabled(conn, true).unwrap();
+        assert!(feed_enabled(conn).unwrap());
+    }
+}
@@ -1,15 +1,36 @@
+use crate::focus::{day_and_time_at, get_current_day_and_time};
 use crate::models::{Category, MatchType, Rule};
+use crate::personal_time::PersonalTime;
 use rusqlite::Connection;
 use std::collections::HashMap;
 
+/// The category a capture was put in, and the rule that put it there
+/// (`None` when no rule matched and it fell back to Uncategorized).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryMatch {
+    pub rule_id: Option<i64>,
+    pub category_id: i64,
+}
+
+/// Day of week (1=Monday, 7=Sunday) and "HH:MM" time that rule conditions
+/// are checked against.
+pub type Clock = fn() -> (u32, String);
+
 pub struct Categorizer {
     rules: Vec<(Rule, Category)>,
     default_category_id: i64,
+    personal_time: PersonalTime,
+    clock: Clock,
 }
 
 impl Categorizer {
     pub fn new(conn: &Connection) -> rusqlite::Result<Self> {
-        let rules = Rule::find_all(conn)?;
+        Self::with_clock(conn, get_current_day_and_time)
+    }
+
+    /// Like `new`, reading the time for conditional rules from `clock`.
+    pub fn with_clock(conn: &Connection, clock: Clock) -> rusqlite::Result<Self> {
+        let rules = Rule::find_enabled(conn)?;
         let categories = Category::find_all(conn)?;
 
         // Use HashMap for O(1) category lookup instead of O(n) linear search
@@ -33,36 +54,103 @@ impl Categorizer {
         Ok(Self {
             rules: rules_with_categories,
             default_category_id,
+            personal_time: PersonalTime::load(conn)?,
+            clock,
         })
     }
 
     pub fn categorize_app(&self, app_name: &str, window_title: Option<&str>) -> i64 {
-        for (rule, _category) in &self.rules {
-            let matches = match rule.match_type {
-                MatchType::App => Self::pattern_matches(&rule.pattern, app_name),
-                MatchType::Title => {
-                    window_title.is_some_and(|t| Self::pattern_matches(&rule.pattern, t))
-                }
-                MatchType::Domain => false,
-            };
+        self.match_app(app_name, window_title).category_id
+    }
 
-            if matches {
-                return rule.category_id;
-            }
-        }
+    /// Like `categorize_app`, also reporting which rule matched.
+    pub fn match_app(&self, app_name: &str, window_title: Option<&str>) -> CategoryMatch {
+        let (day, time) = (self.clock)();
+        self.rules_at(day, time)
+            .find(|rule| Self::matches_app(rule, app_name, window_title))
+            .map_or(
+                CategoryMatch {
+                    rule_id: None,
+                    category_id: self.default_category_id,
+                },
+                |rule| CategoryMatch {
+                    rule_id: Some(rule.id),
+                    category_id: rule.category_id,
+                },
+            )
+    }
+
+    /// Categorize a browser domain. Rules limited to a profile only match
+    /// activity from that profile.
+    pub fn categorize_url(&self, domain: &str, profile: Option<&str>) -> i64 {
+        let (day, time) = (self.clock)();
+        self.rules_at(day, time)
+            .find(|rule| Self::matches_domain(rule, domain, profile))
+            .map_or(self.default_category_id, |rule| rule.category_id)
+    }
+
+    /// Every rule that matches an activity, highest priority first. Browser
+    /// activity (with a `domain`) is matched like `categorize_url`, the rest
+    /// like `categorize_app`, with conditions checked at `timestamp`. The
+    /// first rule decides the category; the rest are runner-ups it beat.
+    pub fn matching_rules(
+        &self,
+        app_name: &str,
+        window_title: Option<&str>,
+        domain: Option<&str>,
+        profile: Option<&str>,
+        timestamp: i64,
+    ) -> Vec<&Rule> {
+        let (day, time) = day_and_time_at(timestamp);
+        self.rules_at(day, time)
+            .filter(|rule| match domain {
+                Some(domain) => Self::matches_domain(rule, domain, profile),
+                None => Self::matches_app(rule, app_name, window_title),
+            })
+            .collect()
+    }
+
+    /// Rules in effect at `time` on `day`, in the order they're tried. In
+    /// personal time the personal-time rules come first; in work time
+    /// they're skipped.
+    fn rules_at(&self, day: u32, time: String) -> impl Iterator<Item = &Rule> {
+        let personal = &self.personal_time;
+        let all = || self.rules.iter().map(|(rule, _)| rule);
+        let personal_first = personal.is_personal_at(day, &time);
+        all()
+            .filter(move |rule| personal_first && personal.includes(rule.group_id))
+            .chain(all().filter(move |rule| !personal.includes(rule.group_id)))
+            .filter(move |rule| Self::in_effect(rule, day, &time))
+    }
 
-        self.default_category_id
+    fn in_effect(rule: &Rule, day: u32, time: &str) -> bool {
+        rule.condition
+            .as_ref()
+            .is_none_or(|condition| condition.applies_at(day, time))
     }
 
-    pub fn categorize_url(&self, domain: &str) -> i64 {
-        for (rule, _category) in &self.rules {
-            if rule.match_type == MatchType::Domain && Self::pattern_matches(&rule.pattern, domain)
-            {
-                return rule.category_id;
+    fn matches_app(rule: &Rule, app_name: &str, window_title: Option<&str>) -> bool {
+        if rule.profile.is_some() {
+            return false;
+        }
+        match rule.match_type {
+            MatchType::App => rule.patterns().any(|p| Self::pattern_matches(p, app_name)),
+            MatchType::Title => {
+                window_title.is_some_and(|t| rule.patterns().any(|p| Self::pattern_matches(p, t)))
             }
+            MatchType::Domain => false,
         }
+    }
 
-        self.default_category_id
+    /// Rules limited to a profile only match activity from that profile.
+    fn matches_domain(rule: &Rule, domain: &str, profile: Option<&str>) -> bool {
+        let in_profile = rule
+            .profile
+            .as_deref()
+            .is_none_or(|limit| Some(limit) == profile);
+        rule.match_type == MatchType::Domain
+            && in_profile
+            && rule.patterns().any(|p| Self::pattern_matches(p, domain))
     }
 
     fn pattern_matches(pattern: &str, text: &str) -> bool {
@@ -93,7 +181,7 @@ impl Categorizer {
     }
 
     pub fn reload(&mut self, conn: &Connection) -> rusqlite::Result<()> {
-        *self = Self::new(conn)?;
+        *self = Self::with_clock(conn, self.clock)?;
         Ok(())
     }
 }
@@ -101,6 +189,8 @@ impl Categorizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{RuleCondition, RuleGroup};
+    use crate::notifications::QuietRange;
     use crate::test_utils::setup_test_db;
 
     #[test]
@@ -138,6 +228,59 @@ mod tests {
         assert_eq!(category_id, coding.id);
     }
 
+    #[test]
+    fn test_match_app_reports_rule() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        let rule = Rule::create(conn, "*.rs*", MatchType::Title, coding.id, 10).unwrap();
+
+        let categorizer = Categorizer::new(conn).unwrap();
+        assert_eq!(
+            categorizer.match_app("Editor", Some("main.rs - foxus")),
+            CategoryMatch {
+                rule_id: Some(rule.id),
+                category_id: coding.id,
+            }
+        );
+        assert_eq!(
+            categorizer.match_app("Editor", Some("notes.txt")).rule_id,
+            None
+        );
+    }
+
+    #[test]
+    fn test_matching_rules_lists_runner_ups() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+        let broad =
+            Rule::create(conn, "*tube*", MatchType::Domain, id_of("Entertainment"), 5).unwrap();
+        let specific = Rule::create(
+            conn,
+            "lecture.tube.test",
+            MatchType::Domain,
+            id_of("Reference"),
+            30,
+        )
+        .unwrap();
+
+        let categorizer = Categorizer::new(conn).unwrap();
+        let ids: Vec<i64> = categorizer
+            .matching_rules("Firefox", None, Some("lecture.tube.test"), None, 0)
+            .iter()
+            .map(|rule| rule.id)
+            .collect();
+        assert_eq!(ids, vec![specific.id, broad.id]);
+    }
+
     #[test]
     fn test_categorize_domain() {
         let (db, _dir) = setup_test_db();
@@ -152,11 +295,168 @@ mod tests {
         Rule::create(conn, "reddit.com", MatchType::Domain, entertainment.id, 10).unwrap();
 
         let categorizer = Categorizer::new(conn).unwrap();
-        let category_id = categorizer.categorize_url("reddit.com");
+        let category_id = categorizer.categorize_url("reddit.com", None);
 
         assert_eq!(category_id, entertainment.id);
     }
 
+    #[test]
+    fn test_any_pattern_of_a_rule_matches() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+        let rule = Rule::create(
+            conn,
+            "cnn.com",
+            MatchType::Domain,
+            id_of("Entertainment"),
+            10,
+        )
+        .unwrap();
+        Rule::set_patterns(conn, rule.id, &["cnn.com", "*.bbc.*"]).unwrap();
+
+        let categorizer = Categorizer::new(conn).unwrap();
+        assert_eq!(
+            categorizer.categorize_url("www.bbc.co.uk", None),
+            id_of("Entertainment")
+        );
+        assert_eq!(
+            categorizer.categorize_url("cnn.com", None),
+            id_of("Entertainment")
+        );
+    }
+
+    #[test]
+    fn test_conditional_rule_applies_only_in_its_window() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+        Rule::create(
+            conn,
+            "cnn.com",
+            MatchType::Domain,
+            id_of("Entertainment"),
+            10,
+        )
+        .unwrap();
+        let lunch = Rule::create(
+            conn,
+            "cnn.com",
+            MatchType::Domain,
+            id_of("Communication"),
+            20,
+        )
+        .unwrap();
+        Rule::set_condition(
+            conn,
+            lunch.id,
+            Some(&RuleCondition {
+                days_of_week: "1,2,3,4,5".into(),
+                start_time: "12:00".into(),
+                end_time: "13:00".into(),
+            }),
+        )
+        .unwrap();
+
+        let at_lunch = Categorizer::with_clock(conn, || (3, "12:15".into())).unwrap();
+        assert_eq!(
+            at_lunch.categorize_url("cnn.com", None),
+            id_of("Communication")
+        );
+        let afternoon = Categorizer::with_clock(conn, || (3, "15:00".into())).unwrap();
+        assert_eq!(
+            afternoon.categorize_url("cnn.com", None),
+            id_of("Entertainment")
+        );
+    }
+
+    #[test]
+    fn test_personal_time_rules_take_over_outside_work_hours() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+        Rule::create(
+            conn,
+            "youtube.com",
+            MatchType::Domain,
+            id_of("Entertainment"),
+            50,
+        )
+        .unwrap();
+        let evenings = RuleGroup::create(conn, "Evenings").unwrap();
+        let leisure = Rule::create(
+            conn,
+            "youtube.com",
+            MatchType::Domain,
+            id_of("Communication"),
+            1,
+        )
+        .unwrap();
+        Rule::set_group(conn, leisure.id, Some(evenings.id)).unwrap();
+        PersonalTime {
+            group_id: Some(evenings.id),
+            work_hours: vec![QuietRange {
+                days_of_week: "1,2,3,4,5".into(),
+                start_time: "09:00".into(),
+                end_time: "18:00".into(),
+            }],
+        }
+        .save(conn)
+        .unwrap();
+
+        // A lower priority doesn't matter in personal time
+        let evening = Categorizer::with_clock(conn, || (3, "21:00".into())).unwrap();
+        assert_eq!(
+            evening.categorize_url("youtube.com", None),
+            id_of("Communication")
+        );
+        let at_work = Categorizer::with_clock(conn, || (3, "10:00".into())).unwrap();
+        assert_eq!(
+            at_work.categorize_url("youtube.com", None),
+            id_of("Entertainment")
+        );
+    }
+
+    #[test]
+    fn test_profile_rules_only_match_their_profile() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        let work = Rule::create(conn, "slack.com", MatchType::Domain, id_of("Coding"), 20).unwrap();
+        Rule::set_profile(conn, work.id, Some("Work")).unwrap();
+        Rule::create(
+            conn,
+            "slack.com",
+            MatchType::Domain,
+            id_of("Entertainment"),
+            10,
+        )
+        .unwrap();
+
+        let categorizer = Categorizer::new(conn).unwrap();
+        assert_eq!(
+            categorizer.categorize_url("slack.com", Some("Work")),
+            id_of("Coding")
+        );
+        assert_eq!(
+            categorizer.categorize_url("slack.com", Some("Personal")),
+            id_of("Entertainment")
+        );
+        assert_eq!(
+            categorizer.categorize_url("slack.com", None),
+            id_of("Entertainment")
+        );
+    }
+
     #[test]
     fn test_pattern_with_wildcard() {
         let (db, _dir) = setup_test_db();
@@ -172,8 +472,14 @@ mod tests {
 
         let categorizer = Categorizer::new(conn).unwrap();
 
-        assert_eq!(categorizer.categorize_url("www.github.com"), coding.id);
-        assert_eq!(categorizer.categorize_url("gist.github.io"), coding.id);
+        assert_eq!(
+            categorizer.categorize_url("www.github.com", None),
+            coding.id
+        );
+        assert_eq!(
+            categorizer.categorize_url("gist.github.io", None),
+            coding.id
+        );
     }
 
     #[test]
@@ -0,0 +1,284 @@
+//! Offline naive Bayes classifier trained on the user's own rule-matched activity.
+//!
+//! Nothing leaves the machine, so it runs daily by default; turn it off with
+//! the `suggestions.offline` setting.
+
+use super::{suggest_rules, Classification, Classifier};
+use crate::constants::SECS_PER_DAY;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Category, Setting, UncategorizedSource};
+use log::{error, info};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+pub const OFFLINE_SUGGESTIONS_SETTING: &str = "suggestions.offline";
+/// Local date of the last automatic run, so it happens once a day.
+const LAST_RUN_SETTING: &str = "suggestions.offline_last_run";
+
+/// Train on the last 90 days of categorized activity.
+const TRAINING_WINDOW_SECS: i64 = 90 * SECS_PER_DAY;
+const MAX_TRAINING_SAMPLES: i64 = 20_000;
+
+/// Too little history gives confident nonsense; stay quiet until there's enough.
+const MIN_TRAINING_SAMPLES: u32 = 50;
+
+/// Only suggest when the winning category is clearly ahead.
+const MIN_CONFIDENCE: f64 = 0.75;
+
+pub struct NaiveBayesClassifier {
+    /// Token counts per category id.
+    token_counts: HashMap<i64, HashMap<String, u32>>,
+    /// Total tokens seen per category id.
+    token_totals: HashMap<i64, u32>,
+    /// Training samples per category id.
+    sample_counts: HashMap<i64, u32>,
+    total_samples: u32,
+    vocabulary: HashSet<String>,
+}
+
+/// Lowercased words from app names, titles, and domains ("docs.rs" -> "docs", "rs").
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() >= 2 && !token.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+}
+
+fn count(len: usize) -> f64 {
+    f64::from(u32::try_from(len).unwrap_or(u32::MAX))
+}
+
+impl NaiveBayesClassifier {
+    /// Build a model from labelled activities.
+    pub fn train(activities: &[Activity]) -> Self {
+        let mut model = Self {
+            token_counts: HashMap::new(),
+            token_totals: HashMap::new(),
+            sample_counts: HashMap::new(),
+            total_samples: 0,
+            vocabulary: HashSet::new(),
+        };
+
+        for activity in activities {
+            let Some(category_id) = activity.category_id else {
+                continue;
+            };
+            let texts = [&activity.app_name, &activity.window_title, &activity.domain];
+            let tokens: Vec<String> = texts
+                .into_iter()
+                .flatten()
+                .flat_map(|text| tokenize(text))
+                .collect();
+
+            let counts = model.token_counts.entry(category_id).or_default();
+            for token in &tokens {
+                *counts.entry(token.clone()).or_default() += 1;
+            }
+            *model.token_totals.entry(category_id).or_default() +=
+                u32::try_from(tokens.len()).unwrap_or(u32::MAX);
+            *model.sample_counts.entry(category_id).or_default() += 1;
+            model.total_samples += 1;
+            model.vocabulary.extend(tokens);
+        }
+        model
+    }
+
+    /// Train on recent categorized activity from the database.
+    pub fn from_history(conn: &Connection, now: i64) -> rusqlite::Result<Self> {
+        let activities =
+            Activity::find_categorized(conn, now - TRAINING_WINDOW_SECS, MAX_TRAINING_SAMPLES)?;
+        Ok(Self::train(&activities))
+    }
+
+    /// Whether there's enough history to make suggestions.
+    pub fn is_trained(&self) -> bool {
+        self.total_samples >= MIN_TRAINING_SAMPLES
+    }
+
+    /// Most likely category and its posterior probability.
+    fn predict(&self, tokens: &[String]) -> Option<(i64, f64)> {
+        let known: Vec<&String> = tokens
+            .iter()
+            .filter(|t| self.vocabulary.contains(*t))
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+
+        let vocabulary_size = count(self.vocabulary.len());
+        let total_samples = f64::from(self.total_samples);
+        let log_scores: Vec<(i64, f64)> = self
+            .sample_counts
+            .iter()
+            .map(|(&category_id, &samples)| {
+                let counts = self.token_counts.get(&category_id);
+                let total = f64::from(self.token_totals.get(&category_id).copied().unwrap_or(0));
+                let likelihood: f64 = known
+                    .iter()
+                    .map(|token| {
+                        let seen = counts.and_then(|c| c.get(*token)).copied().unwrap_or(0);
+                        // Laplace smoothing so unseen tokens don't zero out a category
+                        ((f64::from(seen) + 1.0) / (total + vocabulary_size)).ln()
+                    })
+                    .sum();
+                (
+                    category_id,
+                    (f64::from(samples) / total_samples).ln() + likelihood,
+                )
+            })
+            .collect();
+
+        // Softmax over log scores, shifted by the max for numerical stability
+        let max = log_scores
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let normalizer: f64 = log_scores.iter().map(|(_, s)| (s - max).exp()).sum();
+        log_scores
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(category_id, score)| (category_id, (score - max).exp() / normalizer))
+    }
+}
+
+impl Classifier for NaiveBayesClassifier {
+    fn name(&self) -> &'static str {
+        "bayes"
+    }
+
+    fn classify(
+        &self,
+        sources: &[UncategorizedSource],
+        _categories: &[Category],
+    ) -> Result<Vec<Classification>, AppError> {
+        if !self.is_trained() {
+            return Ok(Vec::new());
+        }
+
+        Ok(sources
+            .iter()
+            .filter_map(|source| {
+                let tokens: Vec<String> = tokenize(&source.name)
+                    .chain(source.sample_title.iter().flat_map(|t| tokenize(t)))
+                    .collect();
+                let (category_id, confidence) = self.predict(&tokens)?;
+                (confidence >= MIN_CONFIDENCE).then(|| Classification {
+                    match_type: source.match_type,
+                    pattern: source.name.clone(),
+                    category_id,
+                    confidence,
+                })
+            })
+            .collect())
+    }
+}
+
+pub fn offline_suggestions_enabled(conn: &Connection) -> rusqlite::Result<bool> {
+    Ok(Setting::get(conn, OFFLINE_SUGGESTIONS_SETTING)?.as_deref() != Some("false"))
+}
+
+/// Train on history and queue suggestions for uncategorized time.
+pub fn suggest_offline(db: &Arc<Mutex<Database>>) -> Result<usize, AppError> {
+    let now = current_timestamp();
+    let classifier = with_connection(db, |conn| NaiveBayesClassifier::from_history(conn, now))?;
+    suggest_rules(db, &classifier, now)
+}
+
+/// Refresh offline suggestions once a day.
+pub fn check_offline_suggestions(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let (today, _, _) = local_today();
+
+    let due = with_connection(&db, |conn| {
+        if !offline_suggestions_enabled(conn)?
+            || Setting::get(conn, LAST_RUN_SETTING)?.as_deref() == Some(today.as_str())
+        {
+            return Ok(false);
+        }
+        Setting::set(conn, LAST_RUN_SETTING, &today)?;
+        Ok(true)
+    });
+
+    match due.and_then(|due| if due { suggest_offline(&db) } else { Ok(0) }) {
+        Ok(0) => {}
+        Ok(added) => info!("Queued {added} offline rule suggestions"),
+        Err(e) => error!("Failed to compute offline rule suggestions: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MatchType;
+
+    const CODING: i64 = 1;
+    const ENTERTAINMENT: i64 = 2;
+
+    fn labelled(app: &str, title: &str, category_id: i64) -> Activity {
+        let mut activity = Activity::new(0, 5, "app", Some(app), Some(title));
+        activity.category_id = Some(category_id);
+        activity
+    }
+
+    fn trained() -> NaiveBayesClassifier {
+        let mut activities = Vec::new();
+        for _ in 0..30 {
+            activities.push(labelled("Code", "main.rs - foxus - rust cargo", CODING));
+            activities.push(labelled(
+                "Firefox",
+                "Funny cats - YouTube video",
+                ENTERTAINMENT,
+            ));
+        }
+        NaiveBayesClassifier::train(&activities)
+    }
+
+    fn source(name: &str, title: Option<&str>) -> UncategorizedSource {
+        UncategorizedSource {
+            match_type: MatchType::App,
+            name: name.into(),
+            sample_title: title.map(Into::into),
+            duration_secs: 600,
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens: Vec<String> = tokenize("docs.rs - Rust 2024 | x").collect();
+        assert_eq!(tokens, vec!["docs", "rs", "rust"]);
+    }
+
+    #[test]
+    fn test_classifies_by_title_words() {
+        let classifier = trained();
+        let sources = [
+            source("Zed", Some("lib.rs - foxus")),
+            source("mpv", Some("cats video")),
+            source("Notes", Some("groceries")),
+        ];
+
+        let result = classifier.classify(&sources, &[]).unwrap();
+        let picked: Vec<(&str, i64)> = result
+            .iter()
+            .map(|c| (c.pattern.as_str(), c.category_id))
+            .collect();
+        assert_eq!(picked, vec![("Zed", CODING), ("mpv", ENTERTAINMENT)]);
+        assert!(result.iter().all(|c| c.confidence >= MIN_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_quiet_without_enough_history() {
+        let classifier =
+            NaiveBayesClassifier::train(&[labelled("Code", "main.rs - foxus", CODING)]);
+        assert!(!classifier.is_trained());
+
+        let sources = [source("Zed", Some("lib.rs - foxus"))];
+        assert!(classifier.classify(&sources, &[]).unwrap().is_empty());
+    }
+}
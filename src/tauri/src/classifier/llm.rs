@@ -0,0 +1,330 @@
+//! Classifier backed by an OpenAI-compatible chat completions endpoint.
+//!
+//! Works with local servers (Ollama, LM Studio, llama.cpp) as well as hosted
+//! APIs. Off by default: nothing is sent until the user enables it and
+//! configures an endpoint.
+
+use super::{Classification, Classifier};
+use crate::error::AppError;
+use crate::models::{Category, MatchType, Setting, UncategorizedSource};
+use crate::secrets;
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::time::Duration;
+
+const ENABLED_SETTING: &str = "llm.enabled";
+const ENDPOINT_SETTING: &str = "llm.endpoint";
+const MODEL_SETTING: &str = "llm.model";
+
+/// Keychain key of the API key, sent as a bearer token. Local servers
+/// usually don't need one.
+pub const API_KEY_SECRET: &str = "llm.api_key";
+
+/// Model requested when none is configured.
+pub const DEFAULT_MODEL: &str = "llama3.2";
+
+/// Local models can be slow to answer a batch, so allow more than the usual 10s.
+const REQUEST_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Confidence assumed when the model doesn't give one.
+const DEFAULT_CONFIDENCE: f64 = 0.5;
+
+const SYSTEM_PROMPT: &str = "You sort computer activity into productivity categories. \
+Reply with JSON only, in the form \
+{\"suggestions\":[{\"kind\":\"app\",\"name\":\"...\",\"category\":\"...\",\"confidence\":0.8}]}. \
+Use the exact kind and name given, and only the listed categories. \
+Leave out anything you are unsure about.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LlmSettings {
+    pub enabled: bool,
+    /// Full chat completions URL, e.g. `http://localhost:11434/v1/chat/completions`.
+    pub endpoint: Option<String>,
+    pub model: String,
+}
+
+impl LlmSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            enabled: Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"),
+            endpoint: Setting::get(conn, ENDPOINT_SETTING)?,
+            model: Setting::get(conn, MODEL_SETTING)?.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, MODEL_SETTING, &self.model)?;
+        match &self.endpoint {
+            Some(endpoint) => Setting::set(conn, ENDPOINT_SETTING, endpoint),
+            None => Setting::delete(conn, ENDPOINT_SETTING),
+        }
+    }
+
+    /// The configured classifier, if the user has opted in.
+    pub fn classifier(&self) -> Option<LlmClassifier> {
+        let endpoint = self.endpoint.clone().filter(|_| self.enabled)?;
+        Some(LlmClassifier {
+            endpoint,
+            model: self.model.clone(),
+            api_key: secrets::read(API_KEY_SECRET),
+        })
+    }
+}
+
+pub struct LlmClassifier {
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    temperature: f64,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct SuggestionList {
+    suggestions: Vec<LlmSuggestion>,
+}
+
+#[derive(Deserialize)]
+struct LlmSuggestion {
+    kind: String,
+    name: String,
+    category: String,
+    confidence: Option<f64>,
+}
+
+impl Classifier for LlmClassifier {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+
+    fn classify(
+        &self,
+        sources: &[UncategorizedSource],
+        categories: &[Category],
+    ) -> std::result::Result<Vec<Classification>, AppError> {
+        let prompt = build_prompt(sources, categories);
+        let request = ChatRequest {
+            model: &self.model,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: SYSTEM_PROMPT,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: &prompt,
+                },
+            ],
+            temperature: 0.0,
+        };
+
+        let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+        let mut call = agent
+            .post(&self.endpoint)
+            .set("User-Agent", concat!("foxus/", env!("CARGO_PKG_VERSION")));
+        if let Some(key) = &self.api_key {
+            call = call.set("Authorization", &format!("Bearer {key}"));
+        }
+        let response: ChatResponse = call
+            .send_json(&request)
+            .map_err(|e| AppError::Internal(format!("LLM request failed: {e}")))?
+            .into_json()
+            .map_err(|e| AppError::Internal(format!("invalid LLM response: {e}")))?;
+
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .unwrap_or_default();
+        parse_suggestions(&content, sources, categories)
+    }
+}
+
+/// List the categories and uncategorized sources for the model.
+fn build_prompt(sources: &[UncategorizedSource], categories: &[Category]) -> String {
+    let mut prompt = String::from("Categories:");
+    for category in categories.iter().filter(|c| c.name != "Uncategorized") {
+        let _ = write!(prompt, "\n- {}", category.name);
+    }
+
+    prompt.push_str("\n\nActivity to categorize:");
+    for source in sources {
+        let _ = write!(
+            prompt,
+            "\n- {}: {}",
+            source.match_type.as_str(),
+            source.name
+        );
+        if let Some(title) = &source.sample_title {
+            let _ = write!(prompt, " (window: {title})");
+        }
+    }
+    prompt
+}
+
+/// Turn the model's reply into classifications, keeping only entries that
+/// name a source we asked about and a category that exists.
+fn parse_suggestions(
+    content: &str,
+    sources: &[UncategorizedSource],
+    categories: &[Category],
+) -> std::result::Result<Vec<Classification>, AppError> {
+    // Models often wrap JSON in a code fence or a sentence; take the outermost object
+    let json = content
+        .find('{')
+        .zip(content.rfind('}'))
+        .and_then(|(start, end)| content.get(start..=end))
+        .ok_or_else(|| AppError::Internal("LLM response contained no JSON".into()))?;
+    let list: SuggestionList = serde_json::from_str(json)
+        .map_err(|e| AppError::Internal(format!("invalid LLM response: {e}")))?;
+
+    Ok(list
+        .suggestions
+        .into_iter()
+        .filter_map(|suggestion| {
+            let match_type = MatchType::from_str(&suggestion.kind)?;
+            let source = sources.iter().find(|s| {
+                s.match_type == match_type && s.name.eq_ignore_ascii_case(&suggestion.name)
+            })?;
+            let category = categories
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(suggestion.category.trim()))?;
+            Some(Classification {
+                match_type,
+                pattern: source.name.clone(),
+                category_id: category.id,
+                confidence: suggestion.confidence.unwrap_or(DEFAULT_CONFIDENCE),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn categories() -> Vec<Category> {
+        vec![
+            Category {
+                id: 1,
+                name: "Coding".into(),
+                productivity: 1,
+                weight: None,
+            },
+            Category {
+                id: 2,
+                name: "Uncategorized".into(),
+                productivity: 0,
+                weight: None,
+            },
+        ]
+    }
+
+    fn sources() -> Vec<UncategorizedSource> {
+        vec![
+            UncategorizedSource {
+                match_type: MatchType::App,
+                name: "Zed".into(),
+                sample_title: Some("main.rs".into()),
+                duration_secs: 600,
+            },
+            UncategorizedSource {
+                match_type: MatchType::Domain,
+                name: "docs.rs".into(),
+                sample_title: None,
+                duration_secs: 300,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_settings_default_off() {
+        let (db, _dir) = setup_test_db();
+        let settings = LlmSettings::load(db.connection()).unwrap();
+
+        assert!(!settings.enabled);
+        assert_eq!(settings.model, DEFAULT_MODEL);
+        assert!(settings.classifier().is_none());
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let settings = LlmSettings {
+            enabled: true,
+            endpoint: Some("http://localhost:11434/v1/chat/completions".into()),
+            model: "qwen2.5".into(),
+        };
+        settings.save(conn).unwrap();
+        assert_eq!(LlmSettings::load(conn).unwrap(), settings);
+        assert!(settings.classifier().is_some());
+    }
+
+    #[test]
+    fn test_build_prompt() {
+        let prompt = build_prompt(&sources(), &categories());
+        assert_eq!(
+            prompt,
+            "Categories:\n- Coding\n\n\
+             Activity to categorize:\n- app: Zed (window: main.rs)\n- domain: docs.rs"
+        );
+    }
+
+    #[test]
+    fn test_parse_suggestions_filters_unknowns() {
+        let reply = "Sure!\n```json\n{\"suggestions\": [\
+            {\"kind\": \"app\", \"name\": \"zed\", \"category\": \"coding\", \"confidence\": 0.9},\
+            {\"kind\": \"domain\", \"name\": \"docs.rs\", \"category\": \"Research\"},\
+            {\"kind\": \"app\", \"name\": \"Vim\", \"category\": \"Coding\"}\
+        ]}\n```";
+
+        let parsed = parse_suggestions(reply, &sources(), &categories()).unwrap();
+        assert_eq!(
+            parsed,
+            vec![Classification {
+                match_type: MatchType::App,
+                pattern: "Zed".into(),
+                category_id: 1,
+                confidence: 0.9,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_suggestions_rejects_non_json() {
+        assert!(parse_suggestions("I can't help", &sources(), &categories()).is_err());
+        assert!(parse_suggestions("{not json}", &sources(), &categories()).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! Pluggable classifiers that propose rules for uncategorized time: an offline
+//! naive Bayes model and an opt-in LLM backend.
+//!
+//! Classifiers only ever feed the suggestions queue (`rule_suggestions`);
+//! a suggestion becomes a rule only when the user accepts it.
+
+pub mod bayes;
+pub mod llm;
+
+use crate::constants::SECS_PER_DAY;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::{Activity, Category, MatchType, RuleSuggestion, UncategorizedSource};
+use rusqlite::Connection;
+use std::sync::{Arc, Mutex};
+
+/// How far back to look for uncategorized time.
+const LOOKBACK_SECS: i64 = 14 * SECS_PER_DAY;
+
+/// Most sources sent to a classifier per run, largest first.
+const MAX_SOURCES: i64 = 25;
+
+/// A classifier's proposal for one uncategorized source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    pub match_type: MatchType,
+    pub pattern: String,
+    pub category_id: i64,
+    /// From 0.0 to 1.0.
+    pub confidence: f64,
+}
+
+pub trait Classifier {
+    /// Short name stored with each suggestion, e.g. "llm".
+    fn name(&self) -> &'static str;
+
+    fn classify(
+        &self,
+        sources: &[UncategorizedSource],
+        categories: &[Category],
+    ) -> Result<Vec<Classification>, AppError>;
+}
+
+/// Run `classifier` over recent uncategorized time and queue what it proposes.
+///
+/// The database lock is released while the classifier runs, so slow backends
+/// never stall activity tracking. Returns how many new suggestions were queued.
+pub fn suggest_rules(
+    db: &Arc<Mutex<Database>>,
+    classifier: &dyn Classifier,
+    now: i64,
+) -> Result<usize, AppError> {
+    let (sources, categories) = with_connection(db, |conn| {
+        Ok((
+            Activity::uncategorized_sources(conn, now - LOOKBACK_SECS, MAX_SOURCES)?,
+            Category::find_all(conn)?,
+        ))
+    })?;
+    if sources.is_empty() {
+        return Ok(0);
+    }
+
+    let classifications = classifier.classify(&sources, &categories)?;
+    with_connection(db, |conn| {
+        queue(conn, classifier.name(), &classifications, &categories)
+    })
+}
+
+fn queue(
+    conn: &Connection,
+    source: &str,
+    classifications: &[Classification],
+    categories: &[Category],
+) -> rusqlite::Result<usize> {
+    let mut added = 0;
+    for classification in classifications {
+        // A rule pointing at "Uncategorized" wouldn't change anything
+        let useful = categories
+            .iter()
+            .any(|c| c.id == classification.category_id && c.name != "Uncategorized");
+        if !useful {
+            continue;
+        }
+
+        let mut suggestion = RuleSuggestion::new(
+            &classification.pattern,
+            classification.match_type,
+            classification.category_id,
+            source,
+            classification.confidence,
+        );
+        if suggestion.save(conn)? {
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    /// Files every source under one category.
+    struct FixedClassifier(String);
+
+    impl Classifier for FixedClassifier {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn classify(
+            &self,
+            sources: &[UncategorizedSource],
+            categories: &[Category],
+        ) -> Result<Vec<Classification>, AppError> {
+            let category = categories
+                .iter()
+                .find(|c| c.name == self.0)
+                .ok_or(AppError::NotFound { entity: "Category" })?;
+            Ok(sources
+                .iter()
+                .map(|source| Classification {
+                    match_type: source.match_type,
+                    pattern: source.name.clone(),
+                    category_id: category.id,
+                    confidence: 0.8,
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_suggest_rules_queues_once() {
+        let (db, _dir) = setup_test_db();
+        let now = 1_700_000_000_i64;
+        Activity::new(now - 60, 120, "app", Some("Zed"), None)
+            .save(db.connection())
+            .unwrap();
+        let db = Arc::new(Mutex::new(db));
+
+        let classifier = FixedClassifier("Coding".into());
+        assert_eq!(suggest_rules(&db, &classifier, now).unwrap(), 1);
+        assert_eq!(suggest_rules(&db, &classifier, now).unwrap(), 0);
+
+        let pending = with_connection(&db, RuleSuggestion::find_pending).unwrap();
+        let suggestion = pending.first().unwrap();
+        assert_eq!(suggestion.pattern, "Zed");
+        assert_eq!(suggestion.source, "fixed");
+    }
+
+    #[test]
+    fn test_suggest_rules_skips_uncategorized_target() {
+        let (db, _dir) = setup_test_db();
+        let now = 1_700_000_000_i64;
+        Activity::new(now - 60, 120, "app", Some("Zed"), None)
+            .save(db.connection())
+            .unwrap();
+        let db = Arc::new(Mutex::new(db));
+
+        let classifier = FixedClassifier("Uncategorized".into());
+        assert_eq!(suggest_rules(&db, &classifier, now).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_suggest_rules_without_uncategorized_time() {
+        let (db, _dir) = setup_test_db();
+        let db = Arc::new(Mutex::new(db));
+
+        // The classifier is never called, so a missing category doesn't matter
+        let classifier = FixedClassifier("Missing".into());
+        assert_eq!(suggest_rules(&db, &classifier, 1_700_000_000).unwrap(), 0);
+    }
+}
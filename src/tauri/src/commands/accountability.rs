@@ -0,0 +1,62 @@
+use crate::accountability::{AccountabilitySettings, WEBHOOK_URL_SECRET};
+use crate::db::{with_connection, Database};
+use crate::secrets;
+use crate::validation::{validate_email, validate_http_url, validate_weekday};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::{AccountabilitySettingsResponse, UpdateAccountabilitySettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_accountability_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<AccountabilitySettingsResponse, String> {
+    let settings = with_connection(&db, AccountabilitySettings::load)?;
+    let webhook_url = secrets::read(WEBHOOK_URL_SECRET);
+    Ok(AccountabilitySettingsResponse::new(settings, webhook_url))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_accountability_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateAccountabilitySettingsRequest,
+) -> Result<AccountabilitySettingsResponse, String> {
+    validate_weekday(request.report_day)?;
+    let webhook_url = request
+        .webhook_url
+        .as_deref()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| validate_http_url(url, "webhook_url"))
+        .transpose()?
+        .map(str::to_string);
+    let partner_email = request
+        .partner_email
+        .as_deref()
+        .filter(|email| !email.trim().is_empty())
+        .map(validate_email)
+        .transpose()?
+        .map(str::to_string);
+
+    match &webhook_url {
+        Some(url) => secrets::set(WEBHOOK_URL_SECRET, url)?,
+        None => secrets::delete(WEBHOOK_URL_SECRET)?,
+    }
+
+    let settings = AccountabilitySettings {
+        enabled: request.enabled,
+        partner_email,
+        weekly_report: request.weekly_report,
+        abandon_notice: request.abandon_notice,
+        report_day: request.report_day,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(AccountabilitySettingsResponse::new(settings, webhook_url))
+}
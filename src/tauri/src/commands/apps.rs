@@ -0,0 +1,54 @@
+use crate::db::{with_connection, Database};
+use crate::models::AppIdentity;
+use crate::validation::validate_app_name;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::AppIdentityResponse;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_app_identities(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<Vec<AppIdentityResponse>, String> {
+    let identities = with_connection(&db, AppIdentity::find_all)?;
+    Ok(identities
+        .into_iter()
+        .map(AppIdentityResponse::from)
+        .collect())
+}
+
+/// Record `identifier` (a bundle id, `WM_CLASS`, or app name) as
+/// `display_name`. Applies to new activity and to stats over past activity.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_app_identity(
+    db: State<Arc<Mutex<Database>>>,
+    identifier: String,
+    display_name: String,
+) -> Result<(), String> {
+    let identifier = validate_app_name("identifier", &identifier)?;
+    let display_name = validate_app_name("display_name", &display_name)?;
+    with_connection(&db, |conn| AppIdentity::set(conn, identifier, display_name))?;
+    Ok(())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn delete_app_identity(
+    db: State<Arc<Mutex<Database>>>,
+    identifier: String,
+) -> Result<bool, String> {
+    Ok(with_connection(&db, |conn| {
+        AppIdentity::delete(conn, &identifier)
+    })?)
+}
@@ -0,0 +1,24 @@
+use crate::audit_log::find_in_range;
+use crate::db::{with_connection, Database};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::stats::{get_current_timestamp, range_bounds};
+use super::AuditEntryResponse;
+
+/// Focus sessions started, ended, extended and unlocked over a range, newest
+/// first, for "why did my session end?".
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_audit_log(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<AuditEntryResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let entries = with_connection(&db, |conn| find_in_range(conn, start, end))?;
+    Ok(entries.into_iter().map(AuditEntryResponse::from).collect())
+}
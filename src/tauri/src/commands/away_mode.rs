@@ -0,0 +1,63 @@
+use crate::away_mode::{come_back, current, on_away_mode_changed, AwayPeriod};
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::validation::validate_away_until;
+use chrono::Local;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::AwayModeResponse;
+
+/// The away period running today, if any.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_away_mode(db: State<Arc<Mutex<Database>>>) -> Result<Option<AwayModeResponse>, String> {
+    let period = with_connection(&db, current)?;
+    Ok(period.map(AwayModeResponse::from))
+}
+
+/// Go away from today until `until_date` (YYYY-MM-DD), when tracking and
+/// schedules resume by themselves. Moves the return date if already away.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_away_mode(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    until_date: String,
+) -> Result<AwayModeResponse, String> {
+    let today = Local::now().date_naive();
+    let until = validate_away_until(&until_date, today)?;
+
+    let period = with_connection(&db, |conn| {
+        AwayPeriod::start(
+            conn,
+            &today.format("%Y-%m-%d").to_string(),
+            &until.format("%Y-%m-%d").to_string(),
+        )
+    })?;
+    on_away_mode_changed(&app, Some(&period));
+    Ok(AwayModeResponse::from(period))
+}
+
+/// Come back before away mode runs out; today counts as a normal day.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn end_away_mode(app: AppHandle, db: State<Arc<Mutex<Database>>>) -> Result<(), String> {
+    if !with_connection(&db, come_back)? {
+        return Err(AppError::NotFound {
+            entity: "Away period",
+        }
+        .to_string());
+    }
+    on_away_mode_changed(&app, None);
+    Ok(())
+}
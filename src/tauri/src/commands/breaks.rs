@@ -0,0 +1,95 @@
+use crate::breaks::{self, BreakReminderState, BreakSettings};
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::focus_session::current_timestamp;
+use crate::models::EnforcedBreak;
+use crate::safe_lock;
+use crate::validation::{
+    validate_break_minutes, validate_break_work_minutes, validate_time_format,
+};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::{ActiveBreakResponse, BreakSettingsResponse, UpdateBreakSettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_break_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<BreakSettingsResponse, String> {
+    let settings = with_connection(&db, BreakSettings::load)?;
+    Ok(BreakSettingsResponse::from(settings))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_break_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateBreakSettingsRequest,
+) -> Result<BreakSettingsResponse, String> {
+    validate_break_work_minutes(request.work_minutes)?;
+    validate_break_minutes(request.break_minutes)?;
+    let quiet_hours = match (request.quiet_start, request.quiet_end) {
+        (Some(start), Some(end)) => {
+            validate_time_format(&start)?;
+            validate_time_format(&end)?;
+            Some((start, end))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(AppError::InvalidInput {
+                field: "quiet_hours",
+                reason: "start and end must be set together".into(),
+            }
+            .to_string())
+        }
+    };
+
+    let settings = BreakSettings {
+        enabled: request.enabled,
+        work_minutes: request.work_minutes,
+        quiet_hours,
+        enforced: request.enforced,
+        break_minutes: request.break_minutes,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(BreakSettingsResponse::from(settings))
+}
+
+/// Skip the upcoming break; the next reminder comes after another full work interval.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn skip_break(reminder: State<BreakReminderState>) {
+    safe_lock(&reminder.0, "Break reminder").skip(current_timestamp());
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_active_break(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<Option<ActiveBreakResponse>, String> {
+    let active = with_connection(&db, EnforcedBreak::find_active)?;
+    Ok(active.map(ActiveBreakResponse::from))
+}
+
+/// End an enforced break early. Skips are logged on the break record.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn emergency_skip_break(app: AppHandle) -> Result<(), String> {
+    Ok(breaks::emergency_skip_break(&app)?)
+}
@@ -0,0 +1,22 @@
+use crate::browser_integration::status;
+use crate::db::{with_connection, Database};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::stats::get_current_timestamp;
+use super::BrowserIntegrationStatusResponse;
+
+/// Whether the browser extension is connected, for "Chrome extension:
+/// connected / not installed / stale since 2h".
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_browser_integration_status(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<BrowserIntegrationStatusResponse, String> {
+    let now = get_current_timestamp()?;
+    let status = with_connection(&db, |conn| status(conn, now))?;
+    Ok(BrowserIntegrationStatusResponse::new(&status, now))
+}
@@ -0,0 +1,44 @@
+use crate::budget_accrual::AccrualSettings;
+use crate::db::{with_connection, Database};
+use crate::validation::{validate_accrual, validate_budget_secs};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::{BudgetAccrualSettingsResponse, UpdateBudgetAccrualSettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_budget_accrual_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<BudgetAccrualSettingsResponse, String> {
+    let settings = with_connection(&db, AccrualSettings::load)?;
+    Ok(BudgetAccrualSettingsResponse::from(settings))
+}
+
+/// Turn "earn it" mode on or off: every `interval_minutes` of productive
+/// time in a session adds `reward_secs` to its distraction budget, up to
+/// `max_earned_secs` per session.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_budget_accrual_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateBudgetAccrualSettingsRequest,
+) -> Result<BudgetAccrualSettingsResponse, String> {
+    validate_accrual(request.interval_minutes, request.reward_secs)?;
+    validate_budget_secs(request.max_earned_secs)?;
+
+    let settings = AccrualSettings {
+        enabled: request.enabled,
+        interval_minutes: request.interval_minutes,
+        reward_secs: request.reward_secs,
+        max_earned_secs: request.max_earned_secs,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(BudgetAccrualSettingsResponse::from(settings))
+}
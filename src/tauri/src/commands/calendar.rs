@@ -0,0 +1,48 @@
+use crate::calendar_feed::{feed_enabled, feed_path, set_feed_enabled, write_feed};
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::CalendarFeedResponse;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_calendar_feed(db: State<Arc<Mutex<Database>>>) -> Result<CalendarFeedResponse, String> {
+    Ok(CalendarFeedResponse {
+        enabled: with_connection(&db, feed_enabled)?,
+        path: feed_path()?.to_string_lossy().into_owned(),
+    })
+}
+
+/// Turn the `focus.ics` feed on (writing it right away) or off (removing it).
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_calendar_feed_enabled(
+    db: State<Arc<Mutex<Database>>>,
+    enabled: bool,
+) -> Result<CalendarFeedResponse, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    set_feed_enabled(db.connection(), enabled).map_err(AppError::from)?;
+    let path = if enabled {
+        write_feed(db.connection())?
+    } else {
+        let path = feed_path()?;
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| {
+                AppError::Internal(format!("failed to remove calendar feed: {e}")).to_string()
+            })?;
+        }
+        path
+    };
+    Ok(CalendarFeedResponse {
+        enabled,
+        path: path.to_string_lossy().into_owned(),
+    })
+}
@@ -1,7 +1,10 @@
 use crate::db::{with_connection, Database};
-use crate::error::{is_fk_violation, is_unique_violation, AppError};
+use crate::error::{is_unique_violation, AppError};
+use crate::focus::FocusManager;
+use crate::journal;
+use crate::models::focus_session::current_timestamp;
 use crate::models::Category;
-use crate::validation::{validate_category_name, validate_productivity};
+use crate::validation::{validate_category_name, validate_productivity, validate_weight};
 use std::sync::{Arc, Mutex};
 use tauri::State;
 
@@ -39,6 +42,7 @@ pub fn create_category(
             | AppError::NotFound { .. }
             | AppError::InvalidInput { .. }
             | AppError::DeleteFailed { .. }
+            | AppError::Conflict { .. }
             | AppError::Database(_)
             | AppError::LockPoisoned
             | AppError::Internal(_) => e,
@@ -54,6 +58,7 @@ pub fn create_category(
 )]
 pub fn update_category(
     db: State<Arc<Mutex<Database>>>,
+    focus_manager: State<Arc<FocusManager>>,
     id: i64,
     name: String,
     productivity: i32,
@@ -70,32 +75,42 @@ pub fn update_category(
             | AppError::NotFound { .. }
             | AppError::InvalidInput { .. }
             | AppError::DeleteFailed { .. }
+            | AppError::Conflict { .. }
             | AppError::Database(_)
             | AppError::LockPoisoned
             | AppError::Internal(_) => e,
         })?;
 
+    // Productivity decides which domains focus mode blocks
+    focus_manager.invalidate_cache();
     Ok(result)
 }
 
+/// Set the weight a category contributes to the productivity score.
+/// `None` goes back to using its productivity value.
 #[tauri::command]
 #[expect(
     clippy::needless_pass_by_value,
     reason = "Tauri command handlers receive owned deserialized values"
 )]
-pub fn delete_category(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<bool, String> {
-    let result = with_connection(&db, |conn| Category::delete(conn, id)).map_err(|e| match &e {
-        AppError::Database(db_err) if is_fk_violation(db_err) => AppError::DeleteFailed {
-            reason: "category is used by rules or activities".into(),
-        },
-        AppError::AlreadyExists { .. }
-        | AppError::NotFound { .. }
-        | AppError::InvalidInput { .. }
-        | AppError::DeleteFailed { .. }
-        | AppError::Database(_)
-        | AppError::LockPoisoned
-        | AppError::Internal(_) => e,
-    })?;
+pub fn set_category_weight(
+    db: State<Arc<Mutex<Database>>>,
+    id: i64,
+    weight: Option<f64>,
+) -> Result<bool, String> {
+    let weight = weight.map(validate_weight).transpose()?;
+    Ok(with_connection(&db, |conn| {
+        Category::set_weight(conn, id, weight)
+    })?)
+}
 
-    Ok(result)
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn delete_category(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<bool, String> {
+    Ok(with_connection(&db, |conn| {
+        journal::delete_category(conn, id, current_timestamp())
+    })?)
 }
@@ -0,0 +1,25 @@
+use crate::{config_file, policy};
+
+use super::ConfigOverrideResponse;
+
+/// Settings set by `config.toml` or locked by the managed policy, which the
+/// app can't change while they're set there.
+#[tauri::command]
+pub fn get_config_overrides() -> Vec<ConfigOverrideResponse> {
+    let mut overrides: std::collections::BTreeMap<_, _> = config_file::overrides()
+        .into_iter()
+        .map(|(key, value)| (key, (value, "config")))
+        .collect();
+    // The policy's locks win over the config file
+    for (key, value) in policy::locked_settings() {
+        overrides.insert(key, (value, "policy"));
+    }
+    overrides
+        .into_iter()
+        .map(|(key, (value, source))| ConfigOverrideResponse {
+            key,
+            value,
+            source: source.to_string(),
+        })
+        .collect()
+}
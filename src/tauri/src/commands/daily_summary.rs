@@ -0,0 +1,47 @@
+use crate::daily_summary::{today_summary, DailySummarySettings};
+use crate::db::{with_connection, Database};
+use crate::validation::validate_time_format;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::{DailySummaryResponse, DailySummarySettingsResponse};
+
+/// Today's summary so far, for the review screen.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_daily_summary(db: State<Arc<Mutex<Database>>>) -> Result<DailySummaryResponse, String> {
+    let summary = with_connection(&db, today_summary)?;
+    Ok(DailySummaryResponse::from(summary))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_daily_summary_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<DailySummarySettingsResponse, String> {
+    let settings = with_connection(&db, DailySummarySettings::load)?;
+    Ok(DailySummarySettingsResponse::from(settings))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_daily_summary_settings(
+    db: State<Arc<Mutex<Database>>>,
+    enabled: bool,
+    time: String,
+) -> Result<DailySummarySettingsResponse, String> {
+    validate_time_format(&time)?;
+
+    let settings = DailySummarySettings { enabled, time };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(DailySummarySettingsResponse::from(settings))
+}
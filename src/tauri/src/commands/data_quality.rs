@@ -0,0 +1,25 @@
+use crate::data_quality::assess_range;
+use crate::db::{with_connection, Database};
+use chrono::Local;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::stats::{get_current_timestamp, range_bounds};
+use super::DayQualityResponse;
+
+/// Tracked time per day for `range` ("today" or "week") against the same
+/// weekday in previous weeks, flagging days with suspiciously little data.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_data_quality(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<DayQualityResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let days = with_connection(&db, |conn| assess_range(conn, &Local, start, end))?;
+    Ok(days.into_iter().map(DayQualityResponse::from).collect())
+}
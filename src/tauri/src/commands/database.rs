@@ -0,0 +1,52 @@
+use crate::db::{location, Database};
+use crate::error::AppError;
+use crate::get_data_dir;
+use crate::validation::validate_folder;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::DatabaseLocationResponse;
+
+fn location_response(path: &Path, data_dir: &Path) -> DatabaseLocationResponse {
+    DatabaseLocationResponse {
+        path: path.to_string_lossy().into_owned(),
+        is_default: path.parent() == Some(data_dir),
+    }
+}
+
+fn data_dir() -> Result<PathBuf, AppError> {
+    get_data_dir().map_err(|e| AppError::Internal(e.to_string()))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_database_location(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<DatabaseLocationResponse, String> {
+    let data_dir = data_dir()?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(location_response(db.path(), &data_dir))
+}
+
+/// Move `foxus.db` into `folder` (an existing directory with no `foxus.db`
+/// yet) and keep using it from there. Pass the default data folder to move it
+/// back.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn migrate_database(
+    db: State<Arc<Mutex<Database>>>,
+    new_path: String,
+) -> Result<DatabaseLocationResponse, String> {
+    let folder = validate_folder(&new_path)?;
+    let data_dir = data_dir()?;
+    let mut db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let path = location::relocate(&mut db, &data_dir, Path::new(folder))?;
+    Ok(location_response(&path, &data_dir))
+}
@@ -0,0 +1,81 @@
+use crate::audit_log::Origin;
+use crate::db::{with_connection, Database};
+use crate::deep_work::DeepWorkSettings;
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::tray::refresh_tray;
+use crate::validation::{validate_budget_minutes, validate_deep_work_minutes};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::{DeepWorkSettingsResponse, UpdateDeepWorkSettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_deep_work_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<DeepWorkSettingsResponse, String> {
+    let settings = with_connection(&db, DeepWorkSettings::load)?;
+    Ok(DeepWorkSettingsResponse::from(settings))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_deep_work_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateDeepWorkSettingsRequest,
+) -> Result<DeepWorkSettingsResponse, String> {
+    validate_deep_work_minutes(request.threshold_minutes, "threshold_minutes")?;
+    validate_deep_work_minutes(request.extension_minutes, "extension_minutes")?;
+
+    let settings = DeepWorkSettings {
+        offer_extension: request.offer_extension,
+        threshold_minutes: request.threshold_minutes,
+        extension_minutes: request.extension_minutes,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(DeepWorkSettingsResponse::from(settings))
+}
+
+/// Extend the active session, by the configured deep-work extension unless
+/// `minutes` is given, optionally adding `extra_budget_minutes` of
+/// distraction time.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn extend_focus_session(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    minutes: Option<i32>,
+    extra_budget_minutes: Option<i32>,
+) -> Result<(), String> {
+    let minutes = match minutes {
+        Some(minutes) => minutes,
+        None => with_connection(&db, DeepWorkSettings::load)?.extension_minutes,
+    };
+    validate_deep_work_minutes(minutes, "minutes")?;
+    let extra_budget_secs = extra_budget_minutes
+        .map(validate_budget_minutes)
+        .transpose()?
+        .unwrap_or(0);
+
+    let session =
+        focus_manager.extend_focus_session(minutes * 60, extra_budget_secs, Origin::Ui)?;
+    if session.is_none() {
+        return Err(AppError::NotFound {
+            entity: "Focus session",
+        }
+        .to_string());
+    }
+    refresh_tray(&app);
+    Ok(())
+}
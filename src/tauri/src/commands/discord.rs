@@ -0,0 +1,53 @@
+use crate::db::{with_connection, Database};
+use crate::discord_presence::{sync_discord_presence, DiscordPresenceSettings};
+use crate::error::AppError;
+use crate::validation::validate_discord_client_id;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::DiscordPresenceSettingsResponse;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_discord_presence_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<DiscordPresenceSettingsResponse, String> {
+    let settings = with_connection(&db, DiscordPresenceSettings::load)?;
+    Ok(DiscordPresenceSettingsResponse::from(settings))
+}
+
+/// Turn Discord Rich Presence on or off. `client_id` is the id of a Discord
+/// application the user created; it is required to enable presence.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_discord_presence_settings(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    enabled: bool,
+    client_id: Option<String>,
+) -> Result<DiscordPresenceSettingsResponse, String> {
+    let client_id = client_id
+        .as_deref()
+        .filter(|id| !id.trim().is_empty())
+        .map(validate_discord_client_id)
+        .transpose()?
+        .map(str::to_string);
+    if enabled && client_id.is_none() {
+        return Err(AppError::InvalidInput {
+            field: "client_id",
+            reason: "required to enable Discord presence".into(),
+        }
+        .to_string());
+    }
+
+    let settings = DiscordPresenceSettings { enabled, client_id };
+    with_connection(&db, |conn| settings.save(conn))?;
+    sync_discord_presence(&app);
+    Ok(DiscordPresenceSettingsResponse::from(settings))
+}
@@ -1,6 +1,42 @@
 // src/tauri/src/commands/dtos.rs
 
-use crate::models::{Category, FocusSchedule, Rule};
+use crate::accountability::AccountabilitySettings;
+use crate::audit_log::AuditEntry;
+use crate::away_mode::AwayPeriod;
+use crate::breaks::BreakSettings;
+use crate::browser_integration::IntegrationStatus;
+use crate::browsers::BrowserUsage;
+use crate::budget_accrual::AccrualSettings;
+use crate::classifier::llm::LlmSettings;
+use crate::context_switches::{ContextSwitchStats, SwitchPair};
+use crate::daily_summary::{DailySummary, DailySummarySettings};
+use crate::data_quality::DayQuality;
+use crate::db::query_plan::QueryPlan;
+use crate::deep_work::DeepWorkSettings;
+use crate::discord_presence::DiscordPresenceSettings;
+use crate::email_report::EmailReportSettings;
+use crate::focus::TemporaryAllow;
+use crate::git_commits::CommitActivity;
+use crate::insights::Insight;
+use crate::journal::Operation;
+use crate::local_time::WeekStart;
+use crate::mobile_sync::{SyncResult, SyncSettings};
+use crate::models::{
+    AppIdentity, BlockedAttempt, Category, CategoryBudget, DistractionEvent, EnforcedBreak,
+    FocusSchedule, FocusSession, Note, Rule, RuleCondition, RuleGroup, RuleSuggestion,
+};
+use crate::mqtt::MqttSettings;
+use crate::notifications::QuietRange;
+use crate::onboarding::OnboardingState;
+use crate::personal_time::PersonalTime;
+use crate::recommendation::FocusRecommendation;
+use crate::schedule_templates::{Applied, ScheduleTemplate};
+use crate::schedule_text::ParsedSchedule;
+use crate::session_queue::QueuedBlock;
+use crate::standup::{StandupItem, StandupSummary};
+use crate::time_export::TimeExportSettings;
+use crate::tracker::TrackerHealth;
+use crate::updates::UpdateInfo;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
@@ -8,7 +44,13 @@ pub struct StatsResponse {
     pub productive_secs: i32,
     pub neutral_secs: i32,
     pub distracting_secs: i32,
+    /// Weighted productivity from 0 to 100, or null with no categorized time.
+    pub productivity_score: Option<f64>,
     pub top_apps: Vec<AppStat>,
+    /// Start of the first productive activity today, null before any.
+    pub first_activity_at: Option<i64>,
+    /// End of the last productive activity today.
+    pub last_activity_at: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -17,6 +59,11 @@ pub struct DailyStats {
     pub productive_secs: i32,
     pub neutral_secs: i32,
     pub distracting_secs: i32,
+    pub productivity_score: Option<f64>,
+    pub first_activity_at: Option<i64>,
+    pub last_activity_at: Option<i64>,
+    /// Seconds from the first to the last productive activity.
+    pub workday_secs: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -25,7 +72,305 @@ pub struct WeeklyStatsResponse {
     pub total_productive_secs: i32,
     pub total_neutral_secs: i32,
     pub total_distracting_secs: i32,
+    pub productivity_score: Option<f64>,
     pub top_apps: Vec<AppStat>,
+    /// Mean workday length over the days with productive activity.
+    pub average_workday_secs: Option<i64>,
+    /// Totals for Monday to Friday of the last 7 days.
+    pub weekday_totals: DayTypeTotals,
+    /// Totals for Saturday and Sunday of the last 7 days.
+    pub weekend_totals: DayTypeTotals,
+    /// Per day of the week, Monday first, over a longer window.
+    pub day_of_week_averages: Vec<DayOfWeekAverage>,
+}
+
+#[derive(Serialize, Default)]
+pub struct DayTypeTotals {
+    /// Days of this kind in the range.
+    pub days: i32,
+    pub productive_secs: i32,
+    pub neutral_secs: i32,
+    pub distracting_secs: i32,
+}
+
+#[derive(Serialize)]
+pub struct DayOfWeekAverage {
+    /// Short day name, e.g. "Tue".
+    pub weekday: String,
+    /// Days of this kind with tracked time; untracked days don't count.
+    pub days: i32,
+    pub average_productive_secs: i32,
+    pub average_productivity_score: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct FocusRecommendationResponse {
+    pub duration_minutes: i32,
+    pub budget_minutes: i32,
+    pub goal_remaining_minutes: i32,
+    /// Minutes until the next scheduled focus block today, if any.
+    pub free_minutes: Option<i32>,
+    pub reason: String,
+}
+
+impl From<FocusRecommendation> for FocusRecommendationResponse {
+    fn from(rec: FocusRecommendation) -> Self {
+        Self {
+            duration_minutes: rec.duration_minutes,
+            budget_minutes: rec.budget_minutes,
+            goal_remaining_minutes: rec.goal_remaining_minutes,
+            free_minutes: rec.free_minutes,
+            reason: rec.reason,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SwitchPairResponse {
+    pub first: String,
+    pub second: String,
+    pub count: u32,
+}
+
+impl From<SwitchPair> for SwitchPairResponse {
+    fn from(pair: SwitchPair) -> Self {
+        Self {
+            first: pair.first,
+            second: pair.second,
+            count: pair.count,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ContextSwitchResponse {
+    pub tracked_secs: i32,
+    pub switches: u32,
+    pub switches_per_hour: f64,
+    pub average_block_secs: f64,
+    /// 0 (no switching) to 100 (a switch every minute or more).
+    pub fragmentation_score: f64,
+    /// App pairs the user switched between most.
+    pub top_pairs: Vec<SwitchPairResponse>,
+}
+
+/// Browser time tracked under one profile or container.
+#[derive(Serialize)]
+pub struct BrowserProfileResponse {
+    /// `None` for activity from extensions that don't report a profile.
+    pub profile: Option<String>,
+    pub duration_secs: i32,
+}
+
+/// Time spent in one browser, counted once across tracker and extension.
+#[derive(Serialize)]
+pub struct BrowserUsageResponse {
+    /// Browser id such as `chrome`, `firefox`, or `edge`.
+    pub browser: String,
+    pub duration_secs: i32,
+    /// False when only the extension reported this browser.
+    pub tracked_by_app: bool,
+}
+
+impl From<BrowserUsage> for BrowserUsageResponse {
+    fn from(usage: BrowserUsage) -> Self {
+        Self {
+            browser: usage.browser,
+            duration_secs: usage.duration_secs,
+            tracked_by_app: usage.tracked_by_app,
+        }
+    }
+}
+
+/// One focus-control action and where it came from.
+#[derive(Serialize)]
+pub struct AuditEntryResponse {
+    pub id: i64,
+    pub timestamp: i64,
+    /// `start`, `end`, `complete`, `extend`, `unlock_requested`, or
+    /// `emergency_unlock`.
+    pub action: String,
+    /// `tray`, `ui`, `schedule`, `cli`, `api`, or `system`.
+    pub origin: String,
+    pub session_id: Option<i64>,
+    pub detail: Option<String>,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            id: entry.id,
+            timestamp: entry.timestamp,
+            action: entry.action,
+            origin: entry.origin,
+            session_id: entry.session_id,
+            detail: entry.detail,
+        }
+    }
+}
+
+/// A focus block or break waiting in the session queue.
+#[derive(Serialize)]
+pub struct QueuedBlockResponse {
+    pub id: i64,
+    /// `focus` or `break`.
+    pub kind: String,
+    pub duration_secs: i32,
+    pub budget_secs: i32,
+}
+
+impl From<QueuedBlock> for QueuedBlockResponse {
+    fn from(block: QueuedBlock) -> Self {
+        Self {
+            id: block.id,
+            kind: block.kind.as_str().to_string(),
+            duration_secs: block.duration_secs,
+            budget_secs: block.budget_secs,
+        }
+    }
+}
+
+/// Whether the browser extension is talking to Foxus.
+#[derive(Serialize)]
+pub struct BrowserIntegrationStatusResponse {
+    /// `connected`, `stale`, or `not_installed`.
+    pub status: String,
+    /// Unix timestamp of the last message from the extension.
+    pub last_seen: Option<i64>,
+    /// Seconds since `last_seen`, for "stale since 2h".
+    pub secs_since_last_seen: Option<i64>,
+}
+
+impl BrowserIntegrationStatusResponse {
+    pub fn new(status: &IntegrationStatus, now: i64) -> Self {
+        Self {
+            status: status.status.as_str().to_string(),
+            last_seen: status.last_seen,
+            secs_since_last_seen: status.last_seen.map(|seen| (now - seen).max(0)),
+        }
+    }
+}
+
+/// Whether the iCalendar feed is kept up to date, and where it is written.
+#[derive(Serialize)]
+pub struct CalendarFeedResponse {
+    pub enabled: bool,
+    pub path: String,
+}
+
+/// Path is `overlay.json`; `overlay.txt` sits beside it.
+#[derive(Serialize)]
+pub struct OverlayResponse {
+    pub enabled: bool,
+    pub path: String,
+}
+
+#[derive(Serialize)]
+pub struct SyncSettingsResponse {
+    pub folder: Option<String>,
+    pub device: String,
+}
+
+impl From<SyncSettings> for SyncSettingsResponse {
+    fn from(settings: SyncSettings) -> Self {
+        Self {
+            folder: settings.folder,
+            device: settings.device,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SyncResultResponse {
+    pub imported_screen_time: usize,
+    pub started_sessions: usize,
+}
+
+impl From<SyncResult> for SyncResultResponse {
+    fn from(result: SyncResult) -> Self {
+        Self {
+            imported_screen_time: result.imported_screen_time,
+            started_sessions: result.started_sessions,
+        }
+    }
+}
+
+/// A commit and the productive time that went into it.
+#[derive(Serialize)]
+pub struct CommitActivityResponse {
+    pub repo: String,
+    pub hash: String,
+    pub branch: Option<String>,
+    pub summary: String,
+    pub timestamp: i64,
+    /// Productive seconds since the previous commit in the repo (at most 2h).
+    pub active_secs: i32,
+}
+
+impl From<CommitActivity> for CommitActivityResponse {
+    fn from(activity: CommitActivity) -> Self {
+        Self {
+            repo: activity.commit.repo,
+            hash: activity.commit.hash,
+            branch: activity.commit.branch,
+            summary: activity.commit.summary,
+            timestamp: activity.commit.timestamp,
+            active_secs: activity.active_secs,
+        }
+    }
+}
+
+/// Editor or terminal time for one language or project.
+#[derive(Serialize)]
+pub struct CodingBreakdownResponse {
+    /// `None` for time reported without that detail.
+    pub name: Option<String>,
+    pub duration_secs: i32,
+}
+
+/// Time in one app grouped by what was on screen beside it.
+#[derive(Serialize)]
+pub struct WindowContextResponse {
+    /// Categories of the windows beside the app; empty when nothing else was
+    /// visible or context capture was off.
+    pub category_ids: Vec<i64>,
+    pub category_names: Vec<String>,
+    pub duration_secs: i32,
+}
+
+/// How the database plans one of the hot stats queries.
+#[derive(Serialize)]
+pub struct QueryPlanResponse {
+    pub name: String,
+    pub steps: Vec<String>,
+    pub full_scan: bool,
+}
+
+impl From<QueryPlan> for QueryPlanResponse {
+    fn from(plan: QueryPlan) -> Self {
+        Self {
+            name: plan.name.to_string(),
+            steps: plan.steps,
+            full_scan: plan.full_scan,
+        }
+    }
+}
+
+impl From<ContextSwitchStats> for ContextSwitchResponse {
+    fn from(stats: ContextSwitchStats) -> Self {
+        Self {
+            tracked_secs: stats.tracked_secs,
+            switches: stats.switches,
+            switches_per_hour: stats.switches_per_hour,
+            average_block_secs: stats.average_block_secs,
+            fragmentation_score: stats.fragmentation_score,
+            top_pairs: stats
+                .top_pairs
+                .into_iter()
+                .map(SwitchPairResponse::from)
+                .collect(),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -35,11 +380,164 @@ pub struct AppStat {
     pub productivity: i32,
 }
 
+#[derive(Serialize)]
+pub struct CurrentActivityResponse {
+    pub app_name: String,
+    pub window_title: String,
+    pub category_id: i64,
+}
+
 #[derive(Serialize)]
 pub struct FocusStateResponse {
     pub active: bool,
     pub budget_remaining: i32,
     pub session_duration_secs: Option<i64>,
+    pub time_remaining_secs: Option<i64>,
+    pub category_budgets: Vec<CategoryBudgetResponse>,
+    /// Domains the session leaves unblocked.
+    pub exceptions: Vec<String>,
+    /// Categories the session blocks on top of the distracting ones.
+    pub blocked_categories: Vec<i64>,
+}
+
+#[derive(Serialize)]
+pub struct CategoryBudgetResponse {
+    pub category_id: i64,
+    pub budget_secs: i32,
+    pub used_secs: i32,
+    pub remaining_secs: i32,
+}
+
+impl From<CategoryBudget> for CategoryBudgetResponse {
+    fn from(budget: CategoryBudget) -> Self {
+        Self {
+            category_id: budget.category_id,
+            budget_secs: budget.budget_secs,
+            used_secs: budget.used_secs,
+            remaining_secs: budget.remaining(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DistractionEventResponse {
+    pub timestamp: i64,
+    pub domain: Option<String>,
+    pub category_id: Option<i64>,
+    pub secs: i32,
+}
+
+impl From<DistractionEvent> for DistractionEventResponse {
+    fn from(event: DistractionEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            domain: event.domain,
+            category_id: event.category_id,
+            secs: event.secs,
+        }
+    }
+}
+
+/// A slice of a new session's budget reserved for one category.
+#[derive(Deserialize)]
+pub struct CategoryBudgetRequest {
+    pub category_id: i64,
+    pub minutes: i32,
+}
+
+#[derive(Serialize)]
+pub struct EmergencyUnlockResponse {
+    pub unlocked: bool,
+    /// Seconds to wait before calling again; 0 once unlocked.
+    pub wait_secs: i64,
+}
+
+#[derive(Serialize)]
+pub struct TemporaryAllowResponse {
+    pub domain: String,
+    /// When the domain is blocked again.
+    pub expires_at: i64,
+    pub budget_remaining_secs: i32,
+}
+
+impl From<TemporaryAllow> for TemporaryAllowResponse {
+    fn from(allow: TemporaryAllow) -> Self {
+        Self {
+            domain: allow.domain,
+            expires_at: allow.expires_at,
+            budget_remaining_secs: allow.budget_remaining,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct EmergencyUnlockSettingsResponse {
+    /// Phrase the user has to type to confirm.
+    pub phrase: String,
+    pub delay_minutes: i32,
+}
+
+/// A past or running session for the session history.
+#[derive(Serialize)]
+pub struct FocusSessionResponse {
+    pub id: i64,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub scheduled: bool,
+    pub strict: bool,
+    pub planned_duration_secs: Option<i32>,
+    pub distraction_used_secs: i32,
+    /// Time the user was active during the session.
+    pub focused_secs: i32,
+    /// Time the user was idle during the session.
+    pub idle_secs: i32,
+    pub longest_streak_secs: i32,
+    pub streak_app: Option<String>,
+    /// Ended early through an emergency unlock.
+    pub emergency_unlocked: bool,
+    /// 0 to 100 from budget used, idle time, and app switching; null while
+    /// running or when nothing was tracked.
+    pub effectiveness_score: Option<f64>,
+    /// Visits to the block page, most tried domain first.
+    pub blocked_attempts: Vec<BlockedAttemptResponse>,
+}
+
+impl From<FocusSession> for FocusSessionResponse {
+    fn from(session: FocusSession) -> Self {
+        Self {
+            id: session.id.unwrap_or(0),
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+            scheduled: session.scheduled,
+            strict: session.strict,
+            planned_duration_secs: session.planned_duration,
+            distraction_used_secs: session.distraction_used,
+            focused_secs: session.focused_secs,
+            idle_secs: session.idle_secs,
+            longest_streak_secs: session.longest_streak_secs,
+            streak_app: session.streak_app,
+            emergency_unlocked: session.emergency_unlocked,
+            effectiveness_score: session.effectiveness_score,
+            blocked_attempts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BlockedAttemptResponse {
+    pub domain: String,
+    pub attempts: i32,
+    pub last_attempt_at: i64,
+}
+
+impl From<BlockedAttempt> for BlockedAttemptResponse {
+    fn from(attempt: BlockedAttempt) -> Self {
+        Self {
+            domain: attempt.domain,
+            attempts: attempt.attempts,
+            last_attempt_at: attempt.last_attempt_at,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -50,6 +548,10 @@ pub struct FocusScheduleResponse {
     pub end_time: String,
     pub distraction_budget_secs: i32,
     pub enabled: bool,
+    pub blocked_categories: Vec<i64>,
+    /// Pass back to `update_focus_schedule` to detect concurrent edits.
+    pub version: i64,
+    pub notify_lead_minutes: i32,
 }
 
 impl From<FocusSchedule> for FocusScheduleResponse {
@@ -61,6 +563,9 @@ impl From<FocusSchedule> for FocusScheduleResponse {
             end_time: schedule.end_time,
             distraction_budget_secs: schedule.distraction_budget,
             enabled: schedule.enabled,
+            blocked_categories: schedule.blocked_categories,
+            version: schedule.version,
+            notify_lead_minutes: schedule.notify_lead_minutes,
         }
     }
 }
@@ -71,6 +576,11 @@ pub struct CreateScheduleRequest {
     pub start_time: String,
     pub end_time: String,
     pub distraction_budget_secs: i32,
+    /// Categories its sessions block even though they aren't distracting.
+    #[serde(default)]
+    pub blocked_categories: Vec<i64>,
+    /// Minutes of notice before it starts; 5 when unset, 0 for none.
+    pub notify_lead_minutes: Option<i32>,
 }
 
 #[derive(Deserialize)]
@@ -81,6 +591,12 @@ pub struct UpdateScheduleRequest {
     pub end_time: String,
     pub distraction_budget_secs: i32,
     pub enabled: bool,
+    #[serde(default)]
+    pub blocked_categories: Vec<i64>,
+    /// Version the schedule was read at; a newer one is a conflict.
+    pub version: Option<i64>,
+    /// Minutes of notice before it starts; unchanged when unset.
+    pub notify_lead_minutes: Option<i32>,
 }
 
 #[derive(Serialize)]
@@ -88,6 +604,8 @@ pub struct CategoryResponse {
     pub id: i64,
     pub name: String,
     pub productivity: i32,
+    /// Custom score weight; null means the productivity value is used.
+    pub weight: Option<f64>,
 }
 
 impl From<Category> for CategoryResponse {
@@ -96,6 +614,109 @@ impl From<Category> for CategoryResponse {
             id: category.id,
             name: category.name,
             productivity: category.productivity,
+            weight: category.weight,
+        }
+    }
+}
+
+/// Time spent in one window-title context (document, repo, ticket).
+#[derive(Serialize)]
+pub struct TitleContextResponse {
+    pub context: String,
+    pub duration_secs: i32,
+}
+
+#[derive(Serialize)]
+pub struct TrackerHealthResponse {
+    pub running: bool,
+    /// Capture backend in use: `x11`, `macos`, or `unavailable`.
+    pub backend: String,
+    pub last_capture_at: Option<i64>,
+    pub consecutive_failures: u32,
+    /// `None` until a window has been captured.
+    pub window_titles_available: Option<bool>,
+    pub idle_detection_available: bool,
+    /// Problems to show as a persistent warning, most serious first.
+    pub warnings: Vec<String>,
+}
+
+impl From<TrackerHealth> for TrackerHealthResponse {
+    fn from(health: TrackerHealth) -> Self {
+        Self {
+            running: health.running,
+            backend: health.backend.to_string(),
+            last_capture_at: health.last_capture_at,
+            consecutive_failures: health.consecutive_failures,
+            window_titles_available: health.window_titles_available(),
+            idle_detection_available: health.idle_detection_available,
+            warnings: health.warnings(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct WeekStartResponse {
+    /// `monday` or `sunday`, as used by weekly stats and heatmaps.
+    pub week_start: &'static str,
+    /// `setting` when chosen by the user, `locale` when detected.
+    pub source: &'static str,
+    /// What the system locale suggests.
+    pub detected: &'static str,
+    /// Schedule day numbers (1=Monday, 7=Sunday) in display order.
+    pub day_order: Vec<u32>,
+}
+
+impl WeekStartResponse {
+    pub fn new(chosen: Option<WeekStart>) -> Self {
+        let detected = WeekStart::detect();
+        let week_start = chosen.unwrap_or(detected);
+        Self {
+            week_start: week_start.as_str(),
+            source: if chosen.is_some() {
+                "setting"
+            } else {
+                "locale"
+            },
+            detected: detected.as_str(),
+            day_order: week_start
+                .days()
+                .map(|day| day.number_from_monday())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct IdleSettingsResponse {
+    /// Threshold the tracker uses when no calibration set one.
+    pub default_threshold_secs: u64,
+    pub threshold_secs: Option<u64>,
+    /// `None` until a calibration ran.
+    pub detection_working: Option<bool>,
+    pub calibrated_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct IdleCalibrationResponse {
+    pub working: bool,
+    pub active_max_secs: u64,
+    pub idle_max_secs: u64,
+    /// `None` when idle detection is broken.
+    pub recommended_threshold_secs: Option<u64>,
+    pub settings: IdleSettingsResponse,
+}
+
+#[derive(Serialize)]
+pub struct AppIdentityResponse {
+    pub identifier: String,
+    pub display_name: String,
+}
+
+impl From<AppIdentity> for AppIdentityResponse {
+    fn from(identity: AppIdentity) -> Self {
+        Self {
+            identifier: identity.identifier,
+            display_name: identity.display_name,
         }
     }
 }
@@ -107,16 +728,774 @@ pub struct RuleResponse {
     pub match_type: String,
     pub category_id: i64,
     pub priority: i32,
+    pub profile: Option<String>,
+    /// `pattern` followed by the rule's other patterns.
+    pub patterns: Vec<String>,
+    pub group_id: Option<i64>,
+    pub condition: Option<RuleConditionResponse>,
+    /// Pass back to `update_rule` to detect concurrent edits.
+    pub version: i64,
+}
+
+#[derive(Serialize)]
+pub struct RuleConditionResponse {
+    pub days_of_week: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Deserialize)]
+pub struct RuleConditionRequest {
+    pub days_of_week: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+impl From<RuleConditionRequest> for RuleCondition {
+    fn from(request: RuleConditionRequest) -> Self {
+        Self {
+            days_of_week: request.days_of_week,
+            start_time: request.start_time,
+            end_time: request.end_time,
+        }
+    }
+}
+
+/// Activities to move in `recategorize_activities`. At least one of
+/// `app_name`, `domain` and `category_id` must be set.
+#[derive(Deserialize)]
+pub struct RecategorizeFilterRequest {
+    /// "today" or "week", like the stats drill-down.
+    pub range: String,
+    pub app_name: Option<String>,
+    pub domain: Option<String>,
+    /// Only move activities currently in this category.
+    pub category_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct RecategorizeResponse {
+    pub updated: usize,
+    /// Rule added with `create_rule`, or the existing app rule it updated.
+    pub rule: Option<RuleResponse>,
 }
 
 impl From<Rule> for RuleResponse {
     fn from(rule: Rule) -> Self {
         Self {
+            patterns: rule.patterns().map(String::from).collect(),
+            condition: rule.condition.map(|c| RuleConditionResponse {
+                days_of_week: c.days_of_week,
+                start_time: c.start_time,
+                end_time: c.end_time,
+            }),
             id: rule.id,
             pattern: rule.pattern,
             match_type: rule.match_type.as_str().to_string(),
             category_id: rule.category_id,
             priority: rule.priority,
+            profile: rule.profile,
+            group_id: rule.group_id,
+            version: rule.version,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RuleGroupResponse {
+    pub id: i64,
+    pub name: String,
+    pub enabled: bool,
+}
+
+impl From<RuleGroup> for RuleGroupResponse {
+    fn from(group: RuleGroup) -> Self {
+        Self {
+            id: group.id,
+            name: group.name,
+            enabled: group.enabled,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct NoteResponse {
+    pub id: i64,
+    pub day: String,
+    pub activity_id: Option<i64>,
+    pub text: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<Note> for NoteResponse {
+    fn from(note: Note) -> Self {
+        Self {
+            id: note.id,
+            day: note.day,
+            activity_id: note.activity_id,
+            text: note.text,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
         }
     }
 }
+
+/// Why an activity landed in its category, for "why is this Coding?" tooltips.
+/// Rules are matched as they are now, so after a rule edit `matched_rule` may
+/// point elsewhere than the stored `category_id`.
+#[derive(Serialize)]
+pub struct CategorizationExplanationResponse {
+    pub activity_id: i64,
+    pub category_id: Option<i64>,
+    /// The highest-priority matching rule; `None` means it fell back to Uncategorized.
+    pub matched_rule: Option<RuleResponse>,
+    /// Other matching rules it beat, highest priority first.
+    pub runner_ups: Vec<RuleResponse>,
+}
+
+#[derive(Serialize)]
+pub struct OnboardingStateResponse {
+    pub completed_steps: Vec<String>,
+    pub next_step: Option<String>,
+    pub finished: bool,
+}
+
+impl From<OnboardingState> for OnboardingStateResponse {
+    fn from(state: OnboardingState) -> Self {
+        Self {
+            completed_steps: state
+                .completed
+                .iter()
+                .map(|(step, _)| step.as_str().to_string())
+                .collect(),
+            next_step: state.next_step().map(|step| step.as_str().to_string()),
+            finished: state.is_finished(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct UpdateInfoResponse {
+    pub current_version: String,
+    pub latest_version: String,
+    pub release_notes: String,
+    pub release_url: String,
+    pub update_available: bool,
+}
+
+impl From<UpdateInfo> for UpdateInfoResponse {
+    fn from(info: UpdateInfo) -> Self {
+        Self {
+            current_version: info.current_version,
+            latest_version: info.latest_version,
+            release_notes: info.release_notes,
+            release_url: info.release_url,
+            update_available: info.update_available,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BreakSettingsResponse {
+    pub enabled: bool,
+    pub work_minutes: i32,
+    pub quiet_start: Option<String>,
+    pub quiet_end: Option<String>,
+    pub enforced: bool,
+    pub break_minutes: i32,
+}
+
+impl From<BreakSettings> for BreakSettingsResponse {
+    fn from(settings: BreakSettings) -> Self {
+        let (quiet_start, quiet_end) = settings.quiet_hours.unzip();
+        Self {
+            enabled: settings.enabled,
+            work_minutes: settings.work_minutes,
+            quiet_start,
+            quiet_end,
+            enforced: settings.enforced,
+            break_minutes: settings.break_minutes,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateBreakSettingsRequest {
+    pub enabled: bool,
+    pub work_minutes: i32,
+    pub quiet_start: Option<String>,
+    pub quiet_end: Option<String>,
+    pub enforced: bool,
+    pub break_minutes: i32,
+}
+
+#[derive(Serialize)]
+pub struct ActiveBreakResponse {
+    pub started_at: i64,
+    pub ends_at: i64,
+    pub trigger: String,
+}
+
+impl From<EnforcedBreak> for ActiveBreakResponse {
+    fn from(enforced: EnforcedBreak) -> Self {
+        Self {
+            started_at: enforced.started_at,
+            ends_at: enforced.ends_at(),
+            trigger: enforced.trigger.as_str().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DailySummaryResponse {
+    pub date: String,
+    pub productive_secs: i32,
+    pub neutral_secs: i32,
+    pub distracting_secs: i32,
+    pub top_distractions: Vec<AppStat>,
+}
+
+impl From<DailySummary> for DailySummaryResponse {
+    fn from(summary: DailySummary) -> Self {
+        Self {
+            date: summary.date,
+            productive_secs: summary.productive_secs,
+            neutral_secs: summary.neutral_secs,
+            distracting_secs: summary.distracting_secs,
+            top_distractions: summary
+                .top_distractions
+                .into_iter()
+                .map(|d| AppStat {
+                    name: d.name,
+                    duration_secs: d.duration_secs,
+                    productivity: -1,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DailySummarySettingsResponse {
+    pub enabled: bool,
+    pub time: String,
+}
+
+impl From<DailySummarySettings> for DailySummarySettingsResponse {
+    fn from(settings: DailySummarySettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            time: settings.time,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct StandupItemResponse {
+    pub label: String,
+    pub duration_secs: i32,
+    pub apps: Vec<String>,
+}
+
+impl From<StandupItem> for StandupItemResponse {
+    fn from(item: StandupItem) -> Self {
+        Self {
+            label: item.label,
+            duration_secs: item.duration_secs,
+            apps: item.apps,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct StandupSummaryResponse {
+    pub date: String,
+    pub items: Vec<StandupItemResponse>,
+    /// Bullet list ready to paste into a standup update.
+    pub text: String,
+}
+
+impl From<StandupSummary> for StandupSummaryResponse {
+    fn from(summary: StandupSummary) -> Self {
+        let text = summary.to_text();
+        Self {
+            date: summary.date,
+            items: summary
+                .items
+                .into_iter()
+                .map(StandupItemResponse::from)
+                .collect(),
+            text,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AccountabilitySettingsResponse {
+    pub enabled: bool,
+    pub webhook_url: Option<String>,
+    pub partner_email: Option<String>,
+    pub weekly_report: bool,
+    pub abandon_notice: bool,
+    pub report_day: u32,
+}
+
+impl AccountabilitySettingsResponse {
+    pub fn new(settings: AccountabilitySettings, webhook_url: Option<String>) -> Self {
+        Self {
+            enabled: settings.enabled,
+            webhook_url,
+            partner_email: settings.partner_email,
+            weekly_report: settings.weekly_report,
+            abandon_notice: settings.abandon_notice,
+            report_day: settings.report_day,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateAccountabilitySettingsRequest {
+    pub enabled: bool,
+    pub webhook_url: Option<String>,
+    pub partner_email: Option<String>,
+    pub weekly_report: bool,
+    pub abandon_notice: bool,
+    pub report_day: u32,
+}
+
+#[derive(Serialize)]
+pub struct RuleSuggestionResponse {
+    pub id: i64,
+    pub pattern: String,
+    pub match_type: String,
+    pub category_id: i64,
+    pub source: String,
+    pub confidence: f64,
+    pub created_at: i64,
+}
+
+impl From<RuleSuggestion> for RuleSuggestionResponse {
+    fn from(suggestion: RuleSuggestion) -> Self {
+        Self {
+            id: suggestion.id.unwrap_or(0),
+            pattern: suggestion.pattern,
+            match_type: suggestion.match_type.as_str().to_string(),
+            category_id: suggestion.category_id,
+            source: suggestion.source,
+            confidence: suggestion.confidence,
+            created_at: suggestion.created_at,
+        }
+    }
+}
+
+/// LLM settings without the API key, which never leaves the backend.
+#[derive(Serialize)]
+pub struct LlmSettingsResponse {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub model: String,
+    pub has_api_key: bool,
+}
+
+impl LlmSettingsResponse {
+    pub fn new(settings: LlmSettings, has_api_key: bool) -> Self {
+        Self {
+            enabled: settings.enabled,
+            endpoint: settings.endpoint,
+            model: settings.model,
+            has_api_key,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateLlmSettingsRequest {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub model: String,
+    /// `None` keeps the stored key, an empty string clears it.
+    pub api_key: Option<String>,
+}
+
+/// Time export settings without the API tokens, which never leave the keychain.
+#[derive(Serialize)]
+pub struct TimeExportSettingsResponse {
+    pub has_toggl_api_token: bool,
+    pub toggl_workspace_id: Option<String>,
+    pub has_clockify_api_key: bool,
+    pub clockify_workspace_id: Option<String>,
+}
+
+impl TimeExportSettingsResponse {
+    pub fn new(
+        settings: TimeExportSettings,
+        has_toggl_api_token: bool,
+        has_clockify_api_key: bool,
+    ) -> Self {
+        Self {
+            has_toggl_api_token,
+            toggl_workspace_id: settings.toggl_workspace_id,
+            has_clockify_api_key,
+            clockify_workspace_id: settings.clockify_workspace_id,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTimeExportSettingsRequest {
+    /// `None` keeps the stored token, an empty string clears it.
+    pub toggl_api_token: Option<String>,
+    pub toggl_workspace_id: Option<String>,
+    /// `None` keeps the stored key, an empty string clears it.
+    pub clockify_api_key: Option<String>,
+    pub clockify_workspace_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DiscordPresenceSettingsResponse {
+    pub enabled: bool,
+    pub client_id: Option<String>,
+}
+
+impl From<DiscordPresenceSettings> for DiscordPresenceSettingsResponse {
+    fn from(settings: DiscordPresenceSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            client_id: settings.client_id,
+        }
+    }
+}
+
+/// MQTT settings without the password, which never leaves the keychain.
+#[derive(Serialize)]
+pub struct MqttSettingsResponse {
+    pub enabled: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    pub username: Option<String>,
+    pub has_password: bool,
+    pub topic_prefix: String,
+}
+
+impl MqttSettingsResponse {
+    pub fn new(settings: MqttSettings, has_password: bool) -> Self {
+        Self {
+            enabled: settings.enabled,
+            host: settings.host,
+            port: settings.port,
+            username: settings.username,
+            has_password,
+            topic_prefix: settings.topic_prefix,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateMqttSettingsRequest {
+    pub enabled: bool,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    /// `None` keeps the stored password, an empty string clears it.
+    pub password: Option<String>,
+    pub topic_prefix: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DeepWorkSettingsResponse {
+    pub offer_extension: bool,
+    pub threshold_minutes: i32,
+    pub extension_minutes: i32,
+}
+
+impl From<DeepWorkSettings> for DeepWorkSettingsResponse {
+    fn from(settings: DeepWorkSettings) -> Self {
+        Self {
+            offer_extension: settings.offer_extension,
+            threshold_minutes: settings.threshold_minutes,
+            extension_minutes: settings.extension_minutes,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateDeepWorkSettingsRequest {
+    pub offer_extension: bool,
+    pub threshold_minutes: i32,
+    pub extension_minutes: i32,
+}
+
+#[derive(Serialize)]
+pub struct BudgetAccrualSettingsResponse {
+    pub enabled: bool,
+    pub interval_minutes: i32,
+    pub reward_secs: i32,
+    pub max_earned_secs: i32,
+}
+
+impl From<AccrualSettings> for BudgetAccrualSettingsResponse {
+    fn from(settings: AccrualSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            interval_minutes: settings.interval_minutes,
+            reward_secs: settings.reward_secs,
+            max_earned_secs: settings.max_earned_secs,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateBudgetAccrualSettingsRequest {
+    pub enabled: bool,
+    pub interval_minutes: i32,
+    pub reward_secs: i32,
+    pub max_earned_secs: i32,
+}
+
+#[derive(Serialize)]
+pub struct UndoResponse {
+    /// `delete_category`, `delete_rule`, `delete_schedule` or `recategorize`.
+    pub kind: String,
+    /// What was undone, e.g. "delete category Coding".
+    pub description: String,
+}
+
+impl From<Operation> for UndoResponse {
+    fn from(operation: Operation) -> Self {
+        Self {
+            kind: operation.kind().to_string(),
+            description: operation.describe(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TrashedRuleResponse {
+    pub rule: RuleResponse,
+    pub deleted_at: i64,
+    /// When it's deleted for good.
+    pub purge_at: i64,
+}
+
+#[derive(Serialize)]
+pub struct TrashedScheduleResponse {
+    pub schedule: FocusScheduleResponse,
+    pub deleted_at: i64,
+    pub purge_at: i64,
+}
+
+#[derive(Serialize)]
+pub struct TrashResponse {
+    /// Most recently deleted first.
+    pub rules: Vec<TrashedRuleResponse>,
+    pub schedules: Vec<TrashedScheduleResponse>,
+}
+
+#[derive(Serialize)]
+pub struct DatabaseLocationResponse {
+    pub path: String,
+    /// Whether the database is in the app's own data folder.
+    pub is_default: bool,
+}
+
+#[derive(Serialize)]
+pub struct ConfigOverrideResponse {
+    pub key: String,
+    pub value: String,
+    /// "config" for `config.toml`, "policy" for a managed-policy lock.
+    pub source: String,
+}
+
+#[derive(Serialize)]
+pub struct InsightResponse {
+    pub id: i64,
+    /// "late_distraction" or "focus_interrupter".
+    pub kind: String,
+    pub week_start: String,
+    pub message: String,
+    pub created_at: i64,
+    /// "new" or "acknowledged".
+    pub status: String,
+}
+
+impl From<Insight> for InsightResponse {
+    fn from(insight: Insight) -> Self {
+        Self {
+            id: insight.id,
+            kind: insight.kind,
+            week_start: insight.week_start,
+            message: insight.message,
+            created_at: insight.created_at,
+            status: insight.status,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DayQualityResponse {
+    pub date: String,
+    pub tracked_secs: i64,
+    pub typical_secs: Option<i64>,
+    /// "ok", "low" or "missing".
+    pub status: String,
+}
+
+impl From<DayQuality> for DayQualityResponse {
+    fn from(day: DayQuality) -> Self {
+        Self {
+            date: day.date,
+            tracked_secs: day.tracked_secs,
+            typical_secs: day.typical_secs,
+            status: day.status.as_str().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ScheduleTemplateResponse {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+}
+
+impl From<&ScheduleTemplate> for ScheduleTemplateResponse {
+    fn from(template: &ScheduleTemplate) -> Self {
+        Self {
+            name: template.name.to_string(),
+            title: template.title.to_string(),
+            description: template.description.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AppliedTemplateResponse {
+    /// Schedules created; empty if they all existed already.
+    pub schedules: Vec<FocusScheduleResponse>,
+    pub categories_added: usize,
+    pub rules_added: usize,
+}
+
+impl From<Applied> for AppliedTemplateResponse {
+    fn from(applied: Applied) -> Self {
+        Self {
+            schedules: applied
+                .schedules
+                .into_iter()
+                .map(FocusScheduleResponse::from)
+                .collect(),
+            categories_added: applied.categories_added,
+            rules_added: applied.rules_added,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ParsedScheduleResponse {
+    pub days_of_week: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub distraction_budget_secs: i32,
+    /// How the text was read, for the user to confirm.
+    pub summary: String,
+    /// The schedule created, once confirmed.
+    pub schedule: Option<FocusScheduleResponse>,
+}
+
+impl ParsedScheduleResponse {
+    pub fn new(parsed: ParsedSchedule, schedule: Option<FocusScheduleResponse>) -> Self {
+        Self {
+            days_of_week: parsed.days_of_week,
+            start_time: parsed.start_time,
+            end_time: parsed.end_time,
+            distraction_budget_secs: parsed.distraction_budget,
+            summary: parsed.summary,
+            schedule,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AwayModeResponse {
+    pub start_date: String,
+    /// When tracking and schedules resume.
+    pub until_date: String,
+}
+
+impl From<AwayPeriod> for AwayModeResponse {
+    fn from(period: AwayPeriod) -> Self {
+        Self {
+            start_date: period.start_date,
+            until_date: period.until_date,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PersonalTimeResponse {
+    /// The rule group used outside work hours, if any.
+    pub group_id: Option<i64>,
+    pub work_hours: Vec<QuietRange>,
+}
+
+impl From<PersonalTime> for PersonalTimeResponse {
+    fn from(personal: PersonalTime) -> Self {
+        Self {
+            group_id: personal.group_id,
+            work_hours: personal.work_hours,
+        }
+    }
+}
+
+/// Email report settings without the SMTP password, which stays in the
+/// keychain.
+#[derive(Serialize)]
+pub struct EmailReportSettingsResponse {
+    pub enabled: bool,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub username: Option<String>,
+    pub has_password: bool,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub day: u32,
+    pub time: String,
+    /// Why the last scheduled email failed, until one goes through.
+    pub last_error: Option<String>,
+}
+
+impl EmailReportSettingsResponse {
+    pub fn new(
+        settings: EmailReportSettings,
+        has_password: bool,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            enabled: settings.enabled,
+            smtp_host: settings.smtp_host,
+            smtp_port: settings.smtp_port,
+            username: settings.username,
+            has_password,
+            from: settings.from,
+            to: settings.to,
+            day: settings.day,
+            time: settings.time,
+            last_error,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateEmailReportSettingsRequest {
+    pub enabled: bool,
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub username: Option<String>,
+    /// `None` keeps the stored password, an empty string clears it.
+    pub password: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub day: u32,
+    pub time: String,
+}
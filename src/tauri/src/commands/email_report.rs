@@ -0,0 +1,115 @@
+use crate::db::{with_connection, Database};
+use crate::email_report::{self, EmailReportSettings, DEFAULT_SMTP_PORT, PASSWORD_SECRET};
+use crate::error::AppError;
+use crate::secrets;
+use crate::validation::{validate_email, validate_host, validate_time_format, validate_weekday};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use super::{EmailReportSettingsResponse, UpdateEmailReportSettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_email_report_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<EmailReportSettingsResponse, String> {
+    let (settings, last_error) = with_connection(&db, |conn| {
+        Ok((
+            EmailReportSettings::load(conn)?,
+            email_report::last_error(conn)?,
+        ))
+    })?;
+    let has_password = secrets::read(PASSWORD_SECRET).is_some();
+    Ok(EmailReportSettingsResponse::new(
+        settings,
+        has_password,
+        last_error,
+    ))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_email_report_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateEmailReportSettingsRequest,
+) -> Result<EmailReportSettingsResponse, String> {
+    validate_weekday(request.day)?;
+    validate_time_format(&request.time)?;
+    let smtp_host = request
+        .smtp_host
+        .as_deref()
+        .filter(|host| !host.trim().is_empty())
+        .map(validate_host)
+        .transpose()?
+        .map(str::to_string);
+    let address = |address: &Option<String>| -> Result<Option<String>, AppError> {
+        Ok(address
+            .as_deref()
+            .filter(|address| !address.trim().is_empty())
+            .map(validate_email)
+            .transpose()?
+            .map(str::to_string))
+    };
+    let from = address(&request.from)?;
+    let to = address(&request.to)?;
+    if request.enabled && (smtp_host.is_none() || from.is_none() || to.is_none()) {
+        return Err(AppError::InvalidInput {
+            field: "smtp_host",
+            reason: "a server and both addresses are required to email reports".into(),
+        }
+        .to_string());
+    }
+    if request.smtp_port == Some(0) {
+        return Err(AppError::InvalidInput {
+            field: "smtp_port",
+            reason: "must be 1-65535".into(),
+        }
+        .to_string());
+    }
+    let username = request
+        .username
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+
+    let has_password = secrets::update(PASSWORD_SECRET, request.password.as_deref())?;
+
+    let settings = EmailReportSettings {
+        enabled: request.enabled,
+        smtp_host,
+        smtp_port: request.smtp_port.unwrap_or(DEFAULT_SMTP_PORT),
+        username,
+        from,
+        to,
+        day: request.day,
+        time: request.time,
+    };
+    let last_error = with_connection(&db, |conn| {
+        settings.save(conn)?;
+        email_report::last_error(conn)
+    })?;
+    Ok(EmailReportSettingsResponse::new(
+        settings,
+        has_password,
+        last_error,
+    ))
+}
+
+/// Email last week's report now, to check the mail settings.
+#[tauri::command]
+pub async fn send_email_report(app: AppHandle) -> Result<(), String> {
+    let db = Arc::clone(app.state::<Arc<Mutex<Database>>>().inner());
+
+    // Sending waits on the mail server, so keep it off the async runtime's worker threads
+    tauri::async_runtime::spawn_blocking(move || email_report::send_now(&db))
+        .await
+        .map_err(|e| AppError::Internal(format!("email report task failed: {e}")).to_string())??;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::aggregate_export::{self, Bucket};
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::local_time::{date_in, day_bounds, days_ending};
+use crate::secrets;
+use crate::time_export::{
+    self, EntrySource, Provider, TimeExportSettings, CLOCKIFY_KEY_SECRET, TOGGL_TOKEN_SECRET,
+};
+use crate::validation::{validate_aggregate_days, validate_folder, validate_workspace_id};
+use chrono::Local;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use super::stats::{get_current_timestamp, range_bounds};
+use super::{TimeExportSettingsResponse, UpdateTimeExportSettingsRequest};
+
+fn workspace_id(field: &'static str, id: Option<&str>) -> Result<Option<String>, AppError> {
+    id.filter(|id| !id.trim().is_empty())
+        .map(|id| validate_workspace_id(field, id).map(str::to_string))
+        .transpose()
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_time_export_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<TimeExportSettingsResponse, String> {
+    let settings = with_connection(&db, TimeExportSettings::load)?;
+    Ok(TimeExportSettingsResponse::new(
+        settings,
+        secrets::read(TOGGL_TOKEN_SECRET).is_some(),
+        secrets::read(CLOCKIFY_KEY_SECRET).is_some(),
+    ))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_time_export_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateTimeExportSettingsRequest,
+) -> Result<TimeExportSettingsResponse, String> {
+    let toggl_workspace_id =
+        workspace_id("toggl_workspace_id", request.toggl_workspace_id.as_deref())?;
+    if toggl_workspace_id
+        .as_deref()
+        .is_some_and(|id| !id.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(AppError::InvalidInput {
+            field: "toggl_workspace_id",
+            reason: "must be numeric".into(),
+        }
+        .to_string());
+    }
+    let clockify_workspace_id = workspace_id(
+        "clockify_workspace_id",
+        request.clockify_workspace_id.as_deref(),
+    )?;
+
+    let has_toggl_api_token = secrets::update(
+        TOGGL_TOKEN_SECRET,
+        request.toggl_api_token.as_deref().map(str::trim),
+    )?;
+    let has_clockify_api_key = secrets::update(
+        CLOCKIFY_KEY_SECRET,
+        request.clockify_api_key.as_deref().map(str::trim),
+    )?;
+
+    let settings = TimeExportSettings {
+        toggl_workspace_id,
+        clockify_workspace_id,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(TimeExportSettingsResponse::new(
+        settings,
+        has_toggl_api_token,
+        has_clockify_api_key,
+    ))
+}
+
+/// Send focus sessions (`source` "sessions") or project blocks ("projects")
+/// over a range to Toggl or Clockify as time entries. Entries already sent
+/// to that provider are skipped. Returns how many were created.
+#[tauri::command]
+pub async fn export_time_entries(
+    app: AppHandle,
+    provider: String,
+    source: String,
+    range: String,
+) -> Result<usize, String> {
+    let provider = Provider::parse(&provider).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "provider",
+            reason: "must be 'toggl' or 'clockify'".into(),
+        }
+        .to_string()
+    })?;
+    let source = EntrySource::parse(&source).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "source",
+            reason: "must be 'sessions' or 'projects'".into(),
+        }
+        .to_string()
+    })?;
+    let (start, end) = range_bounds(&range, get_current_timestamp()?)?;
+    let db = Arc::clone(app.state::<Arc<Mutex<Database>>>().inner());
+
+    // The HTTP requests block, so keep them off the async runtime's worker threads
+    let exported = tauri::async_runtime::spawn_blocking(move || {
+        time_export::export(&db, provider, source, start, end)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("export task failed: {e}")).to_string())??;
+    Ok(exported)
+}
+
+/// Write time per category per `bucket` ("hour" or "day") over the last
+/// `days` days to `foxus-aggregates.csv` in `folder`. Nothing else goes in,
+/// so the file can be shared. Returns the path written.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn export_aggregates_anonymized(
+    db: State<Arc<Mutex<Database>>>,
+    folder: String,
+    days: u32,
+    bucket: String,
+) -> Result<String, String> {
+    let folder = validate_folder(&folder)?;
+    validate_aggregate_days(days)?;
+    let bucket = Bucket::parse(&bucket).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "bucket",
+            reason: "must be 'hour' or 'day'".into(),
+        }
+        .to_string()
+    })?;
+    let now = get_current_timestamp()?;
+    let today = date_in(&Local, now);
+    let first_day = days_ending(today, u64::from(days)).next().unwrap_or(today);
+    let (start, _) = day_bounds(first_day);
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let path = aggregate_export::export(db.connection(), Path::new(folder), start, now, bucket)?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -1,15 +1,36 @@
+use crate::accountability::on_session_ended;
+use crate::audit_log::Origin;
+use crate::categorizer::Categorizer;
+use crate::constants::DEFAULT_HISTORY_LIMIT;
 use crate::db::{with_connection, Database};
+use crate::discord_presence::sync_discord_presence;
 use crate::error::AppError;
-use crate::focus::FocusManager;
-use crate::models::FocusSchedule;
+use crate::focus::{
+    distraction_grant_secs, set_distraction_grant_secs, set_unlock_delay_minutes,
+    unlock_delay_minutes, FocusManager, UnlockStatus, EMERGENCY_UNLOCK_PHRASE,
+};
+use crate::journal;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{BlockedAttempt, DistractionEvent, FocusSchedule, FocusSession};
+use crate::mqtt::publish_focus_state;
+use crate::offline_focus;
+use crate::recommendation::{daily_goal_minutes, set_daily_goal_minutes, today_recommendation};
+use crate::safe_lock;
+use crate::schedule_text;
 use crate::validation::{
-    validate_budget_minutes, validate_budget_secs, validate_days_of_week, validate_time_format,
+    validate_allow_minutes, validate_budget_minutes, validate_budget_secs, validate_days_of_week,
+    validate_domain, validate_goal_minutes, validate_grant_secs, validate_rule_pattern,
+    validate_session_exceptions, validate_session_minutes, validate_time_format,
+    validate_unlock_delay_minutes,
 };
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{AppHandle, State};
 
 use super::{
-    CreateScheduleRequest, FocusScheduleResponse, FocusStateResponse, UpdateScheduleRequest,
+    BlockedAttemptResponse, CategoryBudgetRequest, CategoryBudgetResponse, CreateScheduleRequest,
+    DistractionEventResponse, EmergencyUnlockResponse, EmergencyUnlockSettingsResponse,
+    FocusRecommendationResponse, FocusScheduleResponse, FocusSessionResponse, FocusStateResponse,
+    ParsedScheduleResponse, TemporaryAllowResponse, UpdateScheduleRequest,
 };
 
 #[tauri::command]
@@ -27,6 +48,14 @@ pub fn get_focus_state(
         active: state.active,
         budget_remaining: state.budget_remaining,
         session_duration_secs: state.session_duration_secs,
+        time_remaining_secs: state.time_remaining_secs,
+        category_budgets: state
+            .category_budgets
+            .into_iter()
+            .map(CategoryBudgetResponse::from)
+            .collect(),
+        exceptions: state.allowed_domains,
+        blocked_categories: state.blocked_categories,
     })
 }
 
@@ -35,14 +64,47 @@ pub fn get_focus_state(
     clippy::needless_pass_by_value,
     reason = "Tauri command handlers receive owned deserialized values"
 )]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "Tauri command arguments mirror the frontend call; two are injected state"
+)]
 pub fn start_focus_session(
+    app: AppHandle,
     focus_manager: State<Arc<FocusManager>>,
     budget_minutes: i32,
+    duration_minutes: Option<i32>,
+    strict: Option<bool>,
+    category_budgets: Option<Vec<CategoryBudgetRequest>>,
+    exceptions: Option<Vec<String>>,
+    blocked_categories: Option<Vec<i64>>,
 ) -> Result<(), String> {
     let budget_secs = validate_budget_minutes(budget_minutes)?;
-    focus_manager
-        .start_session(budget_secs)
-        .map_err(|e| AppError::from(e).to_string())?;
+    let exceptions = validate_session_exceptions(&exceptions.unwrap_or_default())?;
+    let category_budgets = category_budgets
+        .unwrap_or_default()
+        .iter()
+        .map(|b| Ok((b.category_id, validate_budget_minutes(b.minutes)?)))
+        .collect::<Result<Vec<_>, AppError>>()?;
+    let duration_secs = duration_minutes.map(validate_session_minutes).transpose()?;
+    let strict = strict.unwrap_or(false);
+    if strict && duration_secs.is_none() {
+        return Err(AppError::InvalidInput {
+            field: "strict",
+            reason: "a strict session needs a duration".into(),
+        }
+        .to_string());
+    }
+    focus_manager.start_scoped_session(
+        budget_secs,
+        duration_secs,
+        strict,
+        &category_budgets,
+        &exceptions,
+        &blocked_categories.unwrap_or_default(),
+        Origin::Ui,
+    )?;
+    sync_discord_presence(&app);
+    publish_focus_state(&app);
     Ok(())
 }
 
@@ -51,10 +113,275 @@ pub fn start_focus_session(
     clippy::needless_pass_by_value,
     reason = "Tauri command handlers receive owned deserialized values"
 )]
-pub fn end_focus_session(focus_manager: State<Arc<FocusManager>>) -> Result<(), String> {
-    focus_manager
-        .end_session()
-        .map_err(|e| AppError::from(e).to_string())?;
+pub fn end_focus_session(
+    app: AppHandle,
+    focus_manager: State<Arc<FocusManager>>,
+) -> Result<(), String> {
+    if let Some(session) = focus_manager.end_session(Origin::Ui)? {
+        on_session_ended(&app, &session);
+    }
+    sync_discord_presence(&app);
+    publish_focus_state(&app);
+    Ok(())
+}
+
+/// Block a domain pattern until the active session ends, without adding a rule.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn add_session_block(
+    focus_manager: State<Arc<FocusManager>>,
+    pattern: String,
+) -> Result<(), String> {
+    let pattern = validate_rule_pattern(&pattern)?.to_lowercase();
+    focus_manager.add_session_blocks(&[&pattern])?;
+    Ok(())
+}
+
+/// Replace the domains the active session leaves unblocked, e.g.
+/// `music.youtube.com` while Entertainment is blocked.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_session_exceptions(
+    focus_manager: State<Arc<FocusManager>>,
+    domains: Vec<String>,
+) -> Result<(), String> {
+    let domains = validate_session_exceptions(&domains)?;
+    focus_manager.set_session_exceptions(&domains)?;
+    Ok(())
+}
+
+/// Block categories that aren't distracting (e.g. Communication) for the
+/// rest of the active session, replacing any set before. Stats still score
+/// them by their productivity.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_session_blocked_categories(
+    focus_manager: State<Arc<FocusManager>>,
+    category_ids: Vec<i64>,
+) -> Result<(), String> {
+    focus_manager.set_session_blocked_categories(&category_ids)?;
+    Ok(())
+}
+
+/// Unblock one domain for a few minutes instead of ending the session. The
+/// time is taken from the distraction budget up front.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn allow_domain_temporarily(
+    focus_manager: State<Arc<FocusManager>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    domain: String,
+    minutes: i32,
+) -> Result<TemporaryAllowResponse, String> {
+    let domain = validate_domain(&domain)?;
+    let secs = validate_allow_minutes(minutes)?;
+    let category_id = safe_lock(&categorizer, "Categorizer").categorize_url(&domain, None);
+    let allow = focus_manager.allow_domain_temporarily(
+        &domain,
+        secs,
+        Some(category_id),
+        current_timestamp(),
+    )?;
+    Ok(TemporaryAllowResponse::from(allow))
+}
+
+/// End a strict or hard-blocked session early.
+///
+/// Needs the confirmation phrase, then a second call once the configured
+/// delay has passed; until then it reports how long is left to wait.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn emergency_unlock(
+    app: AppHandle,
+    focus_manager: State<Arc<FocusManager>>,
+    phrase: String,
+) -> Result<EmergencyUnlockResponse, String> {
+    match focus_manager.emergency_unlock(&phrase, current_timestamp(), Origin::Ui)? {
+        UnlockStatus::Waiting { remaining_secs } => Ok(EmergencyUnlockResponse {
+            unlocked: false,
+            wait_secs: remaining_secs,
+        }),
+        UnlockStatus::Unlocked(session) => {
+            on_session_ended(&app, &session);
+            Ok(EmergencyUnlockResponse {
+                unlocked: true,
+                wait_secs: 0,
+            })
+        }
+    }
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_emergency_unlock_settings(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<EmergencyUnlockSettingsResponse, String> {
+    let delay_minutes = with_connection(&db, unlock_delay_minutes)?;
+    Ok(EmergencyUnlockSettingsResponse {
+        phrase: EMERGENCY_UNLOCK_PHRASE.to_string(),
+        delay_minutes,
+    })
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_emergency_unlock_delay(
+    db: State<Arc<Mutex<Database>>>,
+    minutes: i32,
+) -> Result<(), String> {
+    validate_unlock_delay_minutes(minutes)?;
+    with_connection(&db, |conn| set_unlock_delay_minutes(conn, minutes))?;
+    Ok(())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_distraction_grant(db: State<Arc<Mutex<Database>>>) -> Result<i32, String> {
+    Ok(with_connection(&db, distraction_grant_secs)?)
+}
+
+/// Set how many budget seconds each "use distraction time" in the browser draws.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_distraction_grant(db: State<Arc<Mutex<Database>>>, secs: i32) -> Result<(), String> {
+    validate_grant_secs(secs)?;
+    with_connection(&db, |conn| set_distraction_grant_secs(conn, secs))?;
+    Ok(())
+}
+
+/// Recent focus sessions, newest first, with focused vs idle time and how
+/// often each blocked site was tried.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_focus_history(
+    db: State<Arc<Mutex<Database>>>,
+    limit: Option<u32>,
+) -> Result<Vec<FocusSessionResponse>, String> {
+    let limit = i64::from(limit.unwrap_or(DEFAULT_HISTORY_LIMIT));
+    let history = with_connection(&db, |conn| {
+        FocusSession::find_recent(conn, limit)?
+            .into_iter()
+            .map(|session| {
+                let attempts = match session.id {
+                    Some(id) => BlockedAttempt::find_by_session(conn, id)?,
+                    None => Vec::new(),
+                };
+                Ok(FocusSessionResponse {
+                    blocked_attempts: attempts
+                        .into_iter()
+                        .map(BlockedAttemptResponse::from)
+                        .collect(),
+                    ..FocusSessionResponse::from(session)
+                })
+            })
+            .collect()
+    })?;
+    Ok(history)
+}
+
+/// Log deep work done away from the computer between `start` and `end`
+/// (Unix seconds) as a completed session and a productive activity block.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn log_offline_focus(
+    db: State<Arc<Mutex<Database>>>,
+    start: i64,
+    end: i64,
+    note: Option<String>,
+) -> Result<FocusSessionResponse, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let session = offline_focus::log(
+        db.connection(),
+        start,
+        end,
+        note.as_deref(),
+        current_timestamp(),
+    )?;
+    Ok(FocusSessionResponse::from(session))
+}
+
+/// Where a session's distraction budget went, oldest first.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_distraction_events(
+    db: State<Arc<Mutex<Database>>>,
+    session_id: i64,
+) -> Result<Vec<DistractionEventResponse>, String> {
+    let events = with_connection(&db, |conn| {
+        DistractionEvent::find_by_session(conn, session_id)
+    })?;
+    Ok(events
+        .into_iter()
+        .map(DistractionEventResponse::from)
+        .collect())
+}
+
+/// Suggested session length and budget for starting focus right now.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_focus_recommendation(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<FocusRecommendationResponse, String> {
+    let recommendation = with_connection(&db, today_recommendation)?;
+    Ok(FocusRecommendationResponse::from(recommendation))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_daily_focus_goal(db: State<Arc<Mutex<Database>>>) -> Result<i32, String> {
+    Ok(with_connection(&db, daily_goal_minutes)?)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_daily_focus_goal(db: State<Arc<Mutex<Database>>>, minutes: i32) -> Result<(), String> {
+    validate_goal_minutes(minutes)?;
+    with_connection(&db, |conn| set_daily_goal_minutes(conn, minutes))?;
     Ok(())
 }
 
@@ -88,6 +415,9 @@ pub fn create_focus_schedule(
         &request.days_of_week,
         request.distraction_budget_secs,
     )?;
+    if let Some(minutes) = request.notify_lead_minutes {
+        validate_notify_lead_minutes(minutes)?;
+    }
 
     let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
     let conn = db.connection();
@@ -98,6 +428,10 @@ pub fn create_focus_schedule(
         &request.end_time,
         request.distraction_budget_secs,
     );
+    schedule.blocked_categories = request.blocked_categories;
+    if let Some(minutes) = request.notify_lead_minutes {
+        schedule.notify_lead_minutes = minutes;
+    }
     schedule
         .save(conn)
         .map_err(|e| AppError::from(e).to_string())?;
@@ -105,6 +439,37 @@ pub fn create_focus_schedule(
     Ok(FocusScheduleResponse::from(schedule))
 }
 
+/// Read a schedule from a phrase like "weekdays 9am-11am, 10 min budget".
+/// Returns how it was read; with `confirm` the schedule is also created.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn create_schedule_from_text(
+    db: State<Arc<Mutex<Database>>>,
+    text: String,
+    confirm: bool,
+) -> Result<ParsedScheduleResponse, String> {
+    let parsed = schedule_text::parse(&text)?;
+    let schedule = if confirm {
+        let mut schedule = FocusSchedule::new(
+            &parsed.days_of_week,
+            &parsed.start_time,
+            &parsed.end_time,
+            parsed.distraction_budget,
+        );
+        with_connection(&db, |conn| schedule.save(conn))?;
+        Some(FocusScheduleResponse::from(schedule))
+    } else {
+        None
+    };
+
+    Ok(ParsedScheduleResponse::new(parsed, schedule))
+}
+
+/// Update a schedule. A `request.version` older than the stored one fails
+/// with a conflict instead of overwriting another window's edit.
 #[tauri::command]
 #[expect(
     clippy::needless_pass_by_value,
@@ -120,28 +485,40 @@ pub fn update_focus_schedule(
         &request.days_of_week,
         request.distraction_budget_secs,
     )?;
+    if let Some(minutes) = request.notify_lead_minutes {
+        validate_notify_lead_minutes(minutes)?;
+    }
 
     let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
     let conn = db.connection();
 
-    if FocusSchedule::find_by_id(conn, request.id)
+    let current = FocusSchedule::find_by_id(conn, request.id)
         .map_err(|e| AppError::from(e).to_string())?
-        .is_none()
-    {
-        return Err(AppError::NotFound { entity: "Schedule" }.to_string());
-    }
+        .ok_or_else(|| AppError::NotFound { entity: "Schedule" }.to_string())?;
 
-    let schedule = FocusSchedule {
+    let mut schedule = FocusSchedule {
         id: Some(request.id),
         days_of_week: request.days_of_week,
         start_time: request.start_time,
         end_time: request.end_time,
         distraction_budget: request.distraction_budget_secs,
         enabled: request.enabled,
+        blocked_categories: request.blocked_categories,
+        version: request.version.unwrap_or(current.version),
+        notify_lead_minutes: request
+            .notify_lead_minutes
+            .unwrap_or(current.notify_lead_minutes),
     };
-    schedule
+    if !schedule
         .update(conn)
-        .map_err(|e| AppError::from(e).to_string())?;
+        .map_err(|e| AppError::from(e).to_string())?
+    {
+        return Err(AppError::Conflict {
+            entity: "Schedule",
+            current_version: current.version,
+        }
+        .to_string());
+    }
 
     Ok(FocusScheduleResponse::from(schedule))
 }
@@ -152,7 +529,9 @@ pub fn update_focus_schedule(
     reason = "Tauri command handlers receive owned deserialized values"
 )]
 pub fn delete_focus_schedule(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<bool, String> {
-    let result = with_connection(&db, |conn| FocusSchedule::delete(conn, id))?;
+    let result = with_connection(&db, |conn| {
+        journal::delete_schedule(conn, id, current_timestamp())
+    })?;
     Ok(result)
 }
 
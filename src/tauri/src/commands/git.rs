@@ -0,0 +1,61 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::git_commits;
+use crate::validation::validate_git_repositories;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::stats::{get_current_timestamp, range_bounds};
+use super::CommitActivityResponse;
+
+/// Local repositories scanned by `get_commit_activity`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_git_repositories(db: State<Arc<Mutex<Database>>>) -> Result<Vec<String>, String> {
+    Ok(with_connection(&db, git_commits::repositories)?)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_git_repositories(
+    db: State<Arc<Mutex<Database>>>,
+    repositories: Vec<String>,
+) -> Result<(), String> {
+    let repositories = validate_git_repositories(&repositories)?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(git_commits::set_repositories(
+        db.connection(),
+        &repositories,
+    )?)
+}
+
+/// Your commits over a range in the configured repositories, newest first,
+/// with the productive time tracked since the previous commit in each repo.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_commit_activity(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<CommitActivityResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let repos = with_connection(&db, git_commits::repositories)?;
+    // Run git without holding the database lock
+    let commits = git_commits::scan_all(&repos, start, end);
+    let activity = with_connection(&db, |conn| {
+        git_commits::commit_activity(conn, commits, start)
+    })?;
+    Ok(activity
+        .into_iter()
+        .map(CommitActivityResponse::from)
+        .collect())
+}
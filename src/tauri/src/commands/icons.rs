@@ -0,0 +1,20 @@
+use crate::error::AppError;
+use crate::icons::{icons_dir, read_icon, IconKind};
+
+/// Cached icon for a domain or app as a data URL; `None` until the daily
+/// icon job has found one.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_icon(kind: String, name: String) -> Result<Option<String>, String> {
+    let kind = IconKind::parse(&kind).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "kind",
+            reason: "must be 'domain' or 'app'".into(),
+        }
+        .to_string()
+    })?;
+    Ok(read_icon(&icons_dir()?, kind, &name))
+}
@@ -0,0 +1,47 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::insights::{find_active, set_status, InsightStatus};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::InsightResponse;
+
+/// Insights the user hasn't dismissed, newest week first.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_insights(db: State<Arc<Mutex<Database>>>) -> Result<Vec<InsightResponse>, String> {
+    let insights = with_connection(&db, find_active)?;
+    Ok(insights.into_iter().map(InsightResponse::from).collect())
+}
+
+/// Keep showing an insight, marked as seen.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn acknowledge_insight(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<(), String> {
+    update_status(&db, id, InsightStatus::Acknowledged)
+}
+
+/// Hide an insight for good.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn dismiss_insight(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<(), String> {
+    update_status(&db, id, InsightStatus::Dismissed)
+}
+
+fn update_status(db: &Arc<Mutex<Database>>, id: i64, status: InsightStatus) -> Result<(), String> {
+    let updated = with_connection(db, |conn| set_status(conn, id, status))?;
+    if updated {
+        Ok(())
+    } else {
+        Err(AppError::NotFound { entity: "Insight" }.to_string())
+    }
+}
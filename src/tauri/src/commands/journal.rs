@@ -0,0 +1,34 @@
+use crate::categorizer::Categorizer;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::journal::undo_last;
+use crate::models::focus_session::current_timestamp;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::rules::rules_changed;
+use super::UndoResponse;
+
+/// Reverse the latest deletion or bulk recategorize made in the last
+/// `UNDO_WINDOW_SECS`. `None` when there's nothing left to undo.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn undo_last_operation(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+) -> Result<Option<UndoResponse>, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let undone = undo_last(conn, current_timestamp()).map_err(|e| AppError::from(e).to_string())?;
+    if undone.is_some() {
+        rules_changed(&categorizer, &focus_manager, conn)?;
+    }
+
+    Ok(undone.map(UndoResponse::from))
+}
@@ -0,0 +1,41 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::local_time::{set_week_start as save_week_start, week_start_setting, WeekStart};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::WeekStartResponse;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_week_start(db: State<Arc<Mutex<Database>>>) -> Result<WeekStartResponse, String> {
+    let chosen = with_connection(&db, week_start_setting)?;
+    Ok(WeekStartResponse::new(chosen))
+}
+
+/// Start weeks on `monday` or `sunday`, or `auto` to follow the system locale.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_week_start(
+    db: State<Arc<Mutex<Database>>>,
+    week_start: String,
+) -> Result<WeekStartResponse, String> {
+    let chosen = match week_start.as_str() {
+        "auto" => None,
+        value => Some(WeekStart::parse(value).ok_or_else(|| {
+            AppError::InvalidInput {
+                field: "week_start",
+                reason: "must be 'monday', 'sunday', or 'auto'".into(),
+            }
+            .to_string()
+        })?),
+    };
+    with_connection(&db, |conn| save_week_start(conn, chosen))?;
+    Ok(WeekStartResponse::new(chosen))
+}
@@ -0,0 +1,130 @@
+use crate::categorizer::Categorizer;
+use crate::constants::MAX_SCREEN_TIME_ENTRIES;
+use crate::db::{with_connection, Database};
+use crate::discord_presence::sync_discord_presence;
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::mobile_sync::{self, ScreenTimeEntry, SyncSettings};
+use crate::models::focus_session::current_timestamp;
+use crate::mqtt::publish_focus_state;
+use crate::safe_lock;
+use crate::tray::refresh_tray;
+use crate::validation::{
+    validate_budget_minutes, validate_device_name, validate_folder, validate_session_minutes,
+};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::{SyncResultResponse, SyncSettingsResponse};
+
+/// Store per-app screen time from the phone. Entries sent before are
+/// skipped. Returns how many were new.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn record_screen_time(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    entries: Vec<ScreenTimeEntry>,
+) -> Result<usize, String> {
+    if entries.len() > MAX_SCREEN_TIME_ENTRIES {
+        return Err(AppError::InvalidInput {
+            field: "entries",
+            reason: format!("at most {MAX_SCREEN_TIME_ENTRIES} per call"),
+        }
+        .to_string());
+    }
+    let categorizer = safe_lock(&categorizer, "Categorizer");
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(mobile_sync::record_screen_time(
+        db.connection(),
+        &categorizer,
+        &entries,
+        current_timestamp(),
+    )?)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_sync_settings(db: State<Arc<Mutex<Database>>>) -> Result<SyncSettingsResponse, String> {
+    let settings = with_connection(&db, SyncSettings::load)?;
+    Ok(SyncSettingsResponse::from(settings))
+}
+
+/// Set the shared folder (blank turns sync off) and this device's name.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_sync_settings(
+    db: State<Arc<Mutex<Database>>>,
+    folder: Option<String>,
+    device: Option<String>,
+) -> Result<SyncSettingsResponse, String> {
+    let folder = folder
+        .filter(|f| !f.trim().is_empty())
+        .map(|f| validate_folder(&f).map(str::to_string))
+        .transpose()?;
+    let device = device.map(|d| validate_device_name(&d)).transpose()?;
+
+    let settings = with_connection(&db, |conn| {
+        let current = SyncSettings::load(conn)?;
+        let settings = SyncSettings {
+            folder,
+            device: device.unwrap_or(current.device),
+        };
+        settings.save(conn)?;
+        Ok(settings)
+    })?;
+    Ok(SyncSettingsResponse::from(settings))
+}
+
+/// Ask the other synced devices to start a focus session, e.g. from the
+/// phone, and sync right away.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn request_remote_focus(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    duration_minutes: i32,
+    budget_minutes: i32,
+) -> Result<(), String> {
+    let duration_secs = validate_session_minutes(duration_minutes)?;
+    let budget_secs = validate_budget_minutes(budget_minutes)?;
+    let now = current_timestamp();
+    with_connection(&db, |conn| {
+        mobile_sync::queue_focus_request(conn, duration_secs, budget_secs, now)
+    })?;
+    mobile_sync::sync(&db, &categorizer, &focus_manager, now)?;
+    Ok(())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn sync_now(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+) -> Result<SyncResultResponse, String> {
+    let result = mobile_sync::sync(&db, &categorizer, &focus_manager, current_timestamp())?;
+    if result.started_sessions > 0 {
+        refresh_tray(&app);
+        sync_discord_presence(&app);
+        publish_focus_state(&app);
+    }
+    Ok(SyncResultResponse::from(result))
+}
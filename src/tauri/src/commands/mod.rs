@@ -2,14 +2,92 @@
 //
 // Commands module - provides Tauri IPC command handlers organized by feature.
 
+pub mod accountability;
+pub mod apps;
+pub mod audit_log;
+pub mod away_mode;
+pub mod breaks;
+pub mod browser_integration;
+pub mod budget_accrual;
+pub mod calendar;
 pub mod categories;
+pub mod config;
+pub mod daily_summary;
+pub mod data_quality;
+pub mod database;
+pub mod deep_work;
+pub mod discord;
 mod dtos;
+pub mod email_report;
+pub mod export;
 pub mod focus;
+pub mod git;
+pub mod icons;
+pub mod insights;
+pub mod journal;
+pub mod locale;
+pub mod mobile;
+pub mod mqtt;
+pub mod notes;
+pub mod notifications;
+pub mod onboarding;
+pub mod overlay;
+pub mod rule_groups;
 pub mod rules;
+pub mod schedule_templates;
+pub mod session_queue;
+pub mod snapshot;
+pub mod standup;
 pub mod stats;
+pub mod suggestions;
+pub mod title_context;
+pub mod tracker;
+pub mod trash;
+pub mod tray;
+pub mod updates;
+pub mod widget;
 
+pub use accountability::*;
+pub use apps::*;
+pub use audit_log::*;
+pub use away_mode::*;
+pub use breaks::*;
+pub use browser_integration::*;
+pub use budget_accrual::*;
+pub use calendar::*;
 pub use categories::*;
+pub use config::*;
+pub use daily_summary::*;
+pub use data_quality::*;
+pub use database::*;
+pub use deep_work::*;
+pub use discord::*;
 pub use dtos::*;
+pub use email_report::*;
+pub use export::*;
 pub use focus::*;
+pub use git::*;
+pub use icons::*;
+pub use insights::*;
+pub use journal::*;
+pub use locale::*;
+pub use mobile::*;
+pub use mqtt::*;
+pub use notes::*;
+pub use notifications::*;
+pub use onboarding::*;
+pub use overlay::*;
+pub use rule_groups::*;
 pub use rules::*;
+pub use schedule_templates::*;
+pub use session_queue::*;
+pub use snapshot::*;
+pub use standup::*;
 pub use stats::*;
+pub use suggestions::*;
+pub use title_context::*;
+pub use tracker::*;
+pub use trash::*;
+pub use tray::*;
+pub use updates::*;
+pub use widget::*;
@@ -0,0 +1,81 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::mqtt::{
+    publish_focus_state, MqttSettings, DEFAULT_PORT, DEFAULT_TOPIC_PREFIX, PASSWORD_SECRET,
+};
+use crate::secrets;
+use crate::validation::{validate_host, validate_topic_prefix};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::{MqttSettingsResponse, UpdateMqttSettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_mqtt_settings(db: State<Arc<Mutex<Database>>>) -> Result<MqttSettingsResponse, String> {
+    let settings = with_connection(&db, MqttSettings::load)?;
+    let has_password = secrets::read(PASSWORD_SECRET).is_some();
+    Ok(MqttSettingsResponse::new(settings, has_password))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_mqtt_settings(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateMqttSettingsRequest,
+) -> Result<MqttSettingsResponse, String> {
+    let host = request
+        .host
+        .as_deref()
+        .filter(|host| !host.trim().is_empty())
+        .map(validate_host)
+        .transpose()?
+        .map(str::to_string);
+    if request.enabled && host.is_none() {
+        return Err(AppError::InvalidInput {
+            field: "host",
+            reason: "required to enable MQTT publishing".into(),
+        }
+        .to_string());
+    }
+    if request.port == Some(0) {
+        return Err(AppError::InvalidInput {
+            field: "port",
+            reason: "must be 1-65535".into(),
+        }
+        .to_string());
+    }
+    let topic_prefix = request
+        .topic_prefix
+        .as_deref()
+        .map(validate_topic_prefix)
+        .transpose()?
+        .unwrap_or(DEFAULT_TOPIC_PREFIX)
+        .to_string();
+    let username = request
+        .username
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+
+    let has_password = secrets::update(PASSWORD_SECRET, request.password.as_deref())?;
+
+    let settings = MqttSettings {
+        enabled: request.enabled,
+        host,
+        port: request.port.unwrap_or(DEFAULT_PORT),
+        username,
+        topic_prefix,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    publish_focus_state(&app);
+    Ok(MqttSettingsResponse::new(settings, has_password))
+}
@@ -0,0 +1,79 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Note};
+use crate::validation::{validate_day, validate_note_text};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::NoteResponse;
+
+/// Notes on one local day (YYYY-MM-DD), oldest first.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_notes(
+    db: State<Arc<Mutex<Database>>>,
+    day: String,
+) -> Result<Vec<NoteResponse>, String> {
+    validate_day(&day)?;
+    let notes = with_connection(&db, |conn| Note::find_in_days(conn, &day, &day))?;
+    Ok(notes.into_iter().map(NoteResponse::from).collect())
+}
+
+/// Jot a note on a day, optionally pinned to one of its activity blocks.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn create_note(
+    db: State<Arc<Mutex<Database>>>,
+    day: String,
+    text: String,
+    activity_id: Option<i64>,
+) -> Result<NoteResponse, String> {
+    validate_day(&day)?;
+    let text = validate_note_text(&text)?;
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+    if let Some(id) = activity_id {
+        Activity::find_by_id(conn, id)
+            .map_err(|e| AppError::from(e).to_string())?
+            .ok_or_else(|| AppError::NotFound { entity: "Activity" }.to_string())?;
+    }
+    let note = Note::create(conn, &day, activity_id, text, current_timestamp())
+        .map_err(|e| AppError::from(e).to_string())?;
+    Ok(NoteResponse::from(note))
+}
+
+/// Replace a note's text. Returns `None` if the note doesn't exist.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_note(
+    db: State<Arc<Mutex<Database>>>,
+    id: i64,
+    text: String,
+) -> Result<Option<NoteResponse>, String> {
+    let text = validate_note_text(&text)?;
+    let note = with_connection(&db, |conn| {
+        Note::update_text(conn, id, text, current_timestamp())?;
+        Note::find_by_id(conn, id)
+    })?;
+    Ok(note.map(NoteResponse::from))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn delete_note(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<bool, String> {
+    Ok(with_connection(&db, |conn| Note::delete(conn, id))?)
+}
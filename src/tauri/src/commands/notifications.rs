@@ -0,0 +1,30 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::notifications::{self, QuietRange};
+use crate::validation::validate_quiet_ranges;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_quiet_hours(db: State<Arc<Mutex<Database>>>) -> Result<Vec<QuietRange>, String> {
+    Ok(with_connection(&db, notifications::quiet_hours)?)
+}
+
+/// Replace the quiet hours; an empty list turns them off.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_quiet_hours(
+    db: State<Arc<Mutex<Database>>>,
+    ranges: Vec<QuietRange>,
+) -> Result<(), String> {
+    validate_quiet_ranges(&ranges)?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(notifications::set_quiet_hours(db.connection(), &ranges)?)
+}
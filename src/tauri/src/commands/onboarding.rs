@@ -0,0 +1,40 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::onboarding::{complete_step, OnboardingState, OnboardingStep};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::OnboardingStateResponse;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_onboarding_state(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<OnboardingStateResponse, String> {
+    let state = with_connection(&db, OnboardingState::load)?;
+    Ok(OnboardingStateResponse::from(state))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn complete_onboarding_step(
+    db: State<Arc<Mutex<Database>>>,
+    step: String,
+) -> Result<OnboardingStateResponse, String> {
+    let step = OnboardingStep::from_str(&step).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "step",
+            reason: format!("unknown onboarding step '{step}'"),
+        }
+        .to_string()
+    })?;
+
+    let state = with_connection(&db, |conn| complete_step(conn, step))?;
+    Ok(OnboardingStateResponse::from(state))
+}
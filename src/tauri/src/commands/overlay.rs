@@ -0,0 +1,49 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::overlay::{
+    output_enabled, overlay_path, remove_overlay, set_output_enabled, write_overlay,
+};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::OverlayResponse;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_overlay(db: State<Arc<Mutex<Database>>>) -> Result<OverlayResponse, String> {
+    Ok(OverlayResponse {
+        enabled: with_connection(&db, output_enabled)?,
+        path: overlay_path()?.to_string_lossy().into_owned(),
+    })
+}
+
+/// Turn the streaming overlay files on (writing them right away) or off
+/// (removing them).
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_overlay_enabled(
+    db: State<Arc<Mutex<Database>>>,
+    enabled: bool,
+) -> Result<OverlayResponse, String> {
+    let path = overlay_path()?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    set_output_enabled(db.connection(), enabled).map_err(AppError::from)?;
+    if enabled {
+        let (_, _, today_start) = local_today();
+        write_overlay(db.connection(), &path, today_start, current_timestamp())?;
+    } else {
+        remove_overlay(&path)?;
+    }
+    Ok(OverlayResponse {
+        enabled,
+        path: path.to_string_lossy().into_owned(),
+    })
+}
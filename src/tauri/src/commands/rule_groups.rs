@@ -0,0 +1,195 @@
+use crate::categorizer::Categorizer;
+use crate::db::{with_connection, Database};
+use crate::error::{is_unique_violation, AppError};
+use crate::focus::FocusManager;
+use crate::models::{Rule, RuleGroup};
+use crate::notifications::QuietRange;
+use crate::personal_time::PersonalTime;
+use crate::tray::refresh_tray;
+use crate::validation::{validate_category_name, validate_work_hours};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::rules::{reload_categorizer, rules_changed};
+use super::{PersonalTimeResponse, RuleGroupResponse};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_rule_groups(db: State<Arc<Mutex<Database>>>) -> Result<Vec<RuleGroupResponse>, String> {
+    let groups = with_connection(&db, RuleGroup::find_all)?;
+    Ok(groups.into_iter().map(RuleGroupResponse::from).collect())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn create_rule_group(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    name: String,
+) -> Result<RuleGroupResponse, String> {
+    let name = validate_category_name(&name)?;
+
+    let group =
+        with_connection(&db, |conn| RuleGroup::create(conn, name)).map_err(|e| match &e {
+            AppError::Database(db_err) if is_unique_violation(db_err) => {
+                AppError::AlreadyExists { name: name.into() }
+            }
+            AppError::AlreadyExists { .. }
+            | AppError::NotFound { .. }
+            | AppError::InvalidInput { .. }
+            | AppError::DeleteFailed { .. }
+            | AppError::Conflict { .. }
+            | AppError::Database(_)
+            | AppError::LockPoisoned
+            | AppError::Internal(_) => e,
+        })?;
+
+    refresh_tray(&app);
+    Ok(RuleGroupResponse::from(group))
+}
+
+/// Delete a group. Its rules stay and apply again, ungrouped.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn delete_rule_group(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    id: i64,
+) -> Result<bool, String> {
+    {
+        let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+        let conn = db.connection();
+        let result = RuleGroup::delete(conn, id).map_err(|e| AppError::from(e).to_string())?;
+        if !result {
+            return Ok(false);
+        }
+        reload_categorizer(&categorizer, conn)?;
+    }
+    focus_manager.invalidate_cache();
+    refresh_tray(&app);
+    Ok(true)
+}
+
+/// Turn every rule in a group on or off.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_rule_group_enabled(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    id: i64,
+    enabled: bool,
+) -> Result<bool, String> {
+    {
+        let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+        let conn = db.connection();
+        let result =
+            RuleGroup::set_enabled(conn, id, enabled).map_err(|e| AppError::from(e).to_string())?;
+        if !result {
+            return Ok(false);
+        }
+        reload_categorizer(&categorizer, conn)?;
+    }
+    // Grouped domain rules decide what focus mode blocks
+    focus_manager.invalidate_cache();
+    refresh_tray(&app);
+    Ok(true)
+}
+
+/// Move a rule into a group, or out of any with `None`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_rule_group(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    id: i64,
+    group_id: Option<i64>,
+) -> Result<bool, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    if let Some(group_id) = group_id {
+        let groups = RuleGroup::find_all(conn).map_err(|e| AppError::from(e).to_string())?;
+        if !groups.iter().any(|g| g.id == group_id) {
+            return Err(AppError::NotFound {
+                entity: "Rule group",
+            }
+            .to_string());
+        }
+    }
+
+    let result = Rule::set_group(conn, id, group_id).map_err(|e| AppError::from(e).to_string())?;
+
+    rules_changed(&categorizer, &focus_manager, conn)?;
+
+    Ok(result)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_personal_time(db: State<Arc<Mutex<Database>>>) -> Result<PersonalTimeResponse, String> {
+    let personal = with_connection(&db, PersonalTime::load)?;
+    Ok(PersonalTimeResponse::from(personal))
+}
+
+/// Use a group's rules first outside `work_hours` and skip them during,
+/// or turn personal time off with no group.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_personal_time(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    group_id: Option<i64>,
+    work_hours: Vec<QuietRange>,
+) -> Result<PersonalTimeResponse, String> {
+    validate_work_hours(&work_hours)?;
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    if let Some(group_id) = group_id {
+        let groups = RuleGroup::find_all(conn).map_err(|e| AppError::from(e).to_string())?;
+        if !groups.iter().any(|g| g.id == group_id) {
+            return Err(AppError::NotFound {
+                entity: "Rule group",
+            }
+            .to_string());
+        }
+    }
+
+    let personal = PersonalTime {
+        group_id,
+        work_hours,
+    };
+    personal.save(conn)?;
+
+    rules_changed(&categorizer, &focus_manager, conn)?;
+
+    Ok(PersonalTimeResponse::from(personal))
+}
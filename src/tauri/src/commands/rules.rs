@@ -1,16 +1,27 @@
 use crate::categorizer::Categorizer;
+use crate::constants::QUICK_RULE_PRIORITY;
 use crate::db::Database;
 use crate::error::AppError;
-use crate::models::{Category, MatchType, Rule};
-use crate::validation::{validate_rule_pattern, validate_rule_priority};
+use crate::focus::FocusManager;
+use crate::journal;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, ActivityFilter, Category, MatchType, Rule, RuleCondition};
+use crate::validation::{
+    validate_profile, validate_rule_condition, validate_rule_pattern, validate_rule_patterns,
+    validate_rule_priority,
+};
 use rusqlite::Connection;
 use std::sync::{Arc, Mutex};
 use tauri::State;
 
-use super::RuleResponse;
+use super::stats::{get_current_timestamp, range_bounds};
+use super::{
+    CategorizationExplanationResponse, RecategorizeFilterRequest, RecategorizeResponse,
+    RuleConditionRequest, RuleResponse,
+};
 
 /// Reload categorizer cache after rule mutations.
-fn reload_categorizer(
+pub(super) fn reload_categorizer(
     categorizer: &Arc<Mutex<Categorizer>>,
     conn: &Connection,
 ) -> Result<(), String> {
@@ -22,6 +33,18 @@ fn reload_categorizer(
     Ok(())
 }
 
+/// Reload the categorizer and drop the cached focus state after rules
+/// change: domain rules decide what focus mode blocks.
+pub(super) fn rules_changed(
+    categorizer: &Arc<Mutex<Categorizer>>,
+    focus_manager: &FocusManager,
+    conn: &Connection,
+) -> Result<(), String> {
+    reload_categorizer(categorizer, conn)?;
+    focus_manager.invalidate_cache();
+    Ok(())
+}
+
 #[tauri::command]
 #[expect(
     clippy::needless_pass_by_value,
@@ -41,6 +64,7 @@ pub fn get_rules(db: State<Arc<Mutex<Database>>>) -> Result<Vec<RuleResponse>, S
 pub fn create_rule(
     db: State<Arc<Mutex<Database>>>,
     categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
     pattern: String,
     match_type: String,
     category_id: i64,
@@ -70,24 +94,32 @@ pub fn create_rule(
     let rule = Rule::create(conn, pattern, match_type, category_id, priority)
         .map_err(|e| AppError::from(e).to_string())?;
 
-    reload_categorizer(&categorizer, conn)?;
+    rules_changed(&categorizer, &focus_manager, conn)?;
 
     Ok(RuleResponse::from(rule))
 }
 
+/// Update a rule. Pass the `version` it was read at to fail with a conflict
+/// instead of overwriting an edit made in another window since.
 #[tauri::command]
 #[expect(
     clippy::needless_pass_by_value,
     reason = "Tauri command handlers receive owned deserialized values"
 )]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "Tauri command arguments mirror the frontend call; three are injected state"
+)]
 pub fn update_rule(
     db: State<Arc<Mutex<Database>>>,
     categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
     id: i64,
     pattern: String,
     match_type: String,
     category_id: i64,
     priority: i32,
+    version: Option<i64>,
 ) -> Result<bool, String> {
     let pattern = validate_rule_pattern(&pattern)?;
     let match_type = MatchType::from_str(&match_type).ok_or_else(|| {
@@ -110,9 +142,121 @@ pub fn update_rule(
         return Err(AppError::NotFound { entity: "Category" }.to_string());
     }
 
-    let result = Rule::update(conn, id, pattern, match_type, category_id, priority)
+    let result = Rule::update(
+        conn,
+        id,
+        pattern,
+        match_type,
+        category_id,
+        priority,
+        version,
+    )
+    .map_err(|e| AppError::from(e).to_string())?;
+    if !result {
+        if let Some(current) =
+            Rule::find_by_id(conn, id).map_err(|e| AppError::from(e).to_string())?
+        {
+            return Err(AppError::Conflict {
+                entity: "Rule",
+                current_version: current.version,
+            }
+            .to_string());
+        }
+    }
+
+    rules_changed(&categorizer, &focus_manager, conn)?;
+
+    Ok(result)
+}
+
+/// Replace all patterns of a rule. The rule matches when any of them does,
+/// so a group like "news sites" can live in one rule.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_rule_patterns(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    id: i64,
+    patterns: Vec<String>,
+) -> Result<bool, String> {
+    let patterns = validate_rule_patterns(&patterns)?;
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let result =
+        Rule::set_patterns(conn, id, &patterns).map_err(|e| AppError::from(e).to_string())?;
+
+    rules_changed(&categorizer, &focus_manager, conn)?;
+
+    Ok(result)
+}
+
+/// Limit a rule to some days and hours (e.g. news sites are neutral at lunch),
+/// or apply it all the time again with `None`. Times are local, like focus schedules.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_rule_condition(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    id: i64,
+    condition: Option<RuleConditionRequest>,
+) -> Result<bool, String> {
+    let condition = condition.map(RuleCondition::from);
+    if let Some(condition) = &condition {
+        validate_rule_condition(condition)?;
+    }
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let result = Rule::set_condition(conn, id, condition.as_ref())
         .map_err(|e| AppError::from(e).to_string())?;
 
+    rules_changed(&categorizer, &focus_manager, conn)?;
+
+    Ok(result)
+}
+
+/// Limit a domain rule to one browser profile or container, or apply it
+/// everywhere again with `None`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_rule_profile(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    id: i64,
+    profile: Option<String>,
+) -> Result<bool, String> {
+    let profile = profile.as_deref().map(validate_profile).transpose()?;
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let rule = Rule::find_by_id(conn, id)
+        .map_err(|e| AppError::from(e).to_string())?
+        .ok_or_else(|| AppError::NotFound { entity: "Rule" }.to_string())?;
+    if profile.is_some() && rule.match_type != MatchType::Domain {
+        return Err(AppError::InvalidInput {
+            field: "profile",
+            reason: "only domain rules can be limited to a profile".into(),
+        }
+        .to_string());
+    }
+
+    let result = Rule::set_profile(conn, id, profile).map_err(|e| AppError::from(e).to_string())?;
+
     reload_categorizer(&categorizer, conn)?;
 
     Ok(result)
@@ -126,14 +270,136 @@ pub fn update_rule(
 pub fn delete_rule(
     db: State<Arc<Mutex<Database>>>,
     categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
     id: i64,
 ) -> Result<bool, String> {
     let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
     let conn = db.connection();
 
-    let result = Rule::delete(conn, id).map_err(|e| AppError::from(e).to_string())?;
+    let result = journal::delete_rule(conn, id, current_timestamp())
+        .map_err(|e| AppError::from(e).to_string())?;
 
-    reload_categorizer(&categorizer, conn)?;
+    rules_changed(&categorizer, &focus_manager, conn)?;
 
     Ok(result)
 }
+
+/// Move all activities matching `filter` into `category_id` at once, e.g. a
+/// week of an app that no rule caught. With `create_rule`, a rule for the
+/// filter's domain (or else app) is added in the same transaction so future
+/// activity follows. Can be undone with `undo_last_operation`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn recategorize_activities(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    filter: RecategorizeFilterRequest,
+    category_id: i64,
+    create_rule: Option<bool>,
+) -> Result<RecategorizeResponse, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&filter.range, now)?;
+    let app_name = filter.app_name.as_deref().map(str::trim);
+    let domain = filter.domain.as_deref().map(str::trim);
+    if app_name.is_none() && domain.is_none() && filter.category_id.is_none() {
+        return Err(AppError::InvalidInput {
+            field: "filter",
+            reason: "must set app_name, domain, or category_id".into(),
+        }
+        .to_string());
+    }
+    let rule_source = if create_rule.unwrap_or(false) {
+        Some(match (domain, app_name) {
+            (Some(domain), _) => (validate_rule_pattern(domain)?, MatchType::Domain),
+            (None, Some(app)) => (validate_rule_pattern(app)?, MatchType::App),
+            (None, None) => {
+                return Err(AppError::InvalidInput {
+                    field: "create_rule",
+                    reason: "needs an app_name or domain to match".into(),
+                }
+                .to_string())
+            }
+        })
+    } else {
+        None
+    };
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    if Category::find_by_id(conn, category_id)
+        .map_err(|e| AppError::from(e).to_string())?
+        .is_none()
+    {
+        return Err(AppError::NotFound { entity: "Category" }.to_string());
+    }
+
+    let filter = ActivityFilter {
+        start,
+        end,
+        app_name,
+        domain,
+        category_id: filter.category_id,
+    };
+    let (updated, rule) = journal::recategorize(
+        conn,
+        &filter,
+        category_id,
+        rule_source,
+        QUICK_RULE_PRIORITY,
+        now,
+    )
+    .map_err(|e| AppError::from(e).to_string())?;
+
+    if rule.is_some() {
+        rules_changed(&categorizer, &focus_manager, conn)?;
+    }
+
+    Ok(RecategorizeResponse {
+        updated,
+        rule: rule.map(RuleResponse::from),
+    })
+}
+
+/// Explain which rule categorized an activity and which other rules also matched.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn explain_categorization(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    activity_id: i64,
+) -> Result<CategorizationExplanationResponse, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let activity = Activity::find_by_id(db.connection(), activity_id)
+        .map_err(|e| AppError::from(e).to_string())?
+        .ok_or_else(|| AppError::NotFound { entity: "Activity" }.to_string())?;
+
+    let cat = categorizer
+        .lock()
+        .map_err(|_| AppError::LockPoisoned.to_string())?;
+    let mut rules = cat
+        .matching_rules(
+            activity.app_name.as_deref().unwrap_or_default(),
+            activity.window_title.as_deref(),
+            activity.domain.as_deref(),
+            activity.profile.as_deref(),
+            activity.timestamp,
+        )
+        .into_iter()
+        .cloned()
+        .map(RuleResponse::from);
+
+    Ok(CategorizationExplanationResponse {
+        activity_id,
+        category_id: activity.category_id,
+        matched_rule: rules.next(),
+        runner_ups: rules.collect(),
+    })
+}
@@ -0,0 +1,52 @@
+use crate::categorizer::Categorizer;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::schedule_templates::{find, TEMPLATES};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::rules::rules_changed;
+use super::{AppliedTemplateResponse, ScheduleTemplateResponse};
+
+/// The built-in schedule templates.
+#[tauri::command]
+pub fn get_schedule_templates() -> Vec<ScheduleTemplateResponse> {
+    TEMPLATES
+        .iter()
+        .map(ScheduleTemplateResponse::from)
+        .collect()
+}
+
+/// Create a template's schedules, and the categories and rules it needs, in
+/// one transaction. Parts that already exist are skipped.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn apply_schedule_template(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    name: String,
+) -> Result<AppliedTemplateResponse, String> {
+    let template = find(&name).ok_or_else(|| {
+        AppError::NotFound {
+            entity: "Schedule template",
+        }
+        .to_string()
+    })?;
+
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let applied = template
+        .apply(conn)
+        .map_err(|e| AppError::from(e).to_string())?;
+    if applied.rules_added > 0 {
+        rules_changed(&categorizer, &focus_manager, conn)?;
+    }
+
+    Ok(AppliedTemplateResponse::from(applied))
+}
@@ -0,0 +1,84 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::session_queue::{self, BlockKind};
+use crate::tray::refresh_tray;
+use crate::validation::{
+    validate_break_minutes, validate_budget_minutes, validate_session_minutes,
+};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::QueuedBlockResponse;
+
+/// Blocks waiting to run after the current session, in order.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_session_queue(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<Vec<QueuedBlockResponse>, String> {
+    let queue = with_connection(&db, session_queue::find_all)?;
+    Ok(queue.into_iter().map(QueuedBlockResponse::from).collect())
+}
+
+/// Queue a focus block or a break (`kind` is `focus` or `break`) to run
+/// after everything already queued. Focus blocks need `budget_minutes`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn queue_session(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    kind: String,
+    minutes: i32,
+    budget_minutes: Option<i32>,
+) -> Result<QueuedBlockResponse, String> {
+    let kind = BlockKind::parse(&kind).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "kind",
+            reason: "must be 'focus' or 'break'".into(),
+        }
+        .to_string()
+    })?;
+    let (duration_secs, budget_secs) = match kind {
+        BlockKind::Focus => {
+            let budget_minutes = budget_minutes.ok_or_else(|| {
+                AppError::InvalidInput {
+                    field: "budget_minutes",
+                    reason: "a focus block needs a budget".into(),
+                }
+                .to_string()
+            })?;
+            (
+                validate_session_minutes(minutes)?,
+                validate_budget_minutes(budget_minutes)?,
+            )
+        }
+        BlockKind::Break => {
+            validate_break_minutes(minutes)?;
+            (minutes * 60, 0)
+        }
+    };
+
+    let block = with_connection(&db, |conn| {
+        session_queue::push(conn, kind, duration_secs, budget_secs)
+    })?;
+    refresh_tray(&app);
+    Ok(QueuedBlockResponse::from(block))
+}
+
+/// Empty the queue, ending a queued break that is running.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn clear_session_queue(app: AppHandle, db: State<Arc<Mutex<Database>>>) -> Result<(), String> {
+    with_connection(&db, session_queue::clear)?;
+    refresh_tray(&app);
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::db::Database;
+use crate::error::AppError;
+use crate::snapshot::publish;
+use crate::validation::validate_folder;
+use chrono::Local;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Render the anonymized dashboard to `index.html` in `folder`. Returns the
+/// path written.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn publish_snapshot(db: State<Arc<Mutex<Database>>>, folder: String) -> Result<String, String> {
+    let folder = validate_folder(&folder)?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let path = publish(
+        db.connection(),
+        Path::new(folder),
+        Local::now().date_naive(),
+    )?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,37 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::standup::standup_summary;
+use chrono::{Local, NaiveDate};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::StandupSummaryResponse;
+
+/// Summarize a day's productive work as standup bullets.
+///
+/// `day` is a local date in YYYY-MM-DD format and defaults to yesterday.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_standup_summary(
+    db: State<Arc<Mutex<Database>>>,
+    day: Option<String>,
+) -> Result<StandupSummaryResponse, String> {
+    let date = if let Some(day) = day {
+        NaiveDate::parse_from_str(&day, "%Y-%m-%d").map_err(|_| {
+            AppError::InvalidInput {
+                field: "day",
+                reason: "must be in YYYY-MM-DD format".into(),
+            }
+            .to_string()
+        })?
+    } else {
+        let today = Local::now().date_naive();
+        today.pred_opt().unwrap_or(today)
+    };
+
+    let summary = with_connection(&db, |conn| standup_summary(conn, date))?;
+    Ok(StandupSummaryResponse::from(summary))
+}
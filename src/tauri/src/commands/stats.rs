@@ -1,12 +1,29 @@
-use crate::constants::SECS_PER_DAY;
-use crate::db::Database;
+use crate::browsers;
+use crate::context_switches::analyze;
+use crate::db::{query_plan, with_connection, Database};
 use crate::error::AppError;
-use crate::models::{Activity, Category};
+use crate::local_time::{
+    date_in, day_bounds, day_bounds_in, days_ending, is_weekend, week_start, WeekStart,
+};
+use crate::models::{Activity, Category, EditorActivity};
+use crate::terminal_context;
+use crate::window_context::parse_snapshot;
+use chrono::{Datelike, Local, NaiveDate, TimeZone, Weekday};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::State;
 
-use super::{AppStat, DailyStats, StatsResponse, WeeklyStatsResponse};
+use super::{
+    AppStat, BrowserProfileResponse, BrowserUsageResponse, CodingBreakdownResponse,
+    ContextSwitchResponse, DailyStats, DayOfWeekAverage, DayTypeTotals, QueryPlanResponse,
+    StatsResponse, WeeklyStatsResponse, WindowContextResponse,
+};
+
+/// Domains returned by `get_top_domains`.
+const TOP_DOMAINS_LIMIT: i64 = 10;
+
+/// Weeks of history behind the per-day-of-week averages.
+const DAY_OF_WEEK_WEEKS: u64 = 8;
 
 #[tauri::command]
 #[expect(
@@ -18,21 +35,27 @@ pub fn get_today_stats(db: State<Arc<Mutex<Database>>>) -> Result<StatsResponse,
     let conn = db.connection();
 
     let now = get_current_timestamp()?;
-    let today_start = now - (now % SECS_PER_DAY);
+    let (today_start, _) = day_bounds(date_in(&Local, now));
 
-    let categories = Category::find_all(conn).map_err(|e| AppError::from(e).to_string())?;
     let (productive_secs, neutral_secs, distracting_secs) =
-        calculate_productivity_totals(conn, &categories, today_start, now)?;
+        calculate_productivity_totals(conn, today_start, now)?;
+    let productivity_score = calculate_productivity_score(conn, today_start, now)?;
     let top_apps = query_top_apps(conn, today_start, now, 5)?;
+    let span = calculate_productive_span(conn, today_start, now)?;
 
     Ok(StatsResponse {
         productive_secs,
         neutral_secs,
         distracting_secs,
+        productivity_score,
         top_apps,
+        first_activity_at: span.map(|(first, _)| first),
+        last_activity_at: span.map(|(_, last)| last),
     })
 }
 
+/// Stats for the current week, from its first day (see `get_week_start`)
+/// through today.
 #[tauri::command]
 #[expect(
     clippy::needless_pass_by_value,
@@ -40,49 +63,345 @@ pub fn get_today_stats(db: State<Arc<Mutex<Database>>>) -> Result<StatsResponse,
 )]
 pub fn get_weekly_stats(db: State<Arc<Mutex<Database>>>) -> Result<WeeklyStatsResponse, String> {
     let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
-    let conn = db.connection();
-
-    let now = get_current_timestamp()?;
-    let today_start = now - (now % SECS_PER_DAY);
-    let week_start = today_start - (6 * SECS_PER_DAY);
+    weekly_stats(db.connection(), get_current_timestamp()?)
+}
 
-    let categories = Category::find_all(conn).map_err(|e| AppError::from(e).to_string())?;
+/// The current week up to `now`, day by day.
+pub(crate) fn weekly_stats(
+    conn: &rusqlite::Connection,
+    now: i64,
+) -> Result<WeeklyStatsResponse, String> {
+    let today = date_in(&Local, now);
+    let first_weekday = week_start(conn).map_err(|e| AppError::from(e).to_string())?;
+    let first_day = first_weekday.week_of(today);
+    let (week_start, _) = day_bounds(first_day);
 
     let mut daily_stats = Vec::with_capacity(7);
     let mut total_productive_secs = 0;
     let mut total_neutral_secs = 0;
     let mut total_distracting_secs = 0;
+    let mut weekday_totals = DayTypeTotals::default();
+    let mut weekend_totals = DayTypeTotals::default();
 
-    for day_offset in 0..7 {
-        let day_start = week_start + (day_offset * SECS_PER_DAY);
-        let day_end = day_start + SECS_PER_DAY;
+    for date in first_day.iter_days().take_while(|date| *date <= today) {
+        let (day_start, day_end) = day_bounds(date);
 
         let (productive, neutral, distracting) =
-            calculate_productivity_totals(conn, &categories, day_start, day_end)?;
+            calculate_productivity_totals(conn, day_start, day_end)?;
+        let span = calculate_productive_span(conn, day_start, day_end)?;
 
         daily_stats.push(DailyStats {
             date: day_start,
             productive_secs: productive,
             neutral_secs: neutral,
             distracting_secs: distracting,
+            productivity_score: calculate_productivity_score(conn, day_start, day_end)?,
+            first_activity_at: span.map(|(first, _)| first),
+            last_activity_at: span.map(|(_, last)| last),
+            workday_secs: span.map(|(first, last)| last - first),
         });
 
         total_productive_secs += productive;
         total_neutral_secs += neutral;
         total_distracting_secs += distracting;
+
+        let totals = if is_weekend(date) {
+            &mut weekend_totals
+        } else {
+            &mut weekday_totals
+        };
+        totals.days += 1;
+        totals.productive_secs += productive;
+        totals.neutral_secs += neutral;
+        totals.distracting_secs += distracting;
     }
 
+    let productivity_score = calculate_productivity_score(conn, week_start, now)?;
     let top_apps = query_top_apps(conn, week_start, now, 10)?;
+    let average_workday_secs = average_workday(daily_stats.iter().filter_map(|d| d.workday_secs));
+    let day_of_week_averages = day_of_week_averages(conn, today, first_weekday)?;
 
     Ok(WeeklyStatsResponse {
         daily_stats,
         total_productive_secs,
         total_neutral_secs,
         total_distracting_secs,
+        productivity_score,
         top_apps,
+        average_workday_secs,
+        weekday_totals,
+        weekend_totals,
+        day_of_week_averages,
     })
 }
 
+/// One tracked day: its weekday, productive seconds, and score.
+type TrackedDay = (Weekday, i32, Option<f64>);
+
+/// Average productive time and score per day of the week over the
+/// `DAY_OF_WEEK_WEEKS` weeks ending today.
+fn day_of_week_averages(
+    conn: &rusqlite::Connection,
+    today: NaiveDate,
+    week_start: WeekStart,
+) -> Result<Vec<DayOfWeekAverage>, String> {
+    let mut tracked: Vec<TrackedDay> = Vec::new();
+    for date in days_ending(today, DAY_OF_WEEK_WEEKS * 7) {
+        let (day_start, day_end) = day_bounds(date);
+        let (productive, neutral, distracting) =
+            calculate_productivity_totals(conn, day_start, day_end)?;
+        if productive + neutral + distracting == 0 {
+            continue;
+        }
+        let score = calculate_productivity_score(conn, day_start, day_end)?;
+        tracked.push((date.weekday(), productive, score));
+    }
+    Ok(average_by_weekday(&tracked, week_start))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    let count = u32::try_from(values.len()).ok().filter(|&n| n > 0)?;
+    Some(values.iter().sum::<f64>() / f64::from(count))
+}
+
+/// Averages for each day of the week, starting on `week_start`.
+fn average_by_weekday(tracked: &[TrackedDay], week_start: WeekStart) -> Vec<DayOfWeekAverage> {
+    week_start
+        .days()
+        .map(|weekday| {
+            let days: Vec<_> = tracked
+                .iter()
+                .filter(|(day, _, _)| *day == weekday)
+                .collect();
+            let count = i32::try_from(days.len()).unwrap_or(i32::MAX);
+            let productive: i32 = days.iter().map(|(_, secs, _)| secs).sum();
+            let scores: Vec<f64> = days.iter().filter_map(|(_, _, score)| *score).collect();
+            DayOfWeekAverage {
+                weekday: weekday.to_string(),
+                days: count,
+                average_productive_secs: if count > 0 { productive / count } else { 0 },
+                average_productivity_score: mean(&scores).map(f64::round),
+            }
+        })
+        .collect()
+}
+
+/// App-switching metrics for `range` ("today" or "week").
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_context_switches(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<ContextSwitchResponse, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let activities = with_connection(&db, |conn| Activity::find_in_range(conn, start, end))?;
+    Ok(ContextSwitchResponse::from(analyze(&activities)))
+}
+
+/// Browser time per profile or container, so the UI can offer a profile filter.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_browser_profiles(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<BrowserProfileResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let totals = with_connection(&db, |conn| Activity::profile_totals(conn, start, end))?;
+    Ok(totals
+        .into_iter()
+        .map(|(profile, duration_secs)| BrowserProfileResponse {
+            profile,
+            duration_secs,
+        })
+        .collect())
+}
+
+/// Time per browser (Chrome, Firefox, Edge...) over a range.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_browser_breakdown(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<BrowserUsageResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let usage = with_connection(&db, |conn| browsers::usage(conn, start, end))?;
+    Ok(usage.into_iter().map(BrowserUsageResponse::from).collect())
+}
+
+/// Most visited domains over a range, optionally within one browser profile.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_top_domains(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+    profile: Option<String>,
+) -> Result<Vec<AppStat>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let domains = with_connection(&db, |conn| {
+        Activity::top_domains(conn, start, end, profile.as_deref(), TOP_DOMAINS_LIMIT)
+    })?;
+    Ok(domains
+        .into_iter()
+        .map(|(name, duration_secs, productivity)| AppStat {
+            name,
+            duration_secs,
+            productivity,
+        })
+        .collect())
+}
+
+/// Editor plugin time over a range, grouped `by` "language" or "project".
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_coding_breakdown(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+    by: String,
+) -> Result<Vec<CodingBreakdownResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let totals = match by.as_str() {
+        "language" => with_connection(&db, |conn| {
+            EditorActivity::language_totals(conn, start, end)
+        })?,
+        "project" => with_connection(&db, |conn| EditorActivity::project_totals(conn, start, end))?,
+        _ => {
+            return Err(AppError::InvalidInput {
+                field: "by",
+                reason: "must be 'language' or 'project'".into(),
+            }
+            .to_string())
+        }
+    };
+    Ok(totals
+        .into_iter()
+        .map(|(name, duration_secs)| CodingBreakdownResponse {
+            name,
+            duration_secs,
+        })
+        .collect())
+}
+
+/// Terminal time over a range, attributed to the repository (or directory)
+/// the shell hook last reported.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_terminal_projects(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<CodingBreakdownResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let totals = with_connection(&db, |conn| {
+        terminal_context::project_totals(conn, start, end)
+    })?;
+    Ok(totals
+        .into_iter()
+        .map(|(name, duration_secs)| CodingBreakdownResponse {
+            name,
+            duration_secs,
+        })
+        .collect())
+}
+
+/// Time spent in `app_name` over a range, split by the categories of the
+/// windows visible beside it (e.g. docs vs video next to the editor).
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_window_context_breakdown(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+    app_name: String,
+) -> Result<Vec<WindowContextResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let (totals, categories) = with_connection(&db, |conn| {
+        Ok((
+            Activity::context_totals(conn, start, end, &app_name)?,
+            Category::find_all(conn)?,
+        ))
+    })?;
+
+    Ok(totals
+        .into_iter()
+        .map(|(context, duration_secs)| {
+            let category_ids = parse_snapshot(context.as_deref());
+            let category_names = category_ids
+                .iter()
+                .filter_map(|id| categories.iter().find(|c| c.id == *id))
+                .map(|c| c.name.clone())
+                .collect();
+            WindowContextResponse {
+                category_ids,
+                category_names,
+                duration_secs,
+            }
+        })
+        .collect())
+}
+
+/// Start and end of a named stats range, using the same day boundaries as the other stats.
+/// Development aid: `EXPLAIN QUERY PLAN` for the hot stats queries, flagging
+/// any that read the whole activities table.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn analyze_queries(db: State<Arc<Mutex<Database>>>) -> Result<Vec<QueryPlanResponse>, String> {
+    let plans = with_connection(&db, query_plan::analyze)?;
+    Ok(plans.into_iter().map(QueryPlanResponse::from).collect())
+}
+
+pub(super) fn range_bounds(range: &str, now: i64) -> Result<(i64, i64), String> {
+    range_bounds_in(&Local, range, now)
+}
+
+/// `today` starts at midnight in `tz`; `week` at midnight six days earlier.
+fn range_bounds_in<Tz: TimeZone>(tz: &Tz, range: &str, now: i64) -> Result<(i64, i64), String> {
+    let days = match range {
+        "today" => 1,
+        "week" => 7,
+        _ => {
+            return Err(AppError::InvalidInput {
+                field: "range",
+                reason: "must be 'today' or 'week'".into(),
+            }
+            .to_string())
+        }
+    };
+    let first_day = days_ending(date_in(tz, now), days)
+        .next()
+        .unwrap_or_else(|| date_in(tz, now));
+    let (start, _) = day_bounds_in(tz, first_day);
+    Ok((start, now))
+}
+
 #[expect(
     clippy::cast_possible_wrap,
     reason = "Unix timestamps won't exceed i64::MAX until year 292 billion"
@@ -91,7 +410,7 @@ pub fn get_weekly_stats(db: State<Arc<Mutex<Database>>>) -> Result<WeeklyStatsRe
     clippy::as_conversions,
     reason = "u64 -> i64 widening cast is safe for timestamps (won't overflow until year 292 billion)"
 )]
-fn get_current_timestamp() -> Result<i64, String> {
+pub(super) fn get_current_timestamp() -> Result<i64, String> {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|_| AppError::Internal("system time error".into()).to_string())
@@ -100,29 +419,39 @@ fn get_current_timestamp() -> Result<i64, String> {
 
 fn calculate_productivity_totals(
     conn: &rusqlite::Connection,
-    categories: &[Category],
     start: i64,
     end: i64,
 ) -> Result<(i32, i32, i32), String> {
-    let totals = Activity::total_duration_by_category(conn, start, end)
-        .map_err(|e| AppError::from(e).to_string())?;
+    Activity::productivity_totals(conn, start, end).map_err(|e| AppError::from(e).to_string())
+}
 
-    let mut productive_secs = 0;
-    let mut neutral_secs = 0;
-    let mut distracting_secs = 0;
-
-    for (cat_id, duration) in &totals {
-        if let Some(cat) = categories.iter().find(|c| c.id == *cat_id) {
-            match cat.productivity {
-                1 => productive_secs += duration,
-                0 => neutral_secs += duration,
-                -1 => distracting_secs += duration,
-                _ => {}
-            }
-        }
-    }
+fn calculate_productive_span(
+    conn: &rusqlite::Connection,
+    start: i64,
+    end: i64,
+) -> Result<Option<(i64, i64)>, String> {
+    Activity::productive_span(conn, start, end).map_err(|e| AppError::from(e).to_string())
+}
+
+/// Mean of the given workday lengths, or `None` when there are none.
+fn average_workday(workdays: impl Iterator<Item = i64>) -> Option<i64> {
+    let (total, days) = workdays.fold((0, 0), |(total, days), secs| (total + secs, days + 1));
+    (days > 0).then(|| total / days)
+}
+
+/// Map an average category weight (-1.0 to 1.0) onto a 0-100 score.
+fn normalize_score(average_weight: f64) -> f64 {
+    ((average_weight.clamp(-1.0, 1.0) + 1.0) * 50.0).round()
+}
 
-    Ok((productive_secs, neutral_secs, distracting_secs))
+fn calculate_productivity_score(
+    conn: &rusqlite::Connection,
+    start: i64,
+    end: i64,
+) -> Result<Option<f64>, String> {
+    Activity::average_weight(conn, start, end)
+        .map(|average| average.map(normalize_score))
+        .map_err(|e| AppError::from(e).to_string())
 }
 
 #[expect(
@@ -142,11 +471,13 @@ fn query_top_apps(
 ) -> Result<Vec<AppStat>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT app_name, SUM(duration_secs) as total, c.productivity
+            "SELECT COALESCE(ai.display_name, a.app_name) AS app, SUM(a.duration_secs) as total,
+                    c.productivity
              FROM activities a
              LEFT JOIN categories c ON a.category_id = c.id
+             LEFT JOIN app_identities ai ON ai.identifier = a.app_name
              WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND a.app_name IS NOT NULL
-             GROUP BY app_name
+             GROUP BY app
              ORDER BY total DESC
              LIMIT ?3",
         )
@@ -170,10 +501,69 @@ fn query_top_apps(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::SECS_PER_DAY;
+    use crate::test_utils::CentralEurope;
+    use chrono::Utc;
 
     #[test]
     fn test_get_current_timestamp() {
         let ts = get_current_timestamp().unwrap();
         assert!(ts > 0);
     }
+
+    #[test]
+    fn test_normalize_score() {
+        assert!((normalize_score(1.0) - 100.0).abs() < f64::EPSILON);
+        assert!((normalize_score(0.0) - 50.0).abs() < f64::EPSILON);
+        assert!(normalize_score(-1.0).abs() < f64::EPSILON);
+        assert!((normalize_score(0.25) - 63.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_average_workday() {
+        assert_eq!(average_workday([3600, 7200].into_iter()), Some(5400));
+        assert_eq!(average_workday(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_average_by_weekday() {
+        let tracked = [
+            (Weekday::Tue, 3600, Some(40.0)),
+            (Weekday::Tue, 1800, Some(60.0)),
+            (Weekday::Fri, 600, None),
+        ];
+        let averages = average_by_weekday(&tracked, WeekStart::Monday);
+        assert_eq!(averages.len(), 7);
+        let tuesday = averages.get(1).unwrap();
+        assert_eq!(tuesday.weekday, "Tue");
+        assert_eq!(tuesday.days, 2);
+        assert_eq!(tuesday.average_productive_secs, 2700);
+        assert_eq!(tuesday.average_productivity_score, Some(50.0));
+        let friday = averages.get(4).unwrap();
+        assert_eq!(friday.average_productivity_score, None);
+        assert_eq!(averages.first().unwrap().days, 0);
+
+        let averages = average_by_weekday(&tracked, WeekStart::Sunday);
+        assert_eq!(averages.first().unwrap().weekday, "Sun");
+        assert_eq!(averages.get(2).unwrap().days, 2);
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let now = 10 * SECS_PER_DAY + 3600;
+        assert_eq!(
+            range_bounds_in(&Utc, "today", now).unwrap(),
+            (10 * SECS_PER_DAY, now)
+        );
+        assert_eq!(
+            range_bounds_in(&Utc, "week", now).unwrap(),
+            (4 * SECS_PER_DAY, now)
+        );
+        assert!(range_bounds("month", now).is_err());
+
+        // The week before 2026-03-30 00:30 CEST holds a 23-hour day
+        let now = 1_774_823_400;
+        let (start, _) = range_bounds_in(&CentralEurope, "week", now).unwrap();
+        assert_eq!(now - start, 6 * SECS_PER_DAY - 3600 + 30 * 60);
+    }
 }
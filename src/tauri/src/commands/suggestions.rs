@@ -0,0 +1,195 @@
+use crate::categorizer::Categorizer;
+use crate::classifier::bayes::{
+    offline_suggestions_enabled, suggest_offline, OFFLINE_SUGGESTIONS_SETTING,
+};
+use crate::classifier::llm::{LlmSettings, API_KEY_SECRET};
+use crate::classifier::suggest_rules;
+use crate::constants::SUGGESTED_RULE_PRIORITY;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Rule, RuleSuggestion, Setting, SuggestionStatus};
+use crate::secrets;
+use crate::validation::{validate_http_url, validate_model_name};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use super::rules::rules_changed;
+use super::{LlmSettingsResponse, RuleResponse, RuleSuggestionResponse, UpdateLlmSettingsRequest};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_rule_suggestions(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<Vec<RuleSuggestionResponse>, String> {
+    let suggestions = with_connection(&db, RuleSuggestion::find_pending)?;
+    Ok(suggestions
+        .into_iter()
+        .map(RuleSuggestionResponse::from)
+        .collect())
+}
+
+/// Turn a pending suggestion into a rule.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn accept_rule_suggestion(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    id: i64,
+) -> Result<RuleResponse, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let suggestion = RuleSuggestion::find_by_id(conn, id)
+        .map_err(|e| AppError::from(e).to_string())?
+        .filter(|s| s.status == SuggestionStatus::Pending)
+        .ok_or_else(|| {
+            AppError::NotFound {
+                entity: "Suggestion",
+            }
+            .to_string()
+        })?;
+
+    let rule = Rule::create(
+        conn,
+        &suggestion.pattern,
+        suggestion.match_type,
+        suggestion.category_id,
+        SUGGESTED_RULE_PRIORITY,
+    )
+    .map_err(|e| AppError::from(e).to_string())?;
+    RuleSuggestion::set_status(conn, id, SuggestionStatus::Accepted)
+        .map_err(|e| AppError::from(e).to_string())?;
+
+    rules_changed(&categorizer, &focus_manager, conn)?;
+
+    Ok(RuleResponse::from(rule))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn dismiss_rule_suggestion(db: State<Arc<Mutex<Database>>>, id: i64) -> Result<(), String> {
+    let found = with_connection(&db, |conn| {
+        RuleSuggestion::set_status(conn, id, SuggestionStatus::Dismissed)
+    })?;
+    if !found {
+        return Err(AppError::NotFound {
+            entity: "Suggestion",
+        }
+        .to_string());
+    }
+    Ok(())
+}
+
+/// Ask the configured LLM to classify recent uncategorized time.
+/// Returns how many new suggestions were queued.
+#[tauri::command]
+pub async fn request_llm_suggestions(app: AppHandle) -> Result<usize, String> {
+    let db = Arc::clone(app.state::<Arc<Mutex<Database>>>().inner());
+    let classifier = with_connection(&db, LlmSettings::load)?
+        .classifier()
+        .ok_or_else(|| {
+            AppError::Internal("LLM categorization is not enabled".into()).to_string()
+        })?;
+
+    // The HTTP request blocks, so keep it off the async runtime's worker threads
+    let added = tauri::async_runtime::spawn_blocking(move || {
+        suggest_rules(&db, &classifier, current_timestamp())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("suggestion task failed: {e}")).to_string())??;
+    Ok(added)
+}
+
+/// Train the offline classifier on local history and queue its suggestions.
+/// Returns how many new suggestions were queued.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn request_offline_suggestions(db: State<Arc<Mutex<Database>>>) -> Result<usize, String> {
+    Ok(suggest_offline(&db)?)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_offline_suggestions_enabled(db: State<Arc<Mutex<Database>>>) -> Result<bool, String> {
+    Ok(with_connection(&db, offline_suggestions_enabled)?)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_offline_suggestions_enabled(
+    db: State<Arc<Mutex<Database>>>,
+    enabled: bool,
+) -> Result<(), String> {
+    with_connection(&db, |conn| {
+        Setting::set(conn, OFFLINE_SUGGESTIONS_SETTING, &enabled.to_string())
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_llm_settings(db: State<Arc<Mutex<Database>>>) -> Result<LlmSettingsResponse, String> {
+    let settings = with_connection(&db, LlmSettings::load)?;
+    let has_api_key = secrets::read(API_KEY_SECRET).is_some();
+    Ok(LlmSettingsResponse::new(settings, has_api_key))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn update_llm_settings(
+    db: State<Arc<Mutex<Database>>>,
+    request: UpdateLlmSettingsRequest,
+) -> Result<LlmSettingsResponse, String> {
+    let endpoint = request
+        .endpoint
+        .as_deref()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| validate_http_url(url, "endpoint"))
+        .transpose()?
+        .map(str::to_string);
+    let model = validate_model_name(&request.model)?.to_string();
+    if request.enabled && endpoint.is_none() {
+        return Err(AppError::InvalidInput {
+            field: "endpoint",
+            reason: "required to enable LLM categorization".into(),
+        }
+        .to_string());
+    }
+
+    let has_api_key = secrets::update(API_KEY_SECRET, request.api_key.as_deref().map(str::trim))?;
+
+    let settings = LlmSettings {
+        enabled: request.enabled,
+        endpoint,
+        model,
+    };
+    with_connection(&db, |conn| settings.save(conn))?;
+    Ok(LlmSettingsResponse::new(settings, has_api_key))
+}
@@ -0,0 +1,63 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::Activity;
+use crate::title_context::{self, TitleExtractor};
+use crate::validation::validate_title_extractors;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::stats::{get_current_timestamp, range_bounds};
+use super::TitleContextResponse;
+
+/// How many contexts `get_title_contexts` returns.
+const TOP_CONTEXTS: i64 = 20;
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_title_extractors(
+    db: State<Arc<Mutex<Database>>>,
+) -> Result<Vec<TitleExtractor>, String> {
+    Ok(with_connection(&db, title_context::extractors)?)
+}
+
+/// Replace the extractors; past activity is re-clustered in the background.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_title_extractors(
+    db: State<Arc<Mutex<Database>>>,
+    extractors: Vec<TitleExtractor>,
+) -> Result<(), String> {
+    validate_title_extractors(&extractors)?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(title_context::set_extractors(db.connection(), &extractors)?)
+}
+
+/// Time per document, repo, or ticket over a range, most time first.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_title_contexts(
+    db: State<Arc<Mutex<Database>>>,
+    range: String,
+) -> Result<Vec<TitleContextResponse>, String> {
+    let now = get_current_timestamp()?;
+    let (start, end) = range_bounds(&range, now)?;
+    let totals = with_connection(&db, |conn| {
+        Activity::title_context_totals(conn, start, end, TOP_CONTEXTS)
+    })?;
+    Ok(totals
+        .into_iter()
+        .map(|(context, duration_secs)| TitleContextResponse {
+            context,
+            duration_secs,
+        })
+        .collect())
+}
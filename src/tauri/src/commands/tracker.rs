@@ -0,0 +1,247 @@
+use crate::categorizer::Categorizer;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::external_activity::{self, ExternalActivity};
+use crate::idle_calibration::{
+    analyze, sample, CalibrationPhase, IdleCalibrationState, IdleSettings,
+};
+use crate::notifications::QuietRange;
+use crate::platform::NativeTracker;
+use crate::safe_lock;
+use crate::tracker::{
+    set_tracker_mode as save_tracker_mode, stream_categorization, tracker_mode, TrackerConfig,
+    TrackerMode, TrackerService,
+};
+use crate::tracking_hours;
+use crate::validation::{validate_tracking_hours, validate_watch_secs};
+use crate::window_context::{capture_enabled, set_capture_enabled};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use super::{
+    CurrentActivityResponse, IdleCalibrationResponse, IdleSettingsResponse, TrackerHealthResponse,
+};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_current_activity(
+    tracker: State<Arc<TrackerService>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+) -> Result<Option<CurrentActivityResponse>, String> {
+    let Some(window) = tracker.current_window() else {
+        return Ok(None);
+    };
+
+    let category_id = categorizer
+        .lock()
+        .map_err(|_| AppError::LockPoisoned.to_string())?
+        .categorize_app(&window.app_name, Some(&window.window_title));
+
+    Ok(Some(CurrentActivityResponse {
+        app_name: window.app_name,
+        window_title: window.window_title,
+        category_id,
+    }))
+}
+
+/// Whether the tracker is actually capturing, with warnings for the UI.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_tracker_health(tracker: State<Arc<TrackerService>>) -> TrackerHealthResponse {
+    TrackerHealthResponse::from(tracker.health())
+}
+
+fn idle_settings_response(settings: IdleSettings) -> IdleSettingsResponse {
+    IdleSettingsResponse {
+        default_threshold_secs: TrackerConfig::default().idle_threshold_secs,
+        threshold_secs: settings.threshold_secs,
+        detection_working: settings.detection_working,
+        calibrated_at: settings.calibrated_at,
+    }
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_idle_settings(db: State<Arc<Mutex<Database>>>) -> Result<IdleSettingsResponse, String> {
+    let settings = with_connection(&db, IdleSettings::load)?;
+    Ok(idle_settings_response(settings))
+}
+
+/// Run one phase of the idle calibration, sampling the idle clock for 15
+/// seconds. Call with `active` while the user keeps typing or moving the
+/// mouse (returns `None`), then with `idle` while they leave the computer
+/// alone. The idle phase records whether idle detection works and, if
+/// `apply`, switches to the recommended threshold.
+#[tauri::command]
+pub async fn calibrate_idle(
+    app: AppHandle,
+    phase: String,
+    apply: bool,
+) -> Result<Option<IdleCalibrationResponse>, String> {
+    let phase = CalibrationPhase::parse(&phase).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "phase",
+            reason: "must be 'active' or 'idle'".into(),
+        }
+        .to_string()
+    })?;
+    let state = app.state::<IdleCalibrationState>();
+    if phase == CalibrationPhase::Idle && safe_lock(&state.0, "Idle calibration").is_none() {
+        return Err(AppError::InvalidInput {
+            field: "phase",
+            reason: "run the 'active' phase first".into(),
+        }
+        .to_string());
+    }
+
+    // Sampling sleeps between readings, so keep it off the async runtime's worker threads
+    let readings = tauri::async_runtime::spawn_blocking(|| sample(&NativeTracker::new()))
+        .await
+        .map_err(|e| AppError::Internal(format!("idle calibration failed: {e}")).to_string())?;
+
+    let active = {
+        let mut active = safe_lock(&state.0, "Idle calibration");
+        if phase == CalibrationPhase::Active {
+            *active = Some(readings);
+            return Ok(None);
+        }
+        active.take().unwrap_or_default()
+    };
+    let calibration = analyze(&active, &readings);
+    let db = app.state::<Arc<Mutex<Database>>>();
+    let settings = with_connection(&db, |conn| IdleSettings::record(conn, &calibration, apply))?;
+    Ok(Some(IdleCalibrationResponse {
+        working: calibration.working,
+        active_max_secs: calibration.active_max_secs,
+        idle_max_secs: calibration.idle_max_secs,
+        recommended_threshold_secs: calibration.recommended_threshold_secs,
+        settings: idle_settings_response(settings),
+    }))
+}
+
+/// `full`, `focus_only` (capture only during focus sessions), or `manual`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_tracker_mode(db: State<Arc<Mutex<Database>>>) -> Result<String, String> {
+    Ok(with_connection(&db, tracker_mode)?.as_str().to_string())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_tracker_mode(db: State<Arc<Mutex<Database>>>, mode: String) -> Result<(), String> {
+    let mode = TrackerMode::parse(&mode).ok_or_else(|| {
+        AppError::InvalidInput {
+            field: "mode",
+            reason: "must be full, focus_only, or manual".into(),
+        }
+        .to_string()
+    })?;
+    with_connection(&db, |conn| save_tracker_mode(conn, mode))?;
+    Ok(())
+}
+
+/// Weekly ranges activity is recorded in; empty when it's recorded at all times.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_tracking_hours(db: State<Arc<Mutex<Database>>>) -> Result<Vec<QuietRange>, String> {
+    Ok(with_connection(&db, tracking_hours::tracking_hours)?)
+}
+
+/// Record activity only inside these ranges, e.g. weekdays 08:00-19:00; an
+/// empty list records at all times.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_tracking_hours(
+    db: State<Arc<Mutex<Database>>>,
+    ranges: Vec<QuietRange>,
+) -> Result<(), String> {
+    validate_tracking_hours(&ranges)?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(tracking_hours::set_tracking_hours(
+        db.connection(),
+        &ranges,
+    )?)
+}
+
+/// Stream each capture with its matched rule and category as
+/// `categorization-capture` events for `seconds`, then `categorization-watch-ended`.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn watch_categorization(
+    app: AppHandle,
+    tracker: State<Arc<TrackerService>>,
+    seconds: u32,
+) -> Result<(), String> {
+    validate_watch_secs(seconds)?;
+    stream_categorization(&app, &tracker, u64::from(seconds));
+    Ok(())
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_context_capture(db: State<Arc<Mutex<Database>>>) -> Result<bool, String> {
+    Ok(with_connection(&db, capture_enabled)?)
+}
+
+/// Turn recording of the windows beside the focused one on or off.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_context_capture(db: State<Arc<Mutex<Database>>>, enabled: bool) -> Result<(), String> {
+    with_connection(&db, |conn| set_capture_enabled(conn, enabled))?;
+    Ok(())
+}
+
+/// Store time reported by another tool (terminal tracker, editor plugin, ...)
+/// so it shows up in stats next to app and browser time. Editor plugins use
+/// the `editor` source to add project and language detail. Returns the new
+/// row's id.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn record_external_activity(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    activity: ExternalActivity,
+) -> Result<i64, String> {
+    let categorizer = categorizer
+        .lock()
+        .map_err(|_| AppError::LockPoisoned.to_string())?;
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    Ok(external_activity::record_now(
+        db.connection(),
+        &categorizer,
+        &activity,
+    )?)
+}
@@ -0,0 +1,93 @@
+use crate::categorizer::Categorizer;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::models::{FocusSchedule, Rule};
+use crate::trash::{purge, TRASH_RETENTION_SECS};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::rules::rules_changed;
+use super::{
+    FocusScheduleResponse, RuleResponse, TrashResponse, TrashedRuleResponse,
+    TrashedScheduleResponse,
+};
+
+/// Deleted rules and focus schedules that can still be restored.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_trash(db: State<Arc<Mutex<Database>>>) -> Result<TrashResponse, String> {
+    let (rules, schedules) = with_connection(&db, |conn| {
+        Ok((
+            Rule::find_deleted(conn)?,
+            FocusSchedule::find_deleted(conn)?,
+        ))
+    })?;
+    Ok(TrashResponse {
+        rules: rules
+            .into_iter()
+            .map(|(rule, deleted_at)| TrashedRuleResponse {
+                rule: RuleResponse::from(rule),
+                deleted_at,
+                purge_at: deleted_at + TRASH_RETENTION_SECS,
+            })
+            .collect(),
+        schedules: schedules
+            .into_iter()
+            .map(|(schedule, deleted_at)| TrashedScheduleResponse {
+                schedule: FocusScheduleResponse::from(schedule),
+                deleted_at,
+                purge_at: deleted_at + TRASH_RETENTION_SECS,
+            })
+            .collect(),
+    })
+}
+
+/// Restore a trashed `rule` or `schedule`. Returns false if it isn't in the trash.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn restore_from_trash(
+    db: State<Arc<Mutex<Database>>>,
+    categorizer: State<Arc<Mutex<Categorizer>>>,
+    focus_manager: State<Arc<FocusManager>>,
+    kind: String,
+    id: i64,
+) -> Result<bool, String> {
+    let db = db.lock().map_err(|_| AppError::LockPoisoned.to_string())?;
+    let conn = db.connection();
+
+    let restored = match kind.as_str() {
+        "rule" => Rule::restore(conn, id),
+        "schedule" => FocusSchedule::restore(conn, id),
+        _ => {
+            return Err(AppError::InvalidInput {
+                field: "kind",
+                reason: "must be 'rule' or 'schedule'".into(),
+            }
+            .to_string())
+        }
+    }
+    .map_err(|e| AppError::from(e).to_string())?;
+
+    if restored && kind == "rule" {
+        rules_changed(&categorizer, &focus_manager, conn)?;
+    }
+
+    Ok(restored)
+}
+
+/// Permanently delete everything in the trash. Returns how many items went.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn purge_trash(db: State<Arc<Mutex<Database>>>) -> Result<usize, String> {
+    Ok(with_connection(&db, |conn| purge(conn, i64::MAX))?)
+}
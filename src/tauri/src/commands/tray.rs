@@ -0,0 +1,31 @@
+use crate::db::{with_connection, Database};
+use crate::models::Setting;
+use crate::tray::{countdown_title_enabled, refresh_tray, COUNTDOWN_TITLE_SETTING};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_tray_countdown_enabled(db: State<Arc<Mutex<Database>>>) -> Result<bool, String> {
+    Ok(with_connection(&db, countdown_title_enabled)?)
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_tray_countdown_enabled(
+    app: AppHandle,
+    db: State<Arc<Mutex<Database>>>,
+    enabled: bool,
+) -> Result<(), String> {
+    with_connection(&db, |conn| {
+        Setting::set(conn, COUNTDOWN_TITLE_SETTING, &enabled.to_string())
+    })?;
+    refresh_tray(&app);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::Setting;
+use crate::updates::{auto_check_enabled, fetch_update_info, publish, AUTO_CHECK_SETTING};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+use super::UpdateInfoResponse;
+
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<UpdateInfoResponse, String> {
+    // The HTTP request blocks, so keep it off the async runtime's worker threads
+    let info = tauri::async_runtime::spawn_blocking(fetch_update_info)
+        .await
+        .map_err(|e| AppError::Internal(format!("update check task failed: {e}")).to_string())??;
+
+    publish(&app, &info);
+    Ok(UpdateInfoResponse::from(info))
+}
+
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn get_update_auto_check(db: State<Arc<Mutex<Database>>>) -> Result<bool, String> {
+    Ok(with_connection(&db, auto_check_enabled)?)
+}
+
+/// Turn the daily background check against the releases feed on or off.
+#[tauri::command]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Tauri command handlers receive owned deserialized values"
+)]
+pub fn set_update_auto_check(db: State<Arc<Mutex<Database>>>, enabled: bool) -> Result<(), String> {
+    with_connection(&db, |conn| {
+        Setting::set(conn, AUTO_CHECK_SETTING, &enabled.to_string())
+    })?;
+    Ok(())
+}
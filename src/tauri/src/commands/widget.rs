@@ -0,0 +1,16 @@
+use crate::error::AppError;
+use crate::widget;
+use tauri::AppHandle;
+
+// Async so window creation doesn't deadlock the main thread on Windows
+#[tauri::command]
+pub async fn show_focus_widget(app: AppHandle) -> Result<(), String> {
+    widget::show_focus_widget(&app)
+        .map_err(|e| AppError::Internal(format!("failed to open focus widget: {e}")).to_string())
+}
+
+#[tauri::command]
+pub async fn close_focus_widget(app: AppHandle) -> Result<(), String> {
+    widget::close_focus_widget(&app)
+        .map_err(|e| AppError::Internal(format!("failed to close focus widget: {e}")).to_string())
+}
@@ -0,0 +1,162 @@
+//! Optional `config.toml` in the data directory, for power users and fleet
+//! deployment. Each table maps onto the settings keys of the same name, so
+//!
+//! ```toml
+//! [tracking]
+//! capture_context = false
+//!
+//! [tracker]
+//! poll_interval_secs = 10
+//! ```
+//!
+//! sets `tracking.capture_context` and `tracker.poll_interval_secs`.
+//!
+//! Precedence: a value in `config.toml` wins over the one saved in the app,
+//! which wins over the built-in default. Only a setting locked by the managed
+//! policy (see `policy`) wins over the file. The file is read once at startup;
+//! changing a setting in the app still saves it, but it has no effect while
+//! the file sets it.
+
+use log::{error, info};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Settings values from the config file, by settings key.
+static OVERRIDES: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Read `config.toml` from `data_dir`, if there is one, and apply it to every
+/// settings lookup. A file that doesn't parse is logged and ignored, so a
+/// typo can't keep Foxus from starting.
+pub fn load(data_dir: &Path) {
+    let path = data_dir.join(CONFIG_FILE_NAME);
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match parse(&text) {
+        Ok(values) => {
+            info!("Loaded {} setting(s) from {}", values.len(), path.display());
+            if let Ok(mut overrides) = OVERRIDES.write() {
+                *overrides = values;
+            }
+        }
+        Err(e) => error!("Ignoring {}: {e}", path.display()),
+    }
+}
+
+/// The config file's value for a settings key, if it sets one.
+pub fn overridden(key: &str) -> Option<String> {
+    OVERRIDES.read().ok()?.get(key).cloned()
+}
+
+/// Every setting the config file sets, by key.
+pub fn overrides() -> BTreeMap<String, String> {
+    OVERRIDES
+        .read()
+        .map(|overrides| overrides.clone())
+        .unwrap_or_default()
+}
+
+/// Settings values from a config file's text, flattened to settings keys.
+fn parse(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    flatten_table(&table)
+}
+
+/// Settings values from a TOML table whose tables name settings sections.
+pub(crate) fn flatten_table(table: &toml::Table) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    flatten("", table, &mut values)?;
+    Ok(values)
+}
+
+fn flatten(
+    prefix: &str,
+    table: &toml::Table,
+    values: &mut BTreeMap<String, String>,
+) -> Result<(), String> {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        let value = match value {
+            toml::Value::Table(table) => {
+                flatten(&key, table, values)?;
+                continue;
+            }
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Integer(n) => n.to_string(),
+            toml::Value::Float(n) => n.to_string(),
+            toml::Value::Boolean(b) => b.to_string(),
+            toml::Value::Datetime(d) => d.to_string(),
+            // List settings (e.g. `git.repositories`) are stored as JSON
+            toml::Value::Array(items) => {
+                serde_json::to_string(items).map_err(|e| format!("{key}: {e}"))?
+            }
+        };
+        values.insert(key, value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Setting;
+    use crate::test_utils::setup_test_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_flattens_tables_to_settings_keys() {
+        let values = parse(
+            r#"
+            [tracking]
+            capture_context = false
+
+            [tracker]
+            poll_interval_secs = 10
+
+            [git]
+            repositories = ["~/code/foxus"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            values,
+            BTreeMap::from([
+                ("git.repositories".into(), r#"["~/code/foxus"]"#.into()),
+                ("tracker.poll_interval_secs".into(), "10".into()),
+                ("tracking.capture_context".into(), "false".into()),
+            ])
+        );
+        assert!(parse("[tracking\ncapture_context = ").is_err());
+    }
+
+    #[test]
+    fn test_config_file_wins_over_saved_setting() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        Setting::set(conn, "config_test.saved", "app").unwrap();
+
+        let data_dir = tempdir().unwrap();
+        std::fs::write(
+            data_dir.path().join(CONFIG_FILE_NAME),
+            "[config_test]\nsaved = \"file\"\n",
+        )
+        .unwrap();
+        load(data_dir.path());
+
+        assert_eq!(
+            Setting::get(conn, "config_test.saved").unwrap().as_deref(),
+            Some("file")
+        );
+        assert_eq!(
+            overrides().get("config_test.saved").map(String::as_str),
+            Some("file")
+        );
+    }
+}
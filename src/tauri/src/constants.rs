@@ -17,3 +17,104 @@ pub const MAX_CATEGORY_NAME_LEN: usize = 100;
 
 /// Maximum rule pattern length
 pub const MAX_RULE_PATTERN_LEN: usize = 500;
+
+/// Maximum number of patterns in one rule
+pub const MAX_RULE_PATTERNS: usize = 100;
+
+/// Maximum length of an external activity source label
+pub const MAX_SOURCE_LABEL_LEN: usize = 32;
+
+/// Longest single stretch of time an external tool can report, in seconds
+pub const MAX_EXTERNAL_ACTIVITY_SECS: i32 = 4 * 60 * 60;
+
+/// Longest project, file, or language an editor plugin can report
+pub const MAX_EDITOR_FIELD_LEN: usize = 512;
+
+/// Longest directory path the shell hook can report
+pub const MAX_TERMINAL_PATH_LEN: usize = 4096;
+
+/// Maximum number of git repositories scanned for commits
+pub const MAX_GIT_REPOSITORIES: usize = 20;
+
+/// Longest app identifier (bundle id, `WM_CLASS`) or app display name
+pub const MAX_APP_NAME_LEN: usize = 256;
+
+/// Maximum number of window-title extractors
+pub const MAX_TITLE_EXTRACTORS: usize = 20;
+
+/// Maximum number of weekly ranges in quiet hours or tracking hours
+pub const MAX_QUIET_RANGES: usize = 20;
+
+/// Maximum browser profile or container name length
+pub const MAX_PROFILE_LEN: usize = 64;
+
+/// Priority for rules created by the tray's quick categorize action and
+/// by bulk recategorizing.
+/// Above the seeded defaults (10) so the user's explicit choice wins.
+pub const QUICK_RULE_PRIORITY: i32 = 50;
+
+/// Sessions returned by the focus history when no limit is given
+pub const DEFAULT_HISTORY_LIMIT: u32 = 50;
+
+/// Priority for rules created from accepted suggestions.
+/// Above the seeded defaults (10), below quick-categorize rules.
+pub const SUGGESTED_RULE_PRIORITY: i32 = 20;
+
+/// Maximum continuous work before a break reminder, in minutes (8 hours)
+pub const MAX_BREAK_WORK_MINUTES: i32 = 8 * 60;
+
+/// Maximum deep-work threshold or session extension, in minutes (4 hours)
+pub const MAX_DEEP_WORK_MINUTES: i32 = 4 * 60;
+
+/// Maximum daily focus goal, in minutes (16 hours)
+pub const MAX_DAILY_GOAL_MINUTES: i32 = 16 * 60;
+
+/// Maximum wait before an emergency unlock goes through, in minutes
+pub const MAX_UNLOCK_DELAY_MINUTES: i32 = 60;
+
+/// Bounds for the budget drawn per "use distraction time" request, in seconds
+pub const MIN_DISTRACTION_GRANT_SECS: i32 = 5;
+pub const MAX_DISTRACTION_GRANT_SECS: i32 = 5 * 60;
+
+/// Longest stretch of productive time per earned budget reward, in minutes
+pub const MAX_ACCRUAL_INTERVAL_MINUTES: i32 = 4 * 60;
+
+/// Most budget one earned reward can add, in seconds
+pub const MAX_ACCRUAL_REWARD_SECS: i32 = 30 * 60;
+
+/// Longest a live categorization watch can run, in seconds
+pub const MAX_CATEGORIZATION_WATCH_SECS: u32 = 300;
+
+/// Maximum number of domains a session can leave unblocked
+pub const MAX_SESSION_EXCEPTIONS: usize = 50;
+
+/// Longest a single domain can be allowed during a session, in minutes
+pub const MAX_DOMAIN_ALLOW_MINUTES: i32 = 15;
+
+/// Maximum length of a day note, in bytes
+pub const MAX_NOTE_LEN: usize = 2000;
+
+/// Maximum number of screen-time entries accepted in one call
+pub const MAX_SCREEN_TIME_ENTRIES: usize = 1000;
+
+/// Longest block of offline focus that can be logged at once, in seconds
+pub const MAX_OFFLINE_FOCUS_SECS: i32 = 12 * 60 * 60;
+
+/// Maximum length of an enforced break, in minutes
+pub const MAX_BREAK_MINUTES: i32 = 60;
+
+/// Largest gap between consecutive activity samples that still counts as
+/// continuous tracking (the tracker polls every few seconds)
+pub const MAX_ACTIVITY_GAP_SECS: i64 = 30;
+
+/// Most days an anonymized aggregate export covers.
+pub const MAX_AGGREGATE_DAYS: u32 = 366;
+
+/// Longest notice before a scheduled session starts, in minutes
+pub const MAX_NOTIFY_LEAD_MINUTES: i32 = 60;
+
+/// Maximum length of a schedule described in words
+pub const MAX_SCHEDULE_TEXT_LEN: usize = 200;
+
+/// Longest away mode can be set for, in days
+pub const MAX_AWAY_DAYS: u64 = 366;
@@ -0,0 +1,156 @@
+use crate::constants::MAX_ACTIVITY_GAP_SECS;
+use crate::models::Activity;
+use std::collections::HashMap;
+
+/// Switching apps this often (per tracked hour) or more scores 100.
+const MAX_SCORE_SWITCHES_PER_HOUR: f64 = 60.0;
+
+const TOP_PAIRS_LIMIT: usize = 5;
+
+/// Two apps the user bounced between, in either direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwitchPair {
+    /// Alphabetically first app of the pair.
+    pub first: String,
+    pub second: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextSwitchStats {
+    pub tracked_secs: i32,
+    pub switches: u32,
+    pub switches_per_hour: f64,
+    /// Average time spent in one app before switching or stopping.
+    pub average_block_secs: f64,
+    /// 0 for no switching, 100 for a switch every minute or more often.
+    pub fragmentation_score: f64,
+    pub top_pairs: Vec<SwitchPair>,
+}
+
+/// Measure app switching across `activities` (sorted oldest first).
+///
+/// A gap in tracking (idle, asleep) ends a block but doesn't count as a switch.
+pub fn analyze(activities: &[Activity]) -> ContextSwitchStats {
+    let mut tracked_secs = 0;
+    let mut switches = 0_u32;
+    let mut blocks = 0_u32;
+    let mut pairs: HashMap<(&str, &str), u32> = HashMap::new();
+    let mut previous: Option<(&str, i64)> = None;
+
+    for activity in activities {
+        let Some(app) = activity.app_name.as_deref() else {
+            continue;
+        };
+        tracked_secs += activity.duration_secs;
+
+        let continuous = previous
+            .filter(|(_, end)| activity.timestamp - end <= MAX_ACTIVITY_GAP_SECS)
+            .map(|(previous_app, _)| previous_app);
+        match continuous {
+            Some(previous_app) if previous_app == app => {}
+            Some(previous_app) => {
+                switches += 1;
+                blocks += 1;
+                let key = if previous_app < app {
+                    (previous_app, app)
+                } else {
+                    (app, previous_app)
+                };
+                *pairs.entry(key).or_default() += 1;
+            }
+            None => blocks += 1,
+        }
+        previous = Some((app, activity.timestamp + i64::from(activity.duration_secs)));
+    }
+
+    let tracked_hours = f64::from(tracked_secs) / 3600.0;
+    let switches_per_hour = if tracked_hours > 0.0 {
+        f64::from(switches) / tracked_hours
+    } else {
+        0.0
+    };
+    let average_block_secs = if blocks > 0 {
+        f64::from(tracked_secs) / f64::from(blocks)
+    } else {
+        0.0
+    };
+    let fragmentation_score = (switches_per_hour / MAX_SCORE_SWITCHES_PER_HOUR).min(1.0) * 100.0;
+
+    let mut top_pairs: Vec<SwitchPair> = pairs
+        .into_iter()
+        .map(|((first, second), count)| SwitchPair {
+            first: first.to_string(),
+            second: second.to_string(),
+            count,
+        })
+        .collect();
+    top_pairs.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| (&a.first, &a.second).cmp(&(&b.first, &b.second)))
+    });
+    top_pairs.truncate(TOP_PAIRS_LIMIT);
+
+    ContextSwitchStats {
+        tracked_secs,
+        switches,
+        switches_per_hour,
+        average_block_secs,
+        fragmentation_score,
+        top_pairs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: i64, app: &str) -> Activity {
+        Activity::new(timestamp, 5, "app", Some(app), None)
+    }
+
+    #[test]
+    fn test_counts_switches_and_pairs() {
+        let activities = [
+            sample(0, "Code"),
+            sample(5, "Code"),
+            sample(10, "Slack"),
+            sample(15, "Code"),
+            sample(20, "Slack"),
+            sample(25, "Chrome"),
+        ];
+
+        let stats = analyze(&activities);
+        assert_eq!(stats.tracked_secs, 30);
+        assert_eq!(stats.switches, 4);
+        assert!((stats.average_block_secs - 6.0).abs() < f64::EPSILON);
+        assert!((stats.fragmentation_score - 100.0).abs() < f64::EPSILON);
+        assert_eq!(
+            stats.top_pairs.first(),
+            Some(&SwitchPair {
+                first: "Code".into(),
+                second: "Slack".into(),
+                count: 3,
+            })
+        );
+        assert_eq!(stats.top_pairs.len(), 2);
+    }
+
+    #[test]
+    fn test_tracking_gap_is_not_a_switch() {
+        let activities = [sample(0, "Code"), sample(3600, "Slack")];
+
+        let stats = analyze(&activities);
+        assert_eq!(stats.switches, 0);
+        assert!((stats.average_block_secs - 5.0).abs() < f64::EPSILON);
+        assert!(stats.top_pairs.is_empty());
+    }
+
+    #[test]
+    fn test_empty_range() {
+        let stats = analyze(&[]);
+        assert_eq!(stats.tracked_secs, 0);
+        assert!(stats.fragmentation_score.abs() < f64::EPSILON);
+    }
+}
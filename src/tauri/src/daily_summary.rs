@@ -0,0 +1,234 @@
+use crate::db::{with_connection, Database};
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Setting};
+use crate::notifications::{is_quiet_now, notify};
+use crate::tray::format_duration;
+use log::{error, warn};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+const ENABLED_SETTING: &str = "daily_summary.enabled";
+const TIME_SETTING: &str = "daily_summary.time";
+/// Local date ("YYYY-MM-DD") of the last summary sent, so restarts don't resend it.
+const LAST_SENT_SETTING: &str = "daily_summary.last_sent";
+
+/// Local time the summary is sent when unconfigured.
+pub const DEFAULT_SUMMARY_TIME: &str = "18:00";
+
+/// Event emitted with the compiled summary so the UI can open a review screen.
+pub const DAILY_SUMMARY_EVENT: &str = "daily_summary";
+
+const TOP_DISTRACTIONS_LIMIT: i64 = 3;
+
+/// When (and whether) the end-of-day summary is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailySummarySettings {
+    pub enabled: bool,
+    /// Local time of day in HH:MM format.
+    pub time: String,
+}
+
+impl DailySummarySettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            enabled: Setting::get(conn, ENABLED_SETTING)?.as_deref() != Some("false"),
+            time: Setting::get(conn, TIME_SETTING)?
+                .unwrap_or_else(|| DEFAULT_SUMMARY_TIME.to_string()),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, TIME_SETTING, &self.time)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DistractionStat {
+    pub name: String,
+    pub duration_secs: i32,
+}
+
+/// A day's totals and top distractions.
+#[derive(Debug, Clone, Serialize)]
+pub struct DailySummary {
+    /// Local date in YYYY-MM-DD format.
+    pub date: String,
+    pub productive_secs: i32,
+    pub neutral_secs: i32,
+    pub distracting_secs: i32,
+    pub top_distractions: Vec<DistractionStat>,
+}
+
+impl DailySummary {
+    /// Compile the summary for activity between `start` and `end`.
+    pub fn compile(conn: &Connection, date: &str, start: i64, end: i64) -> Result<Self> {
+        let (productive_secs, neutral_secs, distracting_secs) =
+            Activity::productivity_totals(conn, start, end)?;
+        let top_distractions =
+            Activity::top_distractions(conn, start, end, TOP_DISTRACTIONS_LIMIT)?
+                .into_iter()
+                .map(|(name, duration_secs)| DistractionStat {
+                    name,
+                    duration_secs,
+                })
+                .collect();
+
+        Ok(Self {
+            date: date.to_string(),
+            productive_secs,
+            neutral_secs,
+            distracting_secs,
+            top_distractions,
+        })
+    }
+
+    /// One-paragraph notification text.
+    fn notification_body(&self) -> String {
+        let mut body = format!(
+            "{} productive, {} distracting.",
+            format_duration(self.productive_secs),
+            format_duration(self.distracting_secs)
+        );
+        if let Some(top) = self.top_distractions.first() {
+            let _ = write!(
+                body,
+                " Top distraction: {} ({}).",
+                top.name,
+                format_duration(top.duration_secs)
+            );
+        }
+        body
+    }
+}
+
+/// Whether today's summary should be sent now.
+fn is_due(
+    settings: &DailySummarySettings,
+    last_sent: Option<&str>,
+    today: &str,
+    time: &str,
+) -> bool {
+    settings.enabled && time >= settings.time.as_str() && last_sent != Some(today)
+}
+
+/// Compile today's summary so far.
+pub fn today_summary(conn: &Connection) -> Result<DailySummary> {
+    let (today, _, midnight) = local_today();
+    DailySummary::compile(conn, &today, midnight, current_timestamp())
+}
+
+/// Send today's summary once the configured time has passed and quiet hours
+/// are over.
+pub fn check_daily_summary(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    if is_quiet_now(app) {
+        return;
+    }
+    let (today, time, _) = local_today();
+
+    let compiled = with_connection(&db, |conn| {
+        let settings = DailySummarySettings::load(conn)?;
+        let last_sent = Setting::get(conn, LAST_SENT_SETTING)?;
+        if !is_due(&settings, last_sent.as_deref(), &today, &time) {
+            return Ok(None);
+        }
+        Setting::set(conn, LAST_SENT_SETTING, &today)?;
+        today_summary(conn).map(Some)
+    });
+
+    match compiled {
+        Ok(Some(summary)) => send(app, &summary),
+        Ok(None) => {}
+        Err(e) => error!("Failed to compile daily summary: {e}"),
+    }
+}
+
+fn send(app: &AppHandle, summary: &DailySummary) {
+    if let Err(e) = app.emit(DAILY_SUMMARY_EVENT, summary) {
+        warn!("Failed to emit daily summary: {e}");
+    }
+    notify(app, "Today in Foxus", &summary.notification_body());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    fn settings(enabled: bool) -> DailySummarySettings {
+        DailySummarySettings {
+            enabled,
+            time: "18:00".into(),
+        }
+    }
+
+    #[test]
+    fn test_settings_default_and_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let loaded = DailySummarySettings::load(conn).unwrap();
+        assert_eq!(loaded, settings(true));
+
+        let custom = DailySummarySettings {
+            enabled: false,
+            time: "21:30".into(),
+        };
+        custom.save(conn).unwrap();
+        assert_eq!(DailySummarySettings::load(conn).unwrap(), custom);
+    }
+
+    #[test]
+    fn test_is_due() {
+        assert!(!is_due(&settings(true), None, "2026-01-02", "17:59"));
+        assert!(is_due(&settings(true), None, "2026-01-02", "18:00"));
+        assert!(is_due(
+            &settings(true),
+            Some("2026-01-01"),
+            "2026-01-02",
+            "23:10"
+        ));
+        assert!(!is_due(
+            &settings(true),
+            Some("2026-01-02"),
+            "2026-01-02",
+            "23:10"
+        ));
+        assert!(!is_due(&settings(false), None, "2026-01-02", "18:00"));
+    }
+
+    #[test]
+    fn test_compile_summary() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let start = 1_700_000_000_i64;
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+        for (offset, duration, app, category) in [
+            (0, 3600, "Code", "Coding"),
+            (3600, 600, "Game", "Entertainment"),
+        ] {
+            let mut activity = Activity::new(start + offset, duration, "app", Some(app), None);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        let summary = DailySummary::compile(conn, "2023-11-14", start, start + 86400).unwrap();
+        assert_eq!(summary.productive_secs, 3600);
+        assert_eq!(summary.distracting_secs, 600);
+        assert_eq!(summary.top_distractions.len(), 1);
+        assert_eq!(
+            summary.notification_body(),
+            "1h 0m productive, 10m distracting. Top distraction: Game (10m)."
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Where Foxus keeps its data. `--data-dir <path>` wins, then the
+//! `FOXUS_DATA_DIR` environment variable, then portable mode, then the
+//! platform default. Both the app and the native host honor them, so
+//! packagers, tests and separate profiles can each have their own folder.
+//!
+//! Portable mode (`--portable`, or a `foxus-portable` file next to the
+//! executable) keeps everything in a `foxus-data` folder beside the
+//! executable, e.g. on a USB stick, without touching the home directory.
+
+use crate::InitError;
+use directories::ProjectDirs;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Command-line flag that sets the data directory for this process.
+pub const DATA_DIR_FLAG: &str = "--data-dir";
+
+/// Environment variable that sets the data directory.
+pub const DATA_DIR_ENV: &str = "FOXUS_DATA_DIR";
+
+/// Command-line flag that turns on portable mode.
+pub const PORTABLE_FLAG: &str = "--portable";
+
+/// A file with this name next to the executable turns on portable mode, for
+/// processes started without the flag (the native host, launched by the
+/// browser).
+const PORTABLE_MARKER: &str = "foxus-portable";
+
+/// Folder beside the executable that holds the data in portable mode.
+const PORTABLE_DIR_NAME: &str = "foxus-data";
+
+/// The `--data-dir` value this process was started with.
+static FLAG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Whether this process was started with `--portable`.
+static PORTABLE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Flags {
+    dir: Option<PathBuf>,
+    portable: bool,
+    rest: Vec<String>,
+}
+
+/// Take `--data-dir <path>` (or `--data-dir=<path>`) and `--portable` out of
+/// `args` and use them for the rest of the process. Returns the remaining
+/// arguments.
+pub fn apply_flags(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let flags = split_flags(args);
+    if let Some(dir) = flags.dir {
+        let _ = FLAG_DIR.set(dir);
+    }
+    if flags.portable {
+        PORTABLE.store(true, Ordering::Relaxed);
+    }
+    flags.rest
+}
+
+fn split_flags(args: impl IntoIterator<Item = String>) -> Flags {
+    let mut flags = Flags::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_FLAG {
+            flags.dir = args.next().map(PathBuf::from);
+        } else if let Some(value) = arg.strip_prefix("--data-dir=") {
+            flags.dir = Some(PathBuf::from(value));
+        } else if arg == PORTABLE_FLAG {
+            flags.portable = true;
+        } else {
+            flags.rest.push(arg);
+        }
+    }
+    flags.dir = flags.dir.filter(|d| !d.as_os_str().is_empty());
+    flags
+}
+
+/// The portable data folder, if portable mode is on.
+pub fn portable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let exe_dir = exe.parent()?;
+    (PORTABLE.load(Ordering::Relaxed) || exe_dir.join(PORTABLE_MARKER).is_file())
+        .then(|| exe_dir.join(PORTABLE_DIR_NAME))
+}
+
+/// Where windows keep their web storage in portable mode; `None` leaves it
+/// to the platform.
+pub fn webview_dir() -> Option<PathBuf> {
+    portable_dir().map(|dir| dir.join("webview"))
+}
+
+/// The data directory asked for by flag or environment, if any.
+fn override_dir(flag: Option<&PathBuf>, env: Option<OsString>) -> Option<PathBuf> {
+    flag.cloned()
+        .or_else(|| env.filter(|v| !v.is_empty()).map(PathBuf::from))
+}
+
+/// The data directory, created if needed.
+pub fn resolve() -> Result<PathBuf, InitError> {
+    let data_dir =
+        match override_dir(FLAG_DIR.get(), std::env::var_os(DATA_DIR_ENV)).or_else(portable_dir) {
+            Some(dir) => dir,
+            None => ProjectDirs::from("com", "foxus", "Foxus")
+                .ok_or(InitError::NoProjectDirs)?
+                .data_dir()
+                .to_path_buf(),
+        };
+    std::fs::create_dir_all(&data_dir).map_err(InitError::DataDirCreation)?;
+    Ok(data_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_split_flags() {
+        let flags = split_flags(args(&["foxus", "--data-dir", "/tmp/a", "--read-only"]));
+        assert_eq!(flags.dir, Some(PathBuf::from("/tmp/a")));
+        assert!(!flags.portable);
+        assert_eq!(flags.rest, args(&["foxus", "--read-only"]));
+
+        let flags = split_flags(args(&["launcher", "--data-dir=/tmp/b"]));
+        assert_eq!(flags.dir, Some(PathBuf::from("/tmp/b")));
+        assert_eq!(flags.rest, args(&["launcher"]));
+
+        let flags = split_flags(args(&["foxus", "--portable", "--data-dir"]));
+        assert_eq!(flags.dir, None);
+        assert!(flags.portable);
+        assert_eq!(flags.rest, args(&["foxus"]));
+    }
+
+    #[test]
+    fn test_flag_wins_over_environment() {
+        let flag = PathBuf::from("/flag");
+        assert_eq!(
+            override_dir(Some(&flag), Some("/env".into())),
+            Some(flag.clone())
+        );
+        assert_eq!(
+            override_dir(None, Some("/env".into())),
+            Some(PathBuf::from("/env"))
+        );
+        assert_eq!(override_dir(None, Some(OsString::new())), None);
+        assert_eq!(override_dir(None, None), None);
+    }
+}
@@ -0,0 +1,246 @@
+//! Days with suspiciously little tracked time, e.g. because the tracker
+//! crashed, screen recording permission was revoked or the X connection
+//! died. A day is compared with the same weekday over the previous weeks, up
+//! to the same time of day, so a broken tracker is noticed the day it breaks.
+
+use crate::away_mode::is_away;
+use crate::db::{with_connection, Database};
+use crate::local_time::{date_in, day_bounds_in, local_today};
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Setting};
+use crate::notifications::{is_quiet_now, notify};
+use crate::tracker::{tracker_mode, TrackerMode};
+use crate::tray::format_duration;
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use log::error;
+use rusqlite::{Connection, Result};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// Local date ("YYYY-MM-DD") of the last alert, so it's sent once a day.
+const LAST_ALERT_SETTING: &str = "data_quality.last_alert";
+
+/// Weeks back to look for the same weekday.
+const BASELINE_WEEKS: u64 = 4;
+
+/// Baseline days with data needed before a day is judged.
+const MIN_BASELINE_DAYS: usize = 2;
+
+/// Typical time below which a quiet day isn't worth flagging.
+const MIN_TYPICAL_SECS: i64 = 60 * 60;
+
+/// A day with less than 1/`LOW_FRACTION` of its typical time is flagged.
+const LOW_FRACTION: i64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityStatus {
+    Ok,
+    /// Far less than usual.
+    Low,
+    /// Nothing at all on a day that usually has data.
+    Missing,
+}
+
+impl QualityStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Low => "low",
+            Self::Missing => "missing",
+        }
+    }
+
+    fn judge(tracked_secs: i64, typical_secs: Option<i64>) -> Self {
+        match typical_secs {
+            Some(typical) if typical >= MIN_TYPICAL_SECS => {
+                if tracked_secs == 0 {
+                    Self::Missing
+                } else if tracked_secs * LOW_FRACTION < typical {
+                    Self::Low
+                } else {
+                    Self::Ok
+                }
+            }
+            _ => Self::Ok,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayQuality {
+    /// Local date in YYYY-MM-DD format.
+    pub date: String,
+    /// Seconds the tracker recorded.
+    pub tracked_secs: i64,
+    /// Median for the same weekday over the previous weeks, up to the same
+    /// time of day; `None` without enough history.
+    pub typical_secs: Option<i64>,
+    pub status: QualityStatus,
+}
+
+fn median(mut values: Vec<i64>) -> Option<i64> {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Some((values.get(mid.checked_sub(1)?)? + values.get(mid)?) / 2)
+    } else {
+        values.get(mid).copied()
+    }
+}
+
+/// How `date` looks up to `until` (capped at the end of the day).
+pub fn assess_day<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    date: NaiveDate,
+    until: i64,
+) -> Result<DayQuality> {
+    let (start, end) = day_bounds_in(tz, date);
+    let elapsed = until.min(end) - start;
+    let tracked_secs = Activity::tracked_secs(conn, start, start + elapsed)?;
+
+    let mut baseline = Vec::new();
+    for week in 1..=BASELINE_WEEKS {
+        let Some(earlier) = date.checked_sub_days(Days::new(week * 7)) else {
+            continue;
+        };
+        let (earlier_start, _) = day_bounds_in(tz, earlier);
+        let secs = Activity::tracked_secs(conn, earlier_start, earlier_start + elapsed)?;
+        // A day off isn't a baseline
+        if secs > 0 {
+            baseline.push(secs);
+        }
+    }
+    let typical_secs = if baseline.len() >= MIN_BASELINE_DAYS {
+        median(baseline)
+    } else {
+        None
+    };
+
+    Ok(DayQuality {
+        date: date.format("%Y-%m-%d").to_string(),
+        tracked_secs,
+        typical_secs,
+        status: QualityStatus::judge(tracked_secs, typical_secs),
+    })
+}
+
+/// Each local day in `[start, now]`, oldest first.
+pub fn assess_range<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    start: i64,
+    now: i64,
+) -> Result<Vec<DayQuality>> {
+    date_in(tz, start)
+        .iter_days()
+        .take_while(|date| *date <= date_in(tz, now))
+        .map(|date| assess_day(conn, tz, date, now))
+        .collect()
+}
+
+/// Warn once a day when today has far less data than usual so far. Only in
+/// full tracking mode and not while away; otherwise little is recorded on
+/// purpose.
+pub fn check_data_quality(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    if is_quiet_now(app) {
+        return;
+    }
+    let (today, _, _) = local_today();
+
+    let flagged = with_connection(&db, |conn| {
+        if tracker_mode(conn)? != TrackerMode::Full
+            || is_away(conn)?
+            || Setting::get(conn, LAST_ALERT_SETTING)?.as_deref() == Some(today.as_str())
+        {
+            return Ok(None);
+        }
+        let now = current_timestamp();
+        let quality = assess_day(conn, &Local, date_in(&Local, now), now)?;
+        if quality.status == QualityStatus::Ok {
+            return Ok(None);
+        }
+        Setting::set(conn, LAST_ALERT_SETTING, &today)?;
+        Ok(Some(quality))
+    });
+
+    match flagged {
+        Ok(Some(quality)) => notify(app, "Tracking may be broken", &alert_body(&quality)),
+        Ok(None) => {}
+        Err(e) => error!("Failed to check tracking data: {e}"),
+    }
+}
+
+fn alert_body(quality: &DayQuality) -> String {
+    let typical = quality
+        .typical_secs
+        .and_then(|secs| i32::try_from(secs).ok())
+        .unwrap_or_default();
+    let tracked = i32::try_from(quality.tracked_secs).unwrap_or_default();
+    format!(
+        "Foxus has recorded {} today, against a usual {} by now. Check that it still \
+         has permission to see your windows.",
+        format_duration(tracked),
+        format_duration(typical)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+
+    /// Thursday 2026-10-15 00:00 UTC
+    const THURSDAY: i64 = 1_792_022_400;
+    const WEEK: i64 = 7 * 24 * 3600;
+
+    fn save(conn: &Connection, timestamp: i64, secs: i32, source: &str) {
+        Activity::new(timestamp, secs, source, Some("Code"), None)
+            .save(conn)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_judge() {
+        assert_eq!(QualityStatus::judge(0, None), QualityStatus::Ok);
+        assert_eq!(QualityStatus::judge(0, Some(600)), QualityStatus::Ok);
+        assert_eq!(QualityStatus::judge(0, Some(7200)), QualityStatus::Missing);
+        assert_eq!(QualityStatus::judge(1000, Some(7200)), QualityStatus::Low);
+        assert_eq!(QualityStatus::judge(1800, Some(7200)), QualityStatus::Ok);
+    }
+
+    #[test]
+    fn test_assess_day_against_same_weekday_so_far() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        // Previous Thursdays: 2h in the morning, 4h in the afternoon
+        for week in 1..=3 {
+            let day = THURSDAY - week * WEEK;
+            save(conn, day + 9 * 3600, 7200, "app");
+            save(conn, day + 14 * 3600, 14_400, "app");
+        }
+        // Today only the extension recorded anything
+        save(conn, THURSDAY + 9 * 3600, 3600, "browser");
+        let date = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+
+        let noon = assess_day(conn, &Utc, date, THURSDAY + 12 * 3600).unwrap();
+        assert_eq!(noon.date, "2026-10-15");
+        assert_eq!(noon.tracked_secs, 0);
+        assert_eq!(noon.typical_secs, Some(7200));
+        assert_eq!(noon.status, QualityStatus::Missing);
+
+        save(conn, THURSDAY + 14 * 3600, 600, "app");
+        let evening = assess_day(conn, &Utc, date, THURSDAY + 20 * 3600).unwrap();
+        assert_eq!(evening.typical_secs, Some(21_600));
+        assert_eq!(evening.status, QualityStatus::Low);
+
+        // A Thursday with no history to compare against
+        let week = assess_range(conn, &Utc, THURSDAY - 4 * WEEK, THURSDAY - 4 * WEEK).unwrap();
+        assert_eq!(week.len(), 1);
+        assert_eq!(week.first().unwrap().typical_secs, None);
+    }
+}
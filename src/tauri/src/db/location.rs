@@ -0,0 +1,227 @@
+//! Where `foxus.db` lives. It defaults to the app's data directory; a
+//! `database_location` file there can point it at another folder, such as a
+//! synced or encrypted volume. The pointer lives outside the database because
+//! it is needed to find the database.
+
+use super::Database;
+use crate::error::AppError;
+use crate::models::Setting;
+use crate::InitError;
+use log::warn;
+use rusqlite::{Connection, Result};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DB_FILE_NAME: &str = "foxus.db";
+
+/// File in the data directory naming the folder the database was moved to.
+const POINTER_FILE_NAME: &str = "database_location";
+
+/// Set in a database after it was moved, so a process that still has it
+/// open (the native host) can follow it to the new file.
+const MOVED_TO_KEY: &str = "database.moved_to";
+
+/// The folder set by `migrate_database`, if the database was moved out of
+/// `data_dir`.
+pub fn custom_folder(data_dir: &Path) -> Option<PathBuf> {
+    let folder = std::fs::read_to_string(data_dir.join(POINTER_FILE_NAME)).ok()?;
+    let folder = folder.trim();
+    (!folder.is_empty()).then(|| PathBuf::from(folder))
+}
+
+/// The database file for `data_dir`, following a custom folder if one is set.
+///
+/// A custom folder that's missing, e.g. an external drive that isn't
+/// connected, is an error rather than a reason to start an empty database.
+pub fn db_path(data_dir: &Path) -> std::result::Result<PathBuf, InitError> {
+    match custom_folder(data_dir) {
+        Some(folder) if !folder.is_dir() => Err(InitError::DatabaseFolderMissing(folder)),
+        Some(folder) => Ok(folder.join(DB_FILE_NAME)),
+        None => Ok(data_dir.join(DB_FILE_NAME)),
+    }
+}
+
+/// Move the open database into `folder` and reopen it there. Returns the new
+/// path.
+///
+/// The copy is written next to its destination and renamed into place, and
+/// the pointer file is updated the same way, so an interrupted move leaves
+/// the old database in use. Only then is the old file deleted; the native
+/// host, which may still have it open, follows the move before its next write.
+pub fn relocate(
+    db: &mut Database,
+    data_dir: &Path,
+    folder: &Path,
+) -> std::result::Result<PathBuf, AppError> {
+    let new_path = folder.join(DB_FILE_NAME);
+    if new_path == db.path() {
+        return Err(AppError::InvalidInput {
+            field: "folder",
+            reason: "the database is already there".into(),
+        });
+    }
+    if new_path.exists() {
+        return Err(AppError::InvalidInput {
+            field: "folder",
+            reason: format!("'{}' already has a {DB_FILE_NAME}", folder.display()),
+        });
+    }
+
+    let tmp = new_path.with_extension("db.moving");
+    let copied = copy_to(db.connection(), &tmp).and_then(|()| {
+        std::fs::rename(&tmp, &new_path)
+            .map_err(|e| AppError::Internal(format!("failed to move database: {e}")))
+    });
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    if let Err(e) = write_pointer(data_dir, folder) {
+        let _ = std::fs::remove_file(&new_path);
+        return Err(AppError::Internal(format!(
+            "failed to save database location: {e}"
+        )));
+    }
+
+    Setting::set(db.connection(), MOVED_TO_KEY, &new_path.to_string_lossy())?;
+    let old_path = db.path().to_path_buf();
+    *db = Database::open(&new_path)?;
+    remove_database_files(&old_path);
+    Ok(new_path)
+}
+
+/// Reopen `db` at its new location if another process moved it. Returns
+/// whether it did.
+pub fn follow_move(db: &mut Database) -> Result<bool> {
+    let Some(moved_to) = Setting::get(db.connection(), MOVED_TO_KEY)? else {
+        return Ok(false);
+    };
+    let moved_to = PathBuf::from(moved_to);
+    if !moved_to.is_file() {
+        return Ok(false);
+    }
+    *db = Database::open(&moved_to)?;
+    Ok(true)
+}
+
+/// Write a consistent copy of the database to `path`.
+fn copy_to(conn: &Connection, path: &Path) -> std::result::Result<(), AppError> {
+    let _ = std::fs::remove_file(path);
+    conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])?;
+    Ok(())
+}
+
+/// Point the data directory at `folder`, or back at itself.
+fn write_pointer(data_dir: &Path, folder: &Path) -> io::Result<()> {
+    let pointer = data_dir.join(POINTER_FILE_NAME);
+    if folder == data_dir {
+        return match std::fs::remove_file(&pointer) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    let tmp = pointer.with_extension("tmp");
+    std::fs::write(&tmp, folder.to_string_lossy().as_bytes())?;
+    std::fs::rename(&tmp, &pointer)
+}
+
+/// Delete a database file and its WAL and shared-memory files.
+fn remove_database_files(path: &Path) {
+    let mut paths = vec![path.to_path_buf()];
+    for suffix in ["-wal", "-shm"] {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(suffix);
+        paths.push(PathBuf::from(name));
+    }
+    for path in paths {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                warn!("Failed to remove old database file {}: {e}", path.display());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations;
+    use crate::models::Category;
+    use tempfile::tempdir;
+
+    fn open_in(dir: &Path) -> Database {
+        let db = Database::open(&dir.join(DB_FILE_NAME)).unwrap();
+        migrations::run(db.connection()).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_db_path_follows_pointer() {
+        let data_dir = tempdir().unwrap();
+        let folder = tempdir().unwrap();
+        assert_eq!(
+            db_path(data_dir.path()).unwrap(),
+            data_dir.path().join(DB_FILE_NAME)
+        );
+
+        write_pointer(data_dir.path(), folder.path()).unwrap();
+        assert_eq!(
+            db_path(data_dir.path()).unwrap(),
+            folder.path().join(DB_FILE_NAME)
+        );
+
+        // An unplugged drive: refuse rather than start over
+        let missing = folder.path().join("unplugged");
+        write_pointer(data_dir.path(), &missing).unwrap();
+        assert!(matches!(
+            db_path(data_dir.path()),
+            Err(InitError::DatabaseFolderMissing(path)) if path == missing
+        ));
+    }
+
+    #[test]
+    fn test_relocate_moves_data_and_old_handles_follow() {
+        let data_dir = tempdir().unwrap();
+        let folder = tempdir().unwrap();
+        let mut db = open_in(data_dir.path());
+        let mut other = Database::open(&data_dir.path().join(DB_FILE_NAME)).unwrap();
+        let categories = Category::find_all(db.connection()).unwrap().len();
+
+        let new_path = relocate(&mut db, data_dir.path(), folder.path()).unwrap();
+
+        assert_eq!(new_path, folder.path().join(DB_FILE_NAME));
+        assert_eq!(db.path(), new_path);
+        assert_eq!(
+            Category::find_all(db.connection()).unwrap().len(),
+            categories
+        );
+        assert!(!data_dir.path().join(DB_FILE_NAME).exists());
+        assert_eq!(db_path(data_dir.path()).unwrap(), new_path);
+
+        // A process that had the old file open finds its way over
+        assert!(follow_move(&mut other).unwrap());
+        assert_eq!(other.path(), new_path);
+        assert!(!follow_move(&mut other).unwrap());
+
+        // Moving back home drops the pointer
+        relocate(&mut db, data_dir.path(), data_dir.path()).unwrap();
+        assert_eq!(custom_folder(data_dir.path()), None);
+        assert!(!new_path.exists());
+    }
+
+    #[test]
+    fn test_relocate_refuses_to_overwrite() {
+        let data_dir = tempdir().unwrap();
+        let folder = tempdir().unwrap();
+        let mut db = open_in(data_dir.path());
+        std::fs::write(folder.path().join(DB_FILE_NAME), "").unwrap();
+
+        assert!(matches!(
+            relocate(&mut db, data_dir.path(), folder.path()),
+            Err(AppError::InvalidInput { .. })
+        ));
+        assert_eq!(db.path(), data_dir.path().join(DB_FILE_NAME));
+        assert_eq!(custom_folder(data_dir.path()), None);
+    }
+}
@@ -1,5 +1,5 @@
 use super::schema::{DEFAULT_CATEGORIES, SCHEMA};
-use rusqlite::{Connection, Result};
+use rusqlite::{Connection, OptionalExtension, Result};
 
 pub const DEFAULT_RULES: &[(&str, &str, &str)] = &[
     // Coding (productive)
@@ -34,10 +34,151 @@ pub const DEFAULT_RULES: &[(&str, &str, &str)] = &[
     ("twitch.tv", "domain", "Entertainment"),
 ];
 
+/// Bundle ids and `WM_CLASS` values that name the same app differently on
+/// each platform or version: (identifier, display name).
+pub const DEFAULT_APP_IDENTITIES: &[(&str, &str)] = &[
+    ("com.microsoft.VSCode", "Visual Studio Code"),
+    ("Code", "Visual Studio Code"),
+    ("code-oss", "Visual Studio Code"),
+    ("org.mozilla.firefox", "Firefox"),
+    ("firefox", "Firefox"),
+    ("Navigator", "Firefox"),
+    ("com.google.Chrome", "Google Chrome"),
+    ("google-chrome", "Google Chrome"),
+    ("com.brave.Browser", "Brave Browser"),
+    ("brave-browser", "Brave Browser"),
+    ("com.microsoft.edgemac", "Microsoft Edge"),
+    ("microsoft-edge", "Microsoft Edge"),
+    ("com.tinyspeck.slackmacgap", "Slack"),
+    ("slack", "Slack"),
+    ("com.hnc.Discord", "Discord"),
+    ("discord", "Discord"),
+    ("us.zoom.xos", "Zoom"),
+    ("zoom.us", "Zoom"),
+    ("zoom", "Zoom"),
+    ("com.spotify.client", "Spotify"),
+    ("spotify", "Spotify"),
+    ("com.apple.Terminal", "Terminal"),
+    ("gnome-terminal-server", "Terminal"),
+    ("com.googlecode.iterm2", "iTerm2"),
+    ("com.jetbrains.intellij", "IntelliJ IDEA"),
+    ("jetbrains-idea", "IntelliJ IDEA"),
+    ("org.mozilla.thunderbird", "Thunderbird"),
+    ("thunderbird", "Thunderbird"),
+];
+
+/// Columns added after a table first shipped: (table, column, definition).
+///
+/// `CREATE TABLE IF NOT EXISTS` leaves existing tables untouched, so databases
+/// created by older versions get these via `ALTER TABLE` instead.
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("activities", "context", "TEXT"),
+    ("activities", "profile", "TEXT"),
+    ("activities", "title_context", "TEXT"),
+    ("activities", "browser", "TEXT"),
+    ("categories", "weight", "REAL"),
+    ("focus_sessions", "planned_duration", "INTEGER"),
+    ("focus_sessions", "strict", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "focused_secs", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "idle_secs", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "longest_streak_secs", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "streak_app", "TEXT"),
+    ("focus_sessions", "extension_offered", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "unlock_requested_at", "INTEGER"),
+    ("focus_sessions", "emergency_unlocked", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "last_distraction_request_at", "INTEGER"),
+    ("focus_sessions", "effectiveness_score", "REAL"),
+    ("focus_sessions", "productive_secs", "INTEGER DEFAULT 0"),
+    ("focus_sessions", "earned_budget_secs", "INTEGER DEFAULT 0"),
+    ("focus_schedules", "blocked_categories", "TEXT"),
+    ("focus_schedules", "deleted_at", "INTEGER"),
+    ("focus_schedules", "version", "INTEGER NOT NULL DEFAULT 1"),
+    (
+        "focus_schedules",
+        "notify_lead_minutes",
+        "INTEGER NOT NULL DEFAULT 5",
+    ),
+    ("rules", "profile", "TEXT"),
+    (
+        "rules",
+        "group_id",
+        "INTEGER REFERENCES rule_groups(id) ON DELETE SET NULL",
+    ),
+    ("rules", "condition_days", "TEXT"),
+    ("rules", "condition_start", "TEXT"),
+    ("rules", "condition_end", "TEXT"),
+    ("rules", "deleted_at", "INTEGER"),
+    ("rules", "version", "INTEGER NOT NULL DEFAULT 1"),
+];
+
+/// `user_version` once orphaned rows have been cleaned up. Foreign keys are
+/// enforced from then on, so the scan only has to run once.
+const ORPHANS_REMOVED_VERSION: i32 = 1;
+
 pub fn run(conn: &Connection) -> Result<()> {
     conn.execute_batch(SCHEMA)?;
+    for (table, column, definition) in ADDED_COLUMNS {
+        add_column_if_missing(conn, table, column, definition)?;
+    }
+    if schema_version(conn)? < ORPHANS_REMOVED_VERSION {
+        remove_orphaned_rows(conn)?;
+        conn.pragma_update(None, "user_version", ORPHANS_REMOVED_VERSION)?;
+    }
     seed_default_categories(conn)?;
     seed_default_rules(conn)?;
+    seed_default_app_identities(conn)?;
+    Ok(())
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+        [table, column],
+        |row| row.get(0),
+    )?;
+
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+        ))?;
+    }
+    Ok(())
+}
+
+fn schema_version(conn: &Connection) -> Result<i32> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Resolves rows left dangling by deletes made while foreign keys weren't
+/// enforced, applying each reference's own ON DELETE action: SET NULL
+/// references are cleared, CASCADE rows are removed.
+fn remove_orphaned_rows(conn: &Connection) -> Result<()> {
+    let violations: Vec<(String, i64, i64)> = conn
+        .prepare("SELECT \"table\", rowid, fkid FROM pragma_foreign_key_check")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_>>()?;
+
+    for (table, rowid, fk_id) in violations {
+        let reference: Option<(String, String)> = conn
+            .query_row(
+                "SELECT \"from\", on_delete FROM pragma_foreign_key_list(?1) WHERE id = ?2",
+                rusqlite::params![table, fk_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let statement = match reference {
+            Some((column, on_delete)) if on_delete == "SET NULL" => {
+                format!("UPDATE {table} SET {column} = NULL WHERE rowid = ?1")
+            }
+            Some(_) | None => format!("DELETE FROM {table} WHERE rowid = ?1"),
+        };
+        conn.execute(&statement, [rowid])?;
+    }
     Ok(())
 }
 
@@ -55,6 +196,20 @@ fn seed_default_categories(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn seed_default_app_identities(conn: &Connection) -> Result<()> {
+    let count: i32 = conn.query_row("SELECT COUNT(*) FROM app_identities", [], |row| row.get(0))?;
+
+    if count == 0 {
+        for (identifier, display_name) in DEFAULT_APP_IDENTITIES {
+            conn.execute(
+                "INSERT OR IGNORE INTO app_identities (identifier, display_name) VALUES (?1, ?2)",
+                [*identifier, *display_name],
+            )?;
+        }
+    }
+    Ok(())
+}
+
 fn seed_default_rules(conn: &Connection) -> Result<()> {
     let count: i32 = conn.query_row("SELECT COUNT(*) FROM rules", [], |row| row.get(0))?;
 
@@ -1,25 +1,52 @@
 pub mod helpers;
+pub mod location;
 pub mod migrations;
+pub mod query_plan;
 pub mod schema;
 
 pub use helpers::with_connection;
 
-use rusqlite::{Connection, Result};
-use std::path::PathBuf;
+use rusqlite::{Connection, OpenFlags, Result};
+use std::path::{Path, PathBuf};
 
 pub struct Database {
     conn: Connection,
+    path: PathBuf,
 }
 
 impl Database {
     pub fn open(path: &PathBuf) -> Result<Self> {
         let conn = Connection::open(path)?;
-        Ok(Self { conn })
+        // SQLite leaves foreign keys off per connection unless asked; don't
+        // rely on how the library happened to be compiled.
+        conn.pragma_update(None, "foreign_keys", true)?;
+        Ok(Self {
+            conn,
+            path: path.clone(),
+        })
+    }
+
+    /// Open an existing database without the ability to change it.
+    pub fn open_read_only(path: &PathBuf) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.pragma_update(None, "query_only", true)?;
+        Ok(Self {
+            conn,
+            path: path.clone(),
+        })
     }
 
     pub fn connection(&self) -> &Connection {
         &self.conn
     }
+
+    /// The file this database was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 }
 
 #[cfg(test)]
@@ -35,6 +62,24 @@ mod tests {
         assert!(db_path.exists());
     }
 
+    #[test]
+    fn test_read_only_database_refuses_writes() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        migrations::run(Database::open(&db_path).unwrap().connection()).unwrap();
+
+        let db = Database::open_read_only(&db_path).unwrap();
+        let count: i32 = db
+            .connection()
+            .query_row("SELECT COUNT(*) FROM categories", [], |row| row.get(0))
+            .unwrap();
+        assert!(count > 0);
+        assert!(db
+            .connection()
+            .execute("DELETE FROM categories", [])
+            .is_err());
+    }
+
     #[test]
     fn test_migrations_run() {
         let dir = tempdir().unwrap();
@@ -68,6 +113,8 @@ mod tests {
             "activities",
             "focus_sessions",
             "focus_schedules",
+            "enforced_breaks",
+            "settings",
         ];
         for table in &expected_tables {
             let count: i32 = db
@@ -132,6 +179,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_migrations_add_new_columns_to_existing_tables() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+
+        // Simulate a database created before planned_duration existed
+        db.connection()
+            .execute_batch(
+                "CREATE TABLE focus_sessions (
+                    id INTEGER PRIMARY KEY,
+                    started_at INTEGER NOT NULL,
+                    ended_at INTEGER,
+                    scheduled INTEGER DEFAULT 0,
+                    distraction_budget INTEGER NOT NULL,
+                    distraction_used INTEGER DEFAULT 0
+                );
+                INSERT INTO focus_sessions (started_at, distraction_budget) VALUES (1000, 600);",
+            )
+            .unwrap();
+
+        migrations::run(db.connection()).unwrap();
+
+        let planned: Option<i32> = db
+            .connection()
+            .query_row("SELECT planned_duration FROM focus_sessions", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(planned, None, "Existing rows should keep working");
+    }
+
     #[test]
     fn test_default_rules_seeded() {
         let dir = tempdir().unwrap();
@@ -215,4 +294,101 @@ mod tests {
             "Running migrations twice should not duplicate rules"
         );
     }
+
+    #[test]
+    fn test_foreign_keys_enabled() {
+        let dir = tempdir().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).unwrap();
+        let enabled: bool = db
+            .connection()
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert!(enabled);
+    }
+
+    #[test]
+    fn test_every_foreign_key_declares_on_delete() {
+        let dir = tempdir().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).unwrap();
+        migrations::run(db.connection()).unwrap();
+
+        let implicit: Vec<String> = db
+            .connection()
+            .prepare(
+                "SELECT m.name || '.' || f.\"from\" FROM sqlite_master m
+                 JOIN pragma_foreign_key_list(m.name) f
+                 WHERE m.type = 'table' AND f.on_delete NOT IN ('CASCADE', 'SET NULL')",
+            )
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert!(implicit.is_empty(), "{implicit:?}");
+    }
+
+    #[test]
+    fn test_migrations_clean_up_orphaned_rows() {
+        let dir = tempdir().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).unwrap();
+        let conn = db.connection();
+        migrations::run(conn).unwrap();
+
+        // A database from before the cleanup, with rows written while
+        // foreign keys weren't enforced
+        conn.execute_batch(
+            "PRAGMA user_version = 0;
+             PRAGMA foreign_keys = OFF;
+             INSERT INTO activities (id, timestamp, duration_secs, source, category_id)
+                 VALUES (900, 1000, 5, 'app', 999);
+             INSERT INTO rules (id, pattern, match_type, category_id) VALUES (900, 'x', 'app', 999);
+             INSERT INTO rule_patterns (rule_id, pattern) VALUES (900, 'y');
+             INSERT INTO notes (day, activity_id, text, created_at, updated_at)
+                 VALUES ('2026-01-01', 901, 'n', 0, 0);
+             INSERT INTO session_blocks (session_id, pattern) VALUES (999, 'z');
+             PRAGMA foreign_keys = ON;",
+        )
+        .unwrap();
+
+        migrations::run(conn).unwrap();
+
+        let category: Option<i64> = conn
+            .query_row(
+                "SELECT category_id FROM activities WHERE id = 900",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(category, None, "activity is kept but uncategorized");
+        let note_activity: Option<i64> = conn
+            .query_row("SELECT activity_id FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(note_activity, None);
+        for table in ["rule_patterns", "session_blocks"] {
+            let count: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            assert_eq!(count, 0, "{table} should be emptied");
+        }
+        let orphan_rule: i64 = conn
+            .query_row("SELECT COUNT(*) FROM rules WHERE id = 900", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(orphan_rule, 0);
+        let violations: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(violations, 0);
+
+        // Later launches don't scan again
+        let version: i32 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 1);
+    }
 }
@@ -0,0 +1,116 @@
+//! `EXPLAIN QUERY PLAN` over the hot activity queries behind the dashboard,
+//! so a missing index shows up before the table reaches millions of rows.
+
+use rusqlite::{Connection, Result};
+
+/// Representative copies of the stats queries, named after the model function
+/// they mirror. Parameters stay unbound; the planner doesn't need values.
+const HOT_QUERIES: &[(&str, &str)] = &[
+    (
+        "productivity_totals",
+        "SELECT SUM(a.duration_secs) FROM activities a
+         JOIN categories c ON a.category_id = c.id
+         WHERE a.timestamp >= ?1 AND a.timestamp < ?2",
+    ),
+    (
+        "top_apps",
+        "SELECT COALESCE(ai.display_name, a.app_name) AS app, SUM(a.duration_secs) AS total
+         FROM activities a
+         LEFT JOIN app_identities ai ON ai.identifier = a.app_name
+         WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND a.app_name IS NOT NULL
+         GROUP BY app ORDER BY total DESC LIMIT ?3",
+    ),
+    (
+        "top_domains",
+        "SELECT a.domain, SUM(a.duration_secs) AS total
+         FROM activities a
+         LEFT JOIN categories c ON a.category_id = c.id
+         WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND a.domain IS NOT NULL
+         GROUP BY a.domain ORDER BY total DESC LIMIT ?3",
+    ),
+    (
+        "app_activity",
+        "SELECT context, SUM(duration_secs) FROM activities
+         WHERE app_name = ?1 AND timestamp >= ?2 AND timestamp < ?3
+         GROUP BY context",
+    ),
+    (
+        "domain_activity",
+        "SELECT SUM(duration_secs) FROM activities
+         WHERE domain = ?1 AND timestamp >= ?2 AND timestamp < ?3",
+    ),
+    (
+        "category_activity",
+        "SELECT SUM(duration_secs) FROM activities
+         WHERE category_id = ?1 AND timestamp >= ?2 AND timestamp < ?3",
+    ),
+];
+
+#[derive(Debug, Clone)]
+pub struct QueryPlan {
+    pub name: &'static str,
+    /// Plan steps as the planner reports them, e.g. `SEARCH a USING INDEX ...`.
+    pub steps: Vec<String>,
+    /// A step reads a whole table without any index.
+    pub full_scan: bool,
+}
+
+/// Plans for every hot query against the current schema.
+pub fn analyze(conn: &Connection) -> Result<Vec<QueryPlan>> {
+    HOT_QUERIES
+        .iter()
+        .map(|(name, sql)| {
+            let steps = explain(conn, sql)?;
+            let full_scan = steps
+                .iter()
+                .any(|step| step.starts_with("SCAN ") && !step.contains(" INDEX "));
+            Ok(QueryPlan {
+                name,
+                steps,
+                full_scan,
+            })
+        })
+        .collect()
+}
+
+fn explain(conn: &Connection, sql: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+    let mut rows = stmt.raw_query();
+    let mut steps = Vec::new();
+    while let Some(row) = rows.next()? {
+        steps.push(row.get(3)?);
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn plan<'a>(plans: &'a [QueryPlan], name: &str) -> &'a QueryPlan {
+        plans.iter().find(|p| p.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_hot_queries_avoid_full_scans() {
+        let (db, _dir) = setup_test_db();
+        let plans = analyze(db.connection()).unwrap();
+
+        assert_eq!(plans.len(), HOT_QUERIES.len());
+        for plan in &plans {
+            assert!(!plan.full_scan, "{}: {:?}", plan.name, plan.steps);
+        }
+        for (name, index) in [
+            ("app_activity", "idx_activities_app_timestamp"),
+            ("domain_activity", "idx_activities_domain_timestamp"),
+            ("category_activity", "idx_activities_category_timestamp"),
+        ] {
+            let steps = &plan(&plans, name).steps;
+            assert!(
+                steps.iter().any(|step| step.contains(index)),
+                "{name}: {steps:?}"
+            );
+        }
+    }
+}
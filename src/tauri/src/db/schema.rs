@@ -2,7 +2,8 @@ pub const SCHEMA: &str = r"
 CREATE TABLE IF NOT EXISTS categories (
     id INTEGER PRIMARY KEY,
     name TEXT NOT NULL UNIQUE,
-    productivity INTEGER NOT NULL
+    productivity INTEGER NOT NULL,
+    weight REAL
 );
 
 CREATE TABLE IF NOT EXISTS rules (
@@ -10,9 +11,29 @@ CREATE TABLE IF NOT EXISTS rules (
     pattern TEXT NOT NULL,
     match_type TEXT NOT NULL,
     category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
-    priority INTEGER DEFAULT 0
+    priority INTEGER DEFAULT 0,
+    profile TEXT,
+    group_id INTEGER REFERENCES rule_groups(id) ON DELETE SET NULL,
+    condition_days TEXT,
+    condition_start TEXT,
+    condition_end TEXT,
+    deleted_at INTEGER,
+    version INTEGER NOT NULL DEFAULT 1
 );
 
+CREATE TABLE IF NOT EXISTS rule_groups (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    enabled INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS rule_patterns (
+    rule_id INTEGER NOT NULL REFERENCES rules(id) ON DELETE CASCADE,
+    pattern TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rule_patterns_rule ON rule_patterns(rule_id);
+
 CREATE TABLE IF NOT EXISTS activities (
     id INTEGER PRIMARY KEY,
     timestamp INTEGER NOT NULL,
@@ -22,7 +43,11 @@ CREATE TABLE IF NOT EXISTS activities (
     window_title TEXT,
     url TEXT,
     domain TEXT,
-    category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL
+    category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    context TEXT,
+    profile TEXT,
+    title_context TEXT,
+    browser TEXT
 );
 
 CREATE TABLE IF NOT EXISTS focus_sessions (
@@ -31,7 +56,156 @@ CREATE TABLE IF NOT EXISTS focus_sessions (
     ended_at INTEGER,
     scheduled INTEGER DEFAULT 0,
     distraction_budget INTEGER NOT NULL,
-    distraction_used INTEGER DEFAULT 0
+    distraction_used INTEGER DEFAULT 0,
+    planned_duration INTEGER,
+    strict INTEGER DEFAULT 0,
+    focused_secs INTEGER DEFAULT 0,
+    idle_secs INTEGER DEFAULT 0,
+    longest_streak_secs INTEGER DEFAULT 0,
+    streak_app TEXT,
+    extension_offered INTEGER DEFAULT 0,
+    unlock_requested_at INTEGER,
+    emergency_unlocked INTEGER DEFAULT 0,
+    last_distraction_request_at INTEGER,
+    effectiveness_score REAL,
+    productive_secs INTEGER DEFAULT 0,
+    earned_budget_secs INTEGER DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS session_budgets (
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    budget_secs INTEGER NOT NULL,
+    PRIMARY KEY (session_id, category_id)
+);
+
+CREATE TABLE IF NOT EXISTS session_blocks (
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    pattern TEXT NOT NULL,
+    PRIMARY KEY (session_id, pattern)
+);
+
+CREATE TABLE IF NOT EXISTS session_blocked_categories (
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    PRIMARY KEY (session_id, category_id)
+);
+
+CREATE TABLE IF NOT EXISTS session_exceptions (
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    domain TEXT NOT NULL,
+    PRIMARY KEY (session_id, domain)
+);
+
+CREATE TABLE IF NOT EXISTS domain_allows (
+    id INTEGER PRIMARY KEY,
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    domain TEXT NOT NULL,
+    granted_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS blocked_attempts (
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    domain TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_attempt_at INTEGER NOT NULL,
+    PRIMARY KEY (session_id, domain)
+);
+
+CREATE TABLE IF NOT EXISTS distraction_events (
+    id INTEGER PRIMARY KEY,
+    session_id INTEGER NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+    timestamp INTEGER NOT NULL,
+    domain TEXT,
+    category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    secs INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS notes (
+    id INTEGER PRIMARY KEY,
+    day TEXT NOT NULL,
+    activity_id INTEGER REFERENCES activities(id) ON DELETE SET NULL,
+    text TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notes_day ON notes(day);
+
+CREATE TABLE IF NOT EXISTS focus_requests (
+    id INTEGER PRIMARY KEY,
+    requested_at INTEGER NOT NULL,
+    duration_secs INTEGER NOT NULL,
+    budget_secs INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sync_applied_requests (
+    device TEXT NOT NULL,
+    request_id INTEGER NOT NULL,
+    PRIMARY KEY (device, request_id)
+);
+
+CREATE TABLE IF NOT EXISTS editor_activities (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    duration_secs INTEGER NOT NULL,
+    editor TEXT NOT NULL,
+    project TEXT,
+    file TEXT,
+    language TEXT
+);
+
+CREATE TABLE IF NOT EXISTS terminal_contexts (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    cwd TEXT NOT NULL,
+    repo TEXT
+);
+
+CREATE TABLE IF NOT EXISTS app_identities (
+    identifier TEXT PRIMARY KEY COLLATE NOCASE,
+    display_name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS operation_journal (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    origin TEXT NOT NULL,
+    session_id INTEGER REFERENCES focus_sessions(id) ON DELETE SET NULL,
+    detail TEXT
+);
+
+CREATE TABLE IF NOT EXISTS session_queue (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    duration_secs INTEGER NOT NULL,
+    budget_secs INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS insights (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    week_start TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'new',
+    UNIQUE (kind, week_start)
+);
+
+CREATE TABLE IF NOT EXISTS time_exports (
+    provider TEXT NOT NULL,
+    entry_key TEXT NOT NULL,
+    exported_at INTEGER NOT NULL,
+    PRIMARY KEY (provider, entry_key)
 );
 
 CREATE TABLE IF NOT EXISTS focus_schedules (
@@ -40,14 +214,63 @@ CREATE TABLE IF NOT EXISTS focus_schedules (
     start_time TEXT NOT NULL,
     end_time TEXT NOT NULL,
     distraction_budget INTEGER NOT NULL,
-    enabled INTEGER DEFAULT 1
+    enabled INTEGER DEFAULT 1,
+    blocked_categories TEXT,
+    deleted_at INTEGER,
+    version INTEGER NOT NULL DEFAULT 1,
+    notify_lead_minutes INTEGER NOT NULL DEFAULT 5
+);
+
+CREATE TABLE IF NOT EXISTS away_periods (
+    id INTEGER PRIMARY KEY,
+    start_date TEXT NOT NULL,
+    until_date TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS schedule_skips (
+    schedule_id INTEGER NOT NULL REFERENCES focus_schedules(id) ON DELETE CASCADE,
+    date TEXT NOT NULL,
+    PRIMARY KEY (schedule_id, date)
+);
+
+CREATE TABLE IF NOT EXISTS enforced_breaks (
+    id INTEGER PRIMARY KEY,
+    started_at INTEGER NOT NULL,
+    duration_secs INTEGER NOT NULL,
+    trigger TEXT NOT NULL,
+    ended_at INTEGER,
+    skipped_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS rule_suggestions (
+    id INTEGER PRIMARY KEY,
+    pattern TEXT NOT NULL,
+    match_type TEXT NOT NULL,
+    category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at INTEGER NOT NULL,
+    UNIQUE(pattern, match_type)
+);
+
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
 );
 
 CREATE INDEX IF NOT EXISTS idx_activities_timestamp ON activities(timestamp);
 CREATE INDEX IF NOT EXISTS idx_activities_category ON activities(category_id);
+CREATE INDEX IF NOT EXISTS idx_activities_app_timestamp ON activities(app_name, timestamp);
+CREATE INDEX IF NOT EXISTS idx_activities_domain_timestamp ON activities(domain, timestamp);
+CREATE INDEX IF NOT EXISTS idx_activities_category_timestamp ON activities(category_id, timestamp);
 CREATE INDEX IF NOT EXISTS idx_activities_timestamp_appname ON activities(timestamp DESC, app_name) WHERE app_name IS NOT NULL;
 CREATE INDEX IF NOT EXISTS idx_focus_sessions_active ON focus_sessions(ended_at) WHERE ended_at IS NULL;
 CREATE INDEX IF NOT EXISTS idx_focus_sessions_started_at ON focus_sessions(started_at);
+CREATE INDEX IF NOT EXISTS idx_distraction_events_session ON distraction_events(session_id);
+CREATE INDEX IF NOT EXISTS idx_editor_activities_timestamp ON editor_activities(timestamp);
+CREATE INDEX IF NOT EXISTS idx_terminal_contexts_timestamp ON terminal_contexts(timestamp);
+CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log(timestamp);
 CREATE INDEX IF NOT EXISTS idx_rules_category ON rules(category_id);
 CREATE INDEX IF NOT EXISTS idx_rules_match_type_priority ON rules(match_type, priority DESC);
 ";
@@ -0,0 +1,272 @@
+use crate::constants::MAX_ACTIVITY_GAP_SECS;
+use crate::db::{with_connection, Database};
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Category, FocusSession, Setting};
+use crate::notifications::notify;
+use crate::tray::format_duration;
+use log::{error, warn};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+const OFFER_EXTENSION_SETTING: &str = "deep_work.offer_extension";
+const THRESHOLD_SETTING: &str = "deep_work.threshold_minutes";
+const EXTENSION_SETTING: &str = "deep_work.extension_minutes";
+
+pub const DEFAULT_THRESHOLD_MINUTES: i32 = 30;
+pub const DEFAULT_EXTENSION_MINUTES: i32 = 15;
+
+/// Only offer an extension once the session is close to ending.
+const OFFER_WINDOW_SECS: i64 = 5 * 60;
+
+/// Event emitted when the user can extend a session they're deep in.
+pub const DEEP_WORK_EVENT: &str = "deep-work";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepWorkSettings {
+    /// Offer to extend a timed session when it's about to end mid-streak.
+    pub offer_extension: bool,
+    /// Minutes in one productive app before a stretch counts as deep work.
+    pub threshold_minutes: i32,
+    /// Minutes added when the user accepts the offer.
+    pub extension_minutes: i32,
+}
+
+impl DeepWorkSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let minutes = |key: &str, default: i32| -> Result<i32> {
+            Ok(Setting::get(conn, key)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default))
+        };
+        Ok(Self {
+            offer_extension: Setting::get(conn, OFFER_EXTENSION_SETTING)?.as_deref()
+                != Some("false"),
+            threshold_minutes: minutes(THRESHOLD_SETTING, DEFAULT_THRESHOLD_MINUTES)?,
+            extension_minutes: minutes(EXTENSION_SETTING, DEFAULT_EXTENSION_MINUTES)?,
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(
+            conn,
+            OFFER_EXTENSION_SETTING,
+            &self.offer_extension.to_string(),
+        )?;
+        Setting::set(conn, THRESHOLD_SETTING, &self.threshold_minutes.to_string())?;
+        Setting::set(conn, EXTENSION_SETTING, &self.extension_minutes.to_string())
+    }
+}
+
+/// An unbroken run of productive time in one app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Streak {
+    pub app_name: String,
+    pub started_at: i64,
+    pub secs: i32,
+}
+
+#[derive(Clone, Serialize)]
+struct DeepWorkPayload {
+    app_name: String,
+    streak_secs: i32,
+    extension_minutes: i32,
+}
+
+/// The streak still running at the end of `activities` (sorted oldest first).
+///
+/// It breaks on an app switch, a non-productive sample, or a gap in tracking.
+pub fn current_streak(activities: &[Activity], productive: &HashSet<i64>) -> Option<Streak> {
+    let last = activities.last()?;
+    let app_name = last.app_name.as_deref()?;
+    let end = last.timestamp + i64::from(last.duration_secs);
+
+    let mut started_at = None;
+    let mut next_start = last.timestamp;
+    for activity in activities.iter().rev() {
+        let is_productive = activity
+            .category_id
+            .is_some_and(|id| productive.contains(&id));
+        let gap = next_start - (activity.timestamp + i64::from(activity.duration_secs));
+        if activity.app_name.as_deref() != Some(app_name)
+            || !is_productive
+            || gap > MAX_ACTIVITY_GAP_SECS
+        {
+            break;
+        }
+        started_at = Some(activity.timestamp);
+        next_start = activity.timestamp;
+    }
+
+    let started_at = started_at?;
+    Some(Streak {
+        app_name: app_name.to_string(),
+        started_at,
+        secs: i32::try_from(end - started_at).unwrap_or(i32::MAX),
+    })
+}
+
+/// Whether to offer extending `session` given its current streak.
+fn should_offer(
+    settings: &DeepWorkSettings,
+    session: &FocusSession,
+    streak: &Streak,
+    now: i64,
+) -> bool {
+    settings.offer_extension
+        && !session.extension_offered
+        && streak.secs >= settings.threshold_minutes * 60
+        && session
+            .time_remaining(now)
+            .is_some_and(|left| left <= OFFER_WINDOW_SECS)
+}
+
+/// Track the active session's streak and offer an extension when it's ending mid-streak.
+pub fn check_deep_work(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let now = current_timestamp();
+
+    let offer = with_connection(&db, |conn| {
+        let Some(mut session) = FocusSession::find_active(conn)? else {
+            return Ok(None);
+        };
+        let productive: HashSet<i64> = Category::find_all(conn)?
+            .into_iter()
+            .filter(|c| c.productivity > 0)
+            .map(|c| c.id)
+            .collect();
+        let activities = Activity::find_in_range(conn, session.started_at, now + 1)?;
+        let Some(streak) = current_streak(&activities, &productive) else {
+            return Ok(None);
+        };
+        session.record_streak(conn, streak.secs, &streak.app_name)?;
+
+        let settings = DeepWorkSettings::load(conn)?;
+        if !should_offer(&settings, &session, &streak, now) {
+            return Ok(None);
+        }
+        session.mark_extension_offered(conn)?;
+        Ok(Some(DeepWorkPayload {
+            app_name: streak.app_name,
+            streak_secs: streak.secs,
+            extension_minutes: settings.extension_minutes,
+        }))
+    });
+
+    match offer {
+        Ok(Some(payload)) => offer_extension(app, &payload),
+        Ok(None) => {}
+        Err(e) => error!("Failed to check deep work streak: {e}"),
+    }
+}
+
+fn offer_extension(app: &AppHandle, payload: &DeepWorkPayload) {
+    if let Err(e) = app.emit(DEEP_WORK_EVENT, payload) {
+        warn!("Failed to emit deep work event: {e}");
+    }
+    let body = format!(
+        "You've been in {} for {}. Extend this session by {} minutes?",
+        payload.app_name,
+        format_duration(payload.streak_secs),
+        payload.extension_minutes
+    );
+    notify(app, "You're in the zone", &body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const PRODUCTIVE: i64 = 1;
+    const DISTRACTING: i64 = 2;
+    const POLL: i32 = 5;
+
+    /// Consecutive 5-second samples of `app` starting at `start`.
+    fn samples(app: &str, start: i64, count: i64, category_id: i64) -> Vec<Activity> {
+        (0..count)
+            .map(|i| {
+                let mut activity =
+                    Activity::new(start + i * i64::from(POLL), POLL, "app", Some(app), None);
+                activity.category_id = Some(category_id);
+                activity
+            })
+            .collect()
+    }
+
+    fn productive() -> HashSet<i64> {
+        HashSet::from([PRODUCTIVE])
+    }
+
+    #[test]
+    fn test_settings_default_and_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let defaults = DeepWorkSettings::load(conn).unwrap();
+        assert!(defaults.offer_extension);
+        assert_eq!(defaults.threshold_minutes, DEFAULT_THRESHOLD_MINUTES);
+
+        let custom = DeepWorkSettings {
+            offer_extension: false,
+            threshold_minutes: 45,
+            extension_minutes: 10,
+        };
+        custom.save(conn).unwrap();
+        assert_eq!(DeepWorkSettings::load(conn).unwrap(), custom);
+    }
+
+    #[test]
+    fn test_streak_stops_at_app_switch() {
+        let mut activities = samples("Slack", 0, 10, PRODUCTIVE);
+        activities.extend(samples("Code", 50, 12, PRODUCTIVE));
+
+        let streak = current_streak(&activities, &productive()).unwrap();
+        assert_eq!(streak.app_name, "Code");
+        assert_eq!((streak.started_at, streak.secs), (50, 60));
+    }
+
+    #[test]
+    fn test_streak_stops_at_gap() {
+        let mut activities = samples("Code", 0, 10, PRODUCTIVE);
+        activities.extend(samples("Code", 600, 4, PRODUCTIVE));
+
+        let streak = current_streak(&activities, &productive()).unwrap();
+        assert_eq!((streak.started_at, streak.secs), (600, 20));
+    }
+
+    #[test]
+    fn test_no_streak_when_distracted() {
+        let activities = samples("YouTube", 0, 10, DISTRACTING);
+        assert_eq!(current_streak(&activities, &productive()), None);
+        assert_eq!(current_streak(&[], &productive()), None);
+    }
+
+    #[test]
+    fn test_should_offer_near_end_of_long_streak() {
+        let settings = DeepWorkSettings {
+            offer_extension: true,
+            threshold_minutes: 30,
+            extension_minutes: 15,
+        };
+        let mut session = FocusSession::new(600, false);
+        session.started_at = 0;
+        session.planned_duration = Some(50 * 60);
+        let streak = Streak {
+            app_name: "Code".into(),
+            started_at: 10 * 60,
+            secs: 36 * 60,
+        };
+
+        assert!(should_offer(&settings, &session, &streak, 46 * 60));
+        // Too early in the session
+        assert!(!should_offer(&settings, &session, &streak, 30 * 60));
+
+        session.extension_offered = true;
+        assert!(!should_offer(&settings, &session, &streak, 46 * 60));
+    }
+}
@@ -0,0 +1,396 @@
+//! Opt-in Discord Rich Presence showing "In focus" while a session runs.
+//!
+//! Talks to the local Discord client over its IPC socket: a handshake with
+//! the user's Discord application id, then `SET_ACTIVITY` frames. Discord
+//! drops the status by itself when Foxus exits.
+
+use crate::db::{with_connection, Database};
+use crate::focus::{FocusManager, FocusState};
+use crate::models::focus_session::current_timestamp;
+use crate::models::Setting;
+use log::{debug, error};
+use rusqlite::{Connection, Result};
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const ENABLED_SETTING: &str = "discord.presence_enabled";
+const CLIENT_ID_SETTING: &str = "discord.client_id";
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+/// Replies larger than this aren't from a well-behaved Discord client.
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscordPresenceSettings {
+    pub enabled: bool,
+    /// Id of a Discord application created by the user; its name is shown
+    /// as the activity title.
+    pub client_id: Option<String>,
+}
+
+impl DiscordPresenceSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            enabled: Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"),
+            client_id: Setting::get(conn, CLIENT_ID_SETTING)?,
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        match &self.client_id {
+            Some(id) => Setting::set(conn, CLIENT_ID_SETTING, id),
+            None => Setting::delete(conn, CLIENT_ID_SETTING),
+        }
+    }
+}
+
+/// Status line for an active session, e.g. "In focus \u{2014} 32 min left".
+pub fn status_text(state: &FocusState) -> String {
+    match state.time_remaining_secs {
+        Some(secs) => format!("In focus \u{2014} {} min left", (secs + 59) / 60),
+        None => "In focus".to_string(),
+    }
+}
+
+/// `SET_ACTIVITY` activity for an active session. Timed sessions get an end
+/// timestamp so Discord counts down between updates.
+fn activity(state: &FocusState, now: i64) -> Value {
+    let timestamps = match state.time_remaining_secs {
+        Some(secs) => json!({ "end": now + secs }),
+        None => json!({ "start": now - state.session_duration_secs.unwrap_or_default() }),
+    };
+    json!({
+        "details": status_text(state),
+        "timestamps": timestamps,
+    })
+}
+
+fn write_frame<W: Write>(writer: &mut W, op: u32, payload: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(payload)?;
+    let len = u32::try_from(body.len()).map_err(|_| io::Error::other("frame too large"))?;
+    let mut frame = Vec::with_capacity(body.len() + 8);
+    frame.extend_from_slice(&op.to_le_bytes());
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(&body);
+    writer.write_all(&frame)
+}
+
+fn read_frame<R: Read>(reader: &mut R) -> io::Result<(u32, Value)> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    let [o0, o1, o2, o3, l0, l1, l2, l3] = header;
+    let op = u32::from_le_bytes([o0, o1, o2, o3]);
+    let len = usize::try_from(u32::from_le_bytes([l0, l1, l2, l3]))
+        .ok()
+        .filter(|len| *len <= MAX_FRAME_LEN)
+        .ok_or_else(|| io::Error::other("frame too large"))?;
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok((op, serde_json::from_slice(&body)?))
+}
+
+/// A handshaken connection to the Discord client.
+struct IpcClient<S> {
+    stream: S,
+}
+
+impl<S: Read + Write> IpcClient<S> {
+    fn handshake(mut stream: S, client_id: &str) -> io::Result<Self> {
+        write_frame(
+            &mut stream,
+            OP_HANDSHAKE,
+            &json!({ "v": 1, "client_id": client_id }),
+        )?;
+        let mut client = Self { stream };
+        client.reply()?;
+        Ok(client)
+    }
+
+    /// Next reply, as an error if Discord closed the connection or refused
+    /// the request.
+    fn reply(&mut self) -> io::Result<Value> {
+        let (op, reply) = read_frame(&mut self.stream)?;
+        if op == OP_CLOSE || reply.get("evt").and_then(Value::as_str) == Some("ERROR") {
+            let message = reply
+                .pointer("/data/message")
+                .or_else(|| reply.get("message"))
+                .and_then(Value::as_str)
+                .unwrap_or("connection refused");
+            return Err(io::Error::other(format!("Discord: {message}")));
+        }
+        Ok(reply)
+    }
+
+    /// Show `activity`, or clear the status with `None`.
+    fn set_activity(&mut self, activity: Option<&Value>) -> io::Result<()> {
+        write_frame(
+            &mut self.stream,
+            OP_FRAME,
+            &json!({
+                "cmd": "SET_ACTIVITY",
+                "args": { "pid": std::process::id(), "activity": activity },
+                "nonce": current_timestamp().to_string(),
+            }),
+        )?;
+        self.reply().map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+type IpcStream = std::os::unix::net::UnixStream;
+
+/// Sockets the Discord client may listen on, including Flatpak and Snap installs.
+#[cfg(unix)]
+fn socket_paths() -> Vec<std::path::PathBuf> {
+    let dirs: Vec<String> = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(std::iter::once("/tmp".to_string()))
+        .collect();
+    let mut paths = Vec::new();
+    for dir in &dirs {
+        for sub in ["", "app/com.discordapp.Discord", "snap.discord"] {
+            for n in 0..10 {
+                paths.push(
+                    std::path::Path::new(dir)
+                        .join(sub)
+                        .join(format!("discord-ipc-{n}")),
+                );
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(unix)]
+fn connect(client_id: &str) -> io::Result<IpcClient<IpcStream>> {
+    let timeout = Some(std::time::Duration::from_secs(2));
+    let stream = socket_paths()
+        .iter()
+        .find_map(|path| IpcStream::connect(path).ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Discord is not running"))?;
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)?;
+    IpcClient::handshake(stream, client_id)
+}
+
+#[cfg(not(unix))]
+type IpcStream = std::fs::File;
+
+#[cfg(not(unix))]
+fn connect(_client_id: &str) -> io::Result<IpcClient<IpcStream>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Discord presence is not supported on this platform",
+    ))
+}
+
+/// Connection and the status last shown, so unchanged status isn't resent.
+#[derive(Default)]
+pub struct DiscordPresence {
+    client: Option<IpcClient<IpcStream>>,
+    client_id: Option<String>,
+    shown: Option<String>,
+}
+
+impl DiscordPresence {
+    fn show(&mut self, client_id: &str, activity: Option<&Value>) -> io::Result<()> {
+        if self.client_id.as_deref() != Some(client_id) {
+            self.client = None;
+        }
+        let client = match self.client.as_mut() {
+            Some(client) => client,
+            None => self.client.insert(connect(client_id)?),
+        };
+        self.client_id = Some(client_id.to_string());
+        client.set_activity(activity)
+    }
+}
+
+/// App state wrapper for the Discord connection.
+#[derive(Default)]
+pub struct DiscordPresenceState(pub Mutex<DiscordPresence>);
+
+/// Show or clear the Discord status to match the focus session. Called after
+/// sessions start or end and on every scheduler tick.
+pub fn sync_discord_presence(app: &AppHandle) {
+    let (Some(db), Some(focus_manager), Some(presence)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<FocusManager>>(),
+        app.try_state::<DiscordPresenceState>(),
+    ) else {
+        return;
+    };
+    let settings = match with_connection(&db, DiscordPresenceSettings::load) {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to load Discord presence settings: {e}");
+            return;
+        }
+    };
+    let Ok(mut presence) = presence.0.lock() else {
+        return;
+    };
+
+    let state = match focus_manager.get_state() {
+        Ok(state) => Some(state).filter(|s| s.active && settings.enabled),
+        Err(e) => {
+            error!("Failed to get focus state for Discord presence: {e}");
+            return;
+        }
+    };
+    let wanted = state.as_ref().map(status_text);
+    if wanted == presence.shown {
+        return;
+    }
+    let Some(client_id) = settings.client_id.as_deref() else {
+        return;
+    };
+
+    let activity = state.as_ref().map(|s| activity(s, current_timestamp()));
+    match presence.show(client_id, activity.as_ref()) {
+        Ok(()) => presence.shown = wanted,
+        Err(e) => {
+            // Usually just Discord not running; try again next time
+            debug!("Failed to update Discord presence: {e}");
+            presence.client = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use std::io::Cursor;
+
+    fn focus_state(time_remaining_secs: Option<i64>) -> FocusState {
+        FocusState {
+            active: true,
+            budget_remaining: 300,
+            blocked_domains: Vec::new(),
+            session_duration_secs: Some(600),
+            time_remaining_secs,
+            category_budgets: Vec::new(),
+            allowed_domains: Vec::new(),
+            blocked_categories: Vec::new(),
+            hard_blocked_domains: Vec::new(),
+            soft_limited_domains: Vec::new(),
+            budget_allowed_domains: Vec::new(),
+            blocklist_version: 0,
+        }
+    }
+
+    /// In-memory stream: reads scripted replies, records what was written.
+    struct Scripted {
+        replies: Cursor<Vec<u8>>,
+        written: Vec<u8>,
+    }
+
+    impl Read for Scripted {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.replies.read(buf)
+        }
+    }
+
+    impl Write for Scripted {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.written.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn scripted(replies: &[(u32, Value)]) -> Scripted {
+        let mut bytes = Vec::new();
+        for (op, reply) in replies {
+            write_frame(&mut bytes, *op, reply).unwrap();
+        }
+        Scripted {
+            replies: Cursor::new(bytes),
+            written: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_status_text() {
+        assert_eq!(
+            status_text(&focus_state(Some(31 * 60 + 5))),
+            "In focus \u{2014} 32 min left"
+        );
+        assert_eq!(status_text(&focus_state(None)), "In focus");
+    }
+
+    #[test]
+    fn test_activity_timestamps() {
+        assert_eq!(
+            activity(&focus_state(Some(120)), 1000).get("timestamps"),
+            Some(&json!({ "end": 1120 }))
+        );
+        assert_eq!(
+            activity(&focus_state(None), 1000).get("timestamps"),
+            Some(&json!({ "start": 400 }))
+        );
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, OP_FRAME, &json!({ "cmd": "SET_ACTIVITY" })).unwrap();
+        assert_eq!(bytes.get(..4), Some(&1u32.to_le_bytes()[..]));
+        let (op, payload) = read_frame(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(op, OP_FRAME);
+        assert_eq!(payload, json!({ "cmd": "SET_ACTIVITY" }));
+    }
+
+    #[test]
+    fn test_handshake_and_set_activity() {
+        let stream = scripted(&[
+            (OP_FRAME, json!({ "cmd": "DISPATCH", "evt": "READY" })),
+            (OP_FRAME, json!({ "cmd": "SET_ACTIVITY", "evt": null })),
+        ]);
+        let mut client = IpcClient::handshake(stream, "1234").unwrap();
+        client.set_activity(None).unwrap();
+
+        let mut written = Cursor::new(client.stream.written);
+        let (op, handshake) = read_frame(&mut written).unwrap();
+        assert_eq!(op, OP_HANDSHAKE);
+        assert_eq!(handshake.get("client_id"), Some(&json!("1234")));
+        let (_, frame) = read_frame(&mut written).unwrap();
+        assert_eq!(frame.pointer("/args/activity"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn test_rejected_handshake() {
+        let stream = scripted(&[(
+            OP_CLOSE,
+            json!({ "code": 4000, "message": "Invalid Client ID" }),
+        )]);
+        let err = IpcClient::handshake(stream, "bad").err().unwrap();
+        assert!(err.to_string().contains("Invalid Client ID"));
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let settings = DiscordPresenceSettings::load(conn).unwrap();
+        assert!(!settings.enabled);
+        assert_eq!(settings.client_id, None);
+
+        let updated = DiscordPresenceSettings {
+            enabled: true,
+            client_id: Some("1234".to_string()),
+        };
+        updated.save(conn).unwrap();
+        assert_eq!(DiscordPresenceSettings::load(conn).unwrap(), updated);
+    }
+}
@@ -0,0 +1,412 @@
+//! The weekly report by email on a set day and time, for anyone who'd rather
+//! read it in their inbox than open the app. Mail goes out over SMTP with
+//! TLS through `curl`; the SMTP password lives in the keychain (see
+//! `secrets`), never in the settings table.
+
+use crate::accountability::DEFAULT_REPORT_DAY;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::Setting;
+use crate::notifications::notify;
+use crate::reports::WeeklyReport;
+use crate::secrets;
+use chrono::{Datelike, Local, NaiveDate};
+use log::{error, info, warn};
+use rusqlite::{Connection, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+const ENABLED_SETTING: &str = "email_report.enabled";
+const SMTP_HOST_SETTING: &str = "email_report.smtp_host";
+const SMTP_PORT_SETTING: &str = "email_report.smtp_port";
+const USERNAME_SETTING: &str = "email_report.username";
+const FROM_SETTING: &str = "email_report.from";
+const TO_SETTING: &str = "email_report.to";
+const DAY_SETTING: &str = "email_report.day";
+const TIME_SETTING: &str = "email_report.time";
+/// Local date the last report was emailed, so restarts don't resend it.
+const LAST_SENT_SETTING: &str = "email_report.last_sent";
+/// When the report was last handed to the mail server, so a failed send is
+/// retried after a while rather than on every tick.
+const LAST_ATTEMPT_SETTING: &str = "email_report.last_attempt";
+/// Why the last scheduled send failed, until one succeeds.
+const LAST_ERROR_SETTING: &str = "email_report.last_error";
+
+/// Keychain key of the SMTP password.
+pub const PASSWORD_SECRET: &str = "email_report.password";
+
+/// Submission port, upgraded to TLS with STARTTLS.
+pub const DEFAULT_SMTP_PORT: u16 = 587;
+
+/// Port that speaks TLS from the start.
+const IMPLICIT_TLS_PORT: u16 = 465;
+
+/// Local time the report is emailed when unconfigured.
+pub const DEFAULT_REPORT_TIME: &str = "08:00";
+
+/// Seconds before giving up on a mail server that doesn't answer.
+const SEND_TIMEOUT_SECS: &str = "60";
+
+/// Seconds between attempts while sending keeps failing on the report day.
+/// Longer than a send can take, so an attempt never overlaps the last one.
+const RETRY_INTERVAL_SECS: i64 = 3600;
+
+/// Where and when the weekly report is emailed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailReportSettings {
+    pub enabled: bool,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    /// SMTP login; mail is sent without logging in when unset.
+    pub username: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Weekday the report goes out (1=Monday, 7=Sunday).
+    pub day: u32,
+    /// Local time of day in HH:MM format.
+    pub time: String,
+}
+
+impl EmailReportSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            enabled: Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"),
+            smtp_host: Setting::get(conn, SMTP_HOST_SETTING)?,
+            smtp_port: Setting::get(conn, SMTP_PORT_SETTING)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_SMTP_PORT),
+            username: Setting::get(conn, USERNAME_SETTING)?,
+            from: Setting::get(conn, FROM_SETTING)?,
+            to: Setting::get(conn, TO_SETTING)?,
+            day: Setting::get(conn, DAY_SETTING)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_REPORT_DAY),
+            time: Setting::get(conn, TIME_SETTING)?
+                .unwrap_or_else(|| DEFAULT_REPORT_TIME.to_string()),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, SMTP_PORT_SETTING, &self.smtp_port.to_string())?;
+        Setting::set(conn, DAY_SETTING, &self.day.to_string())?;
+        Setting::set(conn, TIME_SETTING, &self.time)?;
+        for (key, value) in [
+            (SMTP_HOST_SETTING, &self.smtp_host),
+            (USERNAME_SETTING, &self.username),
+            (FROM_SETTING, &self.from),
+            (TO_SETTING, &self.to),
+        ] {
+            match value {
+                Some(value) => Setting::set(conn, key, value)?,
+                None => Setting::delete(conn, key)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the report should go out now: on the configured weekday, once
+    /// the configured time has passed, and not already today.
+    fn is_due(&self, last_sent: Option<&str>, today: &str, weekday: u32, time: &str) -> bool {
+        self.enabled
+            && self.smtp_host.is_some()
+            && weekday == self.day
+            && time >= self.time.as_str()
+            && last_sent != Some(today)
+    }
+
+    fn url(&self, host: &str) -> String {
+        let scheme = if self.smtp_port == IMPLICIT_TLS_PORT {
+            "smtps"
+        } else {
+            "smtp"
+        };
+        format!("{scheme}://{host}:{}", self.smtp_port)
+    }
+}
+
+/// The report as a plain-text email.
+fn message(from: &str, to: &str, report: &WeeklyReport, date: &str) -> String {
+    format!(
+        "From: {from}\r\n\
+         To: {to}\r\n\
+         Subject: Foxus weekly report, {} to {}\r\n\
+         Date: {date}\r\n\
+         MIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\n\
+         Content-Transfer-Encoding: 8bit\r\n\
+         \r\n\
+         {}\r\n",
+        report.start_date,
+        report.end_date,
+        report.to_text().replace('\n', "\r\n"),
+    )
+}
+
+/// A value quoted for a curl config file.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Email `report` with the configured server, logging in with the password
+/// from the keychain.
+pub fn send_report(settings: &EmailReportSettings, report: &WeeklyReport) -> Result<(), AppError> {
+    let (Some(host), Some(from), Some(to)) = (
+        settings.smtp_host.as_deref(),
+        settings.from.as_deref(),
+        settings.to.as_deref(),
+    ) else {
+        return Err(AppError::InvalidInput {
+            field: "smtp_host",
+            reason: "email reports are not configured".into(),
+        });
+    };
+    let login = match settings.username.as_deref() {
+        Some(username) => {
+            let password = secrets::get(PASSWORD_SECRET)?.unwrap_or_default();
+            Some(format!(
+                "user = {}\n",
+                quoted(&format!("{username}:{password}"))
+            ))
+        }
+        None => None,
+    };
+    let message = message(from, to, report, &Local::now().to_rfc2822());
+
+    // Created only readable by us, under a name nobody can guess ahead of
+    // time, and removed when dropped
+    let unwritable =
+        |e: std::io::Error| AppError::Internal(format!("failed to write report email: {e}"));
+    let mut file = tempfile::Builder::new()
+        .prefix("foxus-report-")
+        .suffix(".eml")
+        .tempfile()
+        .map_err(unwritable)?;
+    file.write_all(message.as_bytes()).map_err(unwritable)?;
+    curl(&settings.url(host), from, to, file.path(), login.as_deref())
+}
+
+/// Upload the email at `path` over SMTP. The login goes in on stdin as
+/// curl config, so the password never shows up in the process list.
+fn curl(url: &str, from: &str, to: &str, path: &Path, login: Option<&str>) -> Result<(), AppError> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--ssl-reqd"])
+        .args(["--max-time", SEND_TIMEOUT_SECS])
+        .args(["--url", url, "--mail-from", from, "--mail-rcpt", to])
+        .arg("--upload-file")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if login.is_some() {
+        command.args(["--config", "-"]);
+    }
+
+    let unavailable = |e: std::io::Error| AppError::Internal(format!("failed to run curl: {e}"));
+    let mut child = command.spawn().map_err(unavailable)?;
+    if let (Some(mut stdin), Some(login)) = (child.stdin.take(), login) {
+        stdin.write_all(login.as_bytes()).map_err(unavailable)?;
+    }
+    let output = child.wait_with_output().map_err(unavailable)?;
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "failed to send email: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The report for the seven days before `today`.
+fn last_week(conn: &Connection, today: NaiveDate) -> Result<WeeklyReport> {
+    WeeklyReport::compile(conn, today.pred_opt().unwrap_or(today))
+}
+
+/// Email last week's report right away, whatever the day.
+pub fn send_now(db: &Arc<Mutex<Database>>) -> Result<(), AppError> {
+    let today = Local::now().date_naive();
+    let (settings, report) = with_connection(db, |conn| {
+        Ok((EmailReportSettings::load(conn)?, last_week(conn, today)?))
+    })?;
+    send_report(&settings, &report)
+}
+
+/// Why the last scheduled send failed, if it did.
+pub fn last_error(conn: &Connection) -> Result<Option<String>> {
+    Setting::get(conn, LAST_ERROR_SETTING)
+}
+
+/// Email last week's report on the configured weekday once the configured
+/// time has passed, once per day. A failed send is retried hourly for the
+/// rest of the day.
+pub fn check_email_report(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let (today, time, _) = local_today();
+    let today_date = Local::now().date_naive();
+    let weekday = today_date.weekday().number_from_monday();
+    let now = current_timestamp();
+
+    let compiled = with_connection(&db, |conn| {
+        let settings = EmailReportSettings::load(conn)?;
+        let last_sent = Setting::get(conn, LAST_SENT_SETTING)?;
+        if !settings.is_due(last_sent.as_deref(), &today, weekday, &time) {
+            return Ok(None);
+        }
+        let last_attempt: Option<i64> =
+            Setting::get(conn, LAST_ATTEMPT_SETTING)?.and_then(|v| v.parse().ok());
+        if last_attempt.is_some_and(|at| now - at < RETRY_INTERVAL_SECS) {
+            return Ok(None);
+        }
+        Setting::set(conn, LAST_ATTEMPT_SETTING, &now.to_string())?;
+        Ok(Some((settings, last_week(conn, today_date)?)))
+    });
+
+    match compiled {
+        Ok(Some((settings, report))) => dispatch(app.clone(), today, settings, report),
+        Ok(None) => {}
+        Err(e) => error!("Failed to compile weekly email report: {e}"),
+    }
+}
+
+/// Note how a scheduled send went: a sent report marks the day done, a
+/// failure is kept for the settings screen. Returns whether the failure is
+/// new, so the user is told once rather than on every retry.
+fn record_outcome(conn: &Connection, today: &str, outcome: &Result<(), AppError>) -> Result<bool> {
+    match outcome {
+        Ok(()) => {
+            Setting::set(conn, LAST_SENT_SETTING, today)?;
+            Setting::delete(conn, LAST_ERROR_SETTING)?;
+            Ok(false)
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let new = last_error(conn)?.as_deref() != Some(message.as_str());
+            Setting::set(conn, LAST_ERROR_SETTING, &message)?;
+            Ok(new)
+        }
+    }
+}
+
+/// Send in the background so a slow mail server never holds up other jobs.
+fn dispatch(app: AppHandle, today: String, settings: EmailReportSettings, report: WeeklyReport) {
+    thread::spawn(move || {
+        let outcome = send_report(&settings, &report);
+        match &outcome {
+            Ok(()) => info!("Emailed weekly report"),
+            Err(e) => warn!("Failed to email weekly report: {e}"),
+        }
+        let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+            return;
+        };
+        match with_connection(&db, |conn| record_outcome(conn, &today, &outcome)) {
+            Ok(true) => notify(
+                &app,
+                "Weekly report not emailed",
+                "Sending failed, so Foxus will try again in an hour. Check the email settings.",
+            ),
+            Ok(false) => {}
+            Err(e) => error!("Failed to record weekly report email: {e}"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn settings() -> EmailReportSettings {
+        EmailReportSettings {
+            enabled: true,
+            smtp_host: Some("smtp.example.com".into()),
+            smtp_port: DEFAULT_SMTP_PORT,
+            username: Some("me@example.com".into()),
+            from: Some("me@example.com".into()),
+            to: Some("me@example.com".into()),
+            day: 1,
+            time: "08:00".into(),
+        }
+    }
+
+    #[test]
+    fn test_settings_default_and_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let defaults = EmailReportSettings::load(conn).unwrap();
+        assert!(!defaults.enabled);
+        assert_eq!(defaults.smtp_port, DEFAULT_SMTP_PORT);
+        assert_eq!(defaults.time, DEFAULT_REPORT_TIME);
+
+        let mut settings = settings();
+        settings.save(conn).unwrap();
+        assert_eq!(EmailReportSettings::load(conn).unwrap(), settings);
+
+        settings.username = None;
+        settings.save(conn).unwrap();
+        assert_eq!(EmailReportSettings::load(conn).unwrap().username, None);
+    }
+
+    #[test]
+    fn test_is_due_on_the_day_after_the_time_once() {
+        let settings = settings();
+        assert!(settings.is_due(None, "2026-10-12", 1, "08:00"));
+        assert!(!settings.is_due(None, "2026-10-12", 1, "07:59"));
+        assert!(!settings.is_due(None, "2026-10-13", 2, "08:00"));
+        assert!(!settings.is_due(Some("2026-10-12"), "2026-10-12", 1, "09:00"));
+
+        let unconfigured = EmailReportSettings {
+            smtp_host: None,
+            ..settings
+        };
+        assert!(!unconfigured.is_due(None, "2026-10-12", 1, "08:00"));
+    }
+
+    #[test]
+    fn test_only_a_sent_report_marks_the_day_done() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let failed = Err(AppError::Internal(
+            "failed to send email: login denied".into(),
+        ));
+
+        assert!(record_outcome(conn, "2026-10-12", &failed).unwrap());
+        assert!(!record_outcome(conn, "2026-10-12", &failed).unwrap());
+        assert_eq!(Setting::get(conn, LAST_SENT_SETTING).unwrap(), None);
+        assert!(last_error(conn).unwrap().unwrap().contains("login denied"));
+
+        assert!(!record_outcome(conn, "2026-10-12", &Ok(())).unwrap());
+        assert_eq!(
+            Setting::get(conn, LAST_SENT_SETTING).unwrap().as_deref(),
+            Some("2026-10-12")
+        );
+        assert_eq!(last_error(conn).unwrap(), None);
+    }
+
+    #[test]
+    fn test_message_and_login() {
+        let (db, _dir) = setup_test_db();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let report = last_week(db.connection(), today).unwrap();
+
+        let message = message("me@example.com", "me@example.com", &report, "date");
+        assert!(message.contains("Subject: Foxus weekly report, 2026-10-05 to 2026-10-11\r\n"));
+        assert!(message.contains("\r\n\r\nFoxus weekly report"));
+        assert!(!message.replace("\r\n", "").contains('\n'));
+
+        assert_eq!(
+            settings().url("smtp.example.com"),
+            "smtp://smtp.example.com:587"
+        );
+        assert_eq!(quoted(r#"me:pa"ss\"#), r#""me:pa\"ss\\""#);
+    }
+}
@@ -15,6 +15,13 @@ pub enum AppError {
     #[error("Cannot delete: {reason}")]
     DeleteFailed { reason: String },
 
+    /// The row changed since the caller read it; the UI should reload it.
+    #[error("Conflict: {entity} was changed elsewhere (now version {current_version})")]
+    Conflict {
+        entity: &'static str,
+        current_version: i64,
+    },
+
     #[error("Database error: {0}")]
     Database(#[from] rusqlite::Error),
 
@@ -37,8 +44,3 @@ pub fn is_unique_violation(e: &rusqlite::Error) -> bool {
     matches!(e, rusqlite::Error::SqliteFailure(err, _)
         if err.code == rusqlite::ffi::ErrorCode::ConstraintViolation)
 }
-
-/// Check if a rusqlite error is a FOREIGN KEY constraint violation
-pub fn is_fk_violation(e: &rusqlite::Error) -> bool {
-    e.to_string().contains("FOREIGN KEY constraint failed")
-}
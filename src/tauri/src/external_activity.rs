@@ -0,0 +1,230 @@
+use crate::categorizer::Categorizer;
+use crate::constants::MAX_EDITOR_FIELD_LEN;
+use crate::error::AppError;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, EditorActivity};
+use crate::tracking_hours::is_tracking_time;
+use crate::validation::{validate_external_duration, validate_source_label};
+use rusqlite::Connection;
+use serde::Deserialize;
+
+/// Prefix on `activities.source` for time reported by other tools, followed
+/// by the tool's label (e.g. `external:terminal`).
+pub const EXTERNAL_SOURCE_PREFIX: &str = "external:";
+
+/// Source label for editor plugins. Their time goes to `editor_activities`
+/// for coding stats instead of `activities`, where the editor window is
+/// already tracked.
+pub const EDITOR_SOURCE: &str = "editor";
+
+/// Time reported by another tool: a terminal tracker, editor plugin, phone
+/// shortcut, and so on. Accepted by the `record_external_activity` command
+/// and the native host's `external_activity` message.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalActivity {
+    /// Short label for the reporting tool, e.g. `terminal` or `vscode`.
+    pub source: String,
+    /// What the time was spent in; matched against app rules like an app name.
+    pub name: String,
+    /// Optional detail matched against title rules.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Unix seconds when the time started; defaults to `duration_secs` ago.
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    pub duration_secs: i32,
+    /// Workspace or repository; editor source only.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// File being edited; editor source only.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Language id such as `rust`; editor source only.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Validate, categorize, and store an external activity. Returns the new
+/// row's id.
+pub fn record(
+    conn: &Connection,
+    categorizer: &Categorizer,
+    external: &ExternalActivity,
+    now: i64,
+) -> Result<i64, AppError> {
+    let label = validate_source_label(&external.source)?;
+    validate_external_duration(external.duration_secs)?;
+    let name = external.name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput {
+            field: "name",
+            reason: "cannot be empty".into(),
+        });
+    }
+    let timestamp = external
+        .timestamp
+        .unwrap_or(now - i64::from(external.duration_secs));
+    if timestamp + i64::from(external.duration_secs) > now {
+        return Err(AppError::InvalidInput {
+            field: "timestamp",
+            reason: "activity cannot end in the future".into(),
+        });
+    }
+
+    if label == EDITOR_SOURCE {
+        let mut activity = EditorActivity::new(timestamp, external.duration_secs, name);
+        activity.project = editor_field(external.project.as_deref());
+        activity.file = editor_field(external.file.as_deref());
+        activity.language = editor_field(external.language.as_deref()).map(|l| l.to_lowercase());
+        activity.save(conn)?;
+        return Ok(activity.id.unwrap_or_default());
+    }
+
+    let title = external.title.as_deref().map(str::trim);
+    let mut activity = Activity::new(
+        timestamp,
+        external.duration_secs,
+        &format!("{EXTERNAL_SOURCE_PREFIX}{label}"),
+        Some(name),
+        title,
+    );
+    activity.category_id = Some(categorizer.categorize_app(name, title));
+    activity.save(conn)?;
+    Ok(activity.id.unwrap_or_default())
+}
+
+/// Trimmed editor metadata capped at `MAX_EDITOR_FIELD_LEN` characters;
+/// `None` when blank.
+fn editor_field(value: Option<&str>) -> Option<String> {
+    let value = value?.trim();
+    (!value.is_empty()).then(|| value.chars().take(MAX_EDITOR_FIELD_LEN).collect())
+}
+
+/// `record` at the current time. Refused outside the tracking hours or
+/// while away, so the reporting tool knows the time wasn't kept.
+pub fn record_now(
+    conn: &Connection,
+    categorizer: &Categorizer,
+    external: &ExternalActivity,
+) -> Result<i64, AppError> {
+    if !is_tracking_time(conn)? {
+        return Err(AppError::InvalidInput {
+            field: "timestamp",
+            reason: "nothing is recorded outside the tracking hours or while away".into(),
+        });
+    }
+    record(conn, categorizer, external, current_timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::away_mode::AwayPeriod;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn external(source: &str, name: &str, duration_secs: i32) -> ExternalActivity {
+        ExternalActivity {
+            source: source.to_string(),
+            name: name.to_string(),
+            title: None,
+            timestamp: None,
+            duration_secs,
+            project: None,
+            file: None,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_records_and_categorizes() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let categorizer = Categorizer::new(conn).unwrap();
+
+        record(conn, &categorizer, &external("Terminal", "code", 600), NOW).unwrap();
+        let saved = Activity::find_in_range(conn, NOW - 1000, NOW).unwrap();
+        assert_eq!(saved.len(), 1);
+        let activity = saved.first().unwrap();
+        assert_eq!(activity.source, "external:terminal");
+        assert_eq!(activity.timestamp, NOW - 600);
+
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        assert_eq!(activity.category_id, Some(coding.id));
+    }
+
+    #[test]
+    fn test_editor_source_records_coding_detail() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let categorizer = Categorizer::new(conn).unwrap();
+
+        let editor = ExternalActivity {
+            project: Some(" foxus ".to_string()),
+            file: Some("src/main.rs".to_string()),
+            language: Some("Rust".to_string()),
+            ..external("editor", "vscode", 300)
+        };
+        record(conn, &categorizer, &editor, NOW).unwrap();
+
+        // The editor window is already tracked, so no activity is added
+        assert!(Activity::find_in_range(conn, NOW - 1000, NOW)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            EditorActivity::language_totals(conn, NOW - 1000, NOW).unwrap(),
+            vec![(Some("rust".to_string()), 300)]
+        );
+        assert_eq!(
+            EditorActivity::project_totals(conn, NOW - 1000, NOW).unwrap(),
+            vec![(Some("foxus".to_string()), 300)]
+        );
+    }
+
+    #[test]
+    fn test_editor_field() {
+        assert_eq!(editor_field(Some("  ")), None);
+        assert_eq!(editor_field(None), None);
+        let long = "a".repeat(MAX_EDITOR_FIELD_LEN + 10);
+        assert_eq!(
+            editor_field(Some(&long)).map(|f| f.len()),
+            Some(MAX_EDITOR_FIELD_LEN)
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_activity() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let categorizer = Categorizer::new(conn).unwrap();
+
+        let rejected = |e: &ExternalActivity| record(conn, &categorizer, e, NOW).is_err();
+        assert!(rejected(&external("browser", "code", 60)));
+        assert!(rejected(&external("my tool", "code", 60)));
+        assert!(rejected(&external("terminal", "  ", 60)));
+        assert!(rejected(&external("terminal", "code", 0)));
+        assert!(rejected(&ExternalActivity {
+            timestamp: Some(NOW),
+            ..external("terminal", "code", 60)
+        }));
+    }
+
+    #[test]
+    fn test_record_now_refused_while_away() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let categorizer = Categorizer::new(conn).unwrap();
+        AwayPeriod::start(conn, "2000-01-01", "2999-01-01").unwrap();
+
+        assert!(record_now(conn, &categorizer, &external("terminal", "vim", 60)).is_err());
+        assert!(Activity::find_in_range(conn, 0, i64::MAX)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,12 +1,165 @@
+use crate::audit_log::{self, AuditAction, Origin};
+use crate::away_mode::is_away_at;
+use crate::budget_accrual::AccrualSettings;
 use crate::db::Database;
-use crate::models::{FocusSchedule, FocusSession};
+use crate::error::AppError;
+use crate::local_time::{date_in, day_and_time_in, wall_time_at};
+use crate::models::focus_session::current_timestamp;
+use crate::models::{
+    Activity, BlockedAttempt, Category, CategoryBudget, DistractionEvent, DomainAllow,
+    FocusSchedule, FocusSession, SessionBlock, SessionBlockedCategory, SessionException, Setting,
+};
+use chrono::{Local, TimeZone};
 use log::{info, warn};
-use rusqlite::Connection;
+use rusqlite::{params, Connection};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Minimum interval between `use_distraction_time` calls (rate limiting).
-const DISTRACTION_TIME_RATE_LIMIT: Duration = Duration::from_secs(25);
+/// Minimum seconds between `use_distraction_time` calls for one session (rate limiting).
+const DISTRACTION_TIME_RATE_LIMIT_SECS: i64 = 25;
+
+/// How long cached focus state is trusted. Writes through this manager clear
+/// the cache right away; the TTL only bounds how long changes made by another
+/// process (the native host, or the app seen from the host) go unnoticed.
+const STATE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// How long the tracker can be silent before an open-ended session left
+/// active is treated as cut off by a crash or shutdown.
+const STALE_SESSION_SECS: i64 = 30 * 60;
+
+/// A scheduled start more than this late means the app wasn't running for it
+/// (normal checks happen every minute).
+const MISSED_START_GRACE_SECS: i64 = 2 * 60;
+
+/// A schedule window that opened while the app (or machine) was off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissedStart {
+    /// Scheduled start in HH:MM format.
+    pub start_time: String,
+    pub late_secs: i64,
+}
+
+/// Typed back to confirm an emergency unlock; long enough to break the impulse.
+pub const EMERGENCY_UNLOCK_PHRASE: &str =
+    "I am choosing to break my focus commitment and accept the consequences";
+
+const UNLOCK_DELAY_SETTING: &str = "emergency_unlock.delay_minutes";
+pub const DEFAULT_UNLOCK_DELAY_MINUTES: i32 = 5;
+
+pub fn unlock_delay_minutes(conn: &Connection) -> rusqlite::Result<i32> {
+    Ok(Setting::get(conn, UNLOCK_DELAY_SETTING)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_UNLOCK_DELAY_MINUTES))
+}
+
+pub fn set_unlock_delay_minutes(conn: &Connection, minutes: i32) -> rusqlite::Result<()> {
+    Setting::set(conn, UNLOCK_DELAY_SETTING, &minutes.to_string())
+}
+
+const DISTRACTION_GRANT_SETTING: &str = "focus.distraction_grant_secs";
+pub const DEFAULT_DISTRACTION_GRANT_SECS: i32 = 30;
+
+/// Budget seconds drawn each time the extension lets the user through.
+pub fn distraction_grant_secs(conn: &Connection) -> rusqlite::Result<i32> {
+    Ok(Setting::get(conn, DISTRACTION_GRANT_SETTING)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_DISTRACTION_GRANT_SECS))
+}
+
+pub fn set_distraction_grant_secs(conn: &Connection, secs: i32) -> rusqlite::Result<()> {
+    Setting::set(conn, DISTRACTION_GRANT_SETTING, &secs.to_string())
+}
+
+/// The grant for a request: the configured size unless the extension asked
+/// for less. Requests can't exceed the configured size.
+pub fn capped_grant(requested: Option<i32>, configured: i32) -> i32 {
+    requested.map_or(configured, |secs| secs.clamp(1, configured))
+}
+
+/// Outcome of an emergency unlock attempt.
+#[derive(Debug, Clone)]
+pub enum UnlockStatus {
+    /// The phrase was accepted; try again once the delay has passed.
+    Waiting { remaining_secs: i64 },
+    /// The session was ended.
+    Unlocked(FocusSession),
+}
+
+/// Log `action` on `session` in the audit log.
+fn audit(
+    conn: &Connection,
+    now: i64,
+    action: AuditAction,
+    origin: Origin,
+    session: &FocusSession,
+    detail: Option<&str>,
+) -> rusqlite::Result<()> {
+    audit_log::record(conn, now, action, origin, session.id, detail)
+}
+
+/// Audit detail for a new session, e.g. "planned 25 min, strict".
+fn start_detail(session: &FocusSession) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(planned) = session.planned_duration {
+        parts.push(format!("planned {} min", planned / 60));
+    }
+    if session.strict {
+        parts.push("strict".to_string());
+    }
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+fn session_locked_error() -> AppError {
+    AppError::InvalidInput {
+        field: "session",
+        reason: "a strict session can only be ended early with an emergency unlock".into(),
+    }
+}
+
+/// Whether a blocked-domain pattern covers `domain` or one of its parents.
+fn pattern_blocks(pattern: &str, domain: &str) -> bool {
+    let pattern = pattern.trim_start_matches("*.");
+    domain == pattern
+        || domain
+            .strip_suffix(pattern)
+            .is_some_and(|sub| sub.ends_with('.'))
+}
+
+/// Split blocked patterns into (hard blocked, soft limited).
+///
+/// A pattern is soft limited while it belongs to a distracting category and
+/// both the session budget and that category's own budget have time left.
+fn split_by_severity(
+    blocked_domains: &[String],
+    budgeted_domains: &[(String, i64)],
+    budget_remaining: i32,
+    category_budgets: &[CategoryBudget],
+) -> (Vec<String>, Vec<String>) {
+    let spendable = |pattern: &String| {
+        budget_remaining > 0
+            && budgeted_domains
+                .iter()
+                .filter(|(budgeted, _)| budgeted == pattern)
+                .any(|(_, category_id)| {
+                    category_budgets
+                        .iter()
+                        .find(|b| b.category_id == *category_id)
+                        .is_none_or(|b| b.remaining() > 0)
+                })
+    };
+    let (soft, hard): (Vec<String>, Vec<String>) =
+        blocked_domains.iter().cloned().partition(spendable);
+    (hard, soft)
+}
+
+/// A domain unblocked for part of a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemporaryAllow {
+    pub domain: String,
+    pub expires_at: i64,
+    /// Distraction budget left after paying for the allow.
+    pub budget_remaining: i32,
+}
 
 #[derive(Debug, Clone)]
 pub struct FocusState {
@@ -14,20 +167,165 @@ pub struct FocusState {
     pub budget_remaining: i32,
     pub blocked_domains: Vec<String>,
     pub session_duration_secs: Option<i64>,
+    /// Seconds left of the planned duration; `None` for open-ended sessions.
+    pub time_remaining_secs: Option<i64>,
+    /// Per-category slices of the budget for the active session.
+    pub category_budgets: Vec<CategoryBudget>,
+    /// Domains the active session leaves unblocked despite `blocked_domains`.
+    pub allowed_domains: Vec<String>,
+    /// Categories the active session blocks on top of the distracting ones.
+    pub blocked_categories: Vec<i64>,
+    /// Blocked domains no amount of budget opens: session blocks, categories
+    /// the session blocks, and everything once the budget is used up.
+    pub hard_blocked_domains: Vec<String>,
+    /// Blocked domains the user can still spend distraction budget on.
+    pub soft_limited_domains: Vec<String>,
+    /// Domains currently open because budget was spent on them.
+    pub budget_allowed_domains: Vec<String>,
+    /// Bumped each time `blocked_domains` or `allowed_domains` changes; see
+    /// `blocklist_update`.
+    pub blocklist_version: u64,
+}
+
+/// Changes to the blocked domains since the version the receiver last saw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlocklistUpdate {
+    /// Version the diff applies on top of.
+    pub from_version: u64,
+    pub version: u64,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// The full list of session exceptions, when it changed.
+    pub allowed_domains: Option<Vec<String>>,
+}
+
+/// The last blocked-domain list and exceptions handed out, sorted.
+#[derive(Debug, Default)]
+struct Blocklist {
+    version: u64,
+    domains: Vec<String>,
+    allowed: Vec<String>,
+}
+
+/// Database rows `get_state` is built from.
+#[derive(Debug, Clone)]
+struct CachedState {
+    session: Option<FocusSession>,
+    blocked_domains: Vec<String>,
+    allowed_domains: Vec<String>,
+    blocked_categories: Vec<i64>,
+    /// Blocked patterns from distracting categories, with their category.
+    budgeted_domains: Vec<(String, i64)>,
+    budget_allowed_domains: Vec<String>,
+    category_budgets: Vec<CategoryBudget>,
+    loaded_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct StateCache {
+    /// Bumped on every invalidation, so a load that raced with a write
+    /// doesn't store what it read before the write.
+    generation: u64,
+    entry: Option<CachedState>,
 }
 
 pub struct FocusManager {
     db: Arc<Mutex<Database>>,
-    /// Timestamp of last `use_distraction_time` call for rate limiting.
-    last_distraction_request: Mutex<Option<Instant>>,
+    cache: Mutex<StateCache>,
+    blocklist: Mutex<Blocklist>,
 }
 
 impl FocusManager {
     pub fn new(db: Arc<Mutex<Database>>) -> Self {
         Self {
             db,
-            last_distraction_request: Mutex::new(None),
+            cache: Mutex::new(StateCache::default()),
+            blocklist: Mutex::new(Blocklist::default()),
+        }
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, StateCache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Drop the cached focus state. Call after changing rules or categories,
+    /// which decide the blocked domains.
+    pub fn invalidate_cache(&self) {
+        let mut cache = self.lock_cache();
+        cache.generation += 1;
+        cache.entry = None;
+    }
+
+    /// Lock the database for a change to focus state, clearing the cache.
+    ///
+    /// The cache is cleared while the lock is held, so a concurrent
+    /// `get_state` either finishes loading before the change (and its result
+    /// is discarded) or waits and loads after it.
+    fn lock_db_for_write(&self) -> MutexGuard<'_, Database> {
+        let db = self.lock_db();
+        self.invalidate_cache();
+        db
+    }
+
+    fn cached_state(&self) -> rusqlite::Result<CachedState> {
+        let generation = {
+            let cache = self.lock_cache();
+            if let Some(entry) = cache
+                .entry
+                .as_ref()
+                .filter(|e| e.loaded_at.elapsed() < STATE_CACHE_TTL)
+            {
+                return Ok(entry.clone());
+            }
+            cache.generation
+        };
+
+        let loaded = {
+            let db = self.lock_db();
+            let conn = db.connection();
+            let session = FocusSession::find_active(conn)?;
+            let session_id = session.as_ref().and_then(|s| s.id);
+            let mut blocked_domains = Self::get_blocked_domains(conn, session_id)?;
+            let mut allowed_domains = Vec::new();
+            let mut blocked_categories = Vec::new();
+            let mut budgeted_domains = Vec::new();
+            let mut budget_allowed_domains = Vec::new();
+            let category_budgets = match session_id {
+                Some(id) => {
+                    allowed_domains = SessionException::domains_for_session(conn, id)?;
+                    blocked_categories = SessionBlockedCategory::for_session(conn, id)?;
+                    let session_blocks = SessionBlock::patterns_for_session(conn, id)?;
+                    budgeted_domains = Self::get_budgeted_domains(conn)?;
+                    budgeted_domains.retain(|(pattern, _)| !session_blocks.contains(pattern));
+                    blocked_domains.extend(session_blocks);
+                    // An allow is an exception for its own host, so the
+                    // pattern covering it keeps blocking the rest of the site
+                    budget_allowed_domains =
+                        DomainAllow::active_domains(conn, id, current_timestamp())?;
+                    allowed_domains.extend(budget_allowed_domains.iter().cloned());
+                    CategoryBudget::find_for_session(conn, id)?
+                }
+                None => Vec::new(),
+            };
+            CachedState {
+                session,
+                blocked_domains,
+                allowed_domains,
+                blocked_categories,
+                budgeted_domains,
+                budget_allowed_domains,
+                category_budgets,
+                loaded_at: Instant::now(),
+            }
+        };
+
+        let mut cache = self.lock_cache();
+        if cache.generation == generation {
+            cache.entry = Some(loaded.clone());
         }
+        Ok(loaded)
     }
 
     fn lock_db(&self) -> MutexGuard<'_, Database> {
@@ -40,30 +338,354 @@ impl FocusManager {
         }
     }
 
-    pub fn start_session(&self, distraction_budget_secs: i32) -> rusqlite::Result<FocusSession> {
-        let db = self.lock_db();
+    pub fn start_session(
+        &self,
+        distraction_budget_secs: i32,
+        origin: Origin,
+    ) -> Result<FocusSession, AppError> {
+        self.start_timed_session(distraction_budget_secs, None, false, &[], origin)
+    }
+
+    /// Start a manual focus session that is planned to last `planned_duration_secs`.
+    ///
+    /// A `strict` session is a commitment to the full duration; ending it early
+    /// is reported as abandoning it. `category_budgets` reserves part of the
+    /// budget per category as `(category_id, secs)`; each is also capped by the
+    /// overall budget.
+    pub fn start_timed_session(
+        &self,
+        distraction_budget_secs: i32,
+        planned_duration_secs: Option<i32>,
+        strict: bool,
+        category_budgets: &[(i64, i32)],
+        origin: Origin,
+    ) -> Result<FocusSession, AppError> {
+        self.start_scoped_session(
+            distraction_budget_secs,
+            planned_duration_secs,
+            strict,
+            category_budgets,
+            &[],
+            &[],
+            origin,
+        )
+    }
+
+    /// `start_timed_session` that also sets the session's exceptions and extra
+    /// blocked categories, which a strict session refuses once it is running.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Session settings fixed at start, mirroring start_focus_session"
+    )]
+    pub fn start_scoped_session(
+        &self,
+        distraction_budget_secs: i32,
+        planned_duration_secs: Option<i32>,
+        strict: bool,
+        category_budgets: &[(i64, i32)],
+        exceptions: &[String],
+        blocked_categories: &[i64],
+        origin: Origin,
+    ) -> Result<FocusSession, AppError> {
+        let db = self.lock_db_for_write();
         let conn = db.connection();
+        let now = current_timestamp();
 
-        // End any existing active session
+        // End any existing active session, unless it's a strict one still running
         if let Some(mut existing) = FocusSession::find_active(conn)? {
+            if existing.is_locked(now) {
+                return Err(session_locked_error());
+            }
             existing.end(conn)?;
+            audit(
+                conn,
+                now,
+                AuditAction::End,
+                origin,
+                &existing,
+                Some("replaced by a new session"),
+            )?;
         }
 
         let mut session = FocusSession::new(distraction_budget_secs, false);
+        session.planned_duration = planned_duration_secs;
+        session.strict = strict;
         session.save(conn)?;
+        if let Some(id) = session.id {
+            CategoryBudget::set_for_session(conn, id, category_budgets)?;
+            SessionException::set_for_session(conn, id, exceptions)?;
+            SessionBlockedCategory::set_for_session(conn, id, blocked_categories)?;
+        }
+        audit(
+            conn,
+            now,
+            AuditAction::Start,
+            origin,
+            &session,
+            start_detail(&session).as_deref(),
+        )?;
 
         Ok(session)
     }
 
-    pub fn end_session(&self) -> rusqlite::Result<Option<FocusSession>> {
-        let db = self.lock_db();
+    /// Block extra domain patterns for the rest of the active session only.
+    /// Returns how many weren't blocked for it already.
+    pub fn add_session_blocks(&self, patterns: &[&str]) -> Result<usize, AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let session_id = FocusSession::find_active(conn)?
+            .and_then(|session| session.id)
+            .ok_or(AppError::NotFound {
+                entity: "Focus session",
+            })?;
+        let mut added = 0;
+        for pattern in patterns {
+            if SessionBlock::add(conn, session_id, pattern)? {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Replace the active session's exceptions: domains left unblocked for
+    /// the rest of the session whatever the rules say.
+    ///
+    /// Refused while the session is strict or out of budget, since an
+    /// exception would open what it blocks.
+    pub fn set_session_exceptions(&self, domains: &[String]) -> Result<(), AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let session_id = Self::unlocked_session_id(conn, current_timestamp())?;
+        SessionException::set_for_session(conn, session_id, domains)?;
+        Ok(())
+    }
+
+    /// The active session's id, unless it is strict and still running or has
+    /// spent its budget; those can only be loosened by an emergency unlock.
+    fn unlocked_session_id(conn: &Connection, now: i64) -> Result<i64, AppError> {
+        let session = FocusSession::find_active(conn)?.ok_or(AppError::NotFound {
+            entity: "Focus session",
+        })?;
+        if session.is_locked(now) || session.is_budget_exhausted() {
+            return Err(session_locked_error());
+        }
+        Ok(session.saved_id()?)
+    }
+
+    /// Replace the categories the active session blocks on top of the
+    /// distracting ones, e.g. Communication while writing. Stats still score
+    /// them by their productivity.
+    ///
+    /// Refused while the session is strict or out of budget, since dropping
+    /// a category would open what it blocks.
+    pub fn set_session_blocked_categories(&self, category_ids: &[i64]) -> Result<(), AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let session_id = Self::unlocked_session_id(conn, current_timestamp())?;
+        SessionBlockedCategory::set_for_session(conn, session_id, category_ids)?;
+        Ok(())
+    }
+
+    /// Unblock `domain` for up to `secs` of the active session, paid for up
+    /// front from the distraction budget. The window is shortened to what's
+    /// left of the budget (and of `category_id`'s slice, if it has one).
+    pub fn allow_domain_temporarily(
+        &self,
+        domain: &str,
+        secs: i32,
+        category_id: Option<i64>,
+        now: i64,
+    ) -> Result<TemporaryAllow, AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let mut session = FocusSession::find_active(conn)?.ok_or(AppError::NotFound {
+            entity: "Focus session",
+        })?;
+        let session_id = session.saved_id()?;
+        let secs = secs.min(Self::remaining_budget(conn, &session, category_id)?);
+        if secs <= 0 {
+            return Err(AppError::InvalidInput {
+                field: "domain",
+                reason: "no distraction budget left".into(),
+            });
+        }
+
+        session.add_distraction_time(conn, secs)?;
+        DistractionEvent::new(session_id, Some(domain), category_id, secs).save(conn)?;
+        let expires_at = now + i64::from(secs);
+        DomainAllow::grant(conn, session_id, domain, now, expires_at)?;
+        info!("Allowed {domain} for {secs}s of the focus session");
+
+        Ok(TemporaryAllow {
+            domain: domain.to_string(),
+            expires_at,
+            budget_remaining: Self::remaining_budget(conn, &session, category_id)?,
+        })
+    }
+
+    /// Count a visit to the block page for `domain` against the active
+    /// session. Returns false if no session is active.
+    pub fn record_blocked_attempt(&self, domain: &str, now: i64) -> rusqlite::Result<bool> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let Some(session_id) = FocusSession::find_active(conn)?.and_then(|s| s.id) else {
+            return Ok(false);
+        };
+        BlockedAttempt::record(conn, session_id, domain, now)?;
+        Ok(true)
+    }
+
+    /// Push back the end of the active timed session by `secs` and add
+    /// `extra_budget_secs` to its distraction budget. Open-ended sessions
+    /// have no end to push back and are refused.
+    ///
+    /// Returns the updated session, or `None` if no session is active.
+    pub fn extend_focus_session(
+        &self,
+        secs: i32,
+        extra_budget_secs: i32,
+        origin: Origin,
+    ) -> Result<Option<FocusSession>, AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let Some(mut session) = FocusSession::find_active(conn)? else {
+            return Ok(None);
+        };
+        if session.planned_duration.is_none() {
+            return Err(AppError::InvalidInput {
+                field: "session",
+                reason: "an open-ended session has no end to extend".into(),
+            });
+        }
+        session.extend(conn, secs)?;
+        let mut detail = format!("+{} min", secs / 60);
+        if extra_budget_secs > 0 {
+            session.add_budget(conn, extra_budget_secs)?;
+            detail.push_str(&format!(", +{} min budget", extra_budget_secs / 60));
+        }
+        audit(
+            conn,
+            current_timestamp(),
+            AuditAction::Extend,
+            origin,
+            &session,
+            Some(&detail),
+        )?;
+        Ok(Some(session))
+    }
+
+    /// End the active session. Strict sessions refuse until their time is up.
+    pub fn end_session(&self, origin: Origin) -> Result<Option<FocusSession>, AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+        let now = current_timestamp();
+
+        let Some(mut session) = FocusSession::find_active(conn)? else {
+            return Ok(None);
+        };
+        if session.is_locked(now) {
+            return Err(session_locked_error());
+        }
+        session.end(conn)?;
+        audit(conn, now, AuditAction::End, origin, &session, None)?;
+        Ok(Some(session))
+    }
+
+    /// End a strict or hard-blocked session early, with friction.
+    ///
+    /// The first call with the confirmation phrase starts a countdown of the
+    /// configured delay; a call after it has passed ends the session and marks
+    /// it as emergency-unlocked. The countdown is stored on the session, so
+    /// restarting the app doesn't reset or skip it.
+    pub fn emergency_unlock(
+        &self,
+        phrase: &str,
+        now: i64,
+        origin: Origin,
+    ) -> Result<UnlockStatus, AppError> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let mut session = FocusSession::find_active(conn)?.ok_or(AppError::NotFound {
+            entity: "Focus session",
+        })?;
+        if !session.is_locked(now) && !session.is_budget_exhausted() {
+            return Err(AppError::InvalidInput {
+                field: "session",
+                reason: "only strict or hard-blocked sessions need an emergency unlock".into(),
+            });
+        }
+        if phrase.trim() != EMERGENCY_UNLOCK_PHRASE {
+            return Err(AppError::InvalidInput {
+                field: "phrase",
+                reason: "does not match the confirmation phrase".into(),
+            });
+        }
+
+        if session.unlock_requested_at.is_none() {
+            session.request_unlock(conn, now)?;
+            audit(
+                conn,
+                now,
+                AuditAction::UnlockRequested,
+                origin,
+                &session,
+                None,
+            )?;
+        }
+        let delay_secs = i64::from(unlock_delay_minutes(conn)?) * 60;
+        let waited = now - session.unlock_requested_at.unwrap_or(now);
+        if waited < delay_secs {
+            return Ok(UnlockStatus::Waiting {
+                remaining_secs: delay_secs - waited,
+            });
+        }
+
+        info!(
+            "Emergency unlock of focus session {}",
+            session.id.unwrap_or(0)
+        );
+        session.mark_emergency_unlocked(conn)?;
+        session.end(conn)?;
+        audit(
+            conn,
+            now,
+            AuditAction::EmergencyUnlock,
+            origin,
+            &session,
+            None,
+        )?;
+        Ok(UnlockStatus::Unlocked(session))
+    }
+
+    /// End the active session if it is timed and its planned duration has elapsed.
+    ///
+    /// Returns the session that was ended, so callers can follow up (e.g. start a break).
+    pub fn end_expired_session(&self, now: i64) -> rusqlite::Result<Option<FocusSession>> {
+        let db = self.lock_db_for_write();
         let conn = db.connection();
 
-        if let Some(mut session) = FocusSession::find_active(conn)? {
-            session.end(conn)?;
-            Ok(Some(session))
-        } else {
-            Ok(None)
+        match FocusSession::find_active(conn)? {
+            Some(mut session) if session.time_remaining(now) == Some(0) => {
+                info!("Timed focus session {} completed", session.id.unwrap_or(0));
+                session.end(conn)?;
+                audit(
+                    conn,
+                    now,
+                    AuditAction::Complete,
+                    Origin::System,
+                    &session,
+                    None,
+                )?;
+                Ok(Some(session))
+            }
+            Some(_) | None => Ok(None),
         }
     }
 
@@ -76,75 +698,220 @@ impl FocusManager {
         reason = "u64 -> i64 widening cast is safe for timestamps (won't overflow until year 292 billion)"
     )]
     pub fn get_state(&self) -> rusqlite::Result<FocusState> {
-        let db = self.lock_db();
-        let conn = db.connection();
-
-        let session = FocusSession::find_active(conn)?;
-        let blocked_domains = Self::get_blocked_domains(conn)?;
-
-        let (active, budget_remaining, session_duration_secs) = match session {
+        let CachedState {
+            session,
+            blocked_domains,
+            allowed_domains,
+            blocked_categories,
+            budgeted_domains,
+            budget_allowed_domains,
+            category_budgets,
+            loaded_at: _,
+        } = self.cached_state()?;
+
+        let (active, budget_remaining, session_duration_secs, time_remaining_secs) = match session {
             Some(s) => {
                 let now = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .expect("System clock is before Unix epoch")
                     .as_secs() as i64;
                 let duration = (now - s.started_at).max(0);
-                (true, s.budget_remaining(), Some(duration))
+                (
+                    true,
+                    s.budget_remaining(),
+                    Some(duration),
+                    s.time_remaining(now),
+                )
             }
-            None => (false, 0, None),
+            None => (false, 0, None, None),
         };
 
+        let (blocklist_version, _) = self.sync_blocklist(&blocked_domains, &allowed_domains);
+        // Patterns an allow opens in full are listed as budget-allowed only
+        let still_blocked: Vec<String> = blocked_domains
+            .iter()
+            .filter(|pattern| {
+                let host = pattern.trim_start_matches("*.");
+                !budget_allowed_domains
+                    .iter()
+                    .any(|allowed| pattern_blocks(allowed, host))
+            })
+            .cloned()
+            .collect();
+        let (hard_blocked_domains, soft_limited_domains) = split_by_severity(
+            &still_blocked,
+            &budgeted_domains,
+            budget_remaining,
+            &category_budgets,
+        );
+
         Ok(FocusState {
             active,
             budget_remaining,
             blocked_domains,
             session_duration_secs,
+            time_remaining_secs,
+            category_budgets,
+            allowed_domains,
+            blocked_categories,
+            hard_blocked_domains,
+            soft_limited_domains,
+            budget_allowed_domains,
+            blocklist_version,
         })
     }
 
-    /// Use distraction time from the current focus session's budget.
+    /// Credit one tracker sample in `category_id` to the active session and,
+    /// in "earn it" mode, add any budget its productive time has earned.
     ///
-    /// Rate limited to prevent rapid calls from bypassing budget enforcement.
-    /// Returns None if no active session or if rate limited.
-    pub fn use_distraction_time(&self, secs: i32) -> rusqlite::Result<Option<i32>> {
-        // Rate limiting: Check if enough time has passed since last request
-        {
-            let mut last_request = self
-                .last_distraction_request
-                .lock()
-                .unwrap_or_else(std::sync::PoisonError::into_inner);
-            let now = Instant::now();
-
-            if let Some(last) = *last_request {
-                if now.duration_since(last) < DISTRACTION_TIME_RATE_LIMIT {
-                    // Rate limited - return current budget without deducting
-                    let db = self.lock_db();
-                    let conn = db.connection();
-                    if let Some(session) = FocusSession::find_active(conn)? {
-                        return Ok(Some(session.budget_remaining()));
-                    }
-                    return Ok(None);
-                }
-            }
+    /// Returns the budget seconds added.
+    pub fn record_productive_time(&self, category_id: i64, secs: i32) -> rusqlite::Result<i32> {
+        let db = self.lock_db();
+        let conn = db.connection();
+
+        let settings = AccrualSettings::load(conn)?;
+        if !settings.enabled {
+            return Ok(0);
+        }
+        if !Category::find_by_id(conn, category_id)?.is_some_and(|c| c.productivity > 0) {
+            return Ok(0);
+        }
+        let Some((productive_secs, earned_secs)) =
+            FocusSession::record_productive_time(conn, secs)?
+        else {
+            return Ok(0);
+        };
 
-            *last_request = Some(now);
+        let owed = settings.earned_for(productive_secs) - earned_secs;
+        if owed <= 0 {
+            return Ok(0);
         }
+        FocusSession::add_earned_budget(conn, owed)?;
+        self.invalidate_cache();
+        Ok(owed)
+    }
 
-        let db = self.lock_db();
+    /// Use distraction time from the current focus session's budget.
+    ///
+    /// The time is logged as a distraction event against `domain` and its
+    /// `category_id`. The returned remaining budget is the smaller of the
+    /// session budget and that category's budget, if it has one.
+    ///
+    /// Rate limited per session to prevent rapid calls from bypassing budget
+    /// enforcement. The limit is kept in the database, so it holds across
+    /// restarts and between the app and the native host.
+    /// Returns None if no active session.
+    pub fn use_distraction_time(
+        &self,
+        secs: i32,
+        domain: Option<&str>,
+        category_id: Option<i64>,
+    ) -> rusqlite::Result<Option<i32>> {
+        let db = self.lock_db_for_write();
         let conn = db.connection();
 
-        if let Some(mut session) = FocusSession::find_active(conn)? {
-            session.add_distraction_time(conn, secs)?;
-            Ok(Some(session.budget_remaining()))
-        } else {
-            Ok(None)
+        let Some(mut session) = FocusSession::find_active(conn)? else {
+            return Ok(None);
+        };
+        if !session.claim_distraction_request(
+            conn,
+            current_timestamp(),
+            DISTRACTION_TIME_RATE_LIMIT_SECS,
+        )? {
+            // Rate limited - return current budget without deducting
+            return Self::remaining_budget(conn, &session, category_id).map(Some);
+        }
+        session.add_distraction_time(conn, secs)?;
+        if let Some(id) = session.id {
+            DistractionEvent::new(id, domain, category_id, secs).save(conn)?;
+        }
+        Self::remaining_budget(conn, &session, category_id).map(Some)
+    }
+
+    /// Budget left for `category_id`: the session budget, further capped by
+    /// the category's own slice when it has one.
+    fn remaining_budget(
+        conn: &Connection,
+        session: &FocusSession,
+        category_id: Option<i64>,
+    ) -> rusqlite::Result<i32> {
+        let overall = session.budget_remaining();
+        let (Some(session_id), Some(category_id)) = (session.id, category_id) else {
+            return Ok(overall);
+        };
+        let category = CategoryBudget::find_for_session(conn, session_id)?
+            .into_iter()
+            .find(|b| b.category_id == category_id);
+        Ok(category.map_or(overall, |b| b.remaining().min(overall)))
+    }
+
+    /// Record `domains` and `allowed` as the current blocklist, bumping the
+    /// version if either differs from the last one. Returns the version and,
+    /// on a change, the diff.
+    fn sync_blocklist(
+        &self,
+        domains: &[String],
+        allowed: &[String],
+    ) -> (u64, Option<BlocklistUpdate>) {
+        let mut current: Vec<String> = domains.to_vec();
+        current.sort_unstable();
+        current.dedup();
+        let mut allowed = allowed.to_vec();
+        allowed.sort_unstable();
+        allowed.dedup();
+
+        let mut blocklist = self
+            .blocklist
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if blocklist.domains == current && blocklist.allowed == allowed {
+            return (blocklist.version, None);
         }
+
+        let added = current
+            .iter()
+            .filter(|d| blocklist.domains.binary_search(d).is_err())
+            .cloned()
+            .collect();
+        let removed = blocklist
+            .domains
+            .iter()
+            .filter(|d| current.binary_search(d).is_err())
+            .cloned()
+            .collect();
+        let update = BlocklistUpdate {
+            from_version: blocklist.version,
+            version: blocklist.version + 1,
+            added,
+            removed,
+            allowed_domains: (blocklist.allowed != allowed).then(|| allowed.clone()),
+        };
+        blocklist.version = update.version;
+        blocklist.domains = current;
+        blocklist.allowed = allowed;
+        (update.version, Some(update))
+    }
+
+    /// The blocked domains that changed since they were last handed out, if any.
+    ///
+    /// Polled by the native host so rule changes reach the browser without
+    /// waiting for the extension to ask for the full state again.
+    pub fn blocklist_update(&self) -> rusqlite::Result<Option<BlocklistUpdate>> {
+        let state = self.cached_state()?;
+        Ok(self
+            .sync_blocklist(&state.blocked_domains, &state.allowed_domains)
+            .1)
     }
 
     pub fn is_domain_blocked(&self, domain: &str) -> rusqlite::Result<bool> {
         let state = self.get_state()?;
 
-        if !state.active {
+        if !state.active
+            || state
+                .allowed_domains
+                .iter()
+                .any(|allowed| pattern_blocks(allowed, domain))
+        {
             return Ok(false);
         }
 
@@ -157,22 +924,57 @@ impl FocusManager {
     /// Reset rate limiting state. Used in tests to allow rapid calls.
     #[cfg(test)]
     pub fn reset_rate_limit(&self) {
-        let mut last_request = self
-            .last_distraction_request
-            .lock()
-            .unwrap_or_else(std::sync::PoisonError::into_inner);
-        *last_request = None;
+        self.lock_db_for_write()
+            .connection()
+            .execute(
+                "UPDATE focus_sessions SET last_distraction_request_at = NULL",
+                [],
+            )
+            .expect("reset rate limit");
+    }
+
+    fn get_blocked_domains(
+        conn: &Connection,
+        session_id: Option<i64>,
+    ) -> rusqlite::Result<Vec<String>> {
+        // Get domains from rules that map to distracting categories or ones
+        // the session blocks, skipping rules in disabled groups
+        let mut stmt = conn.prepare(
+            "WITH blocking AS (
+                 SELECT r.id, r.pattern FROM rules r
+                 JOIN categories c ON r.category_id = c.id
+                 LEFT JOIN rule_groups g ON r.group_id = g.id
+                 WHERE r.match_type = 'domain' AND g.enabled IS NOT 0 AND r.deleted_at IS NULL
+                   AND (c.productivity < 0 OR r.category_id IN (
+                       SELECT category_id FROM session_blocked_categories WHERE session_id = ?1))
+             )
+             SELECT pattern FROM blocking
+             UNION ALL
+             SELECT rp.pattern FROM rule_patterns rp JOIN blocking b ON rp.rule_id = b.id",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], |row| row.get(0))?;
+        rows.collect()
     }
 
-    fn get_blocked_domains(conn: &Connection) -> rusqlite::Result<Vec<String>> {
-        // Get domains from rules that map to distracting categories
+    /// Domain patterns of distracting categories with their category, which
+    /// distraction budget can unlock.
+    fn get_budgeted_domains(conn: &Connection) -> rusqlite::Result<Vec<(String, i64)>> {
         let mut stmt = conn.prepare(
-            "SELECT r.pattern FROM rules r
-             JOIN categories c ON r.category_id = c.id
-             WHERE r.match_type = 'domain' AND c.productivity < 0",
+            "WITH distracting AS (
+                 SELECT r.id, r.pattern, r.category_id FROM rules r
+                 JOIN categories c ON r.category_id = c.id
+                 LEFT JOIN rule_groups g ON r.group_id = g.id
+                 WHERE r.match_type = 'domain' AND g.enabled IS NOT 0 AND r.deleted_at IS NULL
+                   AND c.productivity < 0
+             )
+             SELECT pattern, category_id FROM distracting
+             UNION ALL
+             SELECT rp.pattern, d.category_id FROM rule_patterns rp
+             JOIN distracting d ON rp.rule_id = d.id",
         )?;
 
-        let rows = stmt.query_map([], |row| row.get(0))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
         rows.collect()
     }
 
@@ -183,12 +985,79 @@ impl FocusManager {
     /// - If a scheduled session is active but no schedule matches, ends the session
     /// - Manual (non-scheduled) sessions are not affected by schedule checks
     pub fn check_schedules(&self) -> rusqlite::Result<()> {
-        let db = self.lock_db();
+        self.check_schedules_at(&Local, current_timestamp())
+            .map(|_| ())
+    }
+
+    /// Bring focus state in line with the schedules as soon as the app starts,
+    /// instead of waiting for the frontend's first check. A session left open
+    /// by a crash is closed first (see `reconcile_stale_session`).
+    ///
+    /// Returns the missed start when the app wasn't running as a schedule
+    /// window opened, so the user can be told their session started late.
+    pub fn restore_on_startup(&self) -> rusqlite::Result<Option<MissedStart>> {
+        let now = current_timestamp();
+        self.reconcile_stale_session(now)?;
+        self.check_schedules_at(&Local, now)
+    }
+
+    /// Close the active session if it should have ended while the app was down.
+    ///
+    /// A timed session whose planned end has passed ends at that planned end
+    /// (a crash doesn't count as abandoning it). An open-ended session ends at
+    /// the last tracked activity once the tracker has been silent for too long.
+    /// Anything else is still within its window and resumes untouched.
+    pub fn reconcile_stale_session(&self, now: i64) -> rusqlite::Result<Option<FocusSession>> {
+        let db = self.lock_db_for_write();
         let conn = db.connection();
 
-        let (day, time) = get_current_day_and_time();
-        let active_schedule = Self::find_active_schedule(conn, day, &time)?;
+        let Some(mut session) = FocusSession::find_active(conn)? else {
+            return Ok(None);
+        };
+        let (ended_at, action) = match session.expected_end() {
+            Some(expected_end) if expected_end <= now => (expected_end, AuditAction::Complete),
+            Some(_) => return Ok(None),
+            None => {
+                let last_seen = Activity::last_seen_since(conn, session.started_at)?
+                    .map_or(session.started_at, |seen| seen.max(session.started_at));
+                if now - last_seen <= STALE_SESSION_SECS {
+                    return Ok(None);
+                }
+                (last_seen, AuditAction::End)
+            }
+        };
+
+        info!(
+            "Closing focus session {} left open while the app was down",
+            session.id.unwrap_or(0)
+        );
+        session.end_at(conn, ended_at)?;
+        audit(
+            conn,
+            now,
+            action,
+            Origin::System,
+            &session,
+            Some("left open while the app was down"),
+        )?;
+        Ok(Some(session))
+    }
+
+    /// Schedules are wall-clock times, matched against `now` in `tz`.
+    fn check_schedules_at<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        now: i64,
+    ) -> rusqlite::Result<Option<MissedStart>> {
+        let db = self.lock_db_for_write();
+        let conn = db.connection();
+
+        let active_schedule = Self::find_active_schedule(conn, tz, now)?;
         let active_session = FocusSession::find_active(conn)?;
+        let window_start = active_schedule
+            .as_ref()
+            .and_then(FocusSchedule::start_wall_time)
+            .map(|start| wall_time_at(tz, date_in(tz, now), start));
 
         match (active_schedule, active_session) {
             // Schedule active, no session -> start scheduled session
@@ -198,8 +1067,7 @@ impl FocusManager {
                     schedule.id.unwrap_or(0),
                     schedule.distraction_budget
                 );
-                let mut session = FocusSession::new(schedule.distraction_budget, true);
-                session.save(conn)?;
+                return Self::start_scheduled(conn, &schedule, window_start, now);
             }
             // No schedule active, scheduled session exists -> end it
             (None, Some(mut session)) if session.scheduled => {
@@ -208,16 +1076,72 @@ impl FocusManager {
                     session.id.unwrap_or(0)
                 );
                 session.end(conn)?;
+                audit(
+                    conn,
+                    now,
+                    AuditAction::End,
+                    Origin::Schedule,
+                    &session,
+                    Some("schedule no longer active"),
+                )?;
+            }
+            // Scheduled session left over from an earlier window (the app was
+            // closed when that window ended) -> replace it with this window's
+            (Some(schedule), Some(mut session))
+                if session.scheduled
+                    && window_start.is_some_and(|start| session.started_at < start) =>
+            {
+                info!(
+                    "Ending stale scheduled focus session {} from an earlier window",
+                    session.id.unwrap_or(0)
+                );
+                session.end(conn)?;
+                audit(
+                    conn,
+                    now,
+                    AuditAction::End,
+                    Origin::Schedule,
+                    &session,
+                    Some("left over from an earlier window"),
+                )?;
+                return Self::start_scheduled(conn, &schedule, window_start, now);
             }
             // Schedule active with different budget, scheduled session exists -> update session
             (Some(schedule), Some(session)) if session.scheduled => {
                 // Only end and restart if budget changed significantly (prevents churn)
                 if (schedule.distraction_budget - session.distraction_budget).abs() > 60 {
                     info!("Schedule budget changed significantly, restarting session");
+                    let detail = Some("schedule budget changed");
                     let mut session = session;
                     session.end(conn)?;
+                    audit(
+                        conn,
+                        now,
+                        AuditAction::End,
+                        Origin::Schedule,
+                        &session,
+                        detail,
+                    )?;
                     let mut new_session = FocusSession::new(schedule.distraction_budget, true);
                     new_session.save(conn)?;
+                    if let Some(start) = window_start {
+                        new_session.inherit_scheduled_usage(conn, start)?;
+                    }
+                    if let Some(id) = new_session.id {
+                        SessionBlockedCategory::set_for_session(
+                            conn,
+                            id,
+                            &schedule.blocked_categories,
+                        )?;
+                    }
+                    audit(
+                        conn,
+                        now,
+                        AuditAction::Start,
+                        Origin::Schedule,
+                        &new_session,
+                        detail,
+                    )?;
                 }
             }
             // Manual session active - don't interfere
@@ -228,80 +1152,246 @@ impl FocusManager {
             _ => {}
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    /// Start a session for `schedule`, reporting a missed start if its window
+    /// opened well before `now`.
+    fn start_scheduled(
+        conn: &Connection,
+        schedule: &FocusSchedule,
+        window_start: Option<i64>,
+        now: i64,
+    ) -> rusqlite::Result<Option<MissedStart>> {
+        let mut session = FocusSession::new(schedule.distraction_budget, true);
+        session.started_at = now;
+        session.save(conn)?;
+        if let Some(start) = window_start {
+            session.inherit_scheduled_usage(conn, start)?;
+        }
+        if let Some(id) = session.id {
+            SessionBlockedCategory::set_for_session(conn, id, &schedule.blocked_categories)?;
+        }
+        let detail = format!("{}-{}", schedule.start_time, schedule.end_time);
+        audit(
+            conn,
+            now,
+            AuditAction::Start,
+            Origin::Schedule,
+            &session,
+            Some(&detail),
+        )?;
+
+        let missed = window_start
+            .filter(|start| now - start > MISSED_START_GRACE_SECS)
+            .map(|scheduled_start| MissedStart {
+                start_time: schedule.start_time.clone(),
+                late_secs: now - scheduled_start,
+            });
+        if let Some(missed) = &missed {
+            info!(
+                "Scheduled focus session started {}s late; the app wasn't running at {}",
+                missed.late_secs, missed.start_time
+            );
+        }
+        Ok(missed)
     }
 
     /// Find the currently active schedule, if any.
     pub fn get_active_schedule(&self) -> rusqlite::Result<Option<FocusSchedule>> {
         let db = self.lock_db();
         let conn = db.connection();
-        let (day, time) = get_current_day_and_time();
-        Self::find_active_schedule(conn, day, &time)
+        Self::find_active_schedule(conn, &Local, current_timestamp())
     }
 
-    fn find_active_schedule(
+    /// The schedule active at `now` in `tz`, unless it's skipped that day or
+    /// the user is away.
+    fn find_active_schedule<Tz: TimeZone>(
         conn: &Connection,
-        day: u32,
-        time: &str,
+        tz: &Tz,
+        now: i64,
     ) -> rusqlite::Result<Option<FocusSchedule>> {
+        if is_away_at(conn, tz, now)? {
+            return Ok(None);
+        }
+        let (day, time) = day_and_time_in(tz, now);
+        let skipped = skipped_on(conn, tz, now)?;
         let schedules = FocusSchedule::find_enabled(conn)?;
 
-        Ok(schedules.into_iter().find(|s| s.is_active_at(day, time)))
+        Ok(schedules
+            .into_iter()
+            .find(|s| s.is_active_at(day, &time) && !s.id.is_some_and(|id| skipped.contains(&id))))
     }
 
-    /// Start a scheduled focus session with the given budget.
-    /// Unlike `start_session`, this marks the session as scheduled.
-    pub fn start_scheduled_session(
-        &self,
-        distraction_budget_secs: i32,
-    ) -> rusqlite::Result<FocusSession> {
-        let db = self.lock_db();
+    /// The schedule "skip today" applies to: the one running now, else the
+    /// next one starting later today.
+    fn schedule_to_skip<Tz: TimeZone>(
+        conn: &Connection,
+        tz: &Tz,
+        now: i64,
+    ) -> rusqlite::Result<Option<FocusSchedule>> {
+        if is_away_at(conn, tz, now)? {
+            return Ok(None);
+        }
+        let (day, time) = day_and_time_in(tz, now);
+        let schedules = FocusSchedule::find_enabled(conn)?;
+
+        // Ordered by start time, so the first not yet over is the one
+        Ok(schedules
+            .into_iter()
+            .find(|s| s.applies_to_day(day) && time.as_str() < s.end_time.as_str()))
+    }
+
+    /// Whether today's scheduled focus is skipped: `Some(true)` if it is,
+    /// `Some(false)` if a schedule is running or still to come today, `None`
+    /// if there's nothing left to skip.
+    pub fn skip_today_status(&self) -> rusqlite::Result<Option<bool>> {
+        let db = self.lock_db();
+        let conn = db.connection();
+        Self::skip_today_status_at(conn, &Local, current_timestamp())
+    }
+
+    fn skip_today_status_at<Tz: TimeZone>(
+        conn: &Connection,
+        tz: &Tz,
+        now: i64,
+    ) -> rusqlite::Result<Option<bool>> {
+        if !skipped_on(conn, tz, now)?.is_empty() {
+            return Ok(Some(true));
+        }
+        Ok(Self::schedule_to_skip(conn, tz, now)?.map(|_| false))
+    }
+
+    /// Skip today's scheduled focus, or take the skip back.
+    ///
+    /// Skipping covers the schedule running now, else the next one today, and
+    /// ends its session; it's a one-day exception, so the schedule runs again
+    /// tomorrow. Taking it back starts the session if its window is open.
+    pub fn skip_today(&self, skip: bool, origin: Origin) -> Result<Option<FocusSession>, AppError> {
+        let ended = {
+            let db = self.lock_db_for_write();
+            Self::skip_today_at(db.connection(), &Local, current_timestamp(), skip, origin)?
+        };
+        if !skip {
+            self.check_schedules()?;
+        }
+        Ok(ended)
+    }
+
+    fn skip_today_at<Tz: TimeZone>(
+        conn: &Connection,
+        tz: &Tz,
+        now: i64,
+        skip: bool,
+        origin: Origin,
+    ) -> Result<Option<FocusSession>, AppError> {
+        let today = date_in(tz, now).format("%Y-%m-%d").to_string();
+        if !skip {
+            FocusSchedule::clear_skips_on(conn, &today)?;
+            return Ok(None);
+        }
+
+        let Some(schedule) = Self::schedule_to_skip(conn, tz, now)? else {
+            return Err(AppError::NotFound {
+                entity: "Focus schedule",
+            });
+        };
+        let (day, time) = day_and_time_in(tz, now);
+        let session = FocusSession::find_active(conn)?
+            .filter(|session| session.scheduled && schedule.is_active_at(day, &time));
+        if session
+            .as_ref()
+            .is_some_and(|session| session.is_locked(now))
+        {
+            return Err(session_locked_error());
+        }
+        if let Some(id) = schedule.id {
+            FocusSchedule::skip_on(conn, id, &today)?;
+        }
+
+        let Some(mut session) = session else {
+            return Ok(None);
+        };
+        session.end(conn)?;
+        audit(
+            conn,
+            now,
+            AuditAction::End,
+            origin,
+            &session,
+            Some("skipped today"),
+        )?;
+        Ok(Some(session))
+    }
+
+    /// Start a scheduled focus session with the given budget.
+    /// Unlike `start_session`, this marks the session as scheduled.
+    pub fn start_scheduled_session(
+        &self,
+        distraction_budget_secs: i32,
+    ) -> rusqlite::Result<FocusSession> {
+        let db = self.lock_db_for_write();
         let conn = db.connection();
 
+        let now = current_timestamp();
+
         // End any existing active session
         if let Some(mut existing) = FocusSession::find_active(conn)? {
             existing.end(conn)?;
+            audit(
+                conn,
+                now,
+                AuditAction::End,
+                Origin::Schedule,
+                &existing,
+                Some("replaced by a new session"),
+            )?;
         }
 
         let mut session = FocusSession::new(distraction_budget_secs, true);
         session.save(conn)?;
+        audit(
+            conn,
+            now,
+            AuditAction::Start,
+            Origin::Schedule,
+            &session,
+            None,
+        )?;
 
         Ok(session)
     }
 }
 
-/// Get the current day of week (1=Monday, 7=Sunday) and time (HH:MM format).
-#[expect(
-    clippy::cast_possible_truncation,
-    reason = "Day-of-week and time values are always small (< 60), well within u32 range"
-)]
-#[expect(
-    clippy::as_conversions,
-    reason = "u64 -> u32 narrowing cast is safe because values are always small (day 1-7, hours < 24, minutes < 60)"
-)]
-fn get_current_day_and_time() -> (u32, String) {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_else(|_| Duration::from_secs(0))
-        .as_secs();
-
-    // Convert Unix timestamp to day of week and time
-    // Unix epoch (Jan 1, 1970) was a Thursday (day 4 in ISO weekday)
-    let days_since_epoch = now / 86400;
-    let day_of_week = ((days_since_epoch + 3) % 7) + 1; // 1=Monday, 7=Sunday
-
-    let seconds_today = now % 86400;
-    let hours = (seconds_today / 3600) as u32;
-    let minutes = ((seconds_today % 3600) / 60) as u32;
-
-    (day_of_week as u32, format!("{hours:02}:{minutes:02}"))
+/// Ids of the schedules skipped on the local date of `now` in `tz`.
+fn skipped_on<Tz: TimeZone>(conn: &Connection, tz: &Tz, now: i64) -> rusqlite::Result<Vec<i64>> {
+    let today = date_in(tz, now).format("%Y-%m-%d").to_string();
+    Ok(FocusSchedule::find_skips_from(conn, &today)?
+        .into_iter()
+        .filter(|(_, date)| *date == today)
+        .map(|(id, _)| id)
+        .collect())
+}
+
+/// Get the current local day of week (1=Monday, 7=Sunday) and time (HH:MM format).
+pub(crate) fn get_current_day_and_time() -> (u32, String) {
+    day_and_time_at(current_timestamp())
+}
+
+/// Local day of week (1=Monday, 7=Sunday) and wall-clock time (HH:MM) of a
+/// Unix timestamp, following the system time zone and its DST rules.
+pub(crate) fn day_and_time_at(timestamp: i64) -> (u32, String) {
+    day_and_time_in(&Local, timestamp)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::SECS_PER_DAY;
     use crate::db::migrations;
-    use crate::models::{Category, FocusSchedule, MatchType, Rule};
+    use crate::models::{Category, FocusSchedule, MatchType, Rule, RuleGroup};
+    use crate::test_utils::CentralEurope;
+    use chrono::Utc;
     use tempfile::{tempdir, TempDir};
 
     fn setup() -> (Arc<Mutex<Database>>, TempDir) {
@@ -320,14 +1410,14 @@ mod tests {
         let state = manager.get_state().unwrap();
         assert!(!state.active);
 
-        let session = manager.start_session(600).unwrap();
+        let session = manager.start_session(600, Origin::Ui).unwrap();
         assert_eq!(session.distraction_budget, 600);
 
         let state = manager.get_state().unwrap();
         assert!(state.active);
         assert_eq!(state.budget_remaining, 600);
 
-        manager.end_session().unwrap();
+        manager.end_session(Origin::Ui).unwrap();
 
         let state = manager.get_state().unwrap();
         assert!(!state.active);
@@ -338,18 +1428,193 @@ mod tests {
         let (db, _dir) = setup();
         let manager = FocusManager::new(Arc::clone(&db));
 
-        manager.start_session(300).unwrap();
+        manager.start_session(300, Origin::Ui).unwrap();
 
-        let remaining = manager.use_distraction_time(100).unwrap().unwrap();
+        let remaining = manager
+            .use_distraction_time(100, None, None)
+            .unwrap()
+            .unwrap();
         assert_eq!(remaining, 200);
 
         // Reset rate limit to allow immediate second call in test
         manager.reset_rate_limit();
 
-        let remaining = manager.use_distraction_time(200).unwrap().unwrap();
+        let remaining = manager
+            .use_distraction_time(200, None, None)
+            .unwrap()
+            .unwrap();
         assert_eq!(remaining, 0);
     }
 
+    #[test]
+    fn test_productive_time_earns_budget_up_to_cap() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        let (coding, entertainment) = {
+            let db = db.lock().unwrap();
+            let conn = db.connection();
+            AccrualSettings {
+                enabled: true,
+                interval_minutes: 10,
+                reward_secs: 60,
+                max_earned_secs: 120,
+            }
+            .save(conn)
+            .unwrap();
+            let categories = Category::find_all(conn).unwrap();
+            let id = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+            (id("Coding"), id("Entertainment"))
+        };
+
+        assert_eq!(manager.record_productive_time(coding, 600).unwrap(), 0);
+        manager.start_session(300, Origin::Ui).unwrap();
+        assert_eq!(
+            manager.record_productive_time(entertainment, 600).unwrap(),
+            0
+        );
+        assert_eq!(manager.record_productive_time(coding, 599).unwrap(), 0);
+        assert_eq!(manager.record_productive_time(coding, 1).unwrap(), 60);
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 360);
+
+        assert_eq!(manager.record_productive_time(coding, 1800).unwrap(), 60);
+        assert_eq!(manager.record_productive_time(coding, 600).unwrap(), 0);
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 420);
+    }
+
+    #[test]
+    fn test_distraction_grant() {
+        let (db, _dir) = setup();
+        let db = db.lock().unwrap();
+        let conn = db.connection();
+        assert_eq!(
+            distraction_grant_secs(conn).unwrap(),
+            DEFAULT_DISTRACTION_GRANT_SECS
+        );
+        set_distraction_grant_secs(conn, 10).unwrap();
+        assert_eq!(distraction_grant_secs(conn).unwrap(), 10);
+
+        assert_eq!(capped_grant(None, 30), 30);
+        assert_eq!(capped_grant(Some(15), 30), 15);
+        assert_eq!(capped_grant(Some(600), 30), 30);
+        assert_eq!(capped_grant(Some(-5), 30), 1);
+    }
+
+    #[test]
+    fn test_rate_limit_is_shared_between_managers() {
+        let (db, _dir) = setup();
+        // The app and the native host each hold their own manager
+        let app = FocusManager::new(Arc::clone(&db));
+        let host = FocusManager::new(Arc::clone(&db));
+
+        app.start_session(300, Origin::Ui).unwrap();
+        assert_eq!(app.use_distraction_time(30, None, None).unwrap(), Some(270));
+        // Rate limited: nothing is deducted
+        assert_eq!(
+            host.use_distraction_time(30, None, None).unwrap(),
+            Some(270)
+        );
+
+        // A fresh manager (e.g. a restarted host) is still limited
+        let restarted = FocusManager::new(Arc::clone(&db));
+        assert_eq!(
+            restarted.use_distraction_time(30, None, None).unwrap(),
+            Some(270)
+        );
+    }
+
+    #[test]
+    fn test_category_budget_caps_remaining() {
+        let (db, _dir) = setup();
+        let entertainment = {
+            let db_lock = db.lock().unwrap();
+            Category::find_all(db_lock.connection())
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "Entertainment")
+                .unwrap()
+                .id
+        };
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager
+            .start_timed_session(900, None, false, &[(entertainment, 300)], Origin::Ui)
+            .unwrap();
+
+        let remaining = manager
+            .use_distraction_time(120, Some("youtube.com"), Some(entertainment))
+            .unwrap()
+            .unwrap();
+        assert_eq!(remaining, 180);
+
+        // Other categories draw only on the overall budget
+        manager.reset_rate_limit();
+        let remaining = manager
+            .use_distraction_time(60, Some("news.ycombinator.com"), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(remaining, 720);
+
+        manager.reset_rate_limit();
+        let remaining = manager
+            .use_distraction_time(200, Some("youtube.com"), Some(entertainment))
+            .unwrap()
+            .unwrap();
+        assert_eq!(remaining, 0);
+
+        let state = manager.get_state().unwrap();
+        assert_eq!(state.budget_remaining, 520);
+        assert_eq!(state.category_budgets.first().unwrap().used_secs, 320);
+    }
+
+    #[test]
+    fn test_emergency_unlock_requires_phrase_and_delay() {
+        let (db, _dir) = setup();
+        set_unlock_delay_minutes(db.lock().unwrap().connection(), 1).unwrap();
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let session = manager
+            .start_timed_session(600, Some(1500), true, &[], Origin::Ui)
+            .unwrap();
+        let now = session.started_at + 60;
+        assert!(manager.end_session(Origin::Ui).is_err());
+        assert!(manager.start_session(600, Origin::Ui).is_err());
+        assert!(manager
+            .emergency_unlock("let me out", now, Origin::Ui)
+            .is_err());
+
+        let status = manager
+            .emergency_unlock(EMERGENCY_UNLOCK_PHRASE, now, Origin::Ui)
+            .unwrap();
+        assert!(matches!(
+            status,
+            UnlockStatus::Waiting { remaining_secs: 60 }
+        ));
+        let status = manager
+            .emergency_unlock(EMERGENCY_UNLOCK_PHRASE, now + 30, Origin::Ui)
+            .unwrap();
+        assert!(matches!(
+            status,
+            UnlockStatus::Waiting { remaining_secs: 30 }
+        ));
+
+        let status = manager
+            .emergency_unlock(EMERGENCY_UNLOCK_PHRASE, now + 60, Origin::Ui)
+            .unwrap();
+        assert!(matches!(status, UnlockStatus::Unlocked(ended) if ended.emergency_unlocked));
+        assert!(!manager.get_state().unwrap().active);
+    }
+
+    #[test]
+    fn test_emergency_unlock_not_needed_for_relaxed_session() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        let session = manager.start_session(600, Origin::Ui).unwrap();
+
+        assert!(manager
+            .emergency_unlock(EMERGENCY_UNLOCK_PHRASE, session.started_at, Origin::Ui)
+            .is_err());
+        assert!(manager.end_session(Origin::Ui).unwrap().is_some());
+    }
+
     #[test]
     fn test_blocked_domains() {
         let (db, _dir) = setup();
@@ -363,7 +1628,9 @@ mod tests {
                 .into_iter()
                 .find(|c| c.name == "Entertainment")
                 .unwrap();
-            Rule::create(conn, "reddit.com", MatchType::Domain, entertainment.id, 10).unwrap();
+            let rule =
+                Rule::create(conn, "reddit.com", MatchType::Domain, entertainment.id, 10).unwrap();
+            Rule::set_patterns(conn, rule.id, &["reddit.com", "lobste.rs"]).unwrap();
         }
 
         let manager = FocusManager::new(Arc::clone(&db));
@@ -371,16 +1638,396 @@ mod tests {
         // Not blocked when focus mode is off
         assert!(!manager.is_domain_blocked("reddit.com").unwrap());
 
-        manager.start_session(600).unwrap();
+        manager.start_session(600, Origin::Ui).unwrap();
 
         // Blocked when focus mode is on
         assert!(manager.is_domain_blocked("reddit.com").unwrap());
         assert!(manager.is_domain_blocked("www.reddit.com").unwrap());
+        // Every pattern of a rule is blocked
+        assert!(manager.is_domain_blocked("lobste.rs").unwrap());
 
         // Other domains not blocked
         assert!(!manager.is_domain_blocked("github.com").unwrap());
     }
 
+    #[test]
+    fn test_session_blocked_categories() {
+        let (db, _dir) = setup();
+        let communication = {
+            let db_lock = db.lock().unwrap();
+            let conn = db_lock.connection();
+            let communication = Category::find_all(conn)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "Communication")
+                .unwrap();
+            Rule::create(conn, "slack.com", MatchType::Domain, communication.id, 10).unwrap();
+            communication.id
+        };
+
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(manager
+            .set_session_blocked_categories(&[communication])
+            .is_err());
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(!manager.is_domain_blocked("slack.com").unwrap());
+
+        manager
+            .set_session_blocked_categories(&[communication])
+            .unwrap();
+        assert!(manager.is_domain_blocked("slack.com").unwrap());
+        assert_eq!(
+            manager.get_state().unwrap().blocked_categories,
+            vec![communication]
+        );
+
+        // A new session starts without them
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(!manager.is_domain_blocked("slack.com").unwrap());
+    }
+
+    #[test]
+    fn test_locked_session_refuses_blocked_categories() {
+        let (db, _dir) = setup();
+        let communication = {
+            let db_lock = db.lock().unwrap();
+            let conn = db_lock.connection();
+            let communication = Category::find_all(conn)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "Communication")
+                .unwrap();
+            Rule::create(conn, "slack.com", MatchType::Domain, communication.id, 10).unwrap();
+            communication.id
+        };
+
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager
+            .start_scoped_session(
+                600,
+                Some(1500),
+                true,
+                &[],
+                &[],
+                &[communication],
+                Origin::Ui,
+            )
+            .unwrap();
+        assert!(manager.set_session_blocked_categories(&[]).is_err());
+        assert!(manager.is_domain_blocked("slack.com").unwrap());
+    }
+
+    #[test]
+    fn test_disabled_rule_groups_do_not_block() {
+        let (db, _dir) = setup();
+        let group_id = {
+            let db_lock = db.lock().unwrap();
+            let conn = db_lock.connection();
+            let entertainment = Category::find_all(conn)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "Entertainment")
+                .unwrap();
+            let rule =
+                Rule::create(conn, "espn.com", MatchType::Domain, entertainment.id, 10).unwrap();
+            let group = RuleGroup::create(conn, "Weekend mode").unwrap();
+            Rule::set_group(conn, rule.id, Some(group.id)).unwrap();
+            group.id
+        };
+
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(manager.is_domain_blocked("espn.com").unwrap());
+
+        RuleGroup::set_enabled(db.lock().unwrap().connection(), group_id, false).unwrap();
+        manager.invalidate_cache();
+        assert!(!manager.is_domain_blocked("espn.com").unwrap());
+    }
+
+    #[test]
+    fn test_session_blocks_last_for_the_session() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(manager.add_session_blocks(&["news.example"]).is_err());
+
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert_eq!(
+            manager
+                .add_session_blocks(&["news.example", "news.example"])
+                .unwrap(),
+            1
+        );
+        assert!(manager.is_domain_blocked("www.news.example").unwrap());
+
+        manager.end_session(Origin::Ui).unwrap();
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(!manager.is_domain_blocked("news.example").unwrap());
+    }
+
+    #[test]
+    fn test_session_exceptions() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(manager
+            .set_session_exceptions(&["music.youtube.com".into()])
+            .is_err());
+
+        manager.start_session(600, Origin::Ui).unwrap();
+        manager.add_session_blocks(&["youtube.com"]).unwrap();
+        assert_eq!(
+            manager.get_state().unwrap().allowed_domains,
+            Vec::<String>::new()
+        );
+        manager
+            .set_session_exceptions(&["music.youtube.com".into()])
+            .unwrap();
+
+        let update = manager.blocklist_update().unwrap().unwrap();
+        assert!(update.added.is_empty());
+        assert_eq!(
+            update.allowed_domains,
+            Some(vec!["music.youtube.com".to_string()])
+        );
+        assert!(!manager.is_domain_blocked("music.youtube.com").unwrap());
+        assert!(manager.is_domain_blocked("www.youtube.com").unwrap());
+    }
+
+    #[test]
+    fn test_locked_session_refuses_exceptions() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager
+            .start_scoped_session(
+                600,
+                Some(1500),
+                true,
+                &[],
+                &["docs.rs".into()],
+                &[],
+                Origin::Ui,
+            )
+            .unwrap();
+        assert!(manager
+            .set_session_exceptions(&["music.youtube.com".into()])
+            .is_err());
+        assert_eq!(
+            manager.get_state().unwrap().allowed_domains,
+            vec!["docs.rs".to_string()]
+        );
+
+        // Nor one that has spent its budget
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.start_session(0, Origin::Ui).unwrap();
+        assert!(manager
+            .set_session_exceptions(&["music.youtube.com".into()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_pattern_blocks() {
+        assert!(pattern_blocks("reddit.com", "reddit.com"));
+        assert!(pattern_blocks("*.reddit.com", "old.reddit.com"));
+        assert!(!pattern_blocks("reddit.com", "notreddit.com"));
+        assert!(!pattern_blocks("old.reddit.com", "reddit.com"));
+    }
+
+    #[test]
+    fn test_state_splits_blocked_domains_by_severity() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.start_session(600, Origin::Ui).unwrap();
+        manager.add_session_blocks(&["news.example"]).unwrap();
+
+        let state = manager.get_state().unwrap();
+        assert!(state.soft_limited_domains.contains(&"youtube.com".into()));
+        assert_eq!(state.hard_blocked_domains, vec!["news.example".to_string()]);
+        assert!(state.budget_allowed_domains.is_empty());
+
+        manager
+            .allow_domain_temporarily("youtube.com", 60, None, current_timestamp())
+            .unwrap();
+        let state = manager.get_state().unwrap();
+        assert_eq!(
+            state.budget_allowed_domains,
+            vec!["youtube.com".to_string()]
+        );
+        assert!(!state.soft_limited_domains.contains(&"youtube.com".into()));
+    }
+
+    #[test]
+    fn test_split_by_severity() {
+        let blocked = vec!["reddit.com".to_string(), "netflix.com".to_string()];
+        let budgeted = vec![
+            ("reddit.com".to_string(), 1),
+            ("netflix.com".to_string(), 2),
+        ];
+        let budgets = vec![CategoryBudget {
+            category_id: 2,
+            budget_secs: 60,
+            used_secs: 60,
+        }];
+
+        // A used-up category budget hardens only that category
+        let (hard, soft) = split_by_severity(&blocked, &budgeted, 300, &budgets);
+        assert_eq!(hard, vec!["netflix.com".to_string()]);
+        assert_eq!(soft, vec!["reddit.com".to_string()]);
+
+        // Everything is hard once the session budget is gone
+        let (hard, soft) = split_by_severity(&blocked, &budgeted, 0, &budgets);
+        assert_eq!(hard, blocked);
+        assert!(soft.is_empty());
+    }
+
+    #[test]
+    fn test_record_blocked_attempt() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(!manager.record_blocked_attempt("reddit.com", 1000).unwrap());
+
+        let session = manager.start_session(600, Origin::Ui).unwrap();
+        assert!(manager.record_blocked_attempt("reddit.com", 1000).unwrap());
+        let attempts =
+            BlockedAttempt::find_by_session(db.lock().unwrap().connection(), session.id.unwrap())
+                .unwrap();
+        assert_eq!(attempts.len(), 1);
+    }
+
+    #[test]
+    fn test_allow_keeps_rest_of_site_blocked() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.start_session(600, Origin::Ui).unwrap();
+        manager.add_session_blocks(&["reddit.com"]).unwrap();
+        manager
+            .allow_domain_temporarily("old.reddit.com", 60, None, current_timestamp())
+            .unwrap();
+
+        assert!(!manager.is_domain_blocked("old.reddit.com").unwrap());
+        assert!(manager.is_domain_blocked("www.reddit.com").unwrap());
+        assert!(manager.is_domain_blocked("reddit.com").unwrap());
+        let state = manager.get_state().unwrap();
+        assert!(state.blocked_domains.contains(&"reddit.com".into()));
+        assert_eq!(state.allowed_domains, vec!["old.reddit.com".to_string()]);
+    }
+
+    #[test]
+    fn test_allow_domain_temporarily() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        let now = current_timestamp();
+        assert!(manager
+            .allow_domain_temporarily("reddit.com", 60, None, now)
+            .is_err());
+
+        manager.start_session(300, Origin::Ui).unwrap();
+        manager.add_session_blocks(&["reddit.com"]).unwrap();
+        let allow = manager
+            .allow_domain_temporarily("old.reddit.com", 600, None, now)
+            .unwrap();
+        // Shortened to the 300s budget, which is then used up
+        assert_eq!(allow.expires_at, now + 300);
+        assert_eq!(allow.budget_remaining, 0);
+        assert!(!manager.is_domain_blocked("old.reddit.com").unwrap());
+        assert!(manager
+            .allow_domain_temporarily("reddit.com", 60, None, now)
+            .is_err());
+
+        // Expired allows block again
+        manager.start_session(300, Origin::Ui).unwrap();
+        manager.add_session_blocks(&["reddit.com"]).unwrap();
+        manager
+            .allow_domain_temporarily("reddit.com", 60, None, now - 120)
+            .unwrap();
+        assert!(manager.is_domain_blocked("reddit.com").unwrap());
+    }
+
+    #[test]
+    fn test_state_is_cached_until_invalidated() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(!manager.is_domain_blocked("distraction.example").unwrap());
+
+        // A rule written behind the manager's back isn't seen yet
+        {
+            let db_lock = db.lock().unwrap();
+            let conn = db_lock.connection();
+            let entertainment = Category::find_all(conn)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "Entertainment")
+                .unwrap();
+            Rule::create(
+                conn,
+                "distraction.example",
+                MatchType::Domain,
+                entertainment.id,
+                10,
+            )
+            .unwrap();
+        }
+        assert!(!manager.is_domain_blocked("distraction.example").unwrap());
+
+        manager.invalidate_cache();
+        assert!(manager.is_domain_blocked("distraction.example").unwrap());
+    }
+
+    #[test]
+    fn test_blocklist_update_diffs_changes() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        let version = manager.get_state().unwrap().blocklist_version;
+        assert_eq!(manager.blocklist_update().unwrap(), None);
+
+        let rule = {
+            let db_lock = db.lock().unwrap();
+            let conn = db_lock.connection();
+            let entertainment = Category::find_all(conn)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "Entertainment")
+                .unwrap();
+            Rule::create(
+                conn,
+                "distraction.example",
+                MatchType::Domain,
+                entertainment.id,
+                10,
+            )
+            .unwrap()
+        };
+        manager.invalidate_cache();
+
+        let update = manager.blocklist_update().unwrap().unwrap();
+        assert_eq!(update.from_version, version);
+        assert_eq!(update.version, version + 1);
+        assert_eq!(update.added, vec!["distraction.example".to_string()]);
+        assert!(update.removed.is_empty());
+        assert_eq!(manager.get_state().unwrap().blocklist_version, version + 1);
+
+        Rule::delete(db.lock().unwrap().connection(), rule.id).unwrap();
+        manager.invalidate_cache();
+        let update = manager.blocklist_update().unwrap().unwrap();
+        assert_eq!(update.removed, vec!["distraction.example".to_string()]);
+    }
+
+    #[test]
+    fn test_writes_refresh_cached_state() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(!manager.get_state().unwrap().active);
+
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(manager.get_state().unwrap().active);
+
+        manager.use_distraction_time(100, None, None).unwrap();
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 500);
+
+        manager.end_session(Origin::Ui).unwrap();
+        assert!(!manager.get_state().unwrap().active);
+    }
+
     #[test]
     fn test_session_duration_none_when_inactive() {
         let (db, _dir) = setup();
@@ -396,7 +2043,7 @@ mod tests {
         let (db, _dir) = setup();
         let manager = FocusManager::new(Arc::clone(&db));
 
-        manager.start_session(600).unwrap();
+        manager.start_session(600, Origin::Ui).unwrap();
 
         let state = manager.get_state().unwrap();
         assert!(state.active);
@@ -410,16 +2057,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_timed_session_reports_time_remaining() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        manager.start_session(600, Origin::Ui).unwrap();
+        assert!(manager.get_state().unwrap().time_remaining_secs.is_none());
+
+        manager
+            .start_timed_session(600, Some(1500), false, &[], Origin::Ui)
+            .unwrap();
+        let remaining = manager.get_state().unwrap().time_remaining_secs.unwrap();
+        assert!(
+            (1495..=1500).contains(&remaining),
+            "Just-started 25 min session should have ~1500s left, got {remaining}"
+        );
+    }
+
+    #[test]
+    fn test_end_expired_session() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let open_ended = manager.start_session(600, Origin::Ui).unwrap();
+        assert!(manager
+            .end_expired_session(open_ended.started_at + 100_000)
+            .unwrap()
+            .is_none());
+
+        let timed = manager
+            .start_timed_session(600, Some(1500), false, &[], Origin::Ui)
+            .unwrap();
+        assert!(manager
+            .end_expired_session(timed.started_at + 1499)
+            .unwrap()
+            .is_none());
+        assert!(manager
+            .end_expired_session(timed.started_at + 1500)
+            .unwrap()
+            .is_some());
+        assert!(!manager.get_state().unwrap().active);
+    }
+
+    #[test]
+    fn test_extend_focus_session_tops_up_budget() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(manager
+            .extend_focus_session(600, 300, Origin::Tray)
+            .unwrap()
+            .is_none());
+
+        manager
+            .start_timed_session(600, Some(1500), false, &[], Origin::Ui)
+            .unwrap();
+        let session = manager
+            .extend_focus_session(600, 300, Origin::Tray)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.planned_duration, Some(2100));
+        assert_eq!(session.distraction_budget, 900);
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 900);
+
+        let db = db.lock().unwrap();
+        let entries = audit_log::find_in_range(db.connection(), 0, i64::MAX).unwrap();
+        let extend = entries.iter().find(|e| e.action == "extend").unwrap();
+        assert_eq!(extend.detail.as_deref(), Some("+10 min, +5 min budget"));
+    }
+
+    #[test]
+    fn test_extend_refuses_open_ended_session() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.start_session(600, Origin::Ui).unwrap();
+
+        assert!(manager
+            .extend_focus_session(600, 300, Origin::Tray)
+            .is_err());
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 600);
+
+        let db = db.lock().unwrap();
+        let entries = audit_log::find_in_range(db.connection(), 0, i64::MAX).unwrap();
+        assert!(entries.iter().all(|e| e.action != "extend"));
+    }
+
+    #[test]
+    fn test_session_actions_are_audited_with_origin() {
+        let (db, _dir) = setup();
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let session = manager
+            .start_timed_session(600, Some(1500), false, &[], Origin::Tray)
+            .unwrap();
+        manager.extend_focus_session(600, 0, Origin::Cli).unwrap();
+        manager
+            .end_expired_session(session.started_at + 2100)
+            .unwrap();
+
+        let db = db.lock().unwrap();
+        let entries = audit_log::find_in_range(db.connection(), 0, i64::MAX).unwrap();
+        let mut actions: Vec<_> = entries
+            .iter()
+            .map(|e| (e.action.as_str(), e.origin.as_str(), e.detail.as_deref()))
+            .collect();
+        actions.sort_unstable();
+        assert_eq!(
+            actions,
+            vec![
+                ("complete", "system", None),
+                ("extend", "cli", Some("+10 min")),
+                ("start", "tray", Some("planned 25 min")),
+            ]
+        );
+        assert!(entries.iter().all(|e| e.session_id == session.id));
+    }
+
     #[test]
     fn test_session_duration_none_after_end() {
         let (db, _dir) = setup();
         let manager = FocusManager::new(Arc::clone(&db));
 
-        manager.start_session(600).unwrap();
+        manager.start_session(600, Origin::Ui).unwrap();
         let state = manager.get_state().unwrap();
         assert!(state.session_duration_secs.is_some());
 
-        manager.end_session().unwrap();
+        manager.end_session(Origin::Ui).unwrap();
 
         let state = manager.get_state().unwrap();
         assert!(!state.active);
@@ -439,6 +2202,271 @@ mod tests {
         assert!(state.active);
     }
 
+    fn save_session(db: &Arc<Mutex<Database>>, started_at: i64, planned: Option<i32>) -> i64 {
+        let db_lock = db.lock().unwrap();
+        let mut session = FocusSession::new(600, false);
+        session.started_at = started_at;
+        session.planned_duration = planned;
+        session.save(db_lock.connection()).unwrap();
+        session.id.unwrap()
+    }
+
+    #[test]
+    fn test_reconcile_timed_session_ends_at_planned_end() {
+        let (db, _dir) = setup();
+        let now = 1_700_000_000;
+        save_session(&db, now - 3 * 3600, Some(3600));
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let closed = manager.reconcile_stale_session(now).unwrap().unwrap();
+        assert_eq!(closed.ended_at, Some(now - 2 * 3600));
+        assert!(!manager.get_state().unwrap().active);
+    }
+
+    #[test]
+    fn test_reconcile_open_session_ends_at_last_activity() {
+        let (db, _dir) = setup();
+        let now = 1_700_000_000;
+        let started_at = now - 2 * SECS_PER_DAY;
+        save_session(&db, started_at, None);
+        {
+            let db_lock = db.lock().unwrap();
+            Activity::new(started_at + 600, 5, "app", Some("Code"), None)
+                .save(db_lock.connection())
+                .unwrap();
+        }
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let closed = manager.reconcile_stale_session(now).unwrap().unwrap();
+        assert_eq!(closed.ended_at, Some(started_at + 605));
+    }
+
+    #[test]
+    fn test_reconcile_resumes_sessions_within_window() {
+        let (db, _dir) = setup();
+        let now = 1_700_000_000;
+        save_session(&db, now - 600, Some(3600));
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(manager.reconcile_stale_session(now).unwrap().is_none());
+
+        manager.end_session(Origin::Ui).unwrap();
+        save_session(&db, now - 600, None);
+        assert!(manager.reconcile_stale_session(now).unwrap().is_none());
+    }
+
+    /// Monday 1970-01-05 at `hours`:`minutes` UTC.
+    fn monday_at(hours: i64, minutes: i64) -> i64 {
+        4 * SECS_PER_DAY + hours * 3600 + minutes * 60
+    }
+
+    fn save_schedule(db: &Arc<Mutex<Database>>, start: &str, end: &str) {
+        let db_lock = db.lock().unwrap();
+        let mut schedule = FocusSchedule::new("1", start, end, 600);
+        schedule.save(db_lock.connection()).unwrap();
+    }
+
+    #[test]
+    fn test_startup_mid_window_reports_missed_start() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let missed = manager.check_schedules_at(&Utc, monday_at(10, 30)).unwrap();
+        assert_eq!(
+            missed,
+            Some(MissedStart {
+                start_time: "09:00".into(),
+                late_secs: 90 * 60,
+            })
+        );
+        assert!(manager.get_state().unwrap().active);
+    }
+
+    #[test]
+    fn test_schedules_follow_local_time_across_dst() {
+        let (db, _dir) = setup();
+        {
+            let db_lock = db.lock().unwrap();
+            let mut schedule = FocusSchedule::new("7", "01:00", "05:00", 600);
+            schedule.save(db_lock.connection()).unwrap();
+        }
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        // 2026-03-29 02:00 UTC is 04:00 CEST, after clocks sprang forward at
+        // 02:00 CET. The window opened at 01:00 CET, two real hours earlier.
+        let now = 1_774_749_600;
+        let missed = manager.check_schedules_at(&CentralEurope, now).unwrap();
+        assert_eq!(
+            missed,
+            Some(MissedStart {
+                start_time: "01:00".into(),
+                late_secs: 2 * 3600,
+            })
+        );
+
+        // 03:30 UTC is 05:30 CEST, past the window even though it's 03:30 in UTC
+        manager
+            .check_schedules_at(&CentralEurope, now + 90 * 60)
+            .unwrap();
+        assert!(!manager.get_state().unwrap().active);
+    }
+
+    #[test]
+    fn test_on_time_start_is_not_missed() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        let missed = manager.check_schedules_at(&Utc, monday_at(9, 1)).unwrap();
+        assert_eq!(missed, None);
+        assert!(manager.get_state().unwrap().active);
+    }
+
+    #[test]
+    fn test_restart_mid_window_keeps_used_budget() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.check_schedules_at(&Utc, monday_at(9, 0)).unwrap();
+        assert_eq!(
+            manager.use_distraction_time(100, None, None).unwrap(),
+            Some(500)
+        );
+        drop(manager);
+
+        // The app was down for 45 minutes with nothing tracked, so startup
+        // closes the session and the schedule starts another
+        let manager = FocusManager::new(Arc::clone(&db));
+        assert!(manager
+            .reconcile_stale_session(monday_at(9, 45))
+            .unwrap()
+            .is_some());
+        manager.check_schedules_at(&Utc, monday_at(9, 45)).unwrap();
+        let state = manager.get_state().unwrap();
+        assert!(state.active);
+        assert_eq!(state.budget_remaining, 500);
+
+        // Nor does the restart reset the rate limit on distraction time
+        assert_eq!(
+            manager.use_distraction_time(100, None, None).unwrap(),
+            Some(500)
+        );
+    }
+
+    #[test]
+    fn test_ending_a_scheduled_session_by_hand_keeps_used_budget() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.check_schedules_at(&Utc, monday_at(9, 0)).unwrap();
+        manager.use_distraction_time(200, None, None).unwrap();
+        manager.end_session(Origin::Ui).unwrap();
+
+        manager.check_schedules_at(&Utc, monday_at(9, 1)).unwrap();
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 400);
+    }
+
+    #[test]
+    fn test_second_restart_in_a_window_counts_used_budget_once() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.check_schedules_at(&Utc, monday_at(9, 0)).unwrap();
+        manager.use_distraction_time(100, None, None).unwrap();
+
+        manager.end_session(Origin::Ui).unwrap();
+        manager.check_schedules_at(&Utc, monday_at(9, 1)).unwrap();
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 500);
+
+        manager.end_session(Origin::Ui).unwrap();
+        manager.check_schedules_at(&Utc, monday_at(9, 2)).unwrap();
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 500);
+    }
+
+    #[test]
+    fn test_skip_today_ends_the_session_for_the_day_only() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        save_schedule(&db, "14:00", "16:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.check_schedules_at(&Utc, monday_at(9, 0)).unwrap();
+        let status = |now| {
+            let db_lock = db.lock().unwrap();
+            FocusManager::skip_today_status_at(db_lock.connection(), &Utc, now).unwrap()
+        };
+        let skip = |now, on| {
+            let db_lock = db.lock().unwrap();
+            FocusManager::skip_today_at(db_lock.connection(), &Utc, now, on, Origin::Tray)
+        };
+        assert_eq!(status(monday_at(9, 30)), Some(false));
+
+        assert!(skip(monday_at(9, 30), true).unwrap().is_some());
+        manager.check_schedules_at(&Utc, monday_at(9, 31)).unwrap();
+        assert!(!manager.get_state().unwrap().active);
+        assert_eq!(status(monday_at(9, 31)), Some(true));
+
+        // Only the skipped window is off; the afternoon one still runs
+        manager.check_schedules_at(&Utc, monday_at(14, 0)).unwrap();
+        assert!(manager.get_state().unwrap().active);
+        manager.check_schedules_at(&Utc, monday_at(16, 0)).unwrap();
+
+        // Taking the skip back, or a week later, it runs as usual
+        assert!(skip(monday_at(10, 0), false).unwrap().is_none());
+        manager.check_schedules_at(&Utc, monday_at(10, 0)).unwrap();
+        assert!(manager.get_state().unwrap().active);
+        skip(monday_at(10, 1), true).unwrap();
+        manager
+            .check_schedules_at(&Utc, monday_at(9, 0) + 7 * SECS_PER_DAY)
+            .unwrap();
+        assert!(manager.get_state().unwrap().active);
+
+        // Nothing left to skip once the day's windows are over
+        assert_eq!(status(monday_at(17, 0) + 7 * SECS_PER_DAY), None);
+        assert!(matches!(
+            skip(monday_at(17, 0) + 7 * SECS_PER_DAY, true),
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_used_budget_does_not_carry_into_the_next_window() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let manager = FocusManager::new(Arc::clone(&db));
+        manager.check_schedules_at(&Utc, monday_at(9, 0)).unwrap();
+        manager.use_distraction_time(200, None, None).unwrap();
+        manager.check_schedules_at(&Utc, monday_at(12, 30)).unwrap();
+        assert!(!manager.get_state().unwrap().active);
+
+        manager
+            .check_schedules_at(&Utc, monday_at(9, 0) + 7 * SECS_PER_DAY)
+            .unwrap();
+        assert_eq!(manager.get_state().unwrap().budget_remaining, 600);
+    }
+
+    #[test]
+    fn test_stale_scheduled_session_is_replaced() {
+        let (db, _dir) = setup();
+        save_schedule(&db, "09:00", "12:00");
+        let stale_id = {
+            let db_lock = db.lock().unwrap();
+            let mut stale = FocusSession::new(600, true);
+            stale.started_at = monday_at(9, 0) - 7 * SECS_PER_DAY;
+            stale.save(db_lock.connection()).unwrap();
+            stale.id
+        };
+        let manager = FocusManager::new(Arc::clone(&db));
+
+        manager.check_schedules_at(&Utc, monday_at(9, 30)).unwrap();
+
+        let db_lock = db.lock().unwrap();
+        let active = FocusSession::find_active(db_lock.connection())
+            .unwrap()
+            .unwrap();
+        assert_ne!(active.id, stale_id);
+        assert_eq!(active.started_at, monday_at(9, 30));
+    }
+
     #[test]
     fn test_get_current_day_and_time_format() {
         let (day, time) = get_current_day_and_time();
@@ -529,7 +2557,7 @@ mod tests {
         let manager = FocusManager::new(Arc::clone(&db));
 
         // Start a manual session with different budget
-        manager.start_session(600).unwrap();
+        manager.start_session(600, Origin::Ui).unwrap();
 
         // Check schedules should not affect manual session
         manager.check_schedules().unwrap();
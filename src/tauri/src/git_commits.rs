@@ -0,0 +1,270 @@
+use crate::error::AppError;
+use crate::models::{Activity, Setting};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+const REPOSITORIES_SETTING: &str = "git.repositories";
+
+/// Productive time further back than this from a commit isn't credited to
+/// it, so the first commit after a quiet stretch doesn't claim the whole day.
+const MAX_COMMIT_WINDOW_SECS: i64 = 2 * 60 * 60;
+
+/// `git log` field separator (ASCII unit separator), which can't appear in
+/// ref names and won't in practice appear in commit subjects.
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// A commit found in one of the configured repositories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub repo: String,
+    pub hash: String,
+    /// Committer time, Unix seconds.
+    pub timestamp: i64,
+    /// Branch the commit was reached from, e.g. `main` or `origin/feature`.
+    pub branch: Option<String>,
+    pub summary: String,
+}
+
+/// A commit and the productive time spent since the previous commit in the
+/// same repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitActivity {
+    pub commit: Commit,
+    pub active_secs: i32,
+}
+
+/// Local repositories to scan for commits.
+pub fn repositories(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    Ok(Setting::get(conn, REPOSITORIES_SETTING)?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+pub fn set_repositories(conn: &Connection, repos: &[String]) -> Result<(), AppError> {
+    let value = serde_json::to_string(repos).map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Setting::set(conn, REPOSITORIES_SETTING, &value)?)
+}
+
+fn branch_name(source: &str) -> Option<String> {
+    let source = source.trim();
+    let name = source
+        .strip_prefix("refs/heads/")
+        .or_else(|| source.strip_prefix("refs/remotes/"))
+        .unwrap_or(source);
+    (!name.is_empty() && name != "HEAD").then(|| name.to_string())
+}
+
+/// Parse `git log --source --format=%H%x1f%ct%x1f%S%x1f%s` output, skipping
+/// malformed lines.
+pub fn parse_log(repo: &str, output: &str) -> Vec<Commit> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, FIELD_SEPARATOR);
+            let hash = fields.next()?.trim();
+            let timestamp = fields.next()?.trim().parse().ok()?;
+            let branch = branch_name(fields.next()?);
+            let summary = fields.next().unwrap_or_default().trim();
+            (!hash.is_empty()).then(|| Commit {
+                repo: repo.to_string(),
+                hash: hash.to_string(),
+                timestamp,
+                branch,
+                summary: summary.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn git(repo: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Commits in `repo` committed in `[since, until]`, limited to the repo's
+/// configured `user.email` when there is one. Returns nothing if the
+/// repository can't be read.
+pub fn scan(repo: &str, since: i64, until: i64) -> Vec<Commit> {
+    let since = format!("--since=@{since}");
+    let until = format!("--until=@{until}");
+    let author = git(repo, &["config", "user.email"])
+        .map(|email| email.trim().to_string())
+        .filter(|email| !email.is_empty())
+        .map(|email| format!("--author={email}"));
+
+    let mut args = vec![
+        "log",
+        "--all",
+        "--source",
+        "--no-merges",
+        "--format=%H%x1f%ct%x1f%S%x1f%s",
+        since.as_str(),
+        until.as_str(),
+    ];
+    args.extend(author.as_deref());
+
+    if let Some(output) = git(repo, &args) {
+        parse_log(repo, &output)
+    } else {
+        log::warn!("Could not read commits from {repo}");
+        Vec::new()
+    }
+}
+
+/// For each commit, the span of time leading up to it: from the previous
+/// commit in the same repository, at most `MAX_COMMIT_WINDOW_SECS` back.
+/// Returned oldest first.
+fn commit_windows(mut commits: Vec<Commit>) -> Vec<(Commit, i64)> {
+    commits.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.hash.cmp(&b.hash))
+    });
+    commits.dedup_by(|a, b| a.repo == b.repo && a.hash == b.hash);
+
+    let mut previous: HashMap<String, i64> = HashMap::new();
+    commits
+        .into_iter()
+        .map(|commit| {
+            let earliest = commit.timestamp - MAX_COMMIT_WINDOW_SECS;
+            let start = previous
+                .insert(commit.repo.clone(), commit.timestamp)
+                .map_or(earliest, |prev| prev.max(earliest));
+            (commit, start)
+        })
+        .collect()
+}
+
+/// Commits from `scan_all` made at or after `start`, newest first, each with
+/// the productive time tracked since the previous one.
+pub fn commit_activity(
+    conn: &Connection,
+    commits: Vec<Commit>,
+    start: i64,
+) -> rusqlite::Result<Vec<CommitActivity>> {
+    let mut activity = Vec::new();
+    for (commit, window_start) in commit_windows(commits) {
+        if commit.timestamp < start {
+            continue;
+        }
+        let (active_secs, _, _) =
+            Activity::productivity_totals(conn, window_start, commit.timestamp + 1)?;
+        activity.push(CommitActivity {
+            commit,
+            active_secs,
+        });
+    }
+    activity.reverse();
+    Ok(activity)
+}
+
+/// Commits from every configured repository between `start -
+/// MAX_COMMIT_WINDOW_SECS` and `end`; the extra lead-in finds each commit's
+/// predecessor.
+pub fn scan_all(repos: &[String], start: i64, end: i64) -> Vec<Commit> {
+    repos
+        .iter()
+        .filter(|repo| Path::new(repo).is_dir())
+        .flat_map(|repo| scan(repo, start - MAX_COMMIT_WINDOW_SECS, end))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    fn commit(repo: &str, hash: &str, timestamp: i64) -> Commit {
+        Commit {
+            repo: repo.to_string(),
+            hash: hash.to_string(),
+            timestamp,
+            branch: Some("main".to_string()),
+            summary: format!("commit {hash}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_log() {
+        let output = "abc\u{1f}1700000000\u{1f}refs/heads/main\u{1f}Fix: a | b\n\
+                      def\u{1f}1700000100\u{1f}refs/remotes/origin/feature\u{1f}\n\
+                      garbage line\n";
+        let commits = parse_log("/repo", output);
+        assert_eq!(commits.len(), 2);
+        let (fix, feature) = (commits.first().unwrap(), commits.get(1).unwrap());
+        assert_eq!(fix.branch.as_deref(), Some("main"));
+        assert_eq!(fix.summary, "Fix: a | b");
+        assert_eq!(feature.branch.as_deref(), Some("origin/feature"));
+        assert_eq!(feature.timestamp, 1_700_000_100);
+    }
+
+    #[test]
+    fn test_commit_windows_per_repo() {
+        let windows = commit_windows(vec![
+            commit("/b", "b1", 10_000),
+            commit("/a", "a2", 11_000),
+            commit("/a", "a1", 9_000),
+            commit("/a", "a1", 9_000),
+        ]);
+        let spans: Vec<_> = windows
+            .iter()
+            .map(|(c, start)| (c.hash.as_str(), *start))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("a1", 9_000 - MAX_COMMIT_WINDOW_SECS),
+                ("b1", 10_000 - MAX_COMMIT_WINDOW_SECS),
+                ("a2", 9_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_activity_counts_productive_time() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        for timestamp in [9_500, 10_500, 10_900] {
+            let mut activity = Activity::new(timestamp, 60, "app", Some("code"), None);
+            activity.category_id = Some(coding.id);
+            activity.save(conn).unwrap();
+        }
+
+        let activity = commit_activity(
+            conn,
+            vec![commit("/a", "a1", 10_000), commit("/a", "a2", 11_000)],
+            10_000,
+        )
+        .unwrap();
+        let totals: Vec<_> = activity
+            .iter()
+            .map(|a| (a.commit.hash.as_str(), a.active_secs))
+            .collect();
+        assert_eq!(totals, vec![("a2", 120), ("a1", 60)]);
+    }
+
+    #[test]
+    fn test_repositories_setting() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(repositories(conn).unwrap().is_empty());
+        set_repositories(conn, &["/home/me/foxus".to_string()]).unwrap();
+        assert_eq!(repositories(conn).unwrap(), vec!["/home/me/foxus"]);
+    }
+}
@@ -0,0 +1,529 @@
+//! Favicons for top domains and icons for top apps, cached in the data
+//! directory so the dashboard's top lists can show them offline.
+//!
+//! A daily job resolves icons for the week's top sources: favicons are
+//! fetched from the domain itself, app icons come from the installed app
+//! (the bundle on macOS, the desktop entry and icon theme on Linux). Misses
+//! are cached too, so nothing is retried until the entry goes stale.
+
+use crate::constants::SECS_PER_DAY;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::get_data_dir;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Setting};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::{debug, error, info};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const LAST_REFRESH_SETTING: &str = "icons.last_refresh";
+
+const ICONS_DIR_NAME: &str = "icons";
+
+/// How many of the week's top domains and apps get icons.
+const TOP_LIMIT: i64 = 20;
+
+/// Cached icons and misses older than this are resolved again.
+const MAX_AGE: Duration = Duration::from_hours(30 * 24);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Larger responses are not icons worth keeping.
+const MAX_ICON_BYTES: u64 = 256 * 1024;
+
+/// Paths tried on each domain, in order.
+const FAVICON_PATHS: [&str; 2] = ["/favicon.ico", "/apple-touch-icon.png"];
+
+const ICON_EXTENSIONS: [&str; 5] = ["png", "ico", "svg", "gif", "jpg"];
+
+/// Marks a name that had no icon when last resolved.
+const MISS_EXTENSION: &str = "miss";
+
+const MAX_KEY_LEN: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconKind {
+    Domain,
+    App,
+}
+
+impl IconKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "domain" => Some(Self::Domain),
+            "app" => Some(Self::App),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Domain => "domain",
+            Self::App => "app",
+        }
+    }
+}
+
+/// Icon image bytes and the file extension matching their format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icon {
+    pub bytes: Vec<u8>,
+    pub extension: &'static str,
+}
+
+impl Icon {
+    /// Wrap `bytes` if they look like an image format browsers can show.
+    pub fn sniff(bytes: Vec<u8>) -> Option<Self> {
+        let extension = if bytes.starts_with(b"\x89PNG") {
+            "png"
+        } else if bytes.starts_with(&[0, 0, 1, 0]) {
+            "ico"
+        } else if bytes.starts_with(b"GIF8") {
+            "gif"
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            "jpg"
+        } else if String::from_utf8_lossy(bytes.get(..512).unwrap_or(&bytes)).contains("<svg") {
+            "svg"
+        } else {
+            return None;
+        };
+        Some(Self { bytes, extension })
+    }
+}
+
+fn mime_type(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "gif" => "image/gif",
+        _ => "image/jpeg",
+    }
+}
+
+/// Where icons are cached, next to the database.
+pub fn icons_dir() -> Result<PathBuf, AppError> {
+    let data_dir = get_data_dir().map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(data_dir.join(ICONS_DIR_NAME))
+}
+
+/// File name stem for a cached icon; `None` for names with nothing usable.
+fn cache_key(kind: IconKind, name: &str) -> Option<String> {
+    let key: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_KEY_LEN)
+        .collect();
+    if key.trim_matches(['.', '_']).is_empty() {
+        return None;
+    }
+    Some(format!("{}-{key}", kind.as_str()))
+}
+
+/// The cached file for `key`, an icon or a miss marker.
+fn cached_file(dir: &Path, key: &str) -> Option<PathBuf> {
+    ICON_EXTENSIONS
+        .iter()
+        .chain([&MISS_EXTENSION])
+        .map(|extension| dir.join(format!("{key}.{extension}")))
+        .find(|path| path.is_file())
+}
+
+fn is_fresh(path: &Path) -> bool {
+    path.metadata()
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < MAX_AGE)
+}
+
+/// The cached icon for `name` as a data URL, if there is one.
+pub fn read_icon(dir: &Path, kind: IconKind, name: &str) -> Option<String> {
+    let path = cached_file(dir, &cache_key(kind, name)?)?;
+    let extension = path.extension()?.to_str()?;
+    if extension == MISS_EXTENSION {
+        return None;
+    }
+    let bytes = std::fs::read(&path).ok()?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime_type(extension),
+        STANDARD.encode(bytes)
+    ))
+}
+
+/// Replace whatever is cached for `key` with `icon`, or a miss marker.
+fn store(dir: &Path, key: &str, icon: Option<&Icon>) -> io::Result<()> {
+    while let Some(old) = cached_file(dir, key) {
+        std::fs::remove_file(old)?;
+    }
+    match icon {
+        Some(icon) => std::fs::write(dir.join(format!("{key}.{}", icon.extension)), &icon.bytes),
+        None => std::fs::write(dir.join(format!("{key}.{MISS_EXTENSION}")), b""),
+    }
+}
+
+/// Resolve and cache icons for `names` that have no fresh cache entry.
+/// Returns how many icons were found.
+pub fn refresh_icons(
+    dir: &Path,
+    names: &[(IconKind, String)],
+    resolve: impl Fn(IconKind, &str) -> Option<Icon>,
+) -> io::Result<usize> {
+    std::fs::create_dir_all(dir)?;
+    let mut found = 0;
+    for (kind, name) in names {
+        let Some(key) = cache_key(*kind, name) else {
+            continue;
+        };
+        if cached_file(dir, &key).is_some_and(|path| is_fresh(&path)) {
+            continue;
+        }
+        let icon = resolve(*kind, name);
+        found += usize::from(icon.is_some());
+        store(dir, &key, icon.as_ref())?;
+    }
+    Ok(found)
+}
+
+/// Look up an icon from the network or the installed app.
+pub fn resolve(kind: IconKind, name: &str) -> Option<Icon> {
+    match kind {
+        IconKind::Domain => fetch_favicon(name),
+        IconKind::App => app_icon(name),
+    }
+}
+
+/// Only public host names are fetched, never local services.
+fn is_fetchable_domain(domain: &str) -> bool {
+    domain.contains('.')
+        && domain.parse::<std::net::IpAddr>().is_err()
+        && domain.rsplit('.').next() != Some("local")
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+}
+
+fn fetch_favicon(domain: &str) -> Option<Icon> {
+    if !is_fetchable_domain(domain) {
+        return None;
+    }
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    FAVICON_PATHS.iter().find_map(|path| {
+        let response = agent
+            .get(&format!("https://{domain}{path}"))
+            .set("User-Agent", concat!("foxus/", env!("CARGO_PKG_VERSION")))
+            .call()
+            .map_err(|e| debug!("No favicon at {domain}{path}: {e}"))
+            .ok()?;
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_ICON_BYTES)
+            .read_to_end(&mut bytes)
+            .ok()?;
+        Icon::sniff(bytes)
+    })
+}
+
+/// Icon of an app bundle in the usual Applications folders, converted to PNG.
+#[cfg(target_os = "macos")]
+fn app_icon(name: &str) -> Option<Icon> {
+    use std::process::Command;
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let bundle = [
+        Some(PathBuf::from("/Applications")),
+        Some(PathBuf::from("/Applications/Utilities")),
+        Some(PathBuf::from("/System/Applications")),
+        Some(PathBuf::from("/System/Applications/Utilities")),
+        home.map(|home| home.join("Applications")),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|dir| dir.join(format!("{name}.app")))
+    .find(|bundle| bundle.is_dir())?;
+
+    let output = Command::new("plutil")
+        .args(["-extract", "CFBundleIconFile", "raw", "-o", "-"])
+        .arg(bundle.join("Contents/Info.plist"))
+        .output()
+        .ok()?;
+    let icon_file = String::from_utf8(output.stdout).ok()?;
+    let icon_file = icon_file.trim();
+    if !output.status.success() || icon_file.is_empty() {
+        return None;
+    }
+    let mut icns = bundle.join("Contents/Resources").join(icon_file);
+    if icns.extension().is_none() {
+        icns.set_extension("icns");
+    }
+
+    let png = std::env::temp_dir().join(format!("foxus-icon-{}.png", std::process::id()));
+    let converted = Command::new("sips")
+        .args(["-s", "format", "png", "-Z", "64"])
+        .arg(&icns)
+        .arg("--out")
+        .arg(&png)
+        .output()
+        .is_ok_and(|output| output.status.success());
+    let bytes = converted.then(|| std::fs::read(&png).ok()).flatten();
+    let _ = std::fs::remove_file(&png);
+    Icon::sniff(bytes?)
+}
+
+/// Icon named by the app's desktop entry, looked up in the icon theme.
+#[cfg(target_os = "linux")]
+fn app_icon(name: &str) -> Option<Icon> {
+    let data_dirs = linux::data_dirs();
+    let icon = data_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir.join("applications")).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+        .find_map(|path| {
+            let stem = path.file_stem()?.to_str()?.to_string();
+            let contents = std::fs::read_to_string(&path).ok()?;
+            linux::desktop_entry_icon(&contents, &stem, name)
+        })?;
+    let path = linux::icon_path(&data_dirs, &icon)?;
+    Icon::sniff(std::fs::read(path).ok()?)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn app_icon(_name: &str) -> Option<Icon> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::path::{Path, PathBuf};
+
+    const ICON_SIZES: [&str; 5] = ["64x64", "48x48", "128x128", "256x256", "scalable"];
+
+    /// XDG data directories, user first.
+    pub fn data_dirs() -> Vec<PathBuf> {
+        let home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+        let system = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        home.into_iter()
+            .chain(system.split(':').map(PathBuf::from))
+            .collect()
+    }
+
+    /// The `Icon=` of a desktop entry for `app_name`, matched against the
+    /// file name, `StartupWMClass` (the tracker records `WM_CLASS`), or `Name`.
+    pub fn desktop_entry_icon(contents: &str, file_stem: &str, app_name: &str) -> Option<String> {
+        let mut in_entry = false;
+        let mut matches = file_stem.eq_ignore_ascii_case(app_name);
+        let mut icon = None;
+        for line in contents.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_entry {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "StartupWMClass" | "Name" if value.trim().eq_ignore_ascii_case(app_name) => {
+                    matches = true;
+                }
+                "Icon" => icon = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+        icon.filter(|icon| matches && !icon.is_empty())
+    }
+
+    /// Resolve an icon name through the hicolor theme and pixmaps.
+    pub fn icon_path(data_dirs: &[PathBuf], icon: &str) -> Option<PathBuf> {
+        let path = Path::new(icon);
+        if path.is_absolute() {
+            return path.is_file().then(|| path.to_path_buf());
+        }
+        let themed = data_dirs.iter().flat_map(|dir| {
+            ICON_SIZES
+                .iter()
+                .map(move |size| dir.join("icons/hicolor").join(size).join("apps"))
+                .chain([dir.join("pixmaps")])
+        });
+        themed
+            .flat_map(|dir| ["png", "svg"].map(|ext| dir.join(format!("{icon}.{ext}"))))
+            .find(|path| path.is_file())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const ENTRY: &str = "[Desktop Entry]\nName=Visual Studio Code\nIcon=vscode\nStartupWMClass=Code\n\n[Desktop Action new]\nIcon=other\n";
+
+        #[test]
+        fn test_desktop_entry_icon() {
+            assert_eq!(
+                desktop_entry_icon(ENTRY, "code", "Code").as_deref(),
+                Some("vscode")
+            );
+            assert_eq!(
+                desktop_entry_icon(ENTRY, "editor", "visual studio code").as_deref(),
+                Some("vscode")
+            );
+            assert_eq!(desktop_entry_icon(ENTRY, "editor", "Slack"), None);
+        }
+
+        #[test]
+        fn test_icon_path() {
+            let dir = tempfile::tempdir().unwrap();
+            let apps = dir.path().join("icons/hicolor/48x48/apps");
+            std::fs::create_dir_all(&apps).unwrap();
+            std::fs::write(apps.join("vscode.png"), b"png").unwrap();
+            let dirs = [dir.path().to_path_buf()];
+            assert_eq!(icon_path(&dirs, "vscode"), Some(apps.join("vscode.png")));
+            assert_eq!(icon_path(&dirs, "missing"), None);
+        }
+    }
+}
+
+/// Refresh cached icons for the week's top domains and apps once a day.
+pub fn check_icon_cache(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let (today, _, _) = local_today();
+    let now = current_timestamp();
+
+    let names = with_connection(&db, |conn| {
+        if Setting::get(conn, LAST_REFRESH_SETTING)?.as_deref() == Some(today.as_str()) {
+            return Ok(None);
+        }
+        Setting::set(conn, LAST_REFRESH_SETTING, &today)?;
+        let start = now - 7 * SECS_PER_DAY;
+        let domains = Activity::top_domains(conn, start, now, None, TOP_LIMIT)?;
+        let apps = Activity::top_apps(conn, start, now, TOP_LIMIT)?;
+        Ok(Some(
+            domains
+                .into_iter()
+                .map(|(domain, _, _)| (IconKind::Domain, domain))
+                .chain(apps.into_iter().map(|(app, _)| (IconKind::App, app)))
+                .collect::<Vec<_>>(),
+        ))
+    });
+    let names = match names {
+        Ok(Some(names)) => names,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to load top sources for icons: {e}");
+            return;
+        }
+    };
+    let dir = match icons_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            error!("Failed to locate icon cache: {e}");
+            return;
+        }
+    };
+
+    // Fetching favicons can take a while; keep it off the scheduler thread
+    thread::spawn(move || match refresh_icons(&dir, &names, resolve) {
+        Ok(0) => {}
+        Ok(found) => info!("Cached {found} new icons"),
+        Err(e) => error!("Failed to update icon cache: {e}"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nrest";
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(Icon::sniff(PNG.to_vec()).unwrap().extension, "png");
+        assert_eq!(Icon::sniff(vec![0, 0, 1, 0, 1]).unwrap().extension, "ico");
+        assert_eq!(
+            Icon::sniff(b"<?xml version=\"1.0\"?><svg></svg>".to_vec())
+                .unwrap()
+                .extension,
+            "svg"
+        );
+        assert!(Icon::sniff(b"<!doctype html>".to_vec()).is_none());
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key(IconKind::App, " Google Chrome ").as_deref(),
+            Some("app-google_chrome")
+        );
+        assert_eq!(
+            cache_key(IconKind::Domain, "../etc").as_deref(),
+            Some("domain-.._etc")
+        );
+        assert_eq!(cache_key(IconKind::App, "/"), None);
+        assert_eq!(cache_key(IconKind::App, ".."), None);
+    }
+
+    #[test]
+    fn test_is_fetchable_domain() {
+        assert!(is_fetchable_domain("github.com"));
+        assert!(!is_fetchable_domain("localhost"));
+        assert!(!is_fetchable_domain("192.168.1.1"));
+        assert!(!is_fetchable_domain("printer.local"));
+        assert!(!is_fetchable_domain("evil.com/path"));
+    }
+
+    #[test]
+    fn test_refresh_caches_icons_and_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let calls = Cell::new(0);
+        let resolve = |kind: IconKind, name: &str| {
+            calls.set(calls.get() + 1);
+            (kind == IconKind::Domain && name == "github.com").then(|| Icon {
+                bytes: PNG.to_vec(),
+                extension: "png",
+            })
+        };
+        let names = [
+            (IconKind::Domain, "github.com".to_string()),
+            (IconKind::App, "Slack".to_string()),
+        ];
+
+        assert_eq!(refresh_icons(dir.path(), &names, resolve).unwrap(), 1);
+        assert_eq!(calls.get(), 2);
+        let url = read_icon(dir.path(), IconKind::Domain, "github.com").unwrap();
+        assert!(url.starts_with("data:image/png;base64,"));
+        assert_eq!(read_icon(dir.path(), IconKind::App, "Slack"), None);
+
+        // Both entries are fresh, including the miss
+        assert_eq!(refresh_icons(dir.path(), &names, resolve).unwrap(), 0);
+        assert_eq!(calls.get(), 2);
+    }
+}
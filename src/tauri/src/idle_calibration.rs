@@ -0,0 +1,194 @@
+//! Guided check of the platform idle clock. The user keeps using the
+//! computer for one phase and leaves it alone for the next; the readings
+//! show whether idle detection works (some Wayland setups always report 0)
+//! and what threshold suits the clock's resolution.
+
+use crate::models::focus_session::current_timestamp;
+use crate::models::Setting;
+use crate::platform::PlatformTracker;
+use rusqlite::{Connection, Result};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+const THRESHOLD_SETTING: &str = "idle.threshold_secs";
+const WORKING_SETTING: &str = "idle.detection_working";
+const CALIBRATED_AT_SETTING: &str = "idle.calibrated_at";
+
+/// Length of each phase.
+pub const PHASE_SECS: u64 = 15;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Highest reading while the user is active that still means the clock resets on input.
+const ACTIVE_TOLERANCE_SECS: u64 = 5;
+
+/// Lowest threshold recommended, matching the tracker's default.
+pub const MIN_IDLE_THRESHOLD_SECS: u64 = 120;
+
+pub const MAX_IDLE_THRESHOLD_SECS: u64 = 15 * 60;
+
+/// Threshold as a multiple of the clock's coarsest step, so one late tick
+/// never marks the user idle.
+const STEP_MARGIN: u64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationPhase {
+    /// The user keeps moving the mouse or typing.
+    Active,
+    /// The user leaves the keyboard and mouse alone.
+    Idle,
+}
+
+impl CalibrationPhase {
+    pub fn parse(phase: &str) -> Option<Self> {
+        match phase {
+            "active" => Some(Self::Active),
+            "idle" => Some(Self::Idle),
+            _ => None,
+        }
+    }
+}
+
+/// Readings from the active phase, kept until the idle phase runs.
+#[derive(Default)]
+pub struct IdleCalibrationState(pub Mutex<Option<Vec<u64>>>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdleCalibration {
+    /// Whether the idle clock resets on input and climbs without it.
+    pub working: bool,
+    pub active_max_secs: u64,
+    pub idle_max_secs: u64,
+    /// `None` when idle detection is broken and no threshold would help.
+    pub recommended_threshold_secs: Option<u64>,
+}
+
+/// Read the idle clock once a second for `PHASE_SECS`.
+pub fn sample(platform: &dyn PlatformTracker) -> Vec<u64> {
+    let mut readings = Vec::new();
+    for _ in 0..PHASE_SECS {
+        thread::sleep(SAMPLE_INTERVAL);
+        readings.push(platform.get_idle_time_secs());
+    }
+    readings
+}
+
+/// Judge the idle clock from readings taken while active and while idle.
+pub fn analyze(active: &[u64], idle: &[u64]) -> IdleCalibration {
+    let active_max_secs = active.iter().copied().max().unwrap_or(0);
+    let idle_max_secs = idle.iter().copied().max().unwrap_or(0);
+    let working = active_max_secs <= ACTIVE_TOLERANCE_SECS && idle_max_secs >= PHASE_SECS / 2;
+
+    let coarsest_step = idle
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [earlier, later] => Some(later.saturating_sub(*earlier)),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let recommended_threshold_secs = working.then(|| {
+        (coarsest_step.max(active_max_secs) * STEP_MARGIN)
+            .clamp(MIN_IDLE_THRESHOLD_SECS, MAX_IDLE_THRESHOLD_SECS)
+    });
+
+    IdleCalibration {
+        working,
+        active_max_secs,
+        idle_max_secs,
+        recommended_threshold_secs,
+    }
+}
+
+/// Idle detection settings from the last calibration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IdleSettings {
+    /// Overrides the tracker's default threshold.
+    pub threshold_secs: Option<u64>,
+    /// `None` until a calibration ran.
+    pub detection_working: Option<bool>,
+    pub calibrated_at: Option<i64>,
+}
+
+impl IdleSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            threshold_secs: Setting::get(conn, THRESHOLD_SETTING)?.and_then(|v| v.parse().ok()),
+            detection_working: Setting::get(conn, WORKING_SETTING)?.map(|v| v == "true"),
+            calibrated_at: Setting::get(conn, CALIBRATED_AT_SETTING)?.and_then(|v| v.parse().ok()),
+        })
+    }
+
+    /// Record a calibration, switching to its recommended threshold if `apply`.
+    pub fn record(conn: &Connection, calibration: &IdleCalibration, apply: bool) -> Result<Self> {
+        Setting::set(conn, WORKING_SETTING, &calibration.working.to_string())?;
+        Setting::set(
+            conn,
+            CALIBRATED_AT_SETTING,
+            &current_timestamp().to_string(),
+        )?;
+        if let Some(threshold) = calibration.recommended_threshold_secs.filter(|_| apply) {
+            Setting::set(conn, THRESHOLD_SETTING, &threshold.to_string())?;
+        }
+        Self::load(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn climbing(step: u64) -> Vec<u64> {
+        (1..=PHASE_SECS).map(|secs| secs / step * step).collect()
+    }
+
+    #[test]
+    fn test_working_clock_keeps_default_threshold() {
+        let calibration = analyze(&[0, 1, 0, 2, 0], &climbing(1));
+        assert!(calibration.working);
+        assert_eq!(calibration.idle_max_secs, PHASE_SECS);
+        assert_eq!(
+            calibration.recommended_threshold_secs,
+            Some(MIN_IDLE_THRESHOLD_SECS)
+        );
+    }
+
+    #[test]
+    fn test_clock_stuck_at_zero_is_broken() {
+        let calibration = analyze(&[0, 0, 0], &[0; 15]);
+        assert!(!calibration.working);
+        assert_eq!(calibration.recommended_threshold_secs, None);
+    }
+
+    #[test]
+    fn test_clock_that_never_resets_is_broken() {
+        let calibration = analyze(&[300, 301, 302], &[303, 304]);
+        assert!(!calibration.working);
+    }
+
+    #[test]
+    fn test_coarse_clock_raises_threshold() {
+        let idle = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 60];
+        let calibration = analyze(&[0, 0], &idle);
+        assert!(calibration.working);
+        assert_eq!(calibration.recommended_threshold_secs, Some(240));
+    }
+
+    #[test]
+    fn test_record_applies_threshold_only_when_asked() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert_eq!(IdleSettings::load(conn).unwrap(), IdleSettings::default());
+
+        let calibration = analyze(&[0], &climbing(10));
+        let settings = IdleSettings::record(conn, &calibration, false).unwrap();
+        assert_eq!(settings.detection_working, Some(true));
+        assert_eq!(settings.threshold_secs, None);
+        assert!(settings.calibrated_at.is_some());
+
+        let settings = IdleSettings::record(conn, &calibration, true).unwrap();
+        assert_eq!(settings.threshold_secs, Some(MIN_IDLE_THRESHOLD_SECS));
+    }
+}
@@ -0,0 +1,369 @@
+//! Weekly coaching insights: heuristics over last week's activity, such as
+//! distractions climbing late at night or one app cutting the longest focus
+//! blocks short. Each kind is stored once per week for the UI to show until
+//! the user acknowledges or dismisses it.
+
+use crate::constants::MAX_ACTIVITY_GAP_SECS;
+use crate::db::{with_connection, Database};
+use crate::local_time::{day_bounds_in, local_today, week_start};
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Category, Setting};
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone, Timelike};
+use log::{error, info};
+use rusqlite::{params, Connection, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const LAST_RUN_SETTING: &str = "insights.last_run";
+
+/// Local hour from which time counts as late at night.
+const LATE_HOUR: u32 = 21;
+
+/// Late-night time needed before its share of distraction means anything.
+const MIN_LATE_SECS: i64 = 30 * 60;
+
+/// A productive stretch at least this long counts as a focus block.
+const LONG_BLOCK_SECS: i64 = 25 * 60;
+
+/// Blocks one app must have ended before it's called out.
+const MIN_INTERRUPTIONS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsightKind {
+    LateDistraction,
+    FocusInterrupter,
+}
+
+impl InsightKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LateDistraction => "late_distraction",
+            Self::FocusInterrupter => "focus_interrupter",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsightStatus {
+    Acknowledged,
+    Dismissed,
+}
+
+impl InsightStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Acknowledged => "acknowledged",
+            Self::Dismissed => "dismissed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insight {
+    pub id: i64,
+    pub kind: String,
+    /// First day (YYYY-MM-DD) of the week it is about.
+    pub week_start: String,
+    pub message: String,
+    pub created_at: i64,
+    pub status: String,
+}
+
+const COLUMNS: &str = "id, kind, week_start, message, created_at, status";
+
+impl Insight {
+    fn from_row(row: &rusqlite::Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            week_start: row.get(2)?,
+            message: row.get(3)?,
+            created_at: row.get(4)?,
+            status: row.get(5)?,
+        })
+    }
+}
+
+/// "Your distracting time doubles after 9pm": the distracting share of
+/// time from `LATE_HOUR` on, against the rest of the day.
+fn late_distraction<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    start: i64,
+    end: i64,
+) -> Result<Option<String>> {
+    // (distracting, total) seconds: before and from LATE_HOUR
+    let (mut early, mut late) = ((0, 0), (0, 0));
+    for (hour, _, productivity, secs) in Activity::category_hour_totals(conn, start, end)? {
+        let local_hour = DateTime::from_timestamp(hour, 0)
+            .unwrap_or_default()
+            .with_timezone(tz)
+            .hour();
+        let bucket = if local_hour >= LATE_HOUR {
+            &mut late
+        } else {
+            &mut early
+        };
+        if productivity == Some(-1) {
+            bucket.0 += secs;
+        }
+        bucket.1 += secs;
+    }
+    if late.1 < MIN_LATE_SECS || early.1 == 0 || early.0 == 0 {
+        return Ok(None);
+    }
+    let (early_pct, late_pct) = (early.0 * 100 / early.1, late.0 * 100 / late.1);
+    Ok((late_pct >= 2 * early_pct.max(1)).then(|| {
+        format!(
+            "Your distracting time doubles after {}pm: {late_pct}% of your time then, \
+             against {early_pct}% earlier in the day.",
+            LATE_HOUR - 12
+        )
+    }))
+}
+
+/// "Slack interrupts your longest focus blocks": the app or site that most
+/// often comes right after a long productive stretch ends.
+fn focus_interrupter(conn: &Connection, start: i64, end: i64) -> Result<Option<String>> {
+    let productive: HashMap<i64, bool> = Category::find_all(conn)?
+        .into_iter()
+        .map(|c| (c.id, c.productivity > 0))
+        .collect();
+    let is_productive = |a: &Activity| {
+        a.category_id
+            .and_then(|id| productive.get(&id).copied())
+            .unwrap_or(false)
+    };
+
+    let mut interrupters: HashMap<String, usize> = HashMap::new();
+    let mut long_blocks = 0;
+    // Start of the current productive stretch and where it got to
+    let mut block: Option<(i64, i64)> = None;
+    // The tracker's own samples; the extension's would repeat browser time
+    for activity in Activity::find_in_range(conn, start, end)?
+        .into_iter()
+        .filter(|a| a.source == "app")
+    {
+        let activity_end = activity.timestamp + i64::from(activity.duration_secs);
+        let continues =
+            block.is_some_and(|(_, until)| activity.timestamp - until <= MAX_ACTIVITY_GAP_SECS);
+        if is_productive(&activity) {
+            block = match block {
+                Some((from, _)) if continues => Some((from, activity_end)),
+                _ => Some((activity.timestamp, activity_end)),
+            };
+            continue;
+        }
+        if let Some((from, until)) = block.take() {
+            if until - from >= LONG_BLOCK_SECS {
+                long_blocks += 1;
+                if let Some(label) = activity.domain.or(activity.app_name).filter(|_| continues) {
+                    *interrupters.entry(label).or_default() += 1;
+                }
+            }
+        }
+    }
+    if let Some((from, until)) = block {
+        if until - from >= LONG_BLOCK_SECS {
+            long_blocks += 1;
+        }
+    }
+
+    let top = interrupters
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+    Ok(top
+        .filter(|(_, count)| *count >= MIN_INTERRUPTIONS && count * 2 >= long_blocks)
+        .map(|(label, count)| {
+            format!(
+                "{label} interrupts your longest focus blocks: it ended {count} of your \
+                 {long_blocks} blocks over {} minutes.",
+                LONG_BLOCK_SECS / 60
+            )
+        }))
+}
+
+/// Run the heuristics over the week starting `first_day` and store what
+/// they find. A kind already stored for that week is left alone, so a
+/// dismissed insight stays dismissed. Returns how many were added.
+pub fn generate<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    first_day: NaiveDate,
+    now: i64,
+) -> Result<usize> {
+    let week_end = first_day
+        .checked_add_days(Days::new(7))
+        .unwrap_or(first_day);
+    let (start, _) = day_bounds_in(tz, first_day);
+    let (end, _) = day_bounds_in(tz, week_end);
+
+    let found = [
+        (
+            InsightKind::LateDistraction,
+            late_distraction(conn, tz, start, end)?,
+        ),
+        (
+            InsightKind::FocusInterrupter,
+            focus_interrupter(conn, start, end)?,
+        ),
+    ];
+    let week = first_day.format("%Y-%m-%d").to_string();
+    let mut added = 0;
+    for (kind, message) in found {
+        let Some(message) = message else {
+            continue;
+        };
+        added += conn.execute(
+            "INSERT OR IGNORE INTO insights (kind, week_start, message, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![kind.as_str(), week, message, now],
+        )?;
+    }
+    Ok(added)
+}
+
+/// Insights not dismissed, newest week first.
+pub fn find_active(conn: &Connection) -> Result<Vec<Insight>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM insights WHERE status != 'dismissed'
+         ORDER BY week_start DESC, id"
+    ))?;
+    let rows = stmt.query_map([], Insight::from_row)?;
+    rows.collect()
+}
+
+/// Mark an insight acknowledged or dismissed. Returns false if it doesn't
+/// exist.
+pub fn set_status(conn: &Connection, id: i64, status: InsightStatus) -> Result<bool> {
+    let updated = conn.execute(
+        "UPDATE insights SET status = ?1 WHERE id = ?2",
+        params![status.as_str(), id],
+    )?;
+    Ok(updated > 0)
+}
+
+/// Look over last week once a day; `generate` skips what's already stored.
+pub fn check_insights(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let (today, _, _) = local_today();
+    let today_date = Local::now().date_naive();
+
+    let added = with_connection(&db, |conn| {
+        if Setting::get(conn, LAST_RUN_SETTING)?.as_deref() == Some(today.as_str()) {
+            return Ok(0);
+        }
+        Setting::set(conn, LAST_RUN_SETTING, &today)?;
+        let this_week = week_start(conn)?.week_of(today_date);
+        let Some(last_week) = this_week.checked_sub_days(Days::new(7)) else {
+            return Ok(0);
+        };
+        generate(conn, &Local, last_week, current_timestamp())
+    });
+
+    match added {
+        Ok(0) => {}
+        Ok(added) => info!("Generated {added} insight(s) for last week"),
+        Err(e) => error!("Failed to generate insights: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+
+    /// Monday 2026-10-12 00:00 UTC
+    const MONDAY: i64 = 1_791_763_200;
+    const HOUR: i64 = 3600;
+
+    fn category(conn: &Connection, name: &str) -> i64 {
+        Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == name)
+            .unwrap()
+            .id
+    }
+
+    fn save(conn: &Connection, timestamp: i64, secs: i32, app: &str, category_id: i64) {
+        let mut activity = Activity::new(timestamp, secs, "app", Some(app), None);
+        activity.category_id = Some(category_id);
+        activity.save(conn).unwrap();
+    }
+
+    fn week() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 12).unwrap()
+    }
+
+    #[test]
+    fn test_late_distraction() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = category(conn, "Coding");
+        let fun = category(conn, "Entertainment");
+        // Daytime: 10% distracting; late: 50%
+        save(conn, MONDAY + 10 * HOUR, 3240, "Code", coding);
+        save(conn, MONDAY + 11 * HOUR, 360, "Games", fun);
+        save(conn, MONDAY + 21 * HOUR, 1800, "Code", coding);
+        save(conn, MONDAY + 22 * HOUR, 1800, "Games", fun);
+
+        assert_eq!(generate(conn, &Utc, week(), MONDAY).unwrap(), 1);
+        let insights = find_active(conn).unwrap();
+        let insight = insights.first().unwrap();
+        assert_eq!(insight.kind, "late_distraction");
+        assert_eq!(insight.week_start, "2026-10-12");
+        assert!(insight.message.contains("after 9pm: 50%"));
+        assert!(insight.message.contains("against 10%"));
+
+        // Already stored for that week
+        assert_eq!(generate(conn, &Utc, week(), MONDAY).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_focus_interrupter() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = category(conn, "Coding");
+        let chat = category(conn, "Communication");
+        for day in 0..3 {
+            let start = MONDAY + day * 24 * HOUR + 9 * HOUR;
+            save(conn, start, 1800, "Code", coding);
+            save(conn, start + 1800, 120, "Slack", chat);
+        }
+
+        generate(conn, &Utc, week(), MONDAY).unwrap();
+        let insights = find_active(conn).unwrap();
+        let insight = insights.first().unwrap();
+        assert_eq!(insight.kind, "focus_interrupter");
+        assert!(insight
+            .message
+            .starts_with("Slack interrupts your longest focus blocks: it ended 3 of your 3"));
+    }
+
+    #[test]
+    fn test_dismissed_insights_are_hidden() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        conn.execute(
+            "INSERT INTO insights (kind, week_start, message, created_at)
+             VALUES ('late_distraction', '2026-10-12', 'm', 0)",
+            [],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+
+        assert!(set_status(conn, id, InsightStatus::Acknowledged).unwrap());
+        assert_eq!(
+            find_active(conn).unwrap().first().unwrap().status,
+            "acknowledged"
+        );
+        assert!(set_status(conn, id, InsightStatus::Dismissed).unwrap());
+        assert!(find_active(conn).unwrap().is_empty());
+        assert!(!set_status(conn, id + 1, InsightStatus::Dismissed).unwrap());
+    }
+}
@@ -0,0 +1,413 @@
+//! Journal of destructive edits (deletions and bulk recategorizing) so the
+//! last one can be undone for a few minutes afterwards.
+//!
+//! Deleted rules and schedules go to the trash (see `trash`), so undoing
+//! takes them back out.
+//! Deleting a category cascades to its rules and focus session budgets and
+//! clears it from activities, so those are journaled with it. Rule
+//! suggestions for it aren't kept.
+
+use crate::models::{Activity, ActivityFilter, Category, FocusSchedule, MatchType, Rule};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+/// How long an operation can be undone.
+pub const UNDO_WINDOW_SECS: i64 = 10 * 60;
+
+/// A journaled operation, holding what's needed to reverse it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operation {
+    DeleteCategory {
+        category: Category,
+        rules: Vec<Rule>,
+        activity_ids: Vec<i64>,
+        distraction_event_ids: Vec<i64>,
+        /// (session id, budget seconds) of focus session budgets for it.
+        budgets: Vec<(i64, i64)>,
+        /// Focus sessions that blocked it.
+        blocked_session_ids: Vec<i64>,
+    },
+    DeleteRule {
+        rule: Rule,
+    },
+    DeleteSchedule {
+        schedule: FocusSchedule,
+    },
+    Recategorize {
+        /// (activity id, previous category) of each moved activity.
+        moved: Vec<(i64, Option<i64>)>,
+        /// Rule added along with it.
+        created_rule_id: Option<i64>,
+        /// Existing app rule it repointed, as it was before.
+        replaced_rule: Option<Rule>,
+    },
+}
+
+impl Operation {
+    /// Short label for the undo toast, e.g. "delete category Coding".
+    pub fn describe(&self) -> String {
+        match self {
+            Self::DeleteCategory { category, .. } => {
+                format!("delete category {}", category.name)
+            }
+            Self::DeleteRule { rule } => format!("delete rule {}", rule.pattern),
+            Self::DeleteSchedule { schedule } => format!(
+                "delete schedule {}-{}",
+                schedule.start_time, schedule.end_time
+            ),
+            Self::Recategorize { moved, .. } => format!("recategorize {} activities", moved.len()),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::DeleteCategory { .. } => "delete_category",
+            Self::DeleteRule { .. } => "delete_rule",
+            Self::DeleteSchedule { .. } => "delete_schedule",
+            Self::Recategorize { .. } => "recategorize",
+        }
+    }
+
+    fn revert(&self, conn: &Connection) -> Result<()> {
+        match self {
+            Self::DeleteCategory {
+                category,
+                rules,
+                activity_ids,
+                distraction_event_ids,
+                budgets,
+                blocked_session_ids,
+            } => {
+                Category::restore(conn, category)?;
+                for rule in rules {
+                    Rule::reinsert(conn, rule)?;
+                }
+                for id in activity_ids {
+                    Activity::set_category(conn, *id, Some(category.id))?;
+                }
+                for id in distraction_event_ids {
+                    conn.execute(
+                        "UPDATE distraction_events SET category_id = ?1 WHERE id = ?2",
+                        params![category.id, id],
+                    )?;
+                }
+                for (session_id, budget_secs) in budgets {
+                    conn.execute(
+                        "INSERT INTO session_budgets (session_id, category_id, budget_secs)
+                         VALUES (?1, ?2, ?3)",
+                        params![session_id, category.id, budget_secs],
+                    )?;
+                }
+                for session_id in blocked_session_ids {
+                    conn.execute(
+                        "INSERT INTO session_blocked_categories (session_id, category_id)
+                         VALUES (?1, ?2)",
+                        params![session_id, category.id],
+                    )?;
+                }
+                Ok(())
+            }
+            Self::DeleteRule { rule } => Rule::restore(conn, rule.id).map(|_| ()),
+            Self::DeleteSchedule { schedule } => {
+                FocusSchedule::restore(conn, schedule.id.unwrap_or_default()).map(|_| ())
+            }
+            Self::Recategorize {
+                moved,
+                created_rule_id,
+                replaced_rule,
+            } => {
+                for (id, previous) in moved {
+                    Activity::set_category(conn, *id, *previous)?;
+                }
+                if let Some(id) = created_rule_id {
+                    Rule::delete(conn, *id)?;
+                }
+                if let Some(rule) = replaced_rule {
+                    Rule::update(
+                        conn,
+                        rule.id,
+                        &rule.pattern,
+                        rule.match_type,
+                        rule.category_id,
+                        rule.priority,
+                        None,
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Add `operation` to the journal, dropping entries too old to undo.
+pub fn record(conn: &Connection, operation: &Operation, now: i64) -> Result<()> {
+    let payload = serde_json::to_string(operation)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "DELETE FROM operation_journal WHERE created_at < ?1",
+        params![now - UNDO_WINDOW_SECS],
+    )?;
+    conn.execute(
+        "INSERT INTO operation_journal (kind, payload, created_at) VALUES (?1, ?2, ?3)",
+        params![operation.kind(), payload, now],
+    )?;
+    Ok(())
+}
+
+/// Reverse the latest operation still inside the undo window and remove it
+/// from the journal. `None` when there's nothing to undo.
+pub fn undo_last(conn: &Connection, now: i64) -> Result<Option<Operation>> {
+    let tx = conn.unchecked_transaction()?;
+    let latest: Option<(i64, String)> = tx
+        .query_row(
+            "SELECT id, payload FROM operation_journal
+             WHERE created_at >= ?1 ORDER BY id DESC LIMIT 1",
+            params![now - UNDO_WINDOW_SECS],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let Some((id, payload)) = latest else {
+        return Ok(None);
+    };
+    let operation: Operation = serde_json::from_str(&payload)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, Type::Text, Box::new(e)))?;
+    operation.revert(&tx)?;
+    tx.execute("DELETE FROM operation_journal WHERE id = ?1", params![id])?;
+    tx.commit()?;
+    Ok(Some(operation))
+}
+
+/// First column of every row `sql` returns for `id`.
+fn column_for<T: rusqlite::types::FromSql>(
+    conn: &Connection,
+    sql: &str,
+    id: i64,
+) -> Result<Vec<T>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// (session id, budget seconds) of every focus session budget for a category.
+fn budgets_for(conn: &Connection, category_id: i64) -> Result<Vec<(i64, i64)>> {
+    let mut stmt =
+        conn.prepare("SELECT session_id, budget_secs FROM session_budgets WHERE category_id = ?1")?;
+    let rows = stmt.query_map(params![category_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Delete a category and journal it along with everything the delete
+/// cascades to. Returns false if it didn't exist.
+pub fn delete_category(conn: &Connection, id: i64, now: i64) -> Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let Some(category) = Category::find_by_id(&tx, id)? else {
+        return Ok(false);
+    };
+    let operation = Operation::DeleteCategory {
+        rules: Rule::find_all(&tx)?
+            .into_iter()
+            .filter(|rule| rule.category_id == id)
+            .collect(),
+        activity_ids: column_for(&tx, "SELECT id FROM activities WHERE category_id = ?1", id)?,
+        distraction_event_ids: column_for(
+            &tx,
+            "SELECT id FROM distraction_events WHERE category_id = ?1",
+            id,
+        )?,
+        budgets: budgets_for(&tx, id)?,
+        blocked_session_ids: column_for(
+            &tx,
+            "SELECT session_id FROM session_blocked_categories WHERE category_id = ?1",
+            id,
+        )?,
+        category,
+    };
+    Category::delete(&tx, id)?;
+    record(&tx, &operation, now)?;
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Move a rule to the trash and journal it. Returns false if it didn't exist.
+pub fn delete_rule(conn: &Connection, id: i64, now: i64) -> Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let Some(rule) = Rule::find_by_id(&tx, id)? else {
+        return Ok(false);
+    };
+    Rule::trash(&tx, id, now)?;
+    record(&tx, &Operation::DeleteRule { rule }, now)?;
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Move a focus schedule to the trash and journal it. Returns false if it
+/// didn't exist.
+pub fn delete_schedule(conn: &Connection, id: i64, now: i64) -> Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let Some(schedule) = FocusSchedule::find_by_id(&tx, id)? else {
+        return Ok(false);
+    };
+    FocusSchedule::trash(&tx, id, now)?;
+    record(&tx, &Operation::DeleteSchedule { schedule }, now)?;
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Move activities matching `filter` into `category_id`, optionally adding
+/// a rule for `rule_source` (pattern, match type) at `rule_priority`, and
+/// journal it. An app rule reuses an existing one, like `Rule::assign_app`.
+/// Returns how many activities moved and the rule.
+pub fn recategorize(
+    conn: &Connection,
+    filter: &ActivityFilter,
+    category_id: i64,
+    rule_source: Option<(&str, MatchType)>,
+    rule_priority: i32,
+    now: i64,
+) -> Result<(usize, Option<Rule>)> {
+    let tx = conn.unchecked_transaction()?;
+    let moved = Activity::recategorize(&tx, filter, category_id)?;
+    let (rule, created_rule_id, replaced_rule) = match rule_source {
+        Some((pattern, MatchType::App)) => {
+            let before = Rule::find_all(&tx)?;
+            let rule = Rule::assign_app(&tx, pattern, category_id, rule_priority)?;
+            let replaced = before.into_iter().find(|r| r.id == rule.id);
+            let created = replaced.is_none().then_some(rule.id);
+            (Some(rule), created, replaced)
+        }
+        Some((pattern, match_type)) => {
+            let rule = Rule::create(&tx, pattern, match_type, category_id, rule_priority)?;
+            let created = Some(rule.id);
+            (Some(rule), created, None)
+        }
+        None => (None, None, None),
+    };
+    let updated = moved.len();
+    let operation = Operation::Recategorize {
+        moved,
+        created_rule_id,
+        replaced_rule,
+    };
+    record(&tx, &operation, now)?;
+    tx.commit()?;
+    Ok((updated, rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn category_id(conn: &Connection, name: &str) -> i64 {
+        Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == name)
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn test_undo_category_deletion_restores_rules_and_stats() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = category_id(conn, "Coding");
+        let mut activity = Activity::new(NOW, 600, "app", Some("Code"), None);
+        activity.category_id = Some(coding);
+        activity.save(conn).unwrap();
+        let rules_before = Rule::find_all(conn).unwrap().len();
+
+        assert!(delete_category(conn, coding, NOW).unwrap());
+        assert_eq!(Category::find_by_id(conn, coding).unwrap(), None);
+        assert_eq!(
+            Activity::productivity_totals(conn, NOW, NOW + 1).unwrap().0,
+            0
+        );
+
+        let undone = undo_last(conn, NOW + 60).unwrap().unwrap();
+        assert_eq!(undone.describe(), "delete category Coding");
+        assert!(Category::find_by_id(conn, coding).unwrap().is_some());
+        assert_eq!(Rule::find_all(conn).unwrap().len(), rules_before);
+        assert_eq!(
+            Activity::productivity_totals(conn, NOW, NOW + 1).unwrap().0,
+            600
+        );
+        assert!(undo_last(conn, NOW + 60).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undo_rule_and_schedule_deletion() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let rule =
+            Rule::create(conn, "vim", MatchType::App, category_id(conn, "Coding"), 30).unwrap();
+        Rule::set_patterns(conn, rule.id, &["vim", "nvim"]).unwrap();
+        let mut schedule = FocusSchedule::new("1,2,3", "09:00", "12:00", 300);
+        schedule.blocked_categories = vec![category_id(conn, "Communication")];
+        schedule.save(conn).unwrap();
+        let schedule_id = schedule.id.unwrap();
+
+        delete_rule(conn, rule.id, NOW).unwrap();
+        delete_schedule(conn, schedule_id, NOW + 1).unwrap();
+
+        // Most recent first
+        let undone = undo_last(conn, NOW + 2).unwrap().unwrap();
+        assert!(matches!(undone, Operation::DeleteSchedule { .. }));
+        let restored = FocusSchedule::find_by_id(conn, schedule_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored.blocked_categories, schedule.blocked_categories);
+
+        undo_last(conn, NOW + 3).unwrap().unwrap();
+        let restored = Rule::find_by_id(conn, rule.id).unwrap().unwrap();
+        assert_eq!(restored.extra_patterns, vec!["nvim".to_string()]);
+    }
+
+    #[test]
+    fn test_undo_recategorize_restores_categories_and_drops_rule() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let entertainment = category_id(conn, "Entertainment");
+        let mut activity = Activity::new(NOW, 60, "app", Some("Firefox"), None);
+        activity.domain = Some("lecture.example.com".to_string());
+        activity.category_id = Some(entertainment);
+        activity.save(conn).unwrap();
+
+        let filter = ActivityFilter {
+            start: NOW,
+            end: NOW + 60,
+            app_name: None,
+            domain: Some("lecture.example.com"),
+            category_id: None,
+        };
+        let reference = category_id(conn, "Reference");
+        let source = Some(("lecture.example.com", MatchType::Domain));
+        let (updated, rule) = recategorize(conn, &filter, reference, source, 50, NOW).unwrap();
+        assert_eq!(updated, 1);
+        let rule = rule.unwrap();
+
+        undo_last(conn, NOW + 1).unwrap().unwrap();
+        let activity = Activity::find_by_id(conn, activity.id.unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(activity.category_id, Some(entertainment));
+        assert!(Rule::find_by_id(conn, rule.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undo_expires() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = category_id(conn, "Coding");
+        delete_category(conn, coding, NOW).unwrap();
+
+        assert!(undo_last(conn, NOW + UNDO_WINDOW_SECS + 1)
+            .unwrap()
+            .is_none());
+        assert_eq!(Category::find_by_id(conn, coding).unwrap(), None);
+    }
+}
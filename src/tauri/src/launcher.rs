@@ -0,0 +1,226 @@
+//! JSON-lines command mode for launcher extensions (Raycast, Alfred, and the like).
+//!
+//! `foxus-native-host launcher` reads one request per line on stdin and
+//! writes one response per line on stdout, so a launcher plugin only has to
+//! spawn the process and parse a line of JSON:
+//!
+//! ```text
+//! {"command":"status"}
+//! {"command":"start","minutes":25}
+//! {"command":"end"}
+//! ```
+//!
+//! Every response carries the focus state and today's totals, so a plugin can
+//! render its view from the reply to any command.
+
+use crate::audit_log::Origin;
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::focus::FocusManager;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::Activity;
+use crate::validation::{validate_budget_minutes, validate_session_minutes};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+/// Session length when `start` doesn't give one, matching the 25 minute tray preset.
+const DEFAULT_SESSION_MINUTES: i32 = 25;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum LauncherRequest {
+    Status,
+    Start {
+        #[serde(default)]
+        minutes: Option<i32>,
+        /// Distraction budget; defaults to the session length like the tray presets.
+        #[serde(default)]
+        budget_minutes: Option<i32>,
+    },
+    End,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct LauncherFocus {
+    pub active: bool,
+    pub budget_remaining_secs: i32,
+    pub time_remaining_secs: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct LauncherToday {
+    pub productive_secs: i32,
+    pub neutral_secs: i32,
+    pub distracting_secs: i32,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LauncherResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub focus: LauncherFocus,
+    pub today: LauncherToday,
+}
+
+pub struct Launcher {
+    db: Arc<Mutex<Database>>,
+    focus_manager: Arc<FocusManager>,
+}
+
+impl Launcher {
+    pub fn new(db: Arc<Mutex<Database>>, focus_manager: Arc<FocusManager>) -> Self {
+        Self { db, focus_manager }
+    }
+
+    /// Answer requests line by line until `input` closes.
+    pub fn run(&self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str(&line) {
+                Ok(request) => self.handle(&request),
+                Err(e) => self.respond(Err(AppError::InvalidInput {
+                    field: "command",
+                    reason: e.to_string(),
+                })),
+            };
+            serde_json::to_writer(&mut output, &response)?;
+            output.write_all(b"\n")?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn handle(&self, request: &LauncherRequest) -> LauncherResponse {
+        let result = match request {
+            LauncherRequest::Status => Ok(()),
+            LauncherRequest::Start {
+                minutes,
+                budget_minutes,
+            } => self.start(*minutes, *budget_minutes),
+            LauncherRequest::End => self.focus_manager.end_session(Origin::Cli).map(|_| ()),
+        };
+        self.respond(result)
+    }
+
+    fn start(&self, minutes: Option<i32>, budget_minutes: Option<i32>) -> Result<(), AppError> {
+        let minutes = minutes.unwrap_or(DEFAULT_SESSION_MINUTES);
+        let duration_secs = validate_session_minutes(minutes)?;
+        let budget_secs = validate_budget_minutes(budget_minutes.unwrap_or(minutes))?;
+        self.focus_manager.start_timed_session(
+            budget_secs,
+            Some(duration_secs),
+            false,
+            &[],
+            Origin::Cli,
+        )?;
+        Ok(())
+    }
+
+    /// Current state, with the outcome of the command that preceded it.
+    fn respond(&self, result: Result<(), AppError>) -> LauncherResponse {
+        let mut response = match self.snapshot() {
+            Ok(response) => response,
+            Err(e) => LauncherResponse {
+                error: Some(e.to_string()),
+                ..LauncherResponse::default()
+            },
+        };
+        if let Err(e) = result {
+            response.ok = false;
+            response.error = Some(e.to_string());
+        }
+        response
+    }
+
+    fn snapshot(&self) -> Result<LauncherResponse, AppError> {
+        let state = self.focus_manager.get_state()?;
+        let (_, _, midnight) = local_today();
+        let (productive_secs, neutral_secs, distracting_secs) =
+            with_connection(&self.db, |conn| {
+                Activity::productivity_totals(conn, midnight, current_timestamp())
+            })?;
+        Ok(LauncherResponse {
+            ok: true,
+            error: None,
+            focus: LauncherFocus {
+                active: state.active,
+                budget_remaining_secs: state.budget_remaining,
+                time_remaining_secs: state.time_remaining_secs,
+            },
+            today: LauncherToday {
+                productive_secs,
+                neutral_secs,
+                distracting_secs,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use serde_json::Value;
+
+    fn launcher() -> (Launcher, tempfile::TempDir) {
+        let (db, dir) = setup_test_db();
+        let db = Arc::new(Mutex::new(db));
+        let focus_manager = Arc::new(FocusManager::new(Arc::clone(&db)));
+        (Launcher::new(db, focus_manager), dir)
+    }
+
+    fn run(launcher: &Launcher, input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        launcher.run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_start_status_end() {
+        let (launcher, _dir) = launcher();
+        let replies = run(
+            &launcher,
+            "{\"command\":\"start\",\"minutes\":50,\"budget_minutes\":5}\n\n\
+             {\"command\":\"status\"}\n{\"command\":\"end\"}\n",
+        );
+        assert_eq!(replies.len(), 3);
+        let status = replies.get(1).unwrap();
+        assert_eq!(status.get("ok"), Some(&Value::Bool(true)));
+        assert_eq!(status.pointer("/focus/active"), Some(&Value::Bool(true)));
+        assert_eq!(
+            status.pointer("/focus/budget_remaining_secs"),
+            Some(&Value::from(300))
+        );
+        assert_eq!(
+            status.pointer("/today/productive_secs"),
+            Some(&Value::from(0))
+        );
+        let ended = replies.get(2).unwrap();
+        assert_eq!(ended.pointer("/focus/active"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_errors_still_report_state() {
+        let (launcher, _dir) = launcher();
+        let replies = run(
+            &launcher,
+            "{\"command\":\"start\",\"minutes\":0}\nnot json\n",
+        );
+        for reply in &replies {
+            assert_eq!(reply.get("ok"), Some(&Value::Bool(false)));
+            assert!(reply.get("error").is_some());
+            assert_eq!(reply.pointer("/focus/active"), Some(&Value::Bool(false)));
+        }
+        assert_eq!(replies.len(), 2);
+    }
+}
@@ -1,74 +1,87 @@
+mod accountability;
+mod aggregate_export;
+pub mod audit_log;
+mod away_mode;
+#[doc(hidden)]
+pub mod bench_support;
+mod break_window;
+mod breaks;
+mod browser_integration;
+mod browsers;
+mod budget_accrual;
+mod calendar_feed;
 pub mod categorizer;
+mod classifier;
 mod commands;
+pub mod config_file;
 pub mod constants;
+mod context_switches;
+mod daily_summary;
+pub mod data_dir;
+mod data_quality;
 pub mod db;
+mod deep_work;
+mod discord_presence;
+mod email_report;
 pub mod error;
+mod external_activity;
 pub mod focus;
+mod git_commits;
+mod icons;
+mod idle_calibration;
+mod insights;
+mod journal;
+pub mod launcher;
+mod local_time;
+mod mobile_sync;
 mod models;
+mod mqtt;
 pub mod native_host;
+mod notifications;
+mod offline_focus;
+mod onboarding;
+mod overlay;
+mod personal_time;
 mod platform;
+pub mod policy;
+mod read_only;
+mod recommendation;
+mod reports;
+mod schedule_reminders;
+mod schedule_templates;
+mod schedule_text;
+mod scheduler;
+mod secrets;
+mod session_queue;
+mod snapshot;
+mod standup;
+pub mod terminal_context;
 #[cfg(test)]
 mod test_utils;
+mod time_export;
+mod title_context;
 mod tracker;
+mod tracking_hours;
+mod trash;
+mod tray;
+mod updates;
 pub mod validation;
+mod webhook;
+mod widget;
+mod window_context;
 
 use crate::categorizer::Categorizer;
 use crate::db::{migrations, Database};
 use crate::focus::FocusManager;
 use crate::tracker::{TrackerConfig, TrackerService};
-use directories::ProjectDirs;
 use log::{error, warn};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
-use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
-    tray::{TrayIcon, TrayIconBuilder},
-    webview::WebviewWindowBuilder,
-    AppHandle, Manager, RunEvent, Wry,
-};
+use tauri::{webview::WebviewWindowBuilder, Manager, RunEvent};
 
 /// Holds the tracker thread handle for graceful shutdown
 pub struct TrackerHandle(Mutex<Option<JoinHandle<()>>>);
 
-/// Holds the tray icon for dynamic menu updates
-pub struct TrayHandle(Mutex<Option<TrayIcon<Wry>>>);
-
-/// Build the tray menu based on current focus state
-fn build_tray_menu(app: &AppHandle) -> Result<Menu<Wry>, Box<dyn std::error::Error>> {
-    let focus_active = if let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() {
-        focus_manager.get_state().map(|s| s.active).unwrap_or(false)
-    } else {
-        false
-    };
-
-    let open = MenuItem::with_id(app, "open", "Open Foxus", true, None::<&str>)?;
-    let separator = PredefinedMenuItem::separator(app)?;
-
-    if focus_active {
-        let end_focus =
-            MenuItem::with_id(app, "end_focus", "End Focus Session", true, None::<&str>)?;
-        let quit = MenuItem::with_id(app, "quit", "Quit Foxus", true, None::<&str>)?;
-        Ok(Menu::with_items(
-            app,
-            &[&open, &separator, &end_focus, &separator, &quit],
-        )?)
-    } else {
-        let focus_10 =
-            MenuItem::with_id(app, "focus_10", "Start Focus (10 min)", true, None::<&str>)?;
-        let focus_25 =
-            MenuItem::with_id(app, "focus_25", "Start Focus (25 min)", true, None::<&str>)?;
-        let focus_60 =
-            MenuItem::with_id(app, "focus_60", "Start Focus (1 hour)", true, None::<&str>)?;
-        let quit = MenuItem::with_id(app, "quit", "Quit Foxus", true, None::<&str>)?;
-        Ok(Menu::with_items(
-            app,
-            &[
-                &open, &separator, &focus_10, &focus_25, &focus_60, &separator, &quit,
-            ],
-        )?)
-    }
-}
-
 /// Error type for Foxus initialization failures
 #[derive(Debug)]
 pub enum InitError {
@@ -77,6 +90,9 @@ pub enum InitError {
     DatabaseOpen(rusqlite::Error),
     Migration(rusqlite::Error),
     Categorizer(rusqlite::Error),
+    /// The database was moved to a folder that isn't there, e.g. on a drive
+    /// that isn't connected.
+    DatabaseFolderMissing(std::path::PathBuf),
 }
 
 impl std::fmt::Display for InitError {
@@ -87,17 +103,26 @@ impl std::fmt::Display for InitError {
             InitError::DatabaseOpen(e) => write!(f, "Failed to open database: {e}"),
             InitError::Migration(e) => write!(f, "Failed to run database migrations: {e}"),
             InitError::Categorizer(e) => write!(f, "Failed to initialize categorizer: {e}"),
+            InitError::DatabaseFolderMissing(folder) => write!(
+                f,
+                "Database folder {} is not available; is the drive connected?",
+                folder.display()
+            ),
         }
     }
 }
 
 impl std::error::Error for InitError {}
 
+/// The app's data directory, created if needed: `--data-dir`,
+/// `FOXUS_DATA_DIR` or the platform default. The database lives here unless
+/// it was moved with `migrate_database`.
+pub fn get_data_dir() -> Result<std::path::PathBuf, InitError> {
+    data_dir::resolve()
+}
+
 pub fn get_db_path() -> Result<std::path::PathBuf, InitError> {
-    let proj_dirs = ProjectDirs::from("com", "foxus", "Foxus").ok_or(InitError::NoProjectDirs)?;
-    let data_dir = proj_dirs.data_dir();
-    std::fs::create_dir_all(data_dir).map_err(InitError::DataDirCreation)?;
-    Ok(data_dir.join("foxus.db"))
+    db::location::db_path(&get_data_dir()?)
 }
 
 /// Lock a mutex, recovering from poisoning if necessary
@@ -111,8 +136,36 @@ pub fn safe_lock<'a, T>(mutex: &'a Mutex<T>, context: &str) -> std::sync::MutexG
     }
 }
 
+/// Close sessions left open by a crash, start or resume the session a focus
+/// schedule calls for right away, and tell the user if its window opened
+/// while Foxus wasn't running.
+fn restore_scheduled_session(app: &tauri::App, focus_manager: &FocusManager) {
+    match focus_manager.restore_on_startup() {
+        Ok(Some(missed)) => {
+            let body = format!(
+                "Your focus session was scheduled for {} and started {} late.",
+                missed.start_time,
+                tray::format_duration(i32::try_from(missed.late_secs).unwrap_or(i32::MAX))
+            );
+            notifications::notify(app.handle(), "Scheduled focus resumed", &body);
+        }
+        Ok(None) => {}
+        Err(e) => error!("Failed to restore scheduled focus session: {e}"),
+    }
+}
+
 /// Initialize database, categorizer, focus manager, and tracker service.
-fn initialize_services(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+///
+/// Read-only, the database is opened as it is and the tracker isn't started.
+fn initialize_services(
+    app: &mut tauri::App,
+    read_only: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let policy = policy::load(&policy::policy_path());
+    // Any error here is reported by `get_db_path` just below
+    if let Ok(data_dir) = get_data_dir() {
+        config_file::load(&data_dir);
+    }
     let db_path = match get_db_path() {
         Ok(path) => path,
         Err(e) => {
@@ -121,7 +174,12 @@ fn initialize_services(app: &mut tauri::App) -> Result<(), Box<dyn std::error::E
         }
     };
 
-    let db = match Database::open(&db_path) {
+    let opened = if read_only {
+        Database::open_read_only(&db_path)
+    } else {
+        Database::open(&db_path)
+    };
+    let db = match opened {
         Ok(db) => db,
         Err(e) => {
             error!("Failed to open database: {e}");
@@ -129,9 +187,19 @@ fn initialize_services(app: &mut tauri::App) -> Result<(), Box<dyn std::error::E
         }
     };
 
-    if let Err(e) = migrations::run(db.connection()) {
-        error!("Failed to run migrations: {e}");
-        return Err(Box::new(InitError::Migration(e)));
+    if !read_only {
+        if let Err(e) = migrations::run(db.connection()) {
+            error!("Failed to run migrations: {e}");
+            return Err(Box::new(InitError::Migration(e)));
+        }
+        // Before the categorizer loads, so seeded rules apply right away
+        if let Some(policy) = &policy {
+            if let Err(e) = policy.seed(db.connection()) {
+                error!("Failed to seed policy categories and rules: {e}");
+            }
+        }
+        // Older versions kept secrets in the settings table
+        secrets::move_from_settings(db.connection());
     }
 
     let db = Arc::new(Mutex::new(db));
@@ -147,17 +215,26 @@ fn initialize_services(app: &mut tauri::App) -> Result<(), Box<dyn std::error::E
         }
     };
 
+    let tracker_config = {
+        let db_guard = safe_lock(&db, "Database");
+        TrackerConfig::load(db_guard.connection()).unwrap_or_default()
+    };
+
     let focus_manager = Arc::new(FocusManager::new(Arc::clone(&db)));
+    if !read_only {
+        restore_scheduled_session(app, &focus_manager);
+    }
 
     // Start tracker service
     let tracker = TrackerService::new(
         Arc::clone(&db),
         Arc::clone(&categorizer),
-        TrackerConfig::default(),
+        Arc::clone(&focus_manager),
+        tracker_config,
     );
-    let handle = tracker.start();
+    let handle = (!read_only).then(|| tracker.start());
     let tracker = Arc::new(tracker);
-    let tracker_handle = TrackerHandle(Mutex::new(Some(handle)));
+    let tracker_handle = TrackerHandle(Mutex::new(handle));
 
     // Store in app state
     app.manage(db);
@@ -165,145 +242,239 @@ fn initialize_services(app: &mut tauri::App) -> Result<(), Box<dyn std::error::E
     app.manage(focus_manager);
     app.manage(tracker);
     app.manage(tracker_handle);
+    app.manage(read_only::ReadOnly(read_only));
+    app.manage(updates::LatestUpdate::default());
+    app.manage(breaks::BreakReminderState::default());
+    app.manage(idle_calibration::IdleCalibrationState::default());
+    app.manage(discord_presence::DiscordPresenceState::default());
+    app.manage(mqtt::MqttPublisherState::default());
 
     Ok(())
 }
 
-/// Handle tray menu events (focus actions, open, quit).
-fn handle_tray_event(app: &AppHandle, event_id: &str) {
-    if event_id.starts_with("focus_") || event_id == "end_focus" {
-        handle_focus_event(app, event_id);
-    } else if event_id == "open" {
-        if let Some(window) = app.get_webview_window("main") {
-            let _ = window.show();
-            let _ = window.set_focus();
-        }
-    } else if event_id == "quit" {
-        handle_quit(app);
-    }
-}
-
-/// Handle focus-related tray menu events.
-fn handle_focus_event(app: &AppHandle, event_id: &str) {
-    if let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() {
-        let result = match event_id {
-            "focus_10" => focus_manager.start_session(10 * 60).map(|_| ()),
-            "focus_25" => focus_manager.start_session(25 * 60).map(|_| ()),
-            "focus_60" => focus_manager.start_session(60 * 60).map(|_| ()),
-            "end_focus" => focus_manager.end_session().map(|_| ()),
-            _ => Ok(()),
-        };
-
-        if let Err(e) = result {
-            error!("Failed to handle focus action: {e}");
-        }
-
-        // Update tray menu to reflect new focus state
-        if let Some(tray_handle) = app.try_state::<TrayHandle>() {
-            if let Ok(guard) = tray_handle.0.lock() {
-                if let Some(tray) = guard.as_ref() {
-                    match build_tray_menu(app) {
-                        Ok(new_menu) => {
-                            if let Err(e) = tray.set_menu(Some(new_menu)) {
-                                error!("Failed to update tray menu: {e}");
-                            }
-                        }
-                        Err(e) => error!("Failed to build tray menu: {e}"),
-                    }
-                }
-            }
-        }
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+#[expect(
+    clippy::too_many_lines,
+    reason = "generate_handler! needs every command listed inline"
+)]
+pub fn run() {
+    let args = data_dir::apply_flags(std::env::args());
+    let read_only = read_only::requested(args.into_iter());
+    let mut builder = tauri::Builder::default();
+    // A read-only instance can run next to the normal one; anything else must
+    // register this first: a second launch exits here before it can start
+    // another tracker and double-record activities.
+    if !read_only {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            tray::show_main_window(app);
+        }));
     }
-}
 
-/// Gracefully stop the tracker and exit the app.
-fn handle_quit(app: &AppHandle) {
-    if let Some(tracker) = app.try_state::<Arc<TrackerService>>() {
-        tracker.stop();
-    }
-    if let Some(handle_state) = app.try_state::<TrackerHandle>() {
-        if let Ok(mut guard) = handle_state.0.lock() {
-            if let Some(handle) = guard.take() {
-                let _ = handle.join();
-            }
-        }
-    }
-    app.exit(0);
-}
+    let handler: fn(tauri::ipc::Invoke) -> bool = tauri::generate_handler![
+        commands::get_today_stats,
+        commands::get_weekly_stats,
+        commands::get_week_start,
+        commands::set_week_start,
+        commands::get_context_switches,
+        commands::get_window_context_breakdown,
+        commands::analyze_queries,
+        commands::get_browser_profiles,
+        commands::get_browser_integration_status,
+        commands::get_audit_log,
+        commands::get_config_overrides,
+        commands::get_database_location,
+        commands::migrate_database,
+        commands::get_browser_breakdown,
+        commands::get_top_domains,
+        commands::get_coding_breakdown,
+        commands::get_terminal_projects,
+        commands::get_git_repositories,
+        commands::set_git_repositories,
+        commands::get_commit_activity,
+        commands::get_time_export_settings,
+        commands::update_time_export_settings,
+        commands::export_time_entries,
+        commands::export_aggregates_anonymized,
+        commands::get_insights,
+        commands::acknowledge_insight,
+        commands::dismiss_insight,
+        commands::get_data_quality,
+        commands::publish_snapshot,
+        commands::get_calendar_feed,
+        commands::set_calendar_feed_enabled,
+        commands::get_overlay,
+        commands::set_overlay_enabled,
+        commands::get_discord_presence_settings,
+        commands::update_discord_presence_settings,
+        commands::get_mqtt_settings,
+        commands::update_mqtt_settings,
+        commands::get_icon,
+        commands::get_app_identities,
+        commands::set_app_identity,
+        commands::delete_app_identity,
+        commands::get_title_extractors,
+        commands::set_title_extractors,
+        commands::get_title_contexts,
+        commands::get_quiet_hours,
+        commands::set_quiet_hours,
+        commands::get_focus_state,
+        commands::start_focus_session,
+        commands::end_focus_session,
+        commands::add_session_block,
+        commands::allow_domain_temporarily,
+        commands::set_session_exceptions,
+        commands::set_session_blocked_categories,
+        commands::emergency_unlock,
+        commands::get_emergency_unlock_settings,
+        commands::set_emergency_unlock_delay,
+        commands::get_distraction_grant,
+        commands::set_distraction_grant,
+        commands::get_budget_accrual_settings,
+        commands::update_budget_accrual_settings,
+        commands::get_focus_history,
+        commands::log_offline_focus,
+        commands::get_distraction_events,
+        commands::get_focus_recommendation,
+        commands::get_daily_focus_goal,
+        commands::set_daily_focus_goal,
+        commands::extend_focus_session,
+        commands::get_session_queue,
+        commands::queue_session,
+        commands::clear_session_queue,
+        commands::get_deep_work_settings,
+        commands::update_deep_work_settings,
+        commands::get_focus_schedules,
+        commands::create_focus_schedule,
+        commands::create_schedule_from_text,
+        commands::get_schedule_templates,
+        commands::apply_schedule_template,
+        commands::get_away_mode,
+        commands::set_away_mode,
+        commands::end_away_mode,
+        commands::update_focus_schedule,
+        commands::delete_focus_schedule,
+        commands::get_active_schedule,
+        commands::check_focus_schedules,
+        commands::get_categories,
+        commands::create_category,
+        commands::update_category,
+        commands::set_category_weight,
+        commands::delete_category,
+        commands::get_rules,
+        commands::create_rule,
+        commands::update_rule,
+        commands::delete_rule,
+        commands::set_rule_patterns,
+        commands::set_rule_condition,
+        commands::set_rule_profile,
+        commands::get_rule_groups,
+        commands::create_rule_group,
+        commands::delete_rule_group,
+        commands::set_rule_group_enabled,
+        commands::set_rule_group,
+        commands::get_personal_time,
+        commands::set_personal_time,
+        commands::get_onboarding_state,
+        commands::complete_onboarding_step,
+        commands::check_for_updates,
+        commands::get_update_auto_check,
+        commands::set_update_auto_check,
+        commands::get_current_activity,
+        commands::get_tracker_health,
+        commands::get_idle_settings,
+        commands::calibrate_idle,
+        commands::get_tracker_mode,
+        commands::set_tracker_mode,
+        commands::get_tracking_hours,
+        commands::set_tracking_hours,
+        commands::record_screen_time,
+        commands::get_sync_settings,
+        commands::update_sync_settings,
+        commands::request_remote_focus,
+        commands::sync_now,
+        commands::watch_categorization,
+        commands::explain_categorization,
+        commands::recategorize_activities,
+        commands::undo_last_operation,
+        commands::get_trash,
+        commands::restore_from_trash,
+        commands::purge_trash,
+        commands::get_context_capture,
+        commands::set_context_capture,
+        commands::record_external_activity,
+        commands::show_focus_widget,
+        commands::close_focus_widget,
+        commands::get_tray_countdown_enabled,
+        commands::set_tray_countdown_enabled,
+        commands::get_break_settings,
+        commands::update_break_settings,
+        commands::skip_break,
+        commands::get_active_break,
+        commands::emergency_skip_break,
+        commands::get_daily_summary,
+        commands::get_daily_summary_settings,
+        commands::update_daily_summary_settings,
+        commands::get_standup_summary,
+        commands::get_notes,
+        commands::create_note,
+        commands::update_note,
+        commands::delete_note,
+        commands::get_accountability_settings,
+        commands::update_accountability_settings,
+        commands::get_email_report_settings,
+        commands::update_email_report_settings,
+        commands::send_email_report,
+        commands::get_rule_suggestions,
+        commands::accept_rule_suggestion,
+        commands::dismiss_rule_suggestion,
+        commands::request_llm_suggestions,
+        commands::request_offline_suggestions,
+        commands::get_offline_suggestions_enabled,
+        commands::set_offline_suggestions_enabled,
+        commands::get_llm_settings,
+        commands::update_llm_settings,
+    ];
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .setup(|app| {
-            initialize_services(app)?;
+    builder
+        .plugin(tauri_plugin_notification::init())
+        .setup(move |app| {
+            initialize_services(app, read_only)?;
 
             // Create main window at startup (hidden)
-            let _main_window = WebviewWindowBuilder::new(app, "main", tauri::WebviewUrl::default())
-                .title("Foxus")
-                .inner_size(420.0, 600.0)
-                .resizable(true)
-                .visible(false)
-                .center()
-                .build()?;
+            let mut main_window =
+                WebviewWindowBuilder::new(app, "main", tauri::WebviewUrl::default())
+                    .title("Foxus")
+                    .inner_size(420.0, 600.0)
+                    .resizable(true)
+                    .visible(false)
+                    .center();
+            if let Some(dir) = data_dir::webview_dir() {
+                main_window = main_window.data_directory(dir);
+            }
+            main_window.build()?;
 
-            // Setup tray with initial menu
-            let open = MenuItem::with_id(app, "open", "Open Foxus", true, None::<&str>)?;
-            let separator = PredefinedMenuItem::separator(app)?;
-            let focus_10 =
-                MenuItem::with_id(app, "focus_10", "Start Focus (10 min)", true, None::<&str>)?;
-            let focus_25 =
-                MenuItem::with_id(app, "focus_25", "Start Focus (25 min)", true, None::<&str>)?;
-            let focus_60 =
-                MenuItem::with_id(app, "focus_60", "Start Focus (1 hour)", true, None::<&str>)?;
-            let quit = MenuItem::with_id(app, "quit", "Quit Foxus", true, None::<&str>)?;
-            let menu = Menu::with_items(
-                app,
-                &[
-                    &open, &separator, &focus_10, &focus_25, &focus_60, &separator, &quit,
-                ],
-            )?;
+            tray::setup_tray(app.handle())?;
 
-            let tray = TrayIconBuilder::new()
-                .icon(
-                    app.default_window_icon()
-                        .expect("default window icon must be set in tauri.conf.json")
-                        .clone(),
-                )
-                .icon_as_template(true)
-                .menu(&menu)
-                .show_menu_on_left_click(true)
-                .tooltip("Foxus")
-                .on_menu_event(|app, event| {
-                    handle_tray_event(app, event.id.0.as_str());
-                })
-                .build(app)?;
+            let scheduler = scheduler::SchedulerService::new(app.handle().clone());
+            if !read_only {
+                scheduler.start();
+            }
+            app.manage(Arc::new(scheduler));
 
-            // Store tray handle for dynamic menu updates
-            app.manage(TrayHandle(Mutex::new(Some(tray))));
+            if !read_only {
+                updates::start_periodic_check(app.handle());
+                overlay::start_overlay_writer(app.handle());
+            }
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            commands::get_today_stats,
-            commands::get_weekly_stats,
-            commands::get_focus_state,
-            commands::start_focus_session,
-            commands::end_focus_session,
-            commands::get_focus_schedules,
-            commands::create_focus_schedule,
-            commands::update_focus_schedule,
-            commands::delete_focus_schedule,
-            commands::get_active_schedule,
-            commands::check_focus_schedules,
-            commands::get_categories,
-            commands::create_category,
-            commands::update_category,
-            commands::delete_category,
-            commands::get_rules,
-            commands::create_rule,
-            commands::update_rule,
-            commands::delete_rule,
-        ])
+        .invoke_handler(move |invoke| {
+            if read_only && !read_only::allows(invoke.message.command()) {
+                invoke.resolver.reject(read_only::REJECTION);
+                return true;
+            }
+            handler(invoke)
+        })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app, event| {
@@ -0,0 +1,342 @@
+//! Calendar helpers over chrono: local days, wall-clock times, and week
+//! starts. Day and weekday math goes through here so it follows the time
+//! zone and its DST rules rather than dividing Unix seconds.
+
+use crate::models::Setting;
+use chrono::{
+    DateTime, Datelike, Days, Local, MappedLocalTime, NaiveDate, NaiveTime, TimeDelta, TimeZone,
+    Weekday,
+};
+use rusqlite::{Connection, Result};
+
+const WEEK_START_SETTING: &str = "locale.week_start";
+
+/// Regions whose weeks start on Sunday (CLDR `firstDay`); everywhere else
+/// gets Monday.
+const SUNDAY_START_REGIONS: [&str; 46] = [
+    "AG", "AS", "BR", "BS", "BT", "BW", "BZ", "CA", "CO", "DM", "DO", "ET", "GT", "GU", "HK", "HN",
+    "ID", "IL", "IN", "JM", "JP", "KE", "KH", "KR", "LA", "MH", "MM", "MO", "MX", "MZ", "NI", "NP",
+    "PA", "PE", "PH", "PR", "PY", "SA", "SG", "SV", "TH", "TW", "US", "VE", "ZA", "ZW",
+];
+
+/// Local date (YYYY-MM-DD), local time (HH:MM), and local midnight as a Unix timestamp.
+pub fn local_today() -> (String, String, i64) {
+    let now = Local::now();
+    (
+        now.format("%Y-%m-%d").to_string(),
+        now.format("%H:%M").to_string(),
+        local_midnight(now.date_naive()),
+    )
+}
+
+/// Local date (YYYY-MM-DD) of a Unix timestamp.
+pub fn local_day(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Day of week (1=Monday, 7=Sunday) and wall-clock time (HH:MM) of a
+/// Unix timestamp in `tz`.
+pub fn day_and_time_in<Tz: TimeZone>(tz: &Tz, timestamp: i64) -> (u32, String) {
+    let local = DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(tz);
+    (
+        local.weekday().number_from_monday(),
+        local.time().format("%H:%M").to_string(),
+    )
+}
+
+/// Calendar date of a Unix timestamp in `tz`.
+pub fn date_in<Tz: TimeZone>(tz: &Tz, timestamp: i64) -> NaiveDate {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(tz)
+        .date_naive()
+}
+
+/// Unix timestamp of wall-clock `time` on `date` in `tz`. A time repeated
+/// when clocks go back maps to its first occurrence; one skipped when they
+/// go forward maps to the moment of the jump.
+pub fn wall_time_at<Tz: TimeZone>(tz: &Tz, date: NaiveDate, time: NaiveTime) -> i64 {
+    let wall = date.and_time(time);
+    // DST gaps are at most a few hours; step over one a minute at a time
+    (0..=180)
+        .find_map(
+            |minutes| match tz.from_local_datetime(&(wall + TimeDelta::minutes(minutes))) {
+                MappedLocalTime::Single(at) | MappedLocalTime::Ambiguous(at, _) => {
+                    Some(at.timestamp())
+                }
+                MappedLocalTime::None => None,
+            },
+        )
+        .unwrap_or_else(|| wall.and_utc().timestamp())
+}
+
+/// Unix timestamps of local midnight at the start and end of `date`.
+pub fn day_bounds(date: NaiveDate) -> (i64, i64) {
+    day_bounds_in(&Local, date)
+}
+
+/// Unix timestamps of midnight at the start and end of `date` in `tz`.
+/// Days with a DST change are 23 or 25 hours long.
+pub fn day_bounds_in<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> (i64, i64) {
+    let next = date.succ_opt().unwrap_or(date);
+    (midnight_in(tz, date), midnight_in(tz, next))
+}
+
+fn local_midnight(date: NaiveDate) -> i64 {
+    midnight_in(&Local, date)
+}
+
+// Some zones skip midnight on DST days; the day then starts at the jump
+fn midnight_in<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> i64 {
+    wall_time_at(tz, date, NaiveTime::MIN)
+}
+
+/// The `count` days ending on `last`, oldest first.
+pub fn days_ending(last: NaiveDate, count: u64) -> impl Iterator<Item = NaiveDate> {
+    let first = last
+        .checked_sub_days(Days::new(count.saturating_sub(1)))
+        .unwrap_or(last);
+    first
+        .iter_days()
+        .take(usize::try_from(count).unwrap_or(usize::MAX))
+}
+
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// First day of the week for stats, heatmaps, and day pickers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "monday" => Some(Self::Monday),
+            "sunday" => Some(Self::Sunday),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Monday => "monday",
+            Self::Sunday => "sunday",
+        }
+    }
+
+    /// Week start for a POSIX locale such as `en_US.UTF-8`.
+    pub fn for_locale(locale: &str) -> Self {
+        let region = locale
+            .split(['.', '@'])
+            .next()
+            .and_then(|name| name.split_once(['_', '-']))
+            .map(|(_, region)| region.to_ascii_uppercase());
+        match region {
+            Some(region) if SUNDAY_START_REGIONS.contains(&region.as_str()) => Self::Sunday,
+            _ => Self::Monday,
+        }
+    }
+
+    /// Week start of the locale dates are formatted in, from `LC_ALL`,
+    /// `LC_TIME`, or `LANG`.
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map_or(Self::Monday, |locale| Self::for_locale(&locale))
+    }
+
+    fn weekday(self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+
+    /// Days from the start of the week to `weekday`, 0 to 6.
+    pub fn days_into_week(self, weekday: Weekday) -> u32 {
+        weekday.days_since(self.weekday())
+    }
+
+    /// The first day of the week `date` falls in.
+    pub fn week_of(self, date: NaiveDate) -> NaiveDate {
+        date.checked_sub_days(Days::new(u64::from(self.days_into_week(date.weekday()))))
+            .unwrap_or(date)
+    }
+
+    /// The days of the week in display order.
+    pub fn days(self) -> impl Iterator<Item = Weekday> {
+        std::iter::successors(Some(self.weekday()), |day| Some(day.succ())).take(7)
+    }
+}
+
+/// The week start the user chose, if any.
+pub fn week_start_setting(conn: &Connection) -> Result<Option<WeekStart>> {
+    Ok(Setting::get(conn, WEEK_START_SETTING)?
+        .as_deref()
+        .and_then(WeekStart::parse))
+}
+
+/// The week start to use: the user's choice, else the locale's.
+pub fn week_start(conn: &Connection) -> Result<WeekStart> {
+    Ok(week_start_setting(conn)?.unwrap_or_else(WeekStart::detect))
+}
+
+/// Pin the week start, or follow the locale again with `None`.
+pub fn set_week_start(conn: &Connection, week_start: Option<WeekStart>) -> Result<()> {
+    match week_start {
+        Some(week_start) => Setting::set(conn, WEEK_START_SETTING, week_start.as_str()),
+        None => Setting::delete(conn, WEEK_START_SETTING),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::CentralEurope;
+
+    #[test]
+    fn test_day_bounds_span_one_day() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let (start, end) = day_bounds(date);
+        assert_eq!(end - start, 86400);
+    }
+
+    #[test]
+    fn test_day_and_time_follow_dst() {
+        // 2026-03-29 00:30 UTC is 01:30 CET; an hour later clocks jump to 03:30 CEST
+        let before = 1_774_744_200;
+        assert_eq!(day_and_time_in(&CentralEurope, before), (7, "01:30".into()));
+        assert_eq!(
+            day_and_time_in(&CentralEurope, before + 3600),
+            (7, "03:30".into())
+        );
+        assert_eq!(day_and_time_in(&chrono::Utc, before), (7, "00:30".into()));
+    }
+
+    #[test]
+    fn test_wall_time_at_dst_transitions() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let spring = NaiveDate::from_ymd_opt(2026, 3, 29).unwrap();
+        let fall = NaiveDate::from_ymd_opt(2026, 10, 25).unwrap();
+
+        // 01:00 CET is 00:00 UTC; 04:00 CEST is 02:00 UTC
+        let midnight_utc = spring.and_time(NaiveTime::MIN).and_utc().timestamp();
+        assert_eq!(
+            wall_time_at(&CentralEurope, spring, time(1, 0)),
+            midnight_utc
+        );
+        assert_eq!(
+            wall_time_at(&CentralEurope, spring, time(4, 0)),
+            midnight_utc + 2 * 3600
+        );
+        // 02:30 doesn't exist; it maps to the jump at 01:00 UTC
+        assert_eq!(
+            wall_time_at(&CentralEurope, spring, time(2, 30)),
+            midnight_utc + 3600
+        );
+        // 02:30 happens twice; the first time is 00:30 UTC (still CEST)
+        let fall_midnight_utc = fall.and_time(NaiveTime::MIN).and_utc().timestamp();
+        assert_eq!(
+            wall_time_at(&CentralEurope, fall, time(2, 30)),
+            fall_midnight_utc + 30 * 60
+        );
+    }
+
+    #[test]
+    fn test_day_bounds_across_dst() {
+        let spring = NaiveDate::from_ymd_opt(2026, 3, 29).unwrap();
+        let (start, end) = day_bounds_in(&CentralEurope, spring);
+        assert_eq!(end - start, 23 * 3600);
+
+        let fall = NaiveDate::from_ymd_opt(2026, 10, 25).unwrap();
+        let (start, end) = day_bounds_in(&CentralEurope, fall);
+        assert_eq!(end - start, 25 * 3600);
+        assert_eq!(day_and_time_in(&CentralEurope, start), (7, "00:00".into()));
+    }
+
+    #[test]
+    fn test_weekdays_across_leap_days() {
+        let weekday = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap().weekday();
+        assert_eq!(weekday(2028, 2, 29), Weekday::Tue);
+        assert_eq!(weekday(2028, 3, 1), Weekday::Wed);
+        // 2100 is not a leap year
+        assert_eq!(NaiveDate::from_ymd_opt(2100, 2, 29), None);
+        assert_eq!(weekday(2100, 3, 1), Weekday::Mon);
+
+        // 2028-02-29 12:00 UTC
+        assert_eq!(
+            day_and_time_in(&chrono::Utc, 1_835_438_400),
+            (2, "12:00".into())
+        );
+    }
+
+    #[test]
+    fn test_days_ending_spans_leap_day() {
+        let last = NaiveDate::from_ymd_opt(2028, 3, 2).unwrap();
+        let days: Vec<_> = days_ending(last, 4).collect();
+        assert_eq!(days.len(), 4);
+        assert_eq!(days.first(), NaiveDate::from_ymd_opt(2028, 2, 28).as_ref());
+        assert!(days.contains(&NaiveDate::from_ymd_opt(2028, 2, 29).unwrap()));
+        assert_eq!(days_ending(last, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_is_weekend() {
+        // 1970-01-03 was a Saturday, 1970-01-05 a Monday
+        assert!(is_weekend(NaiveDate::from_ymd_opt(1970, 1, 3).unwrap()));
+        assert!(is_weekend(NaiveDate::from_ymd_opt(1970, 1, 4).unwrap()));
+        assert!(!is_weekend(NaiveDate::from_ymd_opt(1970, 1, 5).unwrap()));
+    }
+
+    #[test]
+    fn test_week_start_for_locale() {
+        assert_eq!(WeekStart::for_locale("en_US.UTF-8"), WeekStart::Sunday);
+        assert_eq!(WeekStart::for_locale("pt-BR"), WeekStart::Sunday);
+        assert_eq!(WeekStart::for_locale("en_GB.UTF-8"), WeekStart::Monday);
+        assert_eq!(WeekStart::for_locale("de_DE@euro"), WeekStart::Monday);
+        assert_eq!(WeekStart::for_locale("C"), WeekStart::Monday);
+    }
+
+    #[test]
+    fn test_week_of() {
+        // A Wednesday
+        let date = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+        assert_eq!(
+            WeekStart::Monday.week_of(date),
+            NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()
+        );
+        assert_eq!(
+            WeekStart::Sunday.week_of(date),
+            NaiveDate::from_ymd_opt(2026, 3, 8).unwrap()
+        );
+        let sunday = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+        assert_eq!(WeekStart::Sunday.week_of(sunday), sunday);
+        assert_eq!(WeekStart::Sunday.days_into_week(Weekday::Sat), 6);
+        let days: Vec<_> = WeekStart::Sunday.days().collect();
+        assert_eq!(days.first(), Some(&Weekday::Sun));
+        assert_eq!(days.last(), Some(&Weekday::Sat));
+    }
+
+    #[test]
+    fn test_week_start_setting_overrides_locale() {
+        let (db, _dir) = crate::test_utils::setup_test_db();
+        let conn = db.connection();
+        assert_eq!(week_start_setting(conn).unwrap(), None);
+        set_week_start(conn, Some(WeekStart::Sunday)).unwrap();
+        assert_eq!(week_start(conn).unwrap(), WeekStart::Sunday);
+        set_week_start(conn, None).unwrap();
+        assert_eq!(week_start_setting(conn).unwrap(), None);
+    }
+}
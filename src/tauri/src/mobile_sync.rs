@@ -0,0 +1,478 @@
+//! Backend for the mobile companion: screen time reported by the phone, and
+//! sync between devices through a shared folder (iCloud Drive, Syncthing,
+//! and so on) so a focus session can be started from the phone.
+//!
+//! Each device writes `<device>.foxus-sync.json` to the folder with its own
+//! recent screen time and focus requests, and imports the other devices'
+//! files. Imports are idempotent, so files can be read any number of times.
+
+use crate::audit_log::Origin;
+use crate::categorizer::Categorizer;
+use crate::constants::{MAX_EXTERNAL_ACTIVITY_SECS, SECS_PER_DAY};
+use crate::db::Database;
+use crate::discord_presence::sync_discord_presence;
+use crate::error::AppError;
+use crate::external_activity::EXTERNAL_SOURCE_PREFIX;
+use crate::focus::FocusManager;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Setting};
+use crate::mqtt::publish_focus_state;
+use crate::safe_lock;
+use crate::tray::refresh_tray;
+use crate::validation::{validate_device_name, validate_external_duration};
+use log::{error, warn};
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const FOLDER_SETTING: &str = "sync.folder";
+const DEVICE_SETTING: &str = "sync.device";
+
+/// Label of screen time reported on this device, after `external:`.
+const SCREEN_TIME_LABEL: &str = "mobile";
+
+/// Prefix on `activities.source` for screen time imported from another
+/// device, followed by its name. Kept apart from `external:mobile` so
+/// imported time isn't exported again.
+pub const SYNCED_SOURCE_PREFIX: &str = "synced:";
+
+const SYNC_FILE_SUFFIX: &str = ".foxus-sync.json";
+
+/// How far back screen time is included in a device's sync file.
+const SYNC_WINDOW_SECS: i64 = 7 * SECS_PER_DAY;
+
+/// Focus requests older than this are ignored, so a desktop that was off
+/// doesn't start a session hours after the phone asked for it.
+const FOCUS_REQUEST_TTL_SECS: i64 = 10 * 60;
+
+/// Time an app was in use on the phone, as reported by its screen-time API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenTimeEntry {
+    pub app: String,
+    /// Unix seconds.
+    pub start: i64,
+    pub duration_secs: i32,
+}
+
+/// A focus session asked for on one device, to be started on the others.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusRequest {
+    /// Unique per device.
+    pub id: i64,
+    pub requested_at: i64,
+    pub duration_secs: i32,
+    pub budget_secs: i32,
+}
+
+/// What one device shares with the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncBundle {
+    pub device: String,
+    pub written_at: i64,
+    pub screen_time: Vec<ScreenTimeEntry>,
+    pub focus_requests: Vec<FocusRequest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncSettings {
+    /// Shared folder; sync is off while unset.
+    pub folder: Option<String>,
+    /// Names this device's sync file.
+    pub device: String,
+}
+
+impl SyncSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            folder: Setting::get(conn, FOLDER_SETTING)?,
+            device: Setting::get(conn, DEVICE_SETTING)?.unwrap_or_else(default_device),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        match &self.folder {
+            Some(folder) => Setting::set(conn, FOLDER_SETTING, folder)?,
+            None => Setting::delete(conn, FOLDER_SETTING)?,
+        }
+        Setting::set(conn, DEVICE_SETTING, &self.device)
+    }
+}
+
+fn default_device() -> String {
+    if cfg!(mobile) { "phone" } else { "desktop" }.to_string()
+}
+
+/// What a sync brought in from the other devices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncResult {
+    pub imported_screen_time: usize,
+    pub started_sessions: usize,
+}
+
+/// Store an entry under `source` and categorize it like an app. Returns
+/// false if it was already stored.
+fn store_entry(
+    conn: &Connection,
+    categorizer: &Categorizer,
+    source: &str,
+    entry: &ScreenTimeEntry,
+) -> Result<bool> {
+    let app = entry.app.trim();
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM activities
+         WHERE source = ?1 AND app_name = ?2 AND timestamp = ?3",
+        params![source, app, entry.start],
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Ok(false);
+    }
+    let mut activity = Activity::new(entry.start, entry.duration_secs, source, Some(app), None);
+    activity.category_id = Some(categorizer.categorize_app(app, None));
+    activity.save(conn)?;
+    Ok(true)
+}
+
+/// Store screen time reported on this device. Entries already stored are
+/// skipped, so the phone can resend a whole day. Returns how many were new.
+pub fn record_screen_time(
+    conn: &Connection,
+    categorizer: &Categorizer,
+    entries: &[ScreenTimeEntry],
+    now: i64,
+) -> Result<usize, AppError> {
+    for entry in entries {
+        if entry.app.trim().is_empty() {
+            return Err(AppError::InvalidInput {
+                field: "app",
+                reason: "cannot be empty".into(),
+            });
+        }
+        validate_external_duration(entry.duration_secs)?;
+        if entry.start + i64::from(entry.duration_secs) > now {
+            return Err(AppError::InvalidInput {
+                field: "start",
+                reason: "screen time cannot end in the future".into(),
+            });
+        }
+    }
+
+    let source = format!("{EXTERNAL_SOURCE_PREFIX}{SCREEN_TIME_LABEL}");
+    let tx = conn.unchecked_transaction()?;
+    let mut stored = 0;
+    for entry in entries {
+        if store_entry(&tx, categorizer, &source, entry)? {
+            stored += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(stored)
+}
+
+/// Ask the other devices to start a focus session.
+pub fn queue_focus_request(
+    conn: &Connection,
+    duration_secs: i32,
+    budget_secs: i32,
+    now: i64,
+) -> Result<FocusRequest> {
+    conn.execute(
+        "INSERT INTO focus_requests (requested_at, duration_secs, budget_secs) VALUES (?1, ?2, ?3)",
+        params![now, duration_secs, budget_secs],
+    )?;
+    Ok(FocusRequest {
+        id: conn.last_insert_rowid(),
+        requested_at: now,
+        duration_secs,
+        budget_secs,
+    })
+}
+
+/// This device's recent screen time and focus requests.
+pub fn build_bundle(conn: &Connection, device: &str, now: i64) -> Result<SyncBundle> {
+    let mut stmt = conn.prepare(
+        "SELECT app_name, timestamp, duration_secs FROM activities
+         WHERE source = ?1 AND timestamp >= ?2 ORDER BY timestamp",
+    )?;
+    let screen_time = stmt
+        .query_map(
+            params![
+                format!("{EXTERNAL_SOURCE_PREFIX}{SCREEN_TIME_LABEL}"),
+                now - SYNC_WINDOW_SECS
+            ],
+            |row| {
+                Ok(ScreenTimeEntry {
+                    app: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                    start: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                })
+            },
+        )?
+        .collect::<Result<_>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, requested_at, duration_secs, budget_secs FROM focus_requests
+         WHERE requested_at >= ?1 ORDER BY id",
+    )?;
+    let focus_requests = stmt
+        .query_map(params![now - FOCUS_REQUEST_TTL_SECS], |row| {
+            Ok(FocusRequest {
+                id: row.get(0)?,
+                requested_at: row.get(1)?,
+                duration_secs: row.get(2)?,
+                budget_secs: row.get(3)?,
+            })
+        })?
+        .collect::<Result<_>>()?;
+
+    Ok(SyncBundle {
+        device: device.to_string(),
+        written_at: now,
+        screen_time,
+        focus_requests,
+    })
+}
+
+/// Store another device's screen time and claim its fresh focus requests.
+/// Returns how much screen time was new and the requests to start, each
+/// returned only once.
+pub fn import_bundle(
+    conn: &Connection,
+    categorizer: &Categorizer,
+    bundle: &SyncBundle,
+    now: i64,
+) -> Result<(usize, Vec<FocusRequest>), AppError> {
+    let device = validate_device_name(&bundle.device)?;
+    let source = format!("{SYNCED_SOURCE_PREFIX}{device}");
+
+    let tx = conn.unchecked_transaction()?;
+    let mut imported = 0;
+    for entry in &bundle.screen_time {
+        let valid = !entry.app.trim().is_empty()
+            && (1..=MAX_EXTERNAL_ACTIVITY_SECS).contains(&entry.duration_secs);
+        if valid && store_entry(&tx, categorizer, &source, entry)? {
+            imported += 1;
+        }
+    }
+
+    let mut requests = Vec::new();
+    for request in &bundle.focus_requests {
+        if request.requested_at < now - FOCUS_REQUEST_TTL_SECS || request.requested_at > now {
+            continue;
+        }
+        let claimed = tx.execute(
+            "INSERT OR IGNORE INTO sync_applied_requests (device, request_id) VALUES (?1, ?2)",
+            params![device, request.id],
+        )?;
+        if claimed > 0 {
+            requests.push(request.clone());
+        }
+    }
+    tx.commit()?;
+    Ok((imported, requests))
+}
+
+/// Write `contents` to `path` through a temporary file, so other devices
+/// never read a half-written file.
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents).and_then(|()| std::fs::rename(&tmp, path))
+}
+
+/// Sync files of the other devices in `folder`.
+fn other_bundles(folder: &Path, own_file: &str) -> Vec<SyncBundle> {
+    let entries = match std::fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read sync folder: {e}");
+            return Vec::new();
+        }
+    };
+    entries
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.ends_with(SYNC_FILE_SUFFIX) && name != own_file
+        })
+        .filter_map(|entry| {
+            let contents = std::fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str(&contents)
+                .map_err(|e| warn!("Skipping unreadable sync file: {e}"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Share this device's data through the sync folder and import the other
+/// devices', starting any focus sessions they asked for. Does nothing while
+/// no folder is set.
+pub fn sync(
+    db: &Mutex<Database>,
+    categorizer: &Mutex<Categorizer>,
+    focus_manager: &FocusManager,
+    now: i64,
+) -> Result<SyncResult, AppError> {
+    let (settings, bundle) = {
+        let db = db.lock().map_err(|_| AppError::LockPoisoned)?;
+        let settings = SyncSettings::load(db.connection())?;
+        let bundle = build_bundle(db.connection(), &settings.device, now)?;
+        (settings, bundle)
+    };
+    let Some(folder) = settings.folder else {
+        return Ok(SyncResult::default());
+    };
+    let folder = Path::new(&folder);
+
+    let own_file = format!("{}{SYNC_FILE_SUFFIX}", settings.device);
+    let json = serde_json::to_string(&bundle)
+        .map_err(|e| AppError::Internal(format!("failed to encode sync file: {e}")))?;
+    write_atomically(&folder.join(&own_file), &json)
+        .map_err(|e| AppError::Internal(format!("failed to write sync file: {e}")))?;
+
+    let mut result = SyncResult::default();
+    for other in other_bundles(folder, &own_file) {
+        if other.device == settings.device {
+            continue;
+        }
+        let (imported, requests) = {
+            let categorizer = safe_lock(categorizer, "Categorizer");
+            let db = db.lock().map_err(|_| AppError::LockPoisoned)?;
+            import_bundle(db.connection(), &categorizer, &other, now)?
+        };
+        result.imported_screen_time += imported;
+        for request in requests {
+            match focus_manager.start_timed_session(
+                request.budget_secs,
+                Some(request.duration_secs),
+                false,
+                &[],
+                Origin::Api,
+            ) {
+                Ok(_) => result.started_sessions += 1,
+                Err(e) => warn!("Skipped focus request from {}: {e}", other.device),
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Periodic sync while a folder is set.
+pub fn check_sync(app: &AppHandle) {
+    let (Some(db), Some(categorizer), Some(focus_manager)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<Mutex<Categorizer>>>(),
+        app.try_state::<Arc<FocusManager>>(),
+    ) else {
+        return;
+    };
+    match sync(&db, &categorizer, &focus_manager, current_timestamp()) {
+        Ok(result) => {
+            if result.started_sessions > 0 {
+                refresh_tray(app);
+                sync_discord_presence(app);
+                publish_focus_state(app);
+            }
+        }
+        Err(e) => error!("Failed to sync with other devices: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn entry(app: &str, start: i64) -> ScreenTimeEntry {
+        ScreenTimeEntry {
+            app: app.to_string(),
+            start,
+            duration_secs: 600,
+        }
+    }
+
+    #[test]
+    fn test_record_screen_time_skips_resent_entries() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let categorizer = Categorizer::new(conn).unwrap();
+
+        let entries = [entry("Slack", NOW - 3600), entry("Kindle", NOW - 1800)];
+        assert_eq!(
+            record_screen_time(conn, &categorizer, &entries, NOW).unwrap(),
+            2
+        );
+        assert_eq!(
+            record_screen_time(conn, &categorizer, &entries, NOW).unwrap(),
+            0
+        );
+        assert!(record_screen_time(conn, &categorizer, &[entry("Slack", NOW)], NOW).is_err());
+    }
+
+    #[test]
+    fn test_bundle_round_trip_between_devices() {
+        let (phone_db, _phone_dir) = setup_test_db();
+        let phone = phone_db.connection();
+        let categorizer = Categorizer::new(phone).unwrap();
+        record_screen_time(phone, &categorizer, &[entry("Kindle", NOW - 3600)], NOW).unwrap();
+        queue_focus_request(phone, 1500, 300, NOW - 60).unwrap();
+        queue_focus_request(phone, 1500, 300, NOW - FOCUS_REQUEST_TTL_SECS - 1).unwrap();
+        let bundle = build_bundle(phone, "phone", NOW).unwrap();
+        assert_eq!(bundle.screen_time.len(), 1);
+        assert_eq!(bundle.focus_requests.len(), 1);
+
+        let (desktop_db, _desktop_dir) = setup_test_db();
+        let desktop = desktop_db.connection();
+        let categorizer = Categorizer::new(desktop).unwrap();
+        let (imported, requests) = import_bundle(desktop, &categorizer, &bundle, NOW).unwrap();
+        assert_eq!((imported, requests.len()), (1, 1));
+
+        // Reading the same file again changes nothing
+        let (imported, requests) = import_bundle(desktop, &categorizer, &bundle, NOW).unwrap();
+        assert_eq!((imported, requests.len()), (0, 0));
+
+        // Imported time is the phone's, not re-shared as the desktop's own
+        assert!(build_bundle(desktop, "desktop", NOW)
+            .unwrap()
+            .screen_time
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sync_through_folder_starts_requested_session() {
+        let folder = tempfile::tempdir().unwrap();
+        let folder_path = folder.path().to_string_lossy().into_owned();
+
+        let (phone_db, _phone_dir) = setup_test_db();
+        let phone_settings = SyncSettings {
+            folder: Some(folder_path.clone()),
+            device: "phone".into(),
+        };
+        phone_settings.save(phone_db.connection()).unwrap();
+        queue_focus_request(phone_db.connection(), 1500, 300, NOW - 60).unwrap();
+        let phone_db = Arc::new(Mutex::new(phone_db));
+        let phone_categorizer =
+            Mutex::new(Categorizer::new(phone_db.lock().unwrap().connection()).unwrap());
+        let phone_focus = FocusManager::new(Arc::clone(&phone_db));
+        sync(&phone_db, &phone_categorizer, &phone_focus, NOW).unwrap();
+
+        let (desktop_db, _desktop_dir) = setup_test_db();
+        SyncSettings {
+            folder: Some(folder_path),
+            device: "desktop".into(),
+        }
+        .save(desktop_db.connection())
+        .unwrap();
+        let desktop_db = Arc::new(Mutex::new(desktop_db));
+        let desktop_categorizer =
+            Mutex::new(Categorizer::new(desktop_db.lock().unwrap().connection()).unwrap());
+        let desktop_focus = FocusManager::new(Arc::clone(&desktop_db));
+        let result = sync(&desktop_db, &desktop_categorizer, &desktop_focus, NOW).unwrap();
+        assert_eq!(result.started_sessions, 1);
+        assert!(desktop_focus.get_state().unwrap().active);
+    }
+}
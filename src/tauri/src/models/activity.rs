@@ -1,4 +1,5 @@
-use rusqlite::{params, Connection, Result};
+use super::MatchType;
+use rusqlite::{params, Connection, OptionalExtension, Result};
 
 #[derive(Debug, Clone)]
 pub struct Activity {
@@ -11,8 +12,42 @@ pub struct Activity {
     pub url: Option<String>,
     pub domain: Option<String>,
     pub category_id: Option<i64>,
+    /// Categories of the other windows on screen, when context capture is on.
+    /// See `window_context::snapshot`.
+    pub context: Option<String>,
+    /// Browser profile or container reported by the extension.
+    pub profile: Option<String>,
+    /// Browser id (`chrome`, `firefox`, ...) the extension reported, or the
+    /// tracker recognized from the app name. See `browsers`.
+    pub browser: Option<String>,
 }
 
+/// An app or domain whose time hasn't been matched by any rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UncategorizedSource {
+    /// `Domain` for browser activity, `App` otherwise.
+    pub match_type: MatchType,
+    pub name: String,
+    /// One window title seen for it, as context for classifiers.
+    pub sample_title: Option<String>,
+    pub duration_secs: i32,
+}
+
+/// Which activities `Activity::recategorize` moves. Unset fields match any.
+#[derive(Debug, Clone, Copy)]
+pub struct ActivityFilter<'a> {
+    pub start: i64,
+    pub end: i64,
+    /// Recorded name or app identity display name, as `top_apps` shows it.
+    pub app_name: Option<&'a str>,
+    pub domain: Option<&'a str>,
+    /// Only move rows currently in this category.
+    pub category_id: Option<i64>,
+}
+
+/// (id, app name, window title) of an activity.
+pub type ActivityTitle = (i64, Option<String>, Option<String>);
+
 impl Activity {
     pub fn new(
         timestamp: i64,
@@ -31,13 +66,16 @@ impl Activity {
             url: None,
             domain: None,
             category_id: None,
+            context: None,
+            profile: None,
+            browser: None,
         }
     }
 
     pub fn save(&mut self, conn: &Connection) -> Result<()> {
         conn.execute(
-            "INSERT INTO activities (timestamp, duration_secs, source, app_name, window_title, url, domain, category_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO activities (timestamp, duration_secs, source, app_name, window_title, url, domain, category_id, context, profile, browser)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 self.timestamp,
                 self.duration_secs,
@@ -47,20 +85,65 @@ impl Activity {
                 self.url,
                 self.domain,
                 self.category_id,
+                self.context,
+                self.profile,
+                self.browser,
             ],
         )?;
         self.id = Some(conn.last_insert_rowid());
         Ok(())
     }
 
+    /// End of the latest activity recorded at or after `since`: the last time
+    /// the tracker was known to be running.
+    pub fn last_seen_since(conn: &Connection, since: i64) -> Result<Option<i64>> {
+        conn.query_row(
+            "SELECT MAX(timestamp + duration_secs) FROM activities WHERE timestamp >= ?1",
+            params![since],
+            |row| row.get(0),
+        )
+    }
+
+    /// Seconds the window tracker itself recorded in the range, leaving out
+    /// the extension, editors and other sources.
+    pub fn tracked_secs(conn: &Connection, start: i64, end: i64) -> Result<i64> {
+        conn.query_row(
+            "SELECT COALESCE(SUM(duration_secs), 0) FROM activities
+             WHERE source = 'app' AND timestamp >= ?1 AND timestamp < ?2",
+            params![start, end],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn find_by_id(conn: &Connection, id: i64) -> Result<Option<Self>> {
+        conn.query_row(
+            "SELECT id, timestamp, duration_secs, source, app_name, window_title, url, domain, category_id, context, profile, browser
+             FROM activities WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(Self {
+                    id: Some(row.get(0)?),
+                    timestamp: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    source: row.get(3)?,
+                    app_name: row.get(4)?,
+                    window_title: row.get(5)?,
+                    url: row.get(6)?,
+                    domain: row.get(7)?,
+                    category_id: row.get(8)?,
+                    context: row.get(9)?,
+                    profile: row.get(10)?,
+                    browser: row.get(11)?,
+                })
+            },
+        )
+        .optional()
+    }
+
     /// Find activities within a time range.
-    #[cfg_attr(
-        not(test),
-        expect(dead_code, reason = "Public API for future activity querying")
-    )]
     pub fn find_in_range(conn: &Connection, start: i64, end: i64) -> Result<Vec<Self>> {
         let mut stmt = conn.prepare(
-            "SELECT id, timestamp, duration_secs, source, app_name, window_title, url, domain, category_id
+            "SELECT id, timestamp, duration_secs, source, app_name, window_title, url, domain, category_id, context, profile, browser
              FROM activities WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp"
         )?;
 
@@ -75,12 +158,405 @@ impl Activity {
                 url: row.get(6)?,
                 domain: row.get(7)?,
                 category_id: row.get(8)?,
+                context: row.get(9)?,
+                profile: row.get(10)?,
+                browser: row.get(11)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// Sum tracked seconds by category productivity: (productive, neutral, distracting).
+    pub fn productivity_totals(conn: &Connection, start: i64, end: i64) -> Result<(i32, i32, i32)> {
+        conn.query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN c.productivity = 1 THEN a.duration_secs END), 0),
+                COALESCE(SUM(CASE WHEN c.productivity = 0 THEN a.duration_secs END), 0),
+                COALESCE(SUM(CASE WHEN c.productivity = -1 THEN a.duration_secs END), 0)
+             FROM activities a
+             JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+    }
+
+    /// Start of the first and end of the last productive activity in the
+    /// range, or `None` when nothing productive was tracked. Activity is only
+    /// recorded while the user isn't idle, so this is the working hours span.
+    pub fn productive_span(conn: &Connection, start: i64, end: i64) -> Result<Option<(i64, i64)>> {
+        let (first, last): (Option<i64>, Option<i64>) = conn.query_row(
+            "SELECT MIN(a.timestamp), MAX(a.timestamp + a.duration_secs)
+             FROM activities a
+             JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND c.productivity > 0",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(first.zip(last))
+    }
+
+    /// Duration-weighted average category weight from -1.0 to 1.0, or `None`
+    /// when nothing categorized was tracked in the range.
+    pub fn average_weight(conn: &Connection, start: i64, end: i64) -> Result<Option<f64>> {
+        conn.query_row(
+            "SELECT SUM(a.duration_secs * COALESCE(c.weight, c.productivity))
+                    / SUM(a.duration_secs)
+             FROM activities a
+             JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2",
+            params![start, end],
+            |row| row.get(0),
+        )
+    }
+
+    /// Seconds spent in `app_name` per context snapshot, most time first.
+    /// Activities recorded without a snapshot are grouped under `None`.
+    pub fn context_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+        app_name: &str,
+    ) -> Result<Vec<(Option<String>, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT context, SUM(duration_secs) AS total
+             FROM activities
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND app_name = ?3
+             GROUP BY context
+             ORDER BY total DESC",
+        )?;
+
+        let rows = stmt.query_map(params![start, end, app_name], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Activities after `after_id`, oldest first.
+    pub fn titles_after(
+        conn: &Connection,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<ActivityTitle>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, app_name, window_title FROM activities
+             WHERE id > ?1
+             ORDER BY id
+             LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![after_id, limit], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Store the window-title context found for an activity; empty for none.
+    pub fn set_title_context(conn: &Connection, id: i64, context: &str) -> Result<()> {
+        conn.execute(
+            "UPDATE activities SET title_context = ?1 WHERE id = ?2",
+            params![context, id],
+        )?;
+        Ok(())
+    }
+
+    /// Seconds per window-title context (document, repo, ticket), most time
+    /// first. Activities without a context are left out.
+    pub fn title_context_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT title_context, SUM(duration_secs) AS total
+             FROM activities
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND title_context != ''
+             GROUP BY title_context
+             ORDER BY total DESC
+             LIMIT ?3",
+        )?;
+
+        let rows = stmt.query_map(params![start, end, limit], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Browser seconds per profile, most time first.
+    pub fn profile_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(Option<String>, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT profile, SUM(duration_secs) AS total
+             FROM activities
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND source = 'browser'
+             GROUP BY profile
+             ORDER BY total DESC",
+        )?;
+
+        let rows = stmt.query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        rows.collect()
+    }
+
+    /// Seconds per browser, split by who recorded them:
+    /// (browser, tracker seconds, extension seconds), most time first.
+    pub fn browser_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(String, i32, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT browser,
+                    COALESCE(SUM(CASE WHEN source = 'app' THEN duration_secs END), 0) AS app_secs,
+                    COALESCE(SUM(CASE WHEN source = 'browser' THEN duration_secs END), 0)
+             FROM activities
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND browser IS NOT NULL
+             GROUP BY browser
+             ORDER BY app_secs DESC, browser",
+        )?;
+
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Seconds per UTC hour and category, for anonymized aggregates:
+    /// (hour start, category name, productivity, seconds), oldest first.
+    /// Uncategorized time has no name or productivity.
+    pub fn category_hour_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(i64, Option<String>, Option<i32>, i64)>> {
+        let mut stmt = conn.prepare(
+            "SELECT a.timestamp - a.timestamp % 3600 AS hour, c.name, c.productivity,
+                    SUM(a.duration_secs)
+             FROM activities a
+             LEFT JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2
+             GROUP BY hour, a.category_id
+             ORDER BY hour, c.name",
+        )?;
+
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Top domains by tracked seconds with their category productivity:
+    /// (domain, seconds, productivity). `profile` limits it to one browser profile.
+    pub fn top_domains(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+        profile: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<(String, i32, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT a.domain, SUM(a.duration_secs) AS total, COALESCE(MAX(c.productivity), 0)
+             FROM activities a
+             LEFT JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND a.domain IS NOT NULL
+               AND (?3 IS NULL OR a.profile = ?3)
+             GROUP BY a.domain
+             ORDER BY total DESC
+             LIMIT ?4",
+        )?;
+
+        let rows = stmt.query_map(params![start, end, profile, limit], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Top apps by tracked seconds: (app name, seconds). Names are grouped by
+    /// app identity, so rows recorded before a mapping existed count too.
+    pub fn top_apps(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(ai.display_name, a.app_name) AS app, SUM(a.duration_secs) AS total
+             FROM activities a
+             LEFT JOIN app_identities ai ON ai.identifier = a.app_name
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND a.app_name IS NOT NULL
+             GROUP BY app
+             ORDER BY total DESC
+             LIMIT ?3",
+        )?;
+
+        let rows = stmt.query_map(params![start, end, limit], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Top distracting apps or domains by tracked seconds: (name, seconds).
+    pub fn top_distractions(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(a.domain, ai.display_name, a.app_name) AS source_name,
+                    SUM(a.duration_secs) AS total
+             FROM activities a
+             JOIN categories c ON a.category_id = c.id
+             LEFT JOIN app_identities ai ON ai.identifier = a.app_name
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2 AND c.productivity < 0
+               AND COALESCE(a.domain, a.app_name) IS NOT NULL
+             GROUP BY source_name
+             ORDER BY total DESC
+             LIMIT ?3",
+        )?;
+
+        let rows = stmt.query_map(params![start, end, limit], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+        rows.collect()
+    }
+
+    /// Apps and domains with uncategorized time since `since`, most time first.
+    pub fn uncategorized_sources(
+        conn: &Connection,
+        since: i64,
+        limit: i64,
+    ) -> Result<Vec<UncategorizedSource>> {
+        let mut stmt = conn.prepare(
+            "SELECT CASE WHEN a.domain IS NOT NULL THEN 'domain' ELSE 'app' END AS kind,
+                    COALESCE(a.domain, a.app_name) AS source_name,
+                    MAX(a.window_title),
+                    SUM(a.duration_secs) AS total
+             FROM activities a
+             LEFT JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1
+               AND (c.id IS NULL OR c.name = 'Uncategorized')
+               AND COALESCE(a.domain, a.app_name) IS NOT NULL
+             GROUP BY kind, source_name
+             ORDER BY total DESC
+             LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![since, limit], |row| {
+            let kind: String = row.get(0)?;
+            Ok(UncategorizedSource {
+                match_type: MatchType::from_str(&kind).unwrap_or(MatchType::App),
+                name: row.get(1)?,
+                sample_title: row.get(2)?,
+                duration_secs: row.get(3)?,
             })
         })?;
 
         rows.collect()
     }
 
+    /// Recent activities matched to a real category, newest first.
+    /// Training data for the offline classifier.
+    pub fn find_categorized(conn: &Connection, since: i64, limit: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.timestamp, a.duration_secs, a.source, a.app_name, a.window_title,
+                    a.url, a.domain, a.category_id, a.context, a.profile, a.browser
+             FROM activities a
+             JOIN categories c ON a.category_id = c.id
+             WHERE a.timestamp >= ?1 AND c.name != 'Uncategorized'
+             ORDER BY a.timestamp DESC
+             LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![since, limit], |row| {
+            Ok(Self {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                duration_secs: row.get(2)?,
+                source: row.get(3)?,
+                app_name: row.get(4)?,
+                window_title: row.get(5)?,
+                url: row.get(6)?,
+                domain: row.get(7)?,
+                category_id: row.get(8)?,
+                context: row.get(9)?,
+                profile: row.get(10)?,
+                browser: row.get(11)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// (id, category) of every activity matching `filter`.
+    fn categories_matching(
+        conn: &Connection,
+        filter: &ActivityFilter,
+    ) -> Result<Vec<(i64, Option<i64>)>> {
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.category_id FROM activities a
+             LEFT JOIN app_identities ai ON ai.identifier = a.app_name
+             WHERE a.timestamp >= ?1 AND a.timestamp < ?2
+               AND (?3 IS NULL OR a.app_name = ?3 COLLATE NOCASE
+                    OR ai.display_name = ?3 COLLATE NOCASE)
+               AND (?4 IS NULL OR a.domain = ?4 COLLATE NOCASE)
+               AND (?5 IS NULL OR a.category_id = ?5)",
+        )?;
+
+        let rows = stmt.query_map(
+            params![
+                filter.start,
+                filter.end,
+                filter.app_name,
+                filter.domain,
+                filter.category_id,
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        rows.collect()
+    }
+
+    pub fn set_category(conn: &Connection, id: i64, category_id: Option<i64>) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE activities SET category_id = ?1 WHERE id = ?2",
+            params![category_id, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Move every activity matching `filter` into `category_id`. Returns
+    /// each moved activity's (id, previous category), for undo.
+    pub fn recategorize(
+        conn: &Connection,
+        filter: &ActivityFilter,
+        category_id: i64,
+    ) -> Result<Vec<(i64, Option<i64>)>> {
+        let moved = Self::categories_matching(conn, filter)?;
+        for (id, _) in &moved {
+            Self::set_category(conn, *id, Some(category_id))?;
+        }
+        Ok(moved)
+    }
+
+    /// Sum tracked seconds per category within a time range.
+    #[cfg_attr(
+        not(test),
+        expect(dead_code, reason = "Public API for per-category stats breakdowns")
+    )]
     pub fn total_duration_by_category(
         conn: &Connection,
         start: i64,
@@ -118,6 +594,148 @@ mod tests {
         assert_eq!(found.first().unwrap().app_name, Some("VSCode".to_string()));
     }
 
+    #[test]
+    fn test_top_apps() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        for (offset, app, secs) in [(0, "Code", 30), (5, "Slack", 10), (10, "Code", 5)] {
+            Activity::new(now + offset, secs, "app", Some(app), None)
+                .save(conn)
+                .unwrap();
+        }
+
+        let top = Activity::top_apps(conn, now, now + 60, 1).unwrap();
+        assert_eq!(top, vec![("Visual Studio Code".to_string(), 35)]);
+
+        // Older rows under another identifier of the same app are merged
+        Activity::new(now + 15, 20, "app", Some("com.microsoft.VSCode"), None)
+            .save(conn)
+            .unwrap();
+        let top = Activity::top_apps(conn, now, now + 60, 5).unwrap();
+        assert_eq!(
+            top,
+            vec![
+                ("Visual Studio Code".to_string(), 55),
+                ("Slack".to_string(), 10)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recategorize_matches_filter() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        for (offset, app, domain, category) in [
+            (0, "Code", None, "Coding"),
+            (5, "com.microsoft.VSCode", None, "Coding"),
+            (10, "Firefox", Some("docs.rs"), "Entertainment"),
+            (15, "Code", None, "Entertainment"),
+            (100, "Code", None, "Coding"),
+        ] {
+            let mut activity = Activity::new(now + offset, 5, "app", Some(app), None);
+            activity.domain = domain.map(ToString::to_string);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        let filter = ActivityFilter {
+            start: now,
+            end: now + 60,
+            app_name: Some("visual studio code"),
+            domain: None,
+            category_id: Some(id_of("Coding")),
+        };
+        let moved = Activity::recategorize(conn, &filter, id_of("Reference")).unwrap();
+        assert_eq!(moved.len(), 2);
+        assert!(moved
+            .iter()
+            .all(|(_, previous)| *previous == Some(id_of("Coding"))));
+
+        let by_domain = ActivityFilter {
+            app_name: None,
+            domain: Some("docs.rs"),
+            category_id: None,
+            ..filter
+        };
+        let moved = Activity::recategorize(conn, &by_domain, id_of("Reference")).unwrap();
+        assert_eq!(moved, vec![(3, Some(id_of("Entertainment")))]);
+
+        let categories_now: Vec<Option<i64>> = Activity::find_in_range(conn, now, now + 200)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.category_id)
+            .collect();
+        assert_eq!(
+            categories_now,
+            vec![
+                Some(id_of("Reference")),
+                Some(id_of("Reference")),
+                Some(id_of("Reference")),
+                Some(id_of("Entertainment")),
+                Some(id_of("Coding")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_context_totals() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        for (offset, app, context) in [
+            (0, "Code", Some("[2]")),
+            (5, "Code", Some("[2]")),
+            (10, "Code", Some("[4]")),
+            (15, "Code", None),
+            (20, "Slack", Some("[2]")),
+        ] {
+            let mut activity = Activity::new(now + offset, 5, "app", Some(app), None);
+            activity.context = context.map(ToString::to_string);
+            activity.save(conn).unwrap();
+        }
+
+        let totals = Activity::context_totals(conn, now, now + 100, "Code").unwrap();
+        assert_eq!(totals.first(), Some(&(Some("[2]".to_string()), 10)));
+        assert_eq!(totals.len(), 3);
+        assert!(totals.contains(&(None, 5)));
+    }
+
+    #[test]
+    fn test_profile_totals_and_top_domains() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        for (offset, domain, profile) in [
+            (0, "github.com", Some("Work")),
+            (5, "github.com", Some("Work")),
+            (10, "youtube.com", Some("Personal")),
+            (15, "news.com", None),
+        ] {
+            let mut activity = Activity::new(now + offset, 5, "browser", None, None);
+            activity.domain = Some(domain.to_string());
+            activity.profile = profile.map(ToString::to_string);
+            activity.save(conn).unwrap();
+        }
+
+        let profiles = Activity::profile_totals(conn, now, now + 100).unwrap();
+        assert_eq!(profiles.first(), Some(&(Some("Work".to_string()), 10)));
+        assert_eq!(profiles.len(), 3);
+
+        let work = Activity::top_domains(conn, now, now + 100, Some("Work"), 10).unwrap();
+        assert_eq!(work, vec![("github.com".to_string(), 10, 0)]);
+
+        let all = Activity::top_domains(conn, now, now + 100, None, 10).unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
     #[test]
     fn test_total_duration_by_category() {
         let (db, _dir) = setup_test_db();
@@ -139,4 +757,166 @@ mod tests {
         let coding_total = totals.iter().find(|(id, _)| *id == coding_id);
         assert_eq!(coding_total, Some(&(coding_id, 50)));
     }
+
+    #[test]
+    fn test_productivity_totals() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        for (offset, duration, category) in [
+            (0, 30, "Coding"),
+            (30, 20, "Reference"),
+            (50, 15, "Communication"),
+            (65, 40, "Entertainment"),
+        ] {
+            let mut activity = Activity::new(now + offset, duration, "app", Some("App"), None);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        let totals = Activity::productivity_totals(conn, now, now + 100).unwrap();
+        assert_eq!(totals, (50, 15, 40));
+
+        let empty = Activity::productivity_totals(conn, now + 1000, now + 2000).unwrap();
+        assert_eq!(empty, (0, 0, 0));
+    }
+
+    #[test]
+    fn test_productive_span() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        for (offset, duration, category) in [
+            (0, 30, "Entertainment"),
+            (100, 20, "Coding"),
+            (200, 60, "Coding"),
+            (400, 30, "Entertainment"),
+        ] {
+            let mut activity = Activity::new(now + offset, duration, "app", Some("App"), None);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        assert_eq!(
+            Activity::productive_span(conn, now, now + 1000).unwrap(),
+            Some((now + 100, now + 260))
+        );
+        assert_eq!(
+            Activity::productive_span(conn, now + 1000, now + 2000).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_average_weight() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+        Category::set_weight(conn, id_of("Reference"), Some(0.5)).unwrap();
+
+        for (offset, duration, category) in [
+            (0, 30, "Coding"),
+            (30, 40, "Reference"),
+            (70, 30, "Entertainment"),
+        ] {
+            let mut activity = Activity::new(now + offset, duration, "app", Some("App"), None);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        // (30 * 1 + 40 * 0.5 - 30 * 1) / 100
+        let average = Activity::average_weight(conn, now, now + 100)
+            .unwrap()
+            .unwrap();
+        assert!((average - 0.2).abs() < 1e-9);
+        assert_eq!(
+            Activity::average_weight(conn, now + 1000, now + 2000).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_top_distractions() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        for (offset, duration, app, category) in [
+            (0, 30, "Code", "Coding"),
+            (30, 20, "Slack", "Entertainment"),
+            (50, 15, "Game", "Entertainment"),
+            (65, 40, "Slack", "Entertainment"),
+        ] {
+            let mut activity = Activity::new(now + offset, duration, "app", Some(app), None);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        let top = Activity::top_distractions(conn, now, now + 100, 5).unwrap();
+        assert_eq!(
+            top,
+            vec![("Slack".to_string(), 60), ("Game".to_string(), 15)]
+        );
+
+        let limited = Activity::top_distractions(conn, now, now + 100, 1).unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn test_uncategorized_sources() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        let mut coded = Activity::new(now, 30, "app", Some("Code"), None);
+        coded.category_id = Some(id_of("Coding"));
+        coded.save(conn).unwrap();
+
+        let mut unknown_app = Activity::new(now, 20, "app", Some("Zed"), Some("main.rs"));
+        unknown_app.category_id = Some(id_of("Uncategorized"));
+        unknown_app.save(conn).unwrap();
+
+        let mut unknown_site = Activity::new(now, 45, "browser", Some("Firefox"), None);
+        unknown_site.domain = Some("docs.rs".into());
+        unknown_site.save(conn).unwrap();
+
+        let sources = Activity::uncategorized_sources(conn, now, 10).unwrap();
+        assert_eq!(
+            sources,
+            vec![
+                UncategorizedSource {
+                    match_type: MatchType::Domain,
+                    name: "docs.rs".into(),
+                    sample_title: None,
+                    duration_secs: 45,
+                },
+                UncategorizedSource {
+                    match_type: MatchType::App,
+                    name: "Zed".into(),
+                    sample_title: Some("main.rs".into()),
+                    duration_secs: 20,
+                },
+            ]
+        );
+        assert!(Activity::uncategorized_sources(conn, now + 1, 10)
+            .unwrap()
+            .is_empty());
+    }
 }
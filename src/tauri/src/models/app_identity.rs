@@ -0,0 +1,118 @@
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// Display name for an app identifier (bundle id, `WM_CLASS`, or raw app
+/// name), so the same app is counted once however the platform reports it.
+/// Identifiers match case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppIdentity {
+    pub identifier: String,
+    pub display_name: String,
+}
+
+impl AppIdentity {
+    pub fn find_all(conn: &Connection) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT identifier, display_name FROM app_identities
+             ORDER BY display_name, identifier",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(Self {
+                identifier: row.get(0)?,
+                display_name: row.get(1)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// Insert or replace the display name for `identifier`.
+    pub fn set(conn: &Connection, identifier: &str, display_name: &str) -> Result<()> {
+        conn.execute(
+            "INSERT INTO app_identities (identifier, display_name) VALUES (?1, ?2)
+             ON CONFLICT(identifier) DO UPDATE SET display_name = excluded.display_name",
+            params![identifier, display_name],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(conn: &Connection, identifier: &str) -> Result<bool> {
+        let deleted = conn.execute(
+            "DELETE FROM app_identities WHERE identifier = ?1",
+            params![identifier],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    fn lookup(conn: &Connection, identifier: &str) -> Result<Option<String>> {
+        conn.query_row(
+            "SELECT display_name FROM app_identities WHERE identifier = ?1",
+            params![identifier],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Name to record for an app: the mapping for its bundle id, then for its
+    /// reported name, else the reported name unchanged.
+    pub fn display_name(
+        conn: &Connection,
+        bundle_id: Option<&str>,
+        app_name: &str,
+    ) -> Result<String> {
+        if let Some(bundle_id) = bundle_id {
+            if let Some(name) = Self::lookup(conn, bundle_id)? {
+                return Ok(name);
+            }
+        }
+        Ok(Self::lookup(conn, app_name)?.unwrap_or_else(|| app_name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_display_name_prefers_bundle_id() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        assert_eq!(
+            AppIdentity::display_name(conn, Some("com.microsoft.VSCode"), "Electron").unwrap(),
+            "Visual Studio Code"
+        );
+        // Defaults match the reported name case-insensitively
+        assert_eq!(
+            AppIdentity::display_name(conn, None, "FIREFOX").unwrap(),
+            "Firefox"
+        );
+        assert_eq!(
+            AppIdentity::display_name(conn, Some("com.example.unknown"), "Notes").unwrap(),
+            "Notes"
+        );
+    }
+
+    #[test]
+    fn test_set_and_delete() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        AppIdentity::set(conn, "code", "VS Code").unwrap();
+        assert_eq!(
+            AppIdentity::display_name(conn, None, "Code").unwrap(),
+            "VS Code"
+        );
+        assert!(AppIdentity::find_all(conn)
+            .unwrap()
+            .iter()
+            .any(|identity| identity.display_name == "VS Code"));
+
+        assert!(AppIdentity::delete(conn, "CODE").unwrap());
+        assert_eq!(
+            AppIdentity::display_name(conn, None, "Code").unwrap(),
+            "Code"
+        );
+    }
+}
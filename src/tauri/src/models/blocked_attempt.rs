@@ -0,0 +1,76 @@
+use rusqlite::{params, Connection, Result};
+
+/// How often the user hit the block page for one domain during a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedAttempt {
+    pub domain: String,
+    pub attempts: i32,
+    pub last_attempt_at: i64,
+}
+
+impl BlockedAttempt {
+    /// Count one more attempt to open `domain` during the session.
+    pub fn record(conn: &Connection, session_id: i64, domain: &str, at: i64) -> Result<()> {
+        conn.execute(
+            "INSERT INTO blocked_attempts (session_id, domain, attempts, last_attempt_at)
+             VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(session_id, domain)
+             DO UPDATE SET attempts = attempts + 1, last_attempt_at = excluded.last_attempt_at",
+            params![session_id, domain, at],
+        )?;
+        Ok(())
+    }
+
+    /// The session's attempts, most tried domain first.
+    pub fn find_by_session(conn: &Connection, session_id: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT domain, attempts, last_attempt_at FROM blocked_attempts
+             WHERE session_id = ?1 ORDER BY attempts DESC, domain",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(Self {
+                domain: row.get(0)?,
+                attempts: row.get(1)?,
+                last_attempt_at: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FocusSession;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_attempts_are_counted_per_domain() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        let id = session.id.unwrap();
+
+        BlockedAttempt::record(conn, id, "reddit.com", 1000).unwrap();
+        BlockedAttempt::record(conn, id, "twitter.com", 1010).unwrap();
+        BlockedAttempt::record(conn, id, "twitter.com", 1020).unwrap();
+
+        let attempts = BlockedAttempt::find_by_session(conn, id).unwrap();
+        assert_eq!(
+            attempts,
+            vec![
+                BlockedAttempt {
+                    domain: "twitter.com".into(),
+                    attempts: 2,
+                    last_attempt_at: 1020,
+                },
+                BlockedAttempt {
+                    domain: "reddit.com".into(),
+                    attempts: 1,
+                    last_attempt_at: 1000,
+                },
+            ]
+        );
+    }
+}
@@ -1,17 +1,20 @@
 use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Category {
     pub id: i64,
     pub name: String,
     pub productivity: i32,
+    /// Score weight from -1.0 to 1.0; falls back to `productivity` when unset.
+    pub weight: Option<f64>,
 }
 
 impl Category {
     /// Find a category by its ID.
     pub fn find_by_id(conn: &Connection, id: i64) -> Result<Option<Self>> {
         let mut stmt =
-            conn.prepare("SELECT id, name, productivity FROM categories WHERE id = ?1")?;
+            conn.prepare("SELECT id, name, productivity, weight FROM categories WHERE id = ?1")?;
         let mut rows = stmt.query(params![id])?;
 
         if let Some(row) = rows.next()? {
@@ -19,6 +22,7 @@ impl Category {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 productivity: row.get(2)?,
+                weight: row.get(3)?,
             }))
         } else {
             Ok(None)
@@ -27,12 +31,13 @@ impl Category {
 
     pub fn find_all(conn: &Connection) -> Result<Vec<Self>> {
         let mut stmt =
-            conn.prepare("SELECT id, name, productivity FROM categories ORDER BY name")?;
+            conn.prepare("SELECT id, name, productivity, weight FROM categories ORDER BY name")?;
         let rows = stmt.query_map([], |row| {
             Ok(Self {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 productivity: row.get(2)?,
+                weight: row.get(3)?,
             })
         })?;
 
@@ -50,6 +55,7 @@ impl Category {
             id,
             name: name.to_string(),
             productivity,
+            weight: None,
         })
     }
 
@@ -62,6 +68,30 @@ impl Category {
         Ok(rows_affected > 0)
     }
 
+    /// Set or clear (`None`) the category's score weight.
+    pub fn set_weight(conn: &Connection, id: i64, weight: Option<f64>) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE categories SET weight = ?1 WHERE id = ?2",
+            params![weight, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Put a deleted category back under its old ID, which rules and
+    /// activities still point at.
+    pub fn restore(conn: &Connection, category: &Self) -> Result<()> {
+        conn.execute(
+            "INSERT INTO categories (id, name, productivity, weight) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                category.id,
+                category.name,
+                category.productivity,
+                category.weight
+            ],
+        )?;
+        Ok(())
+    }
+
     /// Delete a category.
     /// Returns true if a category was deleted, false if not found.
     /// Note: This will fail if there are rules or activities referencing this category.
@@ -94,4 +124,23 @@ mod tests {
         let found = Category::find_by_id(db.connection(), cat.id).unwrap();
         assert_eq!(found, Some(cat));
     }
+
+    #[test]
+    fn test_set_weight() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let cat = Category::create(conn, "Reading", 1).unwrap();
+        assert_eq!(cat.weight, None);
+
+        assert!(Category::set_weight(conn, cat.id, Some(0.5)).unwrap());
+        let found = Category::find_by_id(conn, cat.id).unwrap().unwrap();
+        assert_eq!(found.weight, Some(0.5));
+
+        assert!(Category::set_weight(conn, cat.id, None).unwrap());
+        assert_eq!(
+            Category::find_by_id(conn, cat.id).unwrap().unwrap().weight,
+            None
+        );
+        assert!(!Category::set_weight(conn, 999, Some(0.5)).unwrap());
+    }
 }
@@ -0,0 +1,165 @@
+use super::focus_session::current_timestamp;
+use rusqlite::{params, Connection, Result};
+
+/// Distraction time spent during a focus session, and where it went.
+#[derive(Debug, Clone)]
+pub struct DistractionEvent {
+    pub id: Option<i64>,
+    pub session_id: i64,
+    pub timestamp: i64,
+    pub domain: Option<String>,
+    pub category_id: Option<i64>,
+    pub secs: i32,
+}
+
+impl DistractionEvent {
+    pub fn new(session_id: i64, domain: Option<&str>, category_id: Option<i64>, secs: i32) -> Self {
+        Self {
+            id: None,
+            session_id,
+            timestamp: current_timestamp(),
+            domain: domain.map(str::to_string),
+            category_id,
+            secs,
+        }
+    }
+
+    pub fn save(&mut self, conn: &Connection) -> Result<()> {
+        conn.execute(
+            "INSERT INTO distraction_events (session_id, timestamp, domain, category_id, secs)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                self.session_id,
+                self.timestamp,
+                self.domain,
+                self.category_id,
+                self.secs,
+            ],
+        )?;
+        self.id = Some(conn.last_insert_rowid());
+        Ok(())
+    }
+
+    /// Events for a session, oldest first.
+    pub fn find_by_session(conn: &Connection, session_id: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, timestamp, domain, category_id, secs
+             FROM distraction_events WHERE session_id = ?1 ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(Self {
+                id: Some(row.get(0)?),
+                session_id: row.get(1)?,
+                timestamp: row.get(2)?,
+                domain: row.get(3)?,
+                category_id: row.get(4)?,
+                secs: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+/// A slice of a session's distraction budget reserved for one category
+/// (e.g. 5 minutes of social media), with what has been used of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryBudget {
+    pub category_id: i64,
+    pub budget_secs: i32,
+    pub used_secs: i32,
+}
+
+impl CategoryBudget {
+    pub fn remaining(&self) -> i32 {
+        (self.budget_secs - self.used_secs).max(0)
+    }
+
+    /// Set the per-category budgets for a session, replacing any existing ones.
+    pub fn set_for_session(
+        conn: &Connection,
+        session_id: i64,
+        budgets: &[(i64, i32)],
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM session_budgets WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        for (category_id, budget_secs) in budgets {
+            conn.execute(
+                "INSERT OR REPLACE INTO session_budgets (session_id, category_id, budget_secs)
+                 VALUES (?1, ?2, ?3)",
+                params![session_id, category_id, budget_secs],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// A session's category budgets with time used so far.
+    pub fn find_for_session(conn: &Connection, session_id: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT b.category_id, b.budget_secs, COALESCE(SUM(e.secs), 0)
+             FROM session_budgets b
+             LEFT JOIN distraction_events e
+               ON e.session_id = b.session_id AND e.category_id = b.category_id
+             WHERE b.session_id = ?1
+             GROUP BY b.category_id, b.budget_secs
+             ORDER BY b.category_id",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok(Self {
+                category_id: row.get(0)?,
+                budget_secs: row.get(1)?,
+                used_secs: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Category, FocusSession};
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_category_budget_tracks_events() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let entertainment = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Entertainment")
+            .unwrap()
+            .id;
+        let mut session = FocusSession::new(900, false);
+        session.save(conn).unwrap();
+        let session_id = session.id.unwrap();
+
+        CategoryBudget::set_for_session(conn, session_id, &[(entertainment, 300)]).unwrap();
+        DistractionEvent::new(session_id, Some("youtube.com"), Some(entertainment), 120)
+            .save(conn)
+            .unwrap();
+        DistractionEvent::new(session_id, Some("news.ycombinator.com"), None, 60)
+            .save(conn)
+            .unwrap();
+
+        let budgets = CategoryBudget::find_for_session(conn, session_id).unwrap();
+        assert_eq!(
+            budgets,
+            vec![CategoryBudget {
+                category_id: entertainment,
+                budget_secs: 300,
+                used_secs: 120,
+            }]
+        );
+        assert_eq!(budgets.first().unwrap().remaining(), 180);
+
+        let events = DistractionEvent::find_by_session(conn, session_id).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events.first().unwrap().domain.as_deref(),
+            Some("youtube.com")
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use rusqlite::{params, Connection, Result};
+
+/// A domain the user unblocked for a short window of a focus session, paid
+/// for from the distraction budget. Rows are kept as a log of the allows.
+pub struct DomainAllow;
+
+impl DomainAllow {
+    pub fn grant(
+        conn: &Connection,
+        session_id: i64,
+        domain: &str,
+        granted_at: i64,
+        expires_at: i64,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO domain_allows (session_id, domain, granted_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, domain, granted_at, expires_at],
+        )?;
+        Ok(())
+    }
+
+    /// Domains allowed for the session at `now`.
+    pub fn active_domains(conn: &Connection, session_id: i64, now: i64) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT domain FROM domain_allows
+             WHERE session_id = ?1 AND granted_at <= ?2 AND expires_at > ?2
+             ORDER BY domain",
+        )?;
+        let rows = stmt.query_map(params![session_id, now], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FocusSession;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_active_domains_expire() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        let id = session.id.unwrap();
+
+        DomainAllow::grant(conn, id, "youtube.com", 1000, 1300).unwrap();
+        assert_eq!(
+            DomainAllow::active_domains(conn, id, 1299).unwrap(),
+            vec!["youtube.com"]
+        );
+        assert!(DomainAllow::active_domains(conn, id, 1300)
+            .unwrap()
+            .is_empty());
+    }
+}
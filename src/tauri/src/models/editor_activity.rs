@@ -0,0 +1,146 @@
+use rusqlite::{params, Connection, Result};
+
+/// Time an editor plugin reported in one project/file/language.
+///
+/// Kept apart from `activities`: the tracker already counts the editor
+/// window, so this only adds detail for coding stats.
+#[derive(Debug, Clone)]
+pub struct EditorActivity {
+    pub id: Option<i64>,
+    pub timestamp: i64,
+    pub duration_secs: i32,
+    /// Editor that reported it, e.g. `vscode`.
+    pub editor: String,
+    /// Workspace or repository name.
+    pub project: Option<String>,
+    pub file: Option<String>,
+    /// Lowercase language id, e.g. `rust`.
+    pub language: Option<String>,
+}
+
+impl EditorActivity {
+    pub fn new(timestamp: i64, duration_secs: i32, editor: &str) -> Self {
+        Self {
+            id: None,
+            timestamp,
+            duration_secs,
+            editor: editor.to_string(),
+            project: None,
+            file: None,
+            language: None,
+        }
+    }
+
+    pub fn save(&mut self, conn: &Connection) -> Result<()> {
+        conn.execute(
+            "INSERT INTO editor_activities (timestamp, duration_secs, editor, project, file, language)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                self.timestamp,
+                self.duration_secs,
+                self.editor,
+                self.project,
+                self.file,
+                self.language,
+            ],
+        )?;
+        self.id = Some(conn.last_insert_rowid());
+        Ok(())
+    }
+
+    /// Activities in `[start, end)`, oldest first.
+    pub fn find_in_range(conn: &Connection, start: i64, end: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, duration_secs, editor, project, file, language
+             FROM editor_activities WHERE timestamp >= ?1 AND timestamp < ?2
+             ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok(Self {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                duration_secs: row.get(2)?,
+                editor: row.get(3)?,
+                project: row.get(4)?,
+                file: row.get(5)?,
+                language: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Editor seconds per language, most time first.
+    pub fn language_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(Option<String>, i32)>> {
+        Self::totals_by(conn, "language", start, end)
+    }
+
+    /// Editor seconds per project, most time first.
+    pub fn project_totals(
+        conn: &Connection,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(Option<String>, i32)>> {
+        Self::totals_by(conn, "project", start, end)
+    }
+
+    fn totals_by(
+        conn: &Connection,
+        column: &'static str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(Option<String>, i32)>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {column}, SUM(duration_secs) AS total
+             FROM editor_activities
+             WHERE timestamp >= ?1 AND timestamp < ?2
+             GROUP BY {column}
+             ORDER BY total DESC"
+        ))?;
+
+        let rows = stmt.query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn save(conn: &Connection, timestamp: i64, secs: i32, project: &str, language: &str) {
+        let mut activity = EditorActivity::new(timestamp, secs, "vscode");
+        activity.project = Some(project.to_string());
+        activity.language = Some(language.to_string());
+        activity.save(conn).unwrap();
+    }
+
+    #[test]
+    fn test_totals_by_language_and_project() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        save(conn, 100, 60, "foxus", "rust");
+        save(conn, 200, 120, "foxus", "javascript");
+        save(conn, 300, 90, "dotfiles", "rust");
+        save(conn, 5000, 600, "foxus", "rust");
+
+        assert_eq!(
+            EditorActivity::language_totals(conn, 0, 1000).unwrap(),
+            vec![
+                (Some("rust".to_string()), 150),
+                (Some("javascript".to_string()), 120),
+            ]
+        );
+        assert_eq!(
+            EditorActivity::project_totals(conn, 0, 1000).unwrap(),
+            vec![
+                (Some("foxus".to_string()), 180),
+                (Some("dotfiles".to_string()), 90),
+            ]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use super::focus_session::current_timestamp;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+
+/// What caused an enforced break to start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakTrigger {
+    /// A timed focus session ran to completion.
+    Pomodoro,
+    /// The continuous-work limit from the break settings was reached.
+    WorkLimit,
+}
+
+impl BreakTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakTrigger::Pomodoro => "pomodoro",
+            BreakTrigger::WorkLimit => "work_limit",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "pomodoro" => Some(BreakTrigger::Pomodoro),
+            "work_limit" => Some(BreakTrigger::WorkLimit),
+            _ => None,
+        }
+    }
+}
+
+/// A full-screen break the user was asked to take, and whether they skipped it.
+#[derive(Debug, Clone)]
+pub struct EnforcedBreak {
+    pub id: Option<i64>,
+    pub started_at: i64,
+    pub duration_secs: i32,
+    pub trigger: BreakTrigger,
+    pub ended_at: Option<i64>,
+    /// Set when the break was cut short with the emergency skip.
+    pub skipped_at: Option<i64>,
+}
+
+impl EnforcedBreak {
+    pub fn new(duration_secs: i32, trigger: BreakTrigger) -> Self {
+        Self {
+            id: None,
+            started_at: current_timestamp(),
+            duration_secs,
+            trigger,
+            ended_at: None,
+            skipped_at: None,
+        }
+    }
+
+    pub fn save(&mut self, conn: &Connection) -> Result<()> {
+        conn.execute(
+            "INSERT INTO enforced_breaks (started_at, duration_secs, trigger, ended_at, skipped_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                self.started_at,
+                self.duration_secs,
+                self.trigger.as_str(),
+                self.ended_at,
+                self.skipped_at,
+            ],
+        )?;
+        self.id = Some(conn.last_insert_rowid());
+        Ok(())
+    }
+
+    /// Find the break currently in progress, if any.
+    pub fn find_active(conn: &Connection) -> Result<Option<Self>> {
+        conn.query_row(
+            "SELECT id, started_at, duration_secs, trigger, ended_at, skipped_at
+             FROM enforced_breaks WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1",
+            [],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    /// When the break is scheduled to finish.
+    pub fn ends_at(&self) -> i64 {
+        self.started_at + i64::from(self.duration_secs)
+    }
+
+    /// Mark the break as finished after its full duration.
+    pub fn complete(&mut self, conn: &Connection) -> Result<()> {
+        self.finish(conn, false)
+    }
+
+    /// Cut the break short via the emergency skip, recording when it happened.
+    pub fn skip(&mut self, conn: &Connection) -> Result<()> {
+        self.finish(conn, true)
+    }
+
+    fn finish(&mut self, conn: &Connection, skipped: bool) -> Result<()> {
+        let id = self.id.ok_or_else(|| {
+            rusqlite::Error::InvalidParameterName(
+                "Cannot end unsaved break - call save() first".to_string(),
+            )
+        })?;
+
+        let now = current_timestamp();
+        self.ended_at = Some(now);
+        self.skipped_at = skipped.then_some(now);
+
+        conn.execute(
+            "UPDATE enforced_breaks SET ended_at = ?1, skipped_at = ?2 WHERE id = ?3",
+            params![self.ended_at, self.skipped_at, id],
+        )?;
+        Ok(())
+    }
+
+    fn from_row(row: &Row) -> Result<Self> {
+        let trigger: String = row.get(3)?;
+        Ok(Self {
+            id: Some(row.get(0)?),
+            started_at: row.get(1)?,
+            duration_secs: row.get(2)?,
+            trigger: BreakTrigger::from_str(&trigger).unwrap_or(BreakTrigger::WorkLimit),
+            ended_at: row.get(4)?,
+            skipped_at: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_trigger_round_trip() {
+        for trigger in [BreakTrigger::Pomodoro, BreakTrigger::WorkLimit] {
+            assert_eq!(BreakTrigger::from_str(trigger.as_str()), Some(trigger));
+        }
+        assert_eq!(BreakTrigger::from_str("nap"), None);
+    }
+
+    #[test]
+    fn test_save_and_find_active() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        assert!(EnforcedBreak::find_active(conn).unwrap().is_none());
+
+        let mut enforced = EnforcedBreak::new(300, BreakTrigger::Pomodoro);
+        enforced.save(conn).unwrap();
+
+        let found = EnforcedBreak::find_active(conn).unwrap().unwrap();
+        assert_eq!(found.id, enforced.id);
+        assert_eq!(found.trigger, BreakTrigger::Pomodoro);
+        assert_eq!(found.ends_at(), enforced.started_at + 300);
+    }
+
+    #[test]
+    fn test_complete_ends_break_without_skip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut enforced = EnforcedBreak::new(300, BreakTrigger::WorkLimit);
+        enforced.save(conn).unwrap();
+        enforced.complete(conn).unwrap();
+
+        assert!(enforced.ended_at.is_some());
+        assert!(enforced.skipped_at.is_none());
+        assert!(EnforcedBreak::find_active(conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_skip_is_logged() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut enforced = EnforcedBreak::new(300, BreakTrigger::WorkLimit);
+        enforced.save(conn).unwrap();
+        enforced.skip(conn).unwrap();
+
+        let skipped_at: Option<i64> = conn
+            .query_row(
+                "SELECT skipped_at FROM enforced_breaks WHERE id = ?1",
+                params![enforced.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(skipped_at.is_some());
+        assert!(EnforcedBreak::find_active(conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_complete_unsaved_break_returns_error() {
+        let (db, _dir) = setup_test_db();
+
+        let mut enforced = EnforcedBreak::new(300, BreakTrigger::WorkLimit);
+        assert!(enforced.complete(db.connection()).is_err());
+    }
+}
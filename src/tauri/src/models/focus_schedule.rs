@@ -1,19 +1,33 @@
-use rusqlite::{params, Connection, Result};
+use chrono::NaiveTime;
+use rusqlite::{params, Connection, Result, Row};
+use serde::{Deserialize, Serialize};
+
+/// Minutes before a schedule starts that it's announced, unless set.
+pub const DEFAULT_NOTIFY_LEAD_MINUTES: i32 = 5;
 
 /// A recurring focus schedule that can auto-start focus sessions.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FocusSchedule {
     pub id: Option<i64>,
     /// Comma-separated day numbers (1=Monday, 7=Sunday). E.g., "1,2,3,4,5" for weekdays.
     pub days_of_week: String,
-    /// Start time in HH:MM format (24-hour).
+    /// Local wall-clock start time in HH:MM format (24-hour), so the window
+    /// stays put across DST changes.
     pub start_time: String,
-    /// End time in HH:MM format (24-hour).
+    /// Local wall-clock end time in HH:MM format (24-hour).
     pub end_time: String,
     /// Distraction budget in seconds for auto-started sessions.
     pub distraction_budget: i32,
     /// Whether this schedule is enabled.
     pub enabled: bool,
+    /// Categories its sessions block even though they don't count as
+    /// distracting, e.g. Communication.
+    pub blocked_categories: Vec<i64>,
+    /// Bumped on every update, so an edit made from stale data is caught.
+    pub version: i64,
+    /// Minutes before the start to announce the session; 0 for no notice.
+    #[serde(default)]
+    pub notify_lead_minutes: i32,
 }
 
 impl FocusSchedule {
@@ -31,66 +45,87 @@ impl FocusSchedule {
             end_time: end_time.to_string(),
             distraction_budget,
             enabled: true,
+            blocked_categories: Vec::new(),
+            version: 1,
+            notify_lead_minutes: DEFAULT_NOTIFY_LEAD_MINUTES,
         }
     }
 
     /// Save the schedule to the database.
     pub fn save(&mut self, conn: &Connection) -> Result<()> {
         conn.execute(
-            "INSERT INTO focus_schedules (days_of_week, start_time, end_time, distraction_budget, enabled)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO focus_schedules
+                 (days_of_week, start_time, end_time, distraction_budget, enabled, blocked_categories,
+                  notify_lead_minutes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 self.days_of_week,
                 self.start_time,
                 self.end_time,
                 self.distraction_budget,
                 i32::from(self.enabled),
+                join_ids(&self.blocked_categories),
+                self.notify_lead_minutes,
             ],
         )?;
         self.id = Some(conn.last_insert_rowid());
         Ok(())
     }
 
-    /// Update an existing schedule in the database.
-    pub fn update(&self, conn: &Connection) -> Result<()> {
+    /// Update an existing schedule in the database if it's still at
+    /// `version`, then bump the version. Returns false if it has moved on.
+    pub fn update(&mut self, conn: &Connection) -> Result<bool> {
         let id = self.id.ok_or_else(|| {
             rusqlite::Error::InvalidParameterName("Cannot update unsaved schedule".to_string())
         })?;
 
-        conn.execute(
+        let rows_affected = conn.execute(
             "UPDATE focus_schedules
              SET days_of_week = ?1, start_time = ?2, end_time = ?3,
-                 distraction_budget = ?4, enabled = ?5
-             WHERE id = ?6",
+                 distraction_budget = ?4, enabled = ?5, blocked_categories = ?6,
+                 notify_lead_minutes = ?7, version = version + 1
+             WHERE id = ?8 AND version = ?9",
             params![
                 self.days_of_week,
                 self.start_time,
                 self.end_time,
                 self.distraction_budget,
                 i32::from(self.enabled),
+                join_ids(&self.blocked_categories),
+                self.notify_lead_minutes,
                 id,
+                self.version,
             ],
         )?;
-        Ok(())
+        if rows_affected > 0 {
+            self.version += 1;
+        }
+        Ok(rows_affected > 0)
+    }
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: Some(row.get(0)?),
+            days_of_week: row.get(1)?,
+            start_time: row.get(2)?,
+            end_time: row.get(3)?,
+            distraction_budget: row.get(4)?,
+            enabled: row.get::<_, i32>(5)? != 0,
+            blocked_categories: parse_ids(row.get::<_, Option<String>>(6)?.as_deref()),
+            version: row.get(7)?,
+            notify_lead_minutes: row.get(8)?,
+        })
     }
 
     /// Find all schedules.
     pub fn find_all(conn: &Connection) -> Result<Vec<Self>> {
         let mut stmt = conn.prepare(
-            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled
-             FROM focus_schedules ORDER BY start_time",
+            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled, blocked_categories,
+                    version, notify_lead_minutes
+             FROM focus_schedules WHERE deleted_at IS NULL ORDER BY start_time",
         )?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Self {
-                id: Some(row.get(0)?),
-                days_of_week: row.get(1)?,
-                start_time: row.get(2)?,
-                end_time: row.get(3)?,
-                distraction_budget: row.get(4)?,
-                enabled: row.get::<_, i32>(5)? != 0,
-            })
-        })?;
+        let rows = stmt.query_map([], Self::from_row)?;
 
         rows.collect()
     }
@@ -98,20 +133,12 @@ impl FocusSchedule {
     /// Find all enabled schedules.
     pub fn find_enabled(conn: &Connection) -> Result<Vec<Self>> {
         let mut stmt = conn.prepare(
-            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled
-             FROM focus_schedules WHERE enabled = 1 ORDER BY start_time",
+            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled, blocked_categories,
+                    version, notify_lead_minutes
+             FROM focus_schedules WHERE enabled = 1 AND deleted_at IS NULL ORDER BY start_time",
         )?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Self {
-                id: Some(row.get(0)?),
-                days_of_week: row.get(1)?,
-                start_time: row.get(2)?,
-                end_time: row.get(3)?,
-                distraction_budget: row.get(4)?,
-                enabled: row.get::<_, i32>(5)? != 0,
-            })
-        })?;
+        let rows = stmt.query_map([], Self::from_row)?;
 
         rows.collect()
     }
@@ -119,33 +146,83 @@ impl FocusSchedule {
     /// Find a schedule by ID.
     pub fn find_by_id(conn: &Connection, id: i64) -> Result<Option<Self>> {
         let mut stmt = conn.prepare(
-            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled
-             FROM focus_schedules WHERE id = ?1",
+            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled, blocked_categories,
+                    version, notify_lead_minutes
+             FROM focus_schedules WHERE id = ?1 AND deleted_at IS NULL",
         )?;
 
         let mut rows = stmt.query(params![id])?;
 
         if let Some(row) = rows.next()? {
-            Ok(Some(Self {
-                id: Some(row.get(0)?),
-                days_of_week: row.get(1)?,
-                start_time: row.get(2)?,
-                end_time: row.get(3)?,
-                distraction_budget: row.get(4)?,
-                enabled: row.get::<_, i32>(5)? != 0,
-            }))
+            Ok(Some(Self::from_row(row)?))
         } else {
             Ok(None)
         }
     }
 
-    /// Delete a schedule from the database.
-    pub fn delete(conn: &Connection, id: i64) -> Result<bool> {
-        let rows_affected =
-            conn.execute("DELETE FROM focus_schedules WHERE id = ?1", params![id])?;
+    /// Move a schedule to the trash. Returns false if it doesn't exist or is
+    /// already there.
+    pub fn trash(conn: &Connection, id: i64, now: i64) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE focus_schedules SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![now, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Take a schedule out of the trash. Returns false if it isn't there.
+    pub fn restore(conn: &Connection, id: i64) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE focus_schedules SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
         Ok(rows_affected > 0)
     }
 
+    /// Schedules in the trash with when they were deleted, most recent first.
+    pub fn find_deleted(conn: &Connection) -> Result<Vec<(Self, i64)>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, days_of_week, start_time, end_time, distraction_budget, enabled, blocked_categories,
+                    version, notify_lead_minutes, deleted_at
+             FROM focus_schedules WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )?;
+
+        let rows = stmt.query_map([], |row| Ok((Self::from_row(row)?, row.get(9)?)))?;
+        rows.collect()
+    }
+
+    /// Permanently delete schedules trashed before `before`.
+    pub fn purge_deleted(conn: &Connection, before: i64) -> Result<usize> {
+        conn.execute(
+            "DELETE FROM focus_schedules WHERE deleted_at < ?1",
+            params![before],
+        )
+    }
+
+    /// Skip a schedule for one local date (YYYY-MM-DD), e.g. a sick day,
+    /// without disabling it.
+    pub fn skip_on(conn: &Connection, id: i64, date: &str) -> Result<()> {
+        conn.execute(
+            "INSERT OR IGNORE INTO schedule_skips (schedule_id, date) VALUES (?1, ?2)",
+            params![id, date],
+        )?;
+        Ok(())
+    }
+
+    /// Undo every skip on `date`. Returns how many there were.
+    pub fn clear_skips_on(conn: &Connection, date: &str) -> Result<usize> {
+        conn.execute("DELETE FROM schedule_skips WHERE date = ?1", params![date])
+    }
+
+    /// Skips on or after `date` as (schedule id, date).
+    pub fn find_skips_from(conn: &Connection, date: &str) -> Result<Vec<(i64, String)>> {
+        let mut stmt = conn.prepare(
+            "SELECT schedule_id, date FROM schedule_skips WHERE date >= ?1 ORDER BY date",
+        )?;
+        let rows = stmt.query_map(params![date], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
     /// Check if this schedule applies to the given day of week (1=Monday, 7=Sunday).
     pub fn applies_to_day(&self, day: u32) -> bool {
         self.days_of_week
@@ -164,6 +241,11 @@ impl FocusSchedule {
         self.enabled && self.applies_to_day(day) && self.is_time_in_range(time)
     }
 
+    /// `start_time` as a wall-clock time, or `None` if it isn't valid HH:MM.
+    pub fn start_wall_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(&self.start_time, "%H:%M").ok()
+    }
+
     /// Parse `days_of_week` string into a vector of day numbers.
     pub fn get_days(&self) -> Vec<u32> {
         self.days_of_week
@@ -173,6 +255,24 @@ impl FocusSchedule {
     }
 }
 
+/// Category ids as stored: comma-separated, `None` when there are none.
+fn join_ids(ids: &[i64]) -> Option<String> {
+    (!ids.is_empty()).then(|| {
+        ids.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    })
+}
+
+fn parse_ids(stored: Option<&str>) -> Vec<i64> {
+    stored
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| s.trim().parse().ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,31 +362,99 @@ mod tests {
 
         schedule.start_time = "10:00".to_string();
         schedule.distraction_budget = 900;
-        schedule.update(conn).unwrap();
+        schedule.blocked_categories = vec![3, 5];
+        schedule.notify_lead_minutes = 15;
+        assert!(schedule.update(conn).unwrap());
 
         let found = FocusSchedule::find_by_id(conn, id).unwrap().unwrap();
         assert_eq!(found.start_time, "10:00");
         assert_eq!(found.distraction_budget, 900);
+        assert_eq!(found.blocked_categories, vec![3, 5]);
+        assert_eq!(found.notify_lead_minutes, 15);
+        assert_eq!(found.version, 2);
+    }
+
+    #[test]
+    fn test_update_from_stale_version_is_rejected() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut schedule = FocusSchedule::new("1,2,3,4,5", "09:00", "12:00", 600);
+        schedule.save(conn).unwrap();
+        let mut other_window = schedule.clone();
+
+        schedule.start_time = "10:00".to_string();
+        assert!(schedule.update(conn).unwrap());
+
+        other_window.end_time = "13:00".to_string();
+        assert!(!other_window.update(conn).unwrap());
+
+        let found = FocusSchedule::find_by_id(conn, schedule.id.unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.start_time, "10:00");
+        assert_eq!(found.end_time, "12:00");
     }
 
     #[test]
-    fn test_delete() {
+    fn test_trash_restore_and_purge() {
         let (db, _dir) = setup_test_db();
         let conn = db.connection();
+        let now = 1_700_000_000;
 
         let mut schedule = FocusSchedule::new("1,2,3,4,5", "09:00", "12:00", 600);
         schedule.save(conn).unwrap();
         let id = schedule.id.unwrap();
 
-        let deleted = FocusSchedule::delete(conn, id).unwrap();
+        let deleted = FocusSchedule::trash(conn, id, now).unwrap();
         assert!(deleted);
 
         let found = FocusSchedule::find_by_id(conn, id).unwrap();
         assert!(found.is_none());
+        assert!(FocusSchedule::find_all(conn).unwrap().is_empty());
 
-        // Deleting non-existent should return false
-        let deleted_again = FocusSchedule::delete(conn, id).unwrap();
+        // Deleting one already in the trash should return false
+        let deleted_again = FocusSchedule::trash(conn, id, now).unwrap();
         assert!(!deleted_again);
+
+        let trashed = FocusSchedule::find_deleted(conn).unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed.first().unwrap().1, now);
+
+        assert!(FocusSchedule::restore(conn, id).unwrap());
+        assert!(FocusSchedule::find_by_id(conn, id).unwrap().is_some());
+        assert!(!FocusSchedule::restore(conn, id).unwrap());
+
+        FocusSchedule::trash(conn, id, now).unwrap();
+        assert_eq!(FocusSchedule::purge_deleted(conn, now).unwrap(), 0);
+        assert_eq!(FocusSchedule::purge_deleted(conn, now + 1).unwrap(), 1);
+        assert!(FocusSchedule::find_deleted(conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_skips() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut schedule = FocusSchedule::new("1,2,3,4,5", "09:00", "12:00", 600);
+        schedule.save(conn).unwrap();
+        let id = schedule.id.unwrap();
+
+        FocusSchedule::skip_on(conn, id, "2026-10-14").unwrap();
+        FocusSchedule::skip_on(conn, id, "2026-10-15").unwrap();
+        FocusSchedule::skip_on(conn, id, "2026-10-15").unwrap();
+        assert_eq!(
+            FocusSchedule::find_skips_from(conn, "2026-10-15").unwrap(),
+            vec![(id, "2026-10-15".to_string())]
+        );
+
+        assert_eq!(
+            FocusSchedule::clear_skips_on(conn, "2026-10-15").unwrap(),
+            1
+        );
+        assert!(FocusSchedule::find_skips_from(conn, "2026-10-15")
+            .unwrap()
+            .is_empty());
     }
 
     #[test]
@@ -340,12 +508,24 @@ mod tests {
         assert_eq!(days, vec![6, 7]);
     }
 
+    #[test]
+    fn test_start_wall_time() {
+        let schedule = FocusSchedule::new("1", "09:30", "12:00", 600);
+        assert_eq!(
+            schedule.start_wall_time(),
+            NaiveTime::from_hms_opt(9, 30, 0)
+        );
+
+        let invalid = FocusSchedule::new("1", "25:00", "26:00", 600);
+        assert_eq!(invalid.start_wall_time(), None);
+    }
+
     #[test]
     fn test_update_unsaved_returns_error() {
         let (db, _dir) = setup_test_db();
         let conn = db.connection();
 
-        let schedule = FocusSchedule::new("1,2,3,4,5", "09:00", "12:00", 600);
+        let mut schedule = FocusSchedule::new("1,2,3,4,5", "09:00", "12:00", 600);
         let result = schedule.update(conn);
         assert!(result.is_err());
     }
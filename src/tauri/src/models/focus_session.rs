@@ -1,7 +1,13 @@
-use rusqlite::{params, Connection, Result};
+use crate::context_switches::analyze;
+use crate::models::Activity;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "Each flag mirrors an independent column in focus_sessions"
+)]
 pub struct FocusSession {
     pub id: Option<i64>,
     pub started_at: i64,
@@ -9,8 +15,40 @@ pub struct FocusSession {
     pub scheduled: bool,
     pub distraction_budget: i32,
     pub distraction_used: i32,
+    /// Intended session length in seconds; `None` for open-ended sessions.
+    pub planned_duration: Option<i32>,
+    /// Strict sessions are a commitment: ending one early counts as abandoning it.
+    pub strict: bool,
+    /// Seconds the tracker saw the user active while the session ran.
+    pub focused_secs: i32,
+    /// Seconds the tracker saw the user idle while the session ran.
+    pub idle_secs: i32,
+    /// Longest unbroken productive stretch in a single app during the session.
+    pub longest_streak_secs: i32,
+    /// App the longest streak was spent in.
+    pub streak_app: Option<String>,
+    /// Whether the user has already been offered an extension for deep work.
+    pub extension_offered: bool,
+    /// When an emergency unlock was first requested; the unlock waits out a delay from here.
+    pub unlock_requested_at: Option<i64>,
+    /// Whether the session was ended through an emergency unlock.
+    pub emergency_unlocked: bool,
+    /// How well the session went, 0 to 100, set when it ends.
+    pub effectiveness_score: Option<f64>,
 }
 
+/// Weights of the parts of the effectiveness score: distraction budget left,
+/// share of the session spent active, and lack of app switching.
+const BUDGET_WEIGHT: f64 = 0.4;
+const ACTIVE_WEIGHT: f64 = 0.3;
+const SWITCHING_WEIGHT: f64 = 0.3;
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, started_at, ended_at, scheduled, distraction_budget, distraction_used,
+        planned_duration, strict, focused_secs, idle_secs, longest_streak_secs, streak_app,
+        extension_offered, unlock_requested_at, emergency_unlocked, effectiveness_score
+     FROM focus_sessions";
+
 #[expect(
     clippy::cast_possible_wrap,
     reason = "Unix timestamps won't exceed i64::MAX until year 292 billion"
@@ -19,7 +57,7 @@ pub struct FocusSession {
     clippy::as_conversions,
     reason = "u64 -> i64 widening cast is safe for timestamps (won't overflow until year 292 billion)"
 )]
-fn current_timestamp() -> i64 {
+pub(crate) fn current_timestamp() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("System clock is before Unix epoch - this should never happen on properly configured systems")
@@ -35,19 +73,38 @@ impl FocusSession {
             scheduled,
             distraction_budget: distraction_budget_secs,
             distraction_used: 0,
+            planned_duration: None,
+            strict: false,
+            focused_secs: 0,
+            idle_secs: 0,
+            longest_streak_secs: 0,
+            streak_app: None,
+            extension_offered: false,
+            unlock_requested_at: None,
+            emergency_unlocked: false,
+            effectiveness_score: None,
         }
     }
 
     pub fn save(&mut self, conn: &Connection) -> Result<()> {
         conn.execute(
-            "INSERT INTO focus_sessions (started_at, ended_at, scheduled, distraction_budget, distraction_used)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO focus_sessions (started_at, ended_at, scheduled, distraction_budget, distraction_used, planned_duration, strict, focused_secs, idle_secs, longest_streak_secs, streak_app, extension_offered, unlock_requested_at, emergency_unlocked)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
             params![
                 self.started_at,
                 self.ended_at,
                 i32::from(self.scheduled),
                 self.distraction_budget,
                 self.distraction_used,
+                self.planned_duration,
+                i32::from(self.strict),
+                self.focused_secs,
+                self.idle_secs,
+                self.longest_streak_secs,
+                self.streak_app,
+                i32::from(self.extension_offered),
+                self.unlock_requested_at,
+                i32::from(self.emergency_unlocked),
             ],
         )?;
         self.id = Some(conn.last_insert_rowid());
@@ -55,47 +112,150 @@ impl FocusSession {
     }
 
     pub fn find_active(conn: &Connection) -> Result<Option<Self>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, started_at, ended_at, scheduled, distraction_budget, distraction_used
-             FROM focus_sessions WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1",
+        conn.query_row(
+            &format!("{SELECT_COLUMNS} WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1"),
+            [],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    /// Most recent sessions first, for the session history.
+    pub fn find_recent(conn: &Connection, limit: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!(
+            "{SELECT_COLUMNS} ORDER BY started_at DESC LIMIT ?1"
+        ))?;
+        let rows = stmt.query_map(params![limit], Self::from_row)?;
+        rows.collect()
+    }
+
+    /// Sessions that started in `[start, end)` and have ended, oldest first.
+    pub fn find_ended_in_range(conn: &Connection, start: i64, end: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!(
+            "{SELECT_COLUMNS} WHERE started_at >= ?1 AND started_at < ?2 AND ended_at IS NOT NULL
+             ORDER BY started_at"
+        ))?;
+        let rows = stmt.query_map(params![start, end], Self::from_row)?;
+        rows.collect()
+    }
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: Some(row.get(0)?),
+            started_at: row.get(1)?,
+            ended_at: row.get(2)?,
+            scheduled: row.get::<_, i32>(3)? != 0,
+            distraction_budget: row.get(4)?,
+            distraction_used: row.get(5)?,
+            planned_duration: row.get(6)?,
+            strict: row.get::<_, i32>(7)? != 0,
+            focused_secs: row.get(8)?,
+            idle_secs: row.get(9)?,
+            longest_streak_secs: row.get(10)?,
+            streak_app: row.get(11)?,
+            extension_offered: row.get::<_, i32>(12)? != 0,
+            unlock_requested_at: row.get(13)?,
+            emergency_unlocked: row.get::<_, i32>(14)? != 0,
+            effectiveness_score: row.get(15)?,
+        })
+    }
+
+    /// Credit one tracker poll to the active session, as focused or idle time.
+    /// Returns false when no session is active.
+    pub fn record_tracked_time(conn: &Connection, secs: i32, idle: bool) -> Result<bool> {
+        let column = if idle { "idle_secs" } else { "focused_secs" };
+        let rows_affected = conn.execute(
+            &format!("UPDATE focus_sessions SET {column} = {column} + ?1 WHERE ended_at IS NULL"),
+            params![secs],
         )?;
+        Ok(rows_affected > 0)
+    }
 
-        let mut rows = stmt.query([])?;
-
-        if let Some(row) = rows.next()? {
-            Ok(Some(Self {
-                id: Some(row.get(0)?),
-                started_at: row.get(1)?,
-                ended_at: row.get(2)?,
-                scheduled: row.get::<_, i32>(3)? != 0,
-                distraction_budget: row.get(4)?,
-                distraction_used: row.get(5)?,
-            }))
-        } else {
-            Ok(None)
-        }
+    /// Credit productive seconds to the active session for budget accrual.
+    /// Returns its productive and already-earned budget seconds, or `None`
+    /// when no session is active.
+    pub fn record_productive_time(conn: &Connection, secs: i32) -> Result<Option<(i32, i32)>> {
+        conn.execute(
+            "UPDATE focus_sessions SET productive_secs = productive_secs + ?1 WHERE ended_at IS NULL",
+            params![secs],
+        )?;
+        conn.query_row(
+            "SELECT productive_secs, earned_budget_secs FROM focus_sessions
+             WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+    }
+
+    /// Add budget earned through productive time to the active session.
+    pub fn add_earned_budget(conn: &Connection, secs: i32) -> Result<()> {
+        conn.execute(
+            "UPDATE focus_sessions
+             SET distraction_budget = distraction_budget + ?1,
+                 earned_budget_secs = earned_budget_secs + ?1
+             WHERE ended_at IS NULL",
+            params![secs],
+        )?;
+        Ok(())
     }
 
     /// Ends the focus session by setting the `ended_at` timestamp.
     /// Returns an error if the session has not been saved yet (id is None).
     pub fn end(&mut self, conn: &Connection) -> Result<()> {
+        self.end_at(conn, current_timestamp())
+    }
+
+    /// Ends the session at a given time, e.g. when it ran out while the app was closed,
+    /// and scores it.
+    pub fn end_at(&mut self, conn: &Connection, ended_at: i64) -> Result<()> {
         let id = self.id.ok_or_else(|| {
             rusqlite::Error::InvalidParameterName(
                 "Cannot end unsaved session - call save() first".to_string(),
             )
         })?;
 
-        let now = current_timestamp();
-        self.ended_at = Some(now);
+        let activities = Activity::find_in_range(conn, self.started_at, ended_at)?;
+        self.ended_at = Some(ended_at);
+        self.effectiveness_score = self.effectiveness(analyze(&activities).fragmentation_score);
 
         conn.execute(
-            "UPDATE focus_sessions SET ended_at = ?1 WHERE id = ?2",
-            params![now, id],
+            "UPDATE focus_sessions SET ended_at = ?1, effectiveness_score = ?2 WHERE id = ?3",
+            params![ended_at, self.effectiveness_score, id],
         )?;
 
         Ok(())
     }
 
+    /// Score from 0 to 100 combining distraction budget left, the share of
+    /// tracked time the user was active, and how little they switched apps
+    /// (`fragmentation_score` from 0 to 100). `None` when nothing was tracked.
+    pub fn effectiveness(&self, fragmentation_score: f64) -> Option<f64> {
+        let tracked = self.focused_secs + self.idle_secs;
+        if tracked <= 0 {
+            return None;
+        }
+        let budget_left = if self.distraction_budget > 0 {
+            1.0 - f64::from(self.distraction_used) / f64::from(self.distraction_budget)
+        } else if self.distraction_used > 0 {
+            0.0
+        } else {
+            1.0
+        };
+        let active = f64::from(self.focused_secs) / f64::from(tracked);
+        let steady = 1.0 - fragmentation_score / 100.0;
+        let score = BUDGET_WEIGHT * budget_left.clamp(0.0, 1.0)
+            + ACTIVE_WEIGHT * active
+            + SWITCHING_WEIGHT * steady.clamp(0.0, 1.0);
+        Some((score * 100.0).round())
+    }
+
+    /// When the session should have ended if nothing interrupted it; `None` for open-ended ones.
+    pub fn expected_end(&self) -> Option<i64> {
+        self.planned_duration
+            .map(|planned| self.started_at + i64::from(planned))
+    }
+
     /// Adds distraction time to the session.
     /// Returns an error if the session has not been saved yet (id is None).
     pub fn add_distraction_time(&mut self, conn: &Connection, secs: i32) -> Result<()> {
@@ -115,6 +275,152 @@ impl FocusSession {
         Ok(())
     }
 
+    /// Remember a single-app streak if it beats the session's longest so far.
+    /// Returns whether it was stored.
+    pub fn record_streak(&mut self, conn: &Connection, secs: i32, app: &str) -> Result<bool> {
+        let id = self.saved_id()?;
+        if secs <= self.longest_streak_secs {
+            return Ok(false);
+        }
+
+        self.longest_streak_secs = secs;
+        self.streak_app = Some(app.to_string());
+        conn.execute(
+            "UPDATE focus_sessions SET longest_streak_secs = ?1, streak_app = ?2 WHERE id = ?3",
+            params![secs, app, id],
+        )?;
+        Ok(true)
+    }
+
+    /// Note that a deep-work extension was offered, so it's only offered once.
+    pub fn mark_extension_offered(&mut self, conn: &Connection) -> Result<()> {
+        let id = self.saved_id()?;
+        self.extension_offered = true;
+        conn.execute(
+            "UPDATE focus_sessions SET extension_offered = 1 WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Start the emergency unlock countdown, unless one is already running.
+    pub fn request_unlock(&mut self, conn: &Connection, now: i64) -> Result<()> {
+        let id = self.saved_id()?;
+        if self.unlock_requested_at.is_some() {
+            return Ok(());
+        }
+        self.unlock_requested_at = Some(now);
+        conn.execute(
+            "UPDATE focus_sessions SET unlock_requested_at = ?1 WHERE id = ?2",
+            params![now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Claim the next distraction-time request for this session, at most once
+    /// every `min_interval_secs`.
+    ///
+    /// The last claim is stored on the session row and claimed with a single
+    /// conditional update, so the app and the native host share one limit.
+    pub fn claim_distraction_request(
+        &self,
+        conn: &Connection,
+        now: i64,
+        min_interval_secs: i64,
+    ) -> Result<bool> {
+        let id = self.saved_id()?;
+        let claimed = conn.execute(
+            "UPDATE focus_sessions SET last_distraction_request_at = ?1
+             WHERE id = ?2
+               AND (last_distraction_request_at IS NULL OR last_distraction_request_at <= ?3)",
+            params![now, id, now - min_interval_secs],
+        )?;
+        Ok(claimed > 0)
+    }
+
+    /// Take over the budget used by the last scheduled session that started
+    /// at or after `since` and has ended, along with its distraction-time
+    /// rate limit, so ending and restarting within one schedule window (a
+    /// crash, a restart, ending it by hand) doesn't hand out a fresh budget.
+    ///
+    /// Only the last one counts: it already carries what the sessions
+    /// before it used.
+    pub fn inherit_scheduled_usage(&mut self, conn: &Connection, since: i64) -> Result<()> {
+        let id = self.saved_id()?;
+        let previous: Option<(i32, Option<i64>)> = conn
+            .query_row(
+                "SELECT distraction_used, last_distraction_request_at
+                 FROM focus_sessions
+                 WHERE scheduled = 1 AND ended_at IS NOT NULL AND started_at >= ?1 AND id != ?2
+                 ORDER BY ended_at DESC, id DESC
+                 LIMIT 1",
+                params![since, id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((used, last_request)) = previous else {
+            return Ok(());
+        };
+        if used == 0 && last_request.is_none() {
+            return Ok(());
+        }
+        self.distraction_used += used;
+        conn.execute(
+            "UPDATE focus_sessions
+             SET distraction_used = ?1,
+                 last_distraction_request_at = MAX(COALESCE(last_distraction_request_at, 0), COALESCE(?2, 0))
+             WHERE id = ?3",
+            params![self.distraction_used, last_request, id],
+        )?;
+        Ok(())
+    }
+
+    /// Record that the session was ended through an emergency unlock.
+    pub fn mark_emergency_unlocked(&mut self, conn: &Connection) -> Result<()> {
+        let id = self.saved_id()?;
+        self.emergency_unlocked = true;
+        conn.execute(
+            "UPDATE focus_sessions SET emergency_unlocked = 1 WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Push back the planned end of a timed session by `secs`.
+    /// Open-ended sessions have nothing to extend and are left unchanged.
+    pub fn extend(&mut self, conn: &Connection, secs: i32) -> Result<()> {
+        let id = self.saved_id()?;
+        let Some(planned) = self.planned_duration else {
+            return Ok(());
+        };
+
+        self.planned_duration = Some(planned + secs);
+        conn.execute(
+            "UPDATE focus_sessions SET planned_duration = ?1 WHERE id = ?2",
+            params![self.planned_duration, id],
+        )?;
+        Ok(())
+    }
+
+    /// Top up the distraction budget by `secs`.
+    pub fn add_budget(&mut self, conn: &Connection, secs: i32) -> Result<()> {
+        let id = self.saved_id()?;
+        self.distraction_budget += secs;
+        conn.execute(
+            "UPDATE focus_sessions SET distraction_budget = ?1 WHERE id = ?2",
+            params![self.distraction_budget, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn saved_id(&self) -> Result<i64> {
+        self.id.ok_or_else(|| {
+            rusqlite::Error::InvalidParameterName(
+                "Cannot update unsaved session - call save() first".to_string(),
+            )
+        })
+    }
+
     pub fn budget_remaining(&self) -> i32 {
         (self.distraction_budget - self.distraction_used).max(0)
     }
@@ -122,6 +428,38 @@ impl FocusSession {
     pub fn is_budget_exhausted(&self) -> bool {
         self.distraction_used >= self.distraction_budget
     }
+
+    /// Whether this strict session was ended before its planned duration elapsed.
+    pub fn is_abandoned(&self) -> bool {
+        match (self.strict, self.ended_at, self.planned_duration) {
+            (true, Some(ended_at), Some(planned)) => {
+                ended_at < self.started_at + i64::from(planned)
+            }
+            _ => false,
+        }
+    }
+
+    /// Count and total seconds of sessions started in `[start, end)` that have ended.
+    pub fn completed_totals(conn: &Connection, start: i64, end: i64) -> Result<(i32, i64)> {
+        conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(ended_at - started_at), 0)
+             FROM focus_sessions
+             WHERE started_at >= ?1 AND started_at < ?2 AND ended_at IS NOT NULL",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+
+    /// Seconds left of the planned duration at `now`, or `None` for open-ended sessions.
+    pub fn time_remaining(&self, now: i64) -> Option<i64> {
+        self.planned_duration
+            .map(|planned| (i64::from(planned) - (now - self.started_at)).max(0))
+    }
+
+    /// A strict session can't be ended normally until its planned duration is up.
+    pub fn is_locked(&self, now: i64) -> bool {
+        self.strict && self.time_remaining(now).is_some_and(|left| left > 0)
+    }
 }
 
 #[cfg(test)]
@@ -336,6 +674,80 @@ mod tests {
         assert!(session.is_budget_exhausted());
     }
 
+    #[test]
+    fn test_planned_duration_persists() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut session = FocusSession::new(600, false);
+        session.planned_duration = Some(1500);
+        session.save(conn).unwrap();
+
+        let found = FocusSession::find_active(conn).unwrap().unwrap();
+        assert_eq!(found.planned_duration, Some(1500));
+    }
+
+    #[test]
+    fn test_time_remaining() {
+        let mut session = FocusSession::new(600, false);
+        assert_eq!(session.time_remaining(session.started_at), None);
+
+        session.planned_duration = Some(1500);
+        assert_eq!(session.time_remaining(session.started_at + 100), Some(1400));
+        assert_eq!(session.time_remaining(session.started_at + 2000), Some(0));
+    }
+
+    #[test]
+    fn test_strict_flag_persists() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut session = FocusSession::new(600, false);
+        session.strict = true;
+        session.save(conn).unwrap();
+
+        assert!(FocusSession::find_active(conn).unwrap().unwrap().strict);
+    }
+
+    #[test]
+    fn test_is_abandoned() {
+        let mut session = FocusSession::new(600, false);
+        session.planned_duration = Some(1500);
+        session.ended_at = Some(session.started_at + 600);
+        assert!(!session.is_abandoned(), "Non-strict sessions can end early");
+
+        session.strict = true;
+        assert!(session.is_abandoned());
+
+        session.ended_at = Some(session.started_at + 1500);
+        assert!(
+            !session.is_abandoned(),
+            "Completed sessions are not abandoned"
+        );
+    }
+
+    #[test]
+    fn test_completed_totals() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        for (started_at, ended_at) in [(1000, Some(1600)), (2000, Some(2300)), (3000, None)] {
+            let mut session = FocusSession::new(600, false);
+            session.started_at = started_at;
+            session.ended_at = ended_at;
+            session.save(conn).unwrap();
+        }
+
+        assert_eq!(
+            FocusSession::completed_totals(conn, 0, 5000).unwrap(),
+            (2, 900)
+        );
+        assert_eq!(
+            FocusSession::completed_totals(conn, 1500, 5000).unwrap(),
+            (1, 300)
+        );
+    }
+
     #[test]
     fn test_scheduled_flag_persists() {
         let (db, _dir) = setup_test_db();
@@ -386,4 +798,164 @@ mod tests {
             "add_distraction_time() should fail on unsaved session"
         );
     }
+
+    #[test]
+    fn test_record_tracked_time() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        assert!(!FocusSession::record_tracked_time(conn, 5, false).unwrap());
+
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        for idle in [false, false, true] {
+            assert!(FocusSession::record_tracked_time(conn, 5, idle).unwrap());
+        }
+
+        let found = FocusSession::find_active(conn).unwrap().unwrap();
+        assert_eq!((found.focused_secs, found.idle_secs), (10, 5));
+
+        session.end(conn).unwrap();
+        assert!(!FocusSession::record_tracked_time(conn, 5, false).unwrap());
+    }
+
+    #[test]
+    fn test_find_recent_newest_first() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        for started_at in [1000, 3000, 2000] {
+            let mut session = FocusSession::new(600, false);
+            session.started_at = started_at;
+            session.ended_at = Some(started_at + 60);
+            session.save(conn).unwrap();
+        }
+
+        let recent = FocusSession::find_recent(conn, 2).unwrap();
+        let starts: Vec<i64> = recent.iter().map(|s| s.started_at).collect();
+        assert_eq!(starts, vec![3000, 2000]);
+    }
+
+    #[test]
+    fn test_unlock_request_is_kept() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut session = FocusSession::new(600, false);
+        session.strict = true;
+        session.planned_duration = Some(1500);
+        session.save(conn).unwrap();
+        let started_at = session.started_at;
+        assert!(session.is_locked(started_at + 60));
+        assert!(!session.is_locked(started_at + 1500));
+
+        session.request_unlock(conn, started_at + 60).unwrap();
+        // A second request doesn't restart the countdown
+        session.request_unlock(conn, started_at + 90).unwrap();
+        session.mark_emergency_unlocked(conn).unwrap();
+
+        let found = FocusSession::find_active(conn).unwrap().unwrap();
+        assert_eq!(found.unlock_requested_at, Some(started_at + 60));
+        assert!(found.emergency_unlocked);
+    }
+
+    #[test]
+    fn test_claim_distraction_request() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+
+        assert!(session.claim_distraction_request(conn, 1000, 25).unwrap());
+        assert!(!session.claim_distraction_request(conn, 1010, 25).unwrap());
+        assert!(session.claim_distraction_request(conn, 1025, 25).unwrap());
+
+        // Each session keeps its own limit
+        let mut other = FocusSession::new(600, false);
+        other.save(conn).unwrap();
+        assert!(other.claim_distraction_request(conn, 1026, 25).unwrap());
+    }
+
+    #[test]
+    fn test_record_streak_keeps_longest() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+
+        assert!(session.record_streak(conn, 1800, "Code").unwrap());
+        assert!(!session.record_streak(conn, 900, "Terminal").unwrap());
+
+        let found = FocusSession::find_active(conn).unwrap().unwrap();
+        assert_eq!(found.longest_streak_secs, 1800);
+        assert_eq!(found.streak_app.as_deref(), Some("Code"));
+    }
+
+    #[test]
+    fn test_extend_only_timed_sessions() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut open_ended = FocusSession::new(600, false);
+        open_ended.save(conn).unwrap();
+        open_ended.extend(conn, 900).unwrap();
+        assert_eq!(open_ended.planned_duration, None);
+        open_ended.end(conn).unwrap();
+
+        let mut timed = FocusSession::new(600, false);
+        timed.planned_duration = Some(1500);
+        timed.save(conn).unwrap();
+        timed.extend(conn, 900).unwrap();
+        timed.mark_extension_offered(conn).unwrap();
+
+        let found = FocusSession::find_active(conn).unwrap().unwrap();
+        assert_eq!(found.planned_duration, Some(2400));
+        assert!(found.extension_offered);
+    }
+
+    #[test]
+    fn test_add_budget() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        session.add_budget(conn, 300).unwrap();
+
+        let found = FocusSession::find_active(conn).unwrap().unwrap();
+        assert_eq!(found.distraction_budget, 900);
+    }
+
+    #[test]
+    fn test_effectiveness() {
+        let mut session = FocusSession::new(600, false);
+        assert_eq!(session.effectiveness(0.0), None);
+
+        session.focused_secs = 1500;
+        session.idle_secs = 500;
+        session.distraction_used = 300;
+        // 0.4 * 0.5 + 0.3 * 0.75 + 0.3 * 0.8
+        assert_eq!(session.effectiveness(20.0), Some(67.0));
+
+        session.distraction_budget = 0;
+        session.distraction_used = 0;
+        session.idle_secs = 0;
+        assert_eq!(session.effectiveness(0.0), Some(100.0));
+    }
+
+    #[test]
+    fn test_end_stores_effectiveness() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        FocusSession::record_tracked_time(conn, 60, false).unwrap();
+        let mut session = FocusSession::find_active(conn).unwrap().unwrap();
+        session.end(conn).unwrap();
+
+        let stored = FocusSession::find_recent(conn, 1).unwrap();
+        assert_eq!(stored.first().unwrap().effectiveness_score, Some(100.0));
+    }
 }
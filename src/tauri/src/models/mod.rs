@@ -1,11 +1,35 @@
 pub mod activity;
+pub mod app_identity;
+pub mod blocked_attempt;
 pub mod category;
+pub mod distraction_event;
+pub mod domain_allow;
+pub mod editor_activity;
+pub mod enforced_break;
 pub mod focus_schedule;
 pub mod focus_session;
+pub mod note;
 pub mod rule;
+pub mod rule_group;
+pub mod rule_suggestion;
+pub mod session_block;
+pub mod setting;
+pub mod terminal_context;
 
-pub use activity::Activity;
+pub use activity::{Activity, ActivityFilter, UncategorizedSource};
+pub use app_identity::AppIdentity;
+pub use blocked_attempt::BlockedAttempt;
 pub use category::Category;
+pub use distraction_event::{CategoryBudget, DistractionEvent};
+pub use domain_allow::DomainAllow;
+pub use editor_activity::EditorActivity;
+pub use enforced_break::{BreakTrigger, EnforcedBreak};
 pub use focus_schedule::FocusSchedule;
 pub use focus_session::FocusSession;
-pub use rule::{MatchType, Rule};
+pub use note::Note;
+pub use rule::{MatchType, Rule, RuleCondition};
+pub use rule_group::RuleGroup;
+pub use rule_suggestion::{RuleSuggestion, SuggestionStatus};
+pub use session_block::{SessionBlock, SessionBlockedCategory, SessionException};
+pub use setting::Setting;
+pub use terminal_context::TerminalContext;
@@ -0,0 +1,110 @@
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// A journal note on a local day, e.g. "mostly prep for the board meeting",
+/// optionally pinned to one activity block of that day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    pub id: i64,
+    /// Local date, YYYY-MM-DD.
+    pub day: String,
+    pub activity_id: Option<i64>,
+    pub text: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+const COLUMNS: &str = "id, day, activity_id, text, created_at, updated_at";
+
+fn from_row(row: &rusqlite::Row) -> Result<Note> {
+    Ok(Note {
+        id: row.get(0)?,
+        day: row.get(1)?,
+        activity_id: row.get(2)?,
+        text: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+impl Note {
+    pub fn find_by_id(conn: &Connection, id: i64) -> Result<Option<Self>> {
+        conn.query_row(
+            &format!("SELECT {COLUMNS} FROM notes WHERE id = ?1"),
+            params![id],
+            from_row,
+        )
+        .optional()
+    }
+
+    /// Notes on days from `start_day` to `end_day` (inclusive), oldest first.
+    pub fn find_in_days(conn: &Connection, start_day: &str, end_day: &str) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM notes WHERE day >= ?1 AND day <= ?2
+             ORDER BY day, created_at, id"
+        ))?;
+        let rows = stmt.query_map(params![start_day, end_day], from_row)?;
+        rows.collect()
+    }
+
+    pub fn create(
+        conn: &Connection,
+        day: &str,
+        activity_id: Option<i64>,
+        text: &str,
+        now: i64,
+    ) -> Result<Self> {
+        conn.execute(
+            "INSERT INTO notes (day, activity_id, text, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![day, activity_id, text, now],
+        )?;
+        Ok(Self {
+            id: conn.last_insert_rowid(),
+            day: day.to_string(),
+            activity_id,
+            text: text.to_string(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Returns false if the note doesn't exist.
+    pub fn update_text(conn: &Connection, id: i64, text: &str, now: i64) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE notes SET text = ?1, updated_at = ?2 WHERE id = ?3",
+            params![text, now, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    pub fn delete(conn: &Connection, id: i64) -> Result<bool> {
+        let rows_affected = conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+        Ok(rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_notes_by_day() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let note = Note::create(conn, "2026-03-02", None, "Board prep", 1000).unwrap();
+        Note::create(conn, "2026-03-09", None, "Next week", 1000).unwrap();
+        assert!(Note::update_text(conn, note.id, "Mostly board prep", 2000).unwrap());
+
+        let notes = Note::find_in_days(conn, "2026-03-02", "2026-03-08").unwrap();
+        assert_eq!(notes.len(), 1);
+        let first = notes.first().unwrap();
+        assert_eq!(first.text, "Mostly board prep");
+        assert_eq!((first.created_at, first.updated_at), (1000, 2000));
+
+        assert!(Note::delete(conn, note.id).unwrap());
+        assert!(Note::find_by_id(conn, note.id).unwrap().is_none());
+        assert!(!Note::update_text(conn, note.id, "Gone", 3000).unwrap());
+    }
+}
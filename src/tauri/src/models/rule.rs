@@ -1,6 +1,9 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MatchType {
     App,
     Domain,
@@ -28,23 +31,80 @@ impl MatchType {
     }
 }
 
+/// When a rule is in effect: days of the week (1=Monday, 7=Sunday) and an
+/// "HH:MM" window, compared like focus schedules (start inclusive, end exclusive).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleCondition {
+    pub days_of_week: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+impl RuleCondition {
+    pub fn applies_at(&self, day: u32, time: &str) -> bool {
+        let on_day = self
+            .days_of_week
+            .split(',')
+            .filter_map(|s| s.trim().parse::<u32>().ok())
+            .any(|d| d == day);
+        on_day && self.start_time.as_str() <= time && time < self.end_time.as_str()
+    }
+
+    fn from_columns(
+        days_of_week: Option<String>,
+        start_time: Option<String>,
+        end_time: Option<String>,
+    ) -> Option<Self> {
+        Some(Self {
+            days_of_week: days_of_week?,
+            start_time: start_time?,
+            end_time: end_time?,
+        })
+    }
+}
+
 /// A rule for categorizing activities based on patterns.
 /// Fields `id` and `priority` are read from the database and used in tests;
 /// kept as part of the public API for future use (e.g., rule editing UI).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
     pub id: i64,
     pub pattern: String,
     pub match_type: MatchType,
     pub category_id: i64,
     pub priority: i32,
+    /// Browser profile or container the rule is limited to; `None` applies everywhere.
+    pub profile: Option<String>,
+    /// More patterns matched like `pattern`; the rule applies when any of them matches.
+    pub extra_patterns: Vec<String>,
+    /// The `RuleGroup` the rule belongs to, if any.
+    pub group_id: Option<i64>,
+    /// Days and hours the rule applies; `None` applies all the time.
+    pub condition: Option<RuleCondition>,
+    /// Bumped on every change, so an edit made from stale data is caught.
+    pub version: i64,
 }
 
 impl Rule {
     pub fn find_all(conn: &Connection) -> Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, pattern, match_type, category_id, priority FROM rules ORDER BY priority DESC"
-        )?;
+        Self::find_where(conn, "r.deleted_at IS NULL")
+    }
+
+    /// Rules that apply right now: ungrouped ones and those in enabled groups.
+    pub fn find_enabled(conn: &Connection) -> Result<Vec<Self>> {
+        Self::find_where(
+            conn,
+            "r.deleted_at IS NULL AND (r.group_id IS NULL
+                 OR r.group_id NOT IN (SELECT id FROM rule_groups WHERE enabled = 0))",
+        )
+    }
+
+    fn find_where(conn: &Connection, condition: &str) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT r.id, r.pattern, r.match_type, r.category_id, r.priority, r.profile, r.group_id,
+                    r.condition_days, r.condition_start, r.condition_end, r.version
+             FROM rules r WHERE {condition} ORDER BY r.priority DESC"
+        ))?;
 
         let rows = stmt.query_map([], |row| {
             let match_type_str: String = row.get(2)?;
@@ -55,12 +115,71 @@ impl Rule {
                 match_type,
                 category_id: row.get(3)?,
                 priority: row.get(4)?,
+                profile: row.get(5)?,
+                extra_patterns: Vec::new(),
+                group_id: row.get(6)?,
+                condition: RuleCondition::from_columns(row.get(7)?, row.get(8)?, row.get(9)?),
+                version: row.get(10)?,
             })
         })?;
 
+        let mut rules = rows.collect::<Result<Vec<Self>>>()?;
+        let mut extra = Self::all_extra_patterns(conn)?;
+        for rule in &mut rules {
+            rule.extra_patterns = extra.remove(&rule.id).unwrap_or_default();
+        }
+        Ok(rules)
+    }
+
+    /// Every pattern of the rule, `pattern` first.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.pattern.as_str()).chain(self.extra_patterns.iter().map(String::as_str))
+    }
+
+    fn all_extra_patterns(conn: &Connection) -> Result<HashMap<i64, Vec<String>>> {
+        let mut stmt = conn.prepare("SELECT rule_id, pattern FROM rule_patterns ORDER BY rowid")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+
+        let mut extra: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (rule_id, pattern) = row?;
+            extra.entry(rule_id).or_default().push(pattern);
+        }
+        Ok(extra)
+    }
+
+    fn extra_patterns_of(conn: &Connection, id: i64) -> Result<Vec<String>> {
+        let mut stmt =
+            conn.prepare("SELECT pattern FROM rule_patterns WHERE rule_id = ?1 ORDER BY rowid")?;
+        let rows = stmt.query_map(params![id], |row| row.get(0))?;
         rows.collect()
     }
 
+    /// Replace all of a rule's patterns. The first becomes `pattern`, the rest
+    /// `extra_patterns`. Returns false if the rule doesn't exist.
+    pub fn set_patterns(conn: &Connection, id: i64, patterns: &[&str]) -> Result<bool> {
+        let Some((first, rest)) = patterns.split_first() else {
+            return Ok(false);
+        };
+        let tx = conn.unchecked_transaction()?;
+        let rows_affected = tx.execute(
+            "UPDATE rules SET pattern = ?1, version = version + 1 WHERE id = ?2",
+            params![first, id],
+        )?;
+        if rows_affected == 0 {
+            return Ok(false);
+        }
+        tx.execute("DELETE FROM rule_patterns WHERE rule_id = ?1", params![id])?;
+        for pattern in rest {
+            tx.execute(
+                "INSERT INTO rule_patterns (rule_id, pattern) VALUES (?1, ?2)",
+                params![id, pattern],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
     /// Create a new rule.
     pub fn create(
         conn: &Connection,
@@ -80,35 +199,115 @@ impl Rule {
             match_type,
             category_id,
             priority,
+            profile: None,
+            extra_patterns: Vec::new(),
+            group_id: None,
+            condition: None,
+            version: 1,
         })
     }
 
+    /// Insert a hard-deleted rule again under its old ID, with all its settings.
+    /// Run inside a transaction so a failed pattern doesn't leave half a rule.
+    pub fn reinsert(conn: &Connection, rule: &Self) -> Result<()> {
+        conn.execute(
+            "INSERT INTO rules (id, pattern, match_type, category_id, priority, profile, group_id,
+                                condition_days, condition_start, condition_end, version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                rule.id,
+                rule.pattern,
+                rule.match_type.as_str(),
+                rule.category_id,
+                rule.priority,
+                rule.profile,
+                rule.group_id,
+                rule.condition.as_ref().map(|c| &c.days_of_week),
+                rule.condition.as_ref().map(|c| &c.start_time),
+                rule.condition.as_ref().map(|c| &c.end_time),
+                rule.version,
+            ],
+        )?;
+        for pattern in &rule.extra_patterns {
+            conn.execute(
+                "INSERT INTO rule_patterns (rule_id, pattern) VALUES (?1, ?2)",
+                params![rule.id, pattern],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Limit a rule to one browser profile, or clear the limit with `None`.
+    pub fn set_profile(conn: &Connection, id: i64, profile: Option<&str>) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rules SET profile = ?1, version = version + 1 WHERE id = ?2",
+            params![profile, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Limit a rule to some days and hours, or apply it all the time with `None`.
+    pub fn set_condition(
+        conn: &Connection,
+        id: i64,
+        condition: Option<&RuleCondition>,
+    ) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rules SET condition_days = ?1, condition_start = ?2, condition_end = ?3,
+                 version = version + 1
+             WHERE id = ?4",
+            params![
+                condition.map(|c| &c.days_of_week),
+                condition.map(|c| &c.start_time),
+                condition.map(|c| &c.end_time),
+                id
+            ],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Move a rule into a group, or out of any with `None`.
+    pub fn set_group(conn: &Connection, id: i64, group_id: Option<i64>) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rules SET group_id = ?1, version = version + 1 WHERE id = ?2",
+            params![group_id, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
     /// Find a rule by ID.
-    /// Kept as part of the public API for future use (e.g., rule editing UI).
-    #[expect(dead_code, reason = "Public API for future rule editing UI")]
     pub fn find_by_id(conn: &Connection, id: i64) -> Result<Option<Self>> {
         let mut stmt = conn.prepare(
-            "SELECT id, pattern, match_type, category_id, priority FROM rules WHERE id = ?1",
+            "SELECT id, pattern, match_type, category_id, priority, profile, group_id,
+                    condition_days, condition_start, condition_end, version
+             FROM rules WHERE id = ?1 AND deleted_at IS NULL",
         )?;
 
         let mut rows = stmt.query(params![id])?;
 
         if let Some(row) = rows.next()? {
+            let id = row.get(0)?;
             let match_type_str: String = row.get(2)?;
             let match_type = MatchType::from_str(&match_type_str).unwrap_or(MatchType::App);
             Ok(Some(Self {
-                id: row.get(0)?,
+                id,
                 pattern: row.get(1)?,
                 match_type,
                 category_id: row.get(3)?,
                 priority: row.get(4)?,
+                profile: row.get(5)?,
+                extra_patterns: Self::extra_patterns_of(conn, id)?,
+                group_id: row.get(6)?,
+                condition: RuleCondition::from_columns(row.get(7)?, row.get(8)?, row.get(9)?),
+                version: row.get(10)?,
             }))
         } else {
             Ok(None)
         }
     }
 
-    /// Update an existing rule.
+    /// Update an existing rule. With `expected_version`, only a rule still
+    /// at that version is changed. Returns false if nothing was updated.
     pub fn update(
         conn: &Connection,
         id: i64,
@@ -116,19 +315,111 @@ impl Rule {
         match_type: MatchType,
         category_id: i64,
         priority: i32,
+        expected_version: Option<i64>,
     ) -> Result<bool> {
         let rows_affected = conn.execute(
-            "UPDATE rules SET pattern = ?1, match_type = ?2, category_id = ?3, priority = ?4 WHERE id = ?5",
-            params![pattern, match_type.as_str(), category_id, priority, id],
+            "UPDATE rules SET pattern = ?1, match_type = ?2, category_id = ?3, priority = ?4,
+                              version = version + 1
+             WHERE id = ?5 AND (?6 IS NULL OR version = ?6)",
+            params![
+                pattern,
+                match_type.as_str(),
+                category_id,
+                priority,
+                id,
+                expected_version
+            ],
         )?;
         Ok(rows_affected > 0)
     }
 
-    /// Delete a rule.
+    /// Point an app at a category, reusing an existing app rule with the same pattern.
+    ///
+    /// A new rule is created with `priority` only when no app rule matches the name,
+    /// so repeated reassignments don't pile up duplicate rules.
+    pub fn assign_app(
+        conn: &Connection,
+        app_name: &str,
+        category_id: i64,
+        priority: i32,
+    ) -> Result<Self> {
+        let existing: Option<(i64, String, i32)> = conn
+            .query_row(
+                "SELECT id, pattern, priority FROM rules
+                 WHERE match_type = 'app' AND pattern = ?1 COLLATE NOCASE AND deleted_at IS NULL
+                 ORDER BY priority DESC LIMIT 1",
+                params![app_name],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        match existing {
+            Some((id, pattern, priority)) => {
+                Self::update(
+                    conn,
+                    id,
+                    &pattern,
+                    MatchType::App,
+                    category_id,
+                    priority,
+                    None,
+                )?;
+                Self::find_by_id(conn, id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+            }
+            None => Self::create(conn, app_name, MatchType::App, category_id, priority),
+        }
+    }
+
+    /// Delete a rule for good, skipping the trash.
     pub fn delete(conn: &Connection, id: i64) -> Result<bool> {
+        conn.execute("DELETE FROM rule_patterns WHERE rule_id = ?1", params![id])?;
         let rows_affected = conn.execute("DELETE FROM rules WHERE id = ?1", params![id])?;
         Ok(rows_affected > 0)
     }
+
+    /// Move a rule to the trash. Returns false if it doesn't exist or is
+    /// already there.
+    pub fn trash(conn: &Connection, id: i64, now: i64) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rules SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![now, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Take a rule out of the trash. Returns false if it isn't there.
+    pub fn restore(conn: &Connection, id: i64) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rules SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Rules in the trash with when they were deleted, most recent first.
+    pub fn find_deleted(conn: &Connection) -> Result<Vec<(Self, i64)>> {
+        let mut stmt =
+            conn.prepare("SELECT id, deleted_at FROM rules WHERE deleted_at IS NOT NULL")?;
+        let deleted_at = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<i64, i64>>>()?;
+        let mut rules: Vec<(Self, i64)> = Self::find_where(conn, "r.deleted_at IS NOT NULL")?
+            .into_iter()
+            .filter_map(|rule| deleted_at.get(&rule.id).map(|&at| (rule, at)))
+            .collect();
+        rules.sort_by_key(|&(_, at)| std::cmp::Reverse(at));
+        Ok(rules)
+    }
+
+    /// Permanently delete rules trashed before `before`.
+    pub fn purge_deleted(conn: &Connection, before: i64) -> Result<usize> {
+        conn.execute(
+            "DELETE FROM rule_patterns
+             WHERE rule_id IN (SELECT id FROM rules WHERE deleted_at < ?1)",
+            params![before],
+        )?;
+        conn.execute("DELETE FROM rules WHERE deleted_at < ?1", params![before])
+    }
 }
 
 #[cfg(test)]
@@ -216,6 +507,150 @@ mod tests {
         assert_eq!(last_rule.priority, 5);
     }
 
+    #[test]
+    fn test_assign_app_creates_rule() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+
+        let rule = Rule::assign_app(conn, "Zed", coding.id, 50).unwrap();
+
+        assert_eq!(rule.pattern, "Zed");
+        assert_eq!(rule.match_type, MatchType::App);
+        assert_eq!(rule.category_id, coding.id);
+        assert_eq!(rule.priority, 50);
+    }
+
+    #[test]
+    fn test_assign_app_reuses_existing_rule() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let categories = Category::find_all(conn).unwrap();
+        let coding = categories.iter().find(|c| c.name == "Coding").unwrap();
+        let entertainment = categories
+            .iter()
+            .find(|c| c.name == "Entertainment")
+            .unwrap();
+
+        let created = Rule::assign_app(conn, "Zed", coding.id, 50).unwrap();
+        let reassigned = Rule::assign_app(conn, "zed", entertainment.id, 50).unwrap();
+
+        assert_eq!(reassigned.id, created.id);
+        assert_eq!(reassigned.category_id, entertainment.id);
+        let zed_rules = Rule::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.pattern.eq_ignore_ascii_case("zed"))
+            .count();
+        assert_eq!(zed_rules, 1);
+    }
+
+    #[test]
+    fn test_update_checks_expected_version() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        let rule = Rule::create(conn, "zed", MatchType::App, coding.id, 10).unwrap();
+        assert_eq!(rule.version, 1);
+
+        let update = |priority, version| {
+            Rule::update(
+                conn,
+                rule.id,
+                "zed",
+                MatchType::App,
+                coding.id,
+                priority,
+                version,
+            )
+            .unwrap()
+        };
+        assert!(update(20, Some(1)));
+        // A second window still holding version 1
+        assert!(!update(30, Some(1)));
+        assert!(update(40, None));
+
+        let found = Rule::find_by_id(conn, rule.id).unwrap().unwrap();
+        assert_eq!(found.priority, 40);
+        assert_eq!(found.version, 3);
+
+        Rule::set_patterns(conn, rule.id, &["zed", "zeditor"]).unwrap();
+        assert_eq!(Rule::find_by_id(conn, rule.id).unwrap().unwrap().version, 4);
+    }
+
+    #[test]
+    fn test_set_patterns() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let news = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        let rule = Rule::create(conn, "cnn.com", MatchType::Domain, news.id, 10).unwrap();
+
+        assert!(
+            Rule::set_patterns(conn, rule.id, &["bbc.co.uk", "cnn.com", "nytimes.com"]).unwrap()
+        );
+        let found = Rule::find_by_id(conn, rule.id).unwrap().unwrap();
+        assert_eq!(
+            found.patterns().collect::<Vec<_>>(),
+            vec!["bbc.co.uk", "cnn.com", "nytimes.com"]
+        );
+        let listed = Rule::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|r| r.id == rule.id)
+            .unwrap();
+        assert_eq!(listed.extra_patterns, vec!["cnn.com", "nytimes.com"]);
+
+        assert!(Rule::set_patterns(conn, rule.id, &["cnn.com"]).unwrap());
+        assert!(Rule::find_by_id(conn, rule.id)
+            .unwrap()
+            .unwrap()
+            .extra_patterns
+            .is_empty());
+        assert!(!Rule::set_patterns(conn, rule.id + 1000, &["cnn.com"]).unwrap());
+    }
+
+    #[test]
+    fn test_set_condition() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let category = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        let rule = Rule::create(conn, "cnn.com", MatchType::Domain, category.id, 10).unwrap();
+        let lunch = RuleCondition {
+            days_of_week: "1,2,3,4,5".into(),
+            start_time: "12:00".into(),
+            end_time: "13:00".into(),
+        };
+
+        assert!(Rule::set_condition(conn, rule.id, Some(&lunch)).unwrap());
+        let found = Rule::find_by_id(conn, rule.id).unwrap().unwrap();
+        assert_eq!(found.condition.as_ref(), Some(&lunch));
+        assert!(lunch.applies_at(3, "12:30"));
+        assert!(!lunch.applies_at(3, "13:00"));
+        assert!(!lunch.applies_at(6, "12:30"));
+
+        assert!(Rule::set_condition(conn, rule.id, None).unwrap());
+        assert_eq!(
+            Rule::find_by_id(conn, rule.id).unwrap().unwrap().condition,
+            None
+        );
+    }
+
     #[test]
     fn test_match_type_as_str() {
         assert_eq!(MatchType::App.as_str(), "app");
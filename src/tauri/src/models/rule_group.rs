@@ -0,0 +1,97 @@
+use rusqlite::{params, Connection, Result};
+
+/// A named set of rules, e.g. "Work blocklist" or "Weekend mode", that is
+/// turned on and off as a unit. Rules in a disabled group neither categorize
+/// activity nor block domains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleGroup {
+    pub id: i64,
+    pub name: String,
+    pub enabled: bool,
+}
+
+impl RuleGroup {
+    pub fn find_all(conn: &Connection) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare("SELECT id, name, enabled FROM rule_groups ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Self {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                enabled: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Create an enabled group.
+    pub fn create(conn: &Connection, name: &str) -> Result<Self> {
+        conn.execute("INSERT INTO rule_groups (name) VALUES (?1)", params![name])?;
+        Ok(Self {
+            id: conn.last_insert_rowid(),
+            name: name.to_string(),
+            enabled: true,
+        })
+    }
+
+    /// Returns false if the group doesn't exist.
+    pub fn set_enabled(conn: &Connection, id: i64, enabled: bool) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rule_groups SET enabled = ?1 WHERE id = ?2",
+            params![enabled, id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Delete a group. Its rules stay, ungrouped.
+    pub fn delete(conn: &Connection, id: i64) -> Result<bool> {
+        conn.execute(
+            "UPDATE rules SET group_id = NULL, version = version + 1 WHERE group_id = ?1",
+            params![id],
+        )?;
+        let rows_affected = conn.execute("DELETE FROM rule_groups WHERE id = ?1", params![id])?;
+        Ok(rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Category, MatchType, Rule};
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_disabled_group_hides_its_rules() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let category = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        let rule = Rule::create(conn, "espn.com", MatchType::Domain, category.id, 10).unwrap();
+        let weekend = RuleGroup::create(conn, "Weekend mode").unwrap();
+        assert!(Rule::set_group(conn, rule.id, Some(weekend.id)).unwrap());
+
+        let enabled_ids = |conn| -> Vec<i64> {
+            Rule::find_enabled(conn)
+                .unwrap()
+                .iter()
+                .map(|r| r.id)
+                .collect()
+        };
+        assert!(enabled_ids(conn).contains(&rule.id));
+
+        assert!(RuleGroup::set_enabled(conn, weekend.id, false).unwrap());
+        assert!(!enabled_ids(conn).contains(&rule.id));
+        assert!(Rule::find_all(conn)
+            .unwrap()
+            .iter()
+            .any(|r| r.id == rule.id));
+        assert!(!RuleGroup::find_all(conn).unwrap().first().unwrap().enabled);
+
+        assert!(RuleGroup::delete(conn, weekend.id).unwrap());
+        let ungrouped = Rule::find_by_id(conn, rule.id).unwrap().unwrap();
+        assert_eq!(ungrouped.group_id, None);
+        assert!(enabled_ids(conn).contains(&rule.id));
+    }
+}
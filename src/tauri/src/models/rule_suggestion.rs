@@ -0,0 +1,207 @@
+use super::focus_session::current_timestamp;
+use super::MatchType;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+
+/// Where a suggestion is in the review queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionStatus {
+    Pending,
+    /// Turned into a rule by the user.
+    Accepted,
+    /// Rejected by the user; kept so the same suggestion isn't offered again.
+    Dismissed,
+}
+
+impl SuggestionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SuggestionStatus::Pending => "pending",
+            SuggestionStatus::Accepted => "accepted",
+            SuggestionStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(SuggestionStatus::Pending),
+            "accepted" => Some(SuggestionStatus::Accepted),
+            "dismissed" => Some(SuggestionStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// A rule proposed by a classifier, waiting for the user to accept or dismiss it.
+///
+/// Suggestions are never applied on their own; accepting one creates a `Rule`.
+#[derive(Debug, Clone)]
+pub struct RuleSuggestion {
+    pub id: Option<i64>,
+    pub pattern: String,
+    pub match_type: MatchType,
+    pub category_id: i64,
+    /// Name of the classifier that produced it (e.g. "llm").
+    pub source: String,
+    /// Classifier confidence from 0.0 to 1.0.
+    pub confidence: f64,
+    pub status: SuggestionStatus,
+    pub created_at: i64,
+}
+
+impl RuleSuggestion {
+    pub fn new(
+        pattern: &str,
+        match_type: MatchType,
+        category_id: i64,
+        source: &str,
+        confidence: f64,
+    ) -> Self {
+        Self {
+            id: None,
+            pattern: pattern.to_string(),
+            match_type,
+            category_id,
+            source: source.to_string(),
+            confidence: confidence.clamp(0.0, 1.0),
+            status: SuggestionStatus::Pending,
+            created_at: current_timestamp(),
+        }
+    }
+
+    /// Queue the suggestion unless one for the same pattern already exists
+    /// (pending, accepted, or dismissed). Returns whether it was added.
+    pub fn save(&mut self, conn: &Connection) -> Result<bool> {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO rule_suggestions
+                (pattern, match_type, category_id, source, confidence, status, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.pattern,
+                self.match_type.as_str(),
+                self.category_id,
+                self.source,
+                self.confidence,
+                self.status.as_str(),
+                self.created_at,
+            ],
+        )?;
+        if inserted > 0 {
+            self.id = Some(conn.last_insert_rowid());
+        }
+        Ok(inserted > 0)
+    }
+
+    /// Suggestions awaiting review, most confident first.
+    pub fn find_pending(conn: &Connection) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, pattern, match_type, category_id, source, confidence, status, created_at
+             FROM rule_suggestions WHERE status = 'pending'
+             ORDER BY confidence DESC, created_at DESC",
+        )?;
+        let rows = stmt.query_map([], Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn find_by_id(conn: &Connection, id: i64) -> Result<Option<Self>> {
+        conn.query_row(
+            "SELECT id, pattern, match_type, category_id, source, confidence, status, created_at
+             FROM rule_suggestions WHERE id = ?1",
+            params![id],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    /// Record the user's decision. Returns false if the suggestion doesn't exist.
+    pub fn set_status(conn: &Connection, id: i64, status: SuggestionStatus) -> Result<bool> {
+        let rows_affected = conn.execute(
+            "UPDATE rule_suggestions SET status = ?1 WHERE id = ?2",
+            params![status.as_str(), id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    fn from_row(row: &Row) -> Result<Self> {
+        let match_type: String = row.get(2)?;
+        let status: String = row.get(6)?;
+        Ok(Self {
+            id: Some(row.get(0)?),
+            pattern: row.get(1)?,
+            match_type: MatchType::from_str(&match_type).unwrap_or(MatchType::App),
+            category_id: row.get(3)?,
+            source: row.get(4)?,
+            confidence: row.get(5)?,
+            status: SuggestionStatus::from_str(&status).unwrap_or(SuggestionStatus::Pending),
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    fn coding_id(conn: &Connection) -> i64 {
+        Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            SuggestionStatus::Pending,
+            SuggestionStatus::Accepted,
+            SuggestionStatus::Dismissed,
+        ] {
+            assert_eq!(SuggestionStatus::from_str(status.as_str()), Some(status));
+        }
+        assert_eq!(SuggestionStatus::from_str("maybe"), None);
+    }
+
+    #[test]
+    fn test_save_skips_duplicates() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let category_id = coding_id(conn);
+
+        let mut first = RuleSuggestion::new("zed", MatchType::App, category_id, "llm", 0.9);
+        assert!(first.save(conn).unwrap());
+        assert!(first.id.is_some());
+
+        let mut again = RuleSuggestion::new("zed", MatchType::App, category_id, "llm", 0.5);
+        assert!(!again.save(conn).unwrap());
+        assert!(again.id.is_none());
+
+        let pending = RuleSuggestion::find_pending(conn).unwrap();
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn test_dismissed_leaves_queue() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let category_id = coding_id(conn);
+
+        let mut suggestion =
+            RuleSuggestion::new("docs.rs", MatchType::Domain, category_id, "llm", 0.7);
+        suggestion.save(conn).unwrap();
+        let id = suggestion.id.unwrap();
+
+        assert!(RuleSuggestion::set_status(conn, id, SuggestionStatus::Dismissed).unwrap());
+        assert!(RuleSuggestion::find_pending(conn).unwrap().is_empty());
+        assert_eq!(
+            RuleSuggestion::find_by_id(conn, id)
+                .unwrap()
+                .unwrap()
+                .status,
+            SuggestionStatus::Dismissed
+        );
+        assert!(!RuleSuggestion::set_status(conn, 999, SuggestionStatus::Accepted).unwrap());
+    }
+}
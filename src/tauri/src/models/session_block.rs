@@ -0,0 +1,126 @@
+use rusqlite::{params, Connection, Result};
+
+/// A domain pattern blocked only for the rest of one focus session, on top of
+/// the distracting-category rules. Removed with its session.
+pub struct SessionBlock;
+
+impl SessionBlock {
+    /// Block `pattern` for the session. Returns false if it already was.
+    pub fn add(conn: &Connection, session_id: i64, pattern: &str) -> Result<bool> {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO session_blocks (session_id, pattern) VALUES (?1, ?2)",
+            params![session_id, pattern],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    pub fn patterns_for_session(conn: &Connection, session_id: i64) -> Result<Vec<String>> {
+        let mut stmt = conn
+            .prepare("SELECT pattern FROM session_blocks WHERE session_id = ?1 ORDER BY pattern")?;
+        let rows = stmt.query_map(params![session_id], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+/// A category a session blocks even though it doesn't count as distracting
+/// (e.g. Communication), without changing how it's scored in stats.
+pub struct SessionBlockedCategory;
+
+impl SessionBlockedCategory {
+    /// Set the session's blocked categories, replacing any existing ones.
+    pub fn set_for_session(conn: &Connection, session_id: i64, category_ids: &[i64]) -> Result<()> {
+        conn.execute(
+            "DELETE FROM session_blocked_categories WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        for category_id in category_ids {
+            conn.execute(
+                "INSERT OR IGNORE INTO session_blocked_categories (session_id, category_id)
+                 VALUES (?1, ?2)",
+                params![session_id, category_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn for_session(conn: &Connection, session_id: i64) -> Result<Vec<i64>> {
+        let mut stmt = conn.prepare(
+            "SELECT category_id FROM session_blocked_categories
+             WHERE session_id = ?1 ORDER BY category_id",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+/// A domain a session leaves unblocked even when its rules would block it,
+/// e.g. `music.youtube.com` while Entertainment is blocked.
+pub struct SessionException;
+
+impl SessionException {
+    /// Set the session's exceptions, replacing any existing ones.
+    pub fn set_for_session(conn: &Connection, session_id: i64, domains: &[String]) -> Result<()> {
+        conn.execute(
+            "DELETE FROM session_exceptions WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        for domain in domains {
+            conn.execute(
+                "INSERT OR IGNORE INTO session_exceptions (session_id, domain) VALUES (?1, ?2)",
+                params![session_id, domain],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn domains_for_session(conn: &Connection, session_id: i64) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT domain FROM session_exceptions WHERE session_id = ?1 ORDER BY domain",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FocusSession;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_add_and_list() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        let id = session.id.unwrap();
+
+        assert!(SessionBlock::add(conn, id, "reddit.com").unwrap());
+        assert!(!SessionBlock::add(conn, id, "reddit.com").unwrap());
+        assert!(SessionBlock::add(conn, id, "news.ycombinator.com").unwrap());
+        assert_eq!(
+            SessionBlock::patterns_for_session(conn, id).unwrap(),
+            vec!["news.ycombinator.com", "reddit.com"]
+        );
+        assert!(SessionBlock::patterns_for_session(conn, id + 1)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_exceptions_replace() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut session = FocusSession::new(600, false);
+        session.save(conn).unwrap();
+        let id = session.id.unwrap();
+
+        SessionException::set_for_session(conn, id, &["music.youtube.com".into()]).unwrap();
+        SessionException::set_for_session(conn, id, &["docs.rs".into(), "docs.rs".into()]).unwrap();
+        assert_eq!(
+            SessionException::domains_for_session(conn, id).unwrap(),
+            vec!["docs.rs"]
+        );
+    }
+}
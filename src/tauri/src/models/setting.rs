@@ -0,0 +1,102 @@
+use crate::{config_file, policy};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// Key-value application settings persisted in the `settings` table.
+pub struct Setting;
+
+impl Setting {
+    /// Get the raw value for `key`, if any. A value locked by policy or set
+    /// in `config.toml` wins over the stored one; a policy default fills in
+    /// when nothing is stored.
+    pub fn get(conn: &Connection, key: &str) -> Result<Option<String>> {
+        if let Some(value) = policy::locked(key).or_else(|| config_file::overridden(key)) {
+            return Ok(Some(value));
+        }
+        let stored = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(stored.or_else(|| policy::default_for(key)))
+    }
+
+    /// Insert or replace the value stored for `key`.
+    pub fn set(conn: &Connection, key: &str, value: &str) -> Result<()> {
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Remove `key`, reverting it to its default.
+    pub fn delete(conn: &Connection, key: &str) -> Result<()> {
+        conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
+    /// Find all settings whose key starts with `prefix`, ordered by key.
+    pub fn find_by_prefix(conn: &Connection, prefix: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = conn.prepare(
+            "SELECT key, value FROM settings WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+        )?;
+        let rows = stmt.query_map(params![prefix], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_get_missing_returns_none() {
+        let (db, _dir) = setup_test_db();
+        assert_eq!(Setting::get(db.connection(), "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_delete() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        Setting::set(conn, "theme", "dark").unwrap();
+        Setting::delete(conn, "theme").unwrap();
+        assert_eq!(Setting::get(conn, "theme").unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_and_overwrite() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        Setting::set(conn, "theme", "dark").unwrap();
+        assert_eq!(Setting::get(conn, "theme").unwrap(), Some("dark".into()));
+
+        Setting::set(conn, "theme", "light").unwrap();
+        assert_eq!(Setting::get(conn, "theme").unwrap(), Some("light".into()));
+    }
+
+    #[test]
+    fn test_find_by_prefix() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        Setting::set(conn, "onboarding.b", "2").unwrap();
+        Setting::set(conn, "onboarding.a", "1").unwrap();
+        Setting::set(conn, "other", "3").unwrap();
+
+        let found = Setting::find_by_prefix(conn, "onboarding.").unwrap();
+        assert_eq!(
+            found,
+            vec![
+                ("onboarding.a".to_string(), "1".to_string()),
+                ("onboarding.b".to_string(), "2".to_string()),
+            ]
+        );
+    }
+}
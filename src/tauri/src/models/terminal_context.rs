@@ -0,0 +1,53 @@
+use rusqlite::{params, Connection, Result};
+
+/// Where a shell prompt was when it was last drawn, reported by the shell hook.
+#[derive(Debug, Clone)]
+pub struct TerminalContext {
+    pub id: Option<i64>,
+    pub timestamp: i64,
+    pub cwd: String,
+    /// Top level of the git repository containing `cwd`, if any.
+    pub repo: Option<String>,
+}
+
+impl TerminalContext {
+    pub fn new(timestamp: i64, cwd: &str, repo: Option<&str>) -> Self {
+        Self {
+            id: None,
+            timestamp,
+            cwd: cwd.to_string(),
+            repo: repo.map(str::to_string),
+        }
+    }
+
+    pub fn save(&mut self, conn: &Connection) -> Result<()> {
+        conn.execute(
+            "INSERT INTO terminal_contexts (timestamp, cwd, repo) VALUES (?1, ?2, ?3)",
+            params![self.timestamp, self.cwd, self.repo],
+        )?;
+        self.id = Some(conn.last_insert_rowid());
+        Ok(())
+    }
+
+    /// Contexts reported in `[start, end)`, oldest first.
+    pub fn find_in_range(conn: &Connection, start: i64, end: i64) -> Result<Vec<Self>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, cwd, repo FROM terminal_contexts
+             WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp, id",
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok(Self {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                cwd: row.get(2)?,
+                repo: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The repository if there is one, otherwise the directory.
+    pub fn project(&self) -> &str {
+        self.repo.as_deref().unwrap_or(&self.cwd)
+    }
+}
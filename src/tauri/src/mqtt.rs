@@ -0,0 +1,386 @@
+//! Optional MQTT publisher for home automation (Home Assistant and the like).
+//!
+//! Publishes focus state, remaining budget, and today's totals as retained
+//! messages under a configurable topic prefix, using a minimal MQTT 3.1.1
+//! client: connect, publish at `QoS` 0, disconnect. Plain TCP only. The
+//! broker password is kept in the keychain.
+
+use crate::db::{with_connection, Database};
+use crate::focus::{FocusManager, FocusState};
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Setting};
+use crate::secrets;
+use log::{error, warn};
+use rusqlite::{Connection, Result};
+use serde_json::json;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const ENABLED_SETTING: &str = "mqtt.enabled";
+const HOST_SETTING: &str = "mqtt.host";
+const PORT_SETTING: &str = "mqtt.port";
+const USERNAME_SETTING: &str = "mqtt.username";
+const TOPIC_PREFIX_SETTING: &str = "mqtt.topic_prefix";
+
+/// Keychain key of the broker password.
+pub const PASSWORD_SECRET: &str = "mqtt.password";
+
+pub const DEFAULT_PORT: u16 = 1883;
+pub const DEFAULT_TOPIC_PREFIX: &str = "foxus";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Seconds the broker waits for traffic before dropping us; the connection
+/// only lives for one round of publishes.
+const KEEP_ALIVE_SECS: u16 = 60;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+/// PUBLISH at `QoS` 0 with the retain flag, so new subscribers get the last value.
+const PUBLISH_RETAINED: u8 = 0x31;
+const DISCONNECT: u8 = 0xE0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    pub username: Option<String>,
+    /// Topics are `<prefix>/focus/active`, `<prefix>/today/productive_secs`, etc.
+    pub topic_prefix: String,
+}
+
+impl MqttSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            enabled: Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"),
+            host: Setting::get(conn, HOST_SETTING)?,
+            port: Setting::get(conn, PORT_SETTING)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_PORT),
+            username: Setting::get(conn, USERNAME_SETTING)?,
+            topic_prefix: Setting::get(conn, TOPIC_PREFIX_SETTING)?
+                .unwrap_or_else(|| DEFAULT_TOPIC_PREFIX.to_string()),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        Setting::set(conn, ENABLED_SETTING, &self.enabled.to_string())?;
+        Setting::set(conn, PORT_SETTING, &self.port.to_string())?;
+        Setting::set(conn, TOPIC_PREFIX_SETTING, &self.topic_prefix)?;
+        for (key, value) in [
+            (HOST_SETTING, &self.host),
+            (USERNAME_SETTING, &self.username),
+        ] {
+            match value {
+                Some(value) => Setting::set(conn, key, value)?,
+                None => Setting::delete(conn, key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Topic/payload pairs describing the current focus state and today's totals.
+pub fn messages(
+    prefix: &str,
+    state: &FocusState,
+    (productive, neutral, distracting): (i32, i32, i32),
+) -> Vec<(String, String)> {
+    let time_remaining = state
+        .time_remaining_secs
+        .map(|secs| secs.to_string())
+        .unwrap_or_default();
+    let summary = json!({
+        "active": state.active,
+        "budget_remaining_secs": state.budget_remaining,
+        "time_remaining_secs": state.time_remaining_secs,
+        "productive_secs": productive,
+        "neutral_secs": neutral,
+        "distracting_secs": distracting,
+    });
+    [
+        (
+            "focus/active",
+            if state.active { "on" } else { "off" }.to_string(),
+        ),
+        (
+            "focus/budget_remaining_secs",
+            state.budget_remaining.to_string(),
+        ),
+        ("focus/time_remaining_secs", time_remaining),
+        ("today/productive_secs", productive.to_string()),
+        ("today/neutral_secs", neutral.to_string()),
+        ("today/distracting_secs", distracting.to_string()),
+        ("state", summary.to_string()),
+    ]
+    .into_iter()
+    .map(|(topic, payload)| (format!("{prefix}/{topic}"), payload))
+    .collect()
+}
+
+/// MQTT variable-length "remaining length" field.
+fn encode_length(mut len: usize, out: &mut Vec<u8>) {
+    loop {
+        let digit = u8::try_from(len % 128).unwrap_or_default();
+        len /= 128;
+        out.push(if len > 0 { digit | 0x80 } else { digit });
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+/// Length-prefixed UTF-8 string; MQTT strings are capped at 65535 bytes.
+fn encode_string(value: &str, out: &mut Vec<u8>) {
+    let bytes = value.as_bytes();
+    let bytes = bytes.get(..usize::from(u16::MAX)).unwrap_or(bytes);
+    let len = u16::try_from(bytes.len()).unwrap_or(u16::MAX);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    encode_length(body.len(), &mut packet);
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn connect_packet(client_id: &str, username: Option<&str>, password: Option<&str>) -> Vec<u8> {
+    // Clean session, plus the username/password flags when set
+    let mut flags = 0x02;
+    if username.is_some() {
+        flags |= 0x80;
+    }
+    if password.is_some() {
+        flags |= 0x40;
+    }
+    let mut body = Vec::new();
+    encode_string("MQTT", &mut body);
+    body.push(4); // protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&KEEP_ALIVE_SECS.to_be_bytes());
+    encode_string(client_id, &mut body);
+    for value in [username, password].into_iter().flatten() {
+        encode_string(value, &mut body);
+    }
+    packet(CONNECT, &body)
+}
+
+fn publish_packet(topic: &str, payload: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    encode_string(topic, &mut body);
+    body.extend_from_slice(payload.as_bytes());
+    packet(PUBLISH_RETAINED, &body)
+}
+
+/// Send `messages` over a fresh connection to the broker. Blocks until done.
+fn publish(settings: &MqttSettings, messages: &[(String, String)]) -> io::Result<()> {
+    let host = settings
+        .host
+        .as_deref()
+        .ok_or_else(|| io::Error::other("no MQTT host configured"))?;
+    let addr = (host, settings.port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::other(format!("could not resolve {host}")))?;
+    let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+
+    // MQTT only sends a password along with a username
+    let password = settings
+        .username
+        .as_ref()
+        .and_then(|_| secrets::read(PASSWORD_SECRET));
+    stream.write_all(&connect_packet(
+        &format!("foxus-{}", std::process::id()),
+        settings.username.as_deref(),
+        password.as_deref(),
+    ))?;
+    let mut connack = [0u8; 4];
+    stream.read_exact(&mut connack)?;
+    match connack {
+        [CONNACK, 2, _, 0] => {}
+        [CONNACK, 2, _, code] => {
+            return Err(io::Error::other(format!(
+                "broker refused connection (code {code})"
+            )))
+        }
+        _ => return Err(io::Error::other("unexpected reply from broker")),
+    }
+
+    for (topic, payload) in messages {
+        stream.write_all(&publish_packet(topic, payload))?;
+    }
+    stream.write_all(&[DISCONNECT, 0])
+}
+
+/// Topic/payload pairs in publish order.
+type Messages = Vec<(String, String)>;
+
+/// Messages last published, so unchanged state isn't sent every minute.
+#[derive(Default)]
+pub struct MqttPublisherState(pub Arc<Mutex<Option<Messages>>>);
+
+/// Publish focus state and today's totals if they changed since last time.
+/// Called after sessions start or end and on every scheduler tick; the
+/// network work happens on a background thread.
+pub fn publish_focus_state(app: &AppHandle) {
+    let (Some(db), Some(focus_manager), Some(last)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<FocusManager>>(),
+        app.try_state::<MqttPublisherState>(),
+    ) else {
+        return;
+    };
+    let (_, _, midnight) = local_today();
+    let loaded = with_connection(&db, |conn| {
+        let settings = MqttSettings::load(conn)?;
+        if !settings.enabled || settings.host.is_none() {
+            return Ok(None);
+        }
+        let totals = Activity::productivity_totals(conn, midnight, current_timestamp())?;
+        Ok(Some((settings, totals)))
+    });
+    let (settings, totals) = match loaded {
+        Ok(Some(loaded)) => loaded,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to load MQTT settings: {e}");
+            return;
+        }
+    };
+    let state = match focus_manager.get_state() {
+        Ok(state) => state,
+        Err(e) => {
+            error!("Failed to get focus state for MQTT: {e}");
+            return;
+        }
+    };
+
+    let messages = messages(&settings.topic_prefix, &state, totals);
+    let last = Arc::clone(&last.0);
+    if last
+        .lock()
+        .is_ok_and(|last| last.as_ref() == Some(&messages))
+    {
+        return;
+    }
+    thread::spawn(move || match publish(&settings, &messages) {
+        Ok(()) => {
+            if let Ok(mut last) = last.lock() {
+                *last = Some(messages);
+            }
+        }
+        Err(e) => warn!("Failed to publish to MQTT: {e}"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn focus_state() -> FocusState {
+        FocusState {
+            active: true,
+            budget_remaining: 240,
+            blocked_domains: Vec::new(),
+            session_duration_secs: Some(600),
+            time_remaining_secs: Some(1200),
+            category_budgets: Vec::new(),
+            allowed_domains: Vec::new(),
+            blocked_categories: Vec::new(),
+            hard_blocked_domains: Vec::new(),
+            soft_limited_domains: Vec::new(),
+            budget_allowed_domains: Vec::new(),
+            blocklist_version: 0,
+        }
+    }
+
+    #[test]
+    fn test_messages() {
+        let messages = messages("home/foxus", &focus_state(), (3600, 600, 120));
+        let get = |topic: &str| {
+            messages
+                .iter()
+                .find(|(t, _)| t == topic)
+                .map(|(_, payload)| payload.as_str())
+        };
+        assert_eq!(get("home/foxus/focus/active"), Some("on"));
+        assert_eq!(get("home/foxus/focus/budget_remaining_secs"), Some("240"));
+        assert_eq!(get("home/foxus/focus/time_remaining_secs"), Some("1200"));
+        assert_eq!(get("home/foxus/today/distracting_secs"), Some("120"));
+        let summary: serde_json::Value =
+            serde_json::from_str(get("home/foxus/state").unwrap()).unwrap();
+        assert_eq!(summary.get("productive_secs"), Some(&json!(3600)));
+    }
+
+    #[test]
+    fn test_encode_length() {
+        let encoded = |len| {
+            let mut out = Vec::new();
+            encode_length(len, &mut out);
+            out
+        };
+        assert_eq!(encoded(0), vec![0x00]);
+        assert_eq!(encoded(127), vec![0x7F]);
+        assert_eq!(encoded(128), vec![0x80, 0x01]);
+        assert_eq!(encoded(16_383), vec![0xFF, 0x7F]);
+        assert_eq!(encoded(16_384), vec![0x80, 0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_connect_packet() {
+        let packet = connect_packet("foxus", Some("user"), Some("pw"));
+        let expected: Vec<u8> = [
+            &[CONNECT, 27][..],
+            &[0, 4],
+            b"MQTT",
+            &[4, 0xC2, 0, 60],
+            &[0, 5],
+            b"foxus",
+            &[0, 4],
+            b"user",
+            &[0, 2],
+            b"pw",
+        ]
+        .concat();
+        assert_eq!(packet, expected);
+
+        let anonymous = connect_packet("foxus", None, None);
+        assert_eq!(anonymous.get(9), Some(&0x02));
+    }
+
+    #[test]
+    fn test_publish_packet() {
+        let packet = publish_packet("a/b", "on");
+        assert_eq!(
+            packet,
+            [&[PUBLISH_RETAINED, 7, 0, 3][..], b"a/b", b"on"].concat()
+        );
+    }
+
+    #[test]
+    fn test_settings_defaults_and_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut settings = MqttSettings::load(conn).unwrap();
+        assert!(!settings.enabled);
+        assert_eq!(settings.port, DEFAULT_PORT);
+        assert_eq!(settings.topic_prefix, DEFAULT_TOPIC_PREFIX);
+
+        settings.enabled = true;
+        settings.host = Some("homeassistant.local".to_string());
+        settings.username = Some("foxus".to_string());
+        settings.save(conn).unwrap();
+        assert_eq!(MqttSettings::load(conn).unwrap(), settings);
+    }
+}
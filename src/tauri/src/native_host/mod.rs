@@ -1,11 +1,24 @@
+use crate::browser_integration::{record_heartbeat, HEARTBEAT_WRITE_INTERVAL_SECS};
+use crate::browsers::known_browser;
 use crate::categorizer::Categorizer;
-use crate::db::Database;
-use crate::focus::FocusManager;
+use crate::constants::MAX_PROFILE_LEN;
+use crate::db::{location, Database};
+use crate::external_activity::{self, ExternalActivity};
+use crate::focus::{
+    capped_grant, distraction_grant_secs, BlocklistUpdate, FocusManager,
+    DEFAULT_DISTRACTION_GRANT_SECS,
+};
+use crate::models::focus_session::current_timestamp;
 use crate::models::Activity;
+use crate::safe_lock;
+use crate::tracking_hours::is_tracking_time;
+use log::{error, warn};
 use serde::{Deserialize, Serialize};
 use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use url::Url;
 
 #[derive(Debug, Deserialize)]
@@ -16,11 +29,38 @@ pub enum IncomingMessage {
         url: String,
         title: String,
         timestamp: i64,
+        /// Browser profile label or container id; older extensions don't send it.
+        #[serde(default)]
+        profile: Option<String>,
+        /// Browser id such as `chrome` or `firefox`; older extensions don't send it.
+        #[serde(default)]
+        browser: Option<String>,
     },
     #[serde(rename = "request_state")]
     RequestState,
+    /// Time reported by another tool talking to the host directly.
+    #[serde(rename = "external_activity")]
+    ExternalActivity(ExternalActivity),
     #[serde(rename = "use_distraction_time")]
-    UseDistractionTime,
+    UseDistractionTime {
+        /// Domain the time is spent on; older extensions don't send it.
+        #[serde(default)]
+        domain: Option<String>,
+        #[serde(default)]
+        profile: Option<String>,
+        /// Seconds requested; capped at the configured grant size.
+        #[serde(default)]
+        secs: Option<i32>,
+    },
+    /// The user landed on the block page for `url`.
+    #[serde(rename = "blocked_attempt")]
+    BlockedAttempt { url: String },
+    /// Sent every minute so the app can tell the extension is connected.
+    #[serde(rename = "heartbeat")]
+    Heartbeat,
+    /// A type this host doesn't know, e.g. from a newer extension.
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Serialize)]
@@ -34,20 +74,126 @@ pub enum OutgoingMessage {
         budget_remaining: i32,
         #[serde(rename = "blockedDomains")]
         blocked_domains: Vec<String>,
+        /// Session exceptions that stay reachable despite `blockedDomains`.
+        #[serde(rename = "allowedDomains")]
+        allowed_domains: Vec<String>,
+        /// `blockedDomains` that get the hard block page.
+        #[serde(rename = "hardBlockedDomains")]
+        hard_blocked_domains: Vec<String>,
+        /// `blockedDomains` that offer to spend distraction budget instead.
+        #[serde(rename = "softLimitedDomains")]
+        soft_limited_domains: Vec<String>,
+        /// Domains open for now because budget was spent on them.
+        #[serde(rename = "budgetAllowedDomains")]
+        budget_allowed_domains: Vec<String>,
+        /// Budget seconds granted per "use distraction time" request.
+        #[serde(rename = "grantSecs")]
+        grant_secs: i32,
+        #[serde(rename = "blocklistVersion")]
+        blocklist_version: u64,
+    },
+    /// Blocked domains changed; apply on top of `fromVersion`, or request the
+    /// full state if the extension holds a different version.
+    #[serde(rename = "blocklist_updated")]
+    BlocklistUpdated {
+        #[serde(rename = "fromVersion")]
+        from_version: u64,
+        version: u64,
+        added: Vec<String>,
+        removed: Vec<String>,
+        /// Replaces the session exceptions; absent when they didn't change.
+        #[serde(rename = "allowedDomains", skip_serializing_if = "Option::is_none")]
+        allowed_domains: Option<Vec<String>>,
     },
     #[serde(rename = "budget_updated")]
     BudgetUpdated { remaining: i32 },
     #[serde(rename = "hard_blocked")]
     HardBlocked,
+    #[serde(rename = "activity_recorded")]
+    ActivityRecorded { id: i64 },
+    #[serde(rename = "error")]
+    Error { code: ErrorCode, message: String },
+}
+
+/// Machine-readable reason in an `error` response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    MessageTooLarge,
+    InvalidUtf8,
+    InvalidJson,
+    UnknownType,
+    /// The message was well-formed but couldn't be acted on.
+    Rejected,
+}
+
+/// A frame that arrived whole but doesn't hold a message. The stream is
+/// still in step afterwards, so the host answers with an error and reads on.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FrameError {
+    /// The length prefix claimed this many bytes; the body was skipped.
+    TooLarge(usize),
+    InvalidUtf8,
+    /// Not JSON, or JSON that doesn't fit the message's fields.
+    InvalidJson(String),
+}
+
+impl FrameError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::TooLarge(_) => ErrorCode::MessageTooLarge,
+            Self::InvalidUtf8 => ErrorCode::InvalidUtf8,
+            Self::InvalidJson(_) => ErrorCode::InvalidJson,
+        }
+    }
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLarge(len) => write!(
+                f,
+                "Message too large: {len} bytes (max: {MAX_MESSAGE_SIZE} bytes)"
+            ),
+            Self::InvalidUtf8 => write!(f, "Message is not valid UTF-8"),
+            Self::InvalidJson(reason) => write!(f, "Invalid message: {reason}"),
+        }
+    }
+}
+
+impl From<FrameError> for OutgoingMessage {
+    fn from(e: FrameError) -> Self {
+        Self::Error {
+            code: e.code(),
+            message: e.to_string(),
+        }
+    }
 }
 
 /// Chrome Native Messaging protocol maximum message size (1 MB).
 const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
 
+/// How often the host checks for blocklist changes to push to the extension.
+const BLOCKLIST_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+impl From<BlocklistUpdate> for OutgoingMessage {
+    fn from(update: BlocklistUpdate) -> Self {
+        Self::BlocklistUpdated {
+            from_version: update.from_version,
+            version: update.version,
+            added: update.added,
+            removed: update.removed,
+            allowed_domains: update.allowed_domains,
+        }
+    }
+}
+
 pub struct NativeHost {
     db: Arc<Mutex<Database>>,
     focus_manager: Arc<FocusManager>,
     categorizer: Arc<Mutex<Categorizer>>,
+    /// When the heartbeat was last written, to throttle writes.
+    last_heartbeat: AtomicI64,
 }
 
 impl NativeHost {
@@ -60,72 +206,94 @@ impl NativeHost {
             db,
             focus_manager,
             categorizer,
+            last_heartbeat: AtomicI64::new(0),
         }
     }
 
+    /// Answer messages until the extension closes the pipe. Bad messages are
+    /// skipped with an `error` reply; only a broken stream ends the loop.
     pub fn run(&self) -> io::Result<()> {
+        self.watch_blocklist();
+        let mut input = io::stdin().lock();
         loop {
-            let message = Self::read_message()?;
-            let response = self.handle_message(message);
-
-            if let Some(resp) = response {
-                Self::write_message(&resp)?;
+            if let Some(resp) = self.handle_frame(&mut input)? {
+                Self::send(&resp)?;
             }
         }
     }
 
-    #[expect(
-        clippy::as_conversions,
-        reason = "u32 -> usize widening cast is safe on all supported platforms (32-bit and 64-bit)"
-    )]
-    fn read_message() -> io::Result<IncomingMessage> {
-        // Chrome Native Messaging protocol specifies little-endian byte order
-        let mut len_bytes = [0u8; 4];
-        io::stdin().read_exact(&mut len_bytes)?;
-        let len = u32::from_le_bytes(len_bytes) as usize;
-
-        if len > MAX_MESSAGE_SIZE {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Message too large: {len} bytes (max: {MAX_MESSAGE_SIZE} bytes)"),
-            ));
-        }
-
-        let mut buffer = vec![0u8; len];
-        io::stdin().read_exact(&mut buffer)?;
-
-        serde_json::from_slice(&buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    /// Read one frame from `input` and answer it. Malformed frames get an
+    /// `error` response; only a broken stream (e.g. EOF) is an `Err`.
+    pub fn handle_frame(&self, input: &mut impl Read) -> io::Result<Option<OutgoingMessage>> {
+        let frame = read_message(input)?;
+        self.follow_move();
+        // Even a malformed frame shows the extension is connected
+        self.record_heartbeat(current_timestamp());
+        Ok(match frame {
+            Ok(message) => self.handle_message(message),
+            Err(e) => {
+                warn!("Skipping malformed message: {e}");
+                Some(OutgoingMessage::from(e))
+            }
+        })
     }
 
-    #[expect(
-        clippy::cast_possible_truncation,
-        reason = "Message size is validated to be <= MAX_MESSAGE_SIZE (1MB), well within u32 range"
-    )]
-    #[expect(
-        clippy::as_conversions,
-        reason = "usize -> u32 narrowing cast is safe because json.len() is validated <= MAX_MESSAGE_SIZE (1MB)"
-    )]
-    fn write_message(message: &OutgoingMessage) -> io::Result<()> {
-        let json = serde_json::to_vec(message)?;
-
-        if json.len() > MAX_MESSAGE_SIZE {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!(
-                    "Outgoing message too large: {} bytes (max: {MAX_MESSAGE_SIZE} bytes)",
-                    json.len()
-                ),
-            ));
+    /// Pick up a database the app moved since the host started. Checked
+    /// before every message, since the old file is gone once it has moved
+    /// and anything written to it would be lost.
+    fn follow_move(&self) {
+        let mut db = safe_lock(&self.db, "Database");
+        if let Err(e) = location::follow_move(&mut db) {
+            error!("Failed to follow moved database: {e}");
         }
+    }
 
-        let len = json.len() as u32;
+    /// Note that the extension is connected, at most once per
+    /// `HEARTBEAT_WRITE_INTERVAL_SECS`.
+    fn record_heartbeat(&self, now: i64) {
+        let last = self.last_heartbeat.load(Ordering::Relaxed);
+        if now - last < HEARTBEAT_WRITE_INTERVAL_SECS {
+            return;
+        }
+        self.last_heartbeat.store(now, Ordering::Relaxed);
+        let db = safe_lock(&self.db, "Database");
+        if let Err(e) = record_heartbeat(db.connection(), now) {
+            error!("Failed to record browser heartbeat: {e}");
+        }
+    }
 
-        // Chrome Native Messaging protocol specifies little-endian byte order
-        io::stdout().write_all(&len.to_le_bytes())?;
-        io::stdout().write_all(&json)?;
-        io::stdout().flush()?;
+    /// Push blocklist changes (e.g. rules edited in the app) to the extension
+    /// as they happen. Stops once the extension side of the pipe is gone.
+    fn watch_blocklist(&self) {
+        let focus_manager = Arc::clone(&self.focus_manager);
+        thread::spawn(move || loop {
+            thread::sleep(BLOCKLIST_POLL_INTERVAL);
+            match focus_manager.blocklist_update() {
+                Ok(Some(update)) => {
+                    if Self::send(&OutgoingMessage::from(update)).is_err() {
+                        return;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => error!("Failed to check blocklist changes: {e}"),
+            }
+        });
+    }
 
-        Ok(())
+    /// Write `message`, or an `error` in its place if it can't be encoded, so
+    /// one oversized reply doesn't end the connection.
+    fn send(message: &OutgoingMessage) -> io::Result<()> {
+        match encode_message(message) {
+            Ok(frame) => write_frame(&frame),
+            Err(e) => {
+                warn!("Dropping response that can't be sent: {e}");
+                let fallback = OutgoingMessage::Error {
+                    code: ErrorCode::MessageTooLarge,
+                    message: e.to_string(),
+                };
+                write_frame(&encode_message(&fallback)?)
+            }
+        }
     }
 
     fn handle_message(&self, message: IncomingMessage) -> Option<OutgoingMessage> {
@@ -134,12 +302,56 @@ impl NativeHost {
                 url,
                 title,
                 timestamp,
-            } => {
-                self.record_activity(&url, &title, timestamp);
+                profile,
+                browser,
+            } => self
+                .record_activity(
+                    &url,
+                    &title,
+                    timestamp,
+                    normalize_profile(profile),
+                    browser.as_deref().and_then(known_browser),
+                )
+                .err()
+                .map(|e| {
+                    error!("Failed to record browser activity: {e}");
+                    rejected(&e)
+                }),
+            IncomingMessage::RequestState => Some(self.get_state()),
+            IncomingMessage::ExternalActivity(external) => {
+                Some(self.record_external_activity(&external))
+            }
+            IncomingMessage::UseDistractionTime {
+                domain,
+                profile,
+                secs,
+            } => self.use_distraction_time(
+                domain.as_deref(),
+                normalize_profile(profile).as_deref(),
+                secs,
+            ),
+            IncomingMessage::BlockedAttempt { url } => {
+                self.record_blocked_attempt(&url);
                 None
             }
-            IncomingMessage::RequestState => Some(self.get_state()),
-            IncomingMessage::UseDistractionTime => self.use_distraction_time(),
+            IncomingMessage::Heartbeat => None,
+            IncomingMessage::Unknown => Some(OutgoingMessage::Error {
+                code: ErrorCode::UnknownType,
+                message: "Unknown message type".to_string(),
+            }),
+        }
+    }
+
+    fn record_blocked_attempt(&self, url: &str) {
+        let domain = extract_domain(url);
+        if domain.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .focus_manager
+            .record_blocked_attempt(&domain, current_timestamp())
+        {
+            error!("Failed to record blocked attempt: {e}");
         }
     }
 
@@ -151,7 +363,14 @@ impl NativeHost {
         clippy::as_conversions,
         reason = "u64 -> i64 widening cast is safe for timestamps (won't overflow until year 292 billion)"
     )]
-    fn record_activity(&self, url: &str, title: &str, _timestamp: i64) {
+    fn record_activity(
+        &self,
+        url: &str,
+        title: &str,
+        _timestamp: i64,
+        profile: Option<String>,
+        browser: Option<&str>,
+    ) -> rusqlite::Result<()> {
         // Input validation: limit URL and title length to prevent DoS
         const MAX_URL_LEN: usize = 2048;
         const MAX_TITLE_LEN: usize = 512;
@@ -166,37 +385,89 @@ impl NativeHost {
             .as_secs() as i64;
 
         let category_id = match self.categorizer.lock() {
-            Ok(cat) => cat.categorize_url(&domain),
-            Err(poisoned) => poisoned.into_inner().categorize_url(&domain),
+            Ok(cat) => cat.categorize_url(&domain, profile.as_deref()),
+            Err(poisoned) => poisoned
+                .into_inner()
+                .categorize_url(&domain, profile.as_deref()),
         };
 
         let mut activity = Activity::new(timestamp, 5, "browser", None, Some(title));
         activity.url = Some(url.to_string());
         activity.domain = Some(domain);
         activity.category_id = Some(category_id);
+        activity.profile = profile;
+        activity.browser = browser.map(String::from);
 
-        if let Ok(db) = self.db.lock() {
-            let _ = activity.save(db.connection());
+        let db = safe_lock(&self.db, "Database");
+        // Outside the tracking hours, or while away, the page isn't recorded
+        if !is_tracking_time(db.connection())? {
+            return Ok(());
         }
+        activity.save(db.connection())
+    }
+
+    fn record_external_activity(&self, external: &ExternalActivity) -> OutgoingMessage {
+        let categorizer = safe_lock(&self.categorizer, "Categorizer");
+        let db = safe_lock(&self.db, "Database");
+        match external_activity::record_now(db.connection(), &categorizer, external) {
+            Ok(id) => OutgoingMessage::ActivityRecorded { id },
+            Err(e) => rejected(&e),
+        }
+    }
+
+    /// Configured grant size, falling back to the default if it can't be read.
+    fn grant_secs(&self) -> i32 {
+        self.db
+            .lock()
+            .ok()
+            .and_then(|db| distraction_grant_secs(db.connection()).ok())
+            .unwrap_or(DEFAULT_DISTRACTION_GRANT_SECS)
     }
 
     fn get_state(&self) -> OutgoingMessage {
+        let grant_secs = self.grant_secs();
         match self.focus_manager.get_state() {
             Ok(state) => OutgoingMessage::State {
                 focus_active: state.active,
                 budget_remaining: state.budget_remaining,
                 blocked_domains: state.blocked_domains,
+                allowed_domains: state.allowed_domains,
+                hard_blocked_domains: state.hard_blocked_domains,
+                soft_limited_domains: state.soft_limited_domains,
+                budget_allowed_domains: state.budget_allowed_domains,
+                grant_secs,
+                blocklist_version: state.blocklist_version,
             },
             Err(_) => OutgoingMessage::State {
                 focus_active: false,
                 budget_remaining: 0,
                 blocked_domains: vec![],
+                allowed_domains: vec![],
+                hard_blocked_domains: vec![],
+                soft_limited_domains: vec![],
+                budget_allowed_domains: vec![],
+                grant_secs,
+                blocklist_version: 0,
             },
         }
     }
 
-    fn use_distraction_time(&self) -> Option<OutgoingMessage> {
-        match self.focus_manager.use_distraction_time(30) {
+    fn use_distraction_time(
+        &self,
+        domain: Option<&str>,
+        profile: Option<&str>,
+        requested_secs: Option<i32>,
+    ) -> Option<OutgoingMessage> {
+        let secs = capped_grant(requested_secs, self.grant_secs());
+        let domain = domain.filter(|d| !d.is_empty());
+        let category_id = domain.map(|d| match self.categorizer.lock() {
+            Ok(cat) => cat.categorize_url(d, profile),
+            Err(poisoned) => poisoned.into_inner().categorize_url(d, profile),
+        });
+        match self
+            .focus_manager
+            .use_distraction_time(secs, domain, category_id)
+        {
             Ok(Some(remaining)) => {
                 if remaining <= 0 {
                     Some(OutgoingMessage::HardBlocked)
@@ -204,11 +475,107 @@ impl NativeHost {
                     Some(OutgoingMessage::BudgetUpdated { remaining })
                 }
             }
-            _ => None,
+            Ok(None) => None,
+            Err(e) => {
+                error!("Failed to use distraction time: {e}");
+                Some(rejected(&e))
+            }
+        }
+    }
+}
+
+/// Read one length-prefixed frame. The outer `Err` means the stream itself
+/// failed (EOF, a truncated frame); the inner one a frame that was read in
+/// full but isn't a valid message.
+#[expect(
+    clippy::as_conversions,
+    reason = "u32 -> usize widening cast is safe on all supported platforms (32-bit and 64-bit)"
+)]
+pub fn read_message(input: &mut impl Read) -> io::Result<Result<IncomingMessage, FrameError>> {
+    // Chrome Native Messaging protocol specifies little-endian byte order
+    let mut len_bytes = [0u8; 4];
+    input.read_exact(&mut len_bytes)?;
+    let claimed = u32::from_le_bytes(len_bytes);
+    let len = claimed as usize;
+
+    if len > MAX_MESSAGE_SIZE {
+        // Skip the body so the next length prefix lines up
+        let skipped = io::copy(
+            &mut input.by_ref().take(u64::from(claimed)),
+            &mut io::sink(),
+        )?;
+        if skipped < u64::from(claimed) {
+            return Err(io::ErrorKind::UnexpectedEof.into());
         }
+        return Ok(Err(FrameError::TooLarge(len)));
+    }
+
+    let mut buffer = vec![0u8; len];
+    input.read_exact(&mut buffer)?;
+
+    let Ok(text) = std::str::from_utf8(&buffer) else {
+        return Ok(Err(FrameError::InvalidUtf8));
+    };
+    Ok(serde_json::from_str(text).map_err(|e| FrameError::InvalidJson(e.to_string())))
+}
+
+/// Frame `message` for stdout: a little-endian length, then the JSON.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "Message size is validated to be <= MAX_MESSAGE_SIZE (1MB), well within u32 range"
+)]
+#[expect(
+    clippy::as_conversions,
+    reason = "usize -> u32 narrowing cast is safe because json.len() is validated <= MAX_MESSAGE_SIZE (1MB)"
+)]
+fn encode_message(message: &OutgoingMessage) -> io::Result<Vec<u8>> {
+    let json = serde_json::to_vec(message)?;
+
+    if json.len() > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Outgoing message too large: {} bytes (max: {MAX_MESSAGE_SIZE} bytes)",
+                json.len()
+            ),
+        ));
+    }
+
+    // Chrome Native Messaging protocol specifies little-endian byte order
+    let mut frame = (json.len() as u32).to_le_bytes().to_vec();
+    frame.extend(json);
+    Ok(frame)
+}
+
+/// Locked for the whole frame: the blocklist watcher writes from its own thread.
+fn write_frame(frame: &[u8]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(frame)?;
+    stdout.flush()
+}
+
+/// `error` reply for a well-formed message that couldn't be acted on.
+fn rejected(e: &impl std::fmt::Display) -> OutgoingMessage {
+    OutgoingMessage::Error {
+        code: ErrorCode::Rejected,
+        message: e.to_string(),
     }
 }
 
+/// Trim a profile identifier from the extension, dropping empty ones and
+/// capping the length like the other activity fields.
+fn normalize_profile(profile: Option<String>) -> Option<String> {
+    let profile = profile?;
+    let profile = profile.trim();
+    // Cut on a character boundary, or a long non-ASCII label would be kept whole
+    let end = (0..=MAX_PROFILE_LEN.min(profile.len()))
+        .rev()
+        .find(|&end| profile.is_char_boundary(end))
+        .unwrap_or(0);
+    let profile = profile.get(..end).unwrap_or_default();
+    (!profile.is_empty()).then(|| profile.to_string())
+}
+
 /// Extract domain from a URL using proper URL parsing.
 /// This handles edge cases like URLs with userinfo (e.g., `<https://user@evil.com@legitimate.com>`).
 fn extract_domain(url_str: &str) -> String {
@@ -235,6 +602,410 @@ fn extract_domain(url_str: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::away_mode::AwayPeriod;
+    use crate::test_utils::setup_test_db;
+    use proptest::prelude::*;
+
+    const KNOWN_TYPES: &[&str] = &[
+        "activity",
+        "request_state",
+        "external_activity",
+        "use_distraction_time",
+        "blocked_attempt",
+        "heartbeat",
+    ];
+
+    fn host() -> (NativeHost, tempfile::TempDir) {
+        let (db, dir) = setup_test_db();
+        let categorizer = Arc::new(Mutex::new(Categorizer::new(db.connection()).unwrap()));
+        let db = Arc::new(Mutex::new(db));
+        let focus_manager = Arc::new(FocusManager::new(Arc::clone(&db)));
+        (NativeHost::new(db, focus_manager, categorizer), dir)
+    }
+
+    fn frame(body: &[u8]) -> Vec<u8> {
+        let mut bytes = u32::try_from(body.len()).unwrap().to_le_bytes().to_vec();
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    fn error_code(response: Option<OutgoingMessage>) -> Option<ErrorCode> {
+        match response {
+            Some(OutgoingMessage::Error { code, .. }) => Some(code),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_read_message_skips_oversized_frame() {
+        let claimed = MAX_MESSAGE_SIZE + 1;
+        let mut bytes = u32::try_from(claimed).unwrap().to_le_bytes().to_vec();
+        bytes.resize(4 + claimed, b' ');
+        bytes.extend(frame(br#"{"type":"request_state"}"#));
+        let mut input = bytes.as_slice();
+
+        assert_eq!(
+            read_message(&mut input).unwrap().unwrap_err(),
+            FrameError::TooLarge(claimed)
+        );
+        assert!(matches!(
+            read_message(&mut input).unwrap(),
+            Ok(IncomingMessage::RequestState)
+        ));
+    }
+
+    #[test]
+    fn test_read_message_rejects_invalid_utf8() {
+        let bytes = frame(b"{\"type\":\"activity\xff\"}");
+        assert_eq!(
+            read_message(&mut bytes.as_slice()).unwrap().unwrap_err(),
+            FrameError::InvalidUtf8
+        );
+    }
+
+    #[test]
+    fn test_read_message_truncated_frame_is_eof() {
+        let bytes = frame(br#"{"type":"request_state"}"#);
+        let mut truncated = bytes.get(..bytes.len() - 1).unwrap();
+        assert_eq!(
+            read_message(&mut truncated).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            read_message(&mut &[1u8, 0][..]).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_handle_frame_answers_malformed_frames_and_keeps_reading() {
+        let (host, _dir) = host();
+        let mut bytes = frame(b"{not json");
+        bytes.extend(frame(br#"{"type":"from_the_future"}"#));
+        bytes.extend(frame(br#"{"type":"activity","url":42}"#));
+        bytes.extend(frame(br#"{"type":"request_state"}"#));
+        let mut input = bytes.as_slice();
+
+        assert_eq!(
+            error_code(host.handle_frame(&mut input).unwrap()),
+            Some(ErrorCode::InvalidJson)
+        );
+        assert_eq!(
+            error_code(host.handle_frame(&mut input).unwrap()),
+            Some(ErrorCode::UnknownType)
+        );
+        assert_eq!(
+            error_code(host.handle_frame(&mut input).unwrap()),
+            Some(ErrorCode::InvalidJson)
+        );
+        assert!(matches!(
+            host.handle_frame(&mut input).unwrap(),
+            Some(OutgoingMessage::State { .. })
+        ));
+        assert_eq!(
+            host.handle_frame(&mut input).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_handle_frame_rejects_bad_activity_and_keeps_reading() {
+        let (host, _dir) = host();
+        let mut bytes = frame(
+            br#"{"type":"external_activity","source":"app","name":"vim","duration_secs":60}"#,
+        );
+        bytes.extend(frame(br#"{"type":"request_state"}"#));
+        let mut input = bytes.as_slice();
+
+        assert_eq!(
+            error_code(host.handle_frame(&mut input).unwrap()),
+            Some(ErrorCode::Rejected)
+        );
+        assert!(matches!(
+            host.handle_frame(&mut input).unwrap(),
+            Some(OutgoingMessage::State { .. })
+        ));
+    }
+
+    #[test]
+    fn test_handle_frame_records_heartbeat() {
+        let (host, _dir) = host();
+        let bytes = frame(br#"{"type":"heartbeat"}"#);
+        assert!(host.handle_frame(&mut bytes.as_slice()).unwrap().is_none());
+
+        let db = safe_lock(&host.db, "Database");
+        let status = crate::browser_integration::status(db.connection(), current_timestamp());
+        assert_eq!(
+            status.unwrap().status,
+            crate::browser_integration::ConnectionStatus::Connected
+        );
+    }
+
+    #[test]
+    fn test_handle_frame_writes_to_a_just_moved_database() {
+        let (host, dir) = host();
+        // The heartbeat isn't due again for a while after this one
+        let bytes = frame(br#"{"type":"heartbeat"}"#);
+        assert!(host.handle_frame(&mut bytes.as_slice()).unwrap().is_none());
+
+        let folder = tempfile::tempdir().unwrap();
+        let mut app_db = Database::open(safe_lock(&host.db, "Database").path()).unwrap();
+        let new_path = location::relocate(&mut app_db, dir.path(), folder.path()).unwrap();
+
+        let bytes =
+            frame(br#"{"type":"activity","url":"https://a.com","title":"A","timestamp":1}"#);
+        assert!(host.handle_frame(&mut bytes.as_slice()).unwrap().is_none());
+
+        assert_eq!(safe_lock(&host.db, "Database").path(), new_path);
+        let recorded = Activity::find_in_range(app_db.connection(), 0, i64::MAX).unwrap();
+        assert_eq!(recorded.len(), 1);
+    }
+
+    #[test]
+    fn test_handle_frame_rejects_external_activity_while_away() {
+        let (host, _dir) = host();
+        AwayPeriod::start(
+            safe_lock(&host.db, "Database").connection(),
+            "2000-01-01",
+            "2999-01-01",
+        )
+        .unwrap();
+        let bytes = frame(
+            br#"{"type":"external_activity","source":"terminal","name":"vim","duration_secs":60}"#,
+        );
+        assert_eq!(
+            error_code(host.handle_frame(&mut bytes.as_slice()).unwrap()),
+            Some(ErrorCode::Rejected)
+        );
+    }
+
+    #[test]
+    fn test_handle_frame_records_known_browsers_only() {
+        let (host, _dir) = host();
+        let mut bytes = frame(
+            br#"{"type":"activity","url":"https://a.com","title":"A","timestamp":1,"browser":"Firefox"}"#,
+        );
+        bytes.extend(frame(
+            br#"{"type":"activity","url":"https://b.com","title":"B","timestamp":1,"browser":"netscape"}"#,
+        ));
+        let mut input = bytes.as_slice();
+        assert!(host.handle_frame(&mut input).unwrap().is_none());
+        assert!(host.handle_frame(&mut input).unwrap().is_none());
+
+        let db = safe_lock(&host.db, "Database");
+        let browsers: Vec<_> = Activity::find_in_range(db.connection(), 0, i64::MAX)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.browser)
+            .collect();
+        assert_eq!(browsers, vec![Some("firefox".to_string()), None]);
+    }
+
+    #[test]
+    fn test_encode_message() {
+        let frame = encode_message(&OutgoingMessage::HardBlocked).unwrap();
+        let json = br#"{"type":"hard_blocked"}"#;
+        assert_eq!(
+            frame.get(..4).unwrap(),
+            &u32::try_from(json.len()).unwrap().to_le_bytes()
+        );
+        assert_eq!(frame.get(4..).unwrap(), json);
+
+        let oversized = OutgoingMessage::Error {
+            code: ErrorCode::Rejected,
+            message: "x".repeat(MAX_MESSAGE_SIZE),
+        };
+        assert_eq!(
+            encode_message(&oversized).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_error_message_format() {
+        let message = OutgoingMessage::from(FrameError::InvalidUtf8);
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "type": "error",
+                "code": "invalid_utf8",
+                "message": "Message is not valid UTF-8"
+            })
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_read_message_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut input = bytes.as_slice();
+            while read_message(&mut input).is_ok() {}
+        }
+
+        #[test]
+        fn prop_any_body_leaves_stream_in_step(body in prop::collection::vec(any::<u8>(), 0..1024)) {
+            let mut bytes = frame(&body);
+            bytes.extend(frame(br#"{"type":"request_state"}"#));
+            let mut input = bytes.as_slice();
+
+            prop_assert!(read_message(&mut input).is_ok());
+            prop_assert!(matches!(
+                read_message(&mut input),
+                Ok(Ok(IncomingMessage::RequestState))
+            ));
+        }
+
+        #[test]
+        fn prop_truncated_frames_are_eof(
+            body in prop::collection::vec(any::<u8>(), 1..256),
+            cut in any::<prop::sample::Index>(),
+        ) {
+            let bytes = frame(&body);
+            let mut truncated = bytes.get(..cut.index(bytes.len())).unwrap();
+            let result = read_message(&mut truncated);
+            prop_assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        }
+
+        #[test]
+        fn prop_oversized_claims_are_rejected(extra in 1usize..4096) {
+            let claimed = MAX_MESSAGE_SIZE + extra;
+            let mut bytes = u32::try_from(claimed).unwrap().to_le_bytes().to_vec();
+            bytes.resize(4 + claimed, 0);
+            let result = read_message(&mut bytes.as_slice()).unwrap();
+            prop_assert_eq!(result.unwrap_err(), FrameError::TooLarge(claimed));
+
+            // Claims the stream can't back up end it instead
+            let mut short = bytes.get(..4 + extra).unwrap();
+            let result = read_message(&mut short);
+            prop_assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        }
+
+        #[test]
+        fn prop_unknown_types_are_parsed_as_unknown(message_type in "[a-z_]{1,24}") {
+            prop_assume!(!KNOWN_TYPES.contains(&message_type.as_str()));
+            let body = serde_json::json!({ "type": message_type }).to_string();
+            prop_assert!(matches!(
+                read_message(&mut frame(body.as_bytes()).as_slice()),
+                Ok(Ok(IncomingMessage::Unknown))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_use_distraction_time_domain_is_optional() {
+        let legacy: IncomingMessage =
+            serde_json::from_str(r#"{"type":"use_distraction_time"}"#).unwrap();
+        assert!(matches!(
+            legacy,
+            IncomingMessage::UseDistractionTime {
+                domain: None,
+                profile: None,
+                secs: None
+            }
+        ));
+
+        let with_domain: IncomingMessage =
+            serde_json::from_str(r#"{"type":"use_distraction_time","domain":"youtube.com"}"#)
+                .unwrap();
+        assert!(matches!(
+            with_domain,
+            IncomingMessage::UseDistractionTime { domain: Some(d), .. } if d == "youtube.com"
+        ));
+    }
+
+    #[test]
+    fn test_activity_profile_is_optional() {
+        let legacy: IncomingMessage = serde_json::from_str(
+            r#"{"type":"activity","url":"https://a.com","title":"A","timestamp":1}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            legacy,
+            IncomingMessage::Activity { profile: None, .. }
+        ));
+
+        let with_profile: IncomingMessage = serde_json::from_str(
+            r#"{"type":"activity","url":"https://a.com","title":"A","timestamp":1,"profile":"Work"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            with_profile,
+            IncomingMessage::Activity { profile: Some(p), .. } if p == "Work"
+        ));
+    }
+
+    #[test]
+    fn test_blocked_attempt_message() {
+        let message: IncomingMessage =
+            serde_json::from_str(r#"{"type":"blocked_attempt","url":"https://x.com/home"}"#)
+                .unwrap();
+        assert!(matches!(
+            message,
+            IncomingMessage::BlockedAttempt { url } if url == "https://x.com/home"
+        ));
+    }
+
+    #[test]
+    fn test_blocklist_update_message_format() {
+        let message = OutgoingMessage::from(BlocklistUpdate {
+            from_version: 3,
+            version: 4,
+            added: vec!["reddit.com".into()],
+            removed: vec![],
+            allowed_domains: None,
+        });
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "type": "blocklist_updated",
+                "fromVersion": 3,
+                "version": 4,
+                "added": ["reddit.com"],
+                "removed": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_external_activity_message() {
+        let message: IncomingMessage = serde_json::from_str(
+            r#"{"type":"external_activity","source":"terminal","name":"vim","duration_secs":300}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            message,
+            IncomingMessage::ExternalActivity(ExternalActivity { source, duration_secs: 300, .. })
+                if source == "terminal"
+        ));
+
+        let message: IncomingMessage = serde_json::from_str(
+            r#"{"type":"external_activity","source":"editor","name":"vscode","duration_secs":60,"project":"foxus","language":"rust"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            message,
+            IncomingMessage::ExternalActivity(ExternalActivity { project: Some(project), .. })
+                if project == "foxus"
+        ));
+    }
+
+    #[test]
+    fn test_normalize_profile() {
+        assert_eq!(
+            normalize_profile(Some(" Work ".into())),
+            Some("Work".into())
+        );
+        assert_eq!(normalize_profile(Some("  ".into())), None);
+        assert_eq!(normalize_profile(None), None);
+        assert_eq!(
+            normalize_profile(Some("x".repeat(100))).map(|p| p.len()),
+            Some(MAX_PROFILE_LEN)
+        );
+        // Byte 64 falls inside a two-byte character, so the cut is at 63
+        assert_eq!(
+            normalize_profile(Some(format!("x{}", "é".repeat(40)))),
+            Some(format!("x{}", "é".repeat(31)))
+        );
+    }
 
     #[test]
     fn test_extract_domain() {
@@ -0,0 +1,146 @@
+//! Desktop notifications and the quiet hours that hold them back.
+//!
+//! Quiet hours are a list of weekly time ranges. While one is in effect, break
+//! reminders and the daily summary wait until it's over and other
+//! notifications are dropped; in-app events are still emitted.
+
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::Setting;
+use chrono::{Datelike, Local};
+use log::{error, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const QUIET_HOURS_SETTING: &str = "notifications.quiet_hours";
+
+/// A weekly window, e.g. quiet hours for meetings or evenings. Tracking
+/// hours use the same ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietRange {
+    /// Comma-separated day numbers (1=Monday, 7=Sunday) the range starts on.
+    pub days_of_week: String,
+    /// "HH:MM" start, inclusive.
+    pub start_time: String,
+    /// "HH:MM" end, exclusive. An end before the start wraps past midnight
+    /// into the next day.
+    pub end_time: String,
+}
+
+impl QuietRange {
+    fn starts_on(&self, day: u32) -> bool {
+        self.days_of_week
+            .split(',')
+            .filter_map(|s| s.trim().parse::<u32>().ok())
+            .any(|d| d == day)
+    }
+
+    /// Whether `time` (HH:MM) on `day` (1=Monday, 7=Sunday) is inside the range.
+    pub fn contains(&self, day: u32, time: &str) -> bool {
+        let (start, end) = (self.start_time.as_str(), self.end_time.as_str());
+        if start <= end {
+            return self.starts_on(day) && start <= time && time < end;
+        }
+        // Wraps past midnight: the early hours belong to the previous day's range
+        let previous_day = if day == 1 { 7 } else { day - 1 };
+        (self.starts_on(day) && time >= start) || (self.starts_on(previous_day) && time < end)
+    }
+}
+
+pub fn quiet_hours(conn: &Connection) -> rusqlite::Result<Vec<QuietRange>> {
+    Ok(Setting::get(conn, QUIET_HOURS_SETTING)?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+pub fn set_quiet_hours(conn: &Connection, ranges: &[QuietRange]) -> Result<(), AppError> {
+    if ranges.is_empty() {
+        Setting::delete(conn, QUIET_HOURS_SETTING)?;
+        return Ok(());
+    }
+    let value = serde_json::to_string(ranges).map_err(|e| AppError::Internal(e.to_string()))?;
+    Setting::set(conn, QUIET_HOURS_SETTING, &value)?;
+    Ok(())
+}
+
+pub fn is_quiet(ranges: &[QuietRange], day: u32, time: &str) -> bool {
+    ranges.iter().any(|range| range.contains(day, time))
+}
+
+/// Whether quiet hours are in effect right now, in local time.
+pub fn is_quiet_now(app: &AppHandle) -> bool {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return false;
+    };
+    match with_connection(&db, quiet_hours) {
+        Ok(ranges) => {
+            let now = Local::now();
+            is_quiet(
+                &ranges,
+                now.weekday().number_from_monday(),
+                &now.format("%H:%M").to_string(),
+            )
+        }
+        Err(e) => {
+            error!("Failed to load quiet hours: {e}");
+            false
+        }
+    }
+}
+
+/// Show a desktop notification unless quiet hours are in effect.
+pub fn notify(app: &AppHandle, title: &str, body: &str) {
+    if is_quiet_now(app) {
+        return;
+    }
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        warn!("Failed to show notification \"{title}\": {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn range(days: &str, start: &str, end: &str) -> QuietRange {
+        QuietRange {
+            days_of_week: days.into(),
+            start_time: start.into(),
+            end_time: end.into(),
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let standup = range("1,2,3,4,5", "09:30", "10:00");
+        assert!(standup.contains(2, "09:45"));
+        assert!(!standup.contains(2, "10:00"));
+        assert!(!standup.contains(6, "09:45"));
+
+        let evenings = range("5", "22:00", "07:00");
+        assert!(evenings.contains(5, "23:30"));
+        assert!(evenings.contains(6, "06:59"));
+        assert!(!evenings.contains(5, "06:59"));
+        assert!(range("7", "22:00", "07:00").contains(1, "01:00"));
+    }
+
+    #[test]
+    fn test_quiet_hours_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(quiet_hours(conn).unwrap().is_empty());
+
+        let ranges = vec![range("1,2,3,4,5", "18:00", "08:00")];
+        set_quiet_hours(conn, &ranges).unwrap();
+        assert_eq!(quiet_hours(conn).unwrap(), ranges);
+        assert!(is_quiet(&ranges, 3, "19:00"));
+        assert!(!is_quiet(&ranges, 3, "12:00"));
+
+        set_quiet_hours(conn, &[]).unwrap();
+        assert!(quiet_hours(conn).unwrap().is_empty());
+    }
+}
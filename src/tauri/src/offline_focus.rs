@@ -0,0 +1,142 @@
+//! Focus time logged by hand for deep work away from the computer, such as
+//! reading or whiteboarding, so stats reflect real work and not just screen
+//! time.
+
+use crate::constants::MAX_OFFLINE_FOCUS_SECS;
+use crate::error::AppError;
+use crate::local_time::local_day;
+use crate::models::{Activity, Category, FocusSession, Note};
+use crate::validation::validate_note_text;
+use rusqlite::{params, Connection};
+
+/// `activities.source` of logged offline blocks.
+pub const OFFLINE_SOURCE: &str = "offline";
+
+/// Productive category offline blocks are filed under, created on first use.
+const OFFLINE_CATEGORY: &str = "Offline Focus";
+
+/// Record `[start, end)` as a completed focus session with a productive
+/// activity block covering it. A note, if given, is pinned to the block.
+pub fn log(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+    note: Option<&str>,
+    now: i64,
+) -> Result<FocusSession, AppError> {
+    let duration_secs = i32::try_from(end - start)
+        .ok()
+        .filter(|secs| (1..=MAX_OFFLINE_FOCUS_SECS).contains(secs))
+        .ok_or_else(|| AppError::InvalidInput {
+            field: "end",
+            reason: format!(
+                "must be after start and at most {} hours later",
+                MAX_OFFLINE_FOCUS_SECS / 3600
+            ),
+        })?;
+    if end > now {
+        return Err(AppError::InvalidInput {
+            field: "end",
+            reason: "cannot be in the future".into(),
+        });
+    }
+    let note = note
+        .filter(|n| !n.trim().is_empty())
+        .map(validate_note_text)
+        .transpose()?;
+    if overlaps_session(conn, start, end, now)? {
+        return Err(AppError::InvalidInput {
+            field: "start",
+            reason: "overlaps another focus session".into(),
+        });
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let mut activity = Activity::new(
+        start,
+        duration_secs,
+        OFFLINE_SOURCE,
+        Some(OFFLINE_CATEGORY),
+        note,
+    );
+    activity.category_id = Some(offline_category(&tx)?);
+    activity.save(&tx)?;
+    if let Some(text) = note {
+        Note::create(&tx, &local_day(start), activity.id, text, now)?;
+    }
+
+    let mut session = FocusSession::new(0, false);
+    session.started_at = start;
+    session.planned_duration = Some(duration_secs);
+    session.focused_secs = duration_secs;
+    session.save(&tx)?;
+    session.end_at(&tx, end)?;
+    tx.commit()?;
+    Ok(session)
+}
+
+/// Whether `[start, end)` overlaps a recorded session; running ones count
+/// up to `now`.
+fn overlaps_session(conn: &Connection, start: i64, end: i64, now: i64) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM focus_sessions
+         WHERE started_at < ?2 AND COALESCE(ended_at, ?3) > ?1",
+        params![start, end, now],
+        |row| row.get(0),
+    )
+}
+
+fn offline_category(conn: &Connection) -> rusqlite::Result<i64> {
+    let existing = Category::find_all(conn)?
+        .into_iter()
+        .find(|c| c.name == OFFLINE_CATEGORY);
+    match existing {
+        Some(category) => Ok(category.id),
+        None => Ok(Category::create(conn, OFFLINE_CATEGORY, 1)?.id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn test_log_creates_session_and_productive_block() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let session = log(conn, NOW - 3600, NOW - 1800, Some("Reading papers"), NOW).unwrap();
+        assert_eq!(session.ended_at, Some(NOW - 1800));
+        assert_eq!(session.focused_secs, 1800);
+        assert!(FocusSession::find_active(conn).unwrap().is_none());
+
+        let (productive, _, _) = Activity::productivity_totals(conn, NOW - 3600, NOW).unwrap();
+        assert_eq!(productive, 1800);
+        let day = local_day(NOW - 3600);
+        let notes = Note::find_in_days(conn, &day, &day).unwrap();
+        assert_eq!(notes.first().unwrap().text, "Reading papers");
+
+        // The same time can't be logged twice, and the category is reused
+        assert!(log(conn, NOW - 2000, NOW - 1000, None, NOW).is_err());
+        log(conn, NOW - 1000, NOW - 500, None, NOW).unwrap();
+        let offline = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .filter(|c| c.name == OFFLINE_CATEGORY)
+            .count();
+        assert_eq!(offline, 1);
+    }
+
+    #[test]
+    fn test_log_rejects_bad_ranges() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(log(conn, NOW - 100, NOW - 200, None, NOW).is_err());
+        assert!(log(conn, NOW - 100, NOW + 100, None, NOW).is_err());
+        let too_long = i64::from(MAX_OFFLINE_FOCUS_SECS) + 1;
+        assert!(log(conn, NOW - too_long, NOW, None, NOW).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::models::focus_session::current_timestamp;
+use crate::models::Setting;
+use rusqlite::Connection;
+
+/// Settings key prefix under which completed onboarding steps are stored.
+const STEP_KEY_PREFIX: &str = "onboarding.";
+
+/// A step of the first-run guided setup, in the order the UI presents them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingStep {
+    PermissionsGranted,
+    ExtensionInstalled,
+    FirstRuleCreated,
+    FirstFocusSession,
+}
+
+impl OnboardingStep {
+    /// All steps in presentation order.
+    pub const ALL: [Self; 4] = [
+        Self::PermissionsGranted,
+        Self::ExtensionInstalled,
+        Self::FirstRuleCreated,
+        Self::FirstFocusSession,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PermissionsGranted => "permissions_granted",
+            Self::ExtensionInstalled => "extension_installed",
+            Self::FirstRuleCreated => "first_rule_created",
+            Self::FirstFocusSession => "first_focus_session",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|step| step.as_str() == s)
+    }
+
+    fn setting_key(self) -> String {
+        format!("{STEP_KEY_PREFIX}{}", self.as_str())
+    }
+}
+
+/// Progress through the onboarding flow, derived from the settings table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnboardingState {
+    /// Completed steps in presentation order, with the Unix timestamp of completion.
+    pub completed: Vec<(OnboardingStep, i64)>,
+}
+
+impl OnboardingState {
+    pub fn load(conn: &Connection) -> rusqlite::Result<Self> {
+        let stored = Setting::find_by_prefix(conn, STEP_KEY_PREFIX)?;
+
+        let completed = OnboardingStep::ALL
+            .into_iter()
+            .filter_map(|step| {
+                let key = step.setting_key();
+                stored
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .and_then(|(_, v)| v.parse::<i64>().ok())
+                    .map(|completed_at| (step, completed_at))
+            })
+            .collect();
+
+        Ok(Self { completed })
+    }
+
+    /// The first step, in presentation order, that has not been completed yet.
+    pub fn next_step(&self) -> Option<OnboardingStep> {
+        OnboardingStep::ALL
+            .into_iter()
+            .find(|step| !self.completed.iter().any(|(done, _)| done == step))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_step().is_none()
+    }
+}
+
+/// Mark an onboarding step as completed and return the updated state.
+///
+/// Steps may be completed in any order so users can skip ahead. Completing a
+/// step twice keeps the original completion time.
+pub fn complete_step(conn: &Connection, step: OnboardingStep) -> rusqlite::Result<OnboardingState> {
+    let key = step.setting_key();
+    if Setting::get(conn, &key)?.is_none() {
+        Setting::set(conn, &key, &current_timestamp().to_string())?;
+    }
+    OnboardingState::load(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_fresh_install_starts_at_first_step() {
+        let (db, _dir) = setup_test_db();
+        let state = OnboardingState::load(db.connection()).unwrap();
+
+        assert!(state.completed.is_empty());
+        assert_eq!(state.next_step(), Some(OnboardingStep::PermissionsGranted));
+        assert!(!state.is_finished());
+    }
+
+    #[test]
+    fn test_complete_step_advances_next_step() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let state = complete_step(conn, OnboardingStep::PermissionsGranted).unwrap();
+        assert_eq!(state.next_step(), Some(OnboardingStep::ExtensionInstalled));
+    }
+
+    #[test]
+    fn test_skipped_step_remains_next() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let state = complete_step(conn, OnboardingStep::FirstRuleCreated).unwrap();
+        assert_eq!(state.next_step(), Some(OnboardingStep::PermissionsGranted));
+        assert_eq!(state.completed.len(), 1);
+    }
+
+    #[test]
+    fn test_completing_all_steps_finishes() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        for step in OnboardingStep::ALL {
+            complete_step(conn, step).unwrap();
+        }
+
+        let state = OnboardingState::load(conn).unwrap();
+        assert!(state.is_finished());
+        assert_eq!(state.completed.len(), OnboardingStep::ALL.len());
+    }
+
+    #[test]
+    fn test_complete_step_twice_keeps_original_timestamp() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        Setting::set(conn, "onboarding.extension_installed", "1000").unwrap();
+        let state = complete_step(conn, OnboardingStep::ExtensionInstalled).unwrap();
+
+        assert_eq!(
+            state.completed,
+            vec![(OnboardingStep::ExtensionInstalled, 1000)]
+        );
+    }
+
+    #[test]
+    fn test_step_roundtrip() {
+        for step in OnboardingStep::ALL {
+            assert_eq!(OnboardingStep::from_str(step.as_str()), Some(step));
+        }
+        assert_eq!(OnboardingStep::from_str("unknown"), None);
+    }
+}
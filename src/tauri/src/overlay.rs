@@ -0,0 +1,217 @@
+//! Focus timer for streaming overlays: `overlay.json` for browser sources
+//! and `overlay.txt` for OBS text sources, written next to the database
+//! every few seconds while enabled.
+
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::get_data_dir;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, FocusSession, Setting};
+use crate::tray::format_duration;
+use log::error;
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const ENABLED_SETTING: &str = "overlay.enabled";
+
+const JSON_FILE_NAME: &str = "overlay.json";
+const TEXT_FILE_NAME: &str = "overlay.txt";
+
+/// Often enough for a smooth countdown without busy-writing the disk.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Contents of `overlay.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OverlayState {
+    pub focus_active: bool,
+    /// Seconds left in a timed session.
+    pub remaining_secs: Option<i64>,
+    /// Seconds since the session started.
+    pub elapsed_secs: Option<i64>,
+    /// `remaining_secs` (or `elapsed_secs` for open-ended sessions) as `M:SS` or `H:MM:SS`.
+    pub timer: Option<String>,
+    pub productive_secs_today: i32,
+    pub productive_today: String,
+}
+
+/// Whether the overlay files are kept up to date. Off by default.
+pub fn output_enabled(conn: &Connection) -> Result<bool> {
+    Ok(Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"))
+}
+
+pub fn set_output_enabled(conn: &Connection, enabled: bool) -> Result<()> {
+    Setting::set(conn, ENABLED_SETTING, &enabled.to_string())
+}
+
+/// Where `overlay.json` is written, next to the database. `overlay.txt`
+/// sits beside it.
+pub fn overlay_path() -> Result<PathBuf, AppError> {
+    let data_dir = get_data_dir().map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(data_dir.join(JSON_FILE_NAME))
+}
+
+fn format_timer(secs: i64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+impl OverlayState {
+    pub fn current(conn: &Connection, today_start: i64, now: i64) -> Result<Self> {
+        let session = FocusSession::find_active(conn)?;
+        let remaining_secs = session.as_ref().and_then(|s| s.time_remaining(now));
+        let elapsed_secs = session.as_ref().map(|s| (now - s.started_at).max(0));
+        let (productive_secs_today, _, _) =
+            Activity::productivity_totals(conn, today_start, now + 1)?;
+        Ok(Self {
+            focus_active: session.is_some(),
+            remaining_secs,
+            elapsed_secs,
+            timer: remaining_secs.or(elapsed_secs).map(format_timer),
+            productive_secs_today,
+            productive_today: format_duration(productive_secs_today),
+        })
+    }
+
+    /// One line for a text source, e.g. `Focus 24:13 | 3h 12m productive today`.
+    pub fn to_text(&self) -> String {
+        let productive = format!("{} productive today", self.productive_today);
+        match &self.timer {
+            Some(timer) => format!("Focus {timer} | {productive}"),
+            None => productive,
+        }
+    }
+}
+
+/// Replace `path` with `contents` if they differ, via a temporary file so
+/// overlays never read a half-written one.
+fn replace_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if std::fs::read_to_string(path).ok().as_deref() == Some(contents) {
+        return Ok(());
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents).and_then(|()| std::fs::rename(&tmp, path))
+}
+
+/// Write both overlay files for `now` into `path`'s folder.
+pub fn write_overlay(
+    conn: &Connection,
+    path: &Path,
+    today_start: i64,
+    now: i64,
+) -> Result<(), AppError> {
+    let state = OverlayState::current(conn, today_start, now)?;
+    let json = serde_json::to_string(&state).map_err(|e| AppError::Internal(e.to_string()))?;
+    replace_file(path, &json)
+        .and_then(|()| replace_file(&path.with_file_name(TEXT_FILE_NAME), &state.to_text()))
+        .map_err(|e| AppError::Internal(format!("failed to write overlay: {e}")))
+}
+
+/// Delete both overlay files, ignoring ones already gone.
+pub fn remove_overlay(path: &Path) -> Result<(), AppError> {
+    for file in [path.to_path_buf(), path.with_file_name(TEXT_FILE_NAME)] {
+        if file.exists() {
+            std::fs::remove_file(&file)
+                .map_err(|e| AppError::Internal(format!("failed to remove overlay: {e}")))?;
+        }
+    }
+    Ok(())
+}
+
+fn refresh_overlay(app: &AppHandle, path: &Path) -> Result<(), AppError> {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return Ok(());
+    };
+    if !with_connection(&db, output_enabled)? {
+        return Ok(());
+    }
+    let (_, _, today_start) = local_today();
+    let db = db.lock().map_err(|_| AppError::LockPoisoned)?;
+    write_overlay(db.connection(), path, today_start, current_timestamp())
+}
+
+/// Keep the overlay files current while enabled. Runs on its own thread
+/// because the scheduler only ticks once a minute.
+pub fn start_overlay_writer(app: &AppHandle) {
+    let path = match overlay_path() {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to locate overlay file: {e}");
+            return;
+        }
+    };
+    let app = app.clone();
+    thread::spawn(move || loop {
+        if let Err(e) = refresh_overlay(&app, &path) {
+            error!("Failed to update overlay: {e}");
+        }
+        thread::sleep(REFRESH_INTERVAL);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn test_format_timer() {
+        assert_eq!(format_timer(0), "0:00");
+        assert_eq!(format_timer(24 * 60 + 13), "24:13");
+        assert_eq!(format_timer(3600 + 5 * 60 + 9), "1:05:09");
+    }
+
+    #[test]
+    fn test_overlay_tracks_session_and_productive_time() {
+        let (db, dir) = setup_test_db();
+        let conn = db.connection();
+        let today_start = NOW - 6 * 3600;
+
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        let mut activity = Activity::new(today_start + 60, 5400, "app", Some("Code"), None);
+        activity.category_id = Some(coding.id);
+        activity.save(conn).unwrap();
+
+        let idle = OverlayState::current(conn, today_start, NOW).unwrap();
+        assert!(!idle.focus_active);
+        assert_eq!(idle.timer, None);
+        assert_eq!(idle.to_text(), "1h 30m productive today");
+
+        let mut session = FocusSession::new(600, false);
+        session.started_at = NOW - 300;
+        session.planned_duration = Some(1500);
+        session.save(conn).unwrap();
+
+        let path = dir.path().join(JSON_FILE_NAME);
+        write_overlay(conn, &path, today_start, NOW).unwrap();
+        let state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let field = |name: &str| state.get(name).cloned().unwrap_or_default();
+        assert_eq!(field("focus_active"), true);
+        assert_eq!(field("remaining_secs"), 1200);
+        assert_eq!(field("productive_secs_today"), 5400);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(TEXT_FILE_NAME)).unwrap(),
+            "Focus 20:00 | 1h 30m productive today"
+        );
+
+        remove_overlay(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
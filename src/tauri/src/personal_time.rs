@@ -0,0 +1,90 @@
+//! A second categorization profile for personal time. Outside the work
+//! hours, the rules of one chosen rule group ("Evenings", say) are tried
+//! before all others, and during work hours they're skipped. Putting
+//! youtube.com in a neutral category there keeps evening videos from
+//! counting as distraction, while they still do at work.
+
+use crate::error::AppError;
+use crate::models::Setting;
+use crate::notifications::QuietRange;
+use rusqlite::Connection;
+
+const GROUP_SETTING: &str = "personal_time.group_id";
+const WORK_HOURS_SETTING: &str = "personal_time.work_hours";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersonalTime {
+    /// The rule group holding the personal-time rules; `None` when off.
+    pub group_id: Option<i64>,
+    /// Weekly ranges counted as work time; everything else is personal.
+    pub work_hours: Vec<QuietRange>,
+}
+
+impl PersonalTime {
+    pub fn load(conn: &Connection) -> rusqlite::Result<Self> {
+        Ok(Self {
+            group_id: Setting::get(conn, GROUP_SETTING)?.and_then(|v| v.parse().ok()),
+            work_hours: Setting::get(conn, WORK_HOURS_SETTING)?
+                .and_then(|v| serde_json::from_str(&v).ok())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), AppError> {
+        match self.group_id {
+            Some(id) => Setting::set(conn, GROUP_SETTING, &id.to_string())?,
+            None => Setting::delete(conn, GROUP_SETTING)?,
+        }
+        if self.work_hours.is_empty() {
+            Setting::delete(conn, WORK_HOURS_SETTING)?;
+        } else {
+            let value = serde_json::to_string(&self.work_hours)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            Setting::set(conn, WORK_HOURS_SETTING, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a rule belongs to the personal-time profile.
+    pub fn includes(&self, group_id: Option<i64>) -> bool {
+        self.group_id.is_some() && group_id == self.group_id
+    }
+
+    /// Whether `time` (HH:MM) on `day` (1=Monday, 7=Sunday) is outside the
+    /// work hours. Without work hours there's no personal time.
+    pub fn is_personal_at(&self, day: u32, time: &str) -> bool {
+        !self.work_hours.is_empty() && !self.work_hours.iter().any(|r| r.contains(day, time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_round_trip_and_personal_hours() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert_eq!(PersonalTime::load(conn).unwrap(), PersonalTime::default());
+        assert!(!PersonalTime::default().is_personal_at(3, "21:00"));
+
+        let personal = PersonalTime {
+            group_id: Some(4),
+            work_hours: vec![QuietRange {
+                days_of_week: "1,2,3,4,5".into(),
+                start_time: "09:00".into(),
+                end_time: "18:00".into(),
+            }],
+        };
+        personal.save(conn).unwrap();
+        assert_eq!(PersonalTime::load(conn).unwrap(), personal);
+        assert!(!personal.is_personal_at(3, "10:00"));
+        assert!(personal.is_personal_at(3, "21:00"));
+        assert!(personal.is_personal_at(6, "10:00"));
+        assert!(personal.includes(Some(4)) && !personal.includes(None));
+
+        PersonalTime::default().save(conn).unwrap();
+        assert_eq!(PersonalTime::load(conn).unwrap(), PersonalTime::default());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ActiveWindow, PlatformTracker};
+use super::{ActiveWindow, PlatformTracker, VisibleWindow};
 use x11rb::connection::Connection;
 use x11rb::protocol::screensaver;
 use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, Window};
@@ -107,44 +107,121 @@ impl LinuxTracker {
         let bytes: [u8; 4] = reply.value.get(..4)?.try_into().ok()?;
         Some(u32::from_ne_bytes(bytes))
     }
-}
 
-impl PlatformTracker for LinuxTracker {
-    fn get_active_window(&self) -> Option<ActiveWindow> {
-        let window_id = self.get_active_window_id()?;
+    /// A 32-bit list property (window lists, atom lists, desktop numbers).
+    fn get_u32_list(&self, window: Window, name: &str) -> Vec<u32> {
+        let Some(conn) = self.conn.as_ref() else {
+            return Vec::new();
+        };
+        let Some(atom) = self.get_atom(name) else {
+            return Vec::new();
+        };
+        conn.get_property(false, window, atom, AtomEnum::ANY, 0, 1024)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(Iterator::collect))
+            .unwrap_or_default()
+    }
 
+    /// Idle state from the MIT-SCREEN-SAVER extension, if the server has it.
+    fn idle_info(&self) -> Option<screensaver::QueryInfoReply> {
+        screensaver::query_info(self.conn.as_ref()?, self.root)
+            .ok()?
+            .reply()
+            .ok()
+    }
+
+    /// (`app_name`, `window_title`) from `WM_CLASS` and `_NET_WM_NAME`.
+    fn window_names(&self, window: Window) -> (String, String) {
         let name_atom = self
             .get_atom("_NET_WM_NAME")
-            .or_else(|| Some(AtomEnum::WM_NAME.into()))?;
+            .unwrap_or_else(|| AtomEnum::WM_NAME.into());
 
         let window_title = self
-            .get_window_property(window_id, name_atom)
+            .get_window_property(window, name_atom)
             .unwrap_or_else(|| "Unknown".to_string());
 
-        let class_atom = AtomEnum::WM_CLASS.into();
         let app_name = self
-            .get_window_property(window_id, class_atom)
+            .get_window_property(window, AtomEnum::WM_CLASS.into())
             .and_then(|s| s.split('\0').next().map(ToString::to_string))
             .unwrap_or_else(|| "Unknown".to_string());
 
+        (app_name, window_title)
+    }
+
+    /// Whether a managed window is showing: not minimized, and on the current
+    /// desktop or sticky across all of them.
+    fn is_shown(&self, window: Window, hidden_atom: Option<u32>, desktop: Option<u32>) -> bool {
+        let hidden = hidden_atom
+            .is_some_and(|hidden| self.get_u32_list(window, "_NET_WM_STATE").contains(&hidden));
+        let on_desktop = match (
+            desktop,
+            self.get_u32_list(window, "_NET_WM_DESKTOP").first(),
+        ) {
+            (Some(current), Some(&window_desktop)) => {
+                window_desktop == current || window_desktop == ALL_DESKTOPS
+            }
+            _ => true,
+        };
+        !hidden && on_desktop
+    }
+}
+
+/// `_NET_WM_DESKTOP` value for windows shown on every desktop.
+const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
+impl PlatformTracker for LinuxTracker {
+    fn get_active_window(&self) -> Option<ActiveWindow> {
+        let window_id = self.get_active_window_id()?;
+        let (app_name, window_title) = self.window_names(window_id);
+
         Some(ActiveWindow {
             app_name,
             window_title,
             bundle_id: None,
+            visible: Vec::new(),
         })
     }
 
+    fn get_visible_windows(&self) -> Vec<VisibleWindow> {
+        let active = self.get_active_window_id();
+        let hidden_atom = self.get_atom("_NET_WM_STATE_HIDDEN");
+        let desktop = self
+            .get_u32_list(self.root, "_NET_CURRENT_DESKTOP")
+            .first()
+            .copied();
+
+        // The stacking list runs bottom to top
+        self.get_u32_list(self.root, "_NET_CLIENT_LIST_STACKING")
+            .into_iter()
+            .rev()
+            .filter(|window| Some(*window) != active)
+            .filter(|window| self.is_shown(*window, hidden_atom, desktop))
+            .map(|window| {
+                let (app_name, window_title) = self.window_names(window);
+                VisibleWindow {
+                    app_name,
+                    window_title,
+                }
+            })
+            .collect()
+    }
+
     fn get_idle_time_secs(&self) -> u64 {
-        let Some(conn) = self.conn.as_ref() else {
-            return 0;
-        };
+        self.idle_info()
+            .map_or(0, |i| u64::from(i.ms_since_user_input / 1000))
+    }
 
-        let info = screensaver::query_info(conn, self.root)
-            .ok()
-            .and_then(|cookie| cookie.reply().ok());
+    fn backend(&self) -> &'static str {
+        if self.conn.is_some() {
+            "x11"
+        } else {
+            "unavailable"
+        }
+    }
 
-        info.map(|i| u64::from(i.ms_since_user_input / 1000))
-            .unwrap_or(0)
+    fn idle_detection_available(&self) -> bool {
+        self.idle_info().is_some()
     }
 }
 
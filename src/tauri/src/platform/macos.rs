@@ -74,6 +74,7 @@ impl PlatformTracker for MacOSTracker {
                 app_name,
                 window_title,
                 bundle_id,
+                visible: Vec::new(),
             })
         }
     }
@@ -81,6 +82,10 @@ impl PlatformTracker for MacOSTracker {
     fn get_idle_time_secs(&self) -> u64 {
         get_idle_time_secs_internal()
     }
+
+    fn backend(&self) -> &'static str {
+        "macos"
+    }
 }
 
 /// Get system idle time using CoreGraphics `CGEventSource` API
@@ -1,6 +1,6 @@
 pub mod types;
 
-pub use types::{ActiveWindow, PlatformTracker};
+pub use types::{ActiveWindow, PlatformTracker, VisibleWindow};
 
 #[cfg(target_os = "macos")]
 pub mod macos;
@@ -25,12 +25,21 @@ impl PlatformTracker for NativeTracker {
             app_name: "TestApp".to_string(),
             window_title: "Test Window".to_string(),
             bundle_id: None,
+            visible: Vec::new(),
         })
     }
 
     fn get_idle_time_secs(&self) -> u64 {
         0
     }
+
+    fn backend(&self) -> &'static str {
+        "stub"
+    }
+
+    fn idle_detection_available(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
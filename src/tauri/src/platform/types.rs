@@ -4,15 +4,38 @@ pub struct ActiveWindow {
     pub app_name: String,
     pub window_title: String,
     /// macOS bundle identifier (e.g., "com.apple.Safari").
-    /// Populated by platform tracker; kept for future rule matching by bundle ID.
-    #[expect(
-        dead_code,
-        reason = "Populated by platform tracker; kept for future rule matching by bundle ID"
-    )]
+    /// Used to look up the app's display name in `app_identities`.
     pub bundle_id: Option<String>,
+    /// Other windows on screen next to this one, topmost first.
+    /// Only filled in when context capture is enabled.
+    pub visible: Vec<VisibleWindow>,
+}
+
+/// A window that is on screen but not focused.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VisibleWindow {
+    pub app_name: String,
+    pub window_title: String,
 }
 
 pub trait PlatformTracker: Send + Sync {
     fn get_active_window(&self) -> Option<ActiveWindow>;
     fn get_idle_time_secs(&self) -> u64;
+
+    /// Short name of the capture backend in use, e.g. `x11`.
+    fn backend(&self) -> &'static str;
+
+    /// Whether `get_idle_time_secs` reflects real input; when it can't, it
+    /// reports 0 and idle time counts as active.
+    fn idle_detection_available(&self) -> bool {
+        true
+    }
+
+    /// Unminimized windows on the current desktop across all monitors,
+    /// topmost first, excluding the active one.
+    ///
+    /// Platforms that can't enumerate windows report none.
+    fn get_visible_windows(&self) -> Vec<VisibleWindow> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,275 @@
+//! Managed policy: a read-only, system-wide `policy.toml` that admins use to
+//! pre-seed categories and rules and to lock settings.
+//!
+//! ```toml
+//! # Used where the user hasn't chosen anything
+//! [defaults.tracking]
+//! capture_context = false
+//!
+//! # Always in effect; the app can't change these
+//! [locked.accountability]
+//! enabled = true
+//!
+//! [[categories]]
+//! name = "Client Work"
+//! productivity = 1
+//!
+//! [[rules]]
+//! pattern = "jira.example.com"
+//! match_type = "domain"
+//! category = "Client Work"
+//! ```
+//!
+//! Settings resolve as: locked policy, then `config.toml`, then what the user
+//! saved, then policy defaults, then built-in defaults. Categories and rules
+//! that don't exist yet are added at startup; after that they are the user's
+//! to edit.
+
+use crate::config_file::flatten_table;
+use crate::models::{Category, MatchType, Rule};
+use crate::validation::validate_productivity;
+use log::{error, info, warn};
+use rusqlite::{Connection, OptionalExtension, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Environment variable pointing at a policy file elsewhere, for testing.
+/// Only debug builds read it, so users can't swap out an admin's locks.
+#[cfg(debug_assertions)]
+pub const POLICY_FILE_ENV: &str = "FOXUS_POLICY_FILE";
+
+#[cfg(target_os = "macos")]
+const SYSTEM_POLICY_PATH: &str = "/Library/Application Support/Foxus/policy.toml";
+#[cfg(not(target_os = "macos"))]
+const SYSTEM_POLICY_PATH: &str = "/etc/foxus/policy.toml";
+
+/// Priority of seeded rules, the same as the built-in ones.
+const SEEDED_RULE_PRIORITY: i32 = 10;
+
+static LOCKED: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+static DEFAULTS: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Policy {
+    #[serde(default)]
+    defaults: toml::Table,
+    #[serde(default)]
+    locked: toml::Table,
+    #[serde(default)]
+    categories: Vec<PolicyCategory>,
+    #[serde(default)]
+    rules: Vec<PolicyRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyCategory {
+    name: String,
+    #[serde(default)]
+    productivity: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyRule {
+    pattern: String,
+    match_type: String,
+    /// Category name; seeded categories can be used.
+    category: String,
+}
+
+/// Where the policy file is looked for.
+pub fn policy_path() -> PathBuf {
+    #[cfg(debug_assertions)]
+    if let Some(path) = std::env::var_os(POLICY_FILE_ENV).filter(|v| !v.is_empty()) {
+        return PathBuf::from(path);
+    }
+    PathBuf::from(SYSTEM_POLICY_PATH)
+}
+
+/// Read the policy file, if there is one, and apply its settings to every
+/// settings lookup. A file that doesn't parse is logged and ignored.
+pub fn load(path: &Path) -> Option<Policy> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let policy = match parse(&text) {
+        Ok(policy) => policy,
+        Err(e) => {
+            error!("Ignoring policy {}: {e}", path.display());
+            return None;
+        }
+    };
+    let (locked, defaults) = match (
+        flatten_table(&policy.locked),
+        flatten_table(&policy.defaults),
+    ) {
+        (Ok(locked), Ok(defaults)) => (locked, defaults),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Ignoring policy {}: {e}", path.display());
+            return None;
+        }
+    };
+    info!(
+        "Loaded policy {} ({} locked, {} default setting(s))",
+        path.display(),
+        locked.len(),
+        defaults.len()
+    );
+    if let Ok(mut current) = LOCKED.write() {
+        *current = locked;
+    }
+    if let Ok(mut current) = DEFAULTS.write() {
+        *current = defaults;
+    }
+    Some(policy)
+}
+
+fn parse(text: &str) -> std::result::Result<Policy, String> {
+    toml::from_str(text).map_err(|e: toml::de::Error| e.to_string())
+}
+
+/// The policy's locked value for a settings key, if it locks it.
+pub fn locked(key: &str) -> Option<String> {
+    LOCKED.read().ok()?.get(key).cloned()
+}
+
+/// Every setting the policy locks, by key.
+pub fn locked_settings() -> BTreeMap<String, String> {
+    LOCKED
+        .read()
+        .map(|locked| locked.clone())
+        .unwrap_or_default()
+}
+
+/// The policy's default for a settings key the user hasn't set.
+pub fn default_for(key: &str) -> Option<String> {
+    DEFAULTS.read().ok()?.get(key).cloned()
+}
+
+impl Policy {
+    /// Add the policy's categories and rules that aren't in the database yet.
+    /// Rules the user deleted stay deleted. Returns how many rows were added.
+    pub fn seed(&self, conn: &Connection) -> Result<usize> {
+        let mut added = 0;
+        for category in &self.categories {
+            if validate_productivity(category.productivity).is_err() {
+                warn!(
+                    "Skipping policy category {}: bad productivity",
+                    category.name
+                );
+                continue;
+            }
+            if category_id(conn, &category.name)?.is_none() {
+                Category::create(conn, &category.name, category.productivity)?;
+                added += 1;
+            }
+        }
+        for rule in &self.rules {
+            let Some(match_type) = MatchType::from_str(&rule.match_type) else {
+                warn!("Skipping policy rule {}: bad match type", rule.pattern);
+                continue;
+            };
+            let Some(category_id) = category_id(conn, &rule.category)? else {
+                warn!(
+                    "Skipping policy rule {}: no category {}",
+                    rule.pattern, rule.category
+                );
+                continue;
+            };
+            if !rule_exists(conn, &rule.pattern, match_type)? {
+                Rule::create(
+                    conn,
+                    &rule.pattern,
+                    match_type,
+                    category_id,
+                    SEEDED_RULE_PRIORITY,
+                )?;
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+}
+
+fn category_id(conn: &Connection, name: &str) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM categories WHERE name = ?1 COLLATE NOCASE",
+        [name],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Whether a rule with this pattern exists, counting ones in the trash.
+fn rule_exists(conn: &Connection, pattern: &str, match_type: MatchType) -> Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM rules WHERE pattern = ?1 AND match_type = ?2",
+        [pattern, match_type.as_str()],
+        |row| row.get(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Setting;
+    use crate::test_utils::setup_test_db;
+    use tempfile::tempdir;
+
+    const POLICY: &str = r#"
+        [defaults.policy_test]
+        default_only = "policy"
+        user_wins = "policy"
+
+        [locked.policy_test]
+        locked = "policy"
+
+        [[categories]]
+        name = "Client Work"
+        productivity = 1
+
+        [[rules]]
+        pattern = "jira.example.com"
+        match_type = "domain"
+        category = "Client Work"
+
+        [[rules]]
+        pattern = "nowhere"
+        match_type = "app"
+        category = "Missing"
+    "#;
+
+    #[test]
+    fn test_settings_resolve_around_user_choices() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        std::fs::write(&path, POLICY).unwrap();
+        load(&path).unwrap();
+
+        Setting::set(conn, "policy_test.user_wins", "user").unwrap();
+        Setting::set(conn, "policy_test.locked", "user").unwrap();
+
+        let get = |key| Setting::get(conn, key).unwrap();
+        assert_eq!(get("policy_test.default_only").as_deref(), Some("policy"));
+        assert_eq!(get("policy_test.user_wins").as_deref(), Some("user"));
+        assert_eq!(get("policy_test.locked").as_deref(), Some("policy"));
+    }
+
+    #[test]
+    fn test_seed_adds_missing_categories_and_rules_once() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let policy = parse(POLICY).unwrap();
+
+        // The rule with an unknown category is skipped
+        assert_eq!(policy.seed(conn).unwrap(), 2);
+        assert_eq!(policy.seed(conn).unwrap(), 0);
+
+        let category = category_id(conn, "Client Work").unwrap().unwrap();
+        let rules = Rule::find_all(conn).unwrap();
+        assert!(rules
+            .iter()
+            .any(|r| r.pattern == "jira.example.com" && r.category_id == category));
+    }
+}
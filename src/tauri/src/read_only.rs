@@ -0,0 +1,53 @@
+//! Read-only mode (`--read-only`): the database is opened read-only, nothing
+//! is tracked or scheduled, and commands that change data are refused. Safe
+//! for a second instance on the live database or a dashboard on a shared
+//! screen.
+
+/// Command-line flag that starts Foxus read-only.
+pub const READ_ONLY_FLAG: &str = "--read-only";
+
+/// Returned to the frontend for a command refused in read-only mode.
+pub const REJECTION: &str = "Foxus is in read-only mode";
+
+/// Commands that only read, besides the `get_` ones.
+const READ_COMMANDS: &[&str] = &[
+    "analyze_queries",
+    "explain_categorization",
+    "check_for_updates",
+    "show_focus_widget",
+    "close_focus_widget",
+];
+
+/// Managed app state: whether this instance is read-only.
+pub struct ReadOnly(pub bool);
+
+/// Whether the command line asks for read-only mode.
+pub fn requested(mut args: impl Iterator<Item = String>) -> bool {
+    args.any(|arg| arg == READ_ONLY_FLAG)
+}
+
+/// Whether `command` may run in read-only mode.
+pub fn allows(command: &str) -> bool {
+    command.starts_with("get_") || READ_COMMANDS.contains(&command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requested() {
+        let args = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert!(requested(args(&["foxus", "--read-only"]).into_iter()));
+        assert!(!requested(args(&["foxus"]).into_iter()));
+    }
+
+    #[test]
+    fn test_only_reading_commands_are_allowed() {
+        assert!(allows("get_today_stats"));
+        assert!(allows("explain_categorization"));
+        assert!(!allows("start_focus_session"));
+        assert!(!allows("delete_category"));
+        assert!(!allows("check_focus_schedules"));
+    }
+}
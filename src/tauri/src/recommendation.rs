@@ -0,0 +1,218 @@
+use crate::away_mode::is_away;
+use crate::focus::get_current_day_and_time;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, FocusSchedule, Setting};
+use chrono::{NaiveTime, Timelike};
+use rusqlite::{Connection, Result};
+
+const DAILY_GOAL_SETTING: &str = "goals.daily_focus_minutes";
+
+/// Productive minutes per day the user aims for when unconfigured.
+pub const DEFAULT_DAILY_GOAL_MINUTES: i32 = 4 * 60;
+
+/// Session length once the goal is met (or nothing else narrows it down).
+const DEFAULT_SESSION_MINUTES: i32 = 50;
+const MIN_SESSION_MINUTES: i32 = 15;
+const MAX_SESSION_MINUTES: i32 = 90;
+
+/// Share of the session allowed as distraction budget, in percent.
+const BUDGET_PERCENT: i32 = 10;
+
+/// A day with this much distraction already gets a tighter budget.
+const HEAVY_DISTRACTION_SECS: i32 = 60 * 60;
+
+pub fn daily_goal_minutes(conn: &Connection) -> Result<i32> {
+    Ok(Setting::get(conn, DAILY_GOAL_SETTING)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_DAILY_GOAL_MINUTES))
+}
+
+pub fn set_daily_goal_minutes(conn: &Connection, minutes: i32) -> Result<()> {
+    Setting::set(conn, DAILY_GOAL_SETTING, &minutes.to_string())
+}
+
+/// What today looks like so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecommendationInputs {
+    pub productive_secs: i32,
+    pub distracting_secs: i32,
+    pub goal_minutes: i32,
+    /// Minutes until the next scheduled focus block, if one is coming up today.
+    pub free_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusRecommendation {
+    pub duration_minutes: i32,
+    pub budget_minutes: i32,
+    /// Productive minutes still needed to reach today's goal.
+    pub goal_remaining_minutes: i32,
+    pub free_minutes: Option<i32>,
+    /// Short explanation the UI can show next to the suggestion.
+    pub reason: String,
+}
+
+/// Pick a session length and budget: close the goal gap, fit before the next
+/// scheduled block, and tighten the budget on an already distracted day.
+pub fn recommend(inputs: &RecommendationInputs) -> FocusRecommendation {
+    let goal_remaining_minutes = (inputs.goal_minutes - inputs.productive_secs / 60).max(0);
+    let mut reasons = Vec::new();
+
+    let mut duration = if goal_remaining_minutes > 0 {
+        reasons.push(format!("{goal_remaining_minutes}m left to today's goal"));
+        goal_remaining_minutes.clamp(MIN_SESSION_MINUTES, MAX_SESSION_MINUTES)
+    } else {
+        reasons.push("today's goal is met".to_string());
+        DEFAULT_SESSION_MINUTES
+    };
+    if let Some(free) = inputs.free_minutes.filter(|free| *free < duration) {
+        reasons.push(format!("next scheduled block in {free}m"));
+        duration = free;
+    }
+    // Round down to a tidy five minutes without going under the minimum
+    let duration_minutes = (duration - duration % 5).max(MIN_SESSION_MINUTES);
+
+    let mut budget_minutes = duration_minutes * BUDGET_PERCENT / 100;
+    if inputs.distracting_secs >= HEAVY_DISTRACTION_SECS {
+        reasons.push(format!(
+            "{}m distracted already today",
+            inputs.distracting_secs / 60
+        ));
+        budget_minutes /= 2;
+    }
+
+    FocusRecommendation {
+        duration_minutes,
+        budget_minutes: budget_minutes.max(1),
+        goal_remaining_minutes,
+        free_minutes: inputs.free_minutes,
+        reason: reasons.join(", "),
+    }
+}
+
+fn minutes_of_day(time: &str) -> Option<i32> {
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+    i32::try_from(time.hour() * 60 + time.minute()).ok()
+}
+
+/// Minutes from `time` until the next enabled schedule starts on `day`.
+fn minutes_until_next_schedule(schedules: &[FocusSchedule], day: u32, time: &str) -> Option<i32> {
+    let now = minutes_of_day(time)?;
+    schedules
+        .iter()
+        .filter(|s| s.enabled && s.applies_to_day(day))
+        .filter_map(|s| minutes_of_day(&s.start_time))
+        .filter(|start| *start > now)
+        .min()
+        .map(|start| start - now)
+}
+
+/// Recommend a session from today's activity, goal, and upcoming schedules.
+///
+/// Days use the same boundaries as the stats and schedule checks.
+pub fn today_recommendation(conn: &Connection) -> Result<FocusRecommendation> {
+    let now = current_timestamp();
+    let (_, _, today_start) = local_today();
+    let (productive_secs, _, distracting_secs) =
+        Activity::productivity_totals(conn, today_start, now)?;
+    let (day, time) = get_current_day_and_time();
+    let schedules = FocusSchedule::find_enabled(conn)?;
+    // No goal to reach on a day away
+    let goal_minutes = if is_away(conn)? {
+        0
+    } else {
+        daily_goal_minutes(conn)?
+    };
+
+    Ok(recommend(&RecommendationInputs {
+        productive_secs,
+        distracting_secs,
+        goal_minutes,
+        free_minutes: minutes_until_next_schedule(&schedules, day, &time),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn inputs(productive_mins: i32, distracting_mins: i32) -> RecommendationInputs {
+        RecommendationInputs {
+            productive_secs: productive_mins * 60,
+            distracting_secs: distracting_mins * 60,
+            goal_minutes: 240,
+            free_minutes: None,
+        }
+    }
+
+    #[test]
+    fn test_closes_goal_gap() {
+        let rec = recommend(&inputs(200, 0));
+        assert_eq!(rec.goal_remaining_minutes, 40);
+        assert_eq!((rec.duration_minutes, rec.budget_minutes), (40, 4));
+
+        // A large gap is capped at one long session
+        assert_eq!(
+            recommend(&inputs(0, 0)).duration_minutes,
+            MAX_SESSION_MINUTES
+        );
+    }
+
+    #[test]
+    fn test_goal_met_uses_default() {
+        let rec = recommend(&inputs(300, 0));
+        assert_eq!(rec.goal_remaining_minutes, 0);
+        assert_eq!(rec.duration_minutes, DEFAULT_SESSION_MINUTES);
+    }
+
+    #[test]
+    fn test_fits_before_next_schedule_and_tightens_budget() {
+        let rec = recommend(&RecommendationInputs {
+            free_minutes: Some(33),
+            ..inputs(0, 90)
+        });
+        assert_eq!((rec.duration_minutes, rec.budget_minutes), (30, 1));
+        assert!(rec.reason.contains("next scheduled block"));
+
+        let tiny_gap = recommend(&RecommendationInputs {
+            free_minutes: Some(5),
+            ..inputs(0, 0)
+        });
+        assert_eq!(tiny_gap.duration_minutes, MIN_SESSION_MINUTES);
+    }
+
+    #[test]
+    fn test_minutes_until_next_schedule() {
+        let mut morning = FocusSchedule::new("1,2,3,4,5", "09:00", "12:00", 600);
+        let afternoon = FocusSchedule::new("1,2,3,4,5", "14:30", "17:00", 600);
+        let weekend = FocusSchedule::new("6,7", "10:00", "12:00", 600);
+        let schedules = vec![morning.clone(), afternoon, weekend];
+
+        assert_eq!(
+            minutes_until_next_schedule(&schedules, 1, "08:00"),
+            Some(60)
+        );
+        assert_eq!(
+            minutes_until_next_schedule(&schedules, 1, "10:00"),
+            Some(270)
+        );
+        assert_eq!(minutes_until_next_schedule(&schedules, 1, "18:00"), None);
+
+        morning.enabled = false;
+        assert_eq!(minutes_until_next_schedule(&[morning], 1, "08:00"), None);
+    }
+
+    #[test]
+    fn test_daily_goal_setting() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert_eq!(
+            daily_goal_minutes(conn).unwrap(),
+            DEFAULT_DAILY_GOAL_MINUTES
+        );
+        set_daily_goal_minutes(conn, 180).unwrap();
+        assert_eq!(daily_goal_minutes(conn).unwrap(), 180);
+    }
+}
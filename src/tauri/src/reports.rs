@@ -0,0 +1,156 @@
+use crate::daily_summary::DistractionStat;
+use crate::local_time::day_bounds;
+use crate::models::{Activity, FocusSession, Note};
+use crate::tray::format_duration;
+use chrono::{Days, NaiveDate};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use std::fmt::Write;
+
+const REPORT_DAYS: u64 = 7;
+const TOP_DISTRACTIONS_LIMIT: i64 = 5;
+
+/// Totals for the seven local days ending on `end_date`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReport {
+    /// First day covered, YYYY-MM-DD.
+    pub start_date: String,
+    /// Last day covered (inclusive), YYYY-MM-DD.
+    pub end_date: String,
+    pub productive_secs: i32,
+    pub neutral_secs: i32,
+    pub distracting_secs: i32,
+    pub focus_sessions: i32,
+    pub focus_secs: i64,
+    pub top_distractions: Vec<DistractionStat>,
+    pub notes: Vec<DayNote>,
+}
+
+/// A journal note from one of the report's days.
+#[derive(Debug, Clone, Serialize)]
+pub struct DayNote {
+    /// YYYY-MM-DD.
+    pub day: String,
+    pub text: String,
+}
+
+impl WeeklyReport {
+    pub fn compile(conn: &Connection, end_date: NaiveDate) -> Result<Self> {
+        let start_date = end_date
+            .checked_sub_days(Days::new(REPORT_DAYS - 1))
+            .unwrap_or(end_date);
+        let (start, _) = day_bounds(start_date);
+        let (_, end) = day_bounds(end_date);
+
+        let (productive_secs, neutral_secs, distracting_secs) =
+            Activity::productivity_totals(conn, start, end)?;
+        let (focus_sessions, focus_secs) = FocusSession::completed_totals(conn, start, end)?;
+        let top_distractions =
+            Activity::top_distractions(conn, start, end, TOP_DISTRACTIONS_LIMIT)?
+                .into_iter()
+                .map(|(name, duration_secs)| DistractionStat {
+                    name,
+                    duration_secs,
+                })
+                .collect();
+        let start_date = start_date.format("%Y-%m-%d").to_string();
+        let end_date = end_date.format("%Y-%m-%d").to_string();
+        let notes = Note::find_in_days(conn, &start_date, &end_date)?
+            .into_iter()
+            .map(|note| DayNote {
+                day: note.day,
+                text: note.text,
+            })
+            .collect();
+
+        Ok(Self {
+            start_date,
+            end_date,
+            productive_secs,
+            neutral_secs,
+            distracting_secs,
+            focus_sessions,
+            focus_secs,
+            top_distractions,
+            notes,
+        })
+    }
+
+    /// Plain-text rendering for messages and emails.
+    pub fn to_text(&self) -> String {
+        let focus_secs = i32::try_from(self.focus_secs).unwrap_or(i32::MAX);
+        let mut text = format!(
+            "Foxus weekly report, {} to {}\n\
+             Productive: {}\n\
+             Neutral: {}\n\
+             Distracting: {}\n\
+             Focus sessions: {} ({})",
+            self.start_date,
+            self.end_date,
+            format_duration(self.productive_secs),
+            format_duration(self.neutral_secs),
+            format_duration(self.distracting_secs),
+            self.focus_sessions,
+            format_duration(focus_secs),
+        );
+        if !self.top_distractions.is_empty() {
+            text.push_str("\nTop distractions:");
+            for distraction in &self.top_distractions {
+                let _ = write!(
+                    text,
+                    "\n- {} ({})",
+                    distraction.name,
+                    format_duration(distraction.duration_secs)
+                );
+            }
+        }
+        if !self.notes.is_empty() {
+            text.push_str("\nNotes:");
+            for note in &self.notes {
+                let _ = write!(text, "\n- {}: {}", note.day, note.text);
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_compile_covers_seven_days() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let end_date = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+
+        let categories = Category::find_all(conn).unwrap();
+        let id_of = |name: &str| categories.iter().find(|c| c.name == name).unwrap().id;
+
+        let in_range = day_bounds(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()).0 + 3600;
+        let too_early = day_bounds(NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()).0 + 3600;
+        for (timestamp, category) in [(in_range, "Coding"), (too_early, "Coding")] {
+            let mut activity = Activity::new(timestamp, 1800, "app", Some("Code"), None);
+            activity.category_id = Some(id_of(category));
+            activity.save(conn).unwrap();
+        }
+
+        let mut session = FocusSession::new(600, false);
+        session.started_at = in_range;
+        session.ended_at = Some(in_range + 1500);
+        session.save(conn).unwrap();
+        Note::create(conn, "2026-03-02", None, "Board meeting prep", in_range).unwrap();
+
+        let report = WeeklyReport::compile(conn, end_date).unwrap();
+        assert_eq!(report.start_date, "2026-03-02");
+        assert_eq!(report.end_date, "2026-03-08");
+        assert_eq!(report.productive_secs, 1800);
+        assert_eq!((report.focus_sessions, report.focus_secs), (1, 1500));
+        assert!(report.to_text().contains("Focus sessions: 1 (25m)"));
+        assert!(report
+            .to_text()
+            .contains("Notes:\n- 2026-03-02: Board meeting prep"));
+    }
+}
@@ -0,0 +1,178 @@
+//! "Focus starts in 5 minutes" notices ahead of scheduled sessions, so a
+//! session that starts on its own and blocks sites never catches the user
+//! mid-task. Each schedule sets its own lead time.
+
+use crate::away_mode::is_away_at;
+use crate::db::{with_connection, Database};
+use crate::local_time::{date_in, wall_time_at};
+use crate::models::focus_session::current_timestamp;
+use crate::models::{FocusSchedule, FocusSession, Setting};
+use crate::notifications::notify;
+use chrono::{Datelike, Days, Local, TimeZone};
+use log::{error, warn};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Start of the last window announced, so a notice goes out once.
+const LAST_NOTICE_SETTING: &str = "schedule_reminders.last_start";
+
+/// Event emitted with the upcoming session so the UI can show a countdown.
+pub const SCHEDULE_STARTING_EVENT: &str = "schedule_starting";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpcomingSession {
+    pub schedule_id: i64,
+    /// When the session starts.
+    pub starts_at: i64,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// The next scheduled start within its schedule's lead time of `now`, if
+/// any, leaving out `skipped` (schedule id, date) pairs. Looks at today and
+/// tomorrow, for a schedule starting just after midnight.
+fn upcoming<Tz: TimeZone>(
+    schedules: &[FocusSchedule],
+    skipped: &[(i64, String)],
+    tz: &Tz,
+    now: i64,
+) -> Option<UpcomingSession> {
+    let today = date_in(tz, now);
+    let dates = [Some(today), today.checked_add_days(Days::new(1))];
+    schedules
+        .iter()
+        .filter(|schedule| schedule.enabled && schedule.notify_lead_minutes > 0)
+        .flat_map(|schedule| dates.iter().flatten().map(move |date| (schedule, *date)))
+        .filter(|(schedule, date)| schedule.applies_to_day(date.weekday().number_from_monday()))
+        .filter(|(schedule, date)| {
+            let date = date.format("%Y-%m-%d").to_string();
+            !skipped
+                .iter()
+                .any(|(id, skipped_date)| schedule.id == Some(*id) && *skipped_date == date)
+        })
+        .filter_map(|(schedule, date)| {
+            let starts_at = wall_time_at(tz, date, schedule.start_wall_time()?);
+            let notice_from = starts_at - i64::from(schedule.notify_lead_minutes) * 60;
+            (notice_from..starts_at)
+                .contains(&now)
+                .then(|| UpcomingSession {
+                    schedule_id: schedule.id.unwrap_or_default(),
+                    starts_at,
+                    start_time: schedule.start_time.clone(),
+                    end_time: schedule.end_time.clone(),
+                })
+        })
+        .min_by_key(|session| session.starts_at)
+}
+
+/// The upcoming session to announce now, recording it as announced.
+fn due_notice<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    now: i64,
+) -> rusqlite::Result<Option<UpcomingSession>> {
+    // A session already running keeps the schedule from starting another,
+    // and none start while away
+    if FocusSession::find_active(conn)?.is_some() || is_away_at(conn, tz, now)? {
+        return Ok(None);
+    }
+    let today = date_in(tz, now).format("%Y-%m-%d").to_string();
+    let skipped = FocusSchedule::find_skips_from(conn, &today)?;
+    let Some(session) = upcoming(&FocusSchedule::find_enabled(conn)?, &skipped, tz, now) else {
+        return Ok(None);
+    };
+    let last_start = Setting::get(conn, LAST_NOTICE_SETTING)?.and_then(|v| v.parse::<i64>().ok());
+    if last_start >= Some(session.starts_at) {
+        return Ok(None);
+    }
+    Setting::set(conn, LAST_NOTICE_SETTING, &session.starts_at.to_string())?;
+    Ok(Some(session))
+}
+
+/// Announce a scheduled session once it's within its lead time.
+pub fn check_schedule_reminders(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let now = current_timestamp();
+
+    match with_connection(&db, |conn| due_notice(conn, &Local, now)) {
+        Ok(Some(session)) => {
+            let minutes = (session.starts_at - now + 59) / 60;
+            notify(
+                app,
+                &format!("Focus starts in {minutes} min"),
+                &format!(
+                    "Your scheduled session runs {}-{}. Distracting sites will be blocked.",
+                    session.start_time, session.end_time
+                ),
+            );
+            if let Err(e) = app.emit(SCHEDULE_STARTING_EVENT, &session) {
+                warn!("Failed to emit upcoming session: {e}");
+            }
+        }
+        Ok(None) => {}
+        Err(e) => error!("Failed to check upcoming focus schedules: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+
+    /// Thursday 2026-10-15 08:50 UTC
+    const BEFORE_NINE: i64 = 1_792_054_200;
+
+    fn schedule(days: &str, start: &str, lead: i32) -> FocusSchedule {
+        let mut schedule = FocusSchedule::new(days, start, "23:00", 0);
+        schedule.notify_lead_minutes = lead;
+        schedule
+    }
+
+    #[test]
+    fn test_upcoming_within_lead_time() {
+        let nine = schedule("4", "09:00", 15);
+        let found = upcoming(&[nine.clone()], &[], &Utc, BEFORE_NINE).unwrap();
+        assert_eq!(found.starts_at, BEFORE_NINE + 600);
+
+        // Too early for a 5-minute notice, the wrong day, or no notice wanted
+        assert!(upcoming(&[schedule("4", "09:00", 5)], &[], &Utc, BEFORE_NINE).is_none());
+        assert!(upcoming(&[schedule("5", "09:00", 15)], &[], &Utc, BEFORE_NINE).is_none());
+        assert!(upcoming(&[schedule("4", "09:00", 0)], &[], &Utc, BEFORE_NINE).is_none());
+        // Once it has started, it's no longer upcoming
+        assert!(upcoming(&[nine], &[], &Utc, BEFORE_NINE + 600).is_none());
+    }
+
+    #[test]
+    fn test_upcoming_just_after_midnight() {
+        // Thursday 23:55, for Friday 00:05
+        let late = BEFORE_NINE + 15 * 3600 + 5 * 60;
+        let found = upcoming(&[schedule("5", "00:05", 15)], &[], &Utc, late).unwrap();
+        assert_eq!(found.starts_at, late + 600);
+    }
+
+    #[test]
+    fn test_due_notice_is_sent_once() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        schedule("4", "09:00", 15).save(conn).unwrap();
+
+        assert!(due_notice(conn, &Utc, BEFORE_NINE).unwrap().is_some());
+        assert!(due_notice(conn, &Utc, BEFORE_NINE + 60).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_skipped_day_is_not_announced() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut nine = schedule("4", "09:00", 15);
+        nine.save(conn).unwrap();
+        FocusSchedule::skip_on(conn, nine.id.unwrap(), "2026-10-15").unwrap();
+
+        assert!(due_notice(conn, &Utc, BEFORE_NINE).unwrap().is_none());
+    }
+}
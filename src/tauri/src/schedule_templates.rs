@@ -0,0 +1,226 @@
+//! Built-in focus schedule templates, e.g. weekday deep-work mornings or
+//! evenings away from social media. Applying one creates its schedules, and
+//! any categories and rules they need, in one transaction.
+
+use crate::models::{Category, FocusSchedule, MatchType, Rule};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// Priority of template rules, above the built-in ones so they win over a
+/// default rule for the same site.
+const TEMPLATE_RULE_PRIORITY: i32 = 20;
+
+pub struct TemplateSchedule {
+    pub days_of_week: &'static str,
+    pub start_time: &'static str,
+    pub end_time: &'static str,
+    pub distraction_budget: i32,
+    /// Names of categories its sessions block even though they aren't
+    /// distracting.
+    pub blocked_categories: &'static [&'static str],
+}
+
+pub struct ScheduleTemplate {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub schedules: &'static [TemplateSchedule],
+    /// Categories created if missing: (name, productivity).
+    pub categories: &'static [(&'static str, i32)],
+    /// Rules created if missing: (pattern, match type, category name).
+    pub rules: &'static [(&'static str, &'static str, &'static str)],
+}
+
+pub const TEMPLATES: &[ScheduleTemplate] = &[
+    ScheduleTemplate {
+        name: "deep_work_mornings",
+        title: "9\u{2013}12 deep work weekdays",
+        description: "Focus from 9 to 12 on weekdays with no distraction budget. \
+                      Chat and email are blocked too.",
+        schedules: &[TemplateSchedule {
+            days_of_week: "1,2,3,4,5",
+            start_time: "09:00",
+            end_time: "12:00",
+            distraction_budget: 0,
+            blocked_categories: &["Communication"],
+        }],
+        categories: &[],
+        rules: &[],
+    },
+    ScheduleTemplate {
+        name: "no_social_evenings",
+        title: "No-social evenings",
+        description: "Every evening from 18:00, social networks are blocked along with \
+                      other distracting sites. They get their own Social Media category.",
+        schedules: &[TemplateSchedule {
+            days_of_week: "1,2,3,4,5,6,7",
+            start_time: "18:00",
+            end_time: "23:59",
+            distraction_budget: 0,
+            blocked_categories: &[],
+        }],
+        categories: &[("Social Media", -1)],
+        rules: &[
+            ("twitter.com", "domain", "Social Media"),
+            ("x.com", "domain", "Social Media"),
+            ("facebook.com", "domain", "Social Media"),
+            ("instagram.com", "domain", "Social Media"),
+            ("tiktok.com", "domain", "Social Media"),
+            ("reddit.com", "domain", "Social Media"),
+            ("linkedin.com", "domain", "Social Media"),
+            ("threads.net", "domain", "Social Media"),
+            ("bsky.app", "domain", "Social Media"),
+        ],
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static ScheduleTemplate> {
+    TEMPLATES.iter().find(|t| t.name == name)
+}
+
+/// What applying a template created.
+#[derive(Debug)]
+pub struct Applied {
+    pub schedules: Vec<FocusSchedule>,
+    pub categories_added: usize,
+    pub rules_added: usize,
+}
+
+impl ScheduleTemplate {
+    /// Create the template's schedules, categories and rules. Ones that
+    /// already exist are left alone, so applying a template twice adds
+    /// nothing the second time.
+    pub fn apply(&self, conn: &Connection) -> Result<Applied> {
+        let tx = conn.unchecked_transaction()?;
+
+        let mut categories_added = 0;
+        for (name, productivity) in self.categories {
+            if category_id(&tx, name)?.is_none() {
+                Category::create(&tx, name, *productivity)?;
+                categories_added += 1;
+            }
+        }
+
+        let mut rules_added = 0;
+        for (pattern, match_type, category) in self.rules {
+            let (Some(match_type), Some(category_id)) =
+                (MatchType::from_str(match_type), category_id(&tx, category)?)
+            else {
+                continue;
+            };
+            if !rule_exists(&tx, pattern, match_type, category_id)? {
+                Rule::create(
+                    &tx,
+                    pattern,
+                    match_type,
+                    category_id,
+                    TEMPLATE_RULE_PRIORITY,
+                )?;
+                rules_added += 1;
+            }
+        }
+
+        let existing = FocusSchedule::find_all(&tx)?;
+        let mut schedules = Vec::new();
+        for template in self.schedules {
+            if existing.iter().any(|s| {
+                s.days_of_week == template.days_of_week
+                    && s.start_time == template.start_time
+                    && s.end_time == template.end_time
+            }) {
+                continue;
+            }
+            let mut schedule = FocusSchedule::new(
+                template.days_of_week,
+                template.start_time,
+                template.end_time,
+                template.distraction_budget,
+            );
+            for name in template.blocked_categories {
+                schedule.blocked_categories.extend(category_id(&tx, name)?);
+            }
+            schedule.save(&tx)?;
+            schedules.push(schedule);
+        }
+
+        tx.commit()?;
+        Ok(Applied {
+            schedules,
+            categories_added,
+            rules_added,
+        })
+    }
+}
+
+fn category_id(conn: &Connection, name: &str) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM categories WHERE name = ?1 COLLATE NOCASE",
+        [name],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn rule_exists(
+    conn: &Connection,
+    pattern: &str,
+    match_type: MatchType,
+    category_id: i64,
+) -> Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM rules
+         WHERE pattern = ?1 AND match_type = ?2 AND category_id = ?3 AND deleted_at IS NULL",
+        params![pattern, match_type.as_str(), category_id],
+        |row| row.get(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use crate::validation::{validate_budget_secs, validate_days_of_week, validate_time_format};
+
+    #[test]
+    fn test_templates_are_valid() {
+        for template in TEMPLATES {
+            for schedule in template.schedules {
+                assert!(validate_time_format(schedule.start_time).is_ok());
+                assert!(validate_time_format(schedule.end_time).is_ok());
+                assert!(schedule.start_time < schedule.end_time);
+                assert!(validate_days_of_week(schedule.days_of_week).is_ok());
+                assert!(validate_budget_secs(schedule.distraction_budget).is_ok());
+            }
+            for (_, match_type, _) in template.rules {
+                assert!(MatchType::from_str(match_type).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_creates_everything_once() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        let applied = find("no_social_evenings").unwrap().apply(conn).unwrap();
+        assert_eq!(applied.schedules.len(), 1);
+        assert_eq!(applied.categories_added, 1);
+        assert_eq!(applied.rules_added, 9);
+        let social = category_id(conn, "Social Media").unwrap().unwrap();
+        assert!(Rule::find_all(conn)
+            .unwrap()
+            .iter()
+            .any(|r| r.pattern == "x.com" && r.category_id == social));
+
+        let again = find("no_social_evenings").unwrap().apply(conn).unwrap();
+        assert!(again.schedules.is_empty());
+        assert_eq!((again.categories_added, again.rules_added), (0, 0));
+
+        let deep_work = find("deep_work_mornings").unwrap().apply(conn).unwrap();
+        let communication = category_id(conn, "Communication").unwrap().unwrap();
+        assert_eq!(
+            deep_work.schedules.first().unwrap().blocked_categories,
+            vec![communication]
+        );
+        assert_eq!(FocusSchedule::find_all(conn).unwrap().len(), 2);
+    }
+}
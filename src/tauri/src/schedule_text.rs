@@ -0,0 +1,458 @@
+//! Focus schedules from short phrases such as "weekdays 9am-11am, 10 min
+//! budget" or "mon, wed and fri 14:00 to 16:30". The parser knows day names
+//! and groups, 12- and 24-hour times, and durations; anything else is an
+//! error rather than a guess, and the result comes back as a summary for the
+//! user to confirm.
+
+use crate::constants::MAX_SCHEDULE_TEXT_LEN;
+use crate::error::AppError;
+use crate::validation::{validate_budget_secs, validate_days_of_week, validate_time_format};
+use std::collections::BTreeSet;
+
+const DAY_NAMES: &[(&str, u32)] = &[
+    ("mon", 1),
+    ("monday", 1),
+    ("tue", 2),
+    ("tues", 2),
+    ("tuesday", 2),
+    ("wed", 3),
+    ("weds", 3),
+    ("wednesday", 3),
+    ("thu", 4),
+    ("thur", 4),
+    ("thurs", 4),
+    ("thursday", 4),
+    ("fri", 5),
+    ("friday", 5),
+    ("sat", 6),
+    ("saturday", 6),
+    ("sun", 7),
+    ("sunday", 7),
+];
+
+const DAY_LABELS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Words that carry no meaning of their own here.
+const FILLER: &[&str] = &[
+    "-",
+    "a",
+    "allowed",
+    "an",
+    "and",
+    "at",
+    "budget",
+    "distraction",
+    "distractions",
+    "every",
+    "from",
+    "no",
+    "of",
+    "on",
+    "through",
+    "thru",
+    "till",
+    "to",
+    "until",
+    "with",
+];
+
+const RANGE_WORDS: &[&str] = &["-", "to", "through", "thru", "until", "till"];
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedSchedule {
+    pub days_of_week: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub distraction_budget: i32,
+    /// How the text was read, e.g. "Weekdays 09:00-11:00, 10 min
+    /// distraction budget".
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Meridiem {
+    Am,
+    Pm,
+}
+
+impl Meridiem {
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "am" => Some(Self::Am),
+            "pm" => Some(Self::Pm),
+            _ => None,
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Self::Am => Self::Pm,
+            Self::Pm => Self::Am,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    hour: u32,
+    minute: u32,
+    meridiem: Option<Meridiem>,
+}
+
+impl Clock {
+    /// Minutes since midnight, read with `meridiem` if the clock has none.
+    fn minutes(self, meridiem: Option<Meridiem>) -> Result<u32, AppError> {
+        let hour = match self.meridiem.or(meridiem) {
+            None if self.hour < 24 => self.hour,
+            Some(_) if self.hour == 0 || self.hour > 12 => {
+                return Err(invalid(format!("{}am/pm isn't a time", self.hour)))
+            }
+            Some(Meridiem::Am) => self.hour % 12,
+            Some(Meridiem::Pm) => self.hour % 12 + 12,
+            None => return Err(invalid(format!("{}:00 isn't a time", self.hour))),
+        };
+        Ok(hour * 60 + self.minute)
+    }
+}
+
+fn invalid(reason: String) -> AppError {
+    AppError::InvalidInput {
+        field: "text",
+        reason,
+    }
+}
+
+fn day_number(word: &str) -> Option<u32> {
+    let find = |name: &str| {
+        DAY_NAMES
+            .iter()
+            .find(|(day, _)| *day == name)
+            .map(|(_, number)| *number)
+    };
+    // "mondays"
+    find(word).or_else(|| word.strip_suffix('s').and_then(find))
+}
+
+fn day_group(word: &str) -> Option<std::ops::RangeInclusive<u32>> {
+    match word {
+        "weekday" | "weekdays" => Some(1..=5),
+        "weekend" | "weekends" => Some(6..=7),
+        "daily" | "everyday" => Some(1..=7),
+        _ => None,
+    }
+}
+
+/// `first` through `last`, wrapping past Sunday ("fri-mon").
+fn day_range(first: u32, last: u32) -> Vec<u32> {
+    if first <= last {
+        (first..=last).collect()
+    } else {
+        (first..=7).chain(1..=last).collect()
+    }
+}
+
+/// Seconds in one of `unit`.
+fn duration_unit(word: &str) -> Option<u32> {
+    match word {
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(60),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(3600),
+        _ => None,
+    }
+}
+
+fn parse_clock(word: &str) -> Result<Clock, AppError> {
+    let bad = || invalid(format!("didn't understand the time '{word}'"));
+    let (hour, minute) = match word.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute),
+        Some(_) => return Err(bad()),
+        None => (word, "0"),
+    };
+    let hour: u32 = hour.parse().map_err(|_| bad())?;
+    let minute: u32 = minute.parse().map_err(|_| bad())?;
+    if minute >= 60 {
+        return Err(bad());
+    }
+    Ok(Clock {
+        hour,
+        minute,
+        meridiem: None,
+    })
+}
+
+/// Lowercase words, with numbers split from units ("9am", "10min") and
+/// punctuation dropped ("a.m.").
+fn tokenize(text: &str) -> Vec<String> {
+    let mut spaced = String::new();
+    let mut prev: Option<char> = None;
+    for c in text.to_lowercase().chars() {
+        let c = match c {
+            '\u{2013}' | '\u{2014}' => '-',
+            '.' if prev.is_some_and(|p| p.is_ascii_digit()) => ':',
+            // "a.m."
+            '.' => continue,
+            ',' | ';' | '&' => ' ',
+            c => c,
+        };
+        let boundary = prev.is_some_and(|p| {
+            (p.is_ascii_digit() && c.is_alphabetic()) || (p.is_alphabetic() && c.is_ascii_digit())
+        });
+        if boundary || c == '-' {
+            spaced.push(' ');
+        }
+        spaced.push(c);
+        if c == '-' {
+            spaced.push(' ');
+        }
+        prev = Some(c);
+    }
+    spaced.split_whitespace().map(str::to_string).collect()
+}
+
+/// Read a schedule from `text`. Days default to every day and the budget to
+/// none.
+pub fn parse(text: &str) -> Result<ParsedSchedule, AppError> {
+    if text.chars().count() > MAX_SCHEDULE_TEXT_LEN {
+        return Err(invalid(format!(
+            "must be at most {MAX_SCHEDULE_TEXT_LEN} characters"
+        )));
+    }
+    let tokens = tokenize(text);
+    let word_at = |i: usize| tokens.get(i).map(String::as_str);
+
+    let mut days = BTreeSet::new();
+    let mut times = Vec::new();
+    let mut budget_secs: Option<u32> = None;
+    let mut i = 0;
+    while let Some(word) = word_at(i) {
+        let next = word_at(i + 1);
+        i += 1;
+        if let Some(group) = day_group(word) {
+            days.extend(group);
+        } else if word == "every" && next == Some("day") {
+            days.extend(1..=7);
+            i += 1;
+        } else if let Some(day) = day_number(word) {
+            let last = next
+                .filter(|w| RANGE_WORDS.contains(w))
+                .and_then(|_| word_at(i + 1))
+                .and_then(day_number);
+            if let Some(last) = last {
+                days.extend(day_range(day, last));
+                i += 2;
+            } else {
+                days.insert(day);
+            }
+        } else if word == "noon" {
+            times.push(Clock {
+                hour: 12,
+                minute: 0,
+                meridiem: Some(Meridiem::Pm),
+            });
+        } else if word == "midnight" {
+            times.push(Clock {
+                hour: 12,
+                minute: 0,
+                meridiem: Some(Meridiem::Am),
+            });
+        } else if word.starts_with(|c: char| c.is_ascii_digit()) {
+            if let Some(unit) = next.and_then(duration_unit) {
+                let amount: u32 = word
+                    .parse()
+                    .map_err(|_| invalid(format!("didn't understand the duration '{word}'")))?;
+                budget_secs = Some(
+                    budget_secs
+                        .unwrap_or(0)
+                        .saturating_add(amount.saturating_mul(unit)),
+                );
+                i += 1;
+                continue;
+            }
+            let mut clock = parse_clock(word)?;
+            if let Some(meridiem) = next.and_then(Meridiem::parse) {
+                clock.meridiem = Some(meridiem);
+                i += 1;
+            }
+            times.push(clock);
+        } else if !FILLER.contains(&word) {
+            return Err(invalid(format!("didn't understand '{word}'")));
+        }
+    }
+
+    let [start, end] = *times.as_slice() else {
+        return Err(invalid(
+            "needs a start and an end time, like 9am-11am".into(),
+        ));
+    };
+    let (start, end) = resolve(start, end)?;
+    if start >= end {
+        return Err(invalid(
+            "must end after it starts; schedules can't run past midnight".into(),
+        ));
+    }
+
+    if days.is_empty() {
+        days.extend(1..=7);
+    }
+    let days_of_week = days
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let distraction_budget = i32::try_from(budget_secs.unwrap_or(0))
+        .map_err(|_| invalid("budget is too long".into()))?;
+    let (start_time, end_time) = (hh_mm(start), hh_mm(end));
+    validate_days_of_week(&days_of_week)?;
+    validate_time_format(&start_time)?;
+    validate_time_format(&end_time)?;
+    validate_budget_secs(distraction_budget)?;
+
+    let summary = format!(
+        "{} {start_time}-{end_time}, {}",
+        days_label(&days),
+        budget_label(distraction_budget)
+    );
+    Ok(ParsedSchedule {
+        days_of_week,
+        start_time,
+        end_time,
+        distraction_budget,
+        summary,
+    })
+}
+
+/// Minutes since midnight for a start and end time where only one may say
+/// am or pm: "9-11am" is morning, "11-1pm" late morning to afternoon and
+/// "9-5" nine to five. An end at midnight is the end of the day.
+fn resolve(start: Clock, end: Clock) -> Result<(u32, u32), AppError> {
+    let (start, mut end) = match (start.meridiem, end.meridiem) {
+        (None, Some(meridiem)) if (1..=12).contains(&start.hour) => {
+            let end = end.minutes(None)?;
+            let same = start.minutes(Some(meridiem))?;
+            let start = if same < end {
+                same
+            } else {
+                start.minutes(Some(meridiem.other()))?
+            };
+            (start, end)
+        }
+        (Some(meridiem), None) if (1..=12).contains(&end.hour) => {
+            let start = start.minutes(None)?;
+            let same = end.minutes(Some(meridiem))?;
+            let end = if same > start {
+                same
+            } else {
+                end.minutes(Some(meridiem.other()))?
+            };
+            (start, end)
+        }
+        _ => {
+            let start = start.minutes(None)?;
+            let mut end_minutes = end.minutes(None)?;
+            if end.meridiem.is_none() && end_minutes <= start && end.hour < 12 {
+                end_minutes += 12 * 60;
+            }
+            (start, end_minutes)
+        }
+    };
+    if end == 0 {
+        end = MINUTES_PER_DAY;
+    }
+    Ok((start, end))
+}
+
+/// "HH:MM"; the end of the day is the last minute of it.
+fn hh_mm(minutes: u32) -> String {
+    let minutes = minutes.min(MINUTES_PER_DAY - 1);
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn days_label(days: &BTreeSet<u32>) -> String {
+    let list: Vec<u32> = days.iter().copied().collect();
+    match list.as_slice() {
+        [1, 2, 3, 4, 5] => "Weekdays".to_string(),
+        [6, 7] => "Weekends".to_string(),
+        [1, 2, 3, 4, 5, 6, 7] => "Every day".to_string(),
+        _ => list
+            .iter()
+            .filter_map(|day| DAY_LABELS.get(usize::try_from(day.saturating_sub(1)).ok()?))
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+fn budget_label(secs: i32) -> String {
+    match secs / 60 {
+        0 => "no distraction budget".to_string(),
+        minutes => format!("{minutes} min distraction budget"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(text: &str) -> (String, String, String, i32) {
+        let parsed = parse(text).unwrap();
+        (
+            parsed.days_of_week,
+            parsed.start_time,
+            parsed.end_time,
+            parsed.distraction_budget,
+        )
+    }
+
+    fn expected(days: &str, start: &str, end: &str, budget: i32) -> (String, String, String, i32) {
+        (days.into(), start.into(), end.into(), budget)
+    }
+
+    #[test]
+    fn test_parse_phrases() {
+        assert_eq!(
+            read("weekdays 9am-11am, 10 min budget"),
+            expected("1,2,3,4,5", "09:00", "11:00", 600)
+        );
+        assert_eq!(
+            read("Mon, Wed and Fri 14:00 to 16:30"),
+            expected("1,3,5", "14:00", "16:30", 0)
+        );
+        assert_eq!(
+            read("fri-mon from 11 to 1pm with 1h distraction"),
+            expected("1,5,6,7", "11:00", "13:00", 3600)
+        );
+        assert_eq!(read("9-5"), expected("1,2,3,4,5,6,7", "09:00", "17:00", 0));
+        assert_eq!(
+            read("saturdays 10pm-midnight"),
+            expected("6", "22:00", "23:59", 0)
+        );
+        assert_eq!(
+            read("every day 8.30 a.m. until noon, 5min"),
+            expected("1,2,3,4,5,6,7", "08:30", "12:00", 300)
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            parse("weekdays 9am-11am, 10 min budget").unwrap().summary,
+            "Weekdays 09:00-11:00, 10 min distraction budget"
+        );
+        assert_eq!(
+            parse("tue thu 13:00-15:00").unwrap().summary,
+            "Tue, Thu 13:00-15:00, no distraction budget"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_what_it_cant_read() {
+        assert!(parse("weekdays 9am").is_err());
+        assert!(parse("weekdays 9am-11am please").is_err());
+        assert!(parse("11pm-1am").is_err());
+        assert!(parse("13pm-14pm").is_err());
+        assert!(parse("9:75-11").is_err());
+        assert!(parse("9-11, 25 hours").is_err());
+        assert!(parse(&"a ".repeat(MAX_SCHEDULE_TEXT_LEN)).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::accountability::check_weekly_report;
+use crate::away_mode::check_away_mode;
+use crate::breaks::{check_break_reminder, finish_enforced_break, on_focus_session_completed};
+use crate::calendar_feed::check_calendar_feed;
+use crate::classifier::bayes::check_offline_suggestions;
+use crate::daily_summary::check_daily_summary;
+use crate::data_quality::check_data_quality;
+use crate::deep_work::check_deep_work;
+use crate::discord_presence::sync_discord_presence;
+use crate::email_report::check_email_report;
+use crate::focus::FocusManager;
+use crate::icons::check_icon_cache;
+use crate::insights::check_insights;
+use crate::mobile_sync::check_sync;
+use crate::models::focus_session::current_timestamp;
+use crate::mqtt::publish_focus_state;
+use crate::schedule_reminders::check_schedule_reminders;
+use crate::session_queue::check_session_queue;
+use crate::title_context::check_title_contexts;
+use crate::trash::check_trash;
+use crate::tray::refresh_tray;
+use log::error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often periodic app-level jobs run.
+const TICK_INTERVAL: Duration = Duration::from_mins(1);
+
+/// Background thread running periodic app-level jobs, listed in `run_jobs`.
+///
+/// Separate from `TrackerService` so slow jobs never delay activity capture.
+pub struct SchedulerService {
+    app: AppHandle,
+    running: Arc<AtomicBool>,
+}
+
+impl SchedulerService {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn start(&self) -> thread::JoinHandle<()> {
+        self.running.store(true, Ordering::SeqCst);
+
+        let running = Arc::clone(&self.running);
+        let app = self.app.clone();
+
+        thread::spawn(move || {
+            while running.load(Ordering::SeqCst) {
+                run_jobs(&app);
+                thread::sleep(TICK_INTERVAL);
+            }
+        })
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+/// One pass over every job. Each checks for itself whether it has anything
+/// to do, so a tick that finds nothing due is cheap.
+fn run_jobs(app: &AppHandle) {
+    check_away_mode(app);
+    end_expired_focus_session(app);
+    check_schedule_reminders(app);
+    check_session_queue(app);
+    check_deep_work(app);
+    refresh_tray(app);
+    check_break_reminder(app);
+    finish_enforced_break(app);
+    check_daily_summary(app);
+    check_data_quality(app);
+    check_weekly_report(app);
+    check_email_report(app);
+    check_insights(app);
+    check_offline_suggestions(app);
+    check_calendar_feed(app);
+    check_sync(app);
+    sync_discord_presence(app);
+    publish_focus_state(app);
+    check_icon_cache(app);
+    check_title_contexts(app);
+    check_trash(app);
+}
+
+/// End a timed focus session whose planned duration is up.
+fn end_expired_focus_session(app: &AppHandle) {
+    let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() else {
+        return;
+    };
+    match focus_manager.end_expired_session(current_timestamp()) {
+        Ok(Some(_)) => on_focus_session_completed(app),
+        Ok(None) => {}
+        Err(e) => error!("Failed to end expired focus session: {e}"),
+    }
+}
@@ -0,0 +1,301 @@
+//! Secrets kept in the platform keychain instead of the settings table: the
+//! login keychain on macOS (through the Security framework) and the Secret
+//! Service on Linux (through libsecret's `secret-tool`). Each secret is
+//! stored under the "foxus" service, named by its key.
+//!
+//! Integrations read their secret when they need it rather than with the
+//! rest of their settings, so scheduler ticks don't hit the keychain.
+
+use crate::accountability::WEBHOOK_URL_SECRET;
+use crate::classifier::llm::API_KEY_SECRET;
+use crate::email_report::PASSWORD_SECRET as SMTP_PASSWORD_SECRET;
+use crate::error::AppError;
+use crate::models::Setting;
+use crate::mqtt::PASSWORD_SECRET as MQTT_PASSWORD_SECRET;
+use crate::time_export::{CLOCKIFY_KEY_SECRET, TOGGL_TOKEN_SECRET};
+use log::{info, warn};
+use rusqlite::Connection;
+
+#[cfg(not(test))]
+use keychain as backend;
+#[cfg(test)]
+use memory as backend;
+
+/// Every secret, so none is left behind in the settings table. Each keeps
+/// the settings key it had before moving to the keychain.
+const SECRETS: &[&str] = &[
+    WEBHOOK_URL_SECRET,
+    API_KEY_SECRET,
+    SMTP_PASSWORD_SECRET,
+    MQTT_PASSWORD_SECRET,
+    TOGGL_TOKEN_SECRET,
+    CLOCKIFY_KEY_SECRET,
+];
+
+/// The secret stored under `key`, if any.
+pub fn get(key: &str) -> Result<Option<String>, AppError> {
+    backend::get(key)
+}
+
+/// Store `value` under `key`, replacing any secret already there.
+pub fn set(key: &str, value: &str) -> Result<(), AppError> {
+    backend::set(key, value)
+}
+
+/// Remove the secret under `key`. Removing a missing secret is fine.
+pub fn delete(key: &str) -> Result<(), AppError> {
+    backend::delete(key)
+}
+
+/// Like `get`, for callers that carry on without the secret: a keychain
+/// that can't be read is logged and counts as no secret.
+pub fn read(key: &str) -> Option<String> {
+    get(key).unwrap_or_else(|e| {
+        warn!("Failed to read {key} from the keychain: {e}");
+        None
+    })
+}
+
+/// Apply a secret from a settings form: `None` keeps the stored one, a
+/// blank value removes it, anything else replaces it. Returns whether a
+/// secret is stored afterwards.
+pub fn update(key: &str, value: Option<&str>) -> Result<bool, AppError> {
+    match value {
+        None => Ok(get(key)?.is_some()),
+        Some(value) if value.trim().is_empty() => delete(key).map(|()| false),
+        Some(value) => set(key, value).map(|()| true),
+    }
+}
+
+/// Move secrets saved in the settings table by older versions into the
+/// keychain. Each is deleted from the table once stored; one that can't be
+/// stored stays there and is tried again on the next launch.
+pub fn move_from_settings(conn: &Connection) {
+    for key in SECRETS {
+        let moved = Setting::get(conn, key)
+            .map_err(AppError::from)
+            .and_then(|value| match value {
+                Some(value) => {
+                    set(key, &value)?;
+                    Setting::delete(conn, key)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            });
+        match moved {
+            Ok(true) => info!("Moved {key} from settings to the keychain"),
+            Ok(false) => {}
+            Err(e) => warn!("Failed to move {key} to the keychain: {e}"),
+        }
+    }
+}
+
+#[cfg(not(test))]
+mod keychain {
+    use crate::error::AppError;
+    #[cfg(target_os = "macos")]
+    use security_framework::passwords;
+    #[cfg(target_os = "linux")]
+    use std::process::{Command, Output};
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    const SERVICE: &str = "foxus";
+
+    /// `errSecItemNotFound`: no item matches.
+    #[cfg(target_os = "macos")]
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    #[cfg(target_os = "macos")]
+    pub fn get(key: &str) -> Result<Option<String>, AppError> {
+        match passwords::get_generic_password(SERVICE, key) {
+            Ok(secret) => Ok(Some(String::from_utf8_lossy(&secret).into_owned())),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(failure("read", e)),
+        }
+    }
+
+    /// Goes through the Security framework rather than `security`, which
+    /// would need the secret on its command line.
+    #[cfg(target_os = "macos")]
+    pub fn set(key: &str, value: &str) -> Result<(), AppError> {
+        passwords::set_generic_password(SERVICE, key, value.as_bytes())
+            .map_err(|e| failure("store", e))
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn delete(key: &str) -> Result<(), AppError> {
+        match passwords::delete_generic_password(SERVICE, key) {
+            Ok(()) => Ok(()),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(()),
+            Err(e) => Err(failure("delete", e)),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn failure(action: &str, e: security_framework::base::Error) -> AppError {
+        AppError::Internal(format!("failed to {action} keychain secret: {e}"))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn get(key: &str) -> Result<Option<String>, AppError> {
+        let output =
+            run(Command::new("secret-tool").args(["lookup", "service", SERVICE, "key", key]))?;
+        if output.status.success() {
+            return Ok(Some(secret_from(&output.stdout)));
+        }
+        // Nothing matching exits with an error but no message
+        if output.stderr.is_empty() {
+            Ok(None)
+        } else {
+            Err(failure("read", &output))
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set(key: &str, value: &str) -> Result<(), AppError> {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        // The secret goes in on stdin, so it never shows up in the process list
+        let mut child = Command::new("secret-tool")
+            .args(["store", "--label", &format!("Foxus {key}")])
+            .args(["service", SERVICE, "key", key])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(unavailable)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(value.as_bytes()).map_err(unavailable)?;
+        }
+        let output = child.wait_with_output().map_err(unavailable)?;
+        if !output.status.success() {
+            return Err(failure("store", &output));
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn delete(key: &str) -> Result<(), AppError> {
+        let output =
+            run(Command::new("secret-tool").args(["clear", "service", SERVICE, "key", key]))?;
+        if output.status.success() || output.stderr.is_empty() {
+            Ok(())
+        } else {
+            Err(failure("delete", &output))
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    pub fn get(_key: &str) -> Result<Option<String>, AppError> {
+        Err(unsupported())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    pub fn set(_key: &str, _value: &str) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    pub fn delete(_key: &str) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    fn unsupported() -> AppError {
+        AppError::Internal("no keychain is supported on this platform".into())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn run(command: &mut Command) -> Result<Output, AppError> {
+        command.output().map_err(unavailable)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn unavailable(e: std::io::Error) -> AppError {
+        AppError::Internal(format!("keychain unavailable: {e}"))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn failure(action: &str, output: &Output) -> AppError {
+        AppError::Internal(format!(
+            "failed to {action} keychain secret: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+
+    /// The secret printed by a lookup, without the trailing newline.
+    #[cfg(target_os = "linux")]
+    fn secret_from(stdout: &[u8]) -> String {
+        let secret = String::from_utf8_lossy(stdout);
+        secret.strip_suffix('\n').unwrap_or(&secret).to_string()
+    }
+}
+
+/// Stands in for the keychain in tests, one per test thread.
+#[cfg(test)]
+mod memory {
+    use crate::error::AppError;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    thread_local! {
+        static SECRETS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    }
+
+    #[expect(
+        clippy::unnecessary_wraps,
+        reason = "Matches the keychain backend's signature"
+    )]
+    pub fn get(key: &str) -> Result<Option<String>, AppError> {
+        Ok(SECRETS.with(|secrets| secrets.borrow().get(key).cloned()))
+    }
+
+    #[expect(
+        clippy::unnecessary_wraps,
+        reason = "Matches the keychain backend's signature"
+    )]
+    pub fn set(key: &str, value: &str) -> Result<(), AppError> {
+        SECRETS.with(|secrets| secrets.borrow_mut().insert(key.into(), value.into()));
+        Ok(())
+    }
+
+    #[expect(
+        clippy::unnecessary_wraps,
+        reason = "Matches the keychain backend's signature"
+    )]
+    pub fn delete(key: &str) -> Result<(), AppError> {
+        SECRETS.with(|secrets| secrets.borrow_mut().remove(key));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_update_keeps_replaces_and_clears() {
+        assert!(!update(MQTT_PASSWORD_SECRET, None).unwrap());
+        assert!(update(MQTT_PASSWORD_SECRET, Some("s3cret")).unwrap());
+        assert!(update(MQTT_PASSWORD_SECRET, None).unwrap());
+        assert_eq!(read(MQTT_PASSWORD_SECRET).as_deref(), Some("s3cret"));
+
+        assert!(!update(MQTT_PASSWORD_SECRET, Some(" ")).unwrap());
+        assert_eq!(read(MQTT_PASSWORD_SECRET), None);
+    }
+
+    #[test]
+    fn test_secrets_move_out_of_settings() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        Setting::set(conn, TOGGL_TOKEN_SECRET, "token").unwrap();
+        Setting::set(conn, "mqtt.host", "homeassistant.local").unwrap();
+
+        move_from_settings(conn);
+        assert_eq!(read(TOGGL_TOKEN_SECRET).as_deref(), Some("token"));
+        assert_eq!(Setting::get(conn, TOGGL_TOKEN_SECRET).unwrap(), None);
+        assert!(Setting::get(conn, "mqtt.host").unwrap().is_some());
+    }
+}
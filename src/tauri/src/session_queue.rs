@@ -0,0 +1,227 @@
+//! Back-to-back focus blocks: "after this 50-minute block, a 10-minute break,
+//! then another 50". Queued blocks run in order on the scheduler thread,
+//! each starting once nothing else is running.
+
+use crate::audit_log::Origin;
+use crate::db::{with_connection, Database};
+use crate::focus::FocusManager;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{EnforcedBreak, Setting};
+use crate::tray::{format_duration, refresh_tray};
+use log::{error, info};
+use rusqlite::{params, Connection, Result};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// Settings key holding when a queued break ends, while one is running.
+const BREAK_ENDS_AT_KEY: &str = "session_queue.break_ends_at";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Focus,
+    Break,
+}
+
+impl BlockKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Focus => "focus",
+            Self::Break => "break",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "focus" => Some(Self::Focus),
+            "break" => Some(Self::Break),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedBlock {
+    pub id: i64,
+    pub kind: BlockKind,
+    pub duration_secs: i32,
+    /// Distraction budget of a focus block; 0 for breaks.
+    pub budget_secs: i32,
+}
+
+impl QueuedBlock {
+    /// Tray label such as "Up Next: Focus 50m".
+    pub fn label(&self) -> String {
+        let kind = match self.kind {
+            BlockKind::Focus => "Focus",
+            BlockKind::Break => "Break",
+        };
+        format!("Up Next: {kind} {}", format_duration(self.duration_secs))
+    }
+}
+
+/// Add a block to the end of the queue.
+pub fn push(
+    conn: &Connection,
+    kind: BlockKind,
+    duration_secs: i32,
+    budget_secs: i32,
+) -> Result<QueuedBlock> {
+    conn.execute(
+        "INSERT INTO session_queue (kind, duration_secs, budget_secs) VALUES (?1, ?2, ?3)",
+        params![kind.as_str(), duration_secs, budget_secs],
+    )?;
+    Ok(QueuedBlock {
+        id: conn.last_insert_rowid(),
+        kind,
+        duration_secs,
+        budget_secs,
+    })
+}
+
+/// Queued blocks in the order they will run.
+pub fn find_all(conn: &Connection) -> Result<Vec<QueuedBlock>> {
+    let mut stmt =
+        conn.prepare("SELECT id, kind, duration_secs, budget_secs FROM session_queue ORDER BY id")?;
+    let rows = stmt.query_map([], |row| {
+        let kind: String = row.get(1)?;
+        Ok(QueuedBlock {
+            id: row.get(0)?,
+            kind: BlockKind::parse(&kind).unwrap_or(BlockKind::Break),
+            duration_secs: row.get(2)?,
+            budget_secs: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Drop every queued block and any queued break that is running.
+pub fn clear(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM session_queue", [])?;
+    Setting::delete(conn, BREAK_ENDS_AT_KEY)
+}
+
+/// When the running queued break ends, if one is running at `now`.
+pub fn break_ends_at(conn: &Connection, now: i64) -> Result<Option<i64>> {
+    Ok(Setting::get(conn, BREAK_ENDS_AT_KEY)?
+        .and_then(|v| v.parse().ok())
+        .filter(|ends_at| *ends_at > now))
+}
+
+/// Take the next focus block to start at `now`, if one is due.
+///
+/// Call only while no session is running. A queued break at the front is
+/// started here, and holds back the rest of the queue until it's over.
+pub fn take_due(conn: &Connection, now: i64) -> Result<Option<QueuedBlock>> {
+    if break_ends_at(conn, now)?.is_some() {
+        return Ok(None);
+    }
+    let Some(next) = find_all(conn)?.into_iter().next() else {
+        return Ok(None);
+    };
+    conn.execute("DELETE FROM session_queue WHERE id = ?1", params![next.id])?;
+    match next.kind {
+        BlockKind::Break => {
+            Setting::set(
+                conn,
+                BREAK_ENDS_AT_KEY,
+                &(now + i64::from(next.duration_secs)).to_string(),
+            )?;
+            Ok(None)
+        }
+        BlockKind::Focus => {
+            Setting::delete(conn, BREAK_ENDS_AT_KEY)?;
+            Ok(Some(next))
+        }
+    }
+}
+
+/// Start the next queued block once the session and any break before it are over.
+pub fn check_session_queue(app: &AppHandle) {
+    let (Some(db), Some(focus_manager)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<FocusManager>>(),
+    ) else {
+        return;
+    };
+    if focus_manager.get_state().is_ok_and(|s| s.active) {
+        return;
+    }
+
+    let now = current_timestamp();
+    let due = with_connection(&db, |conn| {
+        if EnforcedBreak::find_active(conn)?.is_some() {
+            return Ok(None);
+        }
+        take_due(conn, now)
+    });
+    let block = match due {
+        Ok(Some(block)) => block,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to read the session queue: {e}");
+            return;
+        }
+    };
+
+    info!("Starting queued {}s focus block", block.duration_secs);
+    if let Err(e) = focus_manager.start_timed_session(
+        block.budget_secs,
+        Some(block.duration_secs),
+        false,
+        &[],
+        Origin::Schedule,
+    ) {
+        error!("Failed to start queued focus block: {e}");
+    }
+    refresh_tray(app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_760_000_000;
+
+    #[test]
+    fn test_blocks_run_in_order_with_breaks_between() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        push(conn, BlockKind::Break, 600, 0).unwrap();
+        let focus = push(conn, BlockKind::Focus, 3000, 300).unwrap();
+
+        // The break starts and holds back the focus block
+        assert_eq!(take_due(conn, NOW).unwrap(), None);
+        assert_eq!(break_ends_at(conn, NOW).unwrap(), Some(NOW + 600));
+        assert_eq!(take_due(conn, NOW + 599).unwrap(), None);
+
+        assert_eq!(take_due(conn, NOW + 600).unwrap(), Some(focus));
+        assert_eq!(break_ends_at(conn, NOW + 600).unwrap(), None);
+        assert!(find_all(conn).unwrap().is_empty());
+        assert_eq!(take_due(conn, NOW + 600).unwrap(), None);
+    }
+
+    #[test]
+    fn test_clear_ends_a_queued_break() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        push(conn, BlockKind::Break, 600, 0).unwrap();
+        push(conn, BlockKind::Focus, 3000, 300).unwrap();
+        take_due(conn, NOW).unwrap();
+
+        clear(conn).unwrap();
+        assert!(find_all(conn).unwrap().is_empty());
+        assert_eq!(break_ends_at(conn, NOW).unwrap(), None);
+    }
+
+    #[test]
+    fn test_label() {
+        let block = QueuedBlock {
+            id: 1,
+            kind: BlockKind::Focus,
+            duration_secs: 3000,
+            budget_secs: 300,
+        };
+        assert_eq!(block.label(), "Up Next: Focus 50m");
+    }
+}
@@ -0,0 +1,324 @@
+//! Anonymized static HTML dashboard for sharing progress publicly. Only
+//! daily and weekly totals go in; no app names, titles, URLs or notes.
+
+use crate::away_mode::AwayPeriod;
+use crate::error::AppError;
+use crate::local_time::{day_bounds, week_start};
+use crate::models::{Activity, FocusSession};
+use crate::tray::format_duration;
+use chrono::{Days, NaiveDate};
+use rusqlite::{Connection, Result};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_FILE_NAME: &str = "index.html";
+
+/// Weeks shown in the heatmap, the current one included.
+const HEATMAP_WEEKS: u64 = 12;
+
+/// Weeks listed in the totals table, the current one included.
+const TOTALS_WEEKS: usize = 4;
+
+/// Productive time a day needs to count towards a streak.
+const STREAK_MIN_SECS: i32 = 3600;
+
+/// Productive seconds at which each heatmap shade starts.
+const HEATMAP_LEVELS: [i32; 4] = [1, 3600, 3 * 3600, 5 * 3600];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayTotals {
+    pub date: NaiveDate,
+    pub productive_secs: i32,
+    pub neutral_secs: i32,
+    pub distracting_secs: i32,
+    /// Spent in away mode; neither counts towards a streak nor breaks one.
+    pub away: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeekTotals {
+    /// First day of the week.
+    pub start_date: NaiveDate,
+    pub productive_secs: i64,
+    pub neutral_secs: i64,
+    pub distracting_secs: i64,
+    pub focus_secs: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub generated_on: NaiveDate,
+    /// Every day from the start of the week `HEATMAP_WEEKS` weeks back
+    /// through today.
+    pub days: Vec<DayTotals>,
+    /// Most recent last.
+    pub weeks: Vec<WeekTotals>,
+    pub current_streak: usize,
+    pub longest_streak: usize,
+}
+
+/// Days in a row, ending today, with at least `STREAK_MIN_SECS` productive.
+/// Today doesn't break the streak while it's still under way, and days away
+/// are passed over.
+fn current_streak(days: &[DayTotals]) -> usize {
+    let counts = |day: &DayTotals| day.productive_secs >= STREAK_MIN_SECS;
+    let mut rest = days;
+    let mut streak = 0;
+    if let Some((today, earlier)) = days.split_last() {
+        rest = earlier;
+        streak = usize::from(!today.away && counts(today));
+    }
+    streak
+        + rest
+            .iter()
+            .rev()
+            .filter(|day| !day.away)
+            .take_while(|day| counts(day))
+            .count()
+}
+
+fn longest_streak(days: &[DayTotals]) -> usize {
+    let worked: Vec<&DayTotals> = days.iter().filter(|day| !day.away).collect();
+    worked
+        .split(|day| day.productive_secs < STREAK_MIN_SECS)
+        .map(<[&DayTotals]>::len)
+        .max()
+        .unwrap_or(0)
+}
+
+fn heatmap_level(productive_secs: i32) -> usize {
+    HEATMAP_LEVELS
+        .iter()
+        .filter(|&&min| productive_secs >= min)
+        .count()
+}
+
+impl Snapshot {
+    pub fn compile(conn: &Connection, today: NaiveDate) -> Result<Self> {
+        let this_week = week_start(conn)?.week_of(today);
+        let first_day = this_week
+            .checked_sub_days(Days::new((HEATMAP_WEEKS - 1) * 7))
+            .unwrap_or(this_week);
+        let away = AwayPeriod::dates_between(conn, first_day, today)?;
+
+        let mut days = Vec::new();
+        for date in first_day.iter_days().take_while(|date| *date <= today) {
+            let (start, end) = day_bounds(date);
+            let (productive_secs, neutral_secs, distracting_secs) =
+                Activity::productivity_totals(conn, start, end)?;
+            days.push(DayTotals {
+                date,
+                productive_secs,
+                neutral_secs,
+                distracting_secs,
+                away: away.contains(&date),
+            });
+        }
+
+        let mut weeks = Vec::new();
+        for week in days.chunks(7).rev().take(TOTALS_WEEKS).rev() {
+            let (Some(first), Some(last)) = (week.first(), week.last()) else {
+                continue;
+            };
+            let (start, _) = day_bounds(first.date);
+            let (_, end) = day_bounds(last.date);
+            let (_, focus_secs) = FocusSession::completed_totals(conn, start, end)?;
+            let sum = |secs: fn(&DayTotals) -> i32| week.iter().map(|d| i64::from(secs(d))).sum();
+            weeks.push(WeekTotals {
+                start_date: first.date,
+                productive_secs: sum(|d| d.productive_secs),
+                neutral_secs: sum(|d| d.neutral_secs),
+                distracting_secs: sum(|d| d.distracting_secs),
+                focus_secs,
+            });
+        }
+
+        Ok(Self {
+            generated_on: today,
+            current_streak: current_streak(&days),
+            longest_streak: longest_streak(&days),
+            days,
+            weeks,
+        })
+    }
+
+    /// Self-contained page with inline styles and no scripts.
+    pub fn to_html(&self) -> String {
+        let duration = |secs: i64| format_duration(i32::try_from(secs).unwrap_or(i32::MAX));
+
+        let mut cells = String::new();
+        for day in &self.days {
+            let _ = write!(
+                cells,
+                "<div class=\"l{}\" title=\"{}: {}\"></div>",
+                heatmap_level(day.productive_secs),
+                day.date.format("%Y-%m-%d"),
+                format_duration(day.productive_secs),
+            );
+        }
+
+        let mut rows = String::new();
+        for week in &self.weeks {
+            let _ = write!(
+                rows,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                week.start_date.format("%b %-d"),
+                duration(week.productive_secs),
+                duration(week.neutral_secs),
+                duration(week.distracting_secs),
+                duration(week.focus_secs),
+            );
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Focus progress</title>
+<style>
+body {{ font-family: system-ui, sans-serif; color: #1f2328; background: #fff; margin: 0; }}
+main {{ max-width: 720px; margin: 0 auto; padding: 32px 16px; }}
+.muted {{ color: #656d76; }}
+.streaks {{ display: flex; gap: 32px; }}
+.streaks strong {{ font-size: 2em; display: block; }}
+.heatmap {{ display: grid; grid-template-rows: repeat(7, 12px); grid-auto-flow: column; grid-auto-columns: 12px; gap: 3px; }}
+.heatmap div {{ border-radius: 2px; }}
+.l0 {{ background: #ebedf0; }}
+.l1 {{ background: #ffd8a8; }}
+.l2 {{ background: #ffa94d; }}
+.l3 {{ background: #f76707; }}
+.l4 {{ background: #c2410c; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: 6px 8px; border-bottom: 1px solid #d0d7de; }}
+</style>
+</head>
+<body>
+<main>
+<h1>Focus progress</h1>
+<p class="muted">Updated {generated_on}</p>
+<section class="streaks">
+<div><strong>{current_streak}</strong>day streak</div>
+<div><strong>{longest_streak}</strong>longest streak in {heatmap_weeks} weeks</div>
+</section>
+<h2>Productive time, last {heatmap_weeks} weeks</h2>
+<div class="heatmap">{cells}</div>
+<h2>Weekly totals</h2>
+<table>
+<thead><tr><th>Week of</th><th>Productive</th><th>Neutral</th><th>Distracting</th><th>Focus sessions</th></tr></thead>
+<tbody>{rows}</tbody>
+</table>
+<p class="muted">Made with Foxus</p>
+</main>
+</body>
+</html>
+"#,
+            generated_on = self.generated_on.format("%Y-%m-%d"),
+            current_streak = self.current_streak,
+            longest_streak = self.longest_streak,
+            heatmap_weeks = HEATMAP_WEEKS,
+        )
+    }
+}
+
+/// Write the dashboard to `index.html` in `folder`. Returns the path.
+pub fn publish(conn: &Connection, folder: &Path, today: NaiveDate) -> Result<PathBuf, AppError> {
+    let html = Snapshot::compile(conn, today)?.to_html();
+    let path = folder.join(SNAPSHOT_FILE_NAME);
+    // Write then rename so a web server never serves a half-written page
+    let tmp = path.with_extension("html.tmp");
+    std::fs::write(&tmp, html)
+        .and_then(|()| std::fs::rename(&tmp, &path))
+        .map_err(|e| AppError::Internal(format!("failed to write snapshot: {e}")))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_time::{set_week_start, WeekStart};
+    use crate::models::Category;
+    use crate::test_utils::setup_test_db;
+
+    fn days(productive: &[i32]) -> Vec<DayTotals> {
+        let start = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        start
+            .iter_days()
+            .zip(productive)
+            .map(|(date, &productive_secs)| DayTotals {
+                date,
+                productive_secs,
+                neutral_secs: 0,
+                distracting_secs: 0,
+                away: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_streaks() {
+        let hour = STREAK_MIN_SECS;
+        assert_eq!(current_streak(&days(&[hour, 0, hour, hour, hour])), 3);
+        // An unfinished today doesn't break the streak
+        assert_eq!(current_streak(&days(&[hour, hour, 60])), 2);
+        assert_eq!(current_streak(&days(&[hour, 0, 60])), 0);
+        assert_eq!(current_streak(&[]), 0);
+
+        assert_eq!(longest_streak(&days(&[hour, hour, hour, 0, hour])), 3);
+        assert_eq!(longest_streak(&days(&[0, 60])), 0);
+
+        // Days away neither break a streak nor add to it
+        let mut holiday = days(&[hour, 0, 0, hour, 60]);
+        for day in holiday.iter_mut().skip(1).take(2) {
+            day.away = true;
+        }
+        assert_eq!(current_streak(&holiday), 2);
+        assert_eq!(longest_streak(&holiday), 2);
+    }
+
+    #[test]
+    fn test_heatmap_level() {
+        assert_eq!(heatmap_level(0), 0);
+        assert_eq!(heatmap_level(600), 1);
+        assert_eq!(heatmap_level(2 * 3600), 2);
+        assert_eq!(heatmap_level(8 * 3600), 4);
+    }
+
+    #[test]
+    fn test_publish_leaves_out_titles_and_urls() {
+        let (db, dir) = setup_test_db();
+        let conn = db.connection();
+        // A Wednesday, so the current week is partial
+        let today = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+        set_week_start(conn, Some(WeekStart::Monday)).unwrap();
+
+        let coding = Category::find_all(conn)
+            .unwrap()
+            .into_iter()
+            .find(|c| c.name == "Coding")
+            .unwrap();
+        let mut activity = Activity::new(
+            day_bounds(today).0 + 3600,
+            7200,
+            "app",
+            Some("SecretApp"),
+            Some("secret-plan.rs"),
+        );
+        activity.url = Some("https://secret.example.com".to_string());
+        activity.category_id = Some(coding.id);
+        activity.save(conn).unwrap();
+
+        let snapshot = Snapshot::compile(conn, today).unwrap();
+        let heatmap_days = usize::try_from((HEATMAP_WEEKS - 1) * 7 + 3).unwrap();
+        assert_eq!(snapshot.days.len(), heatmap_days);
+        assert_eq!(snapshot.weeks.len(), TOTALS_WEEKS);
+        assert_eq!(snapshot.weeks.last().unwrap().productive_secs, 7200);
+        assert_eq!(snapshot.current_streak, 1);
+
+        let path = publish(conn, dir.path(), today).unwrap();
+        let html = std::fs::read_to_string(path).unwrap();
+        assert!(html.contains("2h 0m"));
+        assert!(!html.to_lowercase().contains("secret"));
+    }
+}
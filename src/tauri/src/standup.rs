@@ -0,0 +1,192 @@
+use crate::local_time::day_bounds;
+use crate::models::{Activity, Category};
+use crate::title_context::{find_ticket, title_segments};
+use crate::tray::format_duration;
+use chrono::NaiveDate;
+use rusqlite::{Connection, Result};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Clusters with less productive time than this are left out as noise.
+const MIN_ITEM_SECS: i32 = 5 * 60;
+
+/// Keep the list short enough to paste into a standup.
+const MAX_ITEMS: usize = 6;
+
+/// Productive time spent on one project, ticket, or window-title cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandupItem {
+    pub label: String,
+    pub duration_secs: i32,
+    /// Apps that contributed to this cluster, sorted by name.
+    pub apps: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StandupSummary {
+    /// Local date in YYYY-MM-DD format.
+    pub date: String,
+    pub items: Vec<StandupItem>,
+}
+
+impl StandupSummary {
+    /// Bullet list ready to paste, e.g. "- PROJ-123 (1h 20m)".
+    pub fn to_text(&self) -> String {
+        self.items
+            .iter()
+            .map(|item| format!("- {} ({})", item.label, format_duration(item.duration_secs)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Pick the cluster an activity belongs to: a ticket if the title mentions
+/// one, otherwise the project-like part of the title, otherwise the app.
+fn cluster_label(app_name: &str, title: Option<&str>) -> String {
+    let Some(title) = title.map(str::trim).filter(|t| !t.is_empty()) else {
+        return app_name.to_string();
+    };
+    if let Some(ticket) = find_ticket(title) {
+        return ticket.to_string();
+    }
+
+    // Editors title windows "file - project - App", so the last segment
+    // that isn't the app's own name is usually the project
+    title_segments(app_name, title)
+        .last()
+        .map_or_else(|| app_name.to_string(), ToString::to_string)
+}
+
+/// Group productive activities into standup items, largest first.
+pub fn summarize(
+    date: &str,
+    activities: &[Activity],
+    productive_categories: &HashSet<i64>,
+) -> StandupSummary {
+    let mut clusters: HashMap<String, (i32, BTreeSet<String>)> = HashMap::new();
+
+    for activity in activities {
+        let productive = activity
+            .category_id
+            .is_some_and(|id| productive_categories.contains(&id));
+        let Some(app_name) = activity.app_name.as_deref().filter(|_| productive) else {
+            continue;
+        };
+
+        let label = cluster_label(app_name, activity.window_title.as_deref());
+        let (secs, apps) = clusters.entry(label).or_default();
+        *secs += activity.duration_secs;
+        apps.insert(app_name.to_string());
+    }
+
+    let mut items: Vec<StandupItem> = clusters
+        .into_iter()
+        .filter(|(_, (secs, _))| *secs >= MIN_ITEM_SECS)
+        .map(|(label, (duration_secs, apps))| StandupItem {
+            label,
+            duration_secs,
+            apps: apps.into_iter().collect(),
+        })
+        .collect();
+    items.sort_by(|a, b| {
+        b.duration_secs
+            .cmp(&a.duration_secs)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    items.truncate(MAX_ITEMS);
+
+    StandupSummary {
+        date: date.to_string(),
+        items,
+    }
+}
+
+/// Build the standup summary for a local calendar day.
+pub fn standup_summary(conn: &Connection, date: NaiveDate) -> Result<StandupSummary> {
+    let (start, end) = day_bounds(date);
+    let activities = Activity::find_in_range(conn, start, end)?;
+    let productive_categories = Category::find_all(conn)?
+        .into_iter()
+        .filter(|c| c.productivity > 0)
+        .map(|c| c.id)
+        .collect();
+
+    Ok(summarize(
+        &date.format("%Y-%m-%d").to_string(),
+        &activities,
+        &productive_categories,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRODUCTIVE: i64 = 1;
+    const DISTRACTING: i64 = 2;
+
+    fn activity(app: &str, title: &str, secs: i32, category_id: i64) -> Activity {
+        let mut activity = Activity::new(0, secs, "app", Some(app), Some(title));
+        activity.category_id = Some(category_id);
+        activity
+    }
+
+    #[test]
+    fn test_cluster_label() {
+        assert_eq!(
+            cluster_label(
+                "Code",
+                Some("main.rs \u{2014} foxus \u{2014} Visual Studio Code")
+            ),
+            "foxus"
+        );
+        assert_eq!(
+            cluster_label(
+                "Firefox",
+                Some("ABC-9: Flaky test - Jira - Mozilla Firefox")
+            ),
+            "ABC-9"
+        );
+        assert_eq!(cluster_label("Terminal", None), "Terminal");
+        assert_eq!(cluster_label("Terminal", Some("Terminal")), "Terminal");
+    }
+
+    #[test]
+    fn test_summarize_groups_productive_time() {
+        let productive = HashSet::from([PRODUCTIVE]);
+        let activities = [
+            activity("Code", "main.rs - foxus - Code", 1800, PRODUCTIVE),
+            activity(
+                "Code",
+                "lib.rs \u{2014} foxus \u{2014} Code",
+                1200,
+                PRODUCTIVE,
+            ),
+            activity("Chrome", "PROJ-7 Review - Google Chrome", 600, PRODUCTIVE),
+            activity(
+                "Chrome",
+                "Videos - YouTube - Google Chrome",
+                3600,
+                DISTRACTING,
+            ),
+            activity("Code", "notes.md - scratch - Code", 60, PRODUCTIVE),
+        ];
+
+        let summary = summarize("2026-01-14", &activities, &productive);
+        assert_eq!(
+            summary.items,
+            vec![
+                StandupItem {
+                    label: "foxus".into(),
+                    duration_secs: 3000,
+                    apps: vec!["Code".into()],
+                },
+                StandupItem {
+                    label: "PROJ-7".into(),
+                    duration_secs: 600,
+                    apps: vec!["Chrome".into()],
+                },
+            ]
+        );
+        assert_eq!(summary.to_text(), "- foxus (50m)\n- PROJ-7 (10m)");
+    }
+}
@@ -0,0 +1,162 @@
+use crate::error::AppError;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, TerminalContext};
+use crate::tracking_hours::is_tracking_time;
+use crate::validation::validate_terminal_path;
+use rusqlite::{Connection, Result};
+use std::collections::HashMap;
+
+/// App name fragments (lowercase) that identify terminal emulators.
+const TERMINAL_APPS: &[&str] = &[
+    "terminal",
+    "iterm",
+    "alacritty",
+    "kitty",
+    "wezterm",
+    "ghostty",
+    "konsole",
+    "tilix",
+    "xterm",
+    "warp",
+];
+
+/// A context older than this no longer says where a terminal is. The hook
+/// re-reports at least every 10 minutes, so this only drops stale shells.
+const MAX_CONTEXT_AGE_SECS: i64 = 30 * 60;
+
+pub fn is_terminal_app(app_name: &str) -> bool {
+    let app_name = app_name.to_lowercase();
+    TERMINAL_APPS.iter().any(|t| app_name.contains(t))
+}
+
+/// Store a working directory reported by the shell hook. Returns the new
+/// row's id.
+pub fn record(conn: &Connection, cwd: &str, repo: Option<&str>, now: i64) -> Result<i64, AppError> {
+    let cwd = validate_terminal_path("cwd", cwd)?;
+    let repo = repo
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|r| validate_terminal_path("repo", r))
+        .transpose()?;
+    let mut context = TerminalContext::new(now, cwd, repo);
+    context.save(conn)?;
+    Ok(context.id.unwrap_or_default())
+}
+
+/// `record` at the current time. Outside the tracking hours or while away
+/// nothing is stored and there is no id.
+pub fn record_now(
+    conn: &Connection,
+    cwd: &str,
+    repo: Option<&str>,
+) -> Result<Option<i64>, AppError> {
+    if !is_tracking_time(conn)? {
+        return Ok(None);
+    }
+    record(conn, cwd, repo, current_timestamp()).map(Some)
+}
+
+/// Split terminal activities by the project of the latest context reported
+/// before each one. Time with no recent context goes under `None`.
+fn attribute(activities: &[Activity], contexts: &[TerminalContext]) -> Vec<(Option<String>, i32)> {
+    let mut totals: HashMap<Option<String>, i32> = HashMap::new();
+    for activity in activities {
+        if activity.source != "app" || !activity.app_name.as_deref().is_some_and(is_terminal_app) {
+            continue;
+        }
+        let project = contexts
+            .iter()
+            .take_while(|c| c.timestamp <= activity.timestamp)
+            .last()
+            .filter(|c| activity.timestamp - c.timestamp <= MAX_CONTEXT_AGE_SECS)
+            .map(|c| c.project().to_string());
+        *totals.entry(project).or_default() += activity.duration_secs;
+    }
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+/// Terminal seconds per project over `[start, end)`, most time first.
+pub fn project_totals(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+) -> Result<Vec<(Option<String>, i32)>> {
+    let activities = Activity::find_in_range(conn, start, end)?;
+    let contexts = TerminalContext::find_in_range(conn, start - MAX_CONTEXT_AGE_SECS, end)?;
+    Ok(attribute(&activities, &contexts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::away_mode::AwayPeriod;
+    use crate::test_utils::setup_test_db;
+
+    fn terminal(timestamp: i64, secs: i32) -> Activity {
+        Activity::new(timestamp, secs, "app", Some("Alacritty"), Some("zsh"))
+    }
+
+    #[test]
+    fn test_is_terminal_app() {
+        assert!(is_terminal_app("iTerm2"));
+        assert!(is_terminal_app("gnome-terminal-server"));
+        assert!(!is_terminal_app("Firefox"));
+    }
+
+    #[test]
+    fn test_attribute_uses_latest_recent_context() {
+        let contexts = vec![
+            TerminalContext::new(100, "/home/me/foxus/src", Some("/home/me/foxus")),
+            TerminalContext::new(200, "/tmp", None),
+        ];
+        let activities = vec![
+            terminal(50, 10),
+            terminal(150, 20),
+            terminal(160, 5),
+            terminal(250, 30),
+            terminal(200 + MAX_CONTEXT_AGE_SECS + 1, 40),
+            Activity::new(150, 100, "app", Some("Firefox"), None),
+        ];
+
+        assert_eq!(
+            attribute(&activities, &contexts),
+            vec![
+                (None, 50),
+                (Some("/tmp".to_string()), 30),
+                (Some("/home/me/foxus".to_string()), 25),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_and_totals() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        record(conn, "/home/me/foxus", Some(" /home/me/foxus "), 1000).unwrap();
+        assert!(record(conn, "  ", None, 1000).is_err());
+        terminal(1010, 60).save(conn).unwrap();
+
+        assert_eq!(
+            project_totals(conn, 1000, 2000).unwrap(),
+            vec![(Some("/home/me/foxus".to_string()), 60)]
+        );
+    }
+
+    #[test]
+    fn test_record_now_skipped_while_away() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(record_now(conn, "/home/me/foxus", None).unwrap().is_some());
+
+        AwayPeriod::start(conn, "2000-01-01", "2999-01-01").unwrap();
+        assert_eq!(record_now(conn, "/home/me/foxus", None).unwrap(), None);
+        assert_eq!(
+            TerminalContext::find_in_range(conn, 0, i64::MAX)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -5,6 +5,7 @@
 #![cfg(test)]
 
 use crate::db::{migrations, Database};
+use chrono::{FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use tempfile::{tempdir, TempDir};
 
 /// Create a temporary test database with migrations applied.
@@ -18,3 +19,60 @@ pub fn setup_test_db() -> (Database, TempDir) {
     migrations::run(db.connection()).expect("Failed to run migrations on test DB");
     (db, dir)
 }
+
+/// Central European time with its 2026 DST rules (UTC+1, UTC+2 from
+/// 2026-03-29 01:00 UTC until 2026-10-25 01:00 UTC), for DST tests that
+/// shouldn't depend on the machine's time zone.
+#[derive(Debug, Clone, Copy)]
+pub struct CentralEurope;
+
+impl CentralEurope {
+    const SUMMER_START: i64 = 1_774_746_000;
+    const SUMMER_END: i64 = 1_792_890_000;
+
+    fn offset_at(timestamp: i64) -> FixedOffset {
+        let hours = if (Self::SUMMER_START..Self::SUMMER_END).contains(&timestamp) {
+            2
+        } else {
+            1
+        };
+        FixedOffset::east_opt(hours * 3600).expect("offset within a day")
+    }
+}
+
+impl TimeZone for CentralEurope {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Self {
+        Self
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<FixedOffset> {
+        let wall = local.and_utc().timestamp();
+        // An offset fits if it's the one in effect at the instant it gives;
+        // summer time first, as it gives the earlier instant
+        let mut valid = [2, 1]
+            .map(|hours| FixedOffset::east_opt(hours * 3600).expect("offset within a day"))
+            .into_iter()
+            .filter(|offset| {
+                Self::offset_at(wall - i64::from(offset.local_minus_utc())) == *offset
+            });
+        match (valid.next(), valid.next()) {
+            (Some(first), Some(second)) => MappedLocalTime::Ambiguous(first, second),
+            (Some(offset), None) => MappedLocalTime::Single(offset),
+            (None, _) => MappedLocalTime::None,
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        Self::offset_at(utc.and_time(NaiveTime::MIN).and_utc().timestamp())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        Self::offset_at(utc.and_utc().timestamp())
+    }
+}
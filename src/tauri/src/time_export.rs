@@ -0,0 +1,462 @@
+//! Export focus sessions or project time to Toggl Track or Clockify, so time
+//! tracked in Foxus can be billed without re-entering it.
+//!
+//! Each exported entry is remembered per provider and skipped on later
+//! exports, so the same range can be exported again safely. API tokens are
+//! kept in the keychain.
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::local_time::local_day;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{EditorActivity, FocusSession, Note, Setting};
+use crate::secrets;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat};
+use rusqlite::{params, Connection, Result};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TOGGL_WORKSPACE_SETTING: &str = "export.toggl.workspace_id";
+const CLOCKIFY_WORKSPACE_SETTING: &str = "export.clockify.workspace_id";
+
+/// Keychain keys of the provider API tokens.
+pub const TOGGL_TOKEN_SECRET: &str = "export.toggl.api_token";
+pub const CLOCKIFY_KEY_SECRET: &str = "export.clockify.api_key";
+
+const TOGGL_API: &str = "https://api.track.toggl.com/api/v9";
+const CLOCKIFY_API: &str = "https://api.clockify.me/api/v1";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Editor activity this close together is one block of project work.
+const BLOCK_GAP_SECS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Toggl,
+    Clockify,
+}
+
+impl Provider {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "toggl" => Some(Self::Toggl),
+            "clockify" => Some(Self::Clockify),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Toggl => "toggl",
+            Self::Clockify => "clockify",
+        }
+    }
+}
+
+/// What becomes a time entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntrySource {
+    /// One entry per finished focus session.
+    Sessions,
+    /// One entry per block of editor time in a project.
+    Projects,
+}
+
+impl EntrySource {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sessions" => Some(Self::Sessions),
+            "projects" => Some(Self::Projects),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeExportSettings {
+    pub toggl_workspace_id: Option<String>,
+    pub clockify_workspace_id: Option<String>,
+}
+
+impl TimeExportSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(Self {
+            toggl_workspace_id: Setting::get(conn, TOGGL_WORKSPACE_SETTING)?,
+            clockify_workspace_id: Setting::get(conn, CLOCKIFY_WORKSPACE_SETTING)?,
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        for (key, value) in [
+            (TOGGL_WORKSPACE_SETTING, &self.toggl_workspace_id),
+            (CLOCKIFY_WORKSPACE_SETTING, &self.clockify_workspace_id),
+        ] {
+            match value {
+                Some(value) => Setting::set(conn, key, value)?,
+                None => Setting::delete(conn, key)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Credentials and workspace for `provider`, if both are configured.
+    fn credentials(&self, provider: Provider) -> Option<(String, &str)> {
+        let (key, workspace) = match provider {
+            Provider::Toggl => (TOGGL_TOKEN_SECRET, &self.toggl_workspace_id),
+            Provider::Clockify => (CLOCKIFY_KEY_SECRET, &self.clockify_workspace_id),
+        };
+        let workspace = workspace.as_deref()?;
+        Some((secrets::read(key)?, workspace))
+    }
+}
+
+/// A time entry ready to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeEntry {
+    /// Stable id for the tracked time, so it is only exported once.
+    pub key: String,
+    pub start: i64,
+    pub duration_secs: i64,
+    pub description: String,
+}
+
+/// Finished focus sessions that started in `[start, end)`.
+pub fn session_entries(conn: &Connection, start: i64, end: i64) -> Result<Vec<TimeEntry>> {
+    Ok(FocusSession::find_ended_in_range(conn, start, end)?
+        .into_iter()
+        .filter_map(|session| {
+            let id = session.id?;
+            let duration_secs = session.ended_at? - session.started_at;
+            (duration_secs > 0).then(|| TimeEntry {
+                key: format!("session:{id}"),
+                start: session.started_at,
+                duration_secs,
+                description: "Focus session".to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Blocks of editor time per project in `[start, end)`. Blocks still open at
+/// `now` are left out so they aren't exported before they're complete.
+pub fn project_entries(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+    now: i64,
+) -> Result<Vec<TimeEntry>> {
+    let mut blocks: Vec<TimeEntry> = Vec::new();
+    let mut open: Vec<(String, usize)> = Vec::new();
+    for activity in EditorActivity::find_in_range(conn, start, end)? {
+        let Some(project) = activity.project else {
+            continue;
+        };
+        let activity_end = activity.timestamp + i64::from(activity.duration_secs);
+        let block = open
+            .iter()
+            .find(|(p, _)| *p == project)
+            .and_then(|(_, i)| blocks.get_mut(*i))
+            .filter(|b| activity.timestamp <= b.start + b.duration_secs + BLOCK_GAP_SECS);
+        if let Some(block) = block {
+            block.duration_secs = block.duration_secs.max(activity_end - block.start);
+        } else {
+            open.retain(|(p, _)| *p != project);
+            open.push((project.clone(), blocks.len()));
+            blocks.push(TimeEntry {
+                key: format!("project:{project}:{}", activity.timestamp),
+                start: activity.timestamp,
+                duration_secs: i64::from(activity.duration_secs),
+                description: project,
+            });
+        }
+    }
+    blocks.retain(|b| b.start + b.duration_secs + BLOCK_GAP_SECS <= now);
+    Ok(blocks)
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Request body for a new time entry in the provider's API.
+fn entry_payload(provider: Provider, entry: &TimeEntry, workspace: &str) -> Value {
+    match provider {
+        Provider::Toggl => json!({
+            "created_with": "foxus",
+            "description": entry.description,
+            "start": rfc3339(entry.start),
+            "duration": entry.duration_secs,
+            "workspace_id": workspace.parse::<i64>().unwrap_or_default(),
+        }),
+        Provider::Clockify => json!({
+            "description": entry.description,
+            "start": rfc3339(entry.start),
+            "end": rfc3339(entry.start + entry.duration_secs),
+        }),
+    }
+}
+
+/// Create one time entry. Blocks until the request completes.
+fn send(
+    provider: Provider,
+    secret: &str,
+    workspace: &str,
+    entry: &TimeEntry,
+) -> Result<(), AppError> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    let request = match provider {
+        Provider::Toggl => agent
+            .post(&format!("{TOGGL_API}/workspaces/{workspace}/time_entries"))
+            .set(
+                "Authorization",
+                &format!("Basic {}", STANDARD.encode(format!("{secret}:api_token"))),
+            ),
+        Provider::Clockify => agent
+            .post(&format!(
+                "{CLOCKIFY_API}/workspaces/{workspace}/time-entries"
+            ))
+            .set("X-Api-Key", secret),
+    };
+    request
+        .set("User-Agent", concat!("foxus/", env!("CARGO_PKG_VERSION")))
+        .send_json(entry_payload(provider, entry, workspace))
+        .map_err(|e| AppError::Internal(format!("{} request failed: {e}", provider.as_str())))?;
+    Ok(())
+}
+
+fn exported_keys(conn: &Connection, provider: Provider) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT entry_key FROM time_exports WHERE provider = ?1")?;
+    let rows = stmt.query_map(params![provider.as_str()], |row| row.get(0))?;
+    rows.collect()
+}
+
+fn mark_exported(conn: &Connection, provider: Provider, key: &str, now: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO time_exports (provider, entry_key, exported_at) VALUES (?1, ?2, ?3)",
+        params![provider.as_str(), key, now],
+    )?;
+    Ok(())
+}
+
+/// Entries from `source` in `[start, end)` not yet sent to `provider`.
+pub fn pending_entries(
+    conn: &Connection,
+    provider: Provider,
+    source: EntrySource,
+    start: i64,
+    end: i64,
+    now: i64,
+) -> Result<Vec<TimeEntry>> {
+    let entries = match source {
+        EntrySource::Sessions => session_entries(conn, start, end)?,
+        EntrySource::Projects => project_entries(conn, start, end, now)?,
+    };
+    let exported = exported_keys(conn, provider)?;
+    entries
+        .into_iter()
+        .filter(|e| !exported.contains(&e.key))
+        .map(|entry| with_day_notes(conn, entry))
+        .collect()
+}
+
+/// Append the notes on the entry's local day to its description, so the
+/// context jotted in Foxus travels with the exported time.
+fn with_day_notes(conn: &Connection, mut entry: TimeEntry) -> Result<TimeEntry> {
+    let day = local_day(entry.start);
+    let notes: Vec<String> = Note::find_in_days(conn, &day, &day)?
+        .into_iter()
+        .map(|note| note.text)
+        .collect();
+    if !notes.is_empty() {
+        entry.description = format!("{} ({})", entry.description, notes.join("; "));
+    }
+    Ok(entry)
+}
+
+/// Send new entries from `source` in `[start, end)` to `provider`. Returns
+/// how many were created.
+///
+/// Makes one blocking request per entry without holding the database lock,
+/// so call it off the UI and command threads. Entries sent before a failure
+/// stay recorded as exported.
+pub fn export(
+    db: &Arc<Mutex<Database>>,
+    provider: Provider,
+    source: EntrySource,
+    start: i64,
+    end: i64,
+) -> Result<usize, AppError> {
+    let now = current_timestamp();
+    let (settings, entries) = {
+        let db = db.lock().map_err(|_| AppError::LockPoisoned)?;
+        let conn = db.connection();
+        (
+            TimeExportSettings::load(conn)?,
+            pending_entries(conn, provider, source, start, end, now)?,
+        )
+    };
+    let (secret, workspace) =
+        settings
+            .credentials(provider)
+            .ok_or_else(|| AppError::InvalidInput {
+                field: "provider",
+                reason: format!("{} is not configured", provider.as_str()),
+            })?;
+
+    for entry in &entries {
+        send(provider, &secret, workspace, entry)?;
+        let db = db.lock().map_err(|_| AppError::LockPoisoned)?;
+        mark_exported(db.connection(), provider, &entry.key, now)?;
+    }
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn editor(conn: &Connection, timestamp: i64, secs: i32, project: &str) {
+        let mut activity = EditorActivity::new(timestamp, secs, "vscode");
+        activity.project = Some(project.to_string());
+        activity.save(conn).unwrap();
+    }
+
+    #[test]
+    fn test_project_entries_group_blocks() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        editor(conn, 1000, 120, "foxus");
+        editor(conn, 1200, 60, "foxus");
+        editor(conn, 1150, 30, "client");
+        editor(conn, 3000, 60, "foxus");
+
+        let entries = project_entries(conn, 0, 10_000, 10_000).unwrap();
+        let spans: Vec<_> = entries
+            .iter()
+            .map(|e| (e.description.as_str(), e.start, e.duration_secs))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("foxus", 1000, 260),
+                ("client", 1150, 30),
+                ("foxus", 3000, 60)
+            ]
+        );
+
+        // The last block could still grow
+        assert_eq!(project_entries(conn, 0, 10_000, 3100).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_session_entries_and_dedup() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut session = FocusSession::new(300, false);
+        session.started_at = 1000;
+        session.ended_at = Some(2500);
+        session.save(conn).unwrap();
+        let mut active = FocusSession::new(300, false);
+        active.started_at = 3000;
+        active.save(conn).unwrap();
+
+        let pending =
+            pending_entries(conn, Provider::Toggl, EntrySource::Sessions, 0, 5000, 5000).unwrap();
+        assert_eq!(pending.len(), 1);
+        let entry = pending.first().unwrap();
+        assert_eq!((entry.start, entry.duration_secs), (1000, 1500));
+
+        mark_exported(conn, Provider::Toggl, &entry.key, 5000).unwrap();
+        assert!(
+            pending_entries(conn, Provider::Toggl, EntrySource::Sessions, 0, 5000, 5000)
+                .unwrap()
+                .is_empty()
+        );
+        // Tracked separately per provider
+        assert_eq!(
+            pending_entries(
+                conn,
+                Provider::Clockify,
+                EntrySource::Sessions,
+                0,
+                5000,
+                5000
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_entries_carry_day_notes() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let entry = TimeEntry {
+            key: "session:1".to_string(),
+            start: 1_700_000_000,
+            duration_secs: 1800,
+            description: "Focus session".to_string(),
+        };
+        Note::create(conn, &local_day(entry.start), None, "Board prep", 0).unwrap();
+
+        let entry = with_day_notes(conn, entry).unwrap();
+        assert_eq!(entry.description, "Focus session (Board prep)");
+    }
+
+    #[test]
+    fn test_entry_payloads() {
+        let entry = TimeEntry {
+            key: "session:1".to_string(),
+            start: 1_700_000_000,
+            duration_secs: 1800,
+            description: "Focus session".to_string(),
+        };
+        assert_eq!(
+            entry_payload(Provider::Toggl, &entry, "42"),
+            json!({
+                "created_with": "foxus",
+                "description": "Focus session",
+                "start": "2023-11-14T22:13:20Z",
+                "duration": 1800,
+                "workspace_id": 42,
+            })
+        );
+        assert_eq!(
+            entry_payload(Provider::Clockify, &entry, "abc"),
+            json!({
+                "description": "Focus session",
+                "start": "2023-11-14T22:13:20Z",
+                "end": "2023-11-14T22:43:20Z",
+            })
+        );
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let mut settings = TimeExportSettings::load(conn).unwrap();
+        assert_eq!(settings.credentials(Provider::Toggl), None);
+
+        settings.toggl_workspace_id = Some("42".to_string());
+        settings.save(conn).unwrap();
+        let loaded = TimeExportSettings::load(conn).unwrap();
+        assert_eq!(loaded.credentials(Provider::Toggl), None);
+
+        secrets::set(TOGGL_TOKEN_SECRET, "token").unwrap();
+        assert_eq!(
+            loaded.credentials(Provider::Toggl),
+            Some(("token".to_string(), "42"))
+        );
+        assert_eq!(loaded.credentials(Provider::Clockify), None);
+    }
+}
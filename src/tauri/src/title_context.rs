@@ -0,0 +1,316 @@
+//! Groups window titles into contexts (documents, repositories, tickets) so
+//! stats can show time per document rather than per app.
+//!
+//! A clustering pass runs each scheduler tick over activities recorded since
+//! the last pass and stores the result in `activities.title_context`; an
+//! empty string marks titles no extractor matched. Changing the extractors
+//! re-clusters all activity.
+
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::{Activity, Setting};
+use log::error;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const EXTRACTORS_SETTING: &str = "title_context.extractors";
+
+/// Highest activity id the clustering pass has handled.
+const LAST_ID_SETTING: &str = "title_context.last_id";
+
+/// Activities clustered per tick, so a re-cluster of a long history doesn't
+/// hold the database for long.
+const BATCH_SIZE: i64 = 2000;
+
+/// Separators apps use between the parts of a window title (file, project, app):
+/// em dash, en dash, hyphen, and pipe.
+const TITLE_SEPARATORS: [&str; 4] = [" \u{2014} ", " \u{2013} ", " - ", " | "];
+
+/// Markers editors put around the title of a document with unsaved changes.
+const UNSAVED_MARKERS: [char; 3] = ['\u{25CF}', '\u{2022}', '*'];
+
+/// One way of finding a context in a window title. Extractors are tried in
+/// order and the first match wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TitleExtractor {
+    /// A ticket reference like `PROJ-123` or `#42`.
+    Ticket,
+    /// An `owner/repo` reference, as in code hosting page titles.
+    Repo,
+    /// The first part of the title that isn't the app's name, e.g. the file
+    /// in "report.docx - Word".
+    Document,
+    /// The last part of the title that isn't the app's name, e.g. the
+    /// project in "main.rs - foxus - Visual Studio Code".
+    Project,
+    /// A title pattern where `*` marks the context, e.g. `* - Google Docs`.
+    Pattern { pattern: String },
+}
+
+/// Extractors used until the user configures their own.
+pub fn default_extractors() -> Vec<TitleExtractor> {
+    vec![
+        TitleExtractor::Ticket,
+        TitleExtractor::Repo,
+        TitleExtractor::Document,
+    ]
+}
+
+pub fn extractors(conn: &Connection) -> rusqlite::Result<Vec<TitleExtractor>> {
+    Ok(Setting::get(conn, EXTRACTORS_SETTING)?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_else(default_extractors))
+}
+
+/// Save the extractors and start clustering all activity again.
+pub fn set_extractors(conn: &Connection, extractors: &[TitleExtractor]) -> Result<(), AppError> {
+    let value = serde_json::to_string(extractors).map_err(|e| AppError::Internal(e.to_string()))?;
+    Setting::set(conn, EXTRACTORS_SETTING, &value)?;
+    Setting::delete(conn, LAST_ID_SETTING)?;
+    Ok(())
+}
+
+/// Find a ticket reference like "PROJ-123" or "#42" in a window title.
+pub fn find_ticket(title: &str) -> Option<&str> {
+    title
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '#'))
+        .map(|token| token.trim_matches('-'))
+        .find(|token| is_jira_key(token) || is_issue_number(token))
+}
+
+fn is_jira_key(token: &str) -> bool {
+    let Some((project, number)) = token.split_once('-') else {
+        return false;
+    };
+    project.len() >= 2
+        && project.starts_with(|c: char| c.is_ascii_uppercase())
+        && project
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_issue_number(token: &str) -> bool {
+    token
+        .strip_prefix('#')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Find an `owner/repo` reference in a window title.
+fn find_repo(title: &str) -> Option<&str> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+    title
+        .split(|c: char| !(is_name_char(c) || c == '/'))
+        .map(|token| token.trim_end_matches('.'))
+        .find(|token| {
+            let Some((owner, repo)) = token.split_once('/') else {
+                return false;
+            };
+            owner.starts_with(|c: char| c.is_ascii_alphanumeric())
+                && !repo.is_empty()
+                && owner.chars().chain(repo.chars()).all(is_name_char)
+        })
+}
+
+/// Split a window title at the usual separators into trimmed, non-empty
+/// parts that aren't just the app's own name.
+pub fn title_segments<'a>(app_name: &str, title: &'a str) -> Vec<&'a str> {
+    let app_lower = app_name.to_lowercase();
+    let mut segments = vec![title];
+    for separator in TITLE_SEPARATORS {
+        segments = segments
+            .into_iter()
+            .flat_map(|segment| segment.split(separator))
+            .collect();
+    }
+    segments
+        .into_iter()
+        .map(|s| s.trim().trim_matches(UNSAVED_MARKERS).trim())
+        .filter(|s| {
+            !s.is_empty() && (app_lower.is_empty() || !s.to_lowercase().contains(&app_lower))
+        })
+        .collect()
+}
+
+/// The part of `title` matched by `*` in `pattern`.
+fn match_pattern<'a>(pattern: &str, title: &'a str) -> Option<&'a str> {
+    let (prefix, suffix) = pattern.split_once('*')?;
+    let rest = title.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    Some(rest.trim()).filter(|s| !s.is_empty())
+}
+
+impl TitleExtractor {
+    pub fn extract<'a>(&self, app_name: &str, title: &'a str) -> Option<&'a str> {
+        match self {
+            Self::Ticket => find_ticket(title),
+            Self::Repo => find_repo(title),
+            Self::Document => title_segments(app_name, title).first().copied(),
+            Self::Project => title_segments(app_name, title).last().copied(),
+            Self::Pattern { pattern } => match_pattern(pattern, title),
+        }
+    }
+}
+
+/// The context of a window title under `extractors`, if any matches.
+pub fn extract(extractors: &[TitleExtractor], app_name: &str, title: &str) -> Option<String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return None;
+    }
+    extractors
+        .iter()
+        .find_map(|extractor| extractor.extract(app_name, title))
+        .map(ToString::to_string)
+}
+
+/// Cluster the next batch of activities. Returns how many were handled.
+pub fn cluster_pending(conn: &Connection) -> rusqlite::Result<usize> {
+    let extractors = extractors(conn)?;
+    let last_id = Setting::get(conn, LAST_ID_SETTING)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let titles = Activity::titles_after(conn, last_id, BATCH_SIZE)?;
+    let Some(&(max_id, _, _)) = titles.last() else {
+        return Ok(0);
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    for (id, app_name, title) in &titles {
+        let context = title
+            .as_deref()
+            .and_then(|title| extract(&extractors, app_name.as_deref().unwrap_or(""), title));
+        Activity::set_title_context(&tx, *id, context.as_deref().unwrap_or(""))?;
+    }
+    Setting::set(&tx, LAST_ID_SETTING, &max_id.to_string())?;
+    tx.commit()?;
+    Ok(titles.len())
+}
+
+/// Cluster activities recorded since the last tick.
+pub fn check_title_contexts(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    if let Err(e) = with_connection(&db, cluster_pending) {
+        error!("Failed to cluster window titles: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_find_ticket() {
+        assert_eq!(find_ticket("PROJ-123 Fix login - Jira"), Some("PROJ-123"));
+        assert_eq!(find_ticket("Fix crash · Issue #42 · org/repo"), Some("#42"));
+        assert_eq!(find_ticket("main.rs - foxus"), None);
+        assert_eq!(find_ticket("utf-8 decoding"), None);
+    }
+
+    #[test]
+    fn test_find_repo() {
+        assert_eq!(
+            find_repo("Fix crash \u{b7} Issue #42 \u{b7} vtemian/foxus"),
+            Some("vtemian/foxus")
+        );
+        assert_eq!(
+            find_repo("vercel/next.js: The React Framework"),
+            Some("vercel/next.js")
+        );
+        assert_eq!(find_repo("Inbox (3) - Gmail"), None);
+        assert_eq!(find_repo("/etc/hosts"), None);
+    }
+
+    #[test]
+    fn test_document_and_project() {
+        let title = "\u{25CF} main.rs \u{2014} foxus \u{2014} Visual Studio Code";
+        assert_eq!(
+            TitleExtractor::Document.extract("Code", title),
+            Some("main.rs")
+        );
+        assert_eq!(
+            TitleExtractor::Project.extract("Code", title),
+            Some("foxus")
+        );
+        assert_eq!(
+            TitleExtractor::Document.extract("Word", "Q3 report.docx - Word"),
+            Some("Q3 report.docx")
+        );
+        assert_eq!(
+            TitleExtractor::Document.extract("Terminal", "Terminal"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pattern() {
+        let docs = TitleExtractor::Pattern {
+            pattern: "* - Google Docs".into(),
+        };
+        assert_eq!(
+            docs.extract("Firefox", "Launch plan - Google Docs"),
+            Some("Launch plan")
+        );
+        assert_eq!(docs.extract("Firefox", "Inbox - Gmail"), None);
+    }
+
+    #[test]
+    fn test_extractors_apply_in_order() {
+        let extractors = default_extractors();
+        assert_eq!(
+            extract(
+                &extractors,
+                "Firefox",
+                "ABC-9: Flaky test - Jira - Mozilla Firefox"
+            )
+            .as_deref(),
+            Some("ABC-9")
+        );
+        assert_eq!(extract(&extractors, "Slack", "  "), None);
+    }
+
+    #[test]
+    fn test_cluster_pending_and_recluster() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let now = 1_700_000_000_i64;
+
+        for (offset, title, secs) in [
+            (0, Some("Draft.docx - Word"), 60),
+            (5, Some("Draft.docx - Word"), 30),
+            (10, Some("Notes.docx - Word"), 20),
+            (15, None, 50),
+        ] {
+            Activity::new(now + offset, secs, "app", Some("Word"), title)
+                .save(conn)
+                .unwrap();
+        }
+
+        assert_eq!(cluster_pending(conn).unwrap(), 4);
+        assert_eq!(cluster_pending(conn).unwrap(), 0);
+        assert_eq!(
+            Activity::title_context_totals(conn, now, now + 60, 10).unwrap(),
+            vec![
+                ("Draft.docx".to_string(), 90),
+                ("Notes.docx".to_string(), 20)
+            ]
+        );
+
+        let pattern = TitleExtractor::Pattern {
+            pattern: "Notes*".into(),
+        };
+        set_extractors(conn, std::slice::from_ref(&pattern)).unwrap();
+        assert_eq!(extractors(conn).unwrap(), vec![pattern]);
+        assert_eq!(cluster_pending(conn).unwrap(), 4);
+        assert_eq!(
+            Activity::title_context_totals(conn, now, now + 60, 10).unwrap(),
+            vec![(".docx - Word".to_string(), 20)]
+        );
+    }
+}
@@ -1,13 +1,95 @@
-use crate::categorizer::Categorizer;
+use crate::browsers::browser_for_app;
+use crate::categorizer::{Categorizer, CategoryMatch};
 use crate::db::Database;
-use crate::models::Activity;
-use crate::platform::{NativeTracker, PlatformTracker};
+use crate::focus::FocusManager;
+use crate::idle_calibration::IdleSettings;
+use crate::models::{Activity, AppIdentity, FocusSession, Setting};
+use crate::platform::{ActiveWindow, NativeTracker, PlatformTracker};
+use crate::safe_lock;
+use crate::tracking_hours::is_tracking_time;
+use crate::window_context;
 use log::{error, warn};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying each `CategorizationCapture` while categorization is watched.
+pub const CATEGORIZATION_CAPTURE_EVENT: &str = "categorization-capture";
+
+/// Event emitted once a categorization watch is over.
+pub const CATEGORIZATION_WATCH_ENDED_EVENT: &str = "categorization-watch-ended";
+
+const MODE_SETTING: &str = "tracking.mode";
+
+/// How much the tracker records on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerMode {
+    /// Capture the foreground window on every poll.
+    Full,
+    /// Capture only while a focus session is running.
+    FocusOnly,
+    /// Never capture; time comes from focus sessions, offline logs, and
+    /// ingested screen time. The default on mobile, which has no windows.
+    Manual,
+}
+
+impl TrackerMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(Self::Full),
+            "focus_only" => Some(Self::FocusOnly),
+            "manual" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::FocusOnly => "focus_only",
+            Self::Manual => "manual",
+        }
+    }
+
+    fn platform_default() -> Self {
+        if cfg!(mobile) {
+            Self::Manual
+        } else {
+            Self::Full
+        }
+    }
+}
+
+pub fn tracker_mode(conn: &Connection) -> Result<TrackerMode> {
+    Ok(Setting::get(conn, MODE_SETTING)?
+        .as_deref()
+        .and_then(TrackerMode::parse)
+        .unwrap_or_else(TrackerMode::platform_default))
+}
+
+pub fn set_tracker_mode(conn: &Connection, mode: TrackerMode) -> Result<()> {
+    Setting::set(conn, MODE_SETTING, mode.as_str())
+}
 
+/// Whether a poll should capture the foreground window under the configured
+/// mode. Nothing is captured outside the tracking hours or in away mode.
+fn should_capture(conn: &Connection) -> Result<bool> {
+    if !is_tracking_time(conn)? {
+        return Ok(false);
+    }
+    match tracker_mode(conn)? {
+        TrackerMode::Full => Ok(true),
+        TrackerMode::FocusOnly => Ok(FocusSession::find_active(conn)?.is_some()),
+        TrackerMode::Manual => Ok(false),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct TrackerConfig {
     pub poll_interval_secs: u64,
     pub idle_threshold_secs: u64,
@@ -22,17 +104,158 @@ impl Default for TrackerConfig {
     }
 }
 
+/// Settings key for the poll interval; usually set in `config.toml`.
+const POLL_INTERVAL_SETTING: &str = "tracker.poll_interval_secs";
+
+/// Longest poll interval a setting can ask for. Each sample counts for one
+/// interval, so longer ones would blur short switches.
+const MAX_POLL_INTERVAL_SECS: u64 = 60;
+
+impl TrackerConfig {
+    /// The defaults, with the poll interval from settings (1-60 s) if set.
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let mut config = Self::default();
+        if let Some(secs) = Setting::get(conn, POLL_INTERVAL_SETTING)?
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| (1..=MAX_POLL_INTERVAL_SECS).contains(secs))
+        {
+            config.poll_interval_secs = secs;
+        }
+        Ok(config)
+    }
+}
+
+/// Polls in a row without a capture before `get_tracker_health` warns.
+const FAILURE_WARNING_POLLS: u32 = 3;
+
+/// Window titles count as unavailable when none of the captures in this
+/// long had one.
+const TITLE_GRACE_SECS: i64 = 10 * 60;
+
+/// How well the tracker is capturing, for the UI to warn about missing
+/// permissions or an unsupported session.
+#[derive(Debug, Clone, Default)]
+pub struct TrackerHealth {
+    pub running: bool,
+    /// Capture backend reported by the platform tracker; empty until started.
+    pub backend: &'static str,
+    pub idle_detection_available: bool,
+    /// Last time a foreground window was captured and saved.
+    pub last_capture_at: Option<i64>,
+    /// Non-idle polls in a row that captured nothing or failed to save.
+    pub consecutive_failures: u32,
+    /// Last capture whose window had a real title rather than a fallback.
+    pub last_titled_capture_at: Option<i64>,
+}
+
+impl TrackerHealth {
+    fn record_capture(&mut self, timestamp: i64, titled: bool, saved: bool) {
+        if !saved {
+            self.consecutive_failures += 1;
+            return;
+        }
+        self.consecutive_failures = 0;
+        self.last_capture_at = Some(timestamp);
+        if titled {
+            self.last_titled_capture_at = Some(timestamp);
+        }
+    }
+
+    /// `None` until something was captured.
+    pub fn window_titles_available(&self) -> Option<bool> {
+        let last = self.last_capture_at?;
+        Some(
+            self.last_titled_capture_at
+                .is_some_and(|titled| last - titled <= TITLE_GRACE_SECS),
+        )
+    }
+
+    /// Problems worth a persistent warning in the UI, most serious first.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.running {
+            warnings.push("Activity tracking is stopped".to_string());
+            return warnings;
+        }
+        if self.backend == "unavailable" {
+            warnings.push("Window tracking unavailable \u{2014} no X11 display found".to_string());
+        } else if self.consecutive_failures >= FAILURE_WARNING_POLLS {
+            warnings.push("The active window can't be read".to_string());
+        }
+        if self.window_titles_available() == Some(false) {
+            warnings.push(if cfg!(target_os = "macos") {
+                "Window titles unavailable \u{2014} grant Accessibility access".to_string()
+            } else {
+                "Window titles unavailable".to_string()
+            });
+        }
+        if !self.idle_detection_available {
+            warnings
+                .push("Idle detection unavailable \u{2014} idle time counts as active".to_string());
+        }
+        warnings
+    }
+}
+
+/// Whether a captured title is real: platforms fall back to the app name
+/// (macOS without permission) or "Unknown" (Linux).
+fn has_title(window: &ActiveWindow) -> bool {
+    let title = window.window_title.trim();
+    !title.is_empty() && title != "Unknown" && title != window.app_name
+}
+
+/// One capture and how it was categorized, streamed while watching.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorizationCapture {
+    pub timestamp: i64,
+    pub app_name: String,
+    pub window_title: String,
+    /// Rule that matched, or `None` when the capture fell back to Uncategorized.
+    pub rule_id: Option<i64>,
+    pub category_id: i64,
+}
+
+/// Where to send captures until `until`.
+struct CategorizationWatch {
+    until: i64,
+    sender: Sender<CategorizationCapture>,
+}
+
+/// Send the capture built by `capture` to the watcher, if any, dropping the
+/// watch once it has expired or its receiver is gone.
+fn report_capture(
+    watch: &Mutex<Option<CategorizationWatch>>,
+    timestamp: i64,
+    capture: impl FnOnce() -> CategorizationCapture,
+) {
+    let mut watch = safe_lock(watch, "Categorization watch");
+    let Some(current) = watch.as_ref() else {
+        return;
+    };
+    if timestamp >= current.until || current.sender.send(capture()).is_err() {
+        *watch = None;
+    }
+}
+
 pub struct TrackerService {
     config: TrackerConfig,
     running: Arc<AtomicBool>,
     db: Arc<Mutex<Database>>,
     categorizer: Arc<Mutex<Categorizer>>,
+    focus_manager: Arc<FocusManager>,
+    /// Most recently captured foreground window, kept across idle periods.
+    current_window: Arc<Mutex<Option<ActiveWindow>>>,
+    /// Start of the current stretch of non-idle activity; cleared when the user goes idle.
+    active_since: Arc<Mutex<Option<i64>>>,
+    health: Arc<Mutex<TrackerHealth>>,
+    watch: Arc<Mutex<Option<CategorizationWatch>>>,
 }
 
 impl TrackerService {
     pub fn new(
         db: Arc<Mutex<Database>>,
         categorizer: Arc<Mutex<Categorizer>>,
+        focus_manager: Arc<FocusManager>,
         config: TrackerConfig,
     ) -> Self {
         Self {
@@ -40,6 +263,11 @@ impl TrackerService {
             running: Arc::new(AtomicBool::new(false)),
             db,
             categorizer,
+            focus_manager,
+            current_window: Arc::new(Mutex::new(None)),
+            active_since: Arc::new(Mutex::new(None)),
+            health: Arc::new(Mutex::new(TrackerHealth::default())),
+            watch: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -61,32 +289,83 @@ impl TrackerService {
         let running = Arc::clone(&self.running);
         let db = Arc::clone(&self.db);
         let categorizer = Arc::clone(&self.categorizer);
+        let focus_manager = Arc::clone(&self.focus_manager);
+        let current_window = Arc::clone(&self.current_window);
+        let active_since = Arc::clone(&self.active_since);
         let poll_interval_secs = self.config.poll_interval_secs;
-        let idle_threshold_secs = self.config.idle_threshold_secs;
+        let default_idle_threshold_secs = self.config.idle_threshold_secs;
+        let health = Arc::clone(&self.health);
+        let watch = Arc::clone(&self.watch);
         let platform = NativeTracker::new();
+        let platform_idle_available = platform.idle_detection_available();
+        {
+            let mut health = safe_lock(&health, "Tracker health");
+            health.backend = platform.backend();
+            health.idle_detection_available = platform_idle_available;
+        }
 
         thread::spawn(move || {
             while running.load(Ordering::SeqCst) {
-                let idle_secs = platform.get_idle_time_secs();
-
-                if idle_secs < idle_threshold_secs {
-                    if let Some(window) = platform.get_active_window() {
-                        let timestamp = SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap_or_else(|_| Duration::from_secs(0))
-                            .as_secs() as i64;
+                let (capture, idle_settings) = {
+                    let db = safe_lock(&db, "Database");
+                    let capture = should_capture(db.connection()).unwrap_or_else(|e| {
+                        error!("Failed to read tracker mode: {e}");
+                        true
+                    });
+                    let idle_settings = IdleSettings::load(db.connection()).unwrap_or_else(|e| {
+                        error!("Failed to read idle settings: {e}");
+                        IdleSettings::default()
+                    });
+                    (capture, idle_settings)
+                };
+                // A calibration can find idle detection broken even where the platform offers it
+                safe_lock(&health, "Tracker health").idle_detection_available =
+                    platform_idle_available && idle_settings.detection_working != Some(false);
+                let idle_threshold_secs = idle_settings
+                    .threshold_secs
+                    .unwrap_or(default_idle_threshold_secs);
+                if !capture {
+                    *safe_lock(&active_since, "Active since") = None;
+                    thread::sleep(Duration::from_secs(poll_interval_secs));
+                    continue;
+                }
 
-                        let category_id = match categorizer.lock() {
-                            Ok(cat) => {
-                                cat.categorize_app(&window.app_name, Some(&window.window_title))
-                            }
+                let idle_secs = platform.get_idle_time_secs();
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_else(|_| Duration::from_secs(0))
+                    .as_secs() as i64;
+
+                let idle = idle_secs >= idle_threshold_secs;
+                record_session_time(&db, poll_interval_secs as i32, idle);
+
+                if idle {
+                    *safe_lock(&active_since, "Active since") = None;
+                } else {
+                    safe_lock(&active_since, "Active since").get_or_insert(timestamp);
+
+                    if let Some(mut window) = platform.get_active_window() {
+                        let titled = has_title(&window);
+                        normalize_app_name(&db, &mut window);
+                        let CategoryMatch {
+                            rule_id,
+                            category_id,
+                        } = match categorizer.lock() {
+                            Ok(cat) => cat.match_app(&window.app_name, Some(&window.window_title)),
                             Err(poisoned) => {
                                 warn!("Categorizer mutex was poisoned, recovering");
                                 poisoned
                                     .into_inner()
-                                    .categorize_app(&window.app_name, Some(&window.window_title))
+                                    .match_app(&window.app_name, Some(&window.window_title))
                             }
                         };
+                        report_capture(&watch, timestamp, || CategorizationCapture {
+                            timestamp,
+                            app_name: window.app_name.clone(),
+                            window_title: window.window_title.clone(),
+                            rule_id,
+                            category_id,
+                        });
 
                         let mut activity = Activity::new(
                             timestamp,
@@ -96,20 +375,33 @@ impl TrackerService {
                             Some(&window.window_title),
                         );
                         activity.category_id = Some(category_id);
+                        activity.browser = browser_for_app(&window.app_name).map(String::from);
+                        activity.context =
+                            capture_context(&platform, &db, &categorizer, &mut window);
 
-                        match db.lock() {
-                            Ok(db_guard) => {
-                                if let Err(e) = activity.save(db_guard.connection()) {
-                                    error!("Failed to save activity: {e}");
-                                }
-                            }
+                        *safe_lock(&current_window, "Current window") = Some(window);
+
+                        let saved = match db.lock() {
+                            Ok(db_guard) => activity
+                                .save(db_guard.connection())
+                                .map_err(|e| error!("Failed to save activity: {e}")),
                             Err(poisoned) => {
                                 warn!("Database mutex was poisoned, recovering");
-                                if let Err(e) = activity.save(poisoned.into_inner().connection()) {
-                                    error!("Failed to save activity after recovery: {e}");
-                                }
+                                activity
+                                    .save(poisoned.into_inner().connection())
+                                    .map_err(|e| {
+                                        error!("Failed to save activity after recovery: {e}");
+                                    })
                             }
                         }
+                        .is_ok();
+                        safe_lock(&health, "Tracker health")
+                            .record_capture(timestamp, titled, saved);
+                        if saved {
+                            earn_budget(&focus_manager, category_id, poll_interval_secs as i32);
+                        }
+                    } else {
+                        safe_lock(&health, "Tracker health").consecutive_failures += 1;
                     }
                 }
 
@@ -122,14 +414,121 @@ impl TrackerService {
         self.running.store(false, Ordering::SeqCst);
     }
 
+    /// The most recently captured foreground window, if any capture succeeded yet.
+    pub fn current_window(&self) -> Option<ActiveWindow> {
+        safe_lock(&self.current_window, "Current window").clone()
+    }
+
+    /// When the current stretch of continuous non-idle activity began, if the user is active.
+    pub fn active_since(&self) -> Option<i64> {
+        *safe_lock(&self.active_since, "Active since")
+    }
+
     /// Check if the tracker is currently running.
-    #[cfg_attr(
-        not(test),
-        expect(dead_code, reason = "Public API for future UI status display")
-    )]
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::SeqCst)
     }
+
+    /// Send each capture and its categorization to `sender` until `until`,
+    /// replacing any earlier watch.
+    pub fn watch_categorization(&self, sender: Sender<CategorizationCapture>, until: i64) {
+        *safe_lock(&self.watch, "Categorization watch") =
+            Some(CategorizationWatch { until, sender });
+    }
+
+    pub fn health(&self) -> TrackerHealth {
+        TrackerHealth {
+            running: self.is_running(),
+            ..safe_lock(&self.health, "Tracker health").clone()
+        }
+    }
+}
+
+/// Emit each capture and its matched rule as a Tauri event for `secs`, so a
+/// user can see live whether a new rule fires.
+pub fn stream_categorization(app: &AppHandle, tracker: &TrackerService, secs: u64) {
+    let (sender, receiver) = mpsc::channel();
+    let deadline = Instant::now() + Duration::from_secs(secs);
+    let until = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .saturating_add(secs);
+    tracker.watch_categorization(sender, i64::try_from(until).unwrap_or(i64::MAX));
+
+    let app = app.clone();
+    thread::spawn(move || {
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(wait) {
+                Ok(capture) => {
+                    if let Err(e) = app.emit(CATEGORIZATION_CAPTURE_EVENT, capture) {
+                        warn!("Failed to emit categorization capture: {e}");
+                    }
+                }
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        if let Err(e) = app.emit(CATEGORIZATION_WATCH_ENDED_EVENT, ()) {
+            warn!("Failed to emit end of categorization watch: {e}");
+        }
+    });
+}
+
+/// Fill in the windows beside `window` and return their context snapshot,
+/// if the user turned context capture on.
+fn capture_context(
+    platform: &impl PlatformTracker,
+    db: &Mutex<Database>,
+    categorizer: &Mutex<Categorizer>,
+    window: &mut ActiveWindow,
+) -> Option<String> {
+    let enabled = window_context::capture_enabled(safe_lock(db, "Database").connection())
+        .unwrap_or_else(|e| {
+            error!("Failed to read context capture setting: {e}");
+            false
+        });
+    if !enabled {
+        return None;
+    }
+
+    window.visible = platform.get_visible_windows();
+    let categorizer = safe_lock(categorizer, "Categorizer");
+    window_context::snapshot(
+        window
+            .visible
+            .iter()
+            .map(|w| categorizer.categorize_app(&w.app_name, Some(&w.window_title))),
+    )
+}
+
+/// Record the app under its display name, so "Code" and "Visual Studio Code"
+/// (or a bundle id) don't show up as separate apps.
+fn normalize_app_name(db: &Mutex<Database>, window: &mut ActiveWindow) {
+    let db = safe_lock(db, "Database");
+    match AppIdentity::display_name(
+        db.connection(),
+        window.bundle_id.as_deref(),
+        &window.app_name,
+    ) {
+        Ok(name) => window.app_name = name,
+        Err(e) => error!("Failed to resolve app identity: {e}"),
+    }
+}
+
+/// Split the active focus session's time into focused and idle seconds.
+fn record_session_time(db: &Mutex<Database>, secs: i32, idle: bool) {
+    let db = safe_lock(db, "Database");
+    if let Err(e) = FocusSession::record_tracked_time(db.connection(), secs, idle) {
+        error!("Failed to record focus session time: {e}");
+    }
+}
+
+/// Feed a saved sample to the focus session's "earn it" budget.
+fn earn_budget(focus_manager: &FocusManager, category_id: i64, secs: i32) {
+    if let Err(e) = focus_manager.record_productive_time(category_id, secs) {
+        error!("Failed to accrue distraction budget: {e}");
+    }
 }
 
 #[cfg(test)]
@@ -153,6 +552,38 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_config_poll_interval_from_settings() {
+        let (db, _categorizer, _dir) = setup();
+        let db = db.lock().unwrap();
+        let conn = db.connection();
+        assert_eq!(TrackerConfig::load(conn).unwrap(), TrackerConfig::default());
+
+        Setting::set(conn, POLL_INTERVAL_SETTING, "10").unwrap();
+        assert_eq!(TrackerConfig::load(conn).unwrap().poll_interval_secs, 10);
+
+        // Out of range falls back to the default
+        Setting::set(conn, POLL_INTERVAL_SETTING, "3600").unwrap();
+        assert_eq!(TrackerConfig::load(conn).unwrap().poll_interval_secs, 5);
+    }
+
+    #[test]
+    fn test_focus_only_mode_captures_during_sessions() {
+        let (db, _categorizer, _dir) = setup();
+        let db = db.lock().unwrap();
+        let conn = db.connection();
+        assert_eq!(tracker_mode(conn).unwrap(), TrackerMode::Full);
+        assert!(should_capture(conn).unwrap());
+
+        set_tracker_mode(conn, TrackerMode::FocusOnly).unwrap();
+        assert!(!should_capture(conn).unwrap());
+        FocusSession::new(600, false).save(conn).unwrap();
+        assert!(should_capture(conn).unwrap());
+
+        set_tracker_mode(conn, TrackerMode::Manual).unwrap();
+        assert!(!should_capture(conn).unwrap());
+    }
+
     #[test]
     fn test_tracker_starts_and_stops() {
         let (db, categorizer, _dir) = setup();
@@ -161,7 +592,8 @@ mod tests {
             idle_threshold_secs: 120,
         };
 
-        let tracker = TrackerService::new(db, categorizer, config);
+        let focus_manager = Arc::new(FocusManager::new(Arc::clone(&db)));
+        let tracker = TrackerService::new(db, categorizer, focus_manager, config);
 
         assert!(!tracker.is_running());
 
@@ -176,6 +608,72 @@ mod tests {
         assert!(!tracker.is_running());
     }
 
+    #[test]
+    fn test_health_warnings() {
+        let mut health = TrackerHealth {
+            running: true,
+            backend: "x11",
+            idle_detection_available: true,
+            ..TrackerHealth::default()
+        };
+        assert_eq!(health.window_titles_available(), None);
+        assert!(health.warnings().is_empty());
+
+        health.record_capture(1000, true, true);
+        health.record_capture(1005, false, false);
+        assert_eq!(health.last_capture_at, Some(1000));
+        assert_eq!(health.consecutive_failures, 1);
+
+        health.consecutive_failures += 2;
+        health.record_capture(1000 + TITLE_GRACE_SECS + 1, false, true);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.window_titles_available(), Some(false));
+        assert_eq!(
+            health.warnings(),
+            vec!["Window titles unavailable".to_string()]
+        );
+
+        health.running = false;
+        assert_eq!(
+            health.warnings(),
+            vec!["Activity tracking is stopped".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_report_capture_until_expiry() {
+        let watch = Mutex::new(None);
+        let capture = |timestamp| CategorizationCapture {
+            timestamp,
+            app_name: "Code".into(),
+            window_title: "main.rs".into(),
+            rule_id: Some(3),
+            category_id: 2,
+        };
+        report_capture(&watch, 100, || capture(100));
+
+        let (sender, captures) = std::sync::mpsc::channel();
+        *watch.lock().unwrap() = Some(CategorizationWatch { until: 110, sender });
+        report_capture(&watch, 105, || capture(105));
+        report_capture(&watch, 110, || capture(110));
+        assert!(watch.lock().unwrap().is_none());
+
+        let received: Vec<i64> = captures.try_iter().map(|c| c.timestamp).collect();
+        assert_eq!(received, vec![105]);
+    }
+
+    #[test]
+    fn test_has_title() {
+        let window = |app: &str, title: &str| ActiveWindow {
+            app_name: app.into(),
+            window_title: title.into(),
+            ..ActiveWindow::default()
+        };
+        assert!(has_title(&window("Code", "main.rs - foxus")));
+        assert!(!has_title(&window("Code", "Code")));
+        assert!(!has_title(&window("firefox", "Unknown")));
+    }
+
     /// Tests that the activity tracking and saving logic works correctly.
     /// This test directly exercises the save logic rather than relying on the
     /// threaded `start()` method, which depends on platform-specific window detection.
@@ -0,0 +1,89 @@
+//! Optional weekly hours outside which nothing is recorded, e.g. 08:00-19:00
+//! on weekdays, for users who want work-time analytics and no record of
+//! their personal time. The tracker, the browser extension's native host and
+//! external activity reports all check them, along with away mode, before
+//! recording anything.
+
+use crate::away_mode::is_away_at;
+use crate::error::AppError;
+use crate::local_time::day_and_time_in;
+use crate::models::focus_session::current_timestamp;
+use crate::models::Setting;
+use crate::notifications::QuietRange;
+use chrono::{Local, TimeZone};
+use rusqlite::{Connection, Result};
+
+const TRACKING_HOURS_SETTING: &str = "tracking.hours";
+
+/// The hours activity is recorded in; empty for all the time.
+pub fn tracking_hours(conn: &Connection) -> Result<Vec<QuietRange>> {
+    Ok(Setting::get(conn, TRACKING_HOURS_SETTING)?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+pub fn set_tracking_hours(conn: &Connection, ranges: &[QuietRange]) -> Result<(), AppError> {
+    if ranges.is_empty() {
+        Setting::delete(conn, TRACKING_HOURS_SETTING)?;
+        return Ok(());
+    }
+    let value = serde_json::to_string(ranges).map_err(|e| AppError::Internal(e.to_string()))?;
+    Setting::set(conn, TRACKING_HOURS_SETTING, &value)?;
+    Ok(())
+}
+
+/// Whether activity at `now` in `tz` may be recorded: inside the tracking
+/// hours, if any are set, and not while away.
+pub fn is_tracking_time_at<Tz: TimeZone>(conn: &Connection, tz: &Tz, now: i64) -> Result<bool> {
+    if is_away_at(conn, tz, now)? {
+        return Ok(false);
+    }
+    let ranges = tracking_hours(conn)?;
+    if ranges.is_empty() {
+        return Ok(true);
+    }
+    let (day, time) = day_and_time_in(tz, now);
+    Ok(ranges.iter().any(|range| range.contains(day, &time)))
+}
+
+/// Whether activity may be recorded right now, in local time.
+pub fn is_tracking_time(conn: &Connection) -> Result<bool> {
+    is_tracking_time_at(conn, &Local, current_timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::away_mode::AwayPeriod;
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+
+    /// Thursday 2026-10-15 09:00 UTC
+    const THURSDAY_NINE: i64 = 1_792_054_800;
+
+    #[test]
+    fn test_tracking_hours() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(is_tracking_time_at(conn, &Utc, THURSDAY_NINE).unwrap());
+
+        let weekdays = QuietRange {
+            days_of_week: "1,2,3,4,5".into(),
+            start_time: "08:00".into(),
+            end_time: "19:00".into(),
+        };
+        set_tracking_hours(conn, &[weekdays.clone()]).unwrap();
+        assert_eq!(tracking_hours(conn).unwrap(), vec![weekdays]);
+        assert!(is_tracking_time_at(conn, &Utc, THURSDAY_NINE).unwrap());
+        // Thursday evening and Saturday morning are personal time
+        assert!(!is_tracking_time_at(conn, &Utc, THURSDAY_NINE + 11 * 3600).unwrap());
+        assert!(!is_tracking_time_at(conn, &Utc, THURSDAY_NINE + 2 * 24 * 3600).unwrap());
+
+        // Nothing is recorded while away, whatever the hours
+        AwayPeriod::start(conn, "2026-10-15", "2026-10-16").unwrap();
+        assert!(!is_tracking_time_at(conn, &Utc, THURSDAY_NINE).unwrap());
+
+        set_tracking_hours(conn, &[]).unwrap();
+        assert!(tracking_hours(conn).unwrap().is_empty());
+    }
+}
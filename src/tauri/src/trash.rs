@@ -0,0 +1,73 @@
+//! Deleted rules and focus schedules stay in the trash for 30 days, where
+//! they can be restored, before being purged for good.
+
+use crate::db::{with_connection, Database};
+use crate::models::focus_session::current_timestamp;
+use crate::models::{FocusSchedule, Rule};
+use log::error;
+use rusqlite::{Connection, Result};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// How long deleted items stay restorable.
+pub const TRASH_RETENTION_SECS: i64 = 30 * 24 * 3600;
+
+/// Permanently delete rules and schedules trashed before `before`. Returns
+/// how many went.
+pub fn purge(conn: &Connection, before: i64) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let purged = Rule::purge_deleted(&tx, before)? + FocusSchedule::purge_deleted(&tx, before)?;
+    tx.commit()?;
+    Ok(purged)
+}
+
+/// Purge items past `TRASH_RETENTION_SECS`.
+pub fn check_trash(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let before = current_timestamp() - TRASH_RETENTION_SECS;
+    if let Err(e) = with_connection(&db, |conn| purge(conn, before)) {
+        error!("Failed to empty expired trash: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Category, MatchType};
+    use crate::test_utils::setup_test_db;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn test_purge_only_drops_expired_items() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        let category = Category::find_all(conn).unwrap().remove(0);
+        let old = Rule::create(conn, "old", MatchType::App, category.id, 10).unwrap();
+        Rule::set_patterns(conn, old.id, &["old", "older"]).unwrap();
+        let recent = Rule::create(conn, "recent", MatchType::App, category.id, 10).unwrap();
+        let mut schedule = FocusSchedule::new("1", "09:00", "10:00", 300);
+        schedule.save(conn).unwrap();
+
+        Rule::trash(conn, old.id, NOW - TRASH_RETENTION_SECS - 1).unwrap();
+        Rule::trash(conn, recent.id, NOW - 60).unwrap();
+        FocusSchedule::trash(conn, schedule.id.unwrap(), NOW - TRASH_RETENTION_SECS - 1).unwrap();
+
+        assert_eq!(purge(conn, NOW - TRASH_RETENTION_SECS).unwrap(), 2);
+        let trashed: Vec<i64> = Rule::find_deleted(conn)
+            .unwrap()
+            .into_iter()
+            .map(|(rule, _)| rule.id)
+            .collect();
+        assert_eq!(trashed, vec![recent.id]);
+        assert!(FocusSchedule::find_deleted(conn).unwrap().is_empty());
+
+        assert!(Rule::restore(conn, recent.id).unwrap());
+        assert!(Rule::find_all(conn)
+            .unwrap()
+            .iter()
+            .any(|r| r.id == recent.id));
+    }
+}
@@ -0,0 +1,688 @@
+use crate::accountability::on_session_ended;
+use crate::audit_log::Origin;
+use crate::away_mode::{come_back, current, on_away_mode_changed};
+use crate::categorizer::Categorizer;
+use crate::constants::QUICK_RULE_PRIORITY;
+use crate::db::Database;
+use crate::focus::FocusManager;
+use crate::local_time::local_today;
+use crate::models::focus_session::current_timestamp;
+use crate::models::{Activity, Category, Rule, RuleGroup, Setting};
+use crate::platform::ActiveWindow;
+use crate::read_only::ReadOnly;
+use crate::scheduler::SchedulerService;
+use crate::session_queue::{self, QueuedBlock};
+use crate::tracker::TrackerService;
+use crate::updates::LatestUpdate;
+use crate::validation::validate_rule_pattern;
+use crate::widget::show_focus_widget;
+use crate::{safe_lock, TrackerHandle};
+use chrono::NaiveDate;
+use log::{error, warn};
+use std::sync::{Arc, Mutex};
+use tauri::{
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{TrayIcon, TrayIconBuilder},
+    AppHandle, Manager, Wry,
+};
+
+/// Holds the tray icon for dynamic menu updates
+pub struct TrayHandle(Mutex<Option<TrayIcon<Wry>>>);
+
+/// Setting that turns the macOS menu bar countdown off when set to "false".
+pub const COUNTDOWN_TITLE_SETTING: &str = "tray.countdown_title";
+
+/// Focus presets offered in the tray while no session is active: (menu id, label, minutes).
+const FOCUS_PRESETS: [(&str, &str, i32); 3] = [
+    ("focus_10", "Start Focus (10 min)", 10),
+    ("focus_25", "Start Focus (25 min)", 25),
+    ("focus_60", "Start Focus (1 hour)", 60),
+];
+
+/// Minutes the tray's "Extend Session" item adds to the running session.
+const TRAY_EXTEND_MINUTES: i32 = 10;
+
+/// Blocklists the tray can add to the running session: (menu id, label, domains).
+const BLOCKLIST_PRESETS: [(&str, &str, &[&str]); 3] = [
+    (
+        "session_block_social",
+        "Social Media",
+        &[
+            "facebook.com",
+            "instagram.com",
+            "linkedin.com",
+            "reddit.com",
+            "tiktok.com",
+            "x.com",
+        ],
+    ),
+    (
+        "session_block_news",
+        "News",
+        &[
+            "bbc.com",
+            "cnn.com",
+            "news.google.com",
+            "news.ycombinator.com",
+            "nytimes.com",
+        ],
+    ),
+    (
+        "session_block_video",
+        "Video",
+        &["netflix.com", "twitch.tv", "youtube.com"],
+    ),
+];
+
+/// Create the tray icon with its initial menu and store it in app state.
+pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_tray_menu(app)?;
+
+    let tray = TrayIconBuilder::new()
+        .icon(
+            app.default_window_icon()
+                .expect("default window icon must be set in tauri.conf.json")
+                .clone(),
+        )
+        .icon_as_template(true)
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .tooltip(tooltip(app))
+        .on_menu_event(|app, event| {
+            handle_tray_event(app, event.id.0.as_str());
+        })
+        .build(app)?;
+
+    // Store tray handle for dynamic menu updates
+    app.manage(TrayHandle(Mutex::new(Some(tray))));
+
+    Ok(())
+}
+
+/// Build the tray menu based on current focus state
+fn build_tray_menu(app: &AppHandle) -> Result<Menu<Wry>, Box<dyn std::error::Error>> {
+    let focus_state = app
+        .try_state::<Arc<FocusManager>>()
+        .and_then(|focus_manager| focus_manager.get_state().ok());
+    let focus_active = focus_state.as_ref().is_some_and(|s| s.active);
+    // Only timed sessions have an end to push back
+    let focus_timed = focus_state
+        .as_ref()
+        .is_some_and(|s| s.time_remaining_secs.is_some());
+
+    let menu = Menu::new(app)?;
+
+    if let Some(summary) = today_summary(app) {
+        menu.append(&MenuItem::with_id(
+            app,
+            "today_summary",
+            summary,
+            false,
+            None::<&str>,
+        )?)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    if let Some(update) = LatestUpdate::get(app) {
+        let label = format!("Update available: {}", update.latest_version);
+        menu.append(&MenuItem::with_id(
+            app,
+            "update_available",
+            label,
+            true,
+            None::<&str>,
+        )?)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    let read_only = app.try_state::<ReadOnly>().is_some_and(|r| r.0);
+    if read_only {
+        menu.append(&MenuItem::with_id(
+            app,
+            "read_only",
+            "Read-Only Mode",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+
+    if let Some(until) = away_until(app) {
+        menu.append(&MenuItem::with_id(
+            app,
+            "away",
+            format!("Away Until {until}"),
+            false,
+            None::<&str>,
+        )?)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            "end_away",
+            "Back Early",
+            true,
+            None::<&str>,
+        )?)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    menu.append(&MenuItem::with_id(
+        app,
+        "open",
+        "Open Foxus",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    if let Some(submenu) = build_categorize_submenu(app)? {
+        menu.append(&submenu)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    if let Some(submenu) = build_rule_groups_submenu(app)? {
+        menu.append(&submenu)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    if focus_active {
+        let blocklists = Submenu::new(app, "Block for This Session", true)?;
+        for (id, label, _) in BLOCKLIST_PRESETS {
+            blocklists.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+        }
+        menu.append(&blocklists)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            "show_widget",
+            "Show Focus Widget",
+            true,
+            None::<&str>,
+        )?)?;
+        if focus_timed {
+            menu.append(&MenuItem::with_id(
+                app,
+                "extend_focus",
+                format!("Extend Session (+{TRAY_EXTEND_MINUTES} min)"),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&MenuItem::with_id(
+            app,
+            "end_focus",
+            "End Focus Session",
+            true,
+            None::<&str>,
+        )?)?;
+    } else {
+        for (id, label, _) in FOCUS_PRESETS {
+            menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+        }
+    }
+
+    if let Some(skipped) = skip_today_status(app) {
+        menu.append(&CheckMenuItem::with_id(
+            app,
+            "skip_today",
+            "Skip Today's Scheduled Focus",
+            true,
+            skipped,
+            None::<&str>,
+        )?)?;
+    }
+
+    if let Some(label) = up_next(app) {
+        menu.append(&MenuItem::with_id(
+            app,
+            "up_next",
+            label,
+            false,
+            None::<&str>,
+        )?)?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "quit",
+        "Quit Foxus",
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(menu)
+}
+
+/// Build the "Categorize <app> as" submenu listing every category.
+///
+/// The app is named in each item's id: by the time an item is clicked the
+/// focused app is usually a different one, such as the tray host.
+fn build_categorize_submenu(
+    app: &AppHandle,
+) -> Result<Option<Submenu<Wry>>, Box<dyn std::error::Error>> {
+    let Some(window) = current_window(app) else {
+        return Ok(None);
+    };
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return Ok(None);
+    };
+    let categories = Category::find_all(safe_lock(&db, "Database").connection())?;
+
+    let label = format!("Categorize \"{}\" as", window.app_name);
+    let submenu = Submenu::new(app, label, true)?;
+    for category in categories {
+        submenu.append(&MenuItem::with_id(
+            app,
+            categorize_item_id(category.id, &window.app_name),
+            &category.name,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    Ok(Some(submenu))
+}
+
+fn categorize_item_id(category_id: i64, app_name: &str) -> String {
+    format!("categorize_{category_id}_{app_name}")
+}
+
+/// The category and app named by a categorize item id.
+fn parse_categorize_item_id(event_id: &str) -> Option<(i64, &str)> {
+    let (category_id, app_name) = event_id.strip_prefix("categorize_")?.split_once('_')?;
+    Some((category_id.parse().ok()?, app_name))
+}
+
+/// Build the "Rule Groups" submenu with a checkbox per group, if any exist.
+fn build_rule_groups_submenu(
+    app: &AppHandle,
+) -> Result<Option<Submenu<Wry>>, Box<dyn std::error::Error>> {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return Ok(None);
+    };
+    let groups = RuleGroup::find_all(safe_lock(&db, "Database").connection())?;
+    if groups.is_empty() {
+        return Ok(None);
+    }
+
+    let submenu = Submenu::new(app, "Rule Groups", true)?;
+    for group in groups {
+        submenu.append(&CheckMenuItem::with_id(
+            app,
+            format!("rule_group_{}", group.id),
+            &group.name,
+            true,
+            group.enabled,
+            None::<&str>,
+        )?)?;
+    }
+
+    Ok(Some(submenu))
+}
+
+fn current_window(app: &AppHandle) -> Option<ActiveWindow> {
+    app.try_state::<Arc<TrackerService>>()
+        .and_then(|tracker| tracker.current_window())
+}
+
+/// Today's headline numbers, e.g. "Today: 3h 12m productive / 41m distracting".
+fn today_summary(app: &AppHandle) -> Option<String> {
+    let db = app.try_state::<Arc<Mutex<Database>>>()?;
+    let now = current_timestamp();
+    let (_, _, today_start) = local_today();
+
+    let (productive, _, distracting) =
+        Activity::productivity_totals(safe_lock(&db, "Database").connection(), today_start, now)
+            .ok()?;
+
+    Some(format!(
+        "Today: {} productive / {} distracting",
+        format_duration(productive),
+        format_duration(distracting)
+    ))
+}
+
+/// The next block in the session queue, e.g. "Up Next: Break 10m".
+fn up_next(app: &AppHandle) -> Option<String> {
+    let db = app.try_state::<Arc<Mutex<Database>>>()?;
+    let queue = session_queue::find_all(safe_lock(&db, "Database").connection()).ok()?;
+    queue.first().map(QueuedBlock::label)
+}
+
+/// Whether today's scheduled focus is skipped, or `None` when there's none
+/// left today to skip.
+fn skip_today_status(app: &AppHandle) -> Option<bool> {
+    app.try_state::<Arc<FocusManager>>()?
+        .skip_today_status()
+        .ok()
+        .flatten()
+}
+
+/// Format seconds as a compact duration like "3h 12m" or "41m".
+pub(crate) fn format_duration(secs: i32) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// When away mode ends, e.g. "Mon Oct 19", if it's on.
+fn away_until(app: &AppHandle) -> Option<String> {
+    let db = app.try_state::<Arc<Mutex<Database>>>()?;
+    let period = current(safe_lock(&db, "Database").connection()).ok()??;
+    let until = NaiveDate::parse_from_str(&period.until_date, "%Y-%m-%d").ok()?;
+    Some(until.format("%a %b %-d").to_string())
+}
+
+/// Tray tooltip text: today's summary, flagging a pending update and away
+/// mode.
+fn tooltip(app: &AppHandle) -> String {
+    let mut tooltip = match LatestUpdate::get(app) {
+        Some(update) => format!("Foxus ({} available)", update.latest_version),
+        None => "Foxus".to_string(),
+    };
+    if let Some(until) = away_until(app) {
+        tooltip.push_str(&format!("\nAway until {until}"));
+    }
+    if let Some(summary) = today_summary(app) {
+        tooltip.push('\n');
+        tooltip.push_str(&summary);
+    }
+    tooltip
+}
+
+/// Whether the menu bar countdown is enabled (on unless explicitly turned off).
+pub fn countdown_title_enabled(conn: &rusqlite::Connection) -> rusqlite::Result<bool> {
+    Ok(Setting::get(conn, COUNTDOWN_TITLE_SETTING)?.as_deref() != Some("false"))
+}
+
+/// Remaining focus time for the menu bar, e.g. "24:10".
+///
+/// Only macOS shows tray titles next to the icon, and only timed sessions count down.
+fn countdown_title(app: &AppHandle) -> Option<String> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    let db = app.try_state::<Arc<Mutex<Database>>>()?;
+    if !countdown_title_enabled(safe_lock(&db, "Database").connection()).ok()? {
+        return None;
+    }
+    let remaining = app
+        .try_state::<Arc<FocusManager>>()?
+        .get_state()
+        .ok()?
+        .time_remaining_secs?;
+    Some(format_countdown(remaining))
+}
+
+/// Format seconds as a "M:SS" countdown.
+fn format_countdown(secs: i64) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Rebuild the tray menu and tooltip to reflect current app state.
+pub fn refresh_tray(app: &AppHandle) {
+    let Some(tray_handle) = app.try_state::<TrayHandle>() else {
+        return;
+    };
+    let Ok(guard) = tray_handle.0.lock() else {
+        return;
+    };
+    let Some(tray) = guard.as_ref() else {
+        return;
+    };
+
+    match build_tray_menu(app) {
+        Ok(new_menu) => {
+            if let Err(e) = tray.set_menu(Some(new_menu)) {
+                error!("Failed to update tray menu: {e}");
+            }
+        }
+        Err(e) => error!("Failed to build tray menu: {e}"),
+    }
+    if let Err(e) = tray.set_tooltip(Some(tooltip(app))) {
+        error!("Failed to update tray tooltip: {e}");
+    }
+    if let Err(e) = tray.set_title(countdown_title(app)) {
+        error!("Failed to update tray title: {e}");
+    }
+}
+
+/// Handle tray menu events (focus actions, open, quit).
+fn handle_tray_event(app: &AppHandle, event_id: &str) {
+    let read_only = app.try_state::<ReadOnly>().is_some_and(|r| r.0);
+    if read_only && !matches!(event_id, "open" | "update_available" | "quit") {
+        warn!("Ignoring tray action {event_id} in read-only mode");
+        return;
+    }
+
+    if event_id.starts_with("focus_") || event_id == "end_focus" || event_id == "extend_focus" {
+        handle_focus_event(app, event_id);
+    } else if let Some((_, _, domains)) =
+        BLOCKLIST_PRESETS.iter().find(|(id, _, _)| *id == event_id)
+    {
+        block_for_session(app, domains);
+    } else if event_id == "open" || event_id == "update_available" {
+        show_main_window(app);
+    } else if event_id == "end_away" {
+        end_away(app);
+    } else if event_id == "skip_today" {
+        toggle_skip_today(app);
+    } else if event_id == "show_widget" {
+        if let Err(e) = show_focus_widget(app) {
+            error!("Failed to show focus widget: {e}");
+        }
+    } else if event_id == "quit" {
+        handle_quit(app);
+    } else if let Some((category_id, app_name)) = parse_categorize_item_id(event_id) {
+        categorize_app(app, app_name, category_id);
+    } else if let Some(group_id) = event_id
+        .strip_prefix("rule_group_")
+        .and_then(|id| id.parse::<i64>().ok())
+    {
+        toggle_rule_group(app, group_id);
+    }
+}
+
+/// Leave away mode before it runs out.
+fn end_away(app: &AppHandle) {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return;
+    };
+    let ended = come_back(safe_lock(&db, "Database").connection());
+    match ended {
+        Ok(true) => on_away_mode_changed(app, None),
+        Ok(false) => {}
+        Err(e) => error!("Failed to end away mode: {e}"),
+    }
+}
+
+/// Skip today's scheduled focus, e.g. on a sick day, or take the skip back.
+fn toggle_skip_today(app: &AppHandle) {
+    let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() else {
+        return;
+    };
+    let skip = skip_today_status(app) == Some(false);
+    match focus_manager.skip_today(skip, Origin::Tray) {
+        Ok(Some(session)) => on_session_ended(app, &session),
+        Ok(None) => {}
+        Err(e) => error!("Failed to skip today's scheduled focus: {e}"),
+    }
+    refresh_tray(app);
+}
+
+/// Flip a rule group on or off and apply it to categorizing and blocking.
+fn toggle_rule_group(app: &AppHandle, group_id: i64) {
+    let (Some(db), Some(categorizer)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<Mutex<Categorizer>>>(),
+    ) else {
+        return;
+    };
+
+    {
+        let db = safe_lock(&db, "Database");
+        let conn = db.connection();
+        let group = match RuleGroup::find_all(conn) {
+            Ok(groups) => groups.into_iter().find(|g| g.id == group_id),
+            Err(e) => {
+                error!("Failed to load rule groups: {e}");
+                return;
+            }
+        };
+        let Some(group) = group else {
+            return;
+        };
+        if let Err(e) = RuleGroup::set_enabled(conn, group.id, !group.enabled) {
+            error!("Failed to toggle rule group {}: {e}", group.name);
+            return;
+        }
+        if let Err(e) = safe_lock(&categorizer, "Categorizer").reload(conn) {
+            error!("Failed to reload categorizer: {e}");
+        }
+    }
+    if let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() {
+        focus_manager.invalidate_cache();
+    }
+
+    refresh_tray(app);
+}
+
+/// Assign an app to a category and apply it immediately.
+fn categorize_app(app: &AppHandle, app_name: &str, category_id: i64) {
+    let (Some(db), Some(categorizer)) = (
+        app.try_state::<Arc<Mutex<Database>>>(),
+        app.try_state::<Arc<Mutex<Categorizer>>>(),
+    ) else {
+        return;
+    };
+    let pattern = match validate_rule_pattern(app_name) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            error!("Cannot categorize {app_name}: {e}");
+            return;
+        }
+    };
+
+    {
+        let db = safe_lock(&db, "Database");
+        let conn = db.connection();
+        if let Err(e) = Rule::assign_app(conn, pattern, category_id, QUICK_RULE_PRIORITY) {
+            error!("Failed to categorize {pattern}: {e}");
+            return;
+        }
+        if let Err(e) = safe_lock(&categorizer, "Categorizer").reload(conn) {
+            error!("Failed to reload categorizer: {e}");
+        }
+    }
+
+    refresh_tray(app);
+}
+
+/// Add a blocklist preset to the running session.
+fn block_for_session(app: &AppHandle, domains: &[&str]) {
+    if let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() {
+        if let Err(e) = focus_manager.add_session_blocks(domains) {
+            error!("Failed to block domains for the session: {e}");
+        }
+    }
+}
+
+/// Bring the main dashboard window to the front.
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Handle focus-related tray menu events.
+fn handle_focus_event(app: &AppHandle, event_id: &str) {
+    if let Some(focus_manager) = app.try_state::<Arc<FocusManager>>() {
+        let preset = FOCUS_PRESETS.iter().find(|(id, _, _)| *id == event_id);
+        let result = match preset {
+            Some((_, _, minutes)) => focus_manager
+                .start_timed_session(minutes * 60, Some(minutes * 60), false, &[], Origin::Tray)
+                .map(|_| ()),
+            None if event_id == "end_focus" => {
+                focus_manager.end_session(Origin::Tray).map(|ended| {
+                    if let Some(session) = ended {
+                        on_session_ended(app, &session);
+                    }
+                })
+            }
+            None if event_id == "extend_focus" => focus_manager
+                .extend_focus_session(TRAY_EXTEND_MINUTES * 60, 0, Origin::Tray)
+                .map(|_| ()),
+            None => Ok(()),
+        };
+
+        if let Err(e) = result {
+            error!("Failed to handle focus action: {e}");
+        }
+
+        // Update tray menu to reflect new focus state
+        refresh_tray(app);
+    }
+}
+
+/// Gracefully stop the tracker and exit the app.
+fn handle_quit(app: &AppHandle) {
+    if let Some(scheduler) = app.try_state::<Arc<SchedulerService>>() {
+        scheduler.stop();
+    }
+    if let Some(tracker) = app.try_state::<Arc<TrackerService>>() {
+        tracker.stop();
+    }
+    if let Some(handle_state) = app.try_state::<TrackerHandle>() {
+        if let Ok(mut guard) = handle_state.0.lock() {
+            if let Some(handle) = guard.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+    app.exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0m");
+        assert_eq!(format_duration(41 * 60 + 59), "41m");
+        assert_eq!(format_duration(3 * 3600 + 12 * 60), "3h 12m");
+        assert_eq!(format_duration(3600), "1h 0m");
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(0), "0:00");
+        assert_eq!(format_countdown(24 * 60 + 10), "24:10");
+        assert_eq!(format_countdown(90 * 60), "90:00");
+    }
+
+    #[test]
+    fn test_countdown_title_enabled_by_default() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+
+        assert!(countdown_title_enabled(conn).unwrap());
+        Setting::set(conn, COUNTDOWN_TITLE_SETTING, "false").unwrap();
+        assert!(!countdown_title_enabled(conn).unwrap());
+    }
+
+    #[test]
+    fn test_categorize_item_id_names_the_app() {
+        let id = categorize_item_id(3, "Visual Studio_Code");
+        assert_eq!(
+            parse_categorize_item_id(&id),
+            Some((3, "Visual Studio_Code"))
+        );
+        assert_eq!(parse_categorize_item_id("categorize_x_Slack"), None);
+        assert_eq!(parse_categorize_item_id("categorize_3"), None);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::db::{with_connection, Database};
+use crate::error::AppError;
+use crate::models::Setting;
+use crate::tray::refresh_tray;
+use log::warn;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// GitHub releases endpoint describing the latest published version.
+const RELEASES_URL: &str = "https://api.github.com/repos/vtemian/foxus/releases/latest";
+
+/// How often the background checker polls the releases feed.
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_hours(24);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Setting that disables the periodic background check when set to "false".
+pub const AUTO_CHECK_SETTING: &str = "updates.auto_check";
+
+/// Event emitted to the frontend when a newer release is found.
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    body: Option<String>,
+    html_url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub release_notes: String,
+    pub release_url: String,
+    pub update_available: bool,
+}
+
+impl UpdateInfo {
+    fn from_release(current_version: &str, release: Release) -> Self {
+        Self {
+            current_version: current_version.to_string(),
+            update_available: is_newer(&release.tag_name, current_version),
+            latest_version: release.tag_name,
+            release_notes: release.body.unwrap_or_default(),
+            release_url: release.html_url,
+        }
+    }
+}
+
+/// Most recent update found by a check, shown in the tray until the app restarts.
+#[derive(Default)]
+pub struct LatestUpdate(Mutex<Option<UpdateInfo>>);
+
+impl LatestUpdate {
+    pub fn get(app: &AppHandle) -> Option<UpdateInfo> {
+        let state = app.try_state::<Self>()?;
+        let guard = state.0.lock().ok()?;
+        guard.clone()
+    }
+}
+
+/// Query the releases feed and compare it against the running version.
+pub fn fetch_update_info() -> Result<UpdateInfo, AppError> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    let release: Release = agent
+        .get(RELEASES_URL)
+        .set("User-Agent", concat!("foxus/", env!("CARGO_PKG_VERSION")))
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|e| AppError::Internal(format!("update check failed: {e}")))?
+        .into_json()
+        .map_err(|e| AppError::Internal(format!("invalid releases response: {e}")))?;
+
+    Ok(UpdateInfo::from_release(env!("CARGO_PKG_VERSION"), release))
+}
+
+/// Record a check result: remember it for the tray and notify the frontend.
+pub fn publish(app: &AppHandle, info: &UpdateInfo) {
+    if !info.update_available {
+        return;
+    }
+
+    if let Some(state) = app.try_state::<LatestUpdate>() {
+        if let Ok(mut guard) = state.0.lock() {
+            *guard = Some(info.clone());
+        }
+    }
+    if let Err(e) = app.emit(UPDATE_AVAILABLE_EVENT, info) {
+        warn!("Failed to emit update event: {e}");
+    }
+    refresh_tray(app);
+}
+
+/// Check for updates now and then once a day, unless disabled in settings.
+pub fn start_periodic_check(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || loop {
+        if auto_check_wanted(&app) {
+            match fetch_update_info() {
+                Ok(info) => publish(&app, &info),
+                Err(e) => warn!("{e}"),
+            }
+        }
+        thread::sleep(UPDATE_CHECK_INTERVAL);
+    });
+}
+
+/// Whether the background check is enabled (on unless explicitly turned off).
+pub fn auto_check_enabled(conn: &Connection) -> rusqlite::Result<bool> {
+    Ok(Setting::get(conn, AUTO_CHECK_SETTING)?.as_deref() != Some("false"))
+}
+
+fn auto_check_wanted(app: &AppHandle) -> bool {
+    let Some(db) = app.try_state::<Arc<Mutex<Database>>>() else {
+        return false;
+    };
+    with_connection(&db, auto_check_enabled).unwrap_or(false)
+}
+
+/// Parse a `major.minor.patch` version, tolerating a `v` prefix and pre-release suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(str::parse::<u64>);
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("0.1.0"), Some((0, 1, 0)));
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("v2.0.0-beta.1"), Some((2, 0, 0)));
+        assert_eq!(parse_version("v3"), Some((3, 0, 0)));
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.2.0", "0.1.0"));
+        assert!(is_newer("v0.1.10", "0.1.9"));
+        assert!(!is_newer("v0.1.0", "0.1.0"));
+        assert!(!is_newer("v0.0.9", "0.1.0"));
+        assert!(!is_newer("nightly", "0.1.0"));
+    }
+
+    #[test]
+    fn test_from_release() {
+        let release = Release {
+            tag_name: "v9.0.0".into(),
+            body: Some("- Faster stats".into()),
+            html_url: "https://github.com/vtemian/foxus/releases/tag/v9.0.0".into(),
+        };
+
+        let info = UpdateInfo::from_release("0.1.0", release);
+        assert!(info.update_available);
+        assert_eq!(info.latest_version, "v9.0.0");
+        assert_eq!(info.release_notes, "- Faster stats");
+    }
+
+    #[test]
+    fn test_from_release_without_notes() {
+        let release = Release {
+            tag_name: "v0.1.0".into(),
+            body: None,
+            html_url: String::new(),
+        };
+
+        let info = UpdateInfo::from_release("0.1.0", release);
+        assert!(!info.update_available);
+        assert!(info.release_notes.is_empty());
+    }
+
+    #[test]
+    fn test_auto_check_on_until_turned_off() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(auto_check_enabled(conn).unwrap());
+
+        Setting::set(conn, AUTO_CHECK_SETTING, "false").unwrap();
+        assert!(!auto_check_enabled(conn).unwrap());
+    }
+}
@@ -1,8 +1,18 @@
 use crate::constants::{
-    MAX_BUDGET_MINUTES, MAX_BUDGET_SECS, MAX_CATEGORY_NAME_LEN, MAX_RULE_PATTERN_LEN,
-    MAX_RULE_PRIORITY,
+    MAX_ACCRUAL_INTERVAL_MINUTES, MAX_ACCRUAL_REWARD_SECS, MAX_AGGREGATE_DAYS, MAX_APP_NAME_LEN,
+    MAX_AWAY_DAYS, MAX_BREAK_MINUTES, MAX_BREAK_WORK_MINUTES, MAX_BUDGET_MINUTES, MAX_BUDGET_SECS,
+    MAX_CATEGORIZATION_WATCH_SECS, MAX_CATEGORY_NAME_LEN, MAX_DAILY_GOAL_MINUTES,
+    MAX_DEEP_WORK_MINUTES, MAX_DISTRACTION_GRANT_SECS, MAX_DOMAIN_ALLOW_MINUTES,
+    MAX_EXTERNAL_ACTIVITY_SECS, MAX_GIT_REPOSITORIES, MAX_NOTE_LEN, MAX_NOTIFY_LEAD_MINUTES,
+    MAX_PROFILE_LEN, MAX_QUIET_RANGES, MAX_RULE_PATTERNS, MAX_RULE_PATTERN_LEN, MAX_RULE_PRIORITY,
+    MAX_SESSION_EXCEPTIONS, MAX_SOURCE_LABEL_LEN, MAX_TERMINAL_PATH_LEN, MAX_TITLE_EXTRACTORS,
+    MAX_UNLOCK_DELAY_MINUTES, MIN_DISTRACTION_GRANT_SECS,
 };
 use crate::error::AppError;
+use crate::models::RuleCondition;
+use crate::notifications::QuietRange;
+use crate::title_context::TitleExtractor;
+use chrono::{Days, NaiveDate};
 
 /// Validate focus session budget in minutes.
 /// Returns `Ok(budget_secs)` if valid.
@@ -22,6 +32,18 @@ pub fn validate_budget_minutes(budget_minutes: i32) -> Result<i32, AppError> {
     Ok(budget_minutes * 60)
 }
 
+/// Validate a planned focus session length in minutes.
+/// Returns `Ok(duration_secs)` if valid.
+pub fn validate_session_minutes(duration_minutes: i32) -> Result<i32, AppError> {
+    if !(1..=MAX_BUDGET_MINUTES).contains(&duration_minutes) {
+        return Err(AppError::InvalidInput {
+            field: "duration_minutes",
+            reason: format!("must be 1-{MAX_BUDGET_MINUTES}"),
+        });
+    }
+    Ok(duration_minutes * 60)
+}
+
 /// Validate focus schedule budget in seconds.
 pub fn validate_budget_secs(budget_secs: i32) -> Result<(), AppError> {
     if budget_secs < 0 {
@@ -108,6 +130,17 @@ pub fn validate_productivity(productivity: i32) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Validate a category score weight (-1.0 to 1.0).
+pub fn validate_weight(weight: f64) -> Result<f64, AppError> {
+    if !(-1.0..=1.0).contains(&weight) {
+        return Err(AppError::InvalidInput {
+            field: "weight",
+            reason: "must be between -1 and 1".into(),
+        });
+    }
+    Ok(weight)
+}
+
 /// Validate category name.
 pub fn validate_category_name(name: &str) -> Result<&str, AppError> {
     let name = name.trim();
@@ -144,6 +177,245 @@ pub fn validate_rule_pattern(pattern: &str) -> Result<&str, AppError> {
     Ok(pattern)
 }
 
+/// Validate a rule's days and hours with the same parsing as focus schedules.
+pub fn validate_rule_condition(condition: &RuleCondition) -> Result<(), AppError> {
+    validate_days_of_week(&condition.days_of_week)?;
+    validate_time_format(&condition.start_time)?;
+    validate_time_format(&condition.end_time)?;
+    if condition.start_time >= condition.end_time {
+        return Err(AppError::InvalidInput {
+            field: "end_time",
+            reason: "must be after start_time".into(),
+        });
+    }
+    Ok(())
+}
+
+/// Validate all patterns of a rule. Returns them trimmed, in order, with
+/// case-insensitive duplicates dropped.
+pub fn validate_rule_patterns(patterns: &[String]) -> Result<Vec<&str>, AppError> {
+    if patterns.is_empty() || patterns.len() > MAX_RULE_PATTERNS {
+        return Err(AppError::InvalidInput {
+            field: "patterns",
+            reason: format!("must have 1-{MAX_RULE_PATTERNS} patterns"),
+        });
+    }
+    let mut valid: Vec<&str> = Vec::with_capacity(patterns.len());
+    for pattern in patterns {
+        let pattern = validate_rule_pattern(pattern)?;
+        if !valid.iter().any(|p| p.eq_ignore_ascii_case(pattern)) {
+            valid.push(pattern);
+        }
+    }
+    Ok(valid)
+}
+
+/// Validate an app identifier or display name. Returns it trimmed.
+pub fn validate_app_name<'a>(field: &'static str, name: &'a str) -> Result<&'a str, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_APP_NAME_LEN {
+        return Err(AppError::InvalidInput {
+            field,
+            reason: format!("must be 1-{MAX_APP_NAME_LEN} characters"),
+        });
+    }
+    Ok(name)
+}
+
+/// Validate window-title extractors: at most `MAX_TITLE_EXTRACTORS`, and
+/// each pattern has exactly one `*` marking the context.
+pub fn validate_title_extractors(extractors: &[TitleExtractor]) -> Result<(), AppError> {
+    let err = |reason: String| AppError::InvalidInput {
+        field: "extractors",
+        reason,
+    };
+    if extractors.len() > MAX_TITLE_EXTRACTORS {
+        return Err(err(format!(
+            "cannot exceed {MAX_TITLE_EXTRACTORS} extractors"
+        )));
+    }
+    for extractor in extractors {
+        if let TitleExtractor::Pattern { pattern } = extractor {
+            if pattern.matches('*').count() != 1 || pattern.len() > MAX_RULE_PATTERN_LEN {
+                return Err(err(format!(
+                    "pattern '{pattern}' needs exactly one '*' and at most {MAX_RULE_PATTERN_LEN} characters"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate notification quiet hours.
+pub fn validate_quiet_ranges(ranges: &[QuietRange]) -> Result<(), AppError> {
+    validate_weekly_ranges("quiet_hours", ranges)
+}
+
+/// Validate the hours activity is recorded in.
+pub fn validate_tracking_hours(ranges: &[QuietRange]) -> Result<(), AppError> {
+    validate_weekly_ranges("tracking_hours", ranges)
+}
+
+/// Validate the work hours outside which personal-time rules apply.
+pub fn validate_work_hours(ranges: &[QuietRange]) -> Result<(), AppError> {
+    validate_weekly_ranges("work_hours", ranges)
+}
+
+/// At most `MAX_QUIET_RANGES` weekly ranges, each with valid days and
+/// distinct HH:MM start and end times.
+fn validate_weekly_ranges(field: &'static str, ranges: &[QuietRange]) -> Result<(), AppError> {
+    if ranges.len() > MAX_QUIET_RANGES {
+        return Err(AppError::InvalidInput {
+            field,
+            reason: format!("cannot exceed {MAX_QUIET_RANGES} ranges"),
+        });
+    }
+    for range in ranges {
+        validate_days_of_week(&range.days_of_week)?;
+        validate_time_format(&range.start_time)?;
+        validate_time_format(&range.end_time)?;
+        if range.start_time == range.end_time {
+            return Err(AppError::InvalidInput {
+                field,
+                reason: "start and end times must differ".into(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Validate a browser profile name a rule is limited to.
+pub fn validate_profile(profile: &str) -> Result<&str, AppError> {
+    let profile = profile.trim();
+    if profile.is_empty() {
+        return Err(AppError::InvalidInput {
+            field: "profile",
+            reason: "cannot be empty".into(),
+        });
+    }
+    if profile.len() > MAX_PROFILE_LEN {
+        return Err(AppError::InvalidInput {
+            field: "profile",
+            reason: format!("cannot exceed {MAX_PROFILE_LEN} characters"),
+        });
+    }
+    Ok(profile)
+}
+
+/// Validate an external activity source label, returning it lowercased.
+///
+/// Labels are short identifiers (`a-z`, `0-9`, `-`, `_`) and can't reuse the
+/// built-in `app` and `browser` sources.
+pub fn validate_source_label(label: &str) -> Result<String, AppError> {
+    let label = label.trim().to_ascii_lowercase();
+    let err = |reason: String| AppError::InvalidInput {
+        field: "source",
+        reason,
+    };
+    if label.is_empty() || label.len() > MAX_SOURCE_LABEL_LEN {
+        return Err(err(format!("must be 1-{MAX_SOURCE_LABEL_LEN} characters")));
+    }
+    if !label
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(err("may only contain letters, digits, '-' and '_'".into()));
+    }
+    if label == "app" || label == "browser" {
+        return Err(err(format!("'{label}' is reserved")));
+    }
+    Ok(label)
+}
+
+/// Validate the name a device syncs under, returning it lowercased. It
+/// names the device's file in the sync folder.
+pub fn validate_device_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SOURCE_LABEL_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AppError::InvalidInput {
+            field: "device",
+            reason: format!("must be 1-{MAX_SOURCE_LABEL_LEN} letters, digits, '-' or '_'"),
+        });
+    }
+    Ok(name)
+}
+
+/// Validate a user-chosen folder, e.g. for sync or snapshots: an existing directory.
+pub fn validate_folder(folder: &str) -> Result<&str, AppError> {
+    let folder = folder.trim();
+    if !std::path::Path::new(folder).is_dir() {
+        return Err(AppError::InvalidInput {
+            field: "folder",
+            reason: format!("'{folder}' is not a directory"),
+        });
+    }
+    Ok(folder)
+}
+
+/// Validate a directory reported by the shell hook. Returns it trimmed.
+pub fn validate_terminal_path<'a>(field: &'static str, path: &'a str) -> Result<&'a str, AppError> {
+    let path = path.trim();
+    if path.is_empty() || path.len() > MAX_TERMINAL_PATH_LEN {
+        return Err(AppError::InvalidInput {
+            field,
+            reason: format!("must be 1-{MAX_TERMINAL_PATH_LEN} characters"),
+        });
+    }
+    Ok(path)
+}
+
+/// Validate repositories to scan for commits: existing directories, at most
+/// `MAX_GIT_REPOSITORIES`. Returns them trimmed and deduplicated.
+pub fn validate_git_repositories(repos: &[String]) -> Result<Vec<String>, AppError> {
+    let mut valid: Vec<String> = Vec::new();
+    for repo in repos.iter().map(|r| r.trim()) {
+        if !std::path::Path::new(repo).is_dir() {
+            return Err(AppError::InvalidInput {
+                field: "repositories",
+                reason: format!("'{repo}' is not a directory"),
+            });
+        }
+        if !valid.iter().any(|r| r == repo) {
+            valid.push(repo.to_string());
+        }
+    }
+    if valid.len() > MAX_GIT_REPOSITORIES {
+        return Err(AppError::InvalidInput {
+            field: "repositories",
+            reason: format!("at most {MAX_GIT_REPOSITORIES} allowed"),
+        });
+    }
+    Ok(valid)
+}
+
+/// Validate a Toggl (numeric) or Clockify (alphanumeric) workspace id.
+pub fn validate_workspace_id<'a>(field: &'static str, id: &'a str) -> Result<&'a str, AppError> {
+    let id = id.trim();
+    if id.is_empty() || id.len() > 64 || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(AppError::InvalidInput {
+            field,
+            reason: "must be 1-64 letters or digits".into(),
+        });
+    }
+    Ok(id)
+}
+
+/// Validate the length of externally reported time.
+pub fn validate_external_duration(secs: i32) -> Result<(), AppError> {
+    if !(1..=MAX_EXTERNAL_ACTIVITY_SECS).contains(&secs) {
+        return Err(AppError::InvalidInput {
+            field: "duration_secs",
+            reason: format!("must be 1-{MAX_EXTERNAL_ACTIVITY_SECS}"),
+        });
+    }
+    Ok(())
+}
+
 /// Validate rule priority.
 pub fn validate_rule_priority(priority: i32) -> Result<(), AppError> {
     if !(0..=MAX_RULE_PRIORITY).contains(&priority) {
@@ -155,10 +427,455 @@ pub fn validate_rule_priority(priority: i32) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Validate continuous work minutes before a break reminder.
+pub fn validate_break_work_minutes(work_minutes: i32) -> Result<(), AppError> {
+    if !(1..=MAX_BREAK_WORK_MINUTES).contains(&work_minutes) {
+        return Err(AppError::InvalidInput {
+            field: "work_minutes",
+            reason: format!("must be 1-{MAX_BREAK_WORK_MINUTES}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate the length of an enforced break in minutes.
+pub fn validate_break_minutes(break_minutes: i32) -> Result<(), AppError> {
+    if !(1..=MAX_BREAK_MINUTES).contains(&break_minutes) {
+        return Err(AppError::InvalidInput {
+            field: "break_minutes",
+            reason: format!("must be 1-{MAX_BREAK_MINUTES}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate how many days an aggregate export covers.
+pub fn validate_aggregate_days(days: u32) -> Result<(), AppError> {
+    if !(1..=MAX_AGGREGATE_DAYS).contains(&days) {
+        return Err(AppError::InvalidInput {
+            field: "days",
+            reason: format!("must be 1-{MAX_AGGREGATE_DAYS}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate how many minutes ahead a schedule is announced (0 for never).
+pub fn validate_notify_lead_minutes(minutes: i32) -> Result<(), AppError> {
+    if !(0..=MAX_NOTIFY_LEAD_MINUTES).contains(&minutes) {
+        return Err(AppError::InvalidInput {
+            field: "notify_lead_minutes",
+            reason: format!("must be 0-{MAX_NOTIFY_LEAD_MINUTES}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate a deep-work threshold or extension in minutes.
+pub fn validate_deep_work_minutes(minutes: i32, field: &'static str) -> Result<(), AppError> {
+    if !(1..=MAX_DEEP_WORK_MINUTES).contains(&minutes) {
+        return Err(AppError::InvalidInput {
+            field,
+            reason: format!("must be 1-{MAX_DEEP_WORK_MINUTES}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate the daily focus goal in minutes.
+pub fn validate_goal_minutes(minutes: i32) -> Result<(), AppError> {
+    if !(1..=MAX_DAILY_GOAL_MINUTES).contains(&minutes) {
+        return Err(AppError::InvalidInput {
+            field: "minutes",
+            reason: format!("must be 1-{MAX_DAILY_GOAL_MINUTES}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate the emergency unlock delay in minutes.
+pub fn validate_unlock_delay_minutes(minutes: i32) -> Result<(), AppError> {
+    if !(1..=MAX_UNLOCK_DELAY_MINUTES).contains(&minutes) {
+        return Err(AppError::InvalidInput {
+            field: "minutes",
+            reason: format!("must be 1-{MAX_UNLOCK_DELAY_MINUTES}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate how long a domain is allowed during a session.
+/// Returns `Ok(secs)` if valid.
+pub fn validate_allow_minutes(minutes: i32) -> Result<i32, AppError> {
+    if !(1..=MAX_DOMAIN_ALLOW_MINUTES).contains(&minutes) {
+        return Err(AppError::InvalidInput {
+            field: "minutes",
+            reason: format!("must be 1-{MAX_DOMAIN_ALLOW_MINUTES}"),
+        });
+    }
+    Ok(minutes * 60)
+}
+
+/// Validate a bare domain name like `news.example.com`, returned lowercased.
+pub fn validate_domain(domain: &str) -> Result<String, AppError> {
+    let domain = domain.trim().to_lowercase();
+    let valid = !domain.is_empty()
+        && domain.len() <= MAX_RULE_PATTERN_LEN
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+    if !valid {
+        return Err(AppError::InvalidInput {
+            field: "domain",
+            reason: "must be a domain name like example.com".into(),
+        });
+    }
+    Ok(domain)
+}
+
+/// Validate a session's exception domains, returned lowercased.
+pub fn validate_session_exceptions(domains: &[String]) -> Result<Vec<String>, AppError> {
+    if domains.len() > MAX_SESSION_EXCEPTIONS {
+        return Err(AppError::InvalidInput {
+            field: "exceptions",
+            reason: format!("cannot exceed {MAX_SESSION_EXCEPTIONS} domains"),
+        });
+    }
+    domains.iter().map(|d| validate_domain(d)).collect()
+}
+
+/// Validate how long to stream live categorization, in seconds.
+pub fn validate_watch_secs(secs: u32) -> Result<(), AppError> {
+    if !(1..=MAX_CATEGORIZATION_WATCH_SECS).contains(&secs) {
+        return Err(AppError::InvalidInput {
+            field: "seconds",
+            reason: format!("must be 1-{MAX_CATEGORIZATION_WATCH_SECS}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate the budget seconds drawn per distraction-time request.
+pub fn validate_grant_secs(secs: i32) -> Result<(), AppError> {
+    if !(MIN_DISTRACTION_GRANT_SECS..=MAX_DISTRACTION_GRANT_SECS).contains(&secs) {
+        return Err(AppError::InvalidInput {
+            field: "secs",
+            reason: format!("must be {MIN_DISTRACTION_GRANT_SECS}-{MAX_DISTRACTION_GRANT_SECS}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate "earn it" budget accrual: reward size and the productive time per reward.
+pub fn validate_accrual(interval_minutes: i32, reward_secs: i32) -> Result<(), AppError> {
+    if !(1..=MAX_ACCRUAL_INTERVAL_MINUTES).contains(&interval_minutes) {
+        return Err(AppError::InvalidInput {
+            field: "interval_minutes",
+            reason: format!("must be 1-{MAX_ACCRUAL_INTERVAL_MINUTES}"),
+        });
+    }
+    if !(1..=MAX_ACCRUAL_REWARD_SECS).contains(&reward_secs) {
+        return Err(AppError::InvalidInput {
+            field: "reward_secs",
+            reason: format!("must be 1-{MAX_ACCRUAL_REWARD_SECS}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate a user-configured endpoint URL: must be absolute http(s).
+pub fn validate_http_url<'a>(url: &'a str, field: &'static str) -> Result<&'a str, AppError> {
+    let url = url.trim();
+    let err = |reason: &str| AppError::InvalidInput {
+        field,
+        reason: reason.into(),
+    };
+    let parsed = url::Url::parse(url).map_err(|_| err("must be a valid URL"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(err("must use http or https"));
+    }
+    Ok(url)
+}
+
+/// Validate an email address loosely: one `@` with text on both sides and a dotted domain.
+pub fn validate_email(email: &str) -> Result<&str, AppError> {
+    let email = email.trim();
+    let valid = email.split_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty()
+            && !domain.contains('@')
+            && domain.split('.').count() >= 2
+            && domain.split('.').all(|part| !part.is_empty())
+            && !email.contains(char::is_whitespace)
+    });
+    if !valid {
+        return Err(AppError::InvalidInput {
+            field: "email",
+            reason: "must be a valid email address".into(),
+        });
+    }
+    Ok(email)
+}
+
+/// Validate an LLM model name.
+pub fn validate_model_name(model: &str) -> Result<&str, AppError> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err(AppError::InvalidInput {
+            field: "model",
+            reason: "cannot be empty".into(),
+        });
+    }
+    Ok(model)
+}
+
+/// Validate a Discord application id (a numeric snowflake).
+pub fn validate_discord_client_id(id: &str) -> Result<&str, AppError> {
+    let id = id.trim();
+    if id.is_empty() || id.len() > 20 || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::InvalidInput {
+            field: "client_id",
+            reason: "must be a numeric Discord application id".into(),
+        });
+    }
+    Ok(id)
+}
+
+/// Validate a server host name or address.
+pub fn validate_host(host: &str) -> Result<&str, AppError> {
+    let host = host.trim();
+    if host.is_empty() || host.len() > 253 || host.contains(|c: char| c.is_whitespace() || c == '/')
+    {
+        return Err(AppError::InvalidInput {
+            field: "host",
+            reason: "must be a host name or IP address".into(),
+        });
+    }
+    Ok(host)
+}
+
+/// Validate an MQTT topic prefix: no wildcards and no leading or trailing `/`.
+pub fn validate_topic_prefix(prefix: &str) -> Result<&str, AppError> {
+    let prefix = prefix.trim();
+    if prefix.is_empty()
+        || prefix.len() > 128
+        || prefix.contains(['+', '#'])
+        || prefix.starts_with('/')
+        || prefix.ends_with('/')
+    {
+        return Err(AppError::InvalidInput {
+            field: "topic_prefix",
+            reason: "must be 1-128 characters without '+', '#', or a leading/trailing '/'".into(),
+        });
+    }
+    Ok(prefix)
+}
+
+/// Validate a weekday number (1=Monday, 7=Sunday).
+pub fn validate_weekday(day: u32) -> Result<(), AppError> {
+    if !(1..=7).contains(&day) {
+        return Err(AppError::InvalidInput {
+            field: "report_day",
+            reason: "must be 1-7".into(),
+        });
+    }
+    Ok(())
+}
+
+/// Validate a local date in YYYY-MM-DD format.
+pub fn validate_day(day: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| AppError::InvalidInput {
+        field: "day",
+        reason: "must be in YYYY-MM-DD format".into(),
+    })
+}
+
+/// Validate the date away mode lasts until: after `today` and within
+/// `MAX_AWAY_DAYS` of it.
+pub fn validate_away_until(until: &str, today: NaiveDate) -> Result<NaiveDate, AppError> {
+    let date =
+        NaiveDate::parse_from_str(until, "%Y-%m-%d").map_err(|_| AppError::InvalidInput {
+            field: "until_date",
+            reason: "must be in YYYY-MM-DD format".into(),
+        })?;
+    if date <= today {
+        return Err(AppError::InvalidInput {
+            field: "until_date",
+            reason: "must be after today".into(),
+        });
+    }
+    if today
+        .checked_add_days(Days::new(MAX_AWAY_DAYS))
+        .is_some_and(|latest| date > latest)
+    {
+        return Err(AppError::InvalidInput {
+            field: "until_date",
+            reason: format!("must be within {MAX_AWAY_DAYS} days"),
+        });
+    }
+    Ok(date)
+}
+
+/// Validate the text of a day note.
+pub fn validate_note_text(text: &str) -> Result<&str, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::InvalidInput {
+            field: "text",
+            reason: "cannot be empty".into(),
+        });
+    }
+    if text.len() > MAX_NOTE_LEN {
+        return Err(AppError::InvalidInput {
+            field: "text",
+            reason: format!("cannot exceed {MAX_NOTE_LEN} characters"),
+        });
+    }
+    Ok(text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_rule_condition() {
+        let condition = |days: &str, start: &str, end: &str| RuleCondition {
+            days_of_week: days.into(),
+            start_time: start.into(),
+            end_time: end.into(),
+        };
+        assert!(validate_rule_condition(&condition("1,2,3,4,5", "12:00", "13:00")).is_ok());
+        assert!(validate_rule_condition(&condition("1,8", "12:00", "13:00")).is_err());
+        assert!(validate_rule_condition(&condition("1", "12:60", "13:00")).is_err());
+        assert!(validate_rule_condition(&condition("1", "13:00", "12:00")).is_err());
+    }
+
+    #[test]
+    fn test_validate_rule_patterns() {
+        let patterns = vec![
+            " cnn.com ".to_string(),
+            "bbc.co.uk".into(),
+            "CNN.com".into(),
+        ];
+        assert_eq!(
+            validate_rule_patterns(&patterns).unwrap(),
+            vec!["cnn.com", "bbc.co.uk"]
+        );
+        assert!(validate_rule_patterns(&[]).is_err());
+        assert!(validate_rule_patterns(&["cnn.com".into(), "  ".into()]).is_err());
+    }
+
+    #[test]
+    fn test_validate_title_extractors() {
+        let pattern = |p: &str| TitleExtractor::Pattern { pattern: p.into() };
+        assert!(validate_title_extractors(&[TitleExtractor::Ticket, pattern("* - Docs")]).is_ok());
+        assert!(validate_title_extractors(&[pattern("Docs")]).is_err());
+        assert!(validate_title_extractors(&[pattern("* - *")]).is_err());
+        assert!(validate_title_extractors(&vec![TitleExtractor::Repo; 21]).is_err());
+    }
+
+    #[test]
+    fn test_validate_domain() {
+        assert_eq!(validate_domain(" YouTube.com ").unwrap(), "youtube.com");
+        assert!(validate_domain("https://youtube.com").is_err());
+        assert!(validate_domain(".com").is_err());
+        assert!(validate_domain("").is_err());
+        assert_eq!(validate_allow_minutes(15).unwrap(), 900);
+        assert!(validate_allow_minutes(16).is_err());
+        assert_eq!(
+            validate_session_exceptions(&["Music.YouTube.com".into()]).unwrap(),
+            vec!["music.youtube.com"]
+        );
+        assert!(validate_session_exceptions(&vec!["a.com".into(); 51]).is_err());
+    }
+
+    #[test]
+    fn test_validate_quiet_ranges() {
+        let range = |days: &str, start: &str, end: &str| QuietRange {
+            days_of_week: days.into(),
+            start_time: start.into(),
+            end_time: end.into(),
+        };
+        assert!(validate_quiet_ranges(&[range("1,2,3,4,5", "18:00", "08:00")]).is_ok());
+        assert!(validate_quiet_ranges(&[range("8", "18:00", "19:00")]).is_err());
+        assert!(validate_quiet_ranges(&[range("1", "18:00", "18:00")]).is_err());
+        assert!(validate_quiet_ranges(&[range("1", "25:00", "08:00")]).is_err());
+    }
+
+    #[test]
+    fn test_validate_app_name() {
+        assert_eq!(
+            validate_app_name("identifier", " com.apple.Terminal ").unwrap(),
+            "com.apple.Terminal"
+        );
+        assert!(validate_app_name("display_name", "  ").is_err());
+        assert!(validate_app_name("display_name", &"a".repeat(300)).is_err());
+    }
+
+    #[test]
+    fn test_validate_host_and_prefix() {
+        assert_eq!(validate_host(" 192.168.1.5 ").unwrap(), "192.168.1.5");
+        assert!(validate_host("mqtt://broker").is_err());
+        assert_eq!(validate_topic_prefix("home/foxus").unwrap(), "home/foxus");
+        assert!(validate_topic_prefix("home/#").is_err());
+        assert!(validate_topic_prefix("foxus/").is_err());
+    }
+
+    #[test]
+    fn test_validate_discord_client_id() {
+        assert_eq!(
+            validate_discord_client_id(" 1234567890 ").unwrap(),
+            "1234567890"
+        );
+        assert!(validate_discord_client_id("").is_err());
+        assert!(validate_discord_client_id("abc").is_err());
+    }
+
+    #[test]
+    fn test_validate_workspace_id() {
+        assert_eq!(validate_workspace_id("w", " 1234 ").unwrap(), "1234");
+        assert!(validate_workspace_id("w", "").is_err());
+        assert!(validate_workspace_id("w", "12/../34").is_err());
+    }
+
+    #[test]
+    fn test_validate_git_repositories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().into_owned();
+        assert_eq!(
+            validate_git_repositories(&[format!(" {path} "), path.clone()]).unwrap(),
+            vec![path.clone()]
+        );
+        assert!(validate_git_repositories(&[format!("{path}/missing")]).is_err());
+        assert!(validate_git_repositories(&[String::new()]).is_err());
+    }
+
+    #[test]
+    fn test_validate_source_label() {
+        assert_eq!(validate_source_label(" VSCode ").unwrap(), "vscode");
+        assert_eq!(validate_source_label("zsh-timer_2").unwrap(), "zsh-timer_2");
+        assert!(validate_source_label("").is_err());
+        assert!(validate_source_label("app").is_err());
+        assert!(validate_source_label("my:tool").is_err());
+        assert!(validate_source_label(&"a".repeat(MAX_SOURCE_LABEL_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_grant_secs() {
+        assert!(validate_grant_secs(30).is_ok());
+        assert!(validate_grant_secs(MIN_DISTRACTION_GRANT_SECS - 1).is_err());
+        assert!(validate_grant_secs(MAX_DISTRACTION_GRANT_SECS + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        assert_eq!(validate_profile(" Work ").unwrap(), "Work");
+        assert!(validate_profile("   ").is_err());
+        assert!(validate_profile(&"p".repeat(MAX_PROFILE_LEN + 1)).is_err());
+    }
+
     #[test]
     fn test_validate_budget_minutes_valid() {
         assert!(validate_budget_minutes(10).is_ok());
@@ -180,6 +897,81 @@ mod tests {
         assert!(validate_budget_minutes(MAX_BUDGET_MINUTES + 1).is_err());
     }
 
+    #[test]
+    fn test_validate_break_work_minutes() {
+        assert!(validate_break_work_minutes(50).is_ok());
+        assert!(validate_break_work_minutes(0).is_err());
+        assert!(validate_break_work_minutes(MAX_BREAK_WORK_MINUTES + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_break_minutes() {
+        assert!(validate_break_minutes(5).is_ok());
+        assert!(validate_break_minutes(0).is_err());
+        assert!(validate_break_minutes(MAX_BREAK_MINUTES + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_http_url() {
+        assert_eq!(
+            validate_http_url(" https://hooks.example.com/x ", "webhook_url").unwrap(),
+            "https://hooks.example.com/x"
+        );
+        assert!(
+            validate_http_url("http://localhost:11434/v1/chat/completions", "endpoint").is_ok()
+        );
+        assert!(validate_http_url("ftp://example.com", "webhook_url").is_err());
+        assert!(validate_http_url("not a url", "webhook_url").is_err());
+    }
+
+    #[test]
+    fn test_validate_weight() {
+        assert!(validate_weight(0.5).is_ok());
+        assert!(validate_weight(-1.0).is_ok());
+        assert!(validate_weight(1.5).is_err());
+        assert!(validate_weight(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_validate_day() {
+        assert!(validate_day("2026-03-02").is_ok());
+        assert!(validate_day("2026-02-30").is_err());
+        assert!(validate_day("03/02/2026").is_err());
+    }
+
+    #[test]
+    fn test_validate_away_until() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert!(validate_away_until("2026-10-16", today).is_ok());
+        assert!(validate_away_until("2026-10-15", today).is_err());
+        assert!(validate_away_until("2028-10-15", today).is_err());
+        assert!(validate_away_until("next week", today).is_err());
+    }
+
+    #[test]
+    fn test_validate_device_name() {
+        assert_eq!(validate_device_name(" Phone ").unwrap(), "phone");
+        assert!(validate_device_name("../desktop").is_err());
+        assert!(validate_device_name("").is_err());
+    }
+
+    #[test]
+    fn test_validate_email() {
+        assert!(validate_email("partner@example.com").is_ok());
+        assert!(validate_email("partner@localhost").is_err());
+        assert!(validate_email("@example.com").is_err());
+        assert!(validate_email("a b@example.com").is_err());
+    }
+
+    #[test]
+    fn test_validate_deep_work_minutes() {
+        assert!(validate_deep_work_minutes(30, "threshold_minutes").is_ok());
+        assert!(validate_deep_work_minutes(0, "threshold_minutes").is_err());
+        assert!(
+            validate_deep_work_minutes(MAX_DEEP_WORK_MINUTES + 1, "extension_minutes").is_err()
+        );
+    }
+
     #[test]
     fn test_validate_time_format_valid() {
         assert!(validate_time_format("09:00").is_ok());
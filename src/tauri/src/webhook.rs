@@ -0,0 +1,18 @@
+use crate::error::AppError;
+use serde::Serialize;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// POST `payload` as JSON to a user-configured webhook.
+///
+/// Blocks until the request completes, so call it off the UI and command threads.
+pub fn post_json<T: Serialize>(url: &str, payload: &T) -> Result<(), AppError> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    agent
+        .post(url)
+        .set("User-Agent", concat!("foxus/", env!("CARGO_PKG_VERSION")))
+        .send_json(payload)
+        .map_err(|e| AppError::Internal(format!("webhook request failed: {e}")))?;
+    Ok(())
+}
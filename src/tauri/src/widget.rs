@@ -0,0 +1,43 @@
+use crate::data_dir::webview_dir;
+use tauri::{webview::WebviewWindowBuilder, AppHandle, Manager, WebviewUrl};
+
+/// Window label of the floating focus widget.
+pub const WIDGET_LABEL: &str = "focus-widget";
+
+/// The frontend renders the compact widget view instead of the dashboard for this hash.
+const WIDGET_URL: &str = "index.html#widget";
+
+const WIDGET_WIDTH: f64 = 220.0;
+const WIDGET_HEIGHT: f64 = 96.0;
+
+/// Show the always-on-top focus widget, creating it on first use.
+pub fn show_focus_widget(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(WIDGET_LABEL) {
+        window.show()?;
+        return window.set_focus();
+    }
+
+    // Undecorated, so the frontend marks its body as a drag region to keep it movable
+    let mut builder =
+        WebviewWindowBuilder::new(app, WIDGET_LABEL, WebviewUrl::App(WIDGET_URL.into()))
+            .title("Foxus Focus")
+            .inner_size(WIDGET_WIDTH, WIDGET_HEIGHT)
+            .resizable(false)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true);
+    if let Some(dir) = webview_dir() {
+        builder = builder.data_directory(dir);
+    }
+    builder.build()?;
+
+    Ok(())
+}
+
+/// Close the focus widget if it is open.
+pub fn close_focus_widget(app: &AppHandle) -> tauri::Result<()> {
+    match app.get_webview_window(WIDGET_LABEL) {
+        Some(window) => window.close(),
+        None => Ok(()),
+    }
+}
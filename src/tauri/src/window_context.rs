@@ -0,0 +1,73 @@
+use crate::models::Setting;
+use rusqlite::{Connection, Result};
+
+const ENABLED_SETTING: &str = "tracking.capture_context";
+
+/// Visible windows beyond this many (topmost first) are left out of a snapshot.
+const MAX_CONTEXT_WINDOWS: usize = 8;
+
+/// Whether the tracker records which other windows are on screen. Off by default.
+pub fn capture_enabled(conn: &Connection) -> Result<bool> {
+    Ok(Setting::get(conn, ENABLED_SETTING)?.as_deref() == Some("true"))
+}
+
+pub fn set_capture_enabled(conn: &Connection, enabled: bool) -> Result<()> {
+    Setting::set(conn, ENABLED_SETTING, &enabled.to_string())
+}
+
+/// Compact snapshot of what sits beside the focused window: the sorted,
+/// deduplicated category ids of the topmost visible windows, e.g. `[2,5]`.
+///
+/// Sorting makes equal surroundings produce equal strings so stats can group
+/// on them. Returns `None` when nothing else is on screen.
+pub fn snapshot(category_ids: impl IntoIterator<Item = i64>) -> Option<String> {
+    let mut ids: Vec<i64> = category_ids.into_iter().take(MAX_CONTEXT_WINDOWS).collect();
+    if ids.is_empty() {
+        return None;
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    serde_json::to_string(&ids).ok()
+}
+
+/// Category ids in a stored snapshot; empty for a missing or malformed one.
+pub fn parse_snapshot(snapshot: Option<&str>) -> Vec<i64> {
+    snapshot
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn test_snapshot_is_canonical() {
+        assert_eq!(snapshot([5, 2, 5]), Some("[2,5]".to_string()));
+        assert_eq!(snapshot([2, 5]), snapshot([5, 2]));
+        assert_eq!(snapshot([]), None);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_topmost_windows() {
+        let ids = snapshot((1..=20).rev()).unwrap();
+        assert_eq!(parse_snapshot(Some(&ids)), (13..=20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_parse_snapshot() {
+        assert_eq!(parse_snapshot(Some("[3,4]")), vec![3, 4]);
+        assert!(parse_snapshot(Some("garbage")).is_empty());
+        assert!(parse_snapshot(None).is_empty());
+    }
+
+    #[test]
+    fn test_capture_setting() {
+        let (db, _dir) = setup_test_db();
+        let conn = db.connection();
+        assert!(!capture_enabled(conn).unwrap());
+        set_capture_enabled(conn, true).unwrap();
+        assert!(capture_enabled(conn).unwrap());
+    }
+}
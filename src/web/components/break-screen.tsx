@@ -0,0 +1,67 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useEffect, useState } from "react";
+import { Button, Typography } from "@/components/ui";
+import type { ActiveBreak } from "@/types/api";
+import { formatBudget, MS_PER_SECOND } from "@/utils/formatters";
+
+const nowSecs = (): number => Math.floor(Date.now() / MS_PER_SECOND);
+
+const useActiveBreak = (): ActiveBreak | null => {
+  const [activeBreak, setActiveBreak] = useState<ActiveBreak | null>(null);
+
+  useEffect(() => {
+    const load = async () => {
+      try {
+        setActiveBreak(await invoke<ActiveBreak | null>("get_active_break"));
+      } catch (e: unknown) {
+        console.error("Failed to load active break:", e);
+      }
+    };
+    void load();
+  }, []);
+
+  return activeBreak;
+};
+
+const useSecondsUntil = (endsAt: number | null): number => {
+  const [now, setNow] = useState(nowSecs);
+
+  useEffect(() => {
+    const interval = setInterval(() => setNow(nowSecs()), MS_PER_SECOND);
+    return () => clearInterval(interval);
+  }, []);
+
+  return endsAt === null ? 0 : Math.max(0, endsAt - now);
+};
+
+const emergencySkip = async () => {
+  try {
+    await invoke("emergency_skip_break");
+  } catch (e: unknown) {
+    console.error("Failed to skip break:", e);
+  }
+};
+
+/**
+ * Full-screen view rendered in the enforced break window.
+ * The backend closes the window when the break ends or is skipped.
+ */
+const BreakScreen = () => {
+  const activeBreak = useActiveBreak();
+  const remaining = useSecondsUntil(activeBreak?.ends_at ?? null);
+
+  return (
+    <div className="h-screen flex flex-col items-center justify-center gap-6 select-none">
+      <Typography variant="h1">Time for a break</Typography>
+      <Typography variant="budget">{formatBudget(remaining)}</Typography>
+      <Typography color="muted">
+        Step away from the screen. Foxus will unlock when the break ends.
+      </Typography>
+      <Button variant="ghost" onClick={() => void emergencySkip()}>
+        Emergency skip
+      </Button>
+    </div>
+  );
+};
+
+export { BreakScreen };
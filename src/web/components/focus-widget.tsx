@@ -0,0 +1,57 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useEffect, useState } from "react";
+import { Typography } from "@/components/ui";
+import type { FocusState } from "@/types/api";
+import { formatBudget, MS_PER_SECOND } from "@/utils/formatters";
+
+const useFocusStatePolling = (): FocusState | null => {
+  const [focusState, setFocusState] = useState<FocusState | null>(null);
+
+  useEffect(() => {
+    const load = async () => {
+      try {
+        setFocusState(await invoke<FocusState>("get_focus_state"));
+      } catch (e: unknown) {
+        console.error("Failed to load focus state:", e);
+      }
+    };
+    void load();
+    const interval = setInterval(() => void load(), MS_PER_SECOND);
+    return () => clearInterval(interval);
+  }, []);
+
+  return focusState;
+};
+
+/**
+ * Compact always-on-top view rendered in the floating focus widget window.
+ * The whole surface is a drag region since the window has no title bar.
+ */
+const FocusWidget = () => {
+  const focusState = useFocusStatePolling();
+  const active = focusState?.active ?? false;
+  const elapsed = focusState?.session_duration_secs ?? 0;
+  const remaining = focusState?.time_remaining_secs ?? null;
+  const timer = remaining === null ? formatBudget(elapsed) : `${formatBudget(remaining)} left`;
+
+  return (
+    <div data-tauri-drag-region className="h-screen p-3 flex flex-col justify-center select-none">
+      {active && focusState ? (
+        <>
+          <Typography variant="label" color="muted" data-tauri-drag-region>
+            Focus {timer}
+          </Typography>
+          <Typography variant="budget" data-tauri-drag-region>
+            {formatBudget(focusState.budget_remaining)}
+          </Typography>
+        </>
+      ) : (
+        <Typography variant="label" color="muted" data-tauri-drag-region>
+          No focus session
+        </Typography>
+      )}
+    </div>
+  );
+};
+
+export { FocusWidget };
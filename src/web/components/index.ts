@@ -2,8 +2,10 @@
 
 export type { AppListItemProps } from "./app-list-item";
 export { AppListItem } from "./app-list-item";
+export { BreakScreen } from "./break-screen";
 export type { FocusViewProps } from "./focus-view";
 export { FocusView } from "./focus-view";
+export { FocusWidget } from "./focus-widget";
 export type { HeaderProps } from "./header";
 export { Header } from "./header";
 export { SettingsView } from "./settings-view";
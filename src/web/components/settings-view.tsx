@@ -3,6 +3,7 @@ import { CategoriesTab } from "@/components/settings/category-tab";
 import { RulesTab } from "@/components/settings/rules-tab";
 import { TabButton } from "@/components/settings/shared";
 import type { SettingsViewProps } from "@/components/settings/types";
+import { UpdateCheckToggle } from "@/components/settings/update-check-toggle";
 import { Typography } from "@/components/ui";
 import type { UseSettingsReturn } from "@/hooks/use-settings";
 import { useSettings } from "@/hooks/use-settings";
@@ -79,6 +80,7 @@ const SettingsView = ({ onClose }: SettingsViewProps) => {
   return (
     <div className="space-y-4">
       <SettingsHeader onClose={onClose} />
+      <UpdateCheckToggle />
       <TabNav activeTab={activeTab} onTabChange={setActiveTab} />
       {settings.error && (
         <Typography variant="body" color="distracting" className="text-xs">
@@ -134,7 +134,7 @@ const RulesTab = ({ rules, categories, createRule, updateRule, deleteRule }: Rul
           categories={categories}
           isEditing={editingId === rule.id}
           onSave={async (pattern, matchType, categoryId, priority) => {
-            await updateRule(rule.id, pattern, matchType, categoryId, priority);
+            await updateRule(rule.id, pattern, matchType, categoryId, priority, rule.version);
             setEditingId(null);
           }}
           onCancelEdit={() => setEditingId(null)}
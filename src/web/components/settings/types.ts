@@ -28,6 +28,7 @@ interface RulesTabProps {
     matchType: MatchType,
     categoryId: number,
     priority: number,
+    version: number,
   ) => Promise<void>;
   deleteRule: (id: number) => Promise<void>;
 }
@@ -0,0 +1,45 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useCallback, useEffect, useState } from "react";
+import { Typography } from "@/components/ui";
+
+const UpdateCheckToggle = () => {
+  const [enabled, setEnabled] = useState<boolean | null>(null);
+
+  useEffect(() => {
+    const load = async () => {
+      try {
+        setEnabled(await invoke<boolean>("get_update_auto_check"));
+      } catch (e: unknown) {
+        console.error("Failed to load update check setting:", e);
+      }
+    };
+    load();
+  }, []);
+
+  const toggle = useCallback(async (next: boolean) => {
+    try {
+      await invoke("set_update_auto_check", { enabled: next });
+      setEnabled(next);
+    } catch (e: unknown) {
+      console.error("Failed to save update check setting:", e);
+    }
+  }, []);
+
+  if (enabled === null) return null;
+
+  return (
+    <label className="flex items-center gap-2 cursor-pointer">
+      <input
+        type="checkbox"
+        checked={enabled}
+        onChange={(e) => toggle(e.target.checked)}
+        className="accent-gray-600"
+      />
+      <Typography variant="body" color="secondary">
+        Check for updates automatically
+      </Typography>
+    </label>
+  );
+};
+
+export { UpdateCheckToggle };
@@ -22,6 +22,7 @@ interface UseSettingsReturn {
     matchType: MatchType,
     categoryId: number,
     priority: number,
+    version: number,
   ) => Promise<void>;
   deleteRule: (id: number) => Promise<void>;
   refresh: () => Promise<void>;
@@ -47,6 +48,10 @@ const useInvokeWithReload = (
         await invoke(cmd, args);
         await reload();
       } catch (e: unknown) {
+        // Someone else changed the row; show their version
+        if (toErrorMessage(e).startsWith("Conflict:")) {
+          await reload();
+        }
         handleOperationError(e, setError);
       }
     },
@@ -124,8 +129,14 @@ const useRuleOperations = (
   );
 
   const updateRule = useCallback(
-    (id: number, pattern: string, matchType: MatchType, categoryId: number, priority: number) =>
-      run("update_rule", { id, pattern, matchType, categoryId, priority }),
+    (
+      id: number,
+      pattern: string,
+      matchType: MatchType,
+      categoryId: number,
+      priority: number,
+      version: number,
+    ) => run("update_rule", { id, pattern, matchType, categoryId, priority, version }),
     [run],
   );
 
@@ -1,6 +1,18 @@
 import { StrictMode } from "react";
 import { createRoot } from "react-dom/client";
 import { App } from "@/App";
+import { BreakScreen } from "@/components/break-screen";
+import { FocusWidget } from "@/components/focus-widget";
+
+/** Location hashes the backend opens its secondary windows with. */
+const WIDGET_HASH = "#widget";
+const BREAK_HASH = "#break";
+
+const RootView = () => {
+  if (window.location.hash === WIDGET_HASH) return <FocusWidget />;
+  if (window.location.hash === BREAK_HASH) return <BreakScreen />;
+  return <App />;
+};
 
 const root = document.getElementById("root");
 
@@ -10,6 +22,6 @@ if (!root) {
 
 createRoot(root).render(
   <StrictMode>
-    <App />
+    <RootView />
   </StrictMode>,
 );
@@ -27,6 +27,8 @@ interface Rule {
   match_type: MatchType;
   category_id: number;
   priority: number;
+  /** Sent back with updates; a stale one is rejected as a conflict. */
+  version: number;
 }
 
 /**
@@ -55,6 +57,28 @@ interface FocusState {
   active: boolean;
   budget_remaining: number;
   session_duration_secs: number | null;
+  time_remaining_secs: number | null;
+}
+
+/**
+ * An enforced break in progress, from get_active_break.
+ * Times are Unix timestamps in seconds.
+ */
+interface ActiveBreak {
+  started_at: number;
+  ends_at: number;
+  trigger: "pomodoro" | "work_limit";
+}
+
+/**
+ * End-of-day review, from get_daily_summary or the daily_summary event.
+ */
+interface DailySummary {
+  date: string; // Local date, YYYY-MM-DD
+  productive_secs: number;
+  neutral_secs: number;
+  distracting_secs: number;
+  top_distractions: AppActivity[];
 }
 
 /**
@@ -93,9 +117,11 @@ const productivityToVariant = (p: ProductivityLevel): ProductivityVariant => {
 };
 
 export type {
+  ActiveBreak,
   AppActivity,
   Category,
   DailyStats,
+  DailySummary,
   FocusState,
   MatchType,
   ProductivityLevel,